@@ -0,0 +1,62 @@
+#include <fcntl.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+#include <sys/ptrace.h>
+#include <unistd.h>
+
+// Tries to detect a debugger with a few common tricks:
+//
+// 1. PTRACE_TRACEME fails if the process is already traced
+// 2. TracerPid in /proc/self/status is not zero if the process is traced
+// 3. Software breakpoints in main can be found by reading /proc/self/mem
+int detected = 0;
+
+void check_traceme(void) {
+  if (ptrace(PTRACE_TRACEME, 0, NULL, NULL) == -1) {
+    fprintf(stderr, "PTRACE_TRACEME failed\n");
+    detected++;
+  }
+}
+
+void check_tracer_pid(void) {
+  char buf[4096] = {0};
+  int fd = open("/proc/self/status", O_RDONLY);
+  if (fd < 0) {
+    return;
+  }
+  read(fd, buf, sizeof(buf) - 1);
+  close(fd);
+
+  char *line = strstr(buf, "TracerPid:");
+  if (line != NULL && atoi(line + strlen("TracerPid:")) != 0) {
+    fprintf(stderr, "TracerPid is not zero\n");
+    detected++;
+  }
+}
+
+int main(void) {
+  check_traceme();
+  check_tracer_pid();
+
+  unsigned char code[256] = {0};
+  int fd = open("/proc/self/mem", O_RDONLY);
+  if (fd >= 0) {
+    pread(fd, code, sizeof(code), (off_t)(size_t)&check_tracer_pid);
+    close(fd);
+  }
+  for (size_t i = 0; i < sizeof(code); i++) {
+    if (code[i] == 0xcc) {
+      fprintf(stderr, "int3 found in check_tracer_pid\n");
+      detected++;
+      break;
+    }
+  }
+
+  if (detected) {
+    fprintf(stderr, "NONONO EVIL DEBUGGER DETECTED\n");
+    return 1;
+  }
+  printf("OK :) No evil debugger.\n");
+  return 0;
+}
@@ -0,0 +1,228 @@
+#ifndef COREMINER_H
+#define COREMINER_H
+
+// Generated with cbindgen from src/ffi.rs, do not edit by hand
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+// The kind of a [`CmEvent`], see [`StopReason`]
+typedef enum CmEventKind {
+  // A breakpoint at [`CmEvent::addr`] was hit
+  CM_EVENT_KIND_BREAKPOINT,
+  // The watched memory range starting at [`CmEvent::addr`] was changed
+  CM_EVENT_KIND_WATCHPOINT,
+  // A single step or a stepping operation was completed
+  CM_EVENT_KIND_STEP_DONE,
+  // The debuggee received the signal [`CmEvent::value`]
+  CM_EVENT_KIND_SIGNAL,
+  // The debuggee entered the syscall [`CmEvent::value`]
+  CM_EVENT_KIND_SYSCALL_ENTRY,
+  // The syscall [`CmEvent::value`] returned [`CmEvent::ret`]
+  CM_EVENT_KIND_SYSCALL_EXIT,
+  // The debuggee stopped at the ptrace event [`CmEvent::value`]
+  CM_EVENT_KIND_PTRACE_EVENT,
+  // The debuggee was stopped by the debugger
+  CM_EVENT_KIND_INTERRUPTED,
+  // The debuggee exited with the exit code [`CmEvent::value`]
+  CM_EVENT_KIND_EXITED,
+  // The debuggee was terminated by the signal [`CmEvent::value`]
+  CM_EVENT_KIND_SIGNALED,
+  // The debuggee panicked and is stopped in `rust_panic` at [`CmEvent::addr`]
+  CM_EVENT_KIND_PANIC,
+} CmEventKind;
+
+// Opaque handle to a debugger, created with [`cm_debugger_new`]
+typedef struct CmDebugger CmDebugger;
+
+// Describes why the debuggee stopped or terminated
+typedef struct CmEvent {
+  // What happened
+  enum CmEventKind kind;
+  // Address of the breakpoint, watchpoint or `rust_panic`, `0` for other kinds
+  uint64_t addr;
+  // Whether the breakpoint is a hardware breakpoint
+  bool hardware;
+  // Signal, syscall or ptrace event number, or exit code, depending on the kind
+  int64_t value;
+  // Return value of the syscall for [`CmEventKind::SyscallExit`], `0` for other kinds
+  int64_t ret;
+  // Human readable description of the event
+  const char *description;
+} CmEvent;
+
+// Called for every [`CmEvent`], with the `user_data` given to [`cm_set_event_callback`]
+//
+// The event and its description are only valid during the call.
+typedef void (*CmEventCallback)(const struct CmEvent *event, void *user_data);
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Creates a new debugger
+//
+// # Returns
+//
+// A handle that must be destroyed with [`cm_debugger_free`], or null if the debugger could not
+// be created.
+struct CmDebugger *cm_debugger_new(void);
+
+// Destroys a debugger, killing or detaching from the debuggee like when quitting
+//
+// # Safety
+//
+// `handle` must be null or a valid handle from [`cm_debugger_new`], which is invalid afterwards.
+void cm_debugger_free(struct CmDebugger *handle);
+
+// Gets the error of the last failed function
+//
+// # Returns
+//
+// A description of the error, or null if the last function did not fail. It is valid until the
+// next function is called with the handle.
+//
+// # Safety
+//
+// `handle` must be null or a valid handle from [`cm_debugger_new`].
+const char *cm_last_error(const struct CmDebugger *handle);
+
+// Sets the function that is called whenever the debuggee stops or terminates
+//
+// The callback is called before the function that resumed the debuggee returns. A null
+// `callback` removes it.
+//
+// # Safety
+//
+// `handle` must be a valid handle from [`cm_debugger_new`]. `user_data` is passed to the
+// callback as is and must be valid for as long as the callback is set.
+int cm_set_event_callback(struct CmDebugger *handle, CmEventCallback callback, void *user_data);
+
+// Launches a program as the debuggee, see [`Debugger::run`]
+//
+// # Parameters
+//
+// * `path` - The program, which is searched in `PATH` if it is not a path
+// * `argv` - The arguments for the program, without the program name, which is set to `path`
+// * `argc` - The number of arguments in `argv`
+//
+// # Safety
+//
+// `handle` must be a valid handle from [`cm_debugger_new`] and `path` a nul terminated string.
+// `argv` must point to `argc` nul terminated strings, or be null if `argc` is `0`.
+int cm_launch(struct CmDebugger *handle, const char *path, const char *const *argv, size_t argc);
+
+// Attaches to the running process `pid` as the debuggee, see [`Debugger::attach`]
+//
+// The process is stopped when this returns.
+//
+// # Safety
+//
+// `handle` must be a valid handle from [`cm_debugger_new`].
+int cm_attach(struct CmDebugger *handle, int pid);
+
+// Continues the debuggee until it stops again
+//
+// # Safety
+//
+// `handle` must be a valid handle from [`cm_debugger_new`].
+int cm_continue(struct CmDebugger *handle);
+
+// Executes a single instruction of the debuggee
+//
+// # Safety
+//
+// `handle` must be a valid handle from [`cm_debugger_new`].
+int cm_step(struct CmDebugger *handle);
+
+// Executes the next instruction of the debuggee, stepping over function calls
+//
+// # Safety
+//
+// `handle` must be a valid handle from [`cm_debugger_new`].
+int cm_step_over(struct CmDebugger *handle);
+
+// Executes the debuggee until it reaches the next source line, stepping into function calls
+//
+// # Safety
+//
+// `handle` must be a valid handle from [`cm_debugger_new`].
+int cm_step_line(struct CmDebugger *handle);
+
+// Executes the debuggee until it reaches the next source line, stepping over function calls
+//
+// # Safety
+//
+// `handle` must be a valid handle from [`cm_debugger_new`].
+int cm_next_line(struct CmDebugger *handle);
+
+// Continues the debuggee until the current function returns
+//
+// # Safety
+//
+// `handle` must be a valid handle from [`cm_debugger_new`].
+int cm_step_out(struct CmDebugger *handle);
+
+// Sets a breakpoint at `addr`, using a debug register if `hardware` is true
+//
+// # Safety
+//
+// `handle` must be a valid handle from [`cm_debugger_new`].
+int cm_set_breakpoint(struct CmDebugger *handle, uint64_t addr, bool hardware);
+
+// Deletes the breakpoint at `addr`
+//
+// # Safety
+//
+// `handle` must be a valid handle from [`cm_debugger_new`].
+int cm_delete_breakpoint(struct CmDebugger *handle, uint64_t addr);
+
+// Reads up to `len` bytes of the memory of the debuggee at `addr` into `buf`
+//
+// Software breakpoints are visible as `int3` instructions in the read memory.
+//
+// # Returns
+//
+// The number of bytes read, which can be less than `len` at the end of mapped memory, or `-1`.
+//
+// # Safety
+//
+// `handle` must be a valid handle from [`cm_debugger_new`] and `buf` must be valid for writing
+// `len` bytes.
+ptrdiff_t cm_read_memory(struct CmDebugger *handle, uint64_t addr, uint8_t *buf, size_t len);
+
+// Writes `len` bytes from `buf` into the memory of the debuggee at `addr`
+//
+// Writing over software breakpoints removes them from the code without deleting them.
+//
+// # Returns
+//
+// The number of bytes written, or `-1`.
+//
+// # Safety
+//
+// `handle` must be a valid handle from [`cm_debugger_new`] and `buf` must be valid for reading
+// `len` bytes.
+ptrdiff_t cm_write_memory(struct CmDebugger *handle, uint64_t addr, const uint8_t *buf, size_t len);
+
+// Reads the register `name` (like `"rip"`) of the debuggee into `value`
+//
+// # Safety
+//
+// `handle` must be a valid handle from [`cm_debugger_new`], `name` a nul terminated string and
+// `value` must be valid for writing.
+int cm_get_register(struct CmDebugger *handle, const char *name, uint64_t *value);
+
+// Sets the register `name` (like `"rip"`) of the debuggee to `value`
+//
+// # Safety
+//
+// `handle` must be a valid handle from [`cm_debugger_new`] and `name` a nul terminated string.
+int cm_set_register(struct CmDebugger *handle, const char *name, uint64_t value);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* COREMINER_H */
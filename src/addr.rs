@@ -13,7 +13,23 @@
 //! - Address arithmetic operations
 //! - Conversions between addresses and various numeric types
 //! - Relative address calculations
+//! - Checked arithmetic and alignment, which do not wrap around silently
+//! - Address ranges with [`AddrRange`]
+//! - Module relative addresses with [`ModuleAddr`]
 //! - Debug and display formatting
+//!
+//! ## Absolute and Module Relative Addresses
+//!
+//! An [`Addr`] is always an address in the address space of the debuggee. Symbols, the line
+//! table, the debug information and the relocations of an ELF file are in the virtual addresses of
+//! the file instead, which only become addresses in the process by adding the load bias of the
+//! module. Such addresses are a [`ModuleAddr`], the
+//! [`LoadedModule`](crate::modules::LoadedModule) of the module turns them into an [`Addr`] and
+//! back.
+//!
+//! The load bias is not the base address of the module: position independent modules are linked
+//! at address zero and have a bias equal to their base, but an executable that is not position
+//! independent is loaded at the addresses in its file, like `0x400000`, and has a bias of zero.
 
 use std::fmt::Display;
 use std::ops::{Add, AddAssign, Sub, SubAssign};
@@ -97,6 +113,111 @@ impl Addr {
     pub fn raw_pointer(&self) -> RawPointer {
         self.0 as RawPointer
     }
+
+    /// Adds `offset` to the address, returning [`None`] if the result would wrap around
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use coreminer::addr::Addr;
+    ///
+    /// assert_eq!(Addr::from(0x1000usize).checked_add(0x10), Some(Addr::from(0x1010usize)));
+    /// assert_eq!(Addr::from(usize::MAX).checked_add(1), None);
+    /// ```
+    #[must_use]
+    pub fn checked_add(self, offset: usize) -> Option<Self> {
+        self.0.checked_add(offset).map(Self)
+    }
+
+    /// Subtracts `offset` from the address, returning [`None`] if the result would wrap around
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use coreminer::addr::Addr;
+    ///
+    /// assert_eq!(Addr::from(0x1000usize).checked_sub(1), Some(Addr::from(0xfffusize)));
+    /// assert_eq!(Addr::NULL.checked_sub(1), None);
+    /// ```
+    #[must_use]
+    pub fn checked_sub(self, offset: usize) -> Option<Self> {
+        self.0.checked_sub(offset).map(Self)
+    }
+
+    /// Returns how many bytes the address is after `base`, or [`None`] if it is before `base`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use coreminer::addr::Addr;
+    ///
+    /// let base = Addr::from(0x1000usize);
+    /// assert_eq!(Addr::from(0x1010usize).offset_from(base), Some(0x10));
+    /// assert_eq!(Addr::from(0x0ff0usize).offset_from(base), None);
+    /// ```
+    #[must_use]
+    pub fn offset_from(self, base: Self) -> Option<usize> {
+        self.0.checked_sub(base.0)
+    }
+
+    /// Checks if the address is a multiple of `align`, which must be a power of two
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use coreminer::addr::Addr;
+    ///
+    /// assert!(Addr::from(0x1000usize).is_aligned(0x1000));
+    /// assert!(!Addr::from(0x1008usize).is_aligned(0x10));
+    /// ```
+    #[must_use]
+    pub fn is_aligned(self, align: usize) -> bool {
+        debug_assert!(
+            align.is_power_of_two(),
+            "alignment {align} is not a power of two"
+        );
+        self.0 & (align - 1) == 0
+    }
+
+    /// Rounds the address down to a multiple of `align`, which must be a power of two
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use coreminer::addr::Addr;
+    ///
+    /// assert_eq!(Addr::from(0x1234usize).align_down(0x1000), Addr::from(0x1000usize));
+    /// ```
+    #[must_use]
+    pub fn align_down(self, align: usize) -> Self {
+        debug_assert!(
+            align.is_power_of_two(),
+            "alignment {align} is not a power of two"
+        );
+        Self(self.0 & !(align - 1))
+    }
+
+    /// Rounds the address up to a multiple of `align`, which must be a power of two, returning
+    /// [`None`] if the result would wrap around
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use coreminer::addr::Addr;
+    ///
+    /// assert_eq!(Addr::from(0x1234usize).align_up(0x1000), Some(Addr::from(0x2000usize)));
+    /// assert_eq!(Addr::from(0x2000usize).align_up(0x1000), Some(Addr::from(0x2000usize)));
+    /// assert_eq!(Addr::from(usize::MAX).align_up(0x1000), None);
+    /// ```
+    #[must_use]
+    pub fn align_up(self, align: usize) -> Option<Self> {
+        debug_assert!(
+            align.is_power_of_two(),
+            "alignment {align} is not a power of two"
+        );
+        self.checked_add(align - 1)
+            .map(|addr| addr.align_down(align))
+    }
 }
 
 impl Display for Addr {
@@ -199,6 +320,159 @@ impl std::fmt::Debug for Addr {
     }
 }
 
+/// A range of addresses, from [`Self::start`] up to but not including [`Self::end`]
+///
+/// Unlike a [`std::ops::Range`] of [`Addr`], a range can only be created if it does not wrap
+/// around the end of the address space, so its length and the addresses in it can be computed
+/// without overflows.
+///
+/// # Examples
+///
+/// ```
+/// use coreminer::addr::{Addr, AddrRange};
+///
+/// let code = AddrRange::with_len(Addr::from(0x1000usize), 0x100).unwrap();
+/// assert!(code.contains(Addr::from(0x10ffusize)));
+/// assert!(!code.contains(Addr::from(0x1100usize)));
+///
+/// let patch = AddrRange::new(Addr::from(0x10f0usize), Addr::from(0x1200usize)).unwrap();
+/// let overlap = code.intersect(&patch).unwrap();
+/// assert_eq!(overlap.start(), Addr::from(0x10f0usize));
+/// assert_eq!(overlap.len(), 0x10);
+///
+/// // these would wrap around
+/// assert!(AddrRange::with_len(Addr::from(usize::MAX), 2).is_none());
+/// assert!(AddrRange::new(Addr::from(0x1000usize), Addr::from(0x0fffusize)).is_none());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct AddrRange {
+    start: Addr,
+    end: Addr,
+}
+
+impl AddrRange {
+    /// Creates the range from `start` up to `end`, or [`None`] if `end` is before `start`
+    #[must_use]
+    pub fn new(start: Addr, end: Addr) -> Option<Self> {
+        (start <= end).then_some(Self { start, end })
+    }
+
+    /// Creates the range of `len` bytes at `start`, or [`None`] if it would wrap around
+    #[must_use]
+    pub fn with_len(start: Addr, len: usize) -> Option<Self> {
+        Some(Self {
+            start,
+            end: start.checked_add(len)?,
+        })
+    }
+
+    /// Returns the first address in the range
+    #[inline]
+    #[must_use]
+    pub fn start(&self) -> Addr {
+        self.start
+    }
+
+    /// Returns the first address after the range
+    #[inline]
+    #[must_use]
+    pub fn end(&self) -> Addr {
+        self.end
+    }
+
+    /// Returns the number of bytes in the range
+    #[inline]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.end.0 - self.start.0
+    }
+
+    /// Returns `true` if the range contains no addresses
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+
+    /// Checks if `addr` is in the range
+    #[inline]
+    #[must_use]
+    pub fn contains(&self, addr: Addr) -> bool {
+        self.start <= addr && addr < self.end
+    }
+
+    /// Returns the addresses that are in both ranges, or [`None`] if there are none
+    #[must_use]
+    pub fn intersect(&self, other: &Self) -> Option<Self> {
+        let range = Self {
+            start: self.start.max(other.start),
+            end: self.end.min(other.end),
+        };
+        (range.start < range.end).then_some(range)
+    }
+
+    /// Checks if the ranges have at least one address in common
+    #[must_use]
+    pub fn overlaps(&self, other: &Self) -> bool {
+        self.intersect(other).is_some()
+    }
+}
+
+impl Display for AddrRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}..{}", self.start, self.end)
+    }
+}
+
+/// An address in a module, as in the virtual addresses of its ELF file
+///
+/// [`Self::rebase`] with the bias of the [`LoadedModule`](crate::modules::LoadedModule) gives the
+/// [`Addr`] in the debuggee, [`LoadedModule::to_module`](crate::modules::LoadedModule::to_module)
+/// is the opposite direction.
+///
+/// # Examples
+///
+/// ```
+/// use coreminer::addr::{Addr, ModuleAddr};
+///
+/// let main = ModuleAddr::new("/usr/bin/hot", 0x1139);
+/// // a position independent executable, loaded at 0x5555_5555_4000
+/// assert_eq!(main.rebase(0x5555_5555_4000), Addr::from(0x5555_5555_5139_usize));
+/// // an executable that is not position independent has no load bias
+/// assert_eq!(main.rebase(0), Addr::from(0x1139_usize));
+/// assert_eq!(main.to_string(), "/usr/bin/hot@0x1139");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct ModuleAddr {
+    /// Path of the module, like the executable or a shared library
+    pub module: String,
+    /// The virtual address in the ELF file of the module
+    pub offset: u64,
+}
+
+impl ModuleAddr {
+    /// Creates a [`ModuleAddr`] for the virtual address `offset` of the file `module`
+    #[must_use]
+    pub fn new(module: impl Into<String>, offset: u64) -> Self {
+        Self {
+            module: module.into(),
+            offset,
+        }
+    }
+
+    /// Returns the address in the process if the module was loaded with the load bias `bias`
+    #[must_use]
+    pub fn rebase(&self, bias: usize) -> Addr {
+        Addr(bias.wrapping_add(self.offset as usize))
+    }
+}
+
+impl Display for ModuleAddr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}@{:#x}", self.module, self.offset)
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -225,4 +499,27 @@ mod test {
         let b: Addr = serde_json::from_str(&json).unwrap();
         assert_eq!(a, b);
     }
+
+    #[test]
+    fn test_addr_range() {
+        let a = AddrRange::with_len(Addr::from(0x1000usize), 0x10).unwrap();
+        let b = AddrRange::with_len(Addr::from(0x1010usize), 0x10).unwrap();
+        assert_eq!(a.intersect(&b), None);
+        assert!(!a.overlaps(&b));
+        assert!(AddrRange::new(a.end(), a.end()).unwrap().is_empty());
+        assert_eq!(a.to_string(), "0x0000000000001000..0x0000000000001010");
+        assert_eq!(
+            AddrRange::with_len(Addr::from(usize::MAX - 1), 1).map(|r| r.len()),
+            Some(1)
+        );
+    }
+
+    #[test]
+    fn test_module_addr_rebase() {
+        let addr = ModuleAddr::new("/bin/true", 0x0040_1136);
+        assert_eq!(addr.rebase(0), Addr::from(0x0040_1136_usize));
+        assert_eq!(addr.rebase(0x1000), Addr::from(0x0040_2136_usize));
+        let json = serde_json::to_string(&addr).unwrap();
+        assert_eq!(json, r#"{"module":"/bin/true","offset":4198710}"#);
+    }
 }
@@ -0,0 +1,330 @@
+//! # Address Expression Module
+//!
+//! Provides addresses that are written as symbols or source locations instead of numbers.
+//!
+//! An [`AddrExpr`] is what the user gives wherever an address is expected, like for a
+//! [Breakpoint](crate::breakpoint::Breakpoint), a disassembly or a memory read. Addresses change
+//! with every run because of address space layout randomization, so looking up `main` or
+//! `hot.c:12` first is tedious. The [`Debugger`](crate::debugger::Debugger) resolves the
+//! expression against the symbols and line table of the debuggee and rebases it to the load
+//! address, see [`Debugger::resolve_addr`](crate::debugger::Debugger::resolve_addr).
+//!
+//! The text form, which the CLI and cmserve both accept, is one of:
+//!
+//! - `0x401136` or `401136`: an absolute address in hexadecimal
+//! - `main`, `main+0x10` or `main-8`: a symbol with an optional offset in hexadecimal
+//! - `$rsp`, `$rsp+0x20` or `$rbp-8`: the current value of a register with an optional offset
+//! - `$1`, `$` or `$base+8`: a value of the [value history](crate::history) with an optional
+//!   offset
+//! - `hot.c:12`: the first statement of a line of a source file, in decimal
+//!
+//! Offsets are sums and differences of hexadecimal numbers, which may be multiplied, like
+//! `$rsp+8*3` for the fourth word on the stack.
+//!
+//! A name that consists of hexadecimal digits only, like `add`, is read as a number. Such a
+//! symbol can be written with an offset, like `add+0`.
+
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::errors::{DebuggerError, Result};
+use crate::{Addr, Register};
+
+/// An address, given as number, symbol, register, value or source location
+///
+/// In JSON, an absolute address is a number like an [`Addr`], the others are strings in the text
+/// form of the [module](self) documentation.
+///
+/// # Examples
+///
+/// ```
+/// use coreminer::addrexpr::AddrExpr;
+/// use coreminer::addr::Addr;
+/// use coreminer::Register;
+///
+/// let expr: AddrExpr = "main+0x10".parse().unwrap();
+/// assert_eq!(expr, AddrExpr::Symbol { name: "main".to_string(), offset: 0x10 });
+///
+/// let expr: AddrExpr = "$rsp+8*3".parse().unwrap();
+/// assert_eq!(expr, AddrExpr::Register { reg: Register::rsp, offset: 0x18 });
+///
+/// let expr: AddrExpr = "hot.c:12".parse().unwrap();
+/// assert_eq!(expr, AddrExpr::Line { file: "hot.c".to_string(), line: 12 });
+///
+/// let expr: AddrExpr = serde_json::from_str("4198710").unwrap();
+/// assert_eq!(expr, AddrExpr::Absolute(Addr::from(0x0040_1136_usize)));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum AddrExpr {
+    /// An address in the address space of the debuggee
+    Absolute(Addr),
+    /// An offset from a function of the debug information or a symbol of the executable
+    Symbol {
+        /// Name of the function or symbol
+        name: String,
+        /// Offset from the start of the function or symbol, may be negative
+        offset: i64,
+    },
+    /// An offset from the current value of a register of the debuggee
+    Register {
+        /// The register holding the base address
+        reg: Register,
+        /// Offset from the value of the register, may be negative
+        offset: i64,
+    },
+    /// An offset from a value of the [`ValueHistory`](crate::history::ValueHistory)
+    Value {
+        /// `1` for `$1`, empty for `$` or the name of a convenience variable
+        name: String,
+        /// Offset from the value, may be negative
+        offset: i64,
+    },
+    /// The first statement of a line in a source file
+    Line {
+        /// The source file, or the end of its path, like `hot.c`
+        file: String,
+        /// The line, starting at 1
+        line: u64,
+    },
+}
+
+impl From<Addr> for AddrExpr {
+    fn from(value: Addr) -> Self {
+        Self::Absolute(value)
+    }
+}
+
+impl FromStr for AddrExpr {
+    type Err = DebuggerError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim();
+        let invalid = || DebuggerError::InvalidAddrExpr(s.to_string());
+        if s.is_empty() {
+            return Err(invalid());
+        }
+
+        if let Some((file, line)) = s.rsplit_once(':') {
+            if let (false, Ok(line)) = (file.is_empty(), line.parse::<u64>()) {
+                return Ok(Self::Line {
+                    file: file.to_string(),
+                    line,
+                });
+            }
+        }
+
+        if let Some(addr) = parse_hex(s) {
+            return Ok(Self::Absolute(Addr::from(addr)));
+        }
+
+        let (base, offset) = match s
+            .char_indices()
+            .skip(1)
+            .find(|(_, c)| matches!(c, '+' | '-'))
+        {
+            Some((sign_at, _)) => {
+                let (base, offset) = s.split_at(sign_at);
+                (base.trim_end(), parse_offset(offset).ok_or_else(invalid)?)
+            }
+            None => (s, 0),
+        };
+
+        if let Some(name) = base.strip_prefix('$') {
+            if let Ok(reg) = name.parse() {
+                return Ok(Self::Register { reg, offset });
+            }
+            if !name.is_empty() && !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+                return Err(invalid());
+            }
+            return Ok(Self::Value {
+                name: name.to_string(),
+                offset,
+            });
+        }
+        Ok(Self::Symbol {
+            name: base.to_string(),
+            offset,
+        })
+    }
+}
+
+/// Parses an offset like `+0x10`, `-8` or `+8*3-4`
+///
+/// The offset is a sum of terms, each starting with `+` or `-` and being a product of
+/// hexadecimal numbers.
+fn parse_offset(s: &str) -> Option<i64> {
+    let mut total: i64 = 0;
+    let mut rest = s.trim();
+    while let Some(sign) = rest.chars().next() {
+        let negative = match sign {
+            '+' => false,
+            '-' => true,
+            _ => return None,
+        };
+        rest = &rest[1..];
+        let end = rest.find(['+', '-']).unwrap_or(rest.len());
+        let (term, tail) = rest.split_at(end);
+        rest = tail;
+
+        let mut product: i64 = 1;
+        for factor in term.split('*') {
+            let factor = i64::try_from(parse_hex(factor)?).ok()?;
+            product = product.checked_mul(factor)?;
+        }
+        total = if negative {
+            total.checked_sub(product)?
+        } else {
+            total.checked_add(product)?
+        };
+    }
+    Some(total)
+}
+
+/// Parses a number in hexadecimal with an optional `0x` prefix
+fn parse_hex(s: &str) -> Option<usize> {
+    let s = s.trim();
+    let digits = s.strip_prefix("0x").unwrap_or(s);
+    if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    usize::from_str_radix(digits, 16).ok()
+}
+
+impl Display for AddrExpr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Absolute(addr) => write!(f, "{addr}"),
+            // a name of hexadecimal digits would be read back as a number without the offset
+            Self::Symbol { name, offset: 0 } if parse_hex(name).is_none() => write!(f, "{name}"),
+            Self::Symbol { name, offset } if *offset < 0 => {
+                write!(f, "{name}-{:#x}", offset.unsigned_abs())
+            }
+            Self::Symbol { name, offset } => write!(f, "{name}+{offset:#x}"),
+            Self::Register { reg, offset } => {
+                write!(f, "${reg:?}")?;
+                write_offset(f, *offset)
+            }
+            Self::Value { name, offset } => {
+                write!(f, "${name}")?;
+                write_offset(f, *offset)
+            }
+            Self::Line { file, line } => write!(f, "{file}:{line}"),
+        }
+    }
+}
+
+/// Writes a nonzero `offset` with its sign
+fn write_offset(f: &mut fmt::Formatter<'_>, offset: i64) -> fmt::Result {
+    match offset {
+        0 => Ok(()),
+        o if o < 0 => write!(f, "-{:#x}", o.unsigned_abs()),
+        o => write!(f, "+{o:#x}"),
+    }
+}
+
+impl Serialize for AddrExpr {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        match self {
+            Self::Absolute(addr) => addr.serialize(serializer),
+            other => serializer.collect_str(other),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for AddrExpr {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Raw {
+            Number(Addr),
+            Text(String),
+        }
+
+        match Raw::deserialize(deserializer)? {
+            Raw::Number(addr) => Ok(Self::Absolute(addr)),
+            Raw::Text(text) => text.parse().map_err(serde::de::Error::custom),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn symbol(name: &str, offset: i64) -> AddrExpr {
+        AddrExpr::Symbol {
+            name: name.to_string(),
+            offset,
+        }
+    }
+
+    #[test]
+    fn test_parse_addr_expr() {
+        let cases = [
+            (
+                "0x401136",
+                AddrExpr::Absolute(Addr::from(0x0040_1136_usize)),
+            ),
+            ("401136", AddrExpr::Absolute(Addr::from(0x0040_1136_usize))),
+            ("add", AddrExpr::Absolute(Addr::from(0xaddusize))),
+            ("main", symbol("main", 0)),
+            ("main+0x1b", symbol("main", 0x1b)),
+            ("main+10", symbol("main", 0x10)),
+            ("add+0", symbol("add", 0)),
+            ("fib - 4", symbol("fib", -4)),
+            ("ns::func", symbol("ns::func", 0)),
+            ("main+8*3-4", symbol("main", 0x14)),
+            (
+                "$rsp",
+                AddrExpr::Register {
+                    reg: Register::rsp,
+                    offset: 0,
+                },
+            ),
+            (
+                "$RBP - 0x10",
+                AddrExpr::Register {
+                    reg: Register::rbp,
+                    offset: -0x10,
+                },
+            ),
+            (
+                "$2+8",
+                AddrExpr::Value {
+                    name: "2".to_string(),
+                    offset: 8,
+                },
+            ),
+            (
+                "$",
+                AddrExpr::Value {
+                    name: String::new(),
+                    offset: 0,
+                },
+            ),
+            (
+                "src/hot.c:12",
+                AddrExpr::Line {
+                    file: "src/hot.c".to_string(),
+                    line: 12,
+                },
+            ),
+        ];
+        for (text, expected) in cases {
+            let expr: AddrExpr = text.parse().unwrap();
+            assert_eq!(expr, expected, "{text}");
+            assert_eq!(expr.to_string().parse::<AddrExpr>().unwrap(), expected);
+
+            let json = serde_json::to_string(&expr).unwrap();
+            assert_eq!(serde_json::from_str::<AddrExpr>(&json).unwrap(), expected);
+        }
+
+        assert!("".parse::<AddrExpr>().is_err());
+        assert!("main+zz".parse::<AddrExpr>().is_err());
+        assert!("$a.b".parse::<AddrExpr>().is_err());
+        assert!("$rsp+8*".parse::<AddrExpr>().is_err());
+        assert!(serde_json::from_str::<AddrExpr>("\"main+\"").is_err());
+    }
+}
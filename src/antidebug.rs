@@ -0,0 +1,333 @@
+//! # Anti-Anti-Debugging Module
+//!
+//! Provides countermeasures against common tricks that programs use to detect a debugger.
+//!
+//! Programs that do not want to be debugged often check if they are being traced. This module
+//! implements countermeasures for some of the most common checks. They work by intercepting the
+//! syscalls of the debuggee (see [`crate::syscall`]) and tampering with their arguments and
+//! results:
+//!
+//! - **`ptrace(PTRACE_TRACEME)`**: A process can only have one tracer, so calling
+//!   `PTRACE_TRACEME` fails when a debugger is attached. The syscall is skipped and a success is
+//!   faked.
+//! - **`TracerPid`**: The `TracerPid` field of `/proc/self/status` contains the PID of the
+//!   tracer. Reads from that file are rewritten, so that `TracerPid` is always zero.
+//! - **`int3` scanning**: Software [Breakpoints](crate::breakpoint::Breakpoint) replace
+//!   instructions with `int3` (`0xCC`), which a program can find by scanning its own code. Reads
+//!   from `/proc/self/mem` are rewritten, so that the original bytes are returned instead of the
+//!   `int3` of enabled [Breakpoints](crate::breakpoint::Breakpoint).
+//!
+//! Note that code that scans itself by directly loading its instructions from memory cannot be
+//! fooled this way, as no syscall is involved.
+//!
+//! The countermeasures are configured with an [`AntiDebugConfig`] and enabled with
+//! [`Debugger::set_anti_debug`](crate::debugger::Debugger::set_anti_debug).
+
+use std::collections::HashSet;
+
+use nix::libc::{self, c_long};
+use nix::unistd::Pid;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info, trace};
+
+use crate::debuggee::Debuggee;
+use crate::errors::Result;
+use crate::syscall::{self, SyscallStop};
+use crate::{mem_read, mem_write, Addr};
+
+/// Maximum length of a path that is read from the memory of the debuggee
+const MAX_PATH_LEN: usize = 4096;
+
+/// Selects which anti-debugging tricks should be defeated
+///
+/// All countermeasures are disabled by default.
+///
+/// # Examples
+///
+/// ```
+/// use coreminer::antidebug::AntiDebugConfig;
+///
+/// let config = AntiDebugConfig::default();
+/// assert!(!config.is_active());
+///
+/// let config = AntiDebugConfig::all();
+/// assert!(config.fake_traceme && config.hide_tracer_pid && config.hide_breakpoints);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct AntiDebugConfig {
+    /// Skip `ptrace(PTRACE_TRACEME)` calls of the debuggee and fake a success
+    pub fake_traceme: bool,
+    /// Always show a `TracerPid` of zero when the debuggee reads `/proc/self/status`
+    pub hide_tracer_pid: bool,
+    /// Hide the `int3` of breakpoints when the debuggee reads `/proc/self/mem`
+    pub hide_breakpoints: bool,
+}
+
+impl AntiDebugConfig {
+    /// Creates a config with all countermeasures enabled
+    #[must_use]
+    pub fn all() -> Self {
+        Self {
+            fake_traceme: true,
+            hide_tracer_pid: true,
+            hide_breakpoints: true,
+        }
+    }
+
+    /// Returns `true` if at least one countermeasure is enabled
+    ///
+    /// If this is the case, the syscalls of the debuggee need to be intercepted.
+    #[must_use]
+    pub fn is_active(&self) -> bool {
+        self.fake_traceme || self.hide_tracer_pid || self.hide_breakpoints
+    }
+}
+
+/// Actions that need to be taken when the currently entered syscall exits
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PendingAction {
+    /// Overwrite the return value with a success
+    FakeSuccess,
+    /// Remember the returned file descriptor as one for `/proc/self/status`
+    TrackStatusFd,
+    /// Remember the returned file descriptor as one for `/proc/self/mem`
+    TrackMemFd,
+    /// Zero the `TracerPid` in the data that was read into the buffer
+    PatchStatus { buf: Addr },
+    /// Restore the original bytes of breakpoints in the data that was read into the buffer
+    MaskBreakpoints { buf: Addr, offset: Addr },
+}
+
+/// State of the anti-debugging countermeasures for a debuggee
+///
+/// Keeps track of the file descriptors of the debuggee that need to be watched and of the action
+/// that needs to be taken at the exit of the current syscall.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct AntiDebug {
+    pub(crate) config: AntiDebugConfig,
+    status_fds: HashSet<u64>,
+    mem_fds: HashSet<u64>,
+    pending: Option<PendingAction>,
+}
+
+impl AntiDebug {
+    /// Forgets all state that belongs to a specific debuggee, but keeps the config
+    pub(crate) fn reset(&mut self) {
+        self.status_fds.clear();
+        self.mem_fds.clear();
+        self.pending = None;
+    }
+
+    /// Applies the configured countermeasures at a syscall stop of the debuggee
+    ///
+    /// # Errors
+    ///
+    /// This function fails if the registers or memory of the debuggee cannot be accessed.
+    pub(crate) fn on_syscall(&mut self, dbge: &Debuggee, stop: &SyscallStop) -> Result<()> {
+        if stop.is_entry() {
+            self.pending = self.on_entry(dbge.pid, stop)?;
+            if self.pending.is_some() {
+                trace!("anti debug: pending action {:?}", self.pending);
+            }
+            Ok(())
+        } else if let Some(action) = self.pending.take() {
+            self.on_exit(dbge, stop, action)
+        } else {
+            Ok(())
+        }
+    }
+
+    fn on_entry(&mut self, pid: Pid, stop: &SyscallStop) -> Result<Option<PendingAction>> {
+        let number = stop.number as c_long;
+        let cfg = self.config;
+
+        if cfg.fake_traceme
+            && number == libc::SYS_ptrace
+            && stop.args[0] == u64::from(libc::PTRACE_TRACEME)
+        {
+            info!("anti debug: faking success for PTRACE_TRACEME");
+            syscall::skip_syscall(pid)?;
+            return Ok(Some(PendingAction::FakeSuccess));
+        }
+
+        if number == libc::SYS_open || number == libc::SYS_openat {
+            let path_ptr = if number == libc::SYS_open {
+                stop.args[0]
+            } else {
+                stop.args[1]
+            };
+            let path = read_c_string(pid, Addr::from(path_ptr))?;
+            if cfg.hide_tracer_pid && is_proc_file(&path, pid, "status") {
+                debug!("anti debug: debuggee opens {path}");
+                return Ok(Some(PendingAction::TrackStatusFd));
+            }
+            if cfg.hide_breakpoints && is_proc_file(&path, pid, "mem") {
+                debug!("anti debug: debuggee opens {path}");
+                return Ok(Some(PendingAction::TrackMemFd));
+            }
+            return Ok(None);
+        }
+
+        if number == libc::SYS_read || number == libc::SYS_pread64 {
+            let fd = stop.args[0];
+            let buf = Addr::from(stop.args[1]);
+            if self.status_fds.contains(&fd) {
+                return Ok(Some(PendingAction::PatchStatus { buf }));
+            }
+            if self.mem_fds.contains(&fd) {
+                let offset = if number == libc::SYS_pread64 {
+                    Addr::from(stop.args[3])
+                } else {
+                    Addr::from(fd_position(pid, fd)?)
+                };
+                return Ok(Some(PendingAction::MaskBreakpoints { buf, offset }));
+            }
+            return Ok(None);
+        }
+
+        if number == libc::SYS_close {
+            self.status_fds.remove(&stop.args[0]);
+            self.mem_fds.remove(&stop.args[0]);
+        }
+
+        Ok(None)
+    }
+
+    fn on_exit(
+        &mut self,
+        dbge: &Debuggee,
+        stop: &SyscallStop,
+        action: PendingAction,
+    ) -> Result<()> {
+        let ret = stop.ret.unwrap_or(-1);
+        match action {
+            PendingAction::FakeSuccess => syscall::set_return_value(dbge.pid, 0)?,
+            PendingAction::TrackStatusFd if ret >= 0 => {
+                self.status_fds.insert(ret as u64);
+            }
+            PendingAction::TrackMemFd if ret >= 0 => {
+                self.mem_fds.insert(ret as u64);
+            }
+            PendingAction::PatchStatus { buf } if ret > 0 => {
+                let mut data = vec![0; ret as usize];
+                let len = mem_read(&mut data, dbge.pid, buf)?;
+                data.truncate(len);
+                if normalize_tracer_pid(&mut data) {
+                    info!("anti debug: hiding TracerPid from the debuggee");
+                    mem_write(&data, dbge.pid, buf)?;
+                }
+            }
+            PendingAction::MaskBreakpoints { buf, offset } if ret > 0 => {
+                let end = offset + ret as usize;
+                for (addr, bp) in &dbge.breakpoints {
+                    if *addr < offset || *addr >= end {
+                        continue;
+                    }
+                    if let Some(original) = bp.saved_data() {
+                        info!("anti debug: hiding breakpoint at {addr} from the debuggee");
+                        mem_write(&[original], dbge.pid, buf + (*addr - offset))?;
+                    }
+                }
+            }
+            _ => (), // the syscall failed, nothing to do
+        }
+        Ok(())
+    }
+}
+
+/// Reads a null terminated string from the memory of the debuggee
+fn read_c_string(pid: Pid, addr: Addr) -> Result<String> {
+    let mut buf = vec![0; MAX_PATH_LEN];
+    let len = mem_read(&mut buf, pid, addr)?;
+    buf.truncate(len);
+    if let Some(end) = buf.iter().position(|b| *b == 0) {
+        buf.truncate(end);
+    }
+    Ok(String::from_utf8_lossy(&buf).to_string())
+}
+
+/// Gets the current file position of a file descriptor of the debuggee
+fn fd_position(pid: Pid, fd: u64) -> Result<usize> {
+    let fdinfo = std::fs::read_to_string(format!("/proc/{pid}/fdinfo/{fd}"))?;
+    let pos = fdinfo
+        .lines()
+        .find_map(|l| l.strip_prefix("pos:"))
+        .unwrap_or("0")
+        .trim();
+    Ok(pos.parse()?)
+}
+
+/// Checks if `path` refers to the procfs file `name` of the process `pid`
+///
+/// Matches `/proc/self/NAME`, `/proc/thread-self/NAME`, `/proc/PID/NAME` and the same paths for
+/// the tasks of the process, like `/proc/self/task/TID/NAME`.
+fn is_proc_file(path: &str, pid: Pid, name: &str) -> bool {
+    let parts: Vec<&str> = path.split('/').filter(|p| !p.is_empty()).collect();
+    let pid = pid.to_string();
+    let is_process = |p: &str| p == "self" || p == "thread-self" || p == pid;
+    let is_number = |p: &str| !p.is_empty() && p.bytes().all(|b| b.is_ascii_digit());
+
+    match parts.as_slice() {
+        ["proc", process, file] => is_process(process) && *file == name,
+        ["proc", process, "task", tid, file] => {
+            is_process(process) && is_number(tid) && *file == name
+        }
+        _ => false,
+    }
+}
+
+/// Replaces the value of the `TracerPid` field in the contents of a status file with zeros
+///
+/// Every digit is replaced with `0`, so that the length of the data does not change.
+///
+/// Returns `true` if the data was modified.
+fn normalize_tracer_pid(data: &mut [u8]) -> bool {
+    const FIELD: &[u8] = b"TracerPid:";
+    let Some(start) = data.windows(FIELD.len()).position(|w| w == FIELD) else {
+        return false;
+    };
+
+    let mut modified = false;
+    for byte in data[start + FIELD.len()..]
+        .iter_mut()
+        .skip_while(|b| b.is_ascii_whitespace() && **b != b'\n')
+        .take_while(|b| b.is_ascii_digit())
+    {
+        if *byte != b'0' {
+            *byte = b'0';
+            modified = true;
+        }
+    }
+    modified
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_normalize_tracer_pid() {
+        let mut data = b"Name:\tfoo\nTracerPid:\t13371\nUid:\t1000\n".to_vec();
+        assert!(normalize_tracer_pid(&mut data));
+        assert_eq!(data, b"Name:\tfoo\nTracerPid:\t00000\nUid:\t1000\n");
+
+        // already zero, nothing to do
+        assert!(!normalize_tracer_pid(&mut data));
+
+        let mut data = b"Name:\tfoo\nUid:\t1000\n".to_vec();
+        assert!(!normalize_tracer_pid(&mut data));
+    }
+
+    #[test]
+    fn test_is_proc_file() {
+        let pid = Pid::from_raw(1234);
+        assert!(is_proc_file("/proc/self/status", pid, "status"));
+        assert!(is_proc_file("/proc/thread-self/status", pid, "status"));
+        assert!(is_proc_file("/proc/1234/status", pid, "status"));
+        assert!(is_proc_file("/proc/self/task/1235/status", pid, "status"));
+        assert!(is_proc_file("/proc/self/mem", pid, "mem"));
+        assert!(!is_proc_file("/proc/1/status", pid, "status"));
+        assert!(!is_proc_file("/proc/self/stat", pid, "status"));
+        assert!(!is_proc_file("/etc/status", pid, "status"));
+    }
+}
@@ -0,0 +1,387 @@
+//! # Assemble Module
+//!
+//! Provides a small assembler for `x86_64` instructions in Intel syntax.
+//!
+//! The text is parsed into the mnemonic and the operands of each instruction. Encoding is left to
+//! the encoder of [`iced_x86`]: every instruction variant ([`Code`]) with a fitting mnemonic and
+//! number of operands is tried, and the shortest encoding that iced accepts is used.
+//!
+//! Instructions are separated by `;` or newlines. Operands can be registers, immediates (decimal or
+//! hexadecimal with `0x` prefix, optionally negative) and memory operands like
+//! `qword ptr fs:[rax+rbx*8-0x10]`. The size of a memory operand (`byte`, `word`, `dword`, `qword`,
+//! ...) only needs to be given if it cannot be derived from the other operands. The `ptr` keyword
+//! is optional, so the NASM style used by the [disassembler](crate::disassemble) works too.
+//!
+//! The operand of a relative jump or call is the absolute target address, as shown in the
+//! disassembly.
+
+use iced_x86::{Code, Encoder, Instruction, MemoryOperand, OpCodeOperandKind, Register};
+use serde::Serialize;
+
+use crate::errors::{DebuggerError, Result};
+use crate::Addr;
+
+/// Maximum length of a single `x86_64` instruction in bytes
+pub const MAX_INSTRUCTION_LEN: usize = 15;
+/// Bitness used for encoding
+const CODE_BITNESS: u32 = 64;
+
+/// Describes code that was overwritten with newly assembled instructions
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+pub struct Patch {
+    /// Address of the first patched byte
+    pub addr: Addr,
+    /// The assembled bytes that were written
+    pub bytes: Vec<u8>,
+    /// The bytes of the original instructions that were (partially) overwritten
+    pub replaced: Vec<u8>,
+}
+
+impl Patch {
+    /// Returns `true` if the patch exactly replaced whole instructions
+    ///
+    /// If this is `false`, the last overwritten instruction was cut off, or the patch is shorter
+    /// than the instructions it replaced, which usually leaves garbage behind it.
+    #[inline]
+    #[must_use]
+    pub fn len_matches(&self) -> bool {
+        self.bytes.len() == self.replaced.len()
+    }
+}
+
+/// A parsed operand of an instruction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Operand {
+    Register(Register),
+    Immediate(i64),
+    /// The memory operand and its size in bytes, if it was given explicitly
+    Memory(MemoryOperand, Option<usize>),
+}
+
+/// Assembles one or more instructions
+///
+/// # Parameters
+///
+/// * `text` - The instructions in Intel syntax, separated by `;` or newlines
+/// * `addr` - The address at which the first instruction will be placed, needed for relative
+///   jumps and calls
+///
+/// # Returns
+///
+/// * `Ok(Vec<u8>)` - The machine code of the instructions
+/// * `Err(DebuggerError)` - If an instruction could not be parsed or encoded
+///
+/// # Errors
+///
+/// This function fails if an instruction is not valid, cannot be encoded with the given operands,
+/// or if the size of a memory operand is ambiguous.
+///
+/// # Examples
+///
+/// ```
+/// use coreminer::assemble::assemble;
+/// use coreminer::addr::Addr;
+///
+/// let code = assemble("nop; nop; jmp 0x401050", Addr::from(0x401000usize)).unwrap();
+/// assert_eq!(code, [0x90, 0x90, 0xeb, 0x4c]);
+///
+/// let code = assemble("mov qword ptr [rbp-8], rax", Addr::from(0usize)).unwrap();
+/// assert_eq!(code, [0x48, 0x89, 0x45, 0xf8]);
+/// ```
+pub fn assemble(text: &str, addr: Addr) -> Result<Vec<u8>> {
+    let mut code = Vec::new();
+    for line in text.split([';', '\n']).map(str::trim) {
+        if line.is_empty() {
+            continue;
+        }
+        let bytes = assemble_instruction(line, (addr + code.len()).u64())?;
+        code.extend(bytes);
+    }
+    Ok(code)
+}
+
+/// Assembles a single instruction at `ip`
+fn assemble_instruction(line: &str, ip: u64) -> Result<Vec<u8>> {
+    let line = line.to_lowercase();
+    let (mnemonic, operands) = line
+        .split_once(char::is_whitespace)
+        .unwrap_or((line.as_str(), ""));
+    let operands = split_operands(operands)
+        .into_iter()
+        .map(parse_operand)
+        .collect::<Result<Vec<_>>>()?;
+
+    let explicit_size = operands.iter().find_map(|op| match op {
+        Operand::Memory(_, size) => *size,
+        _ => None,
+    });
+    let has_memory = operands.iter().any(|op| matches!(op, Operand::Memory(..)));
+
+    // (encoded bytes, memory size) of all variants that could encode the instruction
+    let mut candidates: Vec<(Vec<u8>, usize)> = Vec::new();
+    for code in Code::values() {
+        if format!("{:?}", code.mnemonic()).to_lowercase() != mnemonic {
+            continue;
+        }
+        let op_code = code.op_code();
+        if !op_code.is_instruction()
+            || !op_code.mode64()
+            || op_code.op_count() as usize != operands.len()
+        {
+            continue;
+        }
+        let Some(instruction) = build_instruction(code, &operands) else {
+            continue;
+        };
+        let memory_size = instruction.memory_size().size();
+        if explicit_size.is_some_and(|size| size != memory_size) {
+            continue;
+        }
+
+        let mut encoder = Encoder::new(CODE_BITNESS);
+        if encoder.encode(&instruction, ip).is_ok() {
+            candidates.push((encoder.take_buffer(), memory_size));
+        }
+    }
+
+    if has_memory && explicit_size.is_none() {
+        let first_size = candidates.first().map(|(_, size)| *size);
+        if candidates.iter().any(|(_, size)| Some(*size) != first_size) {
+            return Err(DebuggerError::AssemblyFailed(format!(
+                "the operand size of '{line}' is ambiguous, specify it like 'qword ptr [...]'"
+            )));
+        }
+    }
+
+    candidates
+        .into_iter()
+        .map(|(bytes, _)| bytes)
+        .min_by_key(Vec::len)
+        .ok_or_else(|| DebuggerError::AssemblyFailed(format!("could not encode '{line}'")))
+}
+
+/// Creates an [`Instruction`] of the variant `code` with the given operands
+///
+/// Returns [`None`] if the operands do not fit the variant.
+fn build_instruction(code: Code, operands: &[Operand]) -> Option<Instruction> {
+    use Operand::{Immediate as I, Memory as M, Register as R};
+
+    let is_branch = matches!(
+        code.op_code().op_kind(0),
+        OpCodeOperandKind::br64_1 | OpCodeOperandKind::br64_4
+    );
+    let imm32 = |v: i64| i32::try_from(v).ok();
+
+    let instruction = match *operands {
+        [] => Ok(Instruction::with(code)),
+        [I(target)] if is_branch => Instruction::with_branch(code, target as u64),
+        [R(a)] => Instruction::with1(code, a),
+        [I(a)] => Instruction::with1(code, imm32(a)?),
+        [M(a, _)] => Instruction::with1(code, a),
+        [R(a), R(b)] => Instruction::with2(code, a, b),
+        [R(a), I(b)] if b < 0 => Instruction::with2(code, a, b),
+        [R(a), I(b)] => Instruction::with2(code, a, b as u64),
+        [R(a), M(b, _)] => Instruction::with2(code, a, b),
+        [M(a, _), R(b)] => Instruction::with2(code, a, b),
+        [M(a, _), I(b)] => Instruction::with2(code, a, imm32(b)?),
+        [R(a), R(b), R(c)] => Instruction::with3(code, a, b, c),
+        [R(a), R(b), I(c)] => Instruction::with3(code, a, b, imm32(c)?),
+        [R(a), R(b), M(c, _)] => Instruction::with3(code, a, b, c),
+        [R(a), M(b, _), R(c)] => Instruction::with3(code, a, b, c),
+        [R(a), M(b, _), I(c)] => Instruction::with3(code, a, b, imm32(c)?),
+        [M(a, _), R(b), R(c)] => Instruction::with3(code, a, b, c),
+        [M(a, _), R(b), I(c)] => Instruction::with3(code, a, b, imm32(c)?),
+        _ => return None,
+    };
+    instruction.ok()
+}
+
+/// Splits the operands of an instruction at the commas
+fn split_operands(operands: &str) -> Vec<&str> {
+    let operands = operands.trim();
+    if operands.is_empty() {
+        return Vec::new();
+    }
+    operands.split(',').map(str::trim).collect()
+}
+
+/// Parses a single operand
+fn parse_operand(raw: &str) -> Result<Operand> {
+    let invalid = || DebuggerError::AssemblyFailed(format!("invalid operand: '{raw}'"));
+
+    let (size, rest) = match raw.split_once(char::is_whitespace) {
+        Some((word, rest)) => match memory_size_keyword(word) {
+            Some(size) => (Some(size), rest.trim()),
+            None => (None, raw),
+        },
+        None => (None, raw),
+    };
+    let rest = rest.strip_prefix("ptr").map_or(rest, str::trim);
+
+    if let Some(open) = rest.find('[') {
+        let segment = rest[..open].trim().trim_end_matches(':');
+        let segment = if segment.is_empty() {
+            Register::None
+        } else {
+            parse_register(segment).ok_or_else(invalid)?
+        };
+        let inner = rest[open + 1..].strip_suffix(']').ok_or_else(invalid)?;
+        let memory = parse_memory(inner, segment).ok_or_else(invalid)?;
+        return Ok(Operand::Memory(memory, size));
+    }
+    if size.is_some() {
+        return Err(invalid());
+    }
+
+    if let Some(register) = parse_register(rest) {
+        Ok(Operand::Register(register))
+    } else {
+        parse_number(rest)
+            .map(Operand::Immediate)
+            .ok_or_else(invalid)
+    }
+}
+
+/// Parses the inside of the brackets of a memory operand, like `rax+rbx*8-0x10`
+fn parse_memory(inner: &str, segment: Register) -> Option<MemoryOperand> {
+    let mut base = Register::None;
+    let mut index = Register::None;
+    let mut scale = 1;
+    let mut displacement: i64 = 0;
+
+    // insert a separator before each sign to keep the sign with its term
+    let inner = inner.replace('-', "+-");
+    for term in inner.split('+').map(str::trim) {
+        if term.is_empty() {
+            continue;
+        }
+        if let Some((a, b)) = term.split_once('*') {
+            let (register, factor) = match parse_register(a.trim()) {
+                Some(register) => (register, b.trim()),
+                None => (parse_register(b.trim())?, a.trim()),
+            };
+            if index != Register::None {
+                return None;
+            }
+            index = register;
+            scale = u32::try_from(parse_number(factor)?).ok()?;
+        } else if let Some(register) = parse_register(term) {
+            if base == Register::None {
+                base = register;
+            } else if index == Register::None {
+                index = register;
+            } else {
+                return None;
+            }
+        } else {
+            displacement = displacement.checked_add(parse_number(term)?)?;
+        }
+    }
+
+    // an absolute address needs a displacement size of 8 for 64 bit addressing, iced still uses
+    // 32 bits if the address fits
+    let displ_size = if base == Register::None && index == Register::None {
+        8
+    } else {
+        u32::from(displacement != 0)
+    };
+
+    Some(MemoryOperand::new(
+        base,
+        index,
+        scale,
+        displacement,
+        displ_size,
+        false,
+        segment,
+    ))
+}
+
+/// Parses the name of a register, like `rax` or `xmm0`
+fn parse_register(raw: &str) -> Option<Register> {
+    Register::values()
+        .filter(|r| *r != Register::None)
+        .find(|r| format!("{r:?}").eq_ignore_ascii_case(raw))
+}
+
+/// Parses a number, either decimal or hexadecimal with a `0x` prefix, optionally negative
+fn parse_number(raw: &str) -> Option<i64> {
+    let (negative, raw) = match raw.strip_prefix('-') {
+        Some(rest) => (true, rest.trim()),
+        None => (false, raw),
+    };
+    let value = match raw.strip_prefix("0x") {
+        Some(hex) => u64::from_str_radix(hex, 16).ok()?,
+        None => raw.parse::<u64>().ok()?,
+    } as i64;
+    Some(if negative {
+        value.wrapping_neg()
+    } else {
+        value
+    })
+}
+
+/// Returns the size in bytes for a memory size keyword like `qword`
+fn memory_size_keyword(word: &str) -> Option<usize> {
+    Some(match word {
+        "byte" => 1,
+        "word" => 2,
+        "dword" => 4,
+        "fword" => 6,
+        "qword" => 8,
+        "tword" | "tbyte" => 10,
+        "xmmword" | "oword" => 16,
+        "ymmword" => 32,
+        "zmmword" => 64,
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn asm(text: &str) -> Vec<u8> {
+        assemble(text, Addr::from(0x1000usize)).expect("could not assemble")
+    }
+
+    #[test]
+    fn test_assemble() {
+        assert_eq!(asm("nop"), [0x90]);
+        assert_eq!(asm("ret"), [0xc3]);
+        assert_eq!(asm("int3"), [0xcc]);
+        assert_eq!(asm("xor eax, eax"), [0x31, 0xc0]);
+        assert_eq!(asm("mov eax, 42"), [0xb8, 0x2a, 0, 0, 0]);
+        assert_eq!(asm("add rsp, 0x10"), [0x48, 0x83, 0xc4, 0x10]);
+        assert_eq!(asm("sub rsp, -8"), [0x48, 0x83, 0xec, 0xf8]);
+        assert_eq!(
+            asm("mov rax, 0x1122334455667788"),
+            [0x48, 0xb8, 0x88, 0x77, 0x66, 0x55, 0x44, 0x33, 0x22, 0x11]
+        );
+        assert_eq!(asm("push rbp;mov rbp, rsp"), [0x55, 0x48, 0x89, 0xe5]);
+        assert_eq!(
+            asm("lea rax, [rbx+rcx*8+0x10]"),
+            [0x48, 0x8d, 0x44, 0xcb, 0x10]
+        );
+        assert_eq!(
+            asm("mov rcx, qword ptr fs:[0x28]"),
+            [0x64, 0x48, 0x8b, 0x0c, 0x25, 0x28, 0, 0, 0]
+        );
+        // NASM style as produced by the disassembler
+        assert_eq!(asm("mov dword [rbp-4], 0"), [0xc7, 0x45, 0xfc, 0, 0, 0, 0]);
+        // relative branches take the absolute target
+        assert_eq!(asm("jmp 0x1000"), [0xeb, 0xfe]);
+        assert_eq!(asm("call 0x2000"), [0xe8, 0xfb, 0x0f, 0, 0]);
+    }
+
+    #[test]
+    fn test_assemble_errors() {
+        let addr = Addr::from(0usize);
+        assert!(assemble("notaninstruction", addr).is_err());
+        assert!(assemble("mov rax", addr).is_err());
+        assert!(assemble("mov eax, rbx", addr).is_err());
+        // the size of the memory operand is unknown
+        assert!(assemble("inc [rax]", addr).is_err());
+        assert!(assemble("inc qword ptr [rax]", addr).is_ok());
+    }
+}
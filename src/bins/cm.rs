@@ -1,8 +1,13 @@
 use std::path::PathBuf;
 
-use coreminer::debugger::Debugger;
+use coreminer::debugger::{Debugger, LaunchBreak, QuitAction};
 use coreminer::errors::DebuggerError;
+use coreminer::traceopts::PtraceOptions;
 use coreminer::ui::cli::CliUi;
+use coreminer::ui::transcript::{Recorder, Replayer};
+#[cfg(feature = "tui")]
+use coreminer::ui::tui::{TuiLog, TuiUi};
+use coreminer::ui::DebuggerUI;
 
 use clap::Parser;
 use tracing::trace;
@@ -34,6 +39,74 @@ struct Args {
     /// and can be run with the 'run' command without arguments.
     default_executable: Option<PathBuf>,
 
+    #[arg(long, value_name = "FILE")]
+    /// Open the core dump FILE of a crashed process at startup
+    ///
+    /// The process is inspected post-mortem, the executable is the one given, or the one
+    /// recorded in the core dump.
+    core: Option<PathBuf>,
+
+    #[arg(long, value_name = "entry|main")]
+    /// Stop newly run programs at their entry point or main function
+    ///
+    /// Without this, programs are stopped right after they were started, inside
+    /// the dynamic loader.
+    break_at: Option<LaunchBreak>,
+
+    #[arg(long)]
+    /// Kill the debuggee when coreminer exits or crashes
+    exit_kill: bool,
+
+    #[arg(long)]
+    /// Connect the stdin of newly run programs to a pipe instead of the terminal
+    ///
+    /// The input for the program is then sent with the 'stdin' command, so it does not
+    /// compete with coreminer for what is typed into the terminal.
+    pipe_stdin: bool,
+
+    #[arg(long, value_name = "kill|detach", default_value = "kill")]
+    /// What to do with a running debuggee when quitting
+    on_quit: QuitAction,
+
+    #[arg(short = 'x', long = "command", value_name = "FILE")]
+    /// Execute the commands in FILE at startup, one per line
+    ///
+    /// Can be given multiple times, the files are executed in order. Empty lines and
+    /// lines starting with '#' are ignored. Once all commands were executed, or one of
+    /// them failed, the commands are read from the terminal again.
+    commands: Vec<PathBuf>,
+
+    #[arg(short, long, requires = "commands")]
+    /// Only execute the commands given with --command and quit afterwards
+    ///
+    /// Nothing is read from the terminal, so this can run in CI. The exit code is 1 if a
+    /// command failed, for example an 'assert' command. Otherwise, it is the exit code of
+    /// the debuggee, 128 plus the signal number if the debuggee was terminated by a signal,
+    /// or 0 if it did not terminate.
+    batch: bool,
+
+    #[arg(long, value_name = "FILE")]
+    /// Record every command and its feedback into the transcript FILE
+    ///
+    /// The transcript has one JSON object per line and can be replayed with --replay.
+    record: Option<PathBuf>,
+
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["commands", "record"])]
+    /// Replay the commands of the transcript FILE instead of reading commands
+    ///
+    /// Each command is executed again and its feedback is compared with the recorded one.
+    /// The differences are printed, and the exit code is 1 if there were any, else 0.
+    replay: Option<PathBuf>,
+
+    #[cfg(feature = "tui")]
+    #[arg(long, conflicts_with_all = ["batch", "replay", "quiet", "logfile"])]
+    /// Use a full-screen interface with panes for the disassembly, registers and stack
+    ///
+    /// The commands are the same as without it. Since the debuggee shares the terminal,
+    /// --pipe-stdin is recommended, and Ctrl-L redraws the screen after the debuggee wrote
+    /// to it.
+    tui: bool,
+
     #[arg(short, long)]
     /// Do not log anything
     quiet: bool,
@@ -46,16 +119,109 @@ struct Args {
 fn main() -> Result<(), DebuggerError> {
     let args = Args::parse();
 
+    #[cfg(feature = "tui")]
+    if args.tui {
+        return run_tui(&args);
+    }
+
     if !args.quiet {
-        setup(args.logfile);
+        setup(args.logfile.clone());
     }
 
-    let ui = CliUi::build(args.default_executable.as_deref())?;
-    let mut debug: Debugger<CliUi> = Debugger::build(ui)?;
+    if let Some(transcript) = &args.replay {
+        let exit_code = run(Replayer::load(transcript)?, &args, |replayer| {
+            for divergence in replayer.divergences() {
+                eprintln!("diverged at {divergence}");
+            }
+            println!(
+                "replayed {} commands, {} diverged",
+                replayer.replayed(),
+                replayer.divergences().len()
+            );
+            i32::from(!replayer.divergences().is_empty())
+        })?;
+        std::process::exit(exit_code);
+    }
+
+    let mut ui = cli_ui(&args)?;
+    ui.set_batch(args.batch);
+    let exit_code = run_recorded(ui, &args, CliUi::exit_code)?;
+
+    if args.batch {
+        std::process::exit(exit_code);
+    }
+
+    Ok(())
+}
+
+/// Runs a debug session in the full-screen interface, with the log shown in its log pane
+#[cfg(feature = "tui")]
+fn run_tui(args: &Args) -> Result<(), DebuggerError> {
+    human_panic::setup_panic!();
+
+    let log = TuiLog::default();
+    let writer = log.clone();
+    // traces would push the feedback out of the log pane, even in debug builds
+    let subscriber = tracing_subscriber::fmt()
+        .with_max_level(tracing::Level::INFO)
+        .without_time()
+        .with_file(false)
+        .with_target(false)
+        .with_ansi(false)
+        .with_writer(move || writer.clone())
+        .finish();
+    tracing::subscriber::set_global_default(subscriber).expect("could not setup logger");
+
+    let ui = TuiUi::build(cli_ui(args)?, log)?;
+    run_recorded(ui, args, |tui| tui.cli().exit_code())?;
+    Ok(())
+}
+
+/// Creates the CLI, with the commands of the files given with --command
+fn cli_ui(args: &Args) -> Result<CliUi, DebuggerError> {
+    let mut ui = CliUi::build(args.default_executable.as_deref())?;
+    // sourced in reverse, as each file is put in front of the commands of the previous ones
+    for script in args.commands.iter().rev() {
+        ui.source(script)?;
+    }
+    Ok(ui)
+}
+
+/// Like [`run`], but records the session into a transcript if --record was given
+fn run_recorded<UI: DebuggerUI>(
+    ui: UI,
+    args: &Args,
+    exit_code: impl FnOnce(&UI) -> i32,
+) -> Result<i32, DebuggerError> {
+    match &args.record {
+        Some(transcript) => run(Recorder::create(ui, transcript)?, args, |recorder| {
+            exit_code(recorder.inner())
+        }),
+        None => run(ui, args, exit_code),
+    }
+}
+
+/// Runs a debug session with `ui` and returns the exit code that `exit_code` derives from it
+fn run<UI: DebuggerUI>(
+    ui: UI,
+    args: &Args,
+    exit_code: impl FnOnce(&UI) -> i32,
+) -> Result<i32, DebuggerError> {
+    let mut debug: Debugger<UI> = Debugger::build(ui)?;
+    debug.set_launch_break(args.break_at)?;
+    debug.set_quit_action(args.on_quit)?;
+    debug.set_stdin_pipe(args.pipe_stdin)?;
+    debug.set_ptrace_options(PtraceOptions {
+        exit_kill: args.exit_kill,
+        ..Default::default()
+    })?;
+    if let Some(core) = &args.core {
+        debug.open_core(core, args.default_executable.as_deref())?;
+    }
     debug.run_debugger()?;
     debug.cleanup()?;
 
-    Ok(())
+    Ok(exit_code(debug.ui()))
 }
 
 fn setup(logfile: Option<PathBuf>) {
@@ -6,6 +6,7 @@ use coreminer::debugger::Debugger;
 use coreminer::errors::DebuggerError;
 use coreminer::feedback::Feedback;
 use coreminer::ui::json::{Input, JsonUI};
+use coreminer::ui::mi::MiUI;
 
 use clap::Parser;
 use coreminer::feedback::Status;
@@ -56,6 +57,20 @@ struct Args {
     #[arg(long)]
     /// Log into a logfile instead of stderr
     logfile: Option<PathBuf>,
+
+    #[arg(long)]
+    /// Stream long lists in responses in chunks of this many items
+    ///
+    /// Each chunk is sent as a message of its own, followed by the response with the list left
+    /// empty. Without this, every response is sent as one line.
+    chunk_size: Option<usize>,
+
+    #[arg(long, default_value = "json", value_parser = ["json", "mi"])]
+    /// The protocol to talk on stdin and stdout
+    ///
+    /// "json" is the native protocol of cmserve. "mi" is a subset of the GDB Machine Interface,
+    /// for frontends that already drive GDB with `--interpreter=mi`.
+    interpreter: String,
 }
 
 fn main() -> Result<(), DebuggerError> {
@@ -75,8 +90,19 @@ fn main() -> Result<(), DebuggerError> {
         exit(0);
     }
 
-    let ui = JsonUI::build()?;
+    if args.interpreter == "mi" {
+        let mut debug: Debugger<_> = Debugger::build(MiUI::build()?)?;
+        // the stdin of cmserve carries the commands, debuggees get their input with SendStdin
+        debug.set_stdin_pipe(true)?;
+        debug.run_debugger()?;
+        debug.cleanup()?;
+        return Ok(());
+    }
+
+    let mut ui = JsonUI::build()?;
+    ui.set_chunk_size(args.chunk_size)?;
     let mut debug: Debugger<_> = Debugger::build(ui)?;
+    debug.set_stdin_pipe(true)?;
     debug.run_debugger()?;
     debug.cleanup()?;
 
@@ -86,25 +112,151 @@ fn main() -> Result<(), DebuggerError> {
 fn example_statuses() {
     let statuses: &[Status] = &[
         Status::StepOut,
+        Status::StepLine,
+        Status::NextLine,
+        Status::RunUntil(Addr::from(94558432710705usize)),
+        Status::StartRecording(10),
+        Status::StopRecording,
+        Status::ReverseStep,
+        Status::ReverseContinue,
+        Status::Checkpoint,
+        Status::Restore(1),
+        Status::DelCheckpoint(1),
+        Status::CallFunction("fib".to_string(), vec![10]),
+        Status::StepRange(
+            Addr::from(94558432710665usize),
+            Addr::from(94558432710705usize),
+        ),
         Status::DebuggerQuit,
+        Status::SetQuitAction(coreminer::debugger::QuitAction::Detach),
+        Status::SetSchedulerLocking(coreminer::debugger::SchedulerLocking::Step),
+        Status::SetFollowFork(coreminer::debugger::FollowFork::Child),
+        Status::OpenCore(
+            Path::new("/tmp/core.1234").into(),
+            Some(Path::new("/tmp/crashed").into()),
+        ),
+        Status::SetLimits(coreminer::limits::Limits {
+            max_read_size: 1048576,
+            ..Default::default()
+        }),
+        Status::Detach,
         Status::Continue,
+        Status::ContinueWithSignal(10),
+        Status::ContinueTimeout(2000),
+        Status::Profile(5000, 100),
+        Status::ExportFlamegraph(Path::new("/tmp/profile.svg").into(), true),
+        Status::ExportTrace(Path::new("/tmp/session.trace.json").into()),
+        Status::WhyStopped,
+        Status::SendSignal(15),
         Status::ProcMap,
+        Status::ListModules,
+        Status::AddSymbolFile(
+            Path::new("/tmp/firmware.elf").into(),
+            Addr::from(139637976727552usize),
+        ),
+        Status::ShowEnviron,
+        Status::ListFileDescriptors,
+        Status::ProcessInfo,
+        Status::ShowAuxv,
+        Status::ListThreads,
+        Status::ListGot,
+        Status::HookGot("puts".to_string(), Addr::from(94558432710665usize)),
+        Status::InjectCode(vec![0xb8, 0x2a, 0x00, 0x00, 0x00], true),
+        Status::DumpModule(Some("libc".to_string()), Path::new("/tmp/libc.dump").into()),
+        Status::GenerateCore(Path::new("/tmp/core.snapshot").into()),
+        Status::FindXrefs(Addr::from(94558432710665usize).into()),
+        Status::Record(
+            Path::new("/bin/ls").into(),
+            vec![c"-la".into()],
+            Path::new("/tmp/ls-trace").into(),
+        ),
+        Status::Replay(Path::new("/tmp/ls-trace").into(), 1234),
+        Status::ReplayResume(coreminer::gdbremote::Resume::ReverseContinue),
+        Status::ReplayReadMem(Addr::from(140726042009392usize), 16),
+        Status::ExportCallGraph(Some(Path::new("/tmp/calls.dot").into())),
+        Status::ExportBacktraceGraph(None),
+        Status::SetUprobe(Addr::from(94558432710665usize), true),
+        Status::ReadUprobes,
+        Status::StartPerfCounters,
+        Status::ReadPerfCounters,
+        Status::PatchAsm(
+            Addr::from(4198464usize),
+            "nop; nop; jmp 0x401050".to_string(),
+        ),
         #[cfg(feature = "plugins")]
         Status::PluginSetEnable(PluginIDOwned::from("foobar"), true),
         #[cfg(feature = "plugins")]
         Status::PluginGetStatus(PluginIDOwned::from("foobar")),
-        Status::SetBreakpoint(Addr::from(21958295usize)),
+        Status::SetBreakpoint(Addr::from(21958295usize).into()),
+        Status::SetHardwareBreakpoint(Addr::from(21958295usize).into()),
+        Status::SaveBreakpoints(Path::new("/tmp/breakpoints.json").into()),
+        Status::LoadBreakpoints(Path::new("/tmp/breakpoints.json").into()),
+        Status::SaveSession(Path::new("/tmp/session.json").into()),
+        Status::RestoreSession(Path::new("/tmp/session.json").into()),
+        Status::SetWatchpoint(Addr::from(140726042009392usize).into(), 32),
+        Status::SetWatchCondition(
+            Addr::from(140726042009392usize),
+            Some("new == 0".parse().unwrap()),
+        ),
+        Status::SetPageGuard(Addr::from(139888745447440usize), 4194304),
+        Status::SetAntiDebug(coreminer::antidebug::AntiDebugConfig::all()),
+        Status::SetPtraceOptions(coreminer::traceopts::PtraceOptions {
+            exit_kill: true,
+            trace_exit: true,
+            ..Default::default()
+        }),
+        Status::EnableBreakpoint(Addr::from(0x401136usize).into()),
+        Status::DisableBreakpoint("main".parse().unwrap()),
+        Status::SetLogBreakpoint(
+            "main".parse().unwrap(),
+            Some("argc = {argc}, rsi = {rsi}".parse().unwrap()),
+        ),
+        Status::ListBreakpoints,
+        Status::AddAutoDisplay("argc".to_string()),
+        Status::DelAutoDisplay(1),
+        Status::CatchSyscalls(true),
+        Status::CatchSyscall(vec!["openat".to_string(), "write".to_string()]),
+        Status::SetSyscallFilter(Some(coreminer::seccomp::SyscallFilter::new(vec![
+            0, 1, 257,
+        ]))),
+        Status::CatchMemory(coreminer::catchpoint::MemoryCatchConfig::all()),
+        Status::CatchPanics(true),
+        Status::SetSyscallArg(1, 94558432710665),
+        Status::SetSyscallReturn(-2),
         Status::SetRegister(coreminer::Register::r9, 133719),
         Status::DumpRegisters,
         Status::Backtrace,
-        Status::WriteMem(Addr::from(9218098521usize), 0xff),
-        Status::ReadMem(Addr::from(9218098521usize)),
+        Status::SelectFrame(1),
+        Status::ReadFrameVariable(1, "count".to_string()),
+        Status::WriteFrameVariable(1, "count".to_string(), 42),
+        Status::WriteMem(Addr::from(9218098521usize).into(), 0xff),
+        Status::ReadMem(Addr::from(9218098521usize).into()),
         Status::Run(
             Path::new("/bin/ls").into(),
             vec![c"/etc".into(), c"-la".into()],
         ),
+        Status::Attach(1337),
+        Status::SetLaunchBreak(Some(coreminer::debugger::LaunchBreak::Main)),
+        Status::SetLaunchEnv("MALLOC_CHECK_".to_string(), Some("3".to_string())),
+        Status::PreloadLibrary("./libhook.so".to_string()),
+        Status::AddLibraryPath("/opt/lib".to_string()),
+        Status::SetStdinPipe(true),
+        Status::SendStdin(b"hello\n".to_vec()),
+        Status::CloseStdin,
+        Status::GetLaunchEnv,
+        Status::SetWriteVerification(coreminer::debugger::WriteVerification::all()),
         Status::GetSymbolsByName("main".to_string()),
-        Status::DisassembleAt(Addr::from(1337139usize), 50, false),
+        Status::ListFunctions("str*".to_string()),
+        Status::ListTypes("*_t".to_string()),
+        Status::ListSource(Some("hello.c:12".parse().unwrap())),
+        Status::ListVariables("std".to_string(), Some("libc".to_string())),
+        Status::ResolveAddr("main+0x10".parse().unwrap()),
+        Status::SetBreakpoint("hello.c:12".parse().unwrap()),
+        Status::DisassembleAt(Addr::from(1337139usize).into(), 50, false),
+        Status::ReadMem("$rsp+8*3".parse().unwrap()),
+        Status::ReadMemRange("$rsp".parse().unwrap(), 0x40),
+        Status::SetConvenience("base".to_string(), "$1+0x10".parse().unwrap()),
+        Status::ShowValues,
     ];
 
     for s in statuses {
@@ -118,14 +270,112 @@ fn example_statuses() {
 fn example_feedbacks() {
     let feedbacks: &[Feedback] = &[
         Feedback::Ok,
+        Feedback::Returned {
+            function: "fib".to_string(),
+            value: coreminer::variable::VariableValue::Other(55),
+        },
+        Feedback::Checkpoint {
+            id: 1,
+            addr: Addr::from(94558432710705usize),
+            size: 1056768,
+        },
         Feedback::Word(921589215 as Word),
+        Feedback::Exit(coreminer::feedback::ExitStatus::Exited(0)),
+        Feedback::Exit(coreminer::feedback::ExitStatus::Signaled {
+            signal: 11,
+            core_dumped: true,
+        }),
+        Feedback::Stopped(
+            coreminer::feedback::StopReason::Breakpoint {
+                addr: Addr::from(94558432710665usize),
+                hardware: false,
+            }
+            .into(),
+        ),
+        Feedback::Stopped(coreminer::feedback::StopInfo {
+            reason: coreminer::feedback::StopReason::Signal(11),
+            displays: vec![coreminer::feedback::AutoDisplay {
+                number: 1,
+                expression: "argc".to_string(),
+                value: Ok(coreminer::variable::VariableValue::Other(1)),
+            }],
+        }),
         Feedback::Word(Word::MAX),
         Feedback::Word(Word::MIN),
+        Feedback::Recorded {
+            index: 1,
+            feedback: Box::new(Feedback::Word(0x7fff_0000)),
+        },
+        Feedback::Memory(coreminer::memread::MemoryRead {
+            addr: Addr::from(0x7ffc_ffff_eff8_usize),
+            bytes: vec![0x2a, 0, 0, 0, 0, 0, 0, 0],
+            unreadable: Some(coreminer::memread::UnreadableRange {
+                addr: Addr::from(0x7ffd_0000_0000_usize),
+                len: 8,
+                reason: "Input/output error (os error 5)".to_string(),
+            }),
+        }),
         Feedback::Variable(coreminer::variable::VariableValue::Bytes(vec![
             19, 13, 13, 13, 17,
         ])),
+        Feedback::Variable(coreminer::variable::VariableValue::Signed(-3)),
+        Feedback::Variable(coreminer::variable::VariableValue::Float(1.5)),
+        Feedback::Variable(coreminer::variable::VariableValue::OptimizedOut),
+        Feedback::Variable(coreminer::variable::VariableValue::Struct {
+            fields: vec![
+                (
+                    "x".to_string(),
+                    coreminer::variable::VariableValue::Signed(3),
+                ),
+                (
+                    "y".to_string(),
+                    coreminer::variable::VariableValue::Signed(-4),
+                ),
+            ],
+        }),
+        Feedback::Variable(coreminer::variable::VariableValue::Array(vec![
+            coreminer::variable::VariableValue::Unsigned(1),
+            coreminer::variable::VariableValue::Unsigned(2),
+        ])),
+        Feedback::Variable(coreminer::variable::VariableValue::Pointer {
+            address: Addr::from(0x5555_5555_9000_usize),
+            pointee: coreminer::variable::Pointee::Value(Box::new(
+                coreminer::variable::VariableValue::Signed(42),
+            )),
+        }),
+        Feedback::Variable(coreminer::variable::VariableValue::Enum {
+            name: "Color::Red".to_string(),
+            value: 2,
+            fields: Vec::new(),
+        }),
+        Feedback::RrRecording(coreminer::rr::Recording {
+            trace_dir: Path::new("/tmp/ls-trace").into(),
+            exit: coreminer::feedback::ExitStatus::Exited(0),
+        }),
+        Feedback::ReplayStopped(coreminer::gdbremote::StopReply::Signal {
+            signal: 5,
+            pc: Some(Addr::from(94558432710665usize)),
+        }),
+        Feedback::ForkEvent(coreminer::feedback::ForkEvent {
+            parent: 4242,
+            child: 4243,
+            vfork: false,
+            followed: 4243,
+        }),
+        Feedback::CoreGenerated(coreminer::coredump::GeneratedCore {
+            pid: 4242,
+            output: Path::new("/tmp/core.snapshot").into(),
+            threads: 1,
+            segments: 23,
+            size: 4_337_664,
+        }),
         Feedback::Error(DebuggerError::BreakpointIsAlreadyEnabled),
         Feedback::Error(DebuggerError::UnimplementedRegister(1337)),
+        Feedback::Error(DebuggerError::LimitExceeded(
+            coreminer::limits::LimitKind::ReadSize,
+            4294967296,
+            16777216,
+        )),
         Feedback::Error(DebuggerError::Json(serde_json::Error::custom("test err"))),
         #[cfg(feature = "plugins")]
         Feedback::PluginStatus(Some(false)),
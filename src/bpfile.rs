@@ -0,0 +1,202 @@
+//! # Breakpoint File Module
+//!
+//! Provides saving [Breakpoints](crate::breakpoint::Breakpoint) to a file and loading them again
+//! in a later session.
+//!
+//! The addresses of the debuggee change between sessions because of address space layout
+//! randomization, so saving raw addresses is not useful. Instead, each breakpoint is saved with a
+//! [`BreakpointAnchor`] that describes its location independently of the load address: the
+//! function or symbol it is in, or the module it is in. When loading the file, the anchors are
+//! resolved against the load addresses of the new session.
+//!
+//! The file is a JSON document of a [`BreakpointFile`], so it can also be written or edited by
+//! hand or by other tools. Saving and loading is done with
+//! [`Debugger::save_breakpoints`](crate::debugger::Debugger::save_breakpoints) and
+//! [`Debugger::load_breakpoints`](crate::debugger::Debugger::load_breakpoints).
+
+use std::fmt::{self, Display};
+use std::path::Path;
+
+use object::{Object, ObjectSymbol, SymbolKind};
+use serde::{Deserialize, Serialize};
+
+use crate::errors::Result;
+use crate::logpoint::LogFormat;
+use crate::memorymap::ProcessMemoryMap;
+use crate::Addr;
+
+/// Describes where a saved [`Breakpoint`](crate::breakpoint::Breakpoint) is, independently of the
+/// load address
+///
+/// # Examples
+///
+/// ```
+/// use coreminer::bpfile::BreakpointAnchor;
+///
+/// let anchor = BreakpointAnchor::Symbol {
+///     name: "main".to_string(),
+///     offset: 0x1b,
+/// };
+/// assert_eq!(anchor.to_string(), "main+0x1b");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum BreakpointAnchor {
+    /// An offset into a function of the debug information or a symbol of the executable
+    Symbol {
+        /// Name of the function or symbol
+        name: String,
+        /// Offset from the start of the function or symbol
+        offset: u64,
+    },
+    /// An offset from the load address of a mapped file, like a shared library
+    Module {
+        /// Path of the module, as shown in the memory map
+        path: String,
+        /// Offset from the lowest address at which the module is mapped
+        offset: u64,
+    },
+    /// An absolute address, for memory that does not belong to any module
+    ///
+    /// This is only correct if the memory is at the same address in the next session.
+    Absolute(Addr),
+}
+
+/// A [`Breakpoint`](crate::breakpoint::Breakpoint) in a [`BreakpointFile`]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct SavedBreakpoint {
+    /// Where the breakpoint is
+    pub anchor: BreakpointAnchor,
+    /// Whether it is a hardware breakpoint
+    pub hardware: bool,
+    /// Whether it was disabled, it is set and then disabled again when it is restored
+    #[serde(default)]
+    pub disabled: bool,
+    /// The message format, if it is a logging breakpoint
+    #[serde(default)]
+    pub log: Option<LogFormat>,
+}
+
+/// The contents of a file with saved [Breakpoints](crate::breakpoint::Breakpoint)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BreakpointFile {
+    /// The saved breakpoints
+    pub breakpoints: Vec<SavedBreakpoint>,
+}
+
+impl BreakpointFile {
+    /// Reads a [`BreakpointFile`] from `path`
+    ///
+    /// # Errors
+    ///
+    /// This function fails if the file cannot be read or is not a valid breakpoint file.
+    pub fn read(path: impl AsRef<Path>) -> Result<Self> {
+        let data = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&data)?)
+    }
+
+    /// Writes the [`BreakpointFile`] to `path`, replacing the file if it exists
+    ///
+    /// # Errors
+    ///
+    /// This function fails if the file cannot be written.
+    pub fn write(&self, path: impl AsRef<Path>) -> Result<()> {
+        let data = serde_json::to_string_pretty(self)?;
+        std::fs::write(path, data)?;
+        Ok(())
+    }
+}
+
+impl Display for BreakpointAnchor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Symbol { name, offset } => write!(f, "{name}+{offset:#x}"),
+            Self::Module { path, offset } => write!(f, "{path}+{offset:#x}"),
+            Self::Absolute(addr) => write!(f, "{addr}"),
+        }
+    }
+}
+
+/// Creates a [`BreakpointAnchor::Module`] for `addr`, if it is in a mapped file
+#[must_use]
+pub fn module_anchor(map: &ProcessMemoryMap, addr: Addr) -> Option<BreakpointAnchor> {
+    let path = map
+        .regions
+        .iter()
+        .find(|r| r.start_address <= addr && addr < r.end_address)?
+        .path
+        .as_deref()
+        .filter(|p| p.starts_with('/'))?;
+    let base = module_base(map, path)?;
+
+    Some(BreakpointAnchor::Module {
+        path: path.to_string(),
+        offset: (addr - base).u64(),
+    })
+}
+
+/// Gets the lowest address at which the file at `path` is mapped
+#[must_use]
+pub fn module_base(map: &ProcessMemoryMap, path: &str) -> Option<Addr> {
+    map.lowest_address_of(path)
+}
+
+/// Finds the code symbol of an ELF file that contains `addr`
+///
+/// # Parameters
+///
+/// * `obj` - The ELF file
+/// * `addr` - An address as in the ELF file, without the load bias
+///
+/// # Returns
+///
+/// The name of the symbol and the offset of `addr` from its start.
+#[must_use]
+pub fn symbol_containing(obj: &object::File<'_>, addr: u64) -> Option<(String, u64)> {
+    obj.symbols()
+        .chain(obj.dynamic_symbols())
+        .filter(|s| s.kind() == SymbolKind::Text && s.address() != 0)
+        .find(|s| s.address() <= addr && addr < s.address() + s.size().max(1))
+        .and_then(|s| Some((s.name().ok()?.to_string(), addr - s.address())))
+}
+
+/// Gets the address of the code symbol `name` of an ELF file, without the load bias
+#[must_use]
+pub fn symbol_address(obj: &object::File<'_>, name: &str) -> Option<u64> {
+    obj.symbols()
+        .chain(obj.dynamic_symbols())
+        .filter(|s| s.kind() == SymbolKind::Text && s.address() != 0)
+        .find(|s| s.name() == Ok(name))
+        .map(|s| s.address())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_module_anchor_roundtrip() {
+        let maps = proc_maps::get_process_maps(std::process::id() as i32).unwrap();
+        let map = ProcessMemoryMap::from(maps);
+
+        let addr = Addr::from(test_module_anchor_roundtrip as fn() as usize);
+        let anchor = module_anchor(&map, addr).expect("test code is not in a module");
+        let resolved = match &anchor {
+            BreakpointAnchor::Module { path, offset } => {
+                module_base(&map, path).map(|base| base + *offset as usize)
+            }
+            _ => None,
+        };
+        assert_eq!(resolved, Some(addr));
+
+        let file = BreakpointFile {
+            breakpoints: vec![SavedBreakpoint {
+                anchor: anchor.clone(),
+                hardware: true,
+                disabled: true,
+                log: Some("x = {x}".parse().unwrap()),
+            }],
+        };
+        let json = serde_json::to_string(&file).unwrap();
+        assert_eq!(serde_json::from_str::<BreakpointFile>(&json).unwrap(), file);
+    }
+}
@@ -9,13 +9,25 @@
 //! When a breakpoint is hit, the debugger can then restore the original instruction, single-step
 //! the process to execute that instruction, and then replace the breakpoint before continuing
 //! execution.
+//!
+//! Alternatively, a breakpoint can be a hardware breakpoint, which uses a debug register of the
+//! processor instead of an `INT3` (see [`crate::debugreg`]). Hardware breakpoints leave the code
+//! untouched, which is needed for code in read-only memory or code that checks itself for
+//! modifications, but there can only be
+//! [`DEBUG_ADDR_REGISTERS`](crate::debugreg::DEBUG_ADDR_REGISTERS) of them at a time. They stop
+//! the process *before* the instruction is executed, so the instruction pointer is at the
+//! breakpoint address and not behind it.
+
+use std::fmt::{self, Display};
 
 use nix::unistd::Pid;
 use serde::Serialize;
 use tracing::{error, trace};
 
+use crate::debugreg;
 use crate::errors::{DebuggerError, Result};
-use crate::{mem_read_word, mem_write_word, Addr, Word};
+use crate::logpoint::LogFormat;
+use crate::{mem_read_word, mem_write_word, mem_write_word_verified, Addr, Word};
 
 /// Mask to set all bits to 1 (using two's complement)
 pub const MASK_ALL: Word = Word::MAX;
@@ -71,6 +83,11 @@ pub struct Breakpoint {
     #[serde(serialize_with = "ser_pid")]
     pid: Pid,
     saved_data: Option<u8>,
+    hardware_slot: Option<usize>,
+    hardware_enabled: bool,
+    verify: bool,
+    hits: usize,
+    log: Option<LogFormat>,
 }
 
 impl Breakpoint {
@@ -98,9 +115,46 @@ impl Breakpoint {
             pid,
             addr,
             saved_data: None,
+            hardware_slot: None,
+            hardware_enabled: false,
+            verify: true,
+            hits: 0,
+            log: None,
         }
     }
 
+    /// Creates a new, initially disabled hardware breakpoint at the specified address
+    ///
+    /// Instead of writing `INT3` into the code, the breakpoint uses the debug address register
+    /// `slot`, which must not be used by another hardware breakpoint of the process.
+    ///
+    /// # Parameters
+    ///
+    /// * `pid` - Process ID of the target process
+    /// * `addr` - Address where the breakpoint should be set
+    /// * `slot` - The debug address register to use, `0..4`
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use coreminer::breakpoint::Breakpoint;
+    /// use coreminer::addr::Addr;
+    /// use nix::unistd::Pid;
+    ///
+    /// // Use DR0 for a breakpoint at address 0x000055dd73ea3fb8 for process with PID 1234
+    /// let mut bp = Breakpoint::new_hardware(Pid::from_raw(1234), Addr::from(0x000055dd73ea3fb8usize), 0);
+    /// assert!(bp.is_hardware());
+    /// bp.enable().unwrap();
+    /// assert!(bp.is_enabled());
+    /// assert_eq!(bp.saved_data(), None);
+    /// ```
+    #[must_use]
+    pub fn new_hardware(pid: Pid, addr: Addr, slot: usize) -> Self {
+        let mut bp = Self::new(pid, addr);
+        bp.hardware_slot = Some(slot);
+        bp
+    }
+
     /// Checks if the breakpoint is currently enabled
     ///
     /// # Returns
@@ -111,7 +165,70 @@ impl Breakpoint {
     #[inline]
     #[must_use]
     pub fn is_enabled(&self) -> bool {
-        self.saved_data.is_some()
+        self.saved_data.is_some() || self.hardware_enabled
+    }
+
+    /// Checks if this is a hardware breakpoint
+    #[inline]
+    #[must_use]
+    pub fn is_hardware(&self) -> bool {
+        self.hardware_slot.is_some()
+    }
+
+    /// Returns the debug address register used by this breakpoint, if it is a hardware breakpoint
+    #[inline]
+    #[must_use]
+    pub fn hardware_slot(&self) -> Option<usize> {
+        self.hardware_slot
+    }
+
+    /// Returns how often the debuggee stopped at this breakpoint
+    #[inline]
+    #[must_use]
+    pub fn hits(&self) -> usize {
+        self.hits
+    }
+
+    /// Counts a stop of the debuggee at this breakpoint
+    #[inline]
+    pub(crate) fn count_hit(&mut self) {
+        self.hits += 1;
+    }
+
+    /// Returns the message format, if this is a logging breakpoint
+    ///
+    /// Logging breakpoints do not stop the debuggee, see [`crate::logpoint`].
+    #[inline]
+    #[must_use]
+    pub fn log(&self) -> Option<&LogFormat> {
+        self.log.as_ref()
+    }
+
+    /// Makes this a logging breakpoint with the message `log`, or a normal breakpoint again if
+    /// `log` is [`None`]
+    #[inline]
+    pub fn set_log(&mut self, log: Option<LogFormat>) {
+        self.log = log;
+    }
+
+    /// Selects if the code is read back after writing the `INT3` or the original byte, to check
+    /// that the write changed it
+    ///
+    /// This is enabled by default. A failed check makes [`Self::enable`] and [`Self::disable`]
+    /// fail with [`DebuggerError::WriteNotVerified`].
+    #[inline]
+    pub fn set_verify(&mut self, verify: bool) {
+        self.verify = verify;
+    }
+
+    /// Writes a word of the code at the breakpoint through the thread `tid`, checking it if the
+    /// breakpoint verifies its writes
+    fn write_word(&self, tid: Pid, value: Word) -> Result<()> {
+        if self.verify {
+            mem_write_word_verified(tid, self.addr, value)
+        } else {
+            mem_write_word(tid, self.addr, value)
+        }
     }
 
     /// Enables the breakpoint by replacing the original instruction with INT3
@@ -121,6 +238,9 @@ impl Breakpoint {
     /// 2. Saves the original byte
     /// 3. Writes an INT3 instruction (0xCC) to the target address
     ///
+    /// For hardware breakpoints, the address is written into the debug address register instead
+    /// and the breakpoint is enabled in `DR7`.
+    ///
     /// # Errors
     ///
     /// Will return [`DebuggerError::BreakpointIsAlreadyEnabled`] if the breakpoint
@@ -128,7 +248,9 @@ impl Breakpoint {
     ///
     /// This function can fail if:
     /// - Reading from the [Addr] of the [Breakpoint] failed
-    /// - Writing to the [Addr] of the [Breakpoint] failed
+    /// - Writing to the [Addr] of the [Breakpoint] failed, or the `INT3` is not there when
+    ///   it is read back
+    /// - The debug registers could not be written, for hardware breakpoints
     ///
     /// # Examples
     ///
@@ -146,14 +268,20 @@ impl Breakpoint {
         if self.is_enabled() {
             return Err(DebuggerError::BreakpointIsAlreadyEnabled);
         }
+        if let Some(slot) = self.hardware_slot {
+            debugreg::set_execute_breakpoint(self.pid, slot, self.addr)?;
+            self.hardware_enabled = true;
+            return Ok(());
+        }
 
         let data_word: Word = mem_read_word(self.pid, self.addr)?;
         trace!("original word: {data_word:016x}");
-        self.saved_data = Some((data_word & WORD_MASK) as u8);
-        trace!("saved_byte: {:02x}", self.saved_data.as_ref().unwrap());
+        let saved = (data_word & WORD_MASK) as u8;
+        trace!("saved_byte: {saved:02x}");
         let data_word_modified: Word = (data_word & WORD_MASK_INV) | INT3;
         trace!("modified word: {data_word_modified:016x}");
-        mem_write_word(self.pid, self.addr, data_word_modified)?;
+        self.write_word(self.pid, data_word_modified)?;
+        self.saved_data = Some(saved);
 
         Ok(())
     }
@@ -172,7 +300,9 @@ impl Breakpoint {
     ///
     /// This function can fail if:
     /// - Reading from the [Addr] of the [Breakpoint] failed
-    /// - Writing to the [Addr] of the [Breakpoint] failed
+    /// - Writing to the [Addr] of the [Breakpoint] failed, or the original byte is not there
+    ///   when it is read back
+    /// - The debug registers could not be written, for hardware breakpoints
     ///
     /// # Examples
     ///
@@ -193,24 +323,64 @@ impl Breakpoint {
         if !self.is_enabled() {
             return Err(DebuggerError::BreakpointIsAlreadyDisabled);
         }
+        if let Some(slot) = self.hardware_slot {
+            debugreg::clear_breakpoint(self.pid, slot)?;
+            self.hardware_enabled = false;
+            return Ok(());
+        }
 
         let data_word: Word = mem_read_word(self.pid, self.addr)?;
         trace!("breakpo: {data_word:016x}");
         let data_word_restored: Word =
             (data_word & WORD_MASK_INV) | Word::from(self.saved_data.unwrap());
         trace!("restore: {data_word_restored:016x}");
-        mem_write_word(self.pid, self.addr, data_word_restored)?;
+        self.write_word(self.pid, data_word_restored)?;
         self.saved_data = None;
 
         Ok(())
     }
 
+    /// Writes the `INT3` or, if `armed` is false, the original byte through the thread `tid`
+    ///
+    /// This is used to step a thread other than the main thread over an enabled software
+    /// breakpoint while the main thread runs and its memory cannot be accessed. The breakpoint
+    /// stays enabled, only the byte in memory changes.
+    ///
+    /// # Errors
+    ///
+    /// Will return [`DebuggerError::BreakpointIsAlreadyDisabled`] if the breakpoint is not an
+    /// enabled software breakpoint, and fails if the memory cannot be accessed through `tid`.
+    pub(crate) fn write_through(&self, tid: Pid, armed: bool) -> Result<()> {
+        let Some(saved) = self.saved_data else {
+            return Err(DebuggerError::BreakpointIsAlreadyDisabled);
+        };
+        let byte = if armed { INT3 } else { Word::from(saved) };
+        let data_word: Word = mem_read_word(tid, self.addr)?;
+        self.write_word(tid, (data_word & WORD_MASK_INV) | byte)
+    }
+
+    /// Moves the breakpoint to `pid`, a copy of its process that was made by `fork`
+    ///
+    /// The `INT3` of a software breakpoint was copied along with the memory, so only the process
+    /// changes. Debug registers are not copied, so a hardware breakpoint is set again in `pid`.
+    ///
+    /// # Errors
+    ///
+    /// This function fails if the debug registers of `pid` cannot be written.
+    pub(crate) fn move_to(&mut self, pid: Pid) -> Result<()> {
+        self.pid = pid;
+        if let (Some(slot), true) = (self.hardware_slot, self.hardware_enabled) {
+            debugreg::set_execute_breakpoint(pid, slot, self.addr)?;
+        }
+        Ok(())
+    }
+
     /// Returns the saved original instruction byte, if the breakpoint is enabled
     ///
     /// # Returns
     ///
     /// * `Some(u8)` containing the original instruction byte if the breakpoint is enabled
-    /// * `None` if the breakpoint is disabled (no saved data)
+    /// * `None` if the breakpoint is disabled (no saved data) or a hardware breakpoint
     ///
     /// # Examples
     ///
@@ -250,6 +420,73 @@ impl Drop for Breakpoint {
     }
 }
 
+/// What is known about a [`Breakpoint`], as listed by
+/// [`Debugger::list_breakpoints`](crate::debugger::Debugger::list_breakpoints)
+///
+/// # Examples
+///
+/// ```
+/// use coreminer::breakpoint::BreakpointInfo;
+/// use coreminer::addr::Addr;
+///
+/// let info = BreakpointInfo {
+///     addr: Addr::from(0x401136usize),
+///     enabled: false,
+///     hardware: false,
+///     hits: 2,
+///     function: Some("main".to_string()),
+///     file: Some("dummy.c".to_string()),
+///     line: Some(5),
+///     log: None,
+/// };
+/// assert_eq!(
+///     info.to_string(),
+///     "0x0000000000401136  disabled  int3  2 hits  in main at dummy.c:5"
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+pub struct BreakpointInfo {
+    /// Address of the breakpoint
+    pub addr: Addr,
+    /// Whether the breakpoint stops the debuggee
+    pub enabled: bool,
+    /// Whether it is a hardware breakpoint
+    pub hardware: bool,
+    /// How often the debuggee stopped at the breakpoint
+    pub hits: usize,
+    /// The function of the debug information that contains the breakpoint
+    pub function: Option<String>,
+    /// The source file of the line of the breakpoint
+    pub file: Option<String>,
+    /// The line of the breakpoint in [`Self::file`]
+    pub line: Option<u64>,
+    /// The message format, if it is a logging breakpoint
+    pub log: Option<LogFormat>,
+}
+
+impl Display for BreakpointInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}  {:<8}  {}  {} hits",
+            self.addr,
+            if self.enabled { "enabled" } else { "disabled" },
+            if self.hardware { "hw  " } else { "int3" },
+            self.hits
+        )?;
+        if let Some(function) = &self.function {
+            write!(f, "  in {function}")?;
+        }
+        if let (Some(file), Some(line)) = (&self.file, self.line) {
+            write!(f, " at {file}:{line}")?;
+        }
+        if let Some(log) = &self.log {
+            write!(f, "  log \"{log}\"")?;
+        }
+        Ok(())
+    }
+}
+
 // serde always passes a reference. If we pass by value, the serializing
 // will not compile
 #[allow(clippy::trivially_copy_pass_by_ref)]
@@ -0,0 +1,328 @@
+//! # Catchpoint Module
+//!
+//! Provides catchpoints for memory management syscalls of a debugged process.
+//!
+//! Packed or self-modifying programs usually allocate memory, write code into it and then make
+//! it executable before jumping there. The moment new executable memory appears is therefore the
+//! key moment when analyzing such programs: it is the last chance to look at the code before it
+//! runs, and the place to set [Breakpoints](crate::breakpoint::Breakpoint) in it.
+//!
+//! Memory catchpoints stop the debuggee at the exit of `mmap`, `mremap`, `munmap`, `mprotect`
+//! and `brk`, when the kernel has already changed the memory map and the return value is known.
+//! They work by intercepting the syscalls of the debuggee (see [`crate::syscall`]) and are
+//! configured with a [`MemoryCatchConfig`] and enabled with
+//! [`Debugger::catch_memory`](crate::debugger::Debugger::catch_memory).
+
+use std::fmt::{self, Display};
+
+use nix::libc;
+use serde::{Deserialize, Serialize};
+
+use crate::syscall::{SyscallPhase, SyscallStop};
+use crate::Addr;
+
+/// Highest value of `rax` after a syscall that does not indicate an error
+const MAX_ERRNO: i64 = 4095;
+
+/// Selects at which memory management syscalls the debuggee should be stopped
+///
+/// All catchpoints are disabled by default.
+///
+/// # Examples
+///
+/// ```
+/// use coreminer::catchpoint::MemoryCatchConfig;
+///
+/// let config = MemoryCatchConfig::default();
+/// assert!(!config.is_active());
+///
+/// // only stop when executable memory is mapped or memory is made executable
+/// let config = MemoryCatchConfig {
+///     executable_only: true,
+///     ..MemoryCatchConfig::all()
+/// };
+/// assert!(config.is_active());
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct MemoryCatchConfig {
+    /// Stop at `mmap`, `mremap` and `munmap`
+    pub mmap: bool,
+    /// Stop at `mprotect`
+    pub mprotect: bool,
+    /// Stop at `brk`
+    pub brk: bool,
+    /// Only stop if memory is mapped as executable or made executable
+    ///
+    /// With this, `mremap`, `munmap` and `brk` are never caught, as they cannot make memory
+    /// executable.
+    pub executable_only: bool,
+}
+
+/// The memory management syscall that caused a [`MemoryEvent`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum MemoryEventKind {
+    /// New memory was mapped with `mmap`
+    Mmap,
+    /// A mapping was moved or resized with `mremap`
+    Mremap,
+    /// A mapping was removed with `munmap`
+    Munmap,
+    /// The protection of memory was changed with `mprotect`
+    Mprotect,
+    /// The program break was moved with `brk`
+    Brk,
+}
+
+/// Describes a caught memory management syscall
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+pub struct MemoryEvent {
+    /// The syscall that was caught
+    pub kind: MemoryEventKind,
+    /// Start of the affected memory
+    ///
+    /// For `mmap` and `mremap`, this is the returned address if the syscall succeeded. For `brk`,
+    /// it is the new program break.
+    pub addr: Addr,
+    /// Length of the affected memory in bytes, 0 for `brk`
+    pub len: u64,
+    /// The requested protection (`PROT_*` flags), only for `mmap` and `mprotect`
+    pub prot: Option<u64>,
+    /// The raw return value of the syscall
+    pub ret: i64,
+}
+
+impl MemoryCatchConfig {
+    /// Creates a config that stops at all memory management syscalls
+    #[must_use]
+    pub fn all() -> Self {
+        Self {
+            mmap: true,
+            mprotect: true,
+            brk: true,
+            executable_only: false,
+        }
+    }
+
+    /// Returns `true` if at least one catchpoint is enabled
+    ///
+    /// If this is the case, the syscalls of the debuggee need to be intercepted.
+    #[must_use]
+    pub fn is_active(&self) -> bool {
+        self.mmap || self.mprotect || self.brk
+    }
+
+    /// Checks if the debuggee should be stopped at a syscall stop
+    ///
+    /// Only [`SyscallPhase::Exit`] stops are caught, as the memory map has not changed yet at
+    /// the entry of a syscall.
+    ///
+    /// # Returns
+    ///
+    /// The [`MemoryEvent`] describing the syscall if it is caught, [`None`] otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use coreminer::catchpoint::{MemoryCatchConfig, MemoryEventKind};
+    /// use coreminer::syscall::{SyscallPhase, SyscallStop};
+    ///
+    /// let stop = SyscallStop {
+    ///     phase: SyscallPhase::Exit,
+    ///     number: nix::libc::SYS_mprotect as u64,
+    ///     args: [0x7f00_0000_0000, 0x1000, nix::libc::PROT_READ as u64, 0, 0, 0],
+    ///     ret: Some(0),
+    /// };
+    ///
+    /// let event = MemoryCatchConfig::all().check(&stop).unwrap();
+    /// assert_eq!(event.kind, MemoryEventKind::Mprotect);
+    ///
+    /// // the memory was not made executable
+    /// let config = MemoryCatchConfig { executable_only: true, ..MemoryCatchConfig::all() };
+    /// assert!(config.check(&stop).is_none());
+    /// ```
+    #[must_use]
+    pub fn check(&self, stop: &SyscallStop) -> Option<MemoryEvent> {
+        if stop.phase != SyscallPhase::Exit {
+            return None;
+        }
+        let event = MemoryEvent::from_stop(stop)?;
+
+        let enabled = match event.kind {
+            MemoryEventKind::Mmap | MemoryEventKind::Mremap | MemoryEventKind::Munmap => self.mmap,
+            MemoryEventKind::Mprotect => self.mprotect,
+            MemoryEventKind::Brk => self.brk,
+        };
+        if !enabled || (self.executable_only && !event.is_executable()) {
+            return None;
+        }
+        Some(event)
+    }
+}
+
+impl MemoryEvent {
+    /// Creates a [`MemoryEvent`] from the exit of a syscall
+    ///
+    /// # Returns
+    ///
+    /// [`None`] if the syscall is not a memory management syscall or if the stop is not at the
+    /// exit of the syscall.
+    #[must_use]
+    pub fn from_stop(stop: &SyscallStop) -> Option<Self> {
+        let ret = stop.ret?;
+        let args = stop.args;
+        let returned_addr = |requested: u64| {
+            if (-MAX_ERRNO..0).contains(&ret) {
+                Addr::from(requested)
+            } else {
+                Addr::from(ret as u64)
+            }
+        };
+
+        let (kind, addr, len, prot) = match stop.number as i64 {
+            libc::SYS_mmap => (
+                MemoryEventKind::Mmap,
+                returned_addr(args[0]),
+                args[1],
+                Some(args[2]),
+            ),
+            libc::SYS_mremap => (
+                MemoryEventKind::Mremap,
+                returned_addr(args[0]),
+                args[2],
+                None,
+            ),
+            libc::SYS_munmap => (MemoryEventKind::Munmap, Addr::from(args[0]), args[1], None),
+            libc::SYS_mprotect => (
+                MemoryEventKind::Mprotect,
+                Addr::from(args[0]),
+                args[1],
+                Some(args[2]),
+            ),
+            libc::SYS_brk => (MemoryEventKind::Brk, Addr::from(ret as u64), 0, None),
+            _ => return None,
+        };
+
+        Some(Self {
+            kind,
+            addr,
+            len,
+            prot,
+            ret,
+        })
+    }
+
+    /// Returns `true` if the memory was mapped as executable or made executable
+    #[must_use]
+    pub fn is_executable(&self) -> bool {
+        self.prot
+            .is_some_and(|prot| prot & libc::PROT_EXEC as u64 != 0)
+    }
+
+    /// Returns `true` if the syscall succeeded
+    #[must_use]
+    pub fn succeeded(&self) -> bool {
+        !(-MAX_ERRNO..0).contains(&self.ret)
+    }
+}
+
+impl Display for MemoryEventKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Mmap => "mmap",
+            Self::Mremap => "mremap",
+            Self::Munmap => "munmap",
+            Self::Mprotect => "mprotect",
+            Self::Brk => "brk",
+        };
+        write!(f, "{name}")
+    }
+}
+
+impl Display for MemoryEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at {} ({:#x} bytes)", self.kind, self.addr, self.len)?;
+        if let Some(prot) = self.prot {
+            let flag = |bit: i32, c: char| {
+                if prot & bit as u64 == 0 {
+                    '-'
+                } else {
+                    c
+                }
+            };
+            write!(
+                f,
+                " {}{}{}",
+                flag(libc::PROT_READ, 'r'),
+                flag(libc::PROT_WRITE, 'w'),
+                flag(libc::PROT_EXEC, 'x')
+            )?;
+        }
+        if !self.succeeded() {
+            write!(f, " failed with {}", self.ret)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn exit_stop(number: i64, args: [u64; 6], ret: i64) -> SyscallStop {
+        SyscallStop {
+            phase: SyscallPhase::Exit,
+            number: number as u64,
+            args,
+            ret: Some(ret),
+        }
+    }
+
+    #[test]
+    fn test_check_memory_events() {
+        let rwx = (libc::PROT_READ | libc::PROT_WRITE | libc::PROT_EXEC) as u64;
+        let mmap = exit_stop(
+            libc::SYS_mmap,
+            [0, 0x2000, rwx, 0x22, u64::MAX, 0],
+            0x7000_0000,
+        );
+        let brk = exit_stop(libc::SYS_brk, [0; 6], 0x5000_0000);
+        let read = exit_stop(libc::SYS_read, [0; 6], 0);
+
+        let event = MemoryCatchConfig::all()
+            .check(&mmap)
+            .expect("mmap was not caught");
+        assert_eq!(event.kind, MemoryEventKind::Mmap);
+        assert_eq!(event.addr, Addr::from(0x7000_0000usize));
+        assert_eq!(event.len, 0x2000);
+        assert!(event.is_executable());
+        assert!(event.succeeded());
+        assert_eq!(
+            event.to_string(),
+            format!("mmap at {} (0x2000 bytes) rwx", event.addr)
+        );
+
+        let exec_only = MemoryCatchConfig {
+            executable_only: true,
+            ..MemoryCatchConfig::all()
+        };
+        assert!(exec_only.check(&mmap).is_some());
+        assert!(exec_only.check(&brk).is_none());
+        assert!(MemoryCatchConfig::all().check(&brk).is_some());
+        assert!(MemoryCatchConfig::all().check(&read).is_none());
+        assert!(MemoryCatchConfig::default().check(&mmap).is_none());
+
+        let entry = SyscallStop {
+            phase: SyscallPhase::Entry,
+            ret: None,
+            ..mmap
+        };
+        assert!(MemoryCatchConfig::all().check(&entry).is_none());
+
+        let failed = exit_stop(libc::SYS_mmap, [0x1000, 0x2000, rwx, 0, 0, 0], -12);
+        let event = MemoryCatchConfig::all()
+            .check(&failed)
+            .expect("failed mmap was not caught");
+        assert!(!event.succeeded());
+        assert_eq!(event.addr, Addr::from(0x1000usize));
+    }
+}
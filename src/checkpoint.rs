@@ -0,0 +1,136 @@
+//! # Checkpoint Module
+//!
+//! Provides snapshots of the state of a stopped process, which can be restored later.
+//!
+//! A [`Checkpoint`] holds the registers of a thread and the contents of the private writable
+//! memory of the process, like the stack, the heap and the data of the loaded modules. Restoring
+//! it writes them back, so that the thread continues as if it was stopped at the checkpoint
+//! again. Read-only memory like the code of the program does not change while it runs and is not
+//! saved, which also leaves the software breakpoints alone.
+//!
+//! Only the memory and the registers are restored. Open files, other threads, memory that was
+//! mapped after the checkpoint and everything else that the kernel keeps for the process stay as
+//! they are, and a process that wrote to a file or a socket since the checkpoint has done so for
+//! good.
+
+use nix::libc::{user_fpregs_struct, user_regs_struct};
+use nix::sys::ptrace::{self, regset::NT_PRFPREG};
+use nix::unistd::Pid;
+use tracing::debug;
+
+use crate::errors::{DebuggerError, Result};
+use crate::memorymap::{MemoryRegion, ProcessMemoryMap};
+use crate::memread::read_memory;
+use crate::{mem_write, Addr};
+
+/// Number of a checkpoint that was taken with
+/// [`Status::Checkpoint`](crate::feedback::Status::Checkpoint), counting from 1
+pub type CheckpointId = usize;
+
+/// The saved contents of a memory region
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct SavedRegion {
+    start: Addr,
+    bytes: Vec<u8>,
+}
+
+/// The registers and writable memory of a stopped process at some point of its execution
+#[derive(Debug, Clone)]
+pub struct Checkpoint {
+    regs: user_regs_struct,
+    fpregs: user_fpregs_struct,
+    regions: Vec<SavedRegion>,
+}
+
+impl Checkpoint {
+    /// Saves the state of the stopped thread `pid` and the memory of its process
+    ///
+    /// Regions that cannot be read completely are saved up to the first byte that could not be
+    /// read.
+    ///
+    /// # Errors
+    ///
+    /// This function fails if the registers, the memory map or the memory of the process cannot
+    /// be read.
+    pub fn take(pid: Pid) -> Result<Self> {
+        let regs = ptrace::getregs(pid)?;
+        let fpregs = ptrace::getregset::<NT_PRFPREG>(pid)?;
+        let map: ProcessMemoryMap = proc_maps::get_process_maps(pid.into())?.into();
+
+        let mut regions = Vec::new();
+        for region in map.regions.iter().filter(|r| is_saved(r)) {
+            let read = read_memory(pid, region.start_address, region.size)?;
+            if let Some(unreadable) = &read.unreadable {
+                debug!(
+                    "{} bytes at {} are not saved in the checkpoint: {}",
+                    unreadable.len, unreadable.addr, unreadable.reason
+                );
+            }
+            if !read.bytes.is_empty() {
+                regions.push(SavedRegion {
+                    start: region.start_address,
+                    bytes: read.bytes,
+                });
+            }
+        }
+
+        Ok(Self {
+            regs,
+            fpregs,
+            regions,
+        })
+    }
+
+    /// Writes the saved registers and memory back to the stopped thread `pid` and its process
+    ///
+    /// Nothing is written unless every saved region is still mapped and writable.
+    ///
+    /// # Errors
+    ///
+    /// Fails with [`DebuggerError::CheckpointRegionUnmapped`] if a saved region was unmapped or
+    /// made read-only since the checkpoint was taken, and if the memory or the registers cannot
+    /// be written.
+    pub fn restore(&self, pid: Pid) -> Result<()> {
+        let map: ProcessMemoryMap = proc_maps::get_process_maps(pid.into())?.into();
+        for saved in &self.regions {
+            let end = saved.start + saved.bytes.len();
+            if !map.regions.iter().any(|r| {
+                r.permissions.write && r.start_address <= saved.start && end <= r.end_address
+            }) {
+                return Err(DebuggerError::CheckpointRegionUnmapped(saved.start));
+            }
+        }
+
+        for saved in &self.regions {
+            let mut done = 0;
+            while done < saved.bytes.len() {
+                match mem_write(&saved.bytes[done..], pid, saved.start + done)? {
+                    0 => return Err(DebuggerError::CheckpointRegionUnmapped(saved.start + done)),
+                    n => done += n,
+                }
+            }
+        }
+        ptrace::setregset::<NT_PRFPREG>(pid, self.fpregs)?;
+        ptrace::setregs(pid, self.regs)?;
+        Ok(())
+    }
+
+    /// Returns the address of the instruction that the thread was at
+    #[must_use]
+    pub fn pc(&self) -> Addr {
+        Addr::from(self.regs.rip as usize)
+    }
+
+    /// Returns the number of bytes of memory that are saved
+    #[must_use]
+    pub fn size(&self) -> usize {
+        self.regions.iter().map(|r| r.bytes.len()).sum()
+    }
+}
+
+/// Returns `true` if the contents of `region` are saved in a checkpoint
+///
+/// Shared memory is left out, as other processes can change it at any time.
+fn is_saved(region: &MemoryRegion) -> bool {
+    region.permissions.read && region.permissions.write && !region.permissions.shared
+}
@@ -47,3 +47,15 @@ pub const TRAP_UNK: i32 = 0x5;
 /// Generated by the performance monitoring subsystem when
 /// configured to generate `SIGTRAP` signals.
 pub const TRAP_PERF: i32 = 0x6;
+
+// ---------------- SIGSEGV si_codes ---------------------------------------------------------------
+
+/// Address not mapped to object
+///
+/// Generated when the process accesses memory that is not mapped.
+pub const SEGV_MAPERR: i32 = 0x1;
+/// Invalid permissions for mapped object
+///
+/// Generated when the process accesses mapped memory in a way its protection does not allow,
+/// such as writing to read-only pages.
+pub const SEGV_ACCERR: i32 = 0x2;
@@ -0,0 +1,1040 @@
+//! # Core Dump Module
+//!
+//! Provides reading the state of a crashed process from its ELF core dump.
+//!
+//! A core dump is an ELF file of the type `ET_CORE` that the kernel writes when a process is
+//! terminated by a signal like `SIGSEGV` or `SIGABRT`. Its loadable segments contain the memory
+//! of the process, and its notes the registers of every thread (`NT_PRSTATUS`), the auxiliary
+//! vector (`NT_AUXV`) and which files were mapped where (`NT_FILE`).
+//!
+//! By default, the kernel only writes the first page of mapped files into the core dump, which
+//! holds the ELF headers, but not the code. Reads of memory that is not in the core dump are
+//! therefore served from the mapped files, if they still exist. The executable can be given
+//! separately when it is no longer at the path it was run from.
+//!
+//! A [`CoreDump`] is opened by the debugger with
+//! [`Debugger::open_core`](crate::debugger::Debugger::open_core), which then answers requests
+//! for registers, memory, backtraces, disassembly and variables from it instead of a running
+//! process.
+//!
+//! The other way around, [`generate_core`] writes a core dump of a stopped process, to keep an
+//! interesting state for later analysis. Unlike the kernel, it writes all readable memory,
+//! including the code of mapped files, so the core dump does not depend on the files.
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+use nix::libc::user_regs_struct;
+use nix::unistd::Pid;
+use object::elf::{
+    FileHeader64, Ident, NoteHeader64, ProgramHeader64, ELFCLASS64, ELFDATA2LSB, ELFMAG,
+    ELFOSABI_SYSV, EM_X86_64, ET_CORE, EV_CURRENT, NT_AUXV, NT_FILE, NT_PRSTATUS, PF_R, PF_W, PF_X,
+    PT_LOAD, PT_NOTE,
+};
+use object::read::elf::{FileHeader, ProgramHeader};
+use object::{LittleEndian as LE, Object, U16, U32, U64};
+use serde::Serialize;
+use tracing::{debug, warn};
+
+use crate::dbginfo::demangle;
+use crate::dump::align_up;
+use crate::dwarf_parse::{unwind_registers, FrameRegisters};
+use crate::errors::{DebuggerError, Result};
+use crate::memorymap::ProcessMemoryMap;
+use crate::memread::{read_memory, MemoryRead, UnreadableRange};
+use crate::modules::{elf_bias, module_at, LoadedModule};
+use crate::procfs::AuxVector;
+use crate::unwind::{Backtrace, BacktraceFrame};
+use crate::{reg_value, Addr, Register, Word, WORD_BYTES};
+
+/// Most frames that [`CoreDump::backtrace`] unwinds, in case the stack is corrupted in a loop
+const MAX_FRAMES: usize = 1024;
+/// Offset of the signal that terminated the thread (`pr_cursig`) in `NT_PRSTATUS`
+const PRSTATUS_SIGNAL_OFFSET: usize = 12;
+/// Offset of the thread ID (`pr_pid`) in `NT_PRSTATUS`
+const PRSTATUS_PID_OFFSET: usize = 32;
+/// Offset of the registers (`pr_reg`) in `NT_PRSTATUS`
+const PRSTATUS_REGS_OFFSET: usize = 112;
+/// Size of the `NT_PRSTATUS` note, the registers are followed by `pr_fpvalid` and padding
+const PRSTATUS_SIZE: usize = PRSTATUS_REGS_OFFSET + size_of::<user_regs_struct>() + 8;
+/// Size of a memory page, the unit of the offsets in `NT_FILE`
+const PAGE_SIZE: usize = 0x1000;
+
+/// A thread of the crashed process
+#[derive(Debug, Clone, Copy)]
+pub struct CoreThread {
+    /// Thread ID of the thread
+    pub tid: i32,
+    /// The signal the thread received when the core was dumped, 0 if it did not receive one
+    pub signal: i32,
+    /// The general purpose registers of the thread
+    pub regs: user_regs_struct,
+}
+
+/// A file that was mapped into the crashed process, from the `NT_FILE` note
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+pub struct CoreMapping {
+    /// Address of the first byte of the mapping
+    pub start: Addr,
+    /// Address after the last byte of the mapping
+    pub end: Addr,
+    /// Offset in the file that is mapped at `start`
+    pub offset: u64,
+    /// Path of the mapped file
+    pub path: String,
+}
+
+/// A loadable segment of the core dump, which contains a range of the memory of the process
+#[derive(Debug, Clone, Copy)]
+struct CoreSegment {
+    /// Address of the range in the process
+    addr: Addr,
+    /// Offset of the contents in the core dump
+    file_offset: usize,
+    /// Number of bytes that were written into the core dump
+    file_size: usize,
+    /// Size of the range in the process
+    mem_size: usize,
+}
+
+/// Describes a core dump that was generated from a running process
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+pub struct GeneratedCore {
+    /// Process ID of the process
+    pub pid: i32,
+    /// Path of the written core dump
+    pub output: PathBuf,
+    /// Number of threads whose registers were saved
+    pub threads: usize,
+    /// Number of memory regions that were saved
+    pub segments: usize,
+    /// Size of the written core dump in bytes
+    pub size: usize,
+}
+
+/// A memory region of a process, to be written into a core dump
+struct LoadSegment {
+    /// Address of the region
+    addr: Addr,
+    /// Size of the region
+    mem_size: usize,
+    /// The `PF_*` flags of the region
+    flags: u32,
+    /// The contents of the region, from its start up to the first byte that could not be read
+    bytes: Vec<u8>,
+}
+
+/// An opened ELF core dump
+///
+/// # Examples
+///
+/// ```no_run
+/// use coreminer::coredump::CoreDump;
+///
+/// let core = CoreDump::open("core.1234".as_ref(), None).unwrap();
+/// println!("process {} crashed with signal {}", core.pid(), core.signal());
+/// println!("rip was {:#x}", core.registers().rip);
+///
+/// let stack = core.read_memory(core.registers().rsp.into(), 64);
+/// println!("{stack}");
+/// ```
+#[derive(Debug)]
+pub struct CoreDump {
+    path: PathBuf,
+    data: Vec<u8>,
+    segments: Vec<CoreSegment>,
+    threads: Vec<CoreThread>,
+    mappings: Vec<CoreMapping>,
+    auxv: AuxVector,
+}
+
+impl CoreDump {
+    /// Opens and parses the core dump at `path`
+    ///
+    /// # Parameters
+    ///
+    /// * `path` - Path of the core dump
+    /// * `executable` - Path of the executable of the crashed process, if it is not at the path
+    ///   it was run from anymore. Its mappings are read from this file instead.
+    ///
+    /// # Errors
+    ///
+    /// This function fails if the file cannot be read, is not an ELF core dump, or has no
+    /// registers of any thread.
+    pub fn open(path: &Path, executable: Option<&Path>) -> Result<Self> {
+        let data = std::fs::read(path)?;
+        let mut core = Self::parse(data)?;
+        core.path = path.to_path_buf();
+        if let Some(executable) = executable {
+            core.replace_executable(executable);
+        }
+        Ok(core)
+    }
+
+    /// Parses the contents of a core dump
+    fn parse(data: Vec<u8>) -> Result<Self> {
+        let header = FileHeader64::<LE>::parse(&*data)?;
+        if header.e_type(LE) != ET_CORE {
+            return Err(DebuggerError::InvalidCore(
+                "the ELF file is not a core dump".to_string(),
+            ));
+        }
+
+        let mut segments = Vec::new();
+        let mut threads = Vec::new();
+        let mut mappings = Vec::new();
+        let mut auxv = AuxVector::default();
+        for phdr in header.program_headers(LE, &*data)? {
+            match phdr.p_type(LE) {
+                PT_LOAD => segments.push(CoreSegment {
+                    addr: Addr::from(phdr.p_vaddr(LE)),
+                    file_offset: phdr.p_offset(LE) as usize,
+                    file_size: phdr.p_filesz(LE) as usize,
+                    mem_size: phdr.p_memsz(LE) as usize,
+                }),
+                PT_NOTE => {
+                    let Some(mut notes) = phdr.notes(LE, &*data)? else {
+                        continue;
+                    };
+                    while let Some(note) = notes.next()? {
+                        // notes of other owners, like LINUX for the extended registers, reuse
+                        // the numbers
+                        if note.name() != b"CORE" {
+                            continue;
+                        }
+                        match note.n_type(LE) {
+                            NT_PRSTATUS => threads.push(parse_prstatus(note.desc())?),
+                            NT_FILE => mappings = parse_file_note(note.desc())?,
+                            NT_AUXV => auxv = AuxVector::parse(note.desc()),
+                            _ => (),
+                        }
+                    }
+                }
+                _ => (),
+            }
+        }
+
+        if threads.is_empty() {
+            return Err(DebuggerError::InvalidCore(
+                "the core dump has no registers".to_string(),
+            ));
+        }
+        debug!(
+            "core dump with {} segments, {} threads and {} mapped files",
+            segments.len(),
+            threads.len(),
+            mappings.len()
+        );
+
+        Ok(Self {
+            path: PathBuf::new(),
+            data,
+            segments,
+            threads,
+            mappings,
+            auxv,
+        })
+    }
+
+    /// Gets the path of the core dump
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Gets the process ID of the crashed process
+    ///
+    /// This is the thread ID of the first thread, which is the thread that received the signal.
+    #[must_use]
+    pub fn pid(&self) -> i32 {
+        self.threads[0].tid
+    }
+
+    /// Gets the signal that terminated the process
+    #[must_use]
+    pub fn signal(&self) -> i32 {
+        self.threads[0].signal
+    }
+
+    /// Gets the registers of the thread that received the signal
+    #[must_use]
+    pub fn registers(&self) -> user_regs_struct {
+        self.threads[0].regs
+    }
+
+    /// Gets all threads of the crashed process, starting with the one that received the signal
+    #[must_use]
+    pub fn threads(&self) -> &[CoreThread] {
+        &self.threads
+    }
+
+    /// Gets the files that were mapped into the process
+    #[must_use]
+    pub fn mappings(&self) -> &[CoreMapping] {
+        &self.mappings
+    }
+
+    /// Gets the auxiliary vector of the process
+    #[must_use]
+    pub fn auxv(&self) -> &AuxVector {
+        &self.auxv
+    }
+
+    /// Gets the path of the executable of the process
+    ///
+    /// This is the file that is mapped at the entry point from the auxiliary vector.
+    #[must_use]
+    pub fn executable(&self) -> Option<&str> {
+        let entry = self.auxv.entry?;
+        self.mappings
+            .iter()
+            .find(|m| m.start <= entry && entry < m.end)
+            .map(|m| m.path.as_str())
+    }
+
+    /// Reads the executable of the process from `executable` instead of its original path
+    fn replace_executable(&mut self, executable: &Path) {
+        let Some(original) = self.executable().map(str::to_string) else {
+            warn!("the executable of the core dump is unknown, it cannot be replaced");
+            return;
+        };
+        let replacement = std::fs::canonicalize(executable)
+            .unwrap_or_else(|_| executable.to_path_buf())
+            .to_string_lossy()
+            .to_string();
+        for mapping in self.mappings.iter_mut().filter(|m| m.path == original) {
+            mapping.path.clone_from(&replacement);
+        }
+    }
+
+    /// Gets the ELF modules that were loaded into the process, with the executable first
+    ///
+    /// Mapped files whose ELF headers cannot be read are left out.
+    ///
+    /// # Errors
+    ///
+    /// This function fails with [`DebuggerError::NoExecutableModule`] if the executable is not
+    /// known or its headers cannot be read.
+    pub fn modules(&self) -> Result<Vec<LoadedModule>> {
+        let executable = self.executable().ok_or(DebuggerError::NoExecutableModule)?;
+        let mut modules: Vec<LoadedModule> = Vec::new();
+        for mapping in &self.mappings {
+            if modules.iter().any(|m| m.path == mapping.path) {
+                continue;
+            }
+            let Some(base) = self
+                .mappings
+                .iter()
+                .filter(|m| m.path == mapping.path)
+                .map(|m| m.start)
+                .min()
+            else {
+                continue;
+            };
+            match elf_bias(base, |buf, addr| self.read(buf, addr)) {
+                Ok(bias) => modules.push(LoadedModule {
+                    path: mapping.path.clone(),
+                    base,
+                    bias,
+                }),
+                Err(e) => debug!("{} at {base} is not an ELF module: {e}", mapping.path),
+            }
+        }
+        modules.sort_by_key(|m| (m.path != executable, m.base));
+        if modules.first().map(|m| m.path.as_str()) != Some(executable) {
+            return Err(DebuggerError::NoExecutableModule);
+        }
+        Ok(modules)
+    }
+
+    /// Reads bytes of the memory of the process into `buf`
+    ///
+    /// The bytes are read from the core dump, or from the mapped files for memory that was not
+    /// written into it. Like a read from `/proc/<pid>/mem`, the read stops at the first byte that
+    /// is not available.
+    ///
+    /// # Returns
+    ///
+    /// The number of bytes that were read, at least 1 for a non empty `buf`.
+    ///
+    /// # Errors
+    ///
+    /// This function fails with [`DebuggerError::NotInCore`] if the first byte is not available,
+    /// or if a mapped file cannot be read.
+    pub fn read(&self, buf: &mut [u8], addr: Addr) -> Result<usize> {
+        let mut done = 0;
+        while done < buf.len() {
+            let read = self.read_chunk(&mut buf[done..], addr + done)?;
+            if read == 0 {
+                break;
+            }
+            done += read;
+        }
+        if done == 0 && !buf.is_empty() {
+            return Err(DebuggerError::NotInCore(addr));
+        }
+        Ok(done)
+    }
+
+    /// Reads the bytes at `addr` from the segment or the mapped file that contains it
+    ///
+    /// # Returns
+    ///
+    /// The number of bytes that were read, 0 if `addr` is not available.
+    fn read_chunk(&self, buf: &mut [u8], addr: Addr) -> Result<usize> {
+        let Some(segment) = self
+            .segments
+            .iter()
+            .find(|s| s.addr <= addr && addr.usize() < s.addr.usize() + s.mem_size)
+        else {
+            return Ok(0);
+        };
+        let offset = (addr - segment.addr).usize();
+        if offset < segment.file_size {
+            let len = buf.len().min(segment.file_size - offset);
+            let start = segment.file_offset + offset;
+            let Some(bytes) = self.data.get(start..start + len) else {
+                return Ok(0);
+            };
+            buf[..len].copy_from_slice(bytes);
+            return Ok(len);
+        }
+
+        let Some(mapping) = self
+            .mappings
+            .iter()
+            .find(|m| m.start <= addr && addr < m.end)
+        else {
+            return Ok(0);
+        };
+        let segment_end = segment.addr.usize() + segment.mem_size;
+        let len = buf
+            .len()
+            .min(segment_end - addr.usize())
+            .min((mapping.end - addr).usize());
+        let mut file = match File::open(&mapping.path) {
+            Ok(file) => file,
+            Err(e) => {
+                debug!("could not open the mapped file {}: {e}", mapping.path);
+                return Ok(0);
+            }
+        };
+        file.seek(SeekFrom::Start(
+            mapping.offset + (addr - mapping.start).u64(),
+        ))?;
+        Ok(file.read(&mut buf[..len])?)
+    }
+
+    /// Reads a [Word] of the memory of the process
+    ///
+    /// # Errors
+    ///
+    /// This function fails with [`DebuggerError::NotInCore`] if the word is not completely
+    /// available.
+    pub fn read_word(&self, addr: Addr) -> Result<Word> {
+        let mut buf = [0; WORD_BYTES];
+        let read = self.read(&mut buf, addr)?;
+        if read < WORD_BYTES {
+            return Err(DebuggerError::NotInCore(addr + read));
+        }
+        Ok(Word::from_ne_bytes(buf))
+    }
+
+    /// Unwinds the stack of the thread that received the signal
+    ///
+    /// The stack is unwound with the call frame information of the module of each frame, which
+    /// is read from the mapped file. Frames are named after the symbols of their modules. The
+    /// backtrace ends at the first frame that cannot be unwound, for example because the file of
+    /// its module is not available anymore.
+    ///
+    /// # Parameters
+    ///
+    /// * `modules` - The modules of the process, see [`Self::modules`]
+    ///
+    /// # Errors
+    ///
+    /// This function fails if the registers of the innermost frame are not supported.
+    pub fn backtrace(&self, modules: &[LoadedModule]) -> Result<Backtrace> {
+        let raw = self.registers();
+        let mut regs = FrameRegisters::new();
+        for dwarf_reg in 0..=16 {
+            let reg = Register::try_from(gimli::Register(dwarf_reg))?;
+            regs.insert(reg, reg_value(&raw, reg));
+        }
+
+        let mut files: HashMap<String, Rc<[u8]>> = HashMap::new();
+        let mut frames = Vec::new();
+        while frames.len() < MAX_FRAMES {
+            let Some(&rip) = regs.get(&Register::rip).filter(|rip| **rip != 0) else {
+                break;
+            };
+            let mut frame = BacktraceFrame {
+                addr: Addr::from(rip),
+                start_addr: None,
+                name: None,
+            };
+            // the return address of outer frames can be in the next function already
+            let pc = Addr::from(if frames.is_empty() { rip } else { rip - 1 });
+
+            let Some(module) = module_at(modules, pc) else {
+                frames.push(frame);
+                break;
+            };
+            let data = match files.get(&module.path) {
+                Some(data) => Rc::clone(data),
+                None => match std::fs::read(&module.path) {
+                    Ok(data) => {
+                        let data: Rc<[u8]> = data.into();
+                        files.insert(module.path.clone(), Rc::clone(&data));
+                        data
+                    }
+                    Err(e) => {
+                        debug!("could not read {} for unwinding: {e}", module.path);
+                        frames.push(frame);
+                        break;
+                    }
+                },
+            };
+            let obj = object::File::parse(&*data)?;
+            let Some(offset) = module.to_module(pc).map(|a| a.offset) else {
+                frames.push(frame);
+                break;
+            };
+
+            if let Some(symbol) = obj.symbol_map().get(offset) {
+                frame.name = Some(demangle(symbol.name()));
+                frame.start_addr = Some(module.to_process(symbol.address()));
+            }
+            frames.push(frame);
+
+            match unwind_registers(&obj, offset, &regs, |addr| Ok(self.read_word(addr)? as u64)) {
+                Ok(Some(caller)) => regs = caller,
+                Ok(None) => break,
+                Err(e) => {
+                    debug!("could not unwind the frame at {pc}: {e}");
+                    break;
+                }
+            }
+        }
+
+        Ok(Backtrace { frames })
+    }
+
+    /// Reads a range of the memory of the process, which may be partially unavailable
+    ///
+    /// Like [`read_memory`](crate::memread::read_memory), this returns the bytes up to the first
+    /// byte that is not available and describes the rest of the range.
+    #[must_use]
+    pub fn read_memory(&self, addr: Addr, len: usize) -> MemoryRead {
+        let mut bytes = vec![0; len];
+        let mut done = 0;
+        let mut failure = None;
+        while done < len {
+            match self.read(&mut bytes[done..], addr + done) {
+                Ok(n) => done += n,
+                Err(e) => {
+                    failure = Some(e.to_string());
+                    break;
+                }
+            }
+        }
+        bytes.truncate(done);
+
+        MemoryRead {
+            addr,
+            bytes,
+            unreadable: failure.map(|reason| UnreadableRange {
+                addr: addr + done,
+                len: len - done,
+                reason,
+            }),
+        }
+    }
+}
+
+/// Writes a core dump of the stopped process `pid`
+///
+/// The core dump has a `NT_PRSTATUS` note for each of the `threads`, with the first one being
+/// the thread that is shown first when the core dump is opened, the auxiliary vector and the
+/// mapped files of the process. Every readable region of the memory map is saved in a loadable
+/// segment. Regions that cannot be read completely, like `[vvar]`, are saved up to the first
+/// byte that could not be read.
+///
+/// # Parameters
+///
+/// * `pid` - The process ID of the debuggee
+/// * `threads` - The threads of the process, with their registers
+/// * `map` - The memory map of the process
+/// * `output` - Path of the core dump to write
+///
+/// # Errors
+///
+/// This function fails if the memory or the auxiliary vector of the process cannot be read, if
+/// the process has too many memory regions for an ELF file or if the core dump cannot be
+/// written.
+///
+/// # Examples
+///
+/// ```no_run
+/// use coreminer::coredump::{generate_core, CoreThread};
+/// use coreminer::memorymap::ProcessMemoryMap;
+/// use nix::sys::ptrace;
+/// use nix::unistd::Pid;
+///
+/// let pid = Pid::from_raw(1234);
+/// let map: ProcessMemoryMap = proc_maps::get_process_maps(pid.into()).unwrap().into();
+/// let thread = CoreThread {
+///     tid: pid.as_raw(),
+///     signal: 0,
+///     regs: ptrace::getregs(pid).unwrap(),
+/// };
+/// let core = generate_core(pid, &[thread], &map, "/tmp/core.1234".as_ref()).unwrap();
+/// println!("wrote {} bytes to {}", core.size, core.output.display());
+/// ```
+pub fn generate_core(
+    pid: Pid,
+    threads: &[CoreThread],
+    map: &ProcessMemoryMap,
+    output: &Path,
+) -> Result<GeneratedCore> {
+    let mut segments = Vec::new();
+    let mut mappings = Vec::new();
+    for region in &map.regions {
+        if let Some(path) = region.path.as_ref().filter(|p| p.starts_with('/')) {
+            mappings.push(CoreMapping {
+                start: region.start_address,
+                end: region.end_address,
+                offset: region.offset as u64,
+                path: path.clone(),
+            });
+        }
+        if !region.permissions.read {
+            continue;
+        }
+
+        let read = read_memory(pid, region.start_address, region.size)?;
+        if let Some(unreadable) = &read.unreadable {
+            debug!(
+                "{} bytes at {} are not saved in the core dump: {}",
+                unreadable.len, unreadable.addr, unreadable.reason
+            );
+        }
+        let mut flags = PF_R;
+        if region.permissions.write {
+            flags |= PF_W;
+        }
+        if region.permissions.execute {
+            flags |= PF_X;
+        }
+        segments.push(LoadSegment {
+            addr: region.start_address,
+            mem_size: region.size,
+            flags,
+            bytes: read.bytes,
+        });
+    }
+    let auxv = std::fs::read(format!("/proc/{pid}/auxv"))?;
+
+    let data = build_core(threads, &segments, &mappings, &auxv)?;
+    std::fs::write(output, &data)?;
+    Ok(GeneratedCore {
+        pid: pid.as_raw(),
+        output: output.to_path_buf(),
+        threads: threads.len(),
+        segments: segments.len(),
+        size: data.len(),
+    })
+}
+
+/// Builds the contents of a core dump
+///
+/// The notes follow the program headers, and the contents of each segment start at a page
+/// boundary, like in the core dumps of the kernel.
+fn build_core(
+    threads: &[CoreThread],
+    segments: &[LoadSegment],
+    mappings: &[CoreMapping],
+    auxv: &[u8],
+) -> Result<Vec<u8>> {
+    let mut notes = Vec::new();
+    for thread in threads {
+        push_note(&mut notes, NT_PRSTATUS, &prstatus(thread));
+    }
+    push_note(&mut notes, NT_AUXV, auxv);
+    push_note(&mut notes, NT_FILE, &file_note(mappings));
+
+    let phnum = u16::try_from(segments.len() + 1).map_err(|_| {
+        DebuggerError::InvalidCore("too many memory regions for a core dump".to_string())
+    })?;
+    let phoff = size_of::<FileHeader64<LE>>();
+    let notes_offset = phoff + usize::from(phnum) * size_of::<ProgramHeader64<LE>>();
+
+    let mut phdrs = vec![program_header(PT_NOTE, 0, notes_offset, 0, notes.len(), 0)];
+    let mut offset = align_up(notes_offset + notes.len(), PAGE_SIZE);
+    for segment in segments {
+        phdrs.push(program_header(
+            PT_LOAD,
+            segment.flags,
+            offset,
+            segment.addr.u64(),
+            segment.bytes.len(),
+            segment.mem_size,
+        ));
+        offset = align_up(offset + segment.bytes.len(), PAGE_SIZE);
+    }
+
+    let header = FileHeader64::<LE> {
+        e_ident: Ident {
+            magic: ELFMAG,
+            class: ELFCLASS64,
+            data: ELFDATA2LSB,
+            version: EV_CURRENT,
+            os_abi: ELFOSABI_SYSV,
+            abi_version: 0,
+            padding: [0; 7],
+        },
+        e_type: U16::new(LE, ET_CORE),
+        e_machine: U16::new(LE, EM_X86_64),
+        e_version: U32::new(LE, u32::from(EV_CURRENT)),
+        e_entry: U64::new(LE, 0),
+        e_phoff: U64::new(LE, phoff as u64),
+        e_shoff: U64::new(LE, 0),
+        e_flags: U32::new(LE, 0),
+        e_ehsize: U16::new(LE, phoff as u16),
+        e_phentsize: U16::new(LE, size_of::<ProgramHeader64<LE>>() as u16),
+        e_phnum: U16::new(LE, phnum),
+        e_shentsize: U16::new(LE, 0),
+        e_shnum: U16::new(LE, 0),
+        e_shstrndx: U16::new(LE, 0),
+    };
+
+    let mut data = Vec::with_capacity(offset);
+    data.extend_from_slice(object::pod::bytes_of(&header));
+    for phdr in &phdrs {
+        data.extend_from_slice(object::pod::bytes_of(phdr));
+    }
+    data.extend_from_slice(&notes);
+    for segment in segments {
+        data.resize(align_up(data.len(), PAGE_SIZE), 0);
+        data.extend_from_slice(&segment.bytes);
+    }
+    Ok(data)
+}
+
+/// Builds a program header of a core dump
+fn program_header(
+    kind: u32,
+    flags: u32,
+    offset: usize,
+    vaddr: u64,
+    file_size: usize,
+    mem_size: usize,
+) -> ProgramHeader64<LE> {
+    ProgramHeader64 {
+        p_type: U32::new(LE, kind),
+        p_flags: U32::new(LE, flags),
+        p_offset: U64::new(LE, offset as u64),
+        p_vaddr: U64::new(LE, vaddr),
+        p_paddr: U64::new(LE, 0),
+        p_filesz: U64::new(LE, file_size as u64),
+        p_memsz: U64::new(LE, mem_size as u64),
+        p_align: U64::new(LE, if kind == PT_LOAD { PAGE_SIZE as u64 } else { 4 }),
+    }
+}
+
+/// Appends a note of the owner `CORE` to `notes`
+fn push_note(notes: &mut Vec<u8>, kind: u32, desc: &[u8]) {
+    const NAME: &[u8] = b"CORE\0";
+    let header = NoteHeader64::<LE> {
+        n_namesz: U32::new(LE, NAME.len() as u32),
+        n_descsz: U32::new(LE, desc.len() as u32),
+        n_type: U32::new(LE, kind),
+    };
+    notes.extend_from_slice(object::pod::bytes_of(&header));
+    notes.extend_from_slice(NAME);
+    notes.resize(align_up(notes.len(), 4), 0);
+    notes.extend_from_slice(desc);
+    notes.resize(align_up(notes.len(), 4), 0);
+}
+
+/// Builds the `NT_PRSTATUS` note of a thread, the counterpart of [`parse_prstatus`]
+fn prstatus(thread: &CoreThread) -> Vec<u8> {
+    let mut desc = vec![0; PRSTATUS_SIZE];
+    // si_signo of the siginfo at the start
+    desc[..4].copy_from_slice(&thread.signal.to_ne_bytes());
+    desc[PRSTATUS_SIGNAL_OFFSET..PRSTATUS_SIGNAL_OFFSET + 2]
+        .copy_from_slice(&(thread.signal as i16).to_ne_bytes());
+    desc[PRSTATUS_PID_OFFSET..PRSTATUS_PID_OFFSET + 4].copy_from_slice(&thread.tid.to_ne_bytes());
+    for (i, word) in regs_words(&thread.regs).iter().enumerate() {
+        let offset = PRSTATUS_REGS_OFFSET + i * WORD_BYTES;
+        desc[offset..offset + WORD_BYTES].copy_from_slice(&word.to_ne_bytes());
+    }
+    desc
+}
+
+/// Builds the `NT_FILE` note of the mapped files, the counterpart of [`parse_file_note`]
+fn file_note(mappings: &[CoreMapping]) -> Vec<u8> {
+    let mut desc = Vec::new();
+    desc.extend_from_slice(&(mappings.len() as u64).to_ne_bytes());
+    desc.extend_from_slice(&(PAGE_SIZE as u64).to_ne_bytes());
+    for mapping in mappings {
+        desc.extend_from_slice(&mapping.start.u64().to_ne_bytes());
+        desc.extend_from_slice(&mapping.end.u64().to_ne_bytes());
+        desc.extend_from_slice(&(mapping.offset / PAGE_SIZE as u64).to_ne_bytes());
+    }
+    for mapping in mappings {
+        desc.extend_from_slice(mapping.path.as_bytes());
+        desc.push(0);
+    }
+    desc
+}
+
+/// Parses the thread ID, signal and registers of a thread from a `NT_PRSTATUS` note
+fn parse_prstatus(desc: &[u8]) -> Result<CoreThread> {
+    let field = |offset: usize, len: usize| {
+        desc.get(offset..offset + len).ok_or_else(|| {
+            DebuggerError::InvalidCore("the NT_PRSTATUS note is too short".to_string())
+        })
+    };
+    let mut signal = [0; 2];
+    signal.copy_from_slice(field(PRSTATUS_SIGNAL_OFFSET, 2)?);
+    let mut tid = [0; 4];
+    tid.copy_from_slice(field(PRSTATUS_PID_OFFSET, 4)?);
+    let words: Vec<u64> = field(PRSTATUS_REGS_OFFSET, size_of::<user_regs_struct>())?
+        .chunks_exact(WORD_BYTES)
+        .map(ne_u64)
+        .collect();
+
+    Ok(CoreThread {
+        tid: i32::from_ne_bytes(tid),
+        signal: i32::from(i16::from_ne_bytes(signal)),
+        regs: user_regs(&words),
+    })
+}
+
+/// Parses the mapped files from a `NT_FILE` note
+///
+/// The note starts with the number of mappings and the page size, followed by the start, end
+/// and offset in pages of each mapping, and then their paths as null terminated strings.
+fn parse_file_note(desc: &[u8]) -> Result<Vec<CoreMapping>> {
+    let too_short = || DebuggerError::InvalidCore("the NT_FILE note is too short".to_string());
+    let word = |index: usize| -> Result<u64> {
+        desc.get(index * WORD_BYTES..(index + 1) * WORD_BYTES)
+            .map(ne_u64)
+            .ok_or_else(too_short)
+    };
+
+    let count = word(0)? as usize;
+    let page_size = word(1)?;
+    let names_start = (2 + 3 * count) * WORD_BYTES;
+    let mut names = desc
+        .get(names_start..)
+        .ok_or_else(too_short)?
+        .split(|b| *b == 0);
+
+    let mut mappings = Vec::with_capacity(count);
+    for i in 0..count {
+        let path = names.next().ok_or_else(too_short)?;
+        mappings.push(CoreMapping {
+            start: Addr::from(word(2 + 3 * i)?),
+            end: Addr::from(word(3 + 3 * i)?),
+            offset: word(4 + 3 * i)? * page_size,
+            path: String::from_utf8_lossy(path).to_string(),
+        });
+    }
+    Ok(mappings)
+}
+
+/// Converts 8 bytes in native byte order to a [u64]
+fn ne_u64(bytes: &[u8]) -> u64 {
+    let mut word = [0; 8];
+    word.copy_from_slice(bytes);
+    u64::from_ne_bytes(word)
+}
+
+/// Returns the fields of a [`user_regs_struct`], in the order they are stored in `NT_PRSTATUS`
+fn regs_words(r: &user_regs_struct) -> [u64; 27] {
+    [
+        r.r15, r.r14, r.r13, r.r12, r.rbp, r.rbx, r.r11, r.r10, r.r9, r.r8, r.rax, r.rcx, r.rdx,
+        r.rsi, r.rdi, r.orig_rax, r.rip, r.cs, r.eflags, r.rsp, r.ss, r.fs_base, r.gs_base, r.ds,
+        r.es, r.fs, r.gs,
+    ]
+}
+
+/// Builds a [`user_regs_struct`] from its fields, in the order they are stored in `NT_PRSTATUS`
+fn user_regs(r: &[u64]) -> user_regs_struct {
+    user_regs_struct {
+        r15: r[0],
+        r14: r[1],
+        r13: r[2],
+        r12: r[3],
+        rbp: r[4],
+        rbx: r[5],
+        r11: r[6],
+        r10: r[7],
+        r9: r[8],
+        r8: r[9],
+        rax: r[10],
+        rcx: r[11],
+        rdx: r[12],
+        rsi: r[13],
+        rdi: r[14],
+        orig_rax: r[15],
+        rip: r[16],
+        cs: r[17],
+        eflags: r[18],
+        rsp: r[19],
+        ss: r[20],
+        fs_base: r[21],
+        gs_base: r[22],
+        ds: r[23],
+        es: r[24],
+        fs: r[25],
+        gs: r[26],
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Builds a core dump with one thread and a segment of 16 bytes in a 32 byte mapping at
+    /// `0x1000`
+    fn synthetic_core() -> Vec<u8> {
+        let mut prstatus = vec![0; PRSTATUS_REGS_OFFSET + size_of::<user_regs_struct>() + 8];
+        prstatus[PRSTATUS_SIGNAL_OFFSET..PRSTATUS_SIGNAL_OFFSET + 2]
+            .copy_from_slice(&6i16.to_ne_bytes());
+        prstatus[PRSTATUS_PID_OFFSET..PRSTATUS_PID_OFFSET + 4]
+            .copy_from_slice(&4242i32.to_ne_bytes());
+        let rip = PRSTATUS_REGS_OFFSET + 16 * WORD_BYTES;
+        prstatus[rip..rip + WORD_BYTES].copy_from_slice(&0x1008u64.to_ne_bytes());
+
+        let mut note = Vec::new();
+        note.extend(5u32.to_le_bytes());
+        note.extend((prstatus.len() as u32).to_le_bytes());
+        note.extend(NT_PRSTATUS.to_le_bytes());
+        note.extend(b"CORE\0\0\0\0");
+        note.extend(&prstatus);
+
+        let note_offset = 64 + 2 * 56;
+        let load_offset = note_offset + note.len().next_multiple_of(8);
+        let phdr = |kind: u32, offset: usize, vaddr: u64, filesz: usize, memsz: usize| {
+            let mut phdr = Vec::new();
+            phdr.extend(kind.to_le_bytes());
+            phdr.extend(4u32.to_le_bytes());
+            phdr.extend((offset as u64).to_le_bytes());
+            phdr.extend(vaddr.to_le_bytes());
+            phdr.extend(0u64.to_le_bytes());
+            phdr.extend((filesz as u64).to_le_bytes());
+            phdr.extend((memsz as u64).to_le_bytes());
+            phdr.extend(4u64.to_le_bytes());
+            phdr
+        };
+
+        let mut core = vec![0x7f, b'E', b'L', b'F', 2, 1, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+        core.extend(ET_CORE.to_le_bytes());
+        core.extend(62u16.to_le_bytes());
+        core.extend(1u32.to_le_bytes());
+        core.extend(0u64.to_le_bytes());
+        core.extend(64u64.to_le_bytes());
+        core.extend(0u64.to_le_bytes());
+        core.extend(0u32.to_le_bytes());
+        for half in [64u16, 56, 2, 64, 0, 0] {
+            core.extend(half.to_le_bytes());
+        }
+        core.extend(phdr(PT_NOTE, note_offset, 0, note.len(), 0));
+        core.extend(phdr(PT_LOAD, load_offset, 0x1000, 16, 32));
+        core.extend(&note);
+        core.resize(load_offset, 0);
+        core.extend(0x1111_2222_3333_4444u64.to_ne_bytes());
+        core.extend(0x5555_6666_7777_8888u64.to_ne_bytes());
+        core
+    }
+
+    #[test]
+    fn test_parse_core() {
+        let core = CoreDump::parse(synthetic_core()).unwrap();
+        assert_eq!(core.pid(), 4242);
+        assert_eq!(core.signal(), 6);
+        assert_eq!(core.registers().rip, 0x1008);
+        assert_eq!(core.threads().len(), 1);
+        assert!(core.mappings().is_empty());
+
+        let mut not_core = synthetic_core();
+        not_core[16] = 2;
+        assert!(matches!(
+            CoreDump::parse(not_core),
+            Err(DebuggerError::InvalidCore(_))
+        ));
+    }
+
+    #[test]
+    fn test_read_core_memory() {
+        let core = CoreDump::parse(synthetic_core()).unwrap();
+        assert_eq!(
+            core.read_word(Addr::from(0x1008usize)).unwrap(),
+            0x5555_6666_7777_8888u64 as Word
+        );
+
+        // the rest of the segment is neither in the core dump nor in a mapped file
+        let mut buf = [0; 16];
+        assert_eq!(core.read(&mut buf, Addr::from(0x1008usize)).unwrap(), 8);
+        assert!(matches!(
+            core.read(&mut buf, Addr::from(0x1010usize)),
+            Err(DebuggerError::NotInCore(_))
+        ));
+
+        let read = core.read_memory(Addr::from(0x1004usize), 16);
+        assert_eq!(read.bytes.len(), 12);
+        let unreadable = read.unreadable.unwrap();
+        assert_eq!(unreadable.addr, Addr::from(0x1010usize));
+        assert_eq!(unreadable.len, 4);
+    }
+
+    #[test]
+    fn test_build_core() {
+        let mut regs = user_regs(&[0; 27]);
+        regs.rip = 0x2004;
+        regs.rsp = 0x3000;
+        let threads = [
+            CoreThread {
+                tid: 100,
+                signal: 11,
+                regs,
+            },
+            CoreThread {
+                tid: 101,
+                signal: 0,
+                regs,
+            },
+        ];
+        let segments = [LoadSegment {
+            addr: Addr::from(0x2000usize),
+            mem_size: 0x1000,
+            flags: PF_R | PF_X,
+            bytes: vec![0x90; 0x10],
+        }];
+        let mappings = [CoreMapping {
+            start: Addr::from(0x2000usize),
+            end: Addr::from(0x3000usize),
+            offset: 0x1000,
+            path: "/nonexistent/program".to_string(),
+        }];
+
+        let data = build_core(&threads, &segments, &mappings, &[]).unwrap();
+        let core = CoreDump::parse(data).unwrap();
+        assert_eq!(core.pid(), 100);
+        assert_eq!(core.signal(), 11);
+        assert_eq!(core.threads().len(), 2);
+        assert_eq!(core.threads()[1].tid, 101);
+        assert_eq!(core.registers().rip, 0x2004);
+        assert_eq!(core.mappings(), &mappings);
+
+        let read = core.read_memory(Addr::from(0x2008usize), 0x10);
+        assert_eq!(read.bytes, vec![0x90; 8]);
+        assert_eq!(read.unreadable.unwrap().addr, Addr::from(0x2010usize));
+    }
+}
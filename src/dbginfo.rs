@@ -9,14 +9,16 @@
 //! understanding program structure at runtime.
 
 use std::fmt::Debug;
+use std::path::Path;
 use std::rc::Rc;
 
 use gimli::{Attribute, Encoding, EndianRcSlice, NativeEndian, Reader};
-use object::{Object, ObjectSection};
+use object::{Object, ObjectSection, ObjectSegment};
 use serde::Serialize;
 
 use crate::dwarf_parse::GimliReaderThing;
 use crate::errors::{DebuggerError, Result};
+use crate::modules::LoadedModule;
 use crate::Addr;
 
 /// Type alias for the Gimli reader used for DWARF information parsing
@@ -27,15 +29,27 @@ pub type GimliLocation = gimli::Location<GimliReaderThing, <GimliReaderThing as
 /// Represents debug information extracted from an executable
 ///
 /// This struct holds the parsed DWARF debug information along with the
-/// original object file information, providing access to symbols, source
-/// locations, and other debug data.
-pub struct CMDebugInfo<'executable> {
-    /// The original object file information
-    pub object_info: object::File<'executable>,
+/// data of the original object file, providing access to symbols, source
+/// locations, and other debug data. It owns the data, so it does not borrow from anything.
+pub struct CMDebugInfo {
+    data: Rc<[u8]>,
     /// The parsed DWARF debug information
     pub dwarf: gimli::Dwarf<GimliReaderThing>,
 }
 
+/// Symbols from a separate file, placed at an address chosen by the user
+///
+/// Code that the debuggee did not load from an ELF file, like firmware blobs, JIT compiled code
+/// or modules that were unpacked into anonymous memory, has no symbols in the debuggee. If its
+/// symbols are available in a separate ELF file, they can be added at the address the code is
+/// at, see [`Debuggee::add_symbol_file`](crate::debuggee::Debuggee::add_symbol_file).
+pub struct SymbolFile {
+    /// The file as if it was loaded at the chosen address, to rebase its symbols
+    pub module: LoadedModule,
+    /// The debug information and the data of the file
+    pub info: CMDebugInfo,
+}
+
 /// Categorizes different types of symbols found in debug information
 ///
 /// This enum represents the various kinds of symbols that can be found in
@@ -69,6 +83,72 @@ pub enum SymbolKind {
 
     /// A lexical scope block
     Block,
+
+    /// A call in a function, with the return address as its low address
+    CallSite,
+
+    /// A parameter of a [`SymbolKind::CallSite`], which tells what was passed in a register
+    CallSiteParameter,
+
+    /// A member of a struct, union or class
+    Member,
+
+    /// A named value of an enumeration
+    Enumerator,
+
+    /// A struct or class type, with its [members](SymbolKind::Member) as children
+    StructType,
+
+    /// A union type, with its [members](SymbolKind::Member) as children
+    UnionType,
+
+    /// An enumeration type, with its [enumerators](SymbolKind::Enumerator) as children
+    EnumType,
+
+    /// Another name for the type in its datatype
+    Typedef,
+
+    /// A pointer to the type in its datatype, or to `void` if it has none
+    PointerType,
+
+    /// An array of elements of the type in its datatype, with a
+    /// [subrange](SymbolKind::SubrangeType) as child for each dimension
+    ArrayType,
+
+    /// The range of indices of one dimension of an [array](SymbolKind::ArrayType), with the
+    /// number of elements as its count
+    SubrangeType,
+
+    /// A C++ reference to the type in its datatype, which is stored like a pointer
+    ReferenceType,
+
+    /// A base class of a struct or class, with the base class as its datatype and its offset in
+    /// the derived class as member offset
+    Inheritance,
+
+    /// The part of a Rust enum that depends on the variant, with the discriminant
+    /// [member](SymbolKind::Member) and a [variant](SymbolKind::Variant) for each variant as
+    /// children
+    VariantPart,
+
+    /// A variant of a [variant part](SymbolKind::VariantPart), with the member that holds its
+    /// data as child and the discriminant that selects it as discriminant value
+    Variant,
+}
+
+/// An entry of a DWARF location list
+///
+/// Variables of optimized code are often in different places over the course of a function, or
+/// only exist in some parts of it. Their location is then described by a list of DWARF
+/// expressions, each of which is valid for a range of addresses.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LocationListEntry {
+    /// The first address at which the expression is valid
+    pub low_addr: Addr,
+    /// The address after the last address at which the expression is valid
+    pub high_addr: Addr,
+    /// The DWARF expression for the location
+    pub expression: gimli::Expression<GimliReaderThing>,
 }
 
 /// Represents a parsed symbol from the debug information
@@ -77,8 +157,9 @@ pub enum SymbolKind {
 /// information, including its name, address range, type information, and children
 /// symbols that are in its scope.
 ///
-/// Note that `encoding`, `frame_base` and `location` are skipped when serializing this with
-/// [`serde`], as they are [`gimli`] datatypes.
+/// Note that `encoding`, `frame_base`, `location`, `location_list`, `call_value` and
+/// `base_encoding` are skipped when serializing this with [`serde`], as they are [`gimli`]
+/// datatypes.
 #[derive(Clone, Serialize, PartialEq, Eq)]
 pub struct OwnedSymbol {
     offset: usize,
@@ -91,10 +172,25 @@ pub struct OwnedSymbol {
     #[serde(skip)]
     location: Option<Attribute<GimliReaderThing>>,
     #[serde(skip)]
+    location_list: Vec<LocationListEntry>,
+    #[serde(skip)]
     frame_base: Option<Attribute<GimliReaderThing>>,
+    #[serde(skip)]
+    call_value: Option<Attribute<GimliReaderThing>>,
     byte_size: Option<usize>,
+    member_offset: Option<usize>,
+    bit_size: Option<usize>,
+    data_bit_offset: Option<usize>,
+    const_value: Option<i64>,
+    #[serde(skip)]
+    base_encoding: Option<gimli::DwAte>,
     #[serde(skip)]
     encoding: gimli::Encoding,
+    decl_file: Option<String>,
+    linkage_name: Option<String>,
+    count: Option<usize>,
+    discr: Option<usize>,
+    discr_value: Option<i64>,
 }
 
 impl OwnedSymbol {
@@ -131,10 +227,22 @@ impl OwnedSymbol {
             kind,
             datatype: None,
             location: None,
+            location_list: Vec::new(),
             frame_base: None,
+            call_value: None,
             children: children.to_vec(),
             byte_size: None,
+            member_offset: None,
+            bit_size: None,
+            data_bit_offset: None,
+            const_value: None,
+            base_encoding: None,
             encoding,
+            decl_file: None,
+            linkage_name: None,
+            count: None,
+            discr: None,
+            discr_value: None,
         }
     }
 
@@ -178,6 +286,16 @@ impl OwnedSymbol {
         self.location = location;
     }
 
+    /// Sets the location list of this [`OwnedSymbol`].
+    pub fn set_location_list(&mut self, location_list: Vec<LocationListEntry>) {
+        self.location_list = location_list;
+    }
+
+    /// Sets the call value of this [`OwnedSymbol`].
+    pub fn set_call_value(&mut self, call_value: Option<Attribute<GimliReaderThing>>) {
+        self.call_value = call_value;
+    }
+
     /// Sets the frame base of this [`OwnedSymbol`].
     pub fn set_frame_base(&mut self, frame_base: Option<Attribute<GimliReaderThing>>) {
         self.frame_base = frame_base;
@@ -188,6 +306,56 @@ impl OwnedSymbol {
         self.byte_size = byte_size;
     }
 
+    /// Sets the member offset of this [`OwnedSymbol`].
+    pub fn set_member_offset(&mut self, member_offset: Option<usize>) {
+        self.member_offset = member_offset;
+    }
+
+    /// Sets the bit size of this [`OwnedSymbol`].
+    pub fn set_bit_size(&mut self, bit_size: Option<usize>) {
+        self.bit_size = bit_size;
+    }
+
+    /// Sets the data bit offset of this [`OwnedSymbol`].
+    pub fn set_data_bit_offset(&mut self, data_bit_offset: Option<usize>) {
+        self.data_bit_offset = data_bit_offset;
+    }
+
+    /// Sets the const value of this [`OwnedSymbol`].
+    pub fn set_const_value(&mut self, const_value: Option<i64>) {
+        self.const_value = const_value;
+    }
+
+    /// Sets the base encoding of this [`OwnedSymbol`].
+    pub fn set_base_encoding(&mut self, base_encoding: Option<gimli::DwAte>) {
+        self.base_encoding = base_encoding;
+    }
+
+    /// Sets the declaring file of this [`OwnedSymbol`].
+    pub fn set_decl_file(&mut self, decl_file: Option<String>) {
+        self.decl_file = decl_file;
+    }
+
+    /// Sets the linkage name of this [`OwnedSymbol`].
+    pub fn set_linkage_name(&mut self, linkage_name: Option<String>) {
+        self.linkage_name = linkage_name;
+    }
+
+    /// Sets the count of this [`OwnedSymbol`].
+    pub fn set_count(&mut self, count: Option<usize>) {
+        self.count = count;
+    }
+
+    /// Sets the discriminant of this [`OwnedSymbol`].
+    pub fn set_discr(&mut self, discr: Option<usize>) {
+        self.discr = discr;
+    }
+
+    /// Sets the discriminant value of this [`OwnedSymbol`].
+    pub fn set_discr_value(&mut self, discr_value: Option<i64>) {
+        self.discr_value = discr_value;
+    }
+
     /// Sets the encoding of this [`OwnedSymbol`].
     pub fn set_encoding(&mut self, encoding: gimli::Encoding) {
         self.encoding = encoding;
@@ -200,11 +368,36 @@ impl OwnedSymbol {
     }
 
     /// Returns the name of this [`OwnedSymbol`].
+    ///
+    /// Names that are mangled in the debug information are demangled when the symbol is parsed,
+    /// see [`demangle`].
     #[must_use]
     pub fn name(&self) -> Option<&str> {
         self.name.as_deref()
     }
 
+    /// Returns the linkage name of this [`OwnedSymbol`].
+    ///
+    /// This is the mangled name of the symbol in the symbol table, like `_ZN2ns5Point4normEv`,
+    /// which C++ and Rust compilers give to functions and global variables.
+    #[must_use]
+    pub fn linkage_name(&self) -> Option<&str> {
+        self.linkage_name.as_deref()
+    }
+
+    /// Returns the demangled linkage name of this [`OwnedSymbol`], or its name if it has none
+    ///
+    /// Unlike the name, the demangled linkage name is qualified with the namespaces and classes of
+    /// the symbol and includes the parameter types of C++ functions, like `ns::Point::norm() const`,
+    /// which tells overloaded functions apart.
+    #[must_use]
+    pub fn demangled_name(&self) -> Option<String> {
+        match &self.linkage_name {
+            Some(linkage_name) => Some(demangle(linkage_name)),
+            None => self.name.clone(),
+        }
+    }
+
     /// Returns the low addr of this [`OwnedSymbol`].
     #[must_use]
     pub fn low_addr(&self) -> Option<Addr> {
@@ -241,6 +434,24 @@ impl OwnedSymbol {
         self.location.as_ref()
     }
 
+    /// Returns the location list of this [`OwnedSymbol`].
+    ///
+    /// This is empty unless the location of the symbol is a location list, in which case
+    /// [`Self::location`] is only the reference to it.
+    #[must_use]
+    pub fn location_list(&self) -> &[LocationListEntry] {
+        &self.location_list
+    }
+
+    /// Returns the call value of this [`OwnedSymbol`].
+    ///
+    /// For a [`SymbolKind::CallSiteParameter`], this is the DWARF expression for the value that
+    /// was passed, evaluated in the frame of the caller.
+    #[must_use]
+    pub fn call_value(&self) -> Option<&Attribute<GimliReaderThing>> {
+        self.call_value.as_ref()
+    }
+
     /// Returns the frame base of this [`OwnedSymbol`].
     #[must_use]
     pub fn frame_base(&self) -> Option<&Attribute<GimliReaderThing>> {
@@ -253,15 +464,95 @@ impl OwnedSymbol {
         self.byte_size
     }
 
+    /// Returns the member offset of this [`OwnedSymbol`].
+    ///
+    /// For a [`SymbolKind::Member`], this is the offset in bytes from the start of the struct.
+    #[must_use]
+    pub fn member_offset(&self) -> Option<usize> {
+        self.member_offset
+    }
+
+    /// Returns the bit size of this [`OwnedSymbol`].
+    ///
+    /// This is only set for bit-fields, which do not take up whole bytes.
+    #[must_use]
+    pub fn bit_size(&self) -> Option<usize> {
+        self.bit_size
+    }
+
+    /// Returns the data bit offset of this [`OwnedSymbol`].
+    ///
+    /// For a bit-field, this is the offset in bits from the start of the struct to the lowest
+    /// bit of the member.
+    #[must_use]
+    pub fn data_bit_offset(&self) -> Option<usize> {
+        self.data_bit_offset
+    }
+
+    /// Returns the const value of this [`OwnedSymbol`].
+    ///
+    /// For a [`SymbolKind::Enumerator`], this is the value that the enumerator stands for.
+    #[must_use]
+    pub fn const_value(&self) -> Option<i64> {
+        self.const_value
+    }
+
+    /// Returns the count of this [`OwnedSymbol`].
+    ///
+    /// For a [`SymbolKind::SubrangeType`], this is the number of elements in its dimension of the
+    /// array. It is [None] if the number is only known at runtime, like for variable length
+    /// arrays, or not at all, like for flexible array members.
+    #[must_use]
+    pub fn count(&self) -> Option<usize> {
+        self.count
+    }
+
+    /// Returns the discriminant of this [`OwnedSymbol`].
+    ///
+    /// For a [`SymbolKind::VariantPart`], this is the offset of the member that holds the
+    /// discriminant, which is one of its children. It is [None] if the enum has only one variant.
+    #[must_use]
+    pub fn discr(&self) -> Option<usize> {
+        self.discr
+    }
+
+    /// Returns the discriminant value of this [`OwnedSymbol`].
+    ///
+    /// For a [`SymbolKind::Variant`], this is the value of the discriminant that selects it. It is
+    /// [None] for the variant that is selected by all other values, like the variant that holds
+    /// the data of a niche-optimized enum.
+    #[must_use]
+    pub fn discr_value(&self) -> Option<i64> {
+        self.discr_value
+    }
+
+    /// Returns the base encoding of this [`OwnedSymbol`].
+    ///
+    /// For a [`SymbolKind::BaseType`], this tells how the bytes of a value are to be interpreted,
+    /// for example as a signed integer.
+    #[must_use]
+    pub fn base_encoding(&self) -> Option<gimli::DwAte> {
+        self.base_encoding
+    }
+
     /// Returns the encoding of this [`OwnedSymbol`].
     #[must_use]
     pub fn encoding(&self) -> Encoding {
         self.encoding
     }
+
+    /// Returns the declaring file of this [`OwnedSymbol`].
+    ///
+    /// This is the path of the source file in which the symbol was declared, which can be a
+    /// header instead of the file of its compilation unit.
+    #[must_use]
+    pub fn decl_file(&self) -> Option<&str> {
+        self.decl_file.as_deref()
+    }
 }
 
-impl<'executable> CMDebugInfo<'executable> {
-    /// Creates a new debug information instance from an object file
+impl CMDebugInfo {
+    /// Creates a new debug information instance from the data of an object file
     ///
     /// This function parses the DWARF debug information from the provided object file
     /// and creates a structured representation that can be used for debugging operations.
@@ -271,7 +562,7 @@ impl<'executable> CMDebugInfo<'executable> {
     ///
     /// # Parameters
     ///
-    /// * `object_info` - The object file containing debug information
+    /// * `data` - The contents of the object file containing debug information
     ///
     /// # Returns
     ///
@@ -280,13 +571,26 @@ impl<'executable> CMDebugInfo<'executable> {
     ///
     /// # Errors
     ///
-    /// This function will return an error if the DWARF information in the object file
-    /// is invalid or cannot be parsed.
+    /// This function will return an error if `data` is not a valid object file, or the DWARF
+    /// information in it is invalid or cannot be parsed.
     ///
     /// # Panics
     ///
     /// This function panics if the dwarf information cannot be loaded
-    pub fn build(object_info: object::File<'executable>) -> Result<Self> {
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use coreminer::dbginfo::CMDebugInfo;
+    ///
+    /// let data = std::fs::read("/proc/self/exe").unwrap();
+    /// let dbginfo = CMDebugInfo::build(data).unwrap();
+    /// assert!(!dbginfo.data().is_empty());
+    /// assert!(CMDebugInfo::build(vec![0; 16]).is_err());
+    /// ```
+    pub fn build(data: impl Into<Rc<[u8]>>) -> Result<Self> {
+        let data = data.into();
+        let object_info = object::File::parse(&*data)?;
         let loader = |section: gimli::SectionId| -> std::result::Result<_, ()> {
             // does never fail surely
             let data = object_info
@@ -300,7 +604,68 @@ impl<'executable> CMDebugInfo<'executable> {
         };
         let dwarf = gimli::Dwarf::load(loader).unwrap();
 
-        Ok(CMDebugInfo { object_info, dwarf })
+        Ok(CMDebugInfo { data, dwarf })
+    }
+
+    /// Gets the data of the object file
+    #[must_use]
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Parses the object file again, borrowing from the data that is owned by `self`
+    ///
+    /// # Errors
+    ///
+    /// Cannot fail in practice, since the data was already parsed successfully in
+    /// [`Self::build`].
+    pub fn object_info(&self) -> Result<object::File<'_>> {
+        Ok(object::File::parse(&*self.data)?)
+    }
+}
+
+impl SymbolFile {
+    /// Reads the symbols of the ELF file at `path`, to be placed at `base`
+    ///
+    /// `base` is the address of the lowest loadable segment of the file, or of its lowest
+    /// section if it has no segments, like a relocatable object file. The symbols are rebased
+    /// by the difference between `base` and that virtual address.
+    ///
+    /// # Errors
+    ///
+    /// This function fails if the file cannot be read or is not a valid object file.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use coreminer::addr::Addr;
+    /// use coreminer::dbginfo::SymbolFile;
+    ///
+    /// let file = SymbolFile::load("/proc/self/exe", Addr::from(0x7000_0000usize)).unwrap();
+    /// assert_eq!(file.module.base, Addr::from(0x7000_0000usize));
+    /// ```
+    pub fn load(path: impl AsRef<Path>, base: Addr) -> Result<Self> {
+        let path = path.as_ref();
+        let info = CMDebugInfo::build(std::fs::read(path)?)?;
+        let obj = info.object_info()?;
+        let lowest = obj
+            .segments()
+            .filter(|s| s.size() > 0)
+            .map(|s| s.address())
+            .min()
+            .or_else(|| {
+                obj.sections()
+                    .filter(|s| s.address() != 0 && s.size() > 0)
+                    .map(|s| s.address())
+                    .min()
+            })
+            .unwrap_or(0);
+        let module = LoadedModule {
+            path: path.to_string_lossy().to_string(),
+            base,
+            bias: base.usize().wrapping_sub(lowest as usize),
+        };
+        Ok(Self { module, info })
     }
 }
 
@@ -314,6 +679,25 @@ impl TryFrom<gimli::DwTag> for SymbolKind {
             gimli::DW_TAG_constant => SymbolKind::Constant,
             gimli::DW_TAG_formal_parameter => SymbolKind::Parameter,
             gimli::DW_TAG_base_type => SymbolKind::BaseType,
+            gimli::DW_TAG_call_site | gimli::DW_TAG_GNU_call_site => SymbolKind::CallSite,
+            gimli::DW_TAG_call_site_parameter | gimli::DW_TAG_GNU_call_site_parameter => {
+                SymbolKind::CallSiteParameter
+            }
+            gimli::DW_TAG_member => SymbolKind::Member,
+            gimli::DW_TAG_enumerator => SymbolKind::Enumerator,
+            gimli::DW_TAG_structure_type | gimli::DW_TAG_class_type => SymbolKind::StructType,
+            gimli::DW_TAG_union_type => SymbolKind::UnionType,
+            gimli::DW_TAG_enumeration_type => SymbolKind::EnumType,
+            gimli::DW_TAG_typedef => SymbolKind::Typedef,
+            gimli::DW_TAG_pointer_type => SymbolKind::PointerType,
+            gimli::DW_TAG_array_type => SymbolKind::ArrayType,
+            gimli::DW_TAG_subrange_type => SymbolKind::SubrangeType,
+            gimli::DW_TAG_reference_type | gimli::DW_TAG_rvalue_reference_type => {
+                SymbolKind::ReferenceType
+            }
+            gimli::DW_TAG_inheritance => SymbolKind::Inheritance,
+            gimli::DW_TAG_variant_part => SymbolKind::VariantPart,
+            gimli::DW_TAG_variant => SymbolKind::Variant,
             gimli::DW_TAG_try_block
             | gimli::DW_TAG_catch_block
             | gimli::DW_TAG_lexical_block
@@ -336,17 +720,59 @@ impl Debug for OwnedSymbol {
                 "location",
                 &format_args!("{}", &dbg_large_option(self.location())),
             )
+            .field("location_list", &self.location_list.len())
             .field(
                 "frame_base",
                 &format_args!("{}", &dbg_large_option(self.frame_base())),
             )
+            .field(
+                "call_value",
+                &format_args!("{}", &dbg_large_option(self.call_value())),
+            )
             .field("byte_size", &self.byte_size)
+            .field("member_offset", &self.member_offset)
+            .field("bit_size", &self.bit_size)
+            .field("data_bit_offset", &self.data_bit_offset)
+            .field("const_value", &self.const_value)
+            .field("base_encoding", &self.base_encoding)
             .field("children", &self.children)
             .field("encoding", &self.encoding)
+            .field("decl_file", &self.decl_file)
+            .field("linkage_name", &self.linkage_name)
+            .field("count", &self.count)
+            .field("discr", &self.discr)
+            .field("discr_value", &self.discr_value)
             .finish()
     }
 }
 
+/// Demangles the name of a symbol of a Rust or C++ program
+///
+/// Rust names are demangled without their hash, like `core::panicking::panic`, and C++ names
+/// with their parameter types, like `ns::Point::scale(double)`. Names that are not mangled, like
+/// those of C functions, are returned unchanged.
+///
+/// # Examples
+///
+/// ```
+/// use coreminer::dbginfo::demangle;
+///
+/// assert_eq!(demangle("_ZN2ns5Point5scaleEd"), "ns::Point::scale(double)");
+/// assert_eq!(demangle("_ZN4core9panicking5panic17h0123456789abcdefE"), "core::panicking::panic");
+/// assert_eq!(demangle("main"), "main");
+/// ```
+#[must_use]
+pub fn demangle(name: &str) -> String {
+    // legacy Rust names are valid C++ names as well, but only rustc_demangle removes the hash
+    if let Ok(demangled) = rustc_demangle::try_demangle(name) {
+        return format!("{demangled:#}");
+    }
+    match cpp_demangle::Symbol::new(name) {
+        Ok(symbol) => symbol.to_string(),
+        Err(_) => name.to_string(),
+    }
+}
+
 /// Searches for [symbols](OwnedSymbol) matching a predicate in a symbol hierarchy
 ///
 /// This function recursively traverses a symbol tree and collects all symbols
@@ -481,13 +907,64 @@ mod tests {
             SymbolKind::try_from(gimli::DW_TAG_lexical_block).unwrap(),
             SymbolKind::Block
         );
-        // Test unknown tag becomes Other
+        assert_eq!(
+            SymbolKind::try_from(gimli::DW_TAG_class_type).unwrap(),
+            SymbolKind::StructType
+        );
+        assert_eq!(
+            SymbolKind::try_from(gimli::DW_TAG_typedef).unwrap(),
+            SymbolKind::Typedef
+        );
         assert_eq!(
             SymbolKind::try_from(gimli::DW_TAG_array_type).unwrap(),
+            SymbolKind::ArrayType
+        );
+        assert_eq!(
+            SymbolKind::try_from(gimli::DW_TAG_subrange_type).unwrap(),
+            SymbolKind::SubrangeType
+        );
+        assert_eq!(
+            SymbolKind::try_from(gimli::DW_TAG_variant_part).unwrap(),
+            SymbolKind::VariantPart
+        );
+        assert_eq!(
+            SymbolKind::try_from(gimli::DW_TAG_variant).unwrap(),
+            SymbolKind::Variant
+        );
+        assert_eq!(
+            SymbolKind::try_from(gimli::DW_TAG_rvalue_reference_type).unwrap(),
+            SymbolKind::ReferenceType
+        );
+        assert_eq!(
+            SymbolKind::try_from(gimli::DW_TAG_inheritance).unwrap(),
+            SymbolKind::Inheritance
+        );
+
+        // Test unknown tag becomes Other
+        assert_eq!(
+            SymbolKind::try_from(gimli::DW_TAG_label).unwrap(),
             SymbolKind::Other
         );
     }
 
+    #[test]
+    fn test_demangled_name() {
+        let mut sym = OwnedSymbol::new(0, SymbolKind::Function, &[], test_encoding());
+        sym.set_name(Some("scale".to_string()));
+        assert_eq!(sym.demangled_name().as_deref(), Some("scale"));
+
+        sym.set_linkage_name(Some("_ZN2ns5Point5scaleEd".to_string()));
+        assert_eq!(sym.name(), Some("scale"));
+        assert_eq!(
+            sym.demangled_name().as_deref(),
+            Some("ns::Point::scale(double)")
+        );
+
+        assert_eq!(demangle("_ZNK2ns5Point4normEv"), "ns::Point::norm() const");
+        assert_eq!(demangle("_ZN2ns7counterE"), "ns::counter");
+        assert_eq!(demangle("_Z"), "_Z");
+    }
+
     #[test]
     fn test_search_through_symbols() {
         let encoding = test_encoding();
@@ -500,13 +977,16 @@ mod tests {
         let parent = OwnedSymbol::new(0, SymbolKind::Function, &[child1, child2], encoding);
 
         // Search for symbol by name
-        let results = search_through_symbols(&[parent.clone()], |s| s.name() == Some("target"));
+        let results = search_through_symbols(std::slice::from_ref(&parent), |s| {
+            s.name() == Some("target")
+        });
         assert_eq!(results.len(), 1);
         assert_eq!(results[0].name(), Some("target"));
 
         // Search by kind
-        let results =
-            search_through_symbols(&[parent.clone()], |s| s.kind() == SymbolKind::Variable);
+        let results = search_through_symbols(std::slice::from_ref(&parent), |s| {
+            s.kind() == SymbolKind::Variable
+        });
         assert_eq!(results.len(), 1);
         assert_eq!(results[0].offset(), 1);
     }
@@ -533,7 +1013,11 @@ mod tests {
             r#"{"offset":0,"name":"parent","low_addr":null,"high_addr":null,"datatype":null,"#,
             r#""kind":"Function","children":[{"offset":1,"name":"child","low_addr":null,"#,
             r#""high_addr":null,"datatype":null,"kind":"Variable","children":[],"#,
-            r#""byte_size":null}],"byte_size":null}"#
+            r#""byte_size":null,"member_offset":null,"bit_size":null,"data_bit_offset":null,"#,
+            r#""const_value":null,"decl_file":null,"linkage_name":null,"count":null,"#,
+            r#""discr":null,"discr_value":null}],"byte_size":null,"member_offset":null,"#,
+            r#""bit_size":null,"data_bit_offset":null,"const_value":null,"decl_file":null,"#,
+            r#""linkage_name":null,"count":null,"discr":null,"discr_value":null}"#
         );
 
         let encoding = test_encoding();
@@ -549,4 +1033,17 @@ mod tests {
         let json = serde_json::to_string(&parent).unwrap();
         assert_eq!(json, JSON_PREDEFINED);
     }
+
+    #[test]
+    fn test_symbol_file_bias() {
+        // placing the test binary where it is loaded must give the same bias as the process
+        let pid = nix::unistd::getpid();
+        let exe = crate::modules::executable_module(pid).unwrap();
+        let file = SymbolFile::load("/proc/self/exe", exe.base).unwrap();
+        assert_eq!(file.module.bias, exe.bias);
+
+        let obj = file.info.object_info().unwrap();
+        let addr = crate::bpfile::symbol_address(&obj, "main").expect("no main symbol");
+        assert_eq!(file.module.to_process(addr), exe.to_process(addr));
+    }
 }
@@ -7,25 +7,52 @@
 //! is controlled through the [ptrace] API and manages debug symbols, breakpoints,
 //! memory access, and other low-level debugging operations.
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fmt::Display;
+use std::fs::File;
+use std::path::Path;
 
 use gimli::{
-    Attribute, DW_AT_frame_base, DW_AT_high_pc, DW_AT_location, DW_AT_low_pc, DW_AT_name,
-    DW_AT_type, Unit,
+    Attribute, DW_AT_GNU_call_site_value, DW_AT_MIPS_linkage_name, DW_AT_bit_offset,
+    DW_AT_bit_size, DW_AT_byte_size, DW_AT_call_return_pc, DW_AT_call_value, DW_AT_const_value,
+    DW_AT_count, DW_AT_data_bit_offset, DW_AT_data_member_location, DW_AT_decl_file, DW_AT_discr,
+    DW_AT_discr_value, DW_AT_encoding, DW_AT_frame_base, DW_AT_high_pc, DW_AT_linkage_name,
+    DW_AT_location, DW_AT_low_pc, DW_AT_lower_bound, DW_AT_name, DW_AT_specification, DW_AT_type,
+    DW_AT_upper_bound, Unit,
 };
+use nix::errno::Errno;
 use nix::sys::ptrace;
+use nix::sys::wait::{waitid, waitpid, Id, WaitPidFlag, WaitStatus};
 use nix::unistd::Pid;
-use tracing::{debug, warn};
+use tracing::{debug, trace, warn};
 
+use crate::addr::AddrRange;
 use crate::breakpoint::{Breakpoint, INT3_BYTE};
-use crate::dbginfo::{search_through_symbols, CMDebugInfo, OwnedSymbol, SymbolKind};
+use crate::consts::SI_KERNEL;
+use crate::coredump::CoreDump;
+use crate::dbginfo::{
+    demangle, search_through_symbols, CMDebugInfo, OwnedSymbol, SymbolFile, SymbolKind,
+};
+use crate::debugreg;
 use crate::disassemble::Disassembly;
+use crate::dot::{CallGraph, FunctionRange};
 use crate::dwarf_parse::GimliReaderThing;
-use crate::errors::DebuggerError;
-use crate::memorymap::ProcessMemoryMap;
+use crate::limits::{LimitKind, Limits};
+use crate::lines::{self, LineTable};
+use crate::memorymap::{MemoryRegion, ProcessMemoryMap};
+use crate::modules::{executable_module, loaded_modules, LoadedModule};
+use crate::pageguard::PageGuard;
+use crate::perf::PerfCounters;
+use crate::procfs::{AuxVector, Environment, FileDescriptor, ProcessInfo};
+use crate::seccomp::SyscallFilter;
 use crate::stack::Stack;
-use crate::{get_reg, mem_read_word, Result};
+use crate::syscall::SyscallStop;
+use crate::thread::Thread;
+use crate::unwind::Backtrace;
+use crate::uprobe::Uprobe;
+use crate::watchpoint::Watchpoint;
+use crate::xref::{find_calls, find_xrefs, Xref};
+use crate::{get_reg, mem_read_word, DebuggerError, Result};
 use crate::{mem_read, Addr};
 
 /// Represents a process being debugged
@@ -37,11 +64,72 @@ pub struct Debuggee {
     /// Process ID of the debugged process
     pub(crate) pid: Pid,
 
+    /// The thread table, the threads of the process by thread ID
+    pub(crate) threads: BTreeMap<Pid, Thread>,
+
     /// Map of active breakpoints by address
     pub(crate) breakpoints: HashMap<Addr, Breakpoint>,
 
+    /// Map of active watchpoints by start address of the watched range
+    pub(crate) watchpoints: HashMap<Addr, Watchpoint>,
+
+    /// Map of page guards by start address of the watched range
+    pub(crate) page_guards: HashMap<Addr, PageGuard>,
+
+    /// Map of uprobes by probed address
+    pub(crate) uprobes: HashMap<Addr, Uprobe>,
+
+    /// Hardware performance counters, once they were started
+    pub(crate) perf_counters: Option<PerfCounters>,
+
     /// Debug symbols extracted from the executable
     pub(crate) symbols: Vec<OwnedSymbol>,
+
+    /// Whether the debuggee is currently stopped inside of a syscall
+    ///
+    /// Syscall stops do not say if they are an entry or an exit stop, so this needs to be tracked
+    /// while continuing with `PTRACE_SYSCALL`.
+    pub(crate) in_syscall: bool,
+
+    /// The syscall stop the debuggee is currently stopped at, if any
+    pub(crate) syscall_stop: Option<SyscallStop>,
+
+    /// The seccomp filter that the debuggee was launched with, if any
+    pub(crate) seccomp: Option<SyscallFilter>,
+
+    /// The backtraces that were taken so far, for [`CallGraph::from_backtraces`]
+    pub(crate) backtraces: Vec<Backtrace>,
+
+    /// The caps on expensive requests, copied from the debugger
+    pub(crate) limits: Limits,
+
+    /// Whether writes of variables are read back, copied from the debugger
+    pub(crate) verify_writes: bool,
+
+    /// The source lines of the executable and their addresses
+    pub(crate) lines: LineTable,
+
+    /// The executable and its load bias, which the addresses of the debug information are
+    /// rebased with
+    pub(crate) executable: LoadedModule,
+
+    /// The modules that were given when the debugger was created, which are used instead of
+    /// reading them from the memory map of the process
+    pub(crate) modules: Option<Vec<LoadedModule>>,
+
+    /// The files whose symbols were added with [`Self::add_symbol_file`]
+    pub(crate) symbol_files: Vec<SymbolFile>,
+
+    /// The write end of the pipe that is the stdin of the debuggee, if it was launched with one
+    pub(crate) stdin: Option<File>,
+
+    /// Processes forked by the debuggee that are held stopped, see
+    /// [`FollowFork::Both`](crate::debugger::FollowFork::Both)
+    pub(crate) held: Vec<Pid>,
+
+    /// The core dump the state of the process is read from, if the process is not running
+    /// anymore, see [`Debugger::open_core`](crate::debugger::Debugger::open_core)
+    pub(crate) core: Option<CoreDump>,
 }
 
 impl Debuggee {
@@ -52,6 +140,8 @@ impl Debuggee {
     /// * `pid` - The process ID of the debugged process
     /// * `dbginfo` - Debug information extracted from the executable
     /// * `breakpoints` - Any initial breakpoints to set
+    /// * `modules` - The modules of the process with the executable first, or [`None`] to read
+    ///   them from the memory map of the process
     ///
     /// # Returns
     ///
@@ -64,26 +154,73 @@ impl Debuggee {
     /// or if the process cannot be accessed.
     pub(crate) fn build(
         pid: Pid,
-        dbginfo: &CMDebugInfo<'_>,
+        dbginfo: &CMDebugInfo,
         breakpoints: HashMap<Addr, Breakpoint>,
+        modules: Option<Vec<LoadedModule>>,
     ) -> Result<Self> {
         let mut symbols = Vec::new();
         let dwarf = &dbginfo.dwarf;
         let mut iter = dwarf.units();
+        let executable = match modules.as_ref().map(|modules| modules.first()) {
+            Some(Some(executable)) => executable.clone(),
+            Some(None) => return Err(DebuggerError::NoExecutableModule),
+            None => executable_module(pid)?,
+        };
 
         while let Some(header) = iter.next()? {
             let unit = dwarf.unit(header)?;
             let mut tree = unit.entries_tree(None)?;
-            symbols.push(Self::process_tree(pid, dwarf, &unit, tree.root()?)?);
+            symbols.push(Self::process_tree(&executable, dwarf, &unit, tree.root()?)?);
         }
+        let lines = match LineTable::build(dwarf, &executable) {
+            Ok(lines) => lines,
+            Err(e) => {
+                warn!("could not read the line table: {e}");
+                LineTable::default()
+            }
+        };
 
         Ok(Self {
-            pid,
             breakpoints,
-            symbols,
+            lines,
+            modules,
+            ..Self::from_symbols(pid, symbols, executable)
         })
     }
 
+    /// Creates a debuggee with the debug symbols `symbols` and nothing else set up
+    ///
+    /// There are no breakpoints, source lines or given modules, see [`Self::build`].
+    pub(crate) fn from_symbols(
+        pid: Pid,
+        symbols: Vec<OwnedSymbol>,
+        executable: LoadedModule,
+    ) -> Self {
+        Self {
+            pid,
+            threads: BTreeMap::from([(pid, Thread::stopped(pid, true))]),
+            breakpoints: HashMap::new(),
+            watchpoints: HashMap::new(),
+            page_guards: HashMap::new(),
+            uprobes: HashMap::new(),
+            perf_counters: None,
+            symbols,
+            in_syscall: false,
+            syscall_stop: None,
+            seccomp: None,
+            backtraces: Vec::new(),
+            limits: Limits::default(),
+            verify_writes: false,
+            lines: LineTable::default(),
+            executable,
+            modules: None,
+            symbol_files: Vec::new(),
+            stdin: None,
+            held: Vec::new(),
+            core: None,
+        }
+    }
+
     /// Terminates the debugged process
     ///
     /// Uses `PTRAC_KILL` to `SIGKILL` the debuggee process.
@@ -97,10 +234,159 @@ impl Debuggee {
     ///
     /// This function can fail if the ptrace kill operation fails.
     pub fn kill(&self) -> Result<()> {
+        // the process ID of a core dump may belong to an unrelated process by now
+        if self.core.is_some() {
+            return Ok(());
+        }
+        for pid in &self.held {
+            if let Err(e) = ptrace::kill(*pid) {
+                warn!("could not kill the held process {pid}: {e}");
+            }
+        }
         ptrace::kill(self.pid)?;
         Ok(())
     }
 
+    /// Removes the breakpoints from `pid` and detaches from it, so that it runs on
+    ///
+    /// `pid` is a process that was copied from the debuggee by `fork`, or the debuggee itself
+    /// after the debugger followed the copy. It has the `INT3` of the software breakpoints in
+    /// its memory, which would kill it with a `SIGTRAP` once it runs without the debugger.
+    ///
+    /// # Errors
+    ///
+    /// This function fails if the memory or the debug registers of `pid` cannot be written, or
+    /// if it cannot be detached.
+    pub(crate) fn release(&self, pid: Pid) -> Result<()> {
+        for bp in self.breakpoints.values().filter(|bp| bp.is_enabled()) {
+            match bp.hardware_slot() {
+                Some(slot) => debugreg::clear_breakpoint(pid, slot)?,
+                None => bp.write_through(pid, false)?,
+            }
+        }
+        debug!("detaching from process {pid}");
+        ptrace::detach(pid, None)?;
+        Ok(())
+    }
+
+    /// Releases all held processes, see [`Self::release`]
+    ///
+    /// Processes that cannot be released, for example because they were killed meanwhile, are
+    /// only logged.
+    pub(crate) fn release_held(&mut self) {
+        for pid in std::mem::take(&mut self.held) {
+            if let Err(e) = self.release(pid) {
+                warn!("could not release the held process {pid}: {e}");
+            }
+        }
+    }
+
+    /// Writes the `INT3` of all software breakpoints through `pid` again
+    ///
+    /// This is needed after a `vfork` child that shared the memory of the debuggee was released,
+    /// which removed them.
+    ///
+    /// # Errors
+    ///
+    /// This function fails if the memory cannot be written.
+    pub(crate) fn rearm_breakpoints(&self, pid: Pid) -> Result<()> {
+        for bp in self
+            .breakpoints
+            .values()
+            .filter(|bp| bp.is_enabled() && !bp.is_hardware())
+        {
+            bp.write_through(pid, true)?;
+        }
+        Ok(())
+    }
+
+    /// Makes `child`, a copy of the debuggee that was made by `fork`, the debuggee
+    ///
+    /// The breakpoints move along, see [`Breakpoint::move_to`]. The child has only the thread
+    /// that called `fork`.
+    ///
+    /// # Errors
+    ///
+    /// This function fails if a hardware breakpoint cannot be set in `child`.
+    pub(crate) fn follow(&mut self, child: Pid) -> Result<()> {
+        for bp in self.breakpoints.values_mut() {
+            bp.move_to(child)?;
+        }
+        self.pid = child;
+        self.threads = BTreeMap::from([(child, Thread::stopped(child, true))]);
+        self.in_syscall = false;
+        self.syscall_stop = None;
+        Ok(())
+    }
+
+    /// Reaps the threads other than the main thread that have exited
+    ///
+    /// The kernel only reports the exit of the main thread once all other traced threads were
+    /// reaped. The exited threads are removed from the thread table the next time they are
+    /// resumed.
+    ///
+    /// Stops of the threads are reported by `waitid` too, even without `WSTOPPED`, so the status
+    /// is only looked at first and the thread is reaped if it has exited.
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if `waitid` fails for another reason than the thread being gone.
+    pub(crate) fn reap_threads(&self) -> Result<()> {
+        let peek = WaitPidFlag::WEXITED
+            | WaitPidFlag::WNOHANG
+            | WaitPidFlag::WNOWAIT
+            | WaitPidFlag::__WALL;
+        for tid in self.threads.keys().filter(|tid| **tid != self.pid) {
+            match waitid(Id::Pid(*tid), peek) {
+                Ok(WaitStatus::Exited(..) | WaitStatus::Signaled(..)) => {
+                    let status = waitpid(*tid, Some(WaitPidFlag::WNOHANG | WaitPidFlag::__WALL));
+                    trace!("reaped thread {tid}: {status:?}");
+                }
+                Ok(_) | Err(Errno::ECHILD) => (),
+                Err(e) => return Err(e.into()),
+            }
+        }
+        Ok(())
+    }
+
+    /// Moves the instruction pointer of the thread `tid` back to the software [`Breakpoint`] it hit
+    ///
+    /// This is for threads other than the main thread, which stopped with a `SIGTRAP` that was
+    /// not handled yet. The `SIGTRAP` was caused by an `int3` if the kernel sent it. If there is
+    /// a software breakpoint at the `int3`, or the `int3` is gone because its breakpoint was
+    /// disabled or removed since, the instruction pointer is moved back, so that the original
+    /// instruction is executed when the thread is resumed.
+    ///
+    /// # Returns
+    ///
+    /// The address of the `int3`, or [`None`] if the `SIGTRAP` was caused by something else or
+    /// the `int3` belongs to the debuggee.
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if the signal information, registers or memory cannot be accessed
+    /// through the thread.
+    pub(crate) fn rewind_breakpoint_hit(&self, tid: Pid) -> Result<Option<Addr>> {
+        if ptrace::getsiginfo(tid)?.si_code != SI_KERNEL {
+            return Ok(None);
+        }
+        let mut regs = ptrace::getregs(tid)?;
+        let Some(bp_addr) = Addr::from(regs.rip).checked_sub(1) else {
+            return Ok(None);
+        };
+        let ours = match self.breakpoints.get(&bp_addr) {
+            Some(bp) => !bp.is_hardware(),
+            None => mem_read_word(tid, bp_addr)? as u8 != INT3_BYTE,
+        };
+        if !ours {
+            return Ok(None);
+        }
+        trace!("moving rip of thread {tid} back to the breakpoint at {bp_addr}");
+        regs.rip = bp_addr.u64();
+        ptrace::setregs(tid, regs)?;
+        Ok(Some(bp_addr))
+    }
+
     /// Gets the memory map of a process by its PID
     ///
     /// # Parameters
@@ -121,8 +407,8 @@ impl Debuggee {
 
     /// Gets the base address of a process by its PID
     ///
-    /// The base address is typically the starting address of the first mapping in
-    /// the process's address space.
+    /// The base address is the lowest address the executable of the process is mapped at, see
+    /// [`executable_base`](crate::modules::executable_base).
     ///
     /// # Parameters
     ///
@@ -137,13 +423,7 @@ impl Debuggee {
     ///
     /// This function can fail if the process's memory map cannot be accessed.
     pub fn get_base_addr_by_pid(pid: Pid) -> Result<Addr> {
-        let process_map = Self::get_process_map_by_pid(pid)?;
-        if process_map.regions.is_empty() {
-            return Err(DebuggerError::NoDebugee);
-        }
-
-        // Get the start address of the first memory region
-        Ok(process_map.regions[0].start_address)
+        crate::modules::executable_base(pid)
     }
 
     /// Gets the memory map of the debugged process
@@ -161,6 +441,98 @@ impl Debuggee {
         Self::get_process_map_by_pid(self.pid)
     }
 
+    /// Gets the environment of the debugged process
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Environment)` - The environment the process was started with
+    /// * `Err(DebuggerError)` - If the environment could not be read
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if `/proc/<pid>/environ` cannot be read.
+    #[inline]
+    pub fn get_environment(&self) -> Result<Environment> {
+        Environment::read(self.pid)
+    }
+
+    /// Gets the open file descriptors of the debugged process
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Vec<FileDescriptor>)` - The open file descriptors, sorted by their number
+    /// * `Err(DebuggerError)` - If the file descriptors could not be read
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if `/proc/<pid>/fd` cannot be read.
+    #[inline]
+    pub fn get_file_descriptors(&self) -> Result<Vec<FileDescriptor>> {
+        FileDescriptor::read_all(self.pid)
+    }
+
+    /// Gets the status and resource usage of the debugged process
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(ProcessInfo)` - The status of the process
+    /// * `Err(DebuggerError)` - If the status could not be read
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if `/proc/<pid>/status` or `/proc/<pid>/stat` cannot be read or
+    /// parsed.
+    #[inline]
+    pub fn get_process_info(&self) -> Result<ProcessInfo> {
+        ProcessInfo::read(self.pid)
+    }
+
+    /// Gets the auxiliary vector of the debugged process
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(AuxVector)` - The auxiliary vector of the process
+    /// * `Err(DebuggerError)` - If the auxiliary vector could not be read
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if `/proc/<pid>/auxv` cannot be read.
+    #[inline]
+    pub fn get_auxv(&self) -> Result<AuxVector> {
+        match &self.core {
+            Some(core) => Ok(core.auxv().clone()),
+            None => AuxVector::read(self.pid),
+        }
+    }
+
+    /// Reads bytes from the memory of the debuggee, or from its core dump
+    ///
+    /// # Returns
+    ///
+    /// The number of bytes that were read, which can be less than the length of `buf`.
+    ///
+    /// # Errors
+    ///
+    /// This function fails if the memory at `addr` cannot be read.
+    pub(crate) fn read_bytes(&self, buf: &mut [u8], addr: Addr) -> Result<usize> {
+        match &self.core {
+            Some(core) => core.read(buf, addr),
+            None => mem_read(buf, self.pid, addr),
+        }
+    }
+
+    /// Reads a [Word](crate::Word) from the memory of the debuggee, or from its core dump
+    ///
+    /// # Errors
+    ///
+    /// This function fails if the memory at `addr` cannot be read.
+    pub(crate) fn read_word(&self, addr: Addr) -> Result<crate::Word> {
+        match &self.core {
+            Some(core) => core.read_word(addr),
+            None => mem_read_word(self.pid, addr),
+        }
+    }
+
     /// Gets the base address of the debugged process
     ///
     /// # Returns
@@ -175,6 +547,104 @@ impl Debuggee {
         Self::get_base_addr_by_pid(self.pid)
     }
 
+    /// Gets the executable of the debugged process
+    ///
+    /// The [`LoadedModule`] has the load bias that the addresses of the symbols, the debug
+    /// information and the line table of the executable are rebased with. Unlike the base
+    /// address, the bias is zero for executables that are not position independent.
+    #[must_use]
+    pub fn get_executable(&self) -> &LoadedModule {
+        &self.executable
+    }
+
+    /// Gets the modules loaded into the debugged process, ordered by their base address
+    ///
+    /// If the modules were given when the debugger was created, those are returned instead of
+    /// the ones in the memory map of the process. The files added with
+    /// [`Self::add_symbol_file`] are included as well.
+    ///
+    /// # Errors
+    ///
+    /// This function fails if the memory map of the process cannot be read.
+    pub fn modules(&self) -> Result<Vec<LoadedModule>> {
+        let mut modules = match &self.modules {
+            Some(modules) => modules.clone(),
+            None => loaded_modules(self.pid)?,
+        };
+        modules.extend(self.symbol_files.iter().map(|f| f.module.clone()));
+        modules.sort_by_key(|m| m.base);
+        Ok(modules)
+    }
+
+    /// Gets the files whose symbols were added with [`Self::add_symbol_file`]
+    #[must_use]
+    pub fn symbol_files(&self) -> &[SymbolFile] {
+        &self.symbol_files
+    }
+
+    /// Gets the address of the code symbol `name` in the symbol tables of the files added with
+    /// [`Self::add_symbol_file`]
+    pub(crate) fn symbol_file_address(&self, name: &str) -> Option<Addr> {
+        self.symbol_files.iter().find_map(|f| {
+            let obj = f.info.object_info().ok()?;
+            crate::bpfile::symbol_address(&obj, name).map(|a| f.module.to_process(a))
+        })
+    }
+
+    /// Adds the symbols of a separate ELF file, as if it was loaded at `base`
+    ///
+    /// The debug symbols and source lines of the file are merged with those of the executable,
+    /// so they are found by name and by address like the executable's own. The symbol table of
+    /// the file is searched when resolving symbols, after the one of the executable. See
+    /// [`SymbolFile::load`] for how `base` is interpreted.
+    ///
+    /// # Parameters
+    ///
+    /// * `path` - The ELF file with the symbols
+    /// * `base` - The address that the lowest segment of the file is at in the debuggee
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(LoadedModule)` - The file with the load bias that its symbols are rebased with
+    /// * `Err(DebuggerError)` - If the symbols could not be added
+    ///
+    /// # Errors
+    ///
+    /// This function fails if the file cannot be read, is not an ELF file or its debug
+    /// information cannot be parsed. Symbols that were added before stay.
+    pub fn add_symbol_file(&mut self, path: &Path, base: Addr) -> Result<LoadedModule> {
+        let file = SymbolFile::load(path, base)?;
+        let dwarf = &file.info.dwarf;
+
+        let mut symbols = Vec::new();
+        let mut iter = dwarf.units();
+        while let Some(header) = iter.next()? {
+            let unit = dwarf.unit(header)?;
+            let mut tree = unit.entries_tree(None)?;
+            symbols.push(Self::process_tree(
+                &file.module,
+                dwarf,
+                &unit,
+                tree.root()?,
+            )?);
+        }
+        match LineTable::build(dwarf, &file.module) {
+            Ok(lines) => self.lines.merge(lines),
+            Err(e) => warn!("could not read the line table of {}: {e}", path.display()),
+        }
+        debug!(
+            "added {} compilation units from {} with bias {:#x}",
+            symbols.len(),
+            path.display(),
+            file.module.bias
+        );
+
+        self.symbols.extend(symbols);
+        let module = file.module.clone();
+        self.symbol_files.push(file);
+        Ok(module)
+    }
+
     /// Disassembles a section of memory in the debugged process
     ///
     /// # Parameters
@@ -199,22 +669,21 @@ impl Debuggee {
     /// If a [Breakpoint] was found before making the [Disassembly], but the same breakpoint does
     /// not exist after the [Disassembly] was created, this will also panic.
     pub fn disassemble(&self, addr: Addr, len: usize, literal: bool) -> Result<Disassembly> {
+        // the addresses of all bytes can be computed without overflows from here on
+        AddrRange::with_len(addr, len).ok_or(DebuggerError::AddressOverflow(addr, len))?;
         let mut data_raw: Vec<u8> = vec![0; len];
-        mem_read(&mut data_raw, self.pid, addr)?;
+        self.read_bytes(&mut data_raw, addr)?;
 
         let mut bp_indexes = Vec::new();
 
         for (idx, byte) in data_raw.iter_mut().enumerate() {
             if *byte == INT3_BYTE {
-                let bp = match self.breakpoints.get(&(addr + idx)) {
-                    None => {
-                        warn!(
-                            "found an int3 without breakpoint at {}, ignoring",
-                            addr + idx
-                        );
-                        continue;
-                    }
-                    Some(b) => b,
+                let Some(bp) = self.breakpoints.get(&(addr + idx)) else {
+                    warn!(
+                        "found an int3 without breakpoint at {}, ignoring",
+                        addr + idx
+                    );
+                    continue;
                 };
                 bp_indexes.push(idx);
 
@@ -238,11 +707,120 @@ impl Debuggee {
         Ok(out)
     }
 
+    /// Finds all instructions in the executable memory of the process that reference `target`
+    ///
+    /// Every executable region of the process is disassembled, see the [`xref`](crate::xref)
+    /// module for what counts as a reference. Enabled breakpoints are hidden, so the original
+    /// instructions are searched.
+    ///
+    /// # Parameters
+    ///
+    /// * `target` - The address to find references to
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Vec<Xref>)` - The referencing instructions, sorted by address
+    /// * `Err(DebuggerError)` - If the memory map could not be read
+    ///
+    /// # Errors
+    ///
+    /// This function fails if the memory map of the process cannot be read. Regions that cannot
+    /// be read are skipped.
+    pub fn find_xrefs(&self, target: Addr) -> Result<Vec<Xref>> {
+        let map = self.get_process_map()?;
+        let mut xrefs = Vec::new();
+
+        for region in map
+            .regions
+            .iter()
+            .filter(|r| r.permissions.execute && r.permissions.read)
+        {
+            let Some(data) = self.read_code(region) else {
+                continue;
+            };
+            xrefs.extend(find_xrefs(&data, region.start_address, target));
+        }
+
+        Ok(xrefs)
+    }
+
+    /// Builds the [`CallGraph`] of the direct calls in the executable
+    ///
+    /// The executable code of the executable is disassembled, shared libraries are left out.
+    /// Calls are attributed to the functions in the debug symbols, so without debug symbols,
+    /// the graph is empty. See [`CallGraph::from_calls`].
+    ///
+    /// # Errors
+    ///
+    /// This function fails if the memory map of the process cannot be read. Regions that cannot
+    /// be read are skipped.
+    pub fn call_graph(&self) -> Result<CallGraph> {
+        let map = self.get_process_map()?;
+        let exe = map.regions.first().and_then(|r| r.path.clone());
+        let mut calls = Vec::new();
+
+        for region in map
+            .regions
+            .iter()
+            .filter(|r| r.permissions.execute && r.permissions.read && r.path == exe)
+        {
+            let Some(data) = self.read_code(region) else {
+                continue;
+            };
+            calls.extend(find_calls(&data, region.start_address));
+        }
+
+        let functions: Vec<FunctionRange> = self
+            .symbols_query(|s| s.kind() == SymbolKind::Function)
+            .into_iter()
+            .filter_map(|s| {
+                Some(FunctionRange {
+                    start: s.low_addr()?,
+                    end: s.high_addr()?,
+                    name: s.name().map(str::to_string),
+                })
+            })
+            .collect();
+        debug!(
+            "found {} calls in {} functions of the executable",
+            calls.len(),
+            functions.len()
+        );
+
+        Ok(CallGraph::from_calls(&calls, &functions))
+    }
+
+    /// Reads the code in a memory region, with the original bytes at software breakpoints
+    ///
+    /// # Returns
+    ///
+    /// The contents of the region, or [`None`] if it cannot be read.
+    fn read_code(&self, region: &MemoryRegion) -> Option<Vec<u8>> {
+        let mut data = vec![0; region.size];
+        if let Err(e) = self.read_bytes(&mut data, region.start_address) {
+            warn!(
+                "could not read the region at {}, skipping it: {e}",
+                region.start_address
+            );
+            return None;
+        }
+
+        for (addr, bp) in &self.breakpoints {
+            if let Some(saved) = bp.saved_data() {
+                if region.start_address <= *addr && *addr < region.end_address {
+                    data[(*addr - region.start_address).usize()] = saved;
+                }
+            }
+        }
+
+        Some(data)
+    }
+
     /// Creates an [`OwnedSymbol`] from a DWARF debugging information entry
     ///
     /// # Parameters
     ///
-    /// * `pid` - The process ID (used to get the base address)
+    /// * `executable` - The loaded executable, to rebase the addresses of the entry
     /// * `dwarf` - The DWARF debug information
     /// * `unit` - The compilation unit containing the entry
     /// * `entry` - The debugging information entry
@@ -257,36 +835,191 @@ impl Debuggee {
     /// This function can fail if there are issues parsing the debug information
     /// or if required attributes are missing.
     fn entry_from_gimli(
-        pid: Pid,
+        executable: &LoadedModule,
         dwarf: &gimli::Dwarf<GimliReaderThing>,
         unit: &Unit<GimliReaderThing>,
         entry: &gimli::DebuggingInformationEntry<'_, '_, GimliReaderThing>,
     ) -> Result<OwnedSymbol> {
-        let base_addr = Self::get_base_addr_by_pid(pid)?;
-
-        let name = Self::parse_string(dwarf, unit, entry.attr(DW_AT_name)?)?;
+        let mut name = Self::parse_string(dwarf, unit, entry.attr(DW_AT_name)?)?;
+        let mut linkage_name = Self::parse_linkage_name(dwarf, unit, entry)?;
+        let mut datatype: Option<usize> = Self::parse_datatype(entry.attr(DW_AT_type)?);
+        // definitions of C++ methods and static members outside of their class only refer to
+        // the declaration in the class, which has the name and the type
+        if let Some(gimli::AttributeValue::UnitRef(offset)) =
+            entry.attr_value(DW_AT_specification)?
+        {
+            let declaration = unit.entry(offset)?;
+            if name.is_none() {
+                name = Self::parse_string(dwarf, unit, declaration.attr(DW_AT_name)?)?;
+            }
+            if linkage_name.is_none() {
+                linkage_name = Self::parse_linkage_name(dwarf, unit, &declaration)?;
+            }
+            if datatype.is_none() {
+                datatype = Self::parse_datatype(declaration.attr(DW_AT_type)?);
+            }
+        }
+        let name = name.map(|n| demangle(&n));
         let kind = SymbolKind::try_from(entry.tag())?;
-        let low = Self::parse_addr_low(dwarf, unit, entry.attr(DW_AT_low_pc)?, base_addr)?;
+        // call sites have their return address instead of a low address
+        let low_attr = match entry.attr(DW_AT_low_pc)? {
+            Some(attr) => Some(attr),
+            None => entry.attr(DW_AT_call_return_pc)?,
+        };
+        let low = Self::parse_addr_low(dwarf, unit, low_attr, executable)?;
         let high = Self::parse_addr_high(entry.attr(DW_AT_high_pc)?, low)?;
-        let datatype: Option<usize> = Self::parse_datatype(entry.attr(DW_AT_type)?);
         let location: Option<Attribute<GimliReaderThing>> = entry.attr(DW_AT_location)?;
         let frame_base: Option<Attribute<GimliReaderThing>> = entry.attr(DW_AT_frame_base)?;
+        let call_value: Option<Attribute<GimliReaderThing>> = match entry.attr(DW_AT_call_value)? {
+            Some(attr) => Some(attr),
+            None => entry.attr(DW_AT_GNU_call_site_value)?,
+        };
+        let location_list = match &location {
+            Some(attr) => Self::parse_location_list(dwarf, unit, attr, executable)?,
+            None => Vec::new(),
+        };
+        let byte_size: Option<usize> = entry
+            .attr_value(DW_AT_byte_size)?
+            .and_then(|v| v.udata_value())
+            .map(|v| v as usize);
+        let member_offset: Option<usize> =
+            Self::parse_member_offset(entry.attr(DW_AT_data_member_location)?, unit.encoding())?;
+        let bit_size: Option<usize> = entry
+            .attr_value(DW_AT_bit_size)?
+            .and_then(|v| v.udata_value())
+            .map(|v| v as usize);
+        let data_bit_offset = Self::parse_data_bit_offset(
+            entry
+                .attr_value(DW_AT_data_bit_offset)?
+                .and_then(|v| v.udata_value()),
+            entry
+                .attr_value(DW_AT_bit_offset)?
+                .and_then(|v| v.udata_value()),
+            member_offset,
+            byte_size,
+            bit_size,
+        );
+        let const_value = Self::parse_const_value(entry)?;
+        let count = Self::parse_count(entry)?;
+        let (discr, discr_value) = Self::parse_discr_attrs(entry)?;
+        let base_encoding = match entry.attr_value(DW_AT_encoding)? {
+            Some(gimli::AttributeValue::Encoding(e)) => Some(e),
+            _ => None,
+        };
+        let decl_file = match (entry.attr_value(DW_AT_decl_file)?, &unit.line_program) {
+            (Some(gimli::AttributeValue::FileIndex(index)), Some(program)) => {
+                let header = program.header();
+                match header.file(index) {
+                    Some(file) => Some(lines::file_path(dwarf, unit, header, file)?),
+                    None => None,
+                }
+            }
+            _ => None,
+        };
 
         let mut sym = OwnedSymbol::new(entry.offset().0, kind, &[], unit.encoding());
         sym.set_name(name);
         sym.set_location(location);
+        sym.set_location_list(location_list);
+        sym.set_call_value(call_value);
         sym.set_datatype(datatype);
         sym.set_low_addr(low);
         sym.set_high_addr(high);
         sym.set_frame_base(frame_base);
+        sym.set_byte_size(byte_size);
+        sym.set_member_offset(member_offset);
+        sym.set_bit_size(bit_size);
+        sym.set_data_bit_offset(data_bit_offset);
+        sym.set_const_value(const_value);
+        sym.set_base_encoding(base_encoding);
+        sym.set_decl_file(decl_file);
+        sym.set_linkage_name(linkage_name);
+        sym.set_count(count);
+        sym.set_discr(discr);
+        sym.set_discr_value(discr_value);
         Ok(sym)
     }
 
+    /// Parses the number of elements of a subrange of an array type
+    ///
+    /// The number is given directly with `DW_AT_count`, or with the bounds of the indices, where
+    /// the lower bound is 0 unless it is given. Bounds that are computed at runtime, like those of
+    /// variable length arrays, are not supported and result in [None].
+    fn parse_count(
+        entry: &gimli::DebuggingInformationEntry<'_, '_, GimliReaderThing>,
+    ) -> Result<Option<usize>> {
+        let bound = |value: Option<gimli::AttributeValue<GimliReaderThing>>| match value {
+            Some(gimli::AttributeValue::Sdata(v)) => Some(v),
+            Some(other) => other.udata_value().map(|v| v as i64),
+            None => None,
+        };
+        if let Some(count) = bound(entry.attr_value(DW_AT_count)?) {
+            return Ok(usize::try_from(count).ok());
+        }
+        let Some(upper) = bound(entry.attr_value(DW_AT_upper_bound)?) else {
+            return Ok(None);
+        };
+        let lower = bound(entry.attr_value(DW_AT_lower_bound)?).unwrap_or(0);
+        // zero length arrays have an upper bound of -1
+        let count = upper.checked_sub(lower).and_then(|n| n.checked_add(1));
+        Ok(count.map(|n| usize::try_from(n).unwrap_or(0)))
+    }
+
+    /// Parses the value of an enumerator or a constant variable
+    ///
+    /// Constants of other types than integers, like doubles, are in blocks, whose bytes are
+    /// taken as the value.
+    fn parse_const_value(
+        entry: &gimli::DebuggingInformationEntry<'_, '_, GimliReaderThing>,
+    ) -> Result<Option<i64>> {
+        Ok(match entry.attr_value(DW_AT_const_value)? {
+            Some(gimli::AttributeValue::Sdata(v)) => Some(v),
+            Some(gimli::AttributeValue::Block(block)) => {
+                crate::bytes_to_u64(&gimli::Reader::to_slice(&block)?)
+                    .ok()
+                    .map(|v| v as i64)
+            }
+            Some(other) => other.udata_value().map(|v| v as i64),
+            None => None,
+        })
+    }
+
+    /// Parses the discriminant of a variant part and the value of it that selects a variant
+    ///
+    /// The discriminant is the offset of the member that holds it, see
+    /// [`OwnedSymbol::discr`](crate::dbginfo::OwnedSymbol::discr).
+    fn parse_discr_attrs(
+        entry: &gimli::DebuggingInformationEntry<'_, '_, GimliReaderThing>,
+    ) -> Result<(Option<usize>, Option<i64>)> {
+        let discr = Self::parse_datatype(entry.attr(DW_AT_discr)?);
+        let discr_value = match entry.attr_value(DW_AT_discr_value)? {
+            Some(gimli::AttributeValue::Sdata(v)) => Some(v),
+            Some(other) => other.udata_value().map(|v| v as i64),
+            None => None,
+        };
+        Ok((discr, discr_value))
+    }
+
+    /// Parses the linkage name of an entry, the mangled name of its symbol in the symbol table
+    ///
+    /// Older compilers put it in `DW_AT_MIPS_linkage_name` instead of `DW_AT_linkage_name`.
+    fn parse_linkage_name(
+        dwarf: &gimli::Dwarf<GimliReaderThing>,
+        unit: &Unit<GimliReaderThing>,
+        entry: &gimli::DebuggingInformationEntry<'_, '_, GimliReaderThing>,
+    ) -> Result<Option<String>> {
+        let attr = match entry.attr(DW_AT_linkage_name)? {
+            Some(attr) => Some(attr),
+            None => entry.attr(DW_AT_MIPS_linkage_name)?,
+        };
+        Self::parse_string(dwarf, unit, attr)
+    }
+
     /// Recursively processes a DWARF debug information tree
     ///
     /// # Parameters
     ///
-    /// * `pid` - The process ID
+    /// * `executable` - The loaded executable, to rebase the addresses of the entries
     /// * `dwarf` - The DWARF debug information
     /// * `unit` - The compilation unit containing the tree
     /// * `node` - The tree node to process
@@ -300,19 +1033,19 @@ impl Debuggee {
     ///
     /// This function can fail if there are issues parsing the debug information.
     fn process_tree(
-        pid: Pid,
+        executable: &LoadedModule,
         dwarf: &gimli::Dwarf<GimliReaderThing>,
         unit: &Unit<GimliReaderThing>,
         node: gimli::EntriesTreeNode<GimliReaderThing>,
     ) -> Result<OwnedSymbol> {
         let mut children: Vec<OwnedSymbol> = Vec::new();
-        let mut parent = Self::entry_from_gimli(pid, dwarf, unit, node.entry())?;
+        let mut parent = Self::entry_from_gimli(executable, dwarf, unit, node.entry())?;
 
         // then process it's children
         let mut children_tree = node.children();
         while let Some(child) = children_tree.next()? {
             // Recursively process a child.
-            children.push(match Self::process_tree(pid, dwarf, unit, child) {
+            children.push(match Self::process_tree(executable, dwarf, unit, child) {
                 Err(e) => {
                     debug!("could not parse a leaf of the debug symbol tree: {e}");
                     continue;
@@ -498,13 +1231,19 @@ impl Debuggee {
     ///
     /// # Errors
     ///
-    /// This function can fail if the stack memory cannot be read or if the
-    /// register values are not accessible.
+    /// This function can fail if the stack memory cannot be read, if the
+    /// register values are not accessible or if the stack is larger than
+    /// [`Limits::max_read_size`].
     #[allow(clippy::similar_names)] // not my fault they named the registers that
     pub fn get_stack(&self) -> Result<Stack> {
         let rbp: Addr = get_reg(self.pid, crate::Register::rbp)?.into();
         let rsp: Addr = get_reg(self.pid, crate::Register::rsp)?.into();
 
+        self.limits.check(
+            LimitKind::ReadSize,
+            (rbp.usize() + 8).saturating_sub(rsp.usize()),
+        )?;
+
         let mut next: Addr = rbp;
         let mut stack = Stack::new(rbp);
         while next >= rsp {
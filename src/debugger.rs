@@ -23,39 +23,346 @@
 //! - **[Debuggee]** - Various methods of the [Debuggee] struct.
 //!
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::ffi::CString;
 use std::fmt::Display;
+use std::fs::File;
+use std::io::Write;
+use std::os::fd::{AsFd, AsRawFd};
 use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::str::FromStr;
 #[cfg(feature = "plugins")]
 use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
-use iced_x86::FormatterTextKind;
+use iced_x86::{Decoder, DecoderOptions, FlowControl, FormatterTextKind};
+use nix::fcntl::{fcntl, FcntlArg, OFlag};
+use nix::poll::{poll, PollFd, PollFlags, PollTimeout};
 use nix::sys::ptrace;
-use nix::sys::signal::Signal;
+use nix::sys::signal::{SigSet, SigmaskHow, Signal};
+use nix::sys::signalfd::{SfdFlags, SignalFd};
 use nix::sys::wait::{waitpid, WaitPidFlag, WaitStatus};
-use nix::unistd::execv;
+use nix::unistd::{dup2, execve, pipe2, Pid};
+use object::{Object, ObjectSymbol};
+use serde::{Deserialize, Serialize};
 use tracing::{debug, error, info, trace, warn};
 use which::which;
 
-use crate::breakpoint::Breakpoint;
-use crate::consts::{SI_KERNEL, TRAP_BRKPT, TRAP_TRACE};
-use crate::dbginfo::{CMDebugInfo, OwnedSymbol};
+use crate::addr::AddrRange;
+use crate::addrexpr::AddrExpr;
+use crate::antidebug::{AntiDebug, AntiDebugConfig};
+use crate::assemble::{assemble, Patch, MAX_INSTRUCTION_LEN};
+use crate::bpfile::{self, BreakpointAnchor, BreakpointFile, SavedBreakpoint};
+use crate::breakpoint::{Breakpoint, BreakpointInfo};
+use crate::catchpoint::MemoryCatchConfig;
+use crate::checkpoint::{Checkpoint, CheckpointId};
+use crate::consts::{SEGV_ACCERR, SI_KERNEL, TRAP_BRKPT, TRAP_HWBKPT, TRAP_TRACE};
+use crate::coredump::{self, CoreDump, CoreThread};
+use crate::dbginfo::{CMDebugInfo, OwnedSymbol, SymbolKind};
 use crate::debuggee::Debuggee;
+use crate::debugreg::DEBUG_ADDR_REGISTERS;
 use crate::disassemble::Disassembly;
-use crate::dwarf_parse::FrameInfo;
+use crate::dot::CallGraph;
+use crate::dump;
+use crate::dwarf_parse::{canonical_frame_address, unwind_registers, FrameInfo, FrameRegisters};
 use crate::errors::{DebuggerError, Result};
-use crate::feedback::{Feedback, InternalFeedback, Status};
-use crate::ui::DebuggerUI;
+use crate::feedback::{
+    AutoDisplay, ExitStatus, Feedback, ForkEvent, InternalFeedback, Status, StopInfo, StopReason,
+};
+use crate::gdbremote::{self, GdbRemote};
+use crate::got::{got_relocations, GotRelocation};
+use crate::heuristics::{self, HeuristicSymbols};
+use crate::history::ValueHistory;
+use crate::inject;
+use crate::journal::{Journal, JournalEvent};
+use crate::launchenv::LaunchEnv;
+use crate::limits::{LimitKind, Limits};
+use crate::lines::LineEntry;
+use crate::logpoint::LogFormat;
+use crate::memorymap::ProcessMemoryMap;
+use crate::modules::{module_at, LoadedModule};
+use crate::pageguard::PageGuard;
+use crate::perf::PerfCounters;
+use crate::profile::Profile;
+use crate::reverse::{RecordedStop, Recorder};
+use crate::rr;
+use crate::rustpanic::{self, PanicBreakpoint, PanicLocation, RustPanic};
+use crate::seccomp::{self, SyscallFilter};
+use crate::session::{SavedWatchpoint, Session, Settings};
+use crate::source;
+use crate::symindex::{self, NamePattern};
+use crate::syscall::{self, SyscallPhase, SyscallStop};
+use crate::thread::{self, Thread, ThreadState};
+use crate::traceopts::PtraceOptions;
+use crate::ui::{DebuggerUI, Event};
+use crate::uprobe::{Uprobe, UprobeTarget};
+use crate::varexpr::VarExpr;
 use crate::variable::{VariableExpression, VariableValue};
-use crate::{mem_read_word, mem_write_word, unwind, Addr, Register, Word};
+use crate::watchpoint::{WatchCondition, Watchpoint, WatchpointHit};
+use crate::{
+    mem_read_word, mem_write, mem_write_verified, mem_write_word, mem_write_word_verified, unwind,
+    Addr, Register, Word, WORD_BYTES,
+};
 
 // plugin stuff
 use crate::for_hooks; // does nothing without the feature
 #[cfg(feature = "plugins")]
-use crate::plugins::extension_points::{EPreSignalHandler, EPreSigtrap};
+use crate::plugins::extension_points::{
+    EOnBreakpointHit, EOnCommand, EOnExit, EOnStop, EOnSyscall, EPreSignalHandler, EPreSigtrap,
+};
 #[cfg(feature = "plugins")]
-use steckrs::{PluginIDOwned, PluginManager};
+use steckrs::{Plugin, PluginIDOwned, PluginManager};
+
+/// Longest time [`Debugger::wait_timeout`] relies on `SIGCHLD` before checking the debuggee
+pub const MAX_WAIT_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Result of [`Debugger::wait_signalfd`]
+enum WaitOutcome {
+    /// The status of the debuggee changed
+    Status(WaitStatus),
+    /// The deadline was reached
+    TimedOut,
+    /// The debugger received a `SIGINT`
+    Sigint,
+}
+
+/// How the debuggee was resumed the last time
+///
+/// A debuggee with a seccomp filter may stop at a `PTRACE_EVENT_SECCOMP` that nobody asked for,
+/// after which it has to be resumed in the same way.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Resume {
+    /// With `PTRACE_CONT`
+    Cont,
+    /// With `PTRACE_SINGLESTEP`
+    Step,
+    /// With `PTRACE_SYSCALL`
+    Syscall,
+}
+
+/// A checkpoint taken with [`Debugger::take_checkpoint`], with the stop it was taken at
+#[derive(Debug, Clone)]
+struct SavedCheckpoint {
+    checkpoint: Checkpoint,
+    reason: Option<StopReason>,
+    signal: Option<Signal>,
+}
+
+/// Where a newly launched debuggee should be stopped before the UI gets control
+///
+/// Right after it was launched, the debuggee is stopped in the dynamic loader, which is rarely
+/// interesting. See [`Debugger::set_launch_break`].
+///
+/// # Examples
+///
+/// ```
+/// use coreminer::debugger::LaunchBreak;
+///
+/// assert_eq!("main".parse::<LaunchBreak>().unwrap(), LaunchBreak::Main);
+/// assert!("somewhere".parse::<LaunchBreak>().is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum LaunchBreak {
+    /// The entry point of the executable (`AT_ENTRY` in the auxiliary vector)
+    Entry,
+    /// The `main` function, or the entry point if the executable has no symbol for `main`
+    Main,
+}
+
+impl FromStr for LaunchBreak {
+    type Err = DebuggerError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "entry" => Ok(Self::Entry),
+            "main" => Ok(Self::Main),
+            _ => Err(DebuggerError::InvalidLaunchBreak(s.to_string())),
+        }
+    }
+}
+
+/// A stop of the debuggee that was found by [`Debugger::poll_event`]
+#[derive(Debug)]
+pub struct StopEvent {
+    /// The status that was reported by `waitpid`
+    pub wait_status: WaitStatus,
+    /// The processed stop, like it would have been returned by [`Debugger::cont`]
+    pub feedback: Feedback,
+}
+
+/// What happens to a running debuggee when the debugger quits
+///
+/// See [`Debugger::set_quit_action`].
+///
+/// # Examples
+///
+/// ```
+/// use coreminer::debugger::QuitAction;
+///
+/// assert_eq!(QuitAction::default(), QuitAction::Kill);
+/// assert_eq!("detach".parse::<QuitAction>().unwrap(), QuitAction::Detach);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum QuitAction {
+    /// Kill the debuggee
+    #[default]
+    Kill,
+    /// Detach from the debuggee and leave it running, see [`Debugger::detach`]
+    Detach,
+}
+
+impl FromStr for QuitAction {
+    type Err = DebuggerError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "kill" => Ok(Self::Kill),
+            "detach" => Ok(Self::Detach),
+            _ => Err(DebuggerError::InvalidQuitAction(s.to_string())),
+        }
+    }
+}
+
+/// Which threads of the debuggee run while the selected thread is resumed
+///
+/// The debugger controls the main thread of the debuggee, so that is the selected thread. The
+/// other threads run along by default, which means that they can hit [Breakpoints](Breakpoint)
+/// or change data while a single instruction of the selected thread is stepped. This is like
+/// `set scheduler-locking` of gdb. See [`Debugger::set_scheduler_locking`].
+///
+/// # Examples
+///
+/// ```
+/// use coreminer::debugger::SchedulerLocking;
+///
+/// assert_eq!(SchedulerLocking::default(), SchedulerLocking::Off);
+/// assert_eq!("step".parse::<SchedulerLocking>().unwrap(), SchedulerLocking::Step);
+/// assert!("sometimes".parse::<SchedulerLocking>().is_err());
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum SchedulerLocking {
+    /// All threads run whenever the selected thread runs
+    #[default]
+    Off,
+    /// Only the selected thread runs while it is single stepped, all threads run when it is
+    /// continued, also to the temporary breakpoint of stepping out
+    Step,
+    /// Only the selected thread runs
+    On,
+}
+
+impl FromStr for SchedulerLocking {
+    type Err = DebuggerError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "off" => Ok(Self::Off),
+            "step" => Ok(Self::Step),
+            "on" => Ok(Self::On),
+            _ => Err(DebuggerError::InvalidSchedulerLocking(s.to_string())),
+        }
+    }
+}
+
+/// Which process the debugger stays with when the debuggee calls `fork` or `vfork`
+///
+/// The new process is a copy of the debuggee, including the `INT3` of its software
+/// [Breakpoints](Breakpoint). The process that is not followed gets its breakpoints removed and
+/// is detached, so it runs on without the debugger. This is like `set follow-fork-mode` of gdb.
+/// See [`Debugger::set_follow_fork`].
+///
+/// # Examples
+///
+/// ```
+/// use coreminer::debugger::FollowFork;
+///
+/// assert_eq!(FollowFork::default(), FollowFork::Parent);
+/// assert_eq!("child".parse::<FollowFork>().unwrap(), FollowFork::Child);
+/// assert!("grandchild".parse::<FollowFork>().is_err());
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum FollowFork {
+    /// Stay with the parent and let the child run, without stopping the debuggee
+    #[default]
+    Parent,
+    /// Continue with the child as the debuggee and let the parent run
+    ///
+    /// After a `vfork`, the parent shares its memory with the child, so it is held instead, see
+    /// [`FollowFork::Both`].
+    Child,
+    /// Stay with the parent, and hold the child stopped until the debugger detaches or quits
+    Both,
+}
+
+impl FromStr for FollowFork {
+    type Err = DebuggerError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "parent" => Ok(Self::Parent),
+            "child" => Ok(Self::Child),
+            "both" => Ok(Self::Both),
+            _ => Err(DebuggerError::InvalidFollowFork(s.to_string())),
+        }
+    }
+}
+
+/// Selects which writes into the memory of the debuggee are read back to check them
+///
+/// A write through ptrace can succeed and still not leave the written bytes in memory, for
+/// example when another thread of the debuggee writes to the same memory right after. Without
+/// a check, a breakpoint or patch would be silently corrupted. Checked writes fail with
+/// [`DebuggerError::WriteNotVerified`] instead. See [`Debugger::set_write_verification`].
+///
+/// # Examples
+///
+/// ```
+/// use coreminer::debugger::WriteVerification;
+///
+/// // only breakpoints are checked by default
+/// let verification = WriteVerification::default();
+/// assert!(verification.breakpoints);
+/// assert!(!verification.memory);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WriteVerification {
+    /// Check the `INT3` of software [Breakpoints](Breakpoint) and the code that is restored when
+    /// they are removed
+    pub breakpoints: bool,
+    /// Check writes of memory, variables and patches
+    pub memory: bool,
+}
+
+impl Default for WriteVerification {
+    fn default() -> Self {
+        Self {
+            breakpoints: true,
+            memory: false,
+        }
+    }
+}
+
+impl WriteVerification {
+    /// Checks all writes
+    #[must_use]
+    pub fn all() -> Self {
+        Self {
+            breakpoints: true,
+            memory: true,
+        }
+    }
+
+    /// Checks no writes
+    #[must_use]
+    pub fn none() -> Self {
+        Self {
+            breakpoints: false,
+            memory: false,
+        }
+    }
+}
 
 /// Manages the debugging session and coordinates between the UI and debuggee
 ///
@@ -65,7 +372,6 @@ use steckrs::{PluginIDOwned, PluginManager};
 ///
 /// # Type Parameters
 ///
-/// * `'executable` - Lifetime of the executable data
 /// * `UI` - The user interface type, which must implement [`DebuggerUI`]
 ///
 /// ## Examples
@@ -128,7 +434,7 @@ use steckrs::{PluginIDOwned, PluginManager};
 ///     debugger.run(program_path, &args)?;
 ///
 ///     if let Feedback::Registers(regs) = debugger.dump_regs()? {
-///         println!("rip is here: {}", regs.rip)
+///         println!("rip is here: {:?}", regs.get("rip"))
 ///     } else {
 ///         eprintln!("something did not work!")
 ///     }
@@ -146,17 +452,45 @@ use steckrs::{PluginIDOwned, PluginManager};
 ///
 /// # }}
 /// ```
-pub struct Debugger<'executable, UI: DebuggerUI> {
+pub struct Debugger<UI: DebuggerUI> {
     pub(crate) debuggee: Option<Debuggee>,
     pub(crate) ui: UI,
-    stored_obj_data: Option<object::File<'executable>>,
-    stored_obj_data_raw: Vec<u8>,
+    executable_data: Rc<[u8]>,
     last_signal: Option<Signal>,
+    anti_debug: AntiDebug,
+    catch_syscalls: bool,
+    syscall_filter: Option<SyscallFilter>,
+    resumed_with: Resume,
+    catch_memory: MemoryCatchConfig,
+    catch_panics: bool,
+    panic_breakpoints: Vec<(Addr, PanicBreakpoint)>,
+    panic_location: Option<PanicLocation>,
+    launch_break: Option<LaunchBreak>,
+    launch_env: LaunchEnv,
+    pipe_stdin: bool,
+    write_verification: WriteVerification,
+    ptrace_options: PtraceOptions,
+    interrupt_pending: bool,
+    quit_action: QuitAction,
+    scheduler_locking: SchedulerLocking,
+    follow_fork: FollowFork,
+    last_stop: Option<StopReason>,
+    selected_frame: usize,
+    pending_session: Option<Session>,
+    replay: Option<rr::Replay>,
+    profile: Option<Profile>,
+    journal: Journal,
+    limits: Limits,
+    history: ValueHistory,
+    auto_displays: BTreeMap<usize, VariableExpression>,
+    recorder: Option<Recorder>,
+    replaying: bool,
+    checkpoints: BTreeMap<CheckpointId, SavedCheckpoint>,
     #[cfg(feature = "plugins")]
     plugins: Arc<Mutex<PluginManager>>,
 }
 
-impl<'executable, UI: DebuggerUI> Debugger<'executable, UI> {
+impl<UI: DebuggerUI> Debugger<UI> {
     /// Creates a new debugger with the provided user interface
     ///
     /// # Parameters
@@ -190,18 +524,229 @@ impl<'executable, UI: DebuggerUI> Debugger<'executable, UI> {
         Ok(Debugger {
             debuggee: None,
             ui,
-            stored_obj_data: None,
-            stored_obj_data_raw: Vec::new(),
+            executable_data: Rc::from([]),
             last_signal: None,
+            anti_debug: AntiDebug::default(),
+            catch_syscalls: false,
+            syscall_filter: None,
+            resumed_with: Resume::Cont,
+            catch_memory: MemoryCatchConfig::default(),
+            catch_panics: false,
+            panic_breakpoints: Vec::new(),
+            panic_location: None,
+            launch_break: None,
+            launch_env: LaunchEnv::default(),
+            pipe_stdin: false,
+            write_verification: WriteVerification::default(),
+            ptrace_options: PtraceOptions::default(),
+            interrupt_pending: false,
+            quit_action: QuitAction::default(),
+            scheduler_locking: SchedulerLocking::default(),
+            follow_fork: FollowFork::default(),
+            last_stop: None,
+            selected_frame: 0,
+            pending_session: None,
+            replay: None,
+            profile: None,
+            journal: Journal::default(),
+            limits: Limits::default(),
+            history: ValueHistory::default(),
+            auto_displays: BTreeMap::new(),
+            recorder: None,
+            replaying: false,
+            checkpoints: BTreeMap::new(),
             #[cfg(feature = "plugins")]
             plugins: Arc::new(crate::plugins::default_plugin_manager().into()),
         })
     }
 
+    /// Creates a new debugger and launches a debuggee with it
+    ///
+    /// This is [`Self::build`] followed by [`Self::run`]. Settings that affect the launch, like
+    /// [`Self::set_launch_break`], cannot be changed before, use those two functions for that.
+    ///
+    /// # Parameters
+    ///
+    /// * `ui` - The user interface implementation
+    /// * `executable_path` - Path to the executable
+    /// * `arguments` - Command-line arguments for the executable
+    ///
+    /// # Errors
+    ///
+    /// This function fails if the debuggee cannot be launched, see [`Self::run`].
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #[cfg(feature = "cli")]
+    /// # mod featguard { fn _do_thing() {
+    /// use coreminer::debugger::Debugger;
+    /// use coreminer::ui::cli::CliUi;
+    ///
+    /// let ui = CliUi::build(None).unwrap();
+    /// let mut debugger = Debugger::launch(ui, "/bin/true", &[]).unwrap();
+    /// debugger.run_debugger().unwrap();
+    ///
+    /// # }}
+    /// ```
+    pub fn launch(
+        ui: UI,
+        executable_path: impl AsRef<Path>,
+        arguments: &[CString],
+    ) -> Result<Self> {
+        let mut debugger = Self::build(ui)?;
+        debugger.run(executable_path, arguments)?;
+        Ok(debugger)
+    }
+
+    /// Creates a new debugger and attaches it to a running process
+    ///
+    /// This is [`Self::build`] followed by [`Self::attach`].
+    ///
+    /// # Parameters
+    ///
+    /// * `ui` - The user interface implementation
+    /// * `pid` - The process ID of the process
+    ///
+    /// # Errors
+    ///
+    /// This function fails if the process cannot be attached, see [`Self::attach`].
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #[cfg(feature = "cli")]
+    /// # mod featguard { fn _do_thing() {
+    /// use coreminer::debugger::Debugger;
+    /// use coreminer::ui::cli::CliUi;
+    /// use nix::unistd::Pid;
+    ///
+    /// let ui = CliUi::build(None).unwrap();
+    /// let mut debugger = Debugger::from_pid(ui, Pid::from_raw(1234)).unwrap();
+    /// debugger.run_debugger().unwrap();
+    ///
+    /// # }}
+    /// ```
+    pub fn from_pid(ui: UI, pid: Pid) -> Result<Self> {
+        let mut debugger = Self::build(ui)?;
+        debugger.attach(pid)?;
+        Ok(debugger)
+    }
+
+    /// Creates a new debugger and attaches it to a running process with known modules
+    ///
+    /// Unlike [`Self::from_pid`], the modules of the process are not read from its memory map,
+    /// so the debug information is rebased with the load biases in `modules`, and
+    /// [`Self::list_modules`] and the symbol searches use them. This is useful when the memory
+    /// map does not tell where the modules are, for example with a custom loader that maps
+    /// them from anonymous memory.
+    ///
+    /// # Parameters
+    ///
+    /// * `ui` - The user interface implementation
+    /// * `pid` - The process ID of the process
+    /// * `modules` - The modules of the process, the executable must be the first one
+    ///
+    /// # Errors
+    ///
+    /// This function fails if `modules` is empty, the executable cannot be read from the path
+    /// of its module, or the process cannot be attached.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #[cfg(feature = "cli")]
+    /// # mod featguard { fn _do_thing() {
+    /// use coreminer::addr::Addr;
+    /// use coreminer::debugger::Debugger;
+    /// use coreminer::modules::LoadedModule;
+    /// use coreminer::ui::cli::CliUi;
+    /// use nix::unistd::Pid;
+    ///
+    /// let executable = LoadedModule {
+    ///     path: "/usr/local/bin/unpacked".to_string(),
+    ///     base: Addr::from(0x5555_5555_4000usize),
+    ///     bias: 0x5555_5555_4000,
+    /// };
+    /// let ui = CliUi::build(None).unwrap();
+    /// let mut debugger = Debugger::from_modules(ui, Pid::from_raw(1234), vec![executable]).unwrap();
+    /// debugger.run_debugger().unwrap();
+    ///
+    /// # }}
+    /// ```
+    pub fn from_modules(ui: UI, pid: Pid, modules: Vec<LoadedModule>) -> Result<Self> {
+        let mut debugger = Self::build(ui)?;
+        debugger.attach_with_modules(pid, Some(modules))?;
+        Ok(debugger)
+    }
+
+    /// Creates a new debugger and opens a core dump with it for post-mortem debugging
+    ///
+    /// This is [`Self::build`] followed by [`Self::open_core`].
+    ///
+    /// # Parameters
+    ///
+    /// * `ui` - The user interface implementation
+    /// * `core` - Path of the core dump
+    /// * `executable` - Path of the executable of the process, or [`None`] to use the path it was
+    ///   run from, as recorded in the core dump
+    ///
+    /// # Errors
+    ///
+    /// This function fails if the core dump or the executable cannot be opened, see
+    /// [`Self::open_core`].
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #[cfg(feature = "cli")]
+    /// # mod featguard { fn _do_thing() {
+    /// use coreminer::debugger::Debugger;
+    /// use coreminer::ui::cli::CliUi;
+    /// use std::path::Path;
+    ///
+    /// let ui = CliUi::build(None).unwrap();
+    /// let mut debugger =
+    ///     Debugger::from_core(ui, Path::new("core.1234"), Some(Path::new("./crashed"))).unwrap();
+    /// debugger.run_debugger().unwrap();
+    ///
+    /// # }}
+    /// ```
+    pub fn from_core(ui: UI, core: &Path, executable: Option<&Path>) -> Result<Self> {
+        let mut debugger = Self::build(ui)?;
+        debugger.open_core(core, executable)?;
+        Ok(debugger)
+    }
+
+    /// Gets the user interface of the debugger
+    ///
+    /// This is useful to get results out of the user interface after [`Self::run_debugger`]
+    /// returned, like the exit code of a batch run with [`CliUi`](crate::ui::cli::CliUi).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #[cfg(feature = "cli")]
+    /// # mod featguard { fn _do_thing() {
+    /// use coreminer::debugger::Debugger;
+    /// use coreminer::ui::cli::CliUi;
+    ///
+    /// let ui = CliUi::build(None).unwrap();
+    /// let mut debugger = Debugger::build(ui).unwrap();
+    /// debugger.run_debugger().unwrap();
+    /// let code = debugger.ui().exit_code();
+    ///
+    /// # }}
+    /// ```
+    pub fn ui(&self) -> &UI {
+        &self.ui
+    }
+
     /// Launches a new debuggee process
     ///
     /// This function loads an executable, parses its debug information, forks a new process,
-    /// and sets up ptrace for debugging.
+    /// and sets up ptrace for debugging. It returns once the debuggee stopped after the `execve`,
+    /// as the debug information can only be rebased to the executable once it is mapped.
     ///
     /// # Parameters
     ///
@@ -210,7 +755,8 @@ impl<'executable, UI: DebuggerUI> Debugger<'executable, UI> {
     ///
     /// # Returns
     ///
-    /// * `Ok(())` - If the debuggee was successfully launched
+    /// * `Ok(None)` - If the debuggee was successfully launched
+    /// * `Ok(Some(ExitStatus))` - If the debuggee exited before or in the `execve`
     /// * `Err(DebuggerError)` - If the debuggee could not be launched
     ///
     /// # Errors
@@ -226,7 +772,11 @@ impl<'executable, UI: DebuggerUI> Debugger<'executable, UI> {
     ///
     /// This function will panic if the the argument vector cannot be built from the path and the
     /// arguments. This can happen if the path has unicode.
-    fn launch_debuggee(&mut self, path: impl AsRef<Path>, arguments: &[CString]) -> Result<()> {
+    fn launch_debuggee(
+        &mut self,
+        path: impl AsRef<Path>,
+        arguments: &[CString],
+    ) -> Result<Option<ExitStatus>> {
         let path = path.as_ref();
         let path_as_cstring = CString::new(path.to_string_lossy().as_bytes())
             .expect("could not make argv from given path and args");
@@ -243,9 +793,20 @@ impl<'executable, UI: DebuggerUI> Debugger<'executable, UI> {
             return Err(err);
         }
 
-        let executable_obj_data: object::File<'_> = self.stored_obj_data.take().unwrap();
-
-        let dbginfo: CMDebugInfo = CMDebugInfo::build(executable_obj_data)?;
+        let dbginfo: CMDebugInfo = CMDebugInfo::build(Rc::clone(&self.executable_data))?;
+        let envp = self.launch_env.envp()?;
+        // compile the filter before forking, so that errors are reported here
+        let seccomp_program = self
+            .syscall_filter
+            .as_ref()
+            .map(SyscallFilter::program)
+            .transpose()?;
+        // both ends are closed on exec, only the copy of the read end as stdin stays open
+        let stdin_pipe = if self.pipe_stdin {
+            Some(pipe2(OFlag::O_CLOEXEC)?)
+        } else {
+            None
+        };
 
         let fork_res = unsafe { nix::unistd::fork() };
         match fork_res {
@@ -255,16 +816,54 @@ impl<'executable, UI: DebuggerUI> Debugger<'executable, UI> {
             }
             Ok(fr) => match fr {
                 nix::unistd::ForkResult::Parent { child: pid } => {
-                    let dbge = Debuggee::build(pid, &dbginfo, HashMap::new())?;
-                    self.debuggee = Some(dbge);
-                    Ok(())
+                    if seccomp_program.is_some() {
+                        // the filter applies to the execve already, which would fail with ENOSYS
+                        // if it is selected and not traced
+                        waitpid(pid, None)?;
+                        ptrace::setoptions(pid, ptrace::Options::PTRACE_O_TRACESECCOMP)?;
+                        ptrace::cont(pid, None)?;
+                    }
+                    // consume the stop after the execve, only then the executable is mapped
+                    let mut wait_status = waitpid(pid, None)?;
+                    while let WaitStatus::PtraceEvent(_, _, nix::libc::PTRACE_EVENT_SECCOMP) =
+                        wait_status
+                    {
+                        trace!("skipping a seccomp stop");
+                        ptrace::cont(pid, None)?;
+                        wait_status = waitpid(pid, None)?;
+                    }
+                    if let Some(status) = ExitStatus::from_wait_status(wait_status) {
+                        return Ok(Some(status));
+                    }
+
+                    self.init_debuggee(pid, &dbginfo, None)?;
+                    if let Some(dbge) = self.debuggee.as_mut() {
+                        dbge.seccomp.clone_from(&self.syscall_filter);
+                        if let Some((_, write_end)) = stdin_pipe {
+                            // a full pipe must not block the debugger while the debuggee is
+                            // stopped
+                            fcntl(write_end.as_raw_fd(), FcntlArg::F_SETFL(OFlag::O_NONBLOCK))?;
+                            dbge.stdin = Some(File::from(write_end));
+                        }
+                    }
+                    Ok(None)
                 }
                 nix::unistd::ForkResult::Child => {
                     let cpath = CString::new(path.to_string_lossy().to_string().as_str())?;
                     trace!("CHILD: requested run with executable={cpath:?} and argv={argv:?}");
                     ptrace::traceme()
                         .inspect_err(|e| eprintln!("error while doing traceme: {e}"))?;
-                    execv(&cpath, &argv)?; // NOTE: unsure if args[0] is set to the executable
+                    if let Some((read_end, _)) = &stdin_pipe {
+                        dup2(read_end.as_raw_fd(), nix::libc::STDIN_FILENO)
+                            .inspect_err(|e| eprintln!("error while connecting stdin: {e}"))?;
+                    }
+                    if let Some(program) = &seccomp_program {
+                        seccomp::install(program)
+                            .inspect_err(|e| eprintln!("error while installing seccomp: {e}"))?;
+                        // let the parent set PTRACE_O_TRACESECCOMP before the execve
+                        nix::sys::signal::raise(Signal::SIGSTOP)?;
+                    }
+                    execve(&cpath, &argv, &envp)?; // NOTE: unsure if args[0] is set to the executable
                     unreachable!()
                 }
             },
@@ -303,7 +902,7 @@ impl<'executable, UI: DebuggerUI> Debugger<'executable, UI> {
     /// # // Assume debuggee is already running
     /// #
     /// match debugger.wait_signal() {
-    ///     Ok(Feedback::Exit(code)) => println!("Process exited with code {}", code),
+    ///     Ok(Feedback::Exit(status)) => println!("Process {status}"),
     ///     Ok(Feedback::Ok) => println!("Process stopped"),
     ///     Ok(other) => println!("something else happened: {other}"), // impossible
     ///     Err(e) => eprintln!("Error: {}", e),
@@ -315,68 +914,434 @@ impl<'executable, UI: DebuggerUI> Debugger<'executable, UI> {
     // SIGTERM #43
     pub fn wait_signal(&mut self) -> Result<Feedback> {
         trace!("new wait signal iteration");
-        match self.wait(&[])? {
-            WaitStatus::Exited(_, exit_code) => Ok(Feedback::Exit(exit_code)),
-            WaitStatus::Signaled(_, signal, _) => {
-                info!("Debuggee terminated by signal: {}", signal);
-                Ok(Feedback::Exit(-1))
-            }
-            wait_status => {
-                // Get and handle other signals as before
-                let siginfo = ptrace::getsiginfo(
-                    self.debuggee.as_ref().ok_or(DebuggerError::NoDebugee)?.pid,
-                )?;
-                let sig = Signal::try_from(siginfo.si_signo)?;
-                debug!("wait status: {wait_status:?}");
-
-                for_hooks!(
-                    for hook[EPreSignalHandler] in self {
-                        self.hook_feedback_loop(hook.name(), |f| {
-                            hook.inner_mut().pre_handle_signal(f, &siginfo, &sig, &wait_status)
-                        })?;
-                    }
-                );
-
-                match sig {
-                    Signal::SIGTRAP => {
-                        self.handle_sigtrap(sig, siginfo)?;
-                        Ok(Feedback::Ok)
-                    }
-                    Signal::SIGSEGV
-                    | Signal::SIGINT
-                    | Signal::SIGPIPE
-                    | Signal::SIGSTOP
-                    | Signal::SIGWINCH
-                    | Signal::SIGTERM
-                    | Signal::SIGILL => {
-                        self.handle_important_signal(sig, siginfo)?;
-                        Ok(Feedback::Ok)
-                    }
-                    _ => {
-                        self.handle_other_signal(sig, siginfo)?;
-                        Ok(Feedback::Ok)
-                    }
-                }
+        loop {
+            let wait_status = self.wait_interruptible()?;
+            if !self.skip_seccomp_stop(wait_status)? {
+                return self.process_wait_status(wait_status);
             }
         }
     }
 
-    /// Low-level wait for a change in the debuggee's state
+    /// Resumes the debuggee from a `PTRACE_EVENT_SECCOMP` stop that was not asked for
     ///
-    /// # Parameters
-    ///
-    /// * `options` - Options to pass to waitpid, usually `&[]`
+    /// The debuggee is resumed the same way as before the stop. Stops of other kinds are left
+    /// alone.
     ///
     /// # Returns
     ///
-    /// * `Ok(WaitStatus)` - The status of the wait operation
-    /// * `Err(DebuggerError)` - If there was an error during waiting
+    /// * `Ok(true)` - The stop was a seccomp stop and the debuggee was resumed
+    /// * `Ok(false)` - The stop was something else
     ///
     /// # Errors
     ///
-    /// This function can fail if:
-    /// - The debuggee is not running
-    /// - waitpid fails
+    /// This function can fail if the debuggee cannot be resumed.
+    fn skip_seccomp_stop(&mut self, wait_status: WaitStatus) -> Result<bool> {
+        let WaitStatus::PtraceEvent(pid, _, nix::libc::PTRACE_EVENT_SECCOMP) = wait_status else {
+            return Ok(false);
+        };
+        trace!("skipping a seccomp stop");
+        self.resume_as(pid, self.resumed_with, None)?;
+        Ok(true)
+    }
+
+    /// Resumes the debuggee with `how`, delivering `sig`, and remembers how it was resumed
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if the ptrace request fails.
+    fn resume_as(&mut self, pid: Pid, how: Resume, sig: Option<Signal>) -> Result<()> {
+        self.resumed_with = how;
+        if self.locks_threads() {
+            self.stop_threads()?;
+        }
+        match how {
+            Resume::Cont => ptrace::cont(pid, sig)?,
+            Resume::Step => ptrace::step(pid, sig)?,
+            Resume::Syscall => ptrace::syscall(pid, sig)?,
+        }
+        let dbge = self.debuggee.as_mut().ok_or(DebuggerError::NoDebugee)?;
+        if let Some(main) = dbge.threads.get_mut(&pid) {
+            main.state = ThreadState::Running;
+        }
+        if self.locks_threads() {
+            return Ok(());
+        }
+        self.resume_threads()
+    }
+
+    /// Returns `true` if the other threads stay stopped while the main thread runs, because of
+    /// the [`SchedulerLocking`] and how the main thread was resumed last
+    fn locks_threads(&self) -> bool {
+        match self.scheduler_locking {
+            SchedulerLocking::Off => false,
+            SchedulerLocking::Step => self.resumed_with == Resume::Step,
+            SchedulerLocking::On => true,
+        }
+    }
+
+    /// Resumes the threads of the debuggee other than the main thread
+    ///
+    /// A thread that stopped on its own since it was resumed last is handled first: a software
+    /// [`Breakpoint`] it hit is stepped over, and a signal other than `SIGTRAP` and `SIGSTOP` is
+    /// delivered to it. Threads that exited are removed from the thread table.
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if a thread cannot be resumed.
+    fn resume_threads(&mut self) -> Result<()> {
+        let dbge = self.debuggee.as_ref().ok_or(DebuggerError::NoDebugee)?;
+        let others: Vec<(Pid, ThreadState)> = dbge
+            .threads
+            .iter()
+            .filter(|(tid, _)| **tid != dbge.pid)
+            .map(|(tid, thread)| (*tid, thread.state))
+            .collect();
+
+        for (tid, state) in others {
+            let sig = match state {
+                ThreadState::Stopped => {
+                    let dbge = self.debuggee.as_mut().ok_or(DebuggerError::NoDebugee)?;
+                    dbge.threads
+                        .get_mut(&tid)
+                        .and_then(|thread| thread.pending_signal.take())
+                }
+                ThreadState::Running => {
+                    match waitpid(tid, Some(WaitPidFlag::WNOHANG | WaitPidFlag::__WALL)) {
+                        Ok(WaitStatus::StillAlive) => continue,
+                        Ok(WaitStatus::Stopped(_, sig)) => {
+                            match self.handle_thread_signal(tid, sig) {
+                                Ok(sig) => sig,
+                                // killed meanwhile, the exit is reaped later
+                                Err(DebuggerError::Os(nix::errno::Errno::ESRCH)) => continue,
+                                Err(e) => return Err(e),
+                            }
+                        }
+                        Ok(WaitStatus::Exited(..) | WaitStatus::Signaled(..))
+                        | Err(nix::errno::Errno::ECHILD) => {
+                            self.forget_thread(tid)?;
+                            continue;
+                        }
+                        Ok(other) => {
+                            trace!("thread {tid} stopped: {other:?}");
+                            None
+                        }
+                        Err(e) => return Err(e.into()),
+                    }
+                }
+            };
+            match ptrace::cont(tid, sig) {
+                Ok(()) => {
+                    let dbge = self.debuggee.as_mut().ok_or(DebuggerError::NoDebugee)?;
+                    if let Some(thread) = dbge.threads.get_mut(&tid) {
+                        thread.state = ThreadState::Running;
+                    }
+                }
+                Err(nix::errno::Errno::ESRCH) => self.forget_thread(tid)?,
+                Err(e) => return Err(e.into()),
+            }
+        }
+        Ok(())
+    }
+
+    /// Handles a signal that stopped a thread other than the main thread
+    ///
+    /// # Returns
+    ///
+    /// The signal that should be delivered to the thread when it is resumed.
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if the registers of the thread cannot be accessed or stepping over
+    /// a breakpoint fails.
+    fn handle_thread_signal(&mut self, tid: Pid, sig: Signal) -> Result<Option<Signal>> {
+        match sig {
+            Signal::SIGSTOP => Ok(None),
+            Signal::SIGTRAP => {
+                let dbge = self.debuggee.as_ref().ok_or(DebuggerError::NoDebugee)?;
+                let Some((bp_addr, bp)) = dbge
+                    .rewind_breakpoint_hit(tid)?
+                    .and_then(|bp_addr| Some((bp_addr, dbge.breakpoints.get(&bp_addr)?)))
+                    .filter(|(_, bp)| bp.is_enabled())
+                else {
+                    return Ok(None);
+                };
+                debug!("stepping thread {tid} over the breakpoint at {bp_addr}");
+                // the main thread may be running, so the memory is accessed through this thread
+                bp.write_through(tid, false)?;
+                let stepped = ptrace::step(tid, None)
+                    .and_then(|()| waitpid(tid, Some(WaitPidFlag::__WALL)).map(|_| ()));
+                bp.write_through(tid, true)?;
+                stepped?;
+                Ok(None)
+            }
+            sig => {
+                debug!("delivering {sig} to thread {tid}");
+                Ok(Some(sig))
+            }
+        }
+    }
+
+    /// Removes a thread that has exited from the thread table
+    fn forget_thread(&mut self, tid: Pid) -> Result<()> {
+        let dbge = self.debuggee.as_mut().ok_or(DebuggerError::NoDebugee)?;
+        trace!("thread {tid} is gone");
+        dbge.threads.remove(&tid);
+        Ok(())
+    }
+
+    /// Handles a `fork` or `vfork` of the debuggee with the [`FollowFork`] mode
+    ///
+    /// The new process is traced already and starts with a `SIGSTOP`, which is consumed here.
+    ///
+    /// # Parameters
+    ///
+    /// * `parent` - The debuggee, which is stopped at the fork
+    /// * `child` - The new process
+    /// * `vfork` - Whether the child shares the memory of the parent
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Feedback::ForkEvent)` - The debuggee stopped at the fork, unless only the parent is
+    ///   followed
+    /// * `Ok(Feedback)` - The next stop of the parent, if only the parent is followed
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if the process that is not followed cannot be released, or the
+    /// breakpoints cannot be moved to the child.
+    fn handle_fork(&mut self, parent: Pid, child: Pid, vfork: bool) -> Result<Feedback> {
+        waitpid(child, Some(WaitPidFlag::__WALL))?;
+        info!("the debuggee {parent} forked the process {child}");
+
+        let followed = match self.follow_fork {
+            FollowFork::Parent => {
+                // after a vfork, this removes the breakpoints from the shared memory, they are
+                // put back at PTRACE_EVENT_VFORK_DONE
+                let dbge = self.debuggee.as_ref().ok_or(DebuggerError::NoDebugee)?;
+                dbge.release(child)?;
+                self.push_event(Event::Notification(format!(
+                    "detached from the new process {child}"
+                )));
+                self.resume_as(parent, self.resumed_with, None)?;
+                return self.wait_signal();
+            }
+            FollowFork::Both => {
+                let dbge = self.debuggee.as_mut().ok_or(DebuggerError::NoDebugee)?;
+                dbge.held.push(child);
+                parent
+            }
+            FollowFork::Child => {
+                if vfork {
+                    // releasing it would remove the breakpoints from the memory of the child
+                    let dbge = self.debuggee.as_mut().ok_or(DebuggerError::NoDebugee)?;
+                    dbge.held.push(parent);
+                } else {
+                    self.stop_threads()?;
+                    let dbge = self.debuggee.as_mut().ok_or(DebuggerError::NoDebugee)?;
+                    for (tid, thread) in &mut dbge.threads {
+                        if *tid == parent {
+                            continue;
+                        }
+                        match ptrace::detach(*tid, thread.pending_signal.take()) {
+                            Ok(()) | Err(nix::errno::Errno::ESRCH) => (),
+                            Err(e) => return Err(e.into()),
+                        }
+                    }
+                    dbge.release(parent)?;
+                }
+                let dbge = self.debuggee.as_mut().ok_or(DebuggerError::NoDebugee)?;
+                dbge.follow(child)?;
+                self.push_event(Event::Notification(format!(
+                    "following the new process {child}"
+                )));
+                child
+            }
+        };
+
+        Ok(Feedback::ForkEvent(ForkEvent {
+            parent: parent.as_raw(),
+            child: child.as_raw(),
+            vfork,
+            followed: followed.as_raw(),
+        }))
+    }
+
+    /// Processes a [`WaitStatus`] of the debuggee
+    ///
+    /// This is the part of [`Self::wait_signal`] that comes after the actual waiting. It is used
+    /// on its own where the debugger needs to look at the [`WaitStatus`] first.
+    ///
+    /// # Parameters
+    ///
+    /// * `wait_status` - The status that was returned by [`Self::wait`]
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Feedback)` - The result of the wait operation
+    /// * `Err(DebuggerError)` - If there was an error during processing
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if:
+    /// - The debuggee is not running
+    /// - Signal information cannot be retrieved
+    fn process_wait_status(&mut self, wait_status: WaitStatus) -> Result<Feedback> {
+        if let Some(thread) = wait_status
+            .pid()
+            .zip(self.debuggee.as_mut())
+            .and_then(|(pid, dbge)| dbge.threads.get_mut(&pid))
+        {
+            thread.state = ThreadState::Stopped;
+        }
+        match wait_status {
+            WaitStatus::Exited(_, exit_code) => {
+                let status = ExitStatus::Exited(exit_code);
+                self.last_stop = Some(StopReason::Exited(status));
+                Ok(Feedback::Exit(status))
+            }
+            WaitStatus::Signaled(_, signal, core_dumped) => {
+                info!("Debuggee terminated by signal: {}", signal);
+                let status = ExitStatus::Signaled {
+                    signal: signal as i32,
+                    core_dumped,
+                };
+                self.last_stop = Some(StopReason::Exited(status));
+                Ok(Feedback::Exit(status))
+            }
+            WaitStatus::PtraceEvent(pid, _, event) => self.handle_ptrace_event(pid, event),
+            wait_status => {
+                let dbge = self.debuggee.as_mut().ok_or(DebuggerError::NoDebugee)?;
+                // not a syscall stop, so the debuggee is not inside of a syscall anymore
+                dbge.in_syscall = false;
+                dbge.syscall_stop = None;
+
+                // Get and handle other signals as before
+                let siginfo = ptrace::getsiginfo(dbge.pid)?;
+                let sig = Signal::try_from(siginfo.si_signo)?;
+                debug!("wait status: {wait_status:?}");
+
+                if sig == Signal::SIGSTOP && self.interrupt_pending {
+                    // sent by the debugger itself, so it is not delivered to the debuggee
+                    self.interrupt_pending = false;
+                    self.last_stop = Some(StopReason::Interrupted);
+                    return Ok(Feedback::Interrupted);
+                }
+                if sig == Signal::SIGINT && self.interrupt_pending {
+                    // Ctrl-C in the terminal reaches the debuggee too, but the debugger was
+                    // interrupted, not the debuggee. The SIGSTOP sent by the debugger is pending,
+                    // so the debuggee stops again right away.
+                    debug!("suppressing the SIGINT of the debuggee");
+                    let pid = dbge.pid;
+                    self.resume_as(pid, self.resumed_with, None)?;
+                    return self.wait_signal();
+                }
+
+                for_hooks!(
+                    for hook[EPreSignalHandler] in self {
+                        self.hook_feedback_loop(hook.name(), |f| {
+                            hook.inner_mut().pre_handle_signal(f, &siginfo, &sig, &wait_status)
+                        })?;
+                    }
+                );
+
+                match sig {
+                    Signal::SIGTRAP => {
+                        self.handle_sigtrap(sig, siginfo)?;
+                        let reason = self.sigtrap_stop_reason(&siginfo)?;
+                        if let StopReason::Breakpoint { addr, .. } = reason {
+                            self.count_breakpoint_hit(addr);
+                        }
+                        self.last_stop = Some(reason);
+                        Ok(Feedback::Ok)
+                    }
+                    Signal::SIGSEGV
+                    | Signal::SIGINT
+                    | Signal::SIGPIPE
+                    | Signal::SIGSTOP
+                    | Signal::SIGWINCH
+                    | Signal::SIGTERM
+                    | Signal::SIGILL => {
+                        self.handle_important_signal(sig, siginfo)?;
+                        self.last_stop = Some(StopReason::Signal(sig as i32));
+                        Ok(Feedback::Ok)
+                    }
+                    _ => {
+                        self.handle_other_signal(sig, siginfo)?;
+                        self.last_stop = Some(StopReason::Signal(sig as i32));
+                        Ok(Feedback::Ok)
+                    }
+                }
+            }
+        }
+    }
+
+    /// Handles a stop of the thread `pid` at the ptrace event `event`
+    ///
+    /// Forks are handled with [`Self::handle_fork`], new threads are added to the threads of
+    /// the debuggee and the breakpoints are put back when a vforked child is done with the memory
+    /// of the debuggee.
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if the debuggee is not running or the message of the event cannot
+    /// be read.
+    fn handle_ptrace_event(&mut self, pid: Pid, event: i32) -> Result<Feedback> {
+        self.last_stop = Some(StopReason::PtraceEvent(event));
+        let dbge = self.debuggee.as_mut().ok_or(DebuggerError::NoDebugee)?;
+        dbge.in_syscall = false;
+        dbge.syscall_stop = None;
+
+        // the message is the new pid for fork and clone, and the exit status for exit
+        let message = ptrace::getevent(pid)?;
+        info!("debuggee stopped at ptrace event {event} with message {message}");
+        if event == nix::libc::PTRACE_EVENT_FORK || event == nix::libc::PTRACE_EVENT_VFORK {
+            let child = Pid::from_raw(message as i32);
+            return self.handle_fork(pid, child, event == nix::libc::PTRACE_EVENT_VFORK);
+        }
+        if event == nix::libc::PTRACE_EVENT_VFORK_DONE {
+            // the child let go of the shared memory, so the breakpoints can be put back
+            dbge.rearm_breakpoints(pid)?;
+            self.resume_as(pid, self.resumed_with, None)?;
+            return self.wait_signal();
+        }
+        if event == nix::libc::PTRACE_EVENT_CLONE {
+            // the new thread is traced and starts with a SIGSTOP
+            let tid = Pid::from_raw(message as i32);
+            let mut thread = Thread::stopped(tid, false);
+            thread.state = ThreadState::Running;
+            dbge.threads.insert(tid, thread);
+            self.push_event(Event::Notification(format!("new thread {tid}")));
+        }
+        Ok(Feedback::Ok)
+    }
+
+    /// Counts a hit of the [`Breakpoint`] at `addr`, if there still is one
+    fn count_breakpoint_hit(&mut self, addr: Addr) {
+        if let Some(bp) = self
+            .debuggee
+            .as_mut()
+            .and_then(|dbge| dbge.breakpoints.get_mut(&addr))
+        {
+            bp.count_hit();
+        }
+    }
+
+    /// Low-level wait for a change in the debuggee's state
+    ///
+    /// # Parameters
+    ///
+    /// * `options` - Options to pass to waitpid, usually `&[]`
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(WaitStatus)` - The status of the wait operation
+    /// * `Err(DebuggerError)` - If there was an error during waiting
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if:
+    /// - The debuggee is not running
+    /// - waitpid fails
     ///
     /// # Examples
     ///
@@ -413,12 +1378,158 @@ impl<'executable, UI: DebuggerUI> Debugger<'executable, UI> {
         for f in options {
             flags |= *f;
         }
+        if dbge.threads.len() > 1 && !flags.contains(WaitPidFlag::WNOHANG) {
+            // the exit of the main thread is only reported once the other threads are reaped
+            loop {
+                let wait_status = self.wait(&[flags | WaitPidFlag::WNOHANG])?;
+                if wait_status != WaitStatus::StillAlive {
+                    return Ok(wait_status);
+                }
+                std::thread::sleep(MAX_WAIT_POLL_INTERVAL / 10);
+            }
+        }
+        dbge.reap_threads()?;
         Ok(waitpid(
             dbge.pid,
             if flags.is_empty() { None } else { Some(flags) },
         )?)
     }
 
+    /// Low-level wait for a change in the debuggee's state, giving up after `timeout`
+    ///
+    /// The kernel sends a `SIGCHLD` to the debugger whenever the debuggee stops. While waiting,
+    /// `SIGCHLD` is blocked for the current thread and received through a `signalfd`, which is
+    /// polled until the timeout is reached. As the `SIGCHLD` may be consumed by another thread,
+    /// the debuggee is checked with `WNOHANG` at least every [`MAX_WAIT_POLL_INTERVAL`] anyway.
+    ///
+    /// # Parameters
+    ///
+    /// * `timeout` - How long to wait at most
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Some(WaitStatus))` - The status of the debuggee changed
+    /// * `Ok(None)` - The timeout was reached
+    /// * `Err(DebuggerError)` - If there was an error during waiting
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if:
+    /// - The debuggee is not running
+    /// - waitpid fails
+    /// - The signal mask or the `signalfd` cannot be set up
+    pub fn wait_timeout(&mut self, timeout: Duration) -> Result<Option<WaitStatus>> {
+        match self.wait_signalfd(Some(Instant::now() + timeout), false)? {
+            WaitOutcome::Status(wait_status) => Ok(Some(wait_status)),
+            WaitOutcome::TimedOut | WaitOutcome::Sigint => Ok(None),
+        }
+    }
+
+    /// Waits for a change in the debuggee's state, stopping the debuggee on `SIGINT`
+    ///
+    /// This is the blocking wait used while the debuggee runs. If the debugger receives a
+    /// `SIGINT`, usually because Ctrl-C was pressed, the debuggee is stopped with [`Self::send_interrupt`]
+    /// instead of the debugger being killed, so that the UI gets control back.
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if waiting fails or the debuggee cannot be stopped.
+    fn wait_interruptible(&mut self) -> Result<WaitStatus> {
+        loop {
+            if let Some(wait_status) = self.wait_interruptible_until(None)? {
+                return Ok(wait_status);
+            }
+        }
+    }
+
+    /// Like [`Self::wait_interruptible`], but gives up at `deadline`
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Some(WaitStatus))` - The status of the debuggee changed
+    /// * `Ok(None)` - The deadline was reached
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if waiting fails or the debuggee cannot be stopped.
+    fn wait_interruptible_until(
+        &mut self,
+        deadline: Option<Instant>,
+    ) -> Result<Option<WaitStatus>> {
+        loop {
+            match self.wait_signalfd(deadline, true)? {
+                WaitOutcome::Status(wait_status) => return Ok(Some(wait_status)),
+                WaitOutcome::TimedOut => return Ok(None),
+                WaitOutcome::Sigint => {
+                    info!("received SIGINT, interrupting the debuggee");
+                    self.send_interrupt()?;
+                }
+            }
+        }
+    }
+
+    /// Waits for the debuggee with `SIGCHLD` (and `SIGINT`) received through a `signalfd`
+    ///
+    /// The signals are blocked for the current thread while waiting, so that a pending signal is
+    /// not lost and the default action of `SIGINT`, killing the debugger, is not taken.
+    fn wait_signalfd(
+        &mut self,
+        deadline: Option<Instant>,
+        catch_sigint: bool,
+    ) -> Result<WaitOutcome> {
+        let mut signals = SigSet::empty();
+        signals.add(Signal::SIGCHLD);
+        if catch_sigint {
+            signals.add(Signal::SIGINT);
+        }
+        let old_mask = signals.thread_swap_mask(SigmaskHow::SIG_BLOCK)?;
+
+        let res = self.wait_blocked(&signals, deadline);
+        old_mask.thread_set_mask()?;
+        res
+    }
+
+    /// Waits for the debuggee while `signals` are blocked, see [`Self::wait_signalfd`]
+    fn wait_blocked(&mut self, signals: &SigSet, deadline: Option<Instant>) -> Result<WaitOutcome> {
+        let signal_fd =
+            SignalFd::with_flags(signals, SfdFlags::SFD_NONBLOCK | SfdFlags::SFD_CLOEXEC)?;
+
+        loop {
+            // the debuggee may have stopped before SIGCHLD was blocked
+            let wait_status = self.wait(&[WaitPidFlag::WNOHANG])?;
+            if wait_status != WaitStatus::StillAlive {
+                return Ok(WaitOutcome::Status(wait_status));
+            }
+            // the other threads are not waited for, but their stops are handled meanwhile
+            if !self.locks_threads() {
+                self.resume_threads()?;
+            }
+
+            let mut poll_interval = MAX_WAIT_POLL_INTERVAL;
+            if let Some(deadline) = deadline {
+                let remaining = deadline.saturating_duration_since(Instant::now());
+                if remaining.is_zero() {
+                    return Ok(WaitOutcome::TimedOut);
+                }
+                poll_interval = poll_interval.min(remaining);
+            }
+            let poll_ms = poll_interval.as_millis().max(1) as u16;
+            let mut fds = [PollFd::new(signal_fd.as_fd(), PollFlags::POLLIN)];
+            match poll(&mut fds, PollTimeout::from(poll_ms)) {
+                Ok(_) | Err(nix::errno::Errno::EINTR) => (),
+                Err(e) => return Err(e.into()),
+            }
+
+            let mut sigint = false;
+            while let Some(info) = signal_fd.read_signal()? {
+                sigint |= info.ssi_signo == Signal::SIGINT as u32;
+            }
+            if sigint {
+                return Ok(WaitOutcome::Sigint);
+            }
+        }
+    }
+
     /// Runs the main debugger loop
     ///
     /// This function forms the main execution loop of the debugger, processing
@@ -455,22 +1566,27 @@ impl<'executable, UI: DebuggerUI> Debugger<'executable, UI> {
     /// # }}
     /// ```
     pub fn run_debugger(&mut self) -> Result<()> {
-        if self.debuggee.as_ref().is_some() {
-            self.wait_signal()?; // wait until the debuggee is stopped
-        } else {
+        // every way to get a debuggee leaves it stopped, so there is nothing to wait for
+        if self.debuggee.is_none() {
             info!("debuggee not yet launched");
         }
 
         let mut feedback: Feedback = Feedback::Ok;
         loop {
-            let ui_res = self.ui.process(feedback);
+            let ui_res = self
+                .ui
+                .push_event(Event::Feedback(feedback))
+                .and_then(|()| self.ui.next_status());
             feedback = {
                 match ui_res {
                     Err(e) => {
                         error!("{e}");
                         return Err(e);
                     }
-                    Ok(s) => match self.process_status(&s) {
+                    Ok(s) => match self
+                        .run_command_hooks(&s)
+                        .and_then(|()| self.process_status_interruptible(&s))
+                    {
                         Ok(Feedback::Internal(InternalFeedback::Quit)) => break,
                         other => other,
                     },
@@ -487,6 +1603,32 @@ impl<'executable, UI: DebuggerUI> Debugger<'executable, UI> {
         Ok(())
     }
 
+    /// Processes a [`Status`] while Ctrl-C only interrupts the debuggee
+    ///
+    /// `SIGINT` is blocked while the [`Status`] is processed, so that it is picked up by the waits
+    /// for the running debuggee (see [`Self::wait_interruptible`]) instead of killing the
+    /// debugger. A `SIGINT` that arrived while the debuggee was not running is discarded.
+    ///
+    /// # Errors
+    ///
+    /// This function fails if [`Self::process_status`] fails or the signal mask cannot be changed.
+    fn process_status_interruptible(&mut self, status: &Status) -> Result<Feedback> {
+        let mut sigint = SigSet::empty();
+        sigint.add(Signal::SIGINT);
+        let old_mask = sigint.thread_swap_mask(SigmaskHow::SIG_BLOCK)?;
+
+        let feedback = self.process_status(status);
+
+        let signal_fd =
+            SignalFd::with_flags(&sigint, SfdFlags::SFD_NONBLOCK | SfdFlags::SFD_CLOEXEC)?;
+        if signal_fd.read_signal()?.is_some() {
+            info!("ignoring SIGINT, the debuggee was not running");
+        }
+        drop(signal_fd);
+        old_mask.thread_set_mask()?;
+        feedback
+    }
+
     /// Process a [`Status`] by executing the specified action.
     ///
     /// This function takes a [`Status`] and has the debugger perform actions to generate
@@ -529,7 +1671,7 @@ impl<'executable, UI: DebuggerUI> Debugger<'executable, UI> {
     /// let ui = CliUi::build(None).unwrap();
     /// let mut debugger = Debugger::build(ui).unwrap();
     ///
-    /// let status = Status::ReadMem(Addr::from(98421479usize));
+    /// let status = Status::ReadMem(Addr::from(98421479usize).into());
     /// let feedback: Feedback = debugger.process_status(&status).unwrap();
     ///
     /// if let Feedback::Word(w) = feedback {
@@ -540,31 +1682,169 @@ impl<'executable, UI: DebuggerUI> Debugger<'executable, UI> {
     ///
     /// # }}
     /// ```
+    #[allow(clippy::too_many_lines)] // one arm per status
     pub fn process_status(&mut self, status: &Status) -> Result<Feedback> {
+        if self.is_post_mortem() && !Self::works_post_mortem(status) {
+            return Err(DebuggerError::PostMortem);
+        }
         match status {
             Status::Infos => self.infos(),
             Status::DebuggerQuit => Ok(Feedback::Internal(InternalFeedback::Quit)),
-            Status::Continue => self.cont(),
-            Status::SetBreakpoint(addr) => self.set_bp(*addr),
-            Status::DelBreakpoint(addr) => self.del_bp(*addr),
+            Status::SetQuitAction(action) => self.set_quit_action(*action),
+            Status::SetFollowFork(follow) => self.set_follow_fork(*follow),
+            Status::SetSchedulerLocking(locking) => self.set_scheduler_locking(*locking),
+            Status::SetLimits(limits) => self.set_limits(*limits),
+            Status::Detach => self.detach(),
+            Status::Continue => self.track_stop(status, "continue", false, Self::cont),
+            Status::SetBreakpoint(addr) => self.set_bp(self.resolve_addr(addr)?),
+            Status::SetHardwareBreakpoint(addr) => self.set_hw_bp(self.resolve_addr(addr)?),
+            Status::DelBreakpoint(addr) => self.del_bp(self.resolve_addr(addr)?),
+            Status::EnableBreakpoint(addr) => self.enable_bp(self.resolve_addr(addr)?),
+            Status::DisableBreakpoint(addr) => self.disable_bp(self.resolve_addr(addr)?),
+            Status::SetLogBreakpoint(addr, log) => {
+                self.set_log_bp(self.resolve_addr(addr)?, log.clone())
+            }
+            Status::SaveBreakpoints(path) => self.save_breakpoints(path),
+            Status::LoadBreakpoints(path) => self.load_breakpoints(path),
+            Status::SaveSession(path) => self.save_session(path),
+            Status::RestoreSession(path) => self.restore_session(path),
+            Status::SetWatchpoint(addr, len) => self.set_watchpoint(self.resolve_addr(addr)?, *len),
+            Status::DelWatchpoint(addr) => self.del_watchpoint(self.resolve_addr(addr)?),
+            Status::SetWatchCondition(addr, cond) => self.set_watch_condition(*addr, *cond),
+            Status::SetPageGuard(addr, len) => self.set_page_guard(*addr, *len),
+            Status::DelPageGuard(addr) => self.del_page_guard(*addr),
+            Status::SetAntiDebug(config) => self.set_anti_debug(*config),
+            Status::SetPtraceOptions(options) => self.set_ptrace_options(*options),
+            Status::CatchSyscalls(catch) => self.catch_syscalls(*catch),
+            Status::CatchSyscall(names) => self.catch_syscall_list(names),
+            Status::SetSyscallFilter(filter) => self.set_syscall_filter(filter.clone()),
+            Status::CatchMemory(config) => self.catch_memory(*config),
+            Status::CatchPanics(catch) => self.catch_panics(*catch),
+            Status::SetSyscallArg(index, value) => self.set_syscall_arg(*index, *value),
+            Status::SetSyscallReturn(value) => self.set_syscall_return(*value),
             Status::DumpRegisters => self.dump_regs(),
             Status::SetRegister(r, v) => self.set_reg(*r, *v),
-            Status::WriteMem(a, v) => self.write_mem(*a, *v),
-            Status::ReadMem(a) => self.read_mem(*a),
-            Status::DisassembleAt(a, l, literal) => self.disassemble_at(*a, *l, *literal),
+            Status::WriteMem(a, v) => self.write_mem(self.resolve_addr(a)?, *v),
+            Status::ReadMem(a) => self
+                .read_mem(self.resolve_addr(a)?)
+                .map(|f| self.record_value(f)),
+            Status::ReadMemRange(a, len) => self.read_mem_range(self.resolve_addr(a)?, *len),
+            Status::DisassembleAt(a, l, literal) => {
+                self.disassemble_at(self.resolve_addr(a)?, *l, *literal)
+            }
             Status::GetSymbolsByName(s) => self.get_symbol_by_name(s),
-            Status::StepSingle => self.single_step(),
-            Status::StepOut => self.step_out(),
-            Status::StepInto => self.step_into(),
-            Status::StepOver => self.step_over(),
+            Status::ResolveAddr(expr) => {
+                let addr = self.resolve_addr(expr)?;
+                Ok(self.record_value(Feedback::Addr(addr)))
+            }
+            Status::SetConvenience(name, expr) => self.set_convenience(name, expr),
+            Status::ShowValues => Ok(Feedback::Values(self.history.clone())),
+            Status::StepSingle => self.track_stop(status, "step", true, Self::single_step),
+            Status::StepOut => self.track_stop(status, "step out", true, Self::step_out),
+            Status::StepRange(start, end) => self.track_stop(status, "step range", true, |dbg| {
+                dbg.step_range(*start, *end)
+            }),
+            Status::StepInto => self.track_stop(status, "step into", true, Self::step_into),
+            Status::StepOver => self.track_stop(status, "step over", true, Self::step_over),
+            Status::StepLine => {
+                self.track_stop(status, "step line", true, |dbg| dbg.step_line(false))
+            }
+            Status::NextLine => {
+                self.track_stop(status, "next line", true, |dbg| dbg.step_line(true))
+            }
+            Status::RunUntil(addr) => {
+                self.track_stop(status, "until", true, |dbg| dbg.run_until(*addr))
+            }
+            Status::StartRecording(interval) => self.start_recording(*interval),
+            Status::StopRecording => self.stop_recording(),
+            Status::ReverseStep => self.reverse_step(),
+            Status::ReverseContinue => self.reverse_continue(),
+            Status::Checkpoint => self.take_checkpoint(),
+            Status::Restore(id) => self.restore_checkpoint(*id),
+            Status::DelCheckpoint(id) => self.del_checkpoint(*id),
             Status::Backtrace => self.backtrace(),
-            Status::ReadVariable(va) => self.read_variable(va),
+            Status::SelectFrame(index) => self.select_frame(*index),
+            Status::ReadVariable(va) => self.read_variable(va).map(|f| self.record_value(f)),
+            Status::AddAutoDisplay(expression) => self.add_auto_display(expression.clone()),
+            Status::DelAutoDisplay(number) => self.del_auto_display(*number),
             Status::WriteVariable(va, val) => self.write_variable(va, *val),
+            Status::ReadFrameVariable(frame, va) => self
+                .read_frame_variable(*frame, va)
+                .map(|f| self.record_value(f)),
+            Status::WriteFrameVariable(frame, va, val) => {
+                self.write_frame_variable(*frame, va, *val)
+            }
             Status::GetStack => self.get_stack(),
             Status::ProcMap => self.get_process_map(),
+            Status::ListModules => self.list_modules(),
+            Status::AddSymbolFile(path, base) => self.add_symbol_file(path, *base),
+            Status::ListFunctions(pattern) => self.list_functions(pattern),
+            Status::ListTypes(pattern) => self.list_types(pattern),
+            Status::ListSource(expr) => self.list_source(expr.as_ref()),
+            Status::ListVariables(pattern, module) => {
+                self.list_variables(pattern, module.as_deref())
+            }
+            Status::ShowEnviron => self.get_environment(),
+            Status::ListFileDescriptors => self.get_file_descriptors(),
+            Status::ProcessInfo => self.get_process_info(),
+            Status::ListThreads => self.list_threads(),
+            Status::ShowAuxv => self.get_auxv(),
+            Status::ListGot => self.get_got(),
+            Status::HookGot(symbol, target) => self.hook_got(symbol, *target),
+            Status::InjectCode(code, run) => self.inject_code(code, *run),
+            Status::CallFunction(target, args) => self.call_function(target, args),
+            Status::PatchAsm(addr, text) => self.patch_asm(*addr, text),
+            Status::DumpModule(module, output) => self.dump_module(module.as_deref(), output),
+            Status::GenerateCore(output) => self.generate_core(output),
+            Status::FindXrefs(addr) => self.find_xrefs(self.resolve_addr(addr)?),
+            Status::Record(program, args, trace_dir) => self.rr_record(program, args, trace_dir),
+            Status::Replay(trace_dir, port) => self.rr_replay(trace_dir, *port),
+            Status::ReplayResume(how) => self.replay_resume(*how),
+            Status::ReplayRegisters => self.replay_registers(),
+            Status::ReplayReadMem(addr, len) => self.replay_read_mem(*addr, *len),
+            Status::ReplaySetBreakpoint(addr) => self.replay_set_breakpoint(*addr),
+            Status::ReplayDelBreakpoint(addr) => self.replay_del_breakpoint(*addr),
+            Status::StopReplay => self.stop_replay(),
+            Status::ExportCallGraph(output) => self.export_call_graph(output.as_deref()),
+            Status::ExportBacktraceGraph(output) => self.export_backtrace_graph(output.as_deref()),
+            Status::SetUprobe(addr, capture) => self.set_uprobe(*addr, *capture),
+            Status::DelUprobe(addr) => self.del_uprobe(*addr),
+            Status::ReadUprobes => self.read_uprobes(),
+            Status::StartPerfCounters => self.start_perf_counters(),
+            Status::StopPerfCounters => self.stop_perf_counters(),
+            Status::ReadPerfCounters => self.read_perf_counters(),
             Status::Run(exe, args) => self.run(exe, args),
+            Status::Attach(pid) => self.attach(Pid::from_raw(*pid)),
+            Status::OpenCore(core, executable) => self.open_core(core, executable.as_deref()),
+            Status::SetLaunchBreak(launch_break) => self.set_launch_break(*launch_break),
+            Status::SetWriteVerification(verification) => {
+                self.set_write_verification(*verification)
+            }
+            Status::SetLaunchEnv(name, value) => self.set_launch_env(name, value.as_deref()),
+            Status::PreloadLibrary(library) => self.preload_library(Path::new(library)),
+            Status::AddLibraryPath(dir) => self.add_library_path(Path::new(dir)),
+            Status::GetLaunchEnv => Ok(Feedback::LaunchEnv(self.launch_env.clone())),
+            Status::SetStdinPipe(enabled) => self.set_stdin_pipe(*enabled),
+            Status::SendStdin(bytes) => self.send_stdin(bytes),
+            Status::CloseStdin => self.close_stdin(),
             Status::GetBreakpoint(addr) => self.get_bp(*addr),
+            Status::ListBreakpoints => self.list_breakpoints(),
             Status::SetLastSignal(signum) => self.set_last_signal(*signum),
+            Status::ContinueTimeout(ms) => self.track_stop(status, "continue", false, |dbg| {
+                dbg.cont_timeout(Duration::from_millis(*ms))
+            }),
+            Status::Profile(ms, frequency) => self.track_stop(status, "profile", false, |dbg| {
+                dbg.profile(Duration::from_millis(*ms), *frequency)
+            }),
+            Status::ExportFlamegraph(output, svg) => self.export_flamegraph(output, *svg),
+            Status::ExportTrace(output) => self.export_trace(output),
+            Status::WhyStopped => self.why_stopped(),
+            Status::ContinueWithSignal(signum) => {
+                self.track_stop(status, "continue", false, |dbg| {
+                    dbg.cont_with_signal(*signum)
+                })
+            }
+            Status::SendSignal(signum) => self.send_signal(*signum),
             #[cfg(feature = "plugins")]
             Status::PluginContinue => Err(DebuggerError::UiUsedPluginContinue),
             #[cfg(feature = "plugins")]
@@ -573,6 +1853,10 @@ impl<'executable, UI: DebuggerUI> Debugger<'executable, UI> {
             Status::PluginGetStatus(id) => self.plugin_get_status(id),
             #[cfg(feature = "plugins")]
             Status::PluginGetList => self.list_plugins(),
+            #[cfg(feature = "plugins")]
+            Status::PluginText(text) => Ok(Feedback::Text(text.clone())),
+            #[cfg(feature = "plugins")]
+            Status::PluginCustom(value) => Ok(Feedback::Custom(value.clone())),
         }
     }
 
@@ -581,6 +1865,16 @@ impl<'executable, UI: DebuggerUI> Debugger<'executable, UI> {
     /// This function tells the debuggee to continue execution from its current state.
     /// If a signal is provided, it will be delivered to the debuggee.
     ///
+    /// If any [Watchpoints](Watchpoint) are set, the debuggee is single stepped instead, until
+    /// either a watched memory range changes, a [`Breakpoint`] is hit or a signal is received. See
+    /// [`Self::set_watchpoint`].
+    ///
+    /// If the debuggee is stopped at an enabled hardware [`Breakpoint`], the instruction there is
+    /// executed first, as the breakpoint would be hit again right away otherwise.
+    ///
+    /// If the debugger receives a `SIGINT` while the debuggee runs, for example because Ctrl-C was
+    /// pressed, the debuggee is stopped and [`Feedback::Interrupted`] is returned.
+    ///
     /// # Returns
     ///
     /// * `Ok(Feedback)` - The result of the continuation
@@ -612,109 +1906,314 @@ impl<'executable, UI: DebuggerUI> Debugger<'executable, UI> {
     /// # }}
     /// ```
     pub fn cont(&mut self) -> Result<Feedback> {
-        let dbge = self.debuggee.as_ref().ok_or(DebuggerError::NoDebugee)?;
-        ptrace::cont(dbge.pid, self.take_last_status())?;
-
-        self.wait_signal() // wait until the debuggee is stopped again!!!
-    }
+        self.step_over_hw_bp()?;
 
-    /// Gets the current registers of the debuggee
+        loop {
+            let feedback = self.cont_once()?;
+            if self.is_page_guard_fault()? {
+                if let Some(feedback) = self.step_guarded_write()? {
+                    return Ok(feedback);
+                }
+                continue;
+            }
+            if self.log_breakpoint_hit()? {
+                continue;
+            }
+            match self.panic_breakpoint_hit() {
+                Some(PanicBreakpoint::Location) => {
+                    self.panic_location = self.read_panic_location()?;
+                    self.step_over_hw_bp()?;
+                }
+                Some(PanicBreakpoint::Panic) => return self.panic_stop(),
+                None => return Ok(feedback),
+            }
+        }
+    }
+
+    /// Logs the message of the logging [`Breakpoint`] the debuggee stopped at, if any, and
+    /// steps over it
     ///
     /// # Returns
     ///
-    /// * `Ok(Feedback::Registers)` - The registers
-    /// * `Err(DebuggerError)` - If there was an error retrieving registers
+    /// * `Ok(true)` - If the debuggee stopped at a logging breakpoint and can continue
+    /// * `Ok(false)` - If the debuggee stopped for another reason
     ///
     /// # Errors
     ///
-    /// This function can fail if:
-    /// - The debuggee is not running
-    /// - ptrace's getregs operation fails
+    /// This function can fail if the breakpoint cannot be stepped over.
+    fn log_breakpoint_hit(&mut self) -> Result<bool> {
+        let Some(StopReason::Breakpoint { addr, hardware }) = self.last_stop else {
+            return Ok(false);
+        };
+        let Some(log) = self
+            .debuggee
+            .as_ref()
+            .and_then(|dbge| dbge.breakpoints.get(&addr))
+            .and_then(Breakpoint::log)
+            .cloned()
+        else {
+            return Ok(false);
+        };
+
+        if !hardware {
+            // the INT3 was executed, so rip is behind it
+            self.set_reg(Register::rip, addr.into())?;
+        }
+        let message = log.render(|expr| self.log_value(expr));
+        debug!("logging breakpoint at {addr}: {message}");
+        self.push_event(Event::Feedback(Feedback::Text(message)));
+        self.dse(addr)?;
+        Ok(true)
+    }
+
+    /// Evaluates an expression of a [`LogFormat`], which is a register or a variable expression
+    fn log_value(&self, expr: &str) -> String {
+        let value = match expr.parse::<Register>() {
+            Ok(reg) => self.get_reg(reg).map(|value| format!("{value:#x}")),
+            Err(_) => self
+                .read_variable(&expr.to_string())
+                .map(|feedback| match feedback {
+                    Feedback::Variable(value) => value.to_string(),
+                    other => other.to_string(),
+                }),
+        };
+        value.unwrap_or_else(|e| format!("<error: {e}>"))
+    }
+
+    /// Returns which breakpoint of the panic catchpoint the debuggee stopped at, if any
+    fn panic_breakpoint_hit(&self) -> Option<PanicBreakpoint> {
+        let Some(StopReason::Breakpoint { addr, .. }) = self.last_stop else {
+            return None;
+        };
+        self.panic_breakpoints
+            .iter()
+            .find(|(bp_addr, _)| *bp_addr == addr)
+            .map(|(_, kind)| *kind)
+    }
+
+    /// Reads the location that `panic_fmt` was called with
     ///
-    /// # Examples
+    /// Depending on the version of the compiler, the `fmt::Arguments` of the panic are passed in
+    /// one or two registers, so the location is in `rsi` or `rdx`.
     ///
+    /// # Errors
     ///
-    /// ```no_run
-    /// #[cfg(feature = "cli")]
-    /// # mod featguard { fn _do_thing() {
-    /// # use coreminer::debugger::Debugger;
-    /// # use coreminer::ui::cli::CliUi;
-    /// # use coreminer::feedback::Feedback;
-    /// #
-    /// # let ui = CliUi::build(None).unwrap();
-    /// # let debugger = Debugger::build(ui).unwrap();
-    /// # // Assume debuggee is already running
-    /// #
-    /// if let Ok(Feedback::Registers(regs)) = debugger.dump_regs() {
-    ///     println!("RIP: {:#x}", regs.rip);
-    ///     println!("RSP: {:#x}", regs.rsp);
-    ///     println!("RAX: {:#x}", regs.rax);
-    /// }
+    /// This function can fail if the registers of the debuggee cannot be read.
+    fn read_panic_location(&self) -> Result<Option<PanicLocation>> {
+        let dbge = self.debuggee.as_ref().ok_or(DebuggerError::NoDebugee)?;
+        let regs = ptrace::getregs(dbge.pid)?;
+        let read = Self::memory_reader(dbge.pid);
+        Ok([regs.rsi, regs.rdx]
+            .into_iter()
+            .find_map(|reg| rustpanic::read_location(&read, Addr::from(reg))))
+    }
+
+    /// Reports the panic at which the debuggee stopped in `rust_panic`
     ///
-    /// # }}
-    /// ```
-    pub fn dump_regs(&self) -> Result<Feedback> {
+    /// # Errors
+    ///
+    /// This function can fail if the registers of the debuggee cannot be read.
+    fn panic_stop(&mut self) -> Result<Feedback> {
         let dbge = self.debuggee.as_ref().ok_or(DebuggerError::NoDebugee)?;
         let regs = ptrace::getregs(dbge.pid)?;
-        Ok(Feedback::Registers(regs.into()))
+        // rust_panic takes a `&mut dyn PanicPayload`, a data and a vtable pointer
+        let message = rustpanic::read_message(
+            Self::memory_reader(dbge.pid),
+            Addr::from(regs.rdi),
+            Addr::from(regs.rsi),
+        );
+        let panic = RustPanic {
+            addr: Addr::from(regs.rip),
+            message,
+            location: self.panic_location.take(),
+        };
+        info!("the debuggee {panic}");
+        self.last_stop = Some(StopReason::Panic { addr: panic.addr });
+        Ok(Feedback::Panic(panic))
     }
 
-    /// Cleans up resources used by the debugger
+    /// Returns a function that reads memory of the process `pid`, `None` if not all of it can
+    /// be read
+    fn memory_reader(pid: Pid) -> impl Fn(Addr, usize) -> Option<Vec<u8>> {
+        move |addr, len| {
+            crate::memread::read_memory(pid, addr, len)
+                .ok()
+                .filter(crate::memread::MemoryRead::is_complete)
+                .map(|read| read.bytes)
+        }
+    }
+
+    /// Continues the debuggee until it stops, without handling the faults of [Page
+    /// Guards](PageGuard)
+    fn cont_once(&mut self) -> Result<Feedback> {
+        let dbge = self.debuggee.as_ref().ok_or(DebuggerError::NoDebugee)?;
+        if !dbge.watchpoints.is_empty() {
+            return self.cont_watched();
+        }
+        if self.traces_syscalls() {
+            return self.cont_syscalls();
+        }
+        let (pid, sig) = (dbge.pid, self.take_last_status());
+        self.resume_as(pid, Resume::Cont, sig)?;
+
+        self.wait_signal() // wait until the debuggee is stopped again!!!
+    }
+
+    /// Checks if the debuggee is stopped by a `SIGSEGV` that a [`PageGuard`] caused
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if the signal information of the debuggee cannot be read.
+    fn is_page_guard_fault(&self) -> Result<bool> {
+        let Some(dbge) = self.debuggee.as_ref() else {
+            // the debuggee exited
+            return Ok(false);
+        };
+        if dbge.page_guards.is_empty() || self.last_signal != Some(Signal::SIGSEGV) {
+            return Ok(false);
+        }
+        let siginfo = ptrace::getsiginfo(dbge.pid)?;
+        if siginfo.si_code != SEGV_ACCERR {
+            return Ok(false);
+        }
+        // SAFETY: si_addr is set for every SIGSEGV
+        let addr = Addr::from(unsafe { siginfo.si_addr() } as usize);
+        Ok(dbge.page_guards.values().any(|guard| guard.guards(addr)))
+    }
+
+    /// Executes a write into the pages of a [`PageGuard`] and checks if it changed a guarded range
     ///
-    /// This function terminates the debuggee if it's still running
-    /// and releases any resources held by the debugger.
+    /// The `SIGSEGV` of the write is not delivered to the debuggee. Instead, all
+    /// [Page Guards](PageGuard) are disarmed while the faulting instruction is single stepped.
     ///
     /// # Returns
     ///
-    /// * `Ok(())` - If cleanup was successful
-    /// * `Err(DebuggerError)` - If there was an error during cleanup
+    /// * `Ok(Some(Feedback::WatchpointHit))` - If a guarded memory range was changed
+    /// * `Ok(Some(Feedback))` - If the debuggee exited or stopped for another reason during the
+    ///   step
+    /// * `Ok(None)` - If no guarded memory range was changed, so the debuggee can continue
+    /// * `Err(DebuggerError)` - If there was an error during stepping
     ///
     /// # Errors
     ///
     /// This function can fail if:
-    /// - The exists but debuggee cannot be killed with [`Debuggee::kill`]
+    /// - The debuggee is not running
+    /// - ptrace operations fail
+    /// - The protection of the guarded pages cannot be changed
+    fn step_guarded_write(&mut self) -> Result<Option<Feedback>> {
+        let instruction: Addr = self.get_current_addr()?;
+        trace!("stepping over the guarded write at {instruction}");
+        self.last_signal = None;
+
+        let dbge = self.debuggee.as_mut().ok_or(DebuggerError::NoDebugee)?;
+        for guard in dbge.page_guards.values_mut() {
+            guard.disarm(dbge.pid)?;
+        }
+        let feedback = self.step_instruction()?;
+        if let Feedback::Exit(_) = feedback {
+            return Ok(Some(feedback));
+        }
+        let dbge = self.debuggee.as_mut().ok_or(DebuggerError::NoDebugee)?;
+        for guard in dbge.page_guards.values_mut() {
+            guard.arm(dbge.pid)?;
+        }
+        if let Feedback::Interrupted = feedback {
+            return Ok(Some(feedback));
+        }
+
+        for guard in dbge.page_guards.values_mut() {
+            if let Some(hit) = guard.check(dbge.pid, instruction)? {
+                info!(
+                    "guarded memory at {} was changed by the instruction at {instruction}",
+                    hit.addr
+                );
+                self.last_stop = Some(StopReason::Watchpoint { addr: hit.addr });
+                return Ok(Some(Feedback::WatchpointHit(hit)));
+            }
+        }
+        if self.last_signal.is_some() {
+            return Ok(Some(feedback));
+        }
+        Ok(None)
+    }
+
+    /// Executes the instruction at an enabled hardware [`Breakpoint`] at `rip`, if there is one
     ///
-    /// # Examples
+    /// Hardware breakpoints stop the debuggee before the instruction is executed, so continuing
+    /// right away would hit the breakpoint again.
+    fn step_over_hw_bp(&mut self) -> Result<()> {
+        let dbge = self.debuggee.as_ref().ok_or(DebuggerError::NoDebugee)?;
+        let rip: Addr = self.get_current_addr()?;
+        if dbge
+            .breakpoints
+            .get(&rip)
+            .is_some_and(|bp| bp.is_hardware() && bp.is_enabled())
+        {
+            trace!("stepping over the hardware breakpoint at {rip}");
+            self.dse(rip)?;
+        }
+        Ok(())
+    }
+
+    /// Continues execution of the debuggee without waiting for it to stop again
     ///
+    /// This is the non-blocking counterpart of [`Self::cont`] for embedders with their own event
+    /// loop. After resuming, [`Self::poll_event`] is used to find out when and why the debuggee
+    /// stopped. The debuggee must not be used in any other way until then.
     ///
-    /// ```no_run
-    /// #[cfg(feature = "cli")]
-    /// # mod featguard { fn _do_thing() {
-    /// # use coreminer::debugger::Debugger;
-    /// # use coreminer::ui::cli::CliUi;
-    /// #
-    /// # let ui = CliUi::build(None).unwrap();
-    /// # let mut debugger = Debugger::build(ui).unwrap();
-    /// #
-    /// // Clean up resources at the end of debugging
-    /// debugger.cleanup().unwrap();
+    /// # Returns
     ///
-    /// # }}
-    /// ```
-    pub fn cleanup(&mut self) -> Result<()> {
-        if let Some(dbge) = &self.debuggee {
-            dbge.kill()?;
-            self.debuggee = None;
+    /// * `Ok(())` - The debuggee was resumed
+    /// * `Err(DebuggerError)` - If the debuggee could not be resumed
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if:
+    /// - The debuggee is not running
+    /// - [Watchpoints](Watchpoint) or [Page Guards](PageGuard) are set, which need the debugger
+    ///   to handle the stops of the debuggee
+    /// - ptrace operations fail
+    pub fn resume(&mut self) -> Result<()> {
+        let dbge = self.debuggee.as_ref().ok_or(DebuggerError::NoDebugee)?;
+        if !dbge.watchpoints.is_empty() || !dbge.page_guards.is_empty() {
+            return Err(DebuggerError::ResumeWithWatchpoints);
+        }
+        self.step_over_hw_bp()?;
+
+        let pid = self.debuggee.as_ref().ok_or(DebuggerError::NoDebugee)?.pid;
+        if self.traces_syscalls() {
+            ptrace::setoptions(pid, self.ptrace_flags())?;
+            let sig = self.take_last_status();
+            self.resume_as(pid, Resume::Syscall, sig)?;
+        } else {
+            let sig = self.take_last_status();
+            self.resume_as(pid, Resume::Cont, sig)?;
         }
         Ok(())
     }
 
-    /// Sets a breakpoint at the specified address
+    /// Checks whether the debuggee has stopped, without blocking
     ///
-    /// # Parameters
+    /// Unlike [`Self::wait_signal`], this uses `WNOHANG`, so it returns right away if the debuggee
+    /// is still running. This allows GUIs and async servers to poll the debuggee from their own
+    /// event loop after it was resumed with [`Self::resume`].
     ///
-    /// * `addr` - The address to set the breakpoint at
+    /// Stops the debugger does not need to report, like syscall stops while only
+    /// [anti-anti-debugging](crate::antidebug) is active, are handled and the debuggee is resumed
+    /// again, so that [`None`] is returned for them.
     ///
     /// # Returns
     ///
-    /// * `Ok(Feedback::Ok)` - If the breakpoint was set successfully
-    /// * `Err(DebuggerError)` - If there was an error setting the breakpoint
+    /// * `Ok(Some(StopEvent))` - The debuggee stopped or exited
+    /// * `Ok(None)` - The debuggee is still running
+    /// * `Err(DebuggerError)` - If there was an error while checking or processing the stop
     ///
     /// # Errors
     ///
     /// This function can fail if:
     /// - The debuggee is not running
-    /// - The breakpoint could not be enabled
+    /// - waitpid fails, for example because the debuggee was not resumed
+    /// - The stop cannot be processed
     ///
     /// # Examples
     ///
@@ -724,50 +2223,59 @@ impl<'executable, UI: DebuggerUI> Debugger<'executable, UI> {
     /// # mod featguard { fn _do_thing() {
     /// # use coreminer::debugger::Debugger;
     /// # use coreminer::ui::cli::CliUi;
-    /// # use coreminer::addr::Addr;
+    /// # use coreminer::feedback::Feedback;
     /// #
     /// # let ui = CliUi::build(None).unwrap();
     /// # let mut debugger = Debugger::build(ui).unwrap();
     /// # // Assume debuggee is already running
     /// #
-    /// // Set a breakpoint at absolute address 0x1000
-    /// debugger.set_bp(Addr::from(0x1000usize)).unwrap();
-    ///
-    /// // Set a breakpoint at the program's entry point
-    /// let base = debugger.get_current_addr().unwrap();
-    /// debugger.set_bp(base).unwrap();
-    ///
-    /// // Set a breakpoint at the relative address 0x1000
-    /// let base = debugger.get_current_addr().unwrap();
-    /// debugger.set_bp(base + 0x1000).unwrap();
+    /// debugger.resume().unwrap();
+    /// loop {
+    ///     if let Some(event) = debugger.poll_event().unwrap() {
+    ///         println!("debuggee stopped: {}", event.feedback);
+    ///         break;
+    ///     }
+    ///     // do other work, like redrawing the GUI
+    /// }
     ///
     /// # }}
     /// ```
-    pub fn set_bp(&mut self, addr: Addr) -> Result<Feedback> {
-        let dbge = self.debuggee.as_mut().ok_or(DebuggerError::NoDebugee)?;
-        let mut bp = Breakpoint::new(dbge.pid, addr);
-        bp.enable()?;
-        dbge.breakpoints.insert(addr, bp);
-
-        Ok(Feedback::Ok)
+    pub fn poll_event(&mut self) -> Result<Option<StopEvent>> {
+        let wait_status = self.wait(&[WaitPidFlag::WNOHANG])?;
+        if wait_status == WaitStatus::StillAlive {
+            return Ok(None);
+        }
+        Ok(self
+            .process_resumed_status(wait_status)?
+            .map(|feedback| StopEvent {
+                wait_status,
+                feedback,
+            }))
     }
 
-    /// Removes a breakpoint at the specified address
+    /// Continues the debuggee, but stops it again if it did not stop on its own after `timeout`
+    ///
+    /// This works like [`Self::cont`], but gives the control back to the UI after at most
+    /// `timeout`, so that frontends stay responsive when the debuggee runs for a long time. If
+    /// the timeout is reached, the debuggee is stopped with `SIGSTOP`, which is not delivered to
+    /// it again on the next continue.
     ///
     /// # Parameters
     ///
-    /// * `addr` - The address to remove the breakpoint from
+    /// * `timeout` - How long the debuggee may run
     ///
     /// # Returns
     ///
-    /// * `Ok(Feedback::Ok)` - If the breakpoint was removed successfully
-    /// * `Err(DebuggerError)` - If there was an error removing the breakpoint
+    /// * `Ok(Feedback::Interrupted)` - The timeout was reached and the debuggee was stopped
+    /// * `Ok(Feedback)` - The debuggee stopped or exited on its own, like with [`Self::cont`]
+    /// * `Err(DebuggerError)` - If there was an error during continuation
     ///
     /// # Errors
     ///
     /// This function can fail if:
     /// - The debuggee is not running
-    /// - The breakpoint could not be disabled
+    /// - [Watchpoints](Watchpoint) are set, see [`Self::resume`]
+    /// - ptrace operations or waiting fail
     ///
     /// # Examples
     ///
@@ -777,72 +2285,64 @@ impl<'executable, UI: DebuggerUI> Debugger<'executable, UI> {
     /// # mod featguard { fn _do_thing() {
     /// # use coreminer::debugger::Debugger;
     /// # use coreminer::ui::cli::CliUi;
-    /// # use coreminer::addr::Addr;
+    /// # use coreminer::feedback::Feedback;
+    /// # use std::time::Duration;
     /// #
     /// # let ui = CliUi::build(None).unwrap();
     /// # let mut debugger = Debugger::build(ui).unwrap();
     /// # // Assume debuggee is already running
     /// #
-    /// // Remove a breakpoint at address 0x1000
-    /// debugger.del_bp(Addr::from(0x1000usize)).unwrap();
-    ///
-    /// // Remove a breakpoint at the relative address 0x1000
-    /// let base = debugger.get_current_addr().unwrap();
-    /// debugger.del_bp(base + 0x1000).unwrap();
+    /// match debugger.cont_timeout(Duration::from_secs(2)).unwrap() {
+    ///     Feedback::Interrupted => println!("still running after 2s, stopped it"),
+    ///     other => println!("debuggee stopped: {other}"),
+    /// }
     ///
     /// # }}
     /// ```
-    pub fn del_bp(&mut self, addr: Addr) -> Result<Feedback> {
-        let dbge = self.debuggee.as_mut().ok_or(DebuggerError::NoDebugee)?;
+    pub fn cont_timeout(&mut self, timeout: Duration) -> Result<Feedback> {
+        self.resume()?;
+        let deadline = Instant::now() + timeout;
 
-        if let Some(_bp) = dbge.breakpoints.get_mut(&addr) {
-            dbge.breakpoints.remove(&addr); // gets disabled on dropping
-        } else {
-            warn!("removed a breakpoint at {addr:x?} that did not exist");
+        loop {
+            let Some(wait_status) = self.wait_interruptible_until(Some(deadline))? else {
+                info!("debuggee still running after {timeout:?}, interrupting it");
+                return self.interrupt();
+            };
+            if let Some(feedback) = self.process_resumed_status(wait_status)? {
+                return Ok(feedback);
+            }
         }
-
-        Ok(Feedback::Ok)
     }
 
-    /// Performs a single, atomic step of exactly one instruction through the debuggee
-    ///
-    /// # Returns
+    /// Profiles the debuggee by sampling its call stack while it runs
     ///
-    /// * `Ok(())` - If the step was successful
-    /// * `Err(DebuggerError)` - If there was an error during stepping
+    /// The debuggee is continued for `duration`, but stopped `frequency` times per second to
+    /// unwind its call stack, like with [`Self::cont_timeout`]. The samples are aggregated into
+    /// a [`Profile`], which replaces the previous one and can be exported for flamegraph tools
+    /// with [`Self::export_flamegraph`]. See the [profile module](crate::profile) for details.
     ///
-    /// # Errors
+    /// If the debuggee stops or exits for another reason before `duration` is over, profiling
+    /// ends early. The [`Profile`] of the samples so far is kept, but the [`Feedback`] of the stop
+    /// is returned instead.
     ///
-    /// This function can fail if:
-    /// - The debuggee is not running
-    /// - ptrace's step operation fails
-    fn atomic_single_step(&mut self) -> Result<()> {
-        let dbge = self.debuggee.as_ref().ok_or(DebuggerError::NoDebugee)?;
-
-        // FIXME: this is probably noticeable
-        if let Err(e) = ptrace::step(dbge.pid, self.take_last_status()) {
-            error!("could not do atomic step: {e}");
-            return Err(e.into());
-        }
-
-        Ok(())
-    }
-
-    /// Steps a single instruction in the debuggee
+    /// # Parameters
     ///
-    /// This function advances execution by a single instruction, taking
-    /// care to handle breakpoints appropriately.
+    /// * `duration` - How long the debuggee is profiled
+    /// * `frequency` - How many samples are taken per second
     ///
     /// # Returns
     ///
-    /// * `Ok(Feedback::Ok)` - If the step was successful
-    /// * `Err(DebuggerError)` - If there was an error during stepping
+    /// * `Ok(Feedback::Profile)` - The samples that were taken
+    /// * `Ok(Feedback)` - The debuggee stopped or exited on its own, like with [`Self::cont`]
+    /// * `Err(DebuggerError)` - If there was an error during profiling
     ///
     /// # Errors
     ///
     /// This function can fail if:
     /// - The debuggee is not running
-    /// - ptrace operations fail
+    /// - `frequency` is zero
+    /// - [Watchpoints](Watchpoint) are set, see [`Self::resume`]
+    /// - ptrace operations or waiting fail
     ///
     /// # Examples
     ///
@@ -852,60 +2352,76 @@ impl<'executable, UI: DebuggerUI> Debugger<'executable, UI> {
     /// # mod featguard { fn _do_thing() {
     /// # use coreminer::debugger::Debugger;
     /// # use coreminer::ui::cli::CliUi;
+    /// # use coreminer::feedback::Feedback;
+    /// # use std::time::Duration;
     /// #
     /// # let ui = CliUi::build(None).unwrap();
     /// # let mut debugger = Debugger::build(ui).unwrap();
     /// # // Assume debuggee is already running
     /// #
-    /// // Step through one instruction
-    /// debugger.single_step().unwrap();
-    ///
-    /// // Step through multiple instructions
-    /// for _ in 0..5 {
-    ///     debugger.single_step().unwrap();
+    /// // Take 100 samples per second for 5 seconds
+    /// if let Feedback::Profile(profile) = debugger.profile(Duration::from_secs(5), 100).unwrap() {
+    ///     print!("{}", profile.folded());
     /// }
     ///
     /// # }}
     /// ```
-    pub fn single_step(&mut self) -> Result<Feedback> {
-        if self.go_back_step_over_bp()? {
-            info!("breakpoint before, caught up and continueing with single step");
+    pub fn profile(&mut self, duration: Duration, frequency: u64) -> Result<Feedback> {
+        if frequency == 0 {
+            return Err(DebuggerError::InvalidProfileFrequency);
         }
-        let dbge = self.debuggee.as_ref().ok_or(DebuggerError::NoDebugee)?;
+        let interval = Duration::from_secs(1) / u32::try_from(frequency).unwrap_or(u32::MAX);
+        let deadline = Instant::now() + duration;
+        let mut profile = Profile::default();
+        info!("profiling the debuggee for {duration:?} with {frequency} samples per second");
 
-        let maybe_bp_addr: Addr = self.get_current_addr()?;
-        if dbge.breakpoints.contains_key(&maybe_bp_addr) {
-            trace!("step over instruction with breakpoint");
-            self.dse(maybe_bp_addr)?;
-        } else {
-            trace!("step regular instruction");
-            self.atomic_single_step()?;
-            self.wait_signal()?;
+        while let Some(remaining) = deadline.checked_duration_since(Instant::now()) {
+            let feedback = self.cont_timeout(interval.min(remaining))?;
+            if !matches!(feedback, Feedback::Interrupted) {
+                info!(
+                    "the debuggee stopped after {} samples, profiling ended early",
+                    profile.samples()
+                );
+                self.profile = Some(profile);
+                return Ok(feedback);
+            }
+
+            let pid = self.debuggee.as_ref().ok_or(DebuggerError::NoDebugee)?.pid;
+            match unwind::unwind(pid) {
+                Ok(backtrace) => profile.add(&backtrace),
+                Err(e) => {
+                    trace!("could not unwind the sample: {e}");
+                    profile.add_lost();
+                }
+            }
         }
-        trace!("now at {:018x}", self.get_reg(Register::rip)?);
 
-        Ok(Feedback::Ok)
+        info!("took {} samples", profile.samples());
+        self.profile = Some(profile.clone());
+        Ok(Feedback::Profile(profile))
     }
 
-    /// Steps out of the current function
+    /// Exports the last [`Profile`] for flamegraph tools
     ///
-    /// This function sets a temporary breakpoint at the return address
-    /// and continues execution until that breakpoint is hit.
+    /// The [`Profile`] of the last [`Self::profile`] is written either in the folded stack
+    /// format, which `flamegraph.pl` and `inferno-flamegraph` read, or as an SVG flamegraph that
+    /// is rendered by the debugger. The [`Profile`] is kept after the debuggee exited.
+    ///
+    /// # Parameters
+    ///
+    /// * `output` - Path of the file to write
+    /// * `svg` - Whether to render an SVG flamegraph instead of writing the folded stacks
     ///
     /// # Returns
     ///
-    /// * `Ok(Feedback::Ok)` - If the step-out was successful
-    /// * `Err(DebuggerError)` - If there was an error during step-out
+    /// * `Ok(Feedback::Ok)` - If the file was written
+    /// * `Err(DebuggerError)` - If the profile could not be exported
     ///
     /// # Errors
     ///
     /// This function can fail if:
-    /// - The debuggee is not running
-    /// - No valid return address of the current function can be found
-    /// - The current function is main (cannot step out)
-    /// - Could not read or write a [Register].
-    /// - Could not read from memory.
-    /// - Could not set or delete a [Breakpoint].
+    /// - The debuggee was not profiled yet
+    /// - The output file cannot be written
     ///
     /// # Examples
     ///
@@ -915,171 +2431,162 @@ impl<'executable, UI: DebuggerUI> Debugger<'executable, UI> {
     /// # mod featguard { fn _do_thing() {
     /// # use coreminer::debugger::Debugger;
     /// # use coreminer::ui::cli::CliUi;
+    /// # use std::path::Path;
+    /// # use std::time::Duration;
     /// #
     /// # let ui = CliUi::build(None).unwrap();
     /// # let mut debugger = Debugger::build(ui).unwrap();
-    /// # // Assume debuggee is already running and in a function
+    /// # // Assume debuggee is already running
     /// #
-    /// // Step out of the current function
-    /// debugger.step_out().unwrap();
+    /// debugger.profile(Duration::from_secs(5), 100).unwrap();
+    ///
+    /// // Render it with: inferno-flamegraph /tmp/profile.folded > /tmp/profile.svg
+    /// debugger.export_flamegraph(Path::new("/tmp/profile.folded"), false).unwrap();
+    /// // Or let the debugger render it
+    /// debugger.export_flamegraph(Path::new("/tmp/profile.svg"), true).unwrap();
     ///
     /// # }}
     /// ```
-    pub fn step_out(&mut self) -> Result<Feedback> {
-        let dbge = self.debuggee.as_ref().ok_or(DebuggerError::NoDebugee)?;
-        {
-            let a = dbge.get_function_by_addr(self.get_reg(Register::rip)?.into())?;
-            if let Some(s) = a {
-                debug!("step out in following function: {s:#?}");
-                if s.name() == Some("main") {
-                    error!("you're about to do something stupid: no stepping out of the earliest stack frame allowed");
-                    return Err(DebuggerError::StepOutMain);
-                }
-            } else {
-                warn!("did not find debug symbol for current address");
-            }
-        }
-
-        let stack_frame_pointer: Addr = self.get_reg(Register::rbp)?.into();
-        let return_addr: Addr = mem_read_word(dbge.pid, stack_frame_pointer + 8)?.into();
-        trace!("rsb: {stack_frame_pointer}");
-        trace!("ret_addr: {return_addr}");
-
-        let should_remove_breakpoint = if dbge.breakpoints.contains_key(&return_addr) {
-            false
+    pub fn export_flamegraph(&self, output: &Path, svg: bool) -> Result<Feedback> {
+        let profile = self.profile.as_ref().ok_or(DebuggerError::NoProfile)?;
+        let data = if svg {
+            let title = format!("Flamegraph of {} samples", profile.samples());
+            profile.flamegraph(&title).to_string()
         } else {
-            self.set_bp(return_addr)?;
-            true
+            profile.folded()
         };
-
-        self.cont()?;
-
-        if should_remove_breakpoint {
-            self.del_bp(return_addr)?;
-            self.set_reg(Register::rip, self.get_reg(Register::rip)? - 1)?; // we need to go back
-                                                                            // else we skip an instruction
-        }
+        std::fs::write(output, data)?;
+        info!("wrote the profile to {}", output.display());
         Ok(Feedback::Ok)
     }
 
-    /// Temporarily disables a breakpoint, steps over it, and then re-enables it
+    /// Exports the [`Journal`] of the debug session as Chrome trace
+    ///
+    /// Writes the launches, attaches, runs and stops of this debug session in the Chrome trace
+    /// event format, see [`Journal::chrome_trace`]. The file can be opened with
+    /// `chrome://tracing` or the Perfetto UI to see the session on a timeline. The [`Journal`] is
+    /// kept across debuggees, so that a session with several runs can be exported as a whole.
     ///
     /// # Parameters
     ///
-    /// * `here` - The address of the breakpoint
+    /// * `output` - Path of the JSON file to write
     ///
     /// # Returns
     ///
-    /// * `Ok(())` - If the operation was successful
-    /// * `Err(DebuggerError)` - If there was an error
+    /// * `Ok(Feedback::Ok)` - If the file was written
+    /// * `Err(DebuggerError)` - If the trace could not be written
     ///
     /// # Errors
     ///
-    /// This function can fail if:
-    /// - The debuggee is not running
-    /// - Breakpoint operations fail
-    /// - Step operations fail
-    fn dse(&mut self, here: Addr) -> Result<()> {
-        trace!("disabling the breakpoint");
-        self.debuggee
-            .as_mut()
-            .unwrap()
-            .breakpoints
-            .get_mut(&here)
-            .unwrap()
-            .disable()?;
+    /// This function can fail if the output file cannot be written.
+    ///
+    /// # Examples
+    ///
+    ///
+    /// ```no_run
+    /// #[cfg(feature = "cli")]
+    /// # mod featguard { fn _do_thing() {
+    /// # use coreminer::debugger::Debugger;
+    /// # use coreminer::ui::cli::CliUi;
+    /// # use std::path::Path;
+    /// #
+    /// # let ui = CliUi::build(None).unwrap();
+    /// # let mut debugger = Debugger::build(ui).unwrap();
+    /// # // Assume a debug session happened
+    /// #
+    /// debugger.export_trace(Path::new("/tmp/session.trace.json")).unwrap();
+    ///
+    /// # }}
+    /// ```
+    pub fn export_trace(&self, output: &Path) -> Result<Feedback> {
+        let trace = self.journal.chrome_trace();
+        std::fs::write(output, serde_json::to_string(&trace)?)?;
+        info!(
+            "wrote {} journal entries to {}",
+            self.journal.entries().len(),
+            output.display()
+        );
+        Ok(Feedback::Ok)
+    }
 
-        trace!("atomic step");
-        self.atomic_single_step()?;
-        trace!("waiting");
-        self.wait_signal()
-            .inspect_err(|e| warn!("weird wait_signal error: {e}"))?;
-        trace!("enable stepped over bp again");
-        self.debuggee
-            .as_mut()
-            .unwrap()
-            .breakpoints
-            .get_mut(&here)
-            .unwrap()
-            .enable()?;
-        trace!("dse done");
+    /// Stops the running debuggee with `SIGSTOP` and waits until it is stopped
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Feedback::Interrupted)` - The debuggee was stopped
+    /// * `Ok(Feedback)` - The debuggee stopped or exited for another reason first
+    /// * `Err(DebuggerError)` - If the debuggee could not be stopped
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if the signal cannot be sent or waiting fails.
+    fn interrupt(&mut self) -> Result<Feedback> {
+        self.send_interrupt()?;
+
+        loop {
+            let wait_status = self.wait_interruptible()?;
+            if let Some(feedback) = self.process_resumed_status(wait_status)? {
+                return Ok(feedback);
+            }
+        }
+    }
 
+    /// Sends a `SIGSTOP` to the running debuggee
+    ///
+    /// The `SIGSTOP` is marked as sent by the debugger, so that [`Self::process_wait_status`]
+    /// reports it as [`Feedback::Interrupted`] and does not deliver it to the debuggee again
+    /// later.
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if the signal cannot be sent.
+    fn send_interrupt(&mut self) -> Result<()> {
+        let pid = self.debuggee.as_ref().ok_or(DebuggerError::NoDebugee)?.pid;
+        self.interrupt_pending = true;
+        nix::sys::signal::kill(pid, Signal::SIGSTOP)?;
         Ok(())
     }
 
-    /// Checks if we need to restore an instruction pointer after hitting a breakpoint
+    /// Processes a [`WaitStatus`] of a debuggee that was resumed with [`Self::resume`]
     ///
-    /// When a breakpoint is hit, the instruction pointer is just after the INT3 instruction.
-    /// This function checks if we need to move the instruction pointer back to the breakpoint
-    /// address and execute the original instruction. If so, it does that.
+    /// Syscall stops that should not be reported are handled and the debuggee is resumed again.
     ///
     /// # Returns
     ///
-    /// * `Ok(bool)` - True if the IP was adjusted and a breakpoint was stepped over
-    /// * `Err(DebuggerError)` - If there was an error
+    /// * `Ok(Some(Feedback))` - The debuggee stopped or exited
+    /// * `Ok(None)` - The debuggee was resumed again
+    /// * `Err(DebuggerError)` - If the stop could not be processed
     ///
     /// # Errors
     ///
-    /// This function can fail if:
-    /// - The debuggee is not running
-    /// - Register operations fail
-    /// - Breakpoint operations fail
-    #[allow(clippy::missing_panics_doc)] // this function cant panic
-    pub fn go_back_step_over_bp(&mut self) -> Result<bool> {
-        if self.debuggee.is_none() {
-            return Err(DebuggerError::NoDebugee);
+    /// This function can fail if the stop cannot be processed or the debuggee cannot be resumed.
+    fn process_resumed_status(&mut self, wait_status: WaitStatus) -> Result<Option<Feedback>> {
+        if let WaitStatus::PtraceSyscall(pid) = wait_status {
+            if let Some(feedback) = self.handle_syscall_stop()? {
+                return Ok(Some(feedback));
+            }
+            let sig = self.take_last_status();
+            self.resume_as(pid, Resume::Syscall, sig)?;
+            return Ok(None);
         }
-
-        let maybe_bp_addr: Addr = self.get_current_addr()? - 1;
-        trace!("Checkinf if {maybe_bp_addr} had a breakpoint");
-
-        if self
-            .debuggee
-            .as_mut()
-            // safe because we check earlier, needed because we use a mutable reference
-            // and can only drop in place this way
-            .unwrap()
-            .breakpoints
-            .get_mut(&maybe_bp_addr)
-            .is_some_and(|a| a.is_enabled())
-        {
-            let here = maybe_bp_addr;
-            trace!("set register to {here}");
-            self.set_reg(Register::rip, here.into())?;
-
-            self.dse(here)?;
-            Ok(true)
-        } else {
-            trace!("breakpoint is disabled or does not exist, doing nothing");
-            Ok(false)
+        if self.skip_seccomp_stop(wait_status)? {
+            return Ok(None);
         }
+        self.process_wait_status(wait_status).map(Some)
     }
 
-    /// Disassembles memory at the specified address
-    ///
-    /// # Parameters
-    ///
-    /// * `addr` - The starting address
-    /// * `len` - The number of bytes to disassemble
-    /// * `literal` - Whether to show literal bytes instead of original code (including breakpoint instructions)
+    /// Gets the current registers of the debuggee
     ///
     /// # Returns
     ///
-    /// * `Ok(Feedback::Disassembly)` - The disassembly result
-    /// * `Err(DebuggerError)` - If there was an error during disassembly
+    /// * `Ok(Feedback::Registers)` - The registers, as a portable [`RegisterSet`](crate::regset::RegisterSet)
+    /// * `Err(DebuggerError)` - If there was an error retrieving registers
     ///
     /// # Errors
     ///
     /// This function can fail if:
     /// - The debuggee is not running
-    /// - Memory cannot be read
-    /// - Disassembly fails
-    ///
-    /// # Panics
-    ///
-    /// If a [Breakpoint] is enabled but has no saved data, this will panic.
-    /// If a [Breakpoint] was found before making the [Disassembly], but the same breakpoint does
-    /// not exist after the [Disassembly] was created, this will also panic.
+    /// - ptrace's getregs operation fails
     ///
     /// # Examples
     ///
@@ -1089,49 +2596,44 @@ impl<'executable, UI: DebuggerUI> Debugger<'executable, UI> {
     /// # mod featguard { fn _do_thing() {
     /// # use coreminer::debugger::Debugger;
     /// # use coreminer::ui::cli::CliUi;
-    /// # use coreminer::addr::Addr;
     /// # use coreminer::feedback::Feedback;
     /// #
     /// # let ui = CliUi::build(None).unwrap();
     /// # let debugger = Debugger::build(ui).unwrap();
     /// # // Assume debuggee is already running
     /// #
-    /// // Disassemble 16 bytes at address 0x1000
-    /// if let Ok(Feedback::Disassembly(disasm)) = debugger.disassemble_at(Addr::from(0x1000usize), 16, false) {
-    ///     for (addr, raw, content, has_bp) in disasm.inner() {
-    ///         println!("{}: {} {}", addr, if *has_bp { "*" } else { " " }, content[0].0);
+    /// if let Ok(Feedback::Registers(regs)) = debugger.dump_regs() {
+    ///     for reg in regs.registers() {
+    ///         println!("{}: {:#x}", reg.name, reg.value);
     ///     }
     /// }
     ///
     /// # }}
     /// ```
-    pub fn disassemble_at(&self, addr: Addr, len: usize, literal: bool) -> Result<Feedback> {
+    pub fn dump_regs(&self) -> Result<Feedback> {
         let dbge = self.debuggee.as_ref().ok_or(DebuggerError::NoDebugee)?;
-
-        let t = dbge.disassemble(addr, len, literal)?;
-
-        Ok(Feedback::Disassembly(t))
+        let regs = match &dbge.core {
+            Some(core) => core.registers(),
+            None => ptrace::getregs(dbge.pid)?,
+        };
+        Ok(Feedback::Registers(regs.into()))
     }
 
-    /// Searches for symbols by name
-    ///
-    /// # Parameters
+    /// Cleans up resources used by the debugger
     ///
-    /// * `name` - The name to search for
+    /// This function kills the debuggee if it's still running, or detaches from it if the
+    /// [`QuitAction`] is [`QuitAction::Detach`], and releases any resources held by the debugger.
     ///
     /// # Returns
     ///
-    /// * `Ok(Feedback::Symbols)` - The matching symbols
-    /// * `Err(DebuggerError)` - If there was an error during search
-    ///
-    /// Note: If the executable that is being debugged has no DWARF information (was stripped), this will always
-    /// return no symbols.
+    /// * `Ok(())` - If cleanup was successful
+    /// * `Err(DebuggerError)` - If there was an error during cleanup
     ///
     /// # Errors
     ///
     /// This function can fail if:
-    /// - The debug information is not loaded
-    /// - Symbol information is not available
+    /// - The exists but debuggee cannot be killed with [`Debuggee::kill`]
+    /// - The debuggee cannot be detached with [`Self::detach`]
     ///
     /// # Examples
     ///
@@ -1141,91 +2643,3142 @@ impl<'executable, UI: DebuggerUI> Debugger<'executable, UI> {
     /// # mod featguard { fn _do_thing() {
     /// # use coreminer::debugger::Debugger;
     /// # use coreminer::ui::cli::CliUi;
-    /// # use coreminer::feedback::Feedback;
     /// #
     /// # let ui = CliUi::build(None).unwrap();
-    /// # let debugger = Debugger::build(ui).unwrap();
-    /// # // Assume debuggee is already running
+    /// # let mut debugger = Debugger::build(ui).unwrap();
     /// #
-    /// // Find the "main" function
-    /// if let Ok(Feedback::Symbols(symbols)) = debugger.get_symbol_by_name("main") {
-    ///     for symbol in symbols {
-    ///         println!("Found main at: {:?}", symbol.low_addr());
-    ///     }
-    /// }
+    /// // Clean up resources at the end of debugging
+    /// debugger.cleanup().unwrap();
     ///
     /// # }}
     /// ```
-    pub fn get_symbol_by_name(&self, name: impl Display) -> Result<Feedback> {
-        let dbge = self.debuggee.as_ref().ok_or(DebuggerError::NoDebugee)?;
-
-        let symbols: Vec<OwnedSymbol> = dbge.get_symbol_by_name(name)?;
-        Ok(Feedback::Symbols(symbols))
+    pub fn cleanup(&mut self) -> Result<()> {
+        self.replay = None;
+        let Some(dbge) = &self.debuggee else {
+            return Ok(());
+        };
+        if dbge.core.is_some() {
+            self.debuggee = None;
+            return Ok(());
+        }
+        match self.quit_action {
+            QuitAction::Kill => {
+                dbge.kill()?;
+                self.debuggee = None;
+            }
+            QuitAction::Detach => {
+                self.detach()?;
+            }
+        }
+        Ok(())
     }
 
-    /// Handles a SIGTRAP signal from the debuggee
+    /// Selects what happens to a running debuggee when the debugger quits
+    ///
+    /// The action is taken by [`Self::cleanup`]. By default, the debuggee is killed.
     ///
     /// # Parameters
     ///
-    /// * `sig` - The signal
-    /// * `siginfo` - The signal information
+    /// * `action` - Whether to kill or detach from the debuggee
     ///
     /// # Returns
     ///
-    /// * `Ok(())` - If the signal was handled successfully
-    /// * `Err(DebuggerError)` - If there was an error handling the signal
+    /// * `Ok(Feedback::Ok)` - The setting was applied
     ///
     /// # Errors
     ///
-    /// This function can fail if:
-    /// - The debuggee is not running
-    pub fn handle_sigtrap(
-        &mut self,
-        sig: nix::sys::signal::Signal,
-        siginfo: nix::libc::siginfo_t,
-    ) -> Result<()> {
-        #[allow(unused_mut)] // used for plugins
-        let mut return_early = false;
-
-        for_hooks!(
-            for hook[EPreSigtrap] in self {
-                trace!("process hook {}", hook.name());
-                self.hook_feedback_loop(hook.name(), |f| {
-                    match hook.inner_mut().pre_handle_sigtrap(f, &siginfo, &sig) {
-                        Ok((status, ret_e)) => {
-                            return_early |= ret_e;
-                            Ok(status)
-                        },
-                        Err(e) => Err(e)
-                    }
-                })?;
-            }
-        );
-
-        if return_early {
-            return Ok(());
-        }
-
-        info!("debugee received {}: {}", sig.as_str(), siginfo.si_code);
-
-        match siginfo.si_code {
-            SI_KERNEL => trace!("SI_KERNEL"), // we don't know what do do?
-            TRAP_BRKPT => {
-                trace!("TRAP_BRKPT");
-            }
-            TRAP_TRACE => trace!("TRAP_TRACE"), // single stepping
-            _ => warn!("Strange SIGTRAP code: {}", siginfo.si_code),
-        }
-
-        Ok(())
+    /// Cannot fail.
+    pub fn set_quit_action(&mut self, action: QuitAction) -> Result<Feedback> {
+        info!("quit action: {action:?}");
+        self.quit_action = action;
+        Ok(Feedback::Ok)
     }
 
-    /// Handles important signals from the debuggee
+    /// Selects which threads of the debuggee run while the main thread is resumed
+    ///
+    /// With [`SchedulerLocking::Step`] or [`SchedulerLocking::On`], the other threads are stopped
+    /// before the main thread is resumed and stay stopped until it is resumed without locking
+    /// again. Signals that stopped them meanwhile are delivered then.
     ///
     /// # Parameters
     ///
-    /// * `sig` - The signal
-    /// * `siginfo` - The signal information
+    /// * `locking` - Which threads run
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Feedback::Ok)` - The setting was applied
+    ///
+    /// # Errors
+    ///
+    /// Cannot fail.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #[cfg(feature = "cli")]
+    /// # mod featguard { fn _do_thing() {
+    /// # use coreminer::debugger::{Debugger, SchedulerLocking};
+    /// # use coreminer::ui::cli::CliUi;
+    /// #
+    /// # let ui = CliUi::build(None).unwrap();
+    /// # let mut debugger = Debugger::build(ui).unwrap();
+    /// # // Assume debuggee is already running
+    /// #
+    /// // the other threads do not run while stepping
+    /// debugger.set_scheduler_locking(SchedulerLocking::Step).unwrap();
+    /// debugger.single_step().unwrap();
+    ///
+    /// # }}
+    /// ```
+    pub fn set_scheduler_locking(&mut self, locking: SchedulerLocking) -> Result<Feedback> {
+        info!("scheduler locking: {locking:?}");
+        self.scheduler_locking = locking;
+        Ok(Feedback::Ok)
+    }
+
+    /// Selects which process the debugger stays with when the debuggee forks
+    ///
+    /// New processes of the debuggee are always traced, so that they can be released without
+    /// the breakpoints they inherited. See [`FollowFork`] for the modes.
+    ///
+    /// # Parameters
+    ///
+    /// * `follow` - Which process to follow
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Feedback::Ok)` - The setting was applied
+    ///
+    /// # Errors
+    ///
+    /// Cannot fail.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #[cfg(feature = "cli")]
+    /// # mod featguard { fn _do_thing() {
+    /// # use coreminer::debugger::{Debugger, FollowFork};
+    /// # use coreminer::feedback::Feedback;
+    /// # use coreminer::ui::cli::CliUi;
+    /// #
+    /// # let ui = CliUi::build(None).unwrap();
+    /// # let mut debugger = Debugger::build(ui).unwrap();
+    /// #
+    /// debugger.set_follow_fork(FollowFork::Child).unwrap();
+    /// debugger.run("/bin/sh", &[c"-c".into(), c"/bin/true; exit 3".into()]).unwrap();
+    ///
+    /// // the debugger stops when the shell forks, and continues with the child
+    /// if let Ok(Feedback::ForkEvent(fork)) = debugger.cont() {
+    ///     assert_eq!(fork.followed, fork.child);
+    /// }
+    ///
+    /// # }}
+    /// ```
+    pub fn set_follow_fork(&mut self, follow: FollowFork) -> Result<Feedback> {
+        info!("follow fork: {follow:?}");
+        self.follow_fork = follow;
+        Ok(Feedback::Ok)
+    }
+
+    /// Resolves an [`AddrExpr`] to an address in the debuggee
+    ///
+    /// Symbols are searched like the anchors of a [`BreakpointFile`]: functions of the debug
+    /// information first, code symbols of the executable second, rebased to the load address
+    /// of the debuggee. Source lines are looked up in the line table of the debug information,
+    /// where the lowest address of the line is used if its code is in more than one place.
+    /// Registers are read from the debuggee when the expression is resolved, values are taken
+    /// from the [`ValueHistory`].
+    ///
+    /// # Parameters
+    ///
+    /// * `expr` - The address expression
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Addr)` - The address in the debuggee
+    /// * `Err(DebuggerError)` - If the expression could not be resolved
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if:
+    /// - The expression is not absolute and the debuggee is not running
+    /// - The symbol does not exist
+    /// - The register cannot be read
+    /// - The value or convenience variable does not exist
+    /// - There is no code at or after the line
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #[cfg(feature = "cli")]
+    /// # mod featguard { fn _do_thing() {
+    /// # use coreminer::debugger::Debugger;
+    /// # use coreminer::ui::cli::CliUi;
+    /// #
+    /// # let ui = CliUi::build(None).unwrap();
+    /// # let debugger = Debugger::build(ui).unwrap();
+    /// # // Assume debuggee is already running
+    /// #
+    /// let addr = debugger.resolve_addr(&"main+0x8".parse().unwrap()).unwrap();
+    /// let line = debugger.resolve_addr(&"hello.c:12".parse().unwrap()).unwrap();
+    /// let slot = debugger.resolve_addr(&"$rsp+8*3".parse().unwrap()).unwrap();
+    /// println!("main+0x8 is at {addr}, hello.c:12 at {line}, $rsp+8*3 at {slot}");
+    ///
+    /// # }}
+    /// ```
+    pub fn resolve_addr(&self, expr: &AddrExpr) -> Result<Addr> {
+        match expr {
+            AddrExpr::Absolute(addr) => Ok(*addr),
+            AddrExpr::Symbol { name, offset } => {
+                let anchor = BreakpointAnchor::Symbol {
+                    name: name.clone(),
+                    offset: 0,
+                };
+                let start = self
+                    .resolve_anchors(std::iter::once(&anchor))?
+                    .pop()
+                    .flatten()
+                    .ok_or_else(|| DebuggerError::UnknownSymbol(name.clone()))?;
+                Ok(Addr::from(
+                    start.usize().wrapping_add_signed(*offset as isize),
+                ))
+            }
+            AddrExpr::Register { reg, offset } => {
+                let value = self.get_reg(*reg)? as usize;
+                Ok(Addr::from(value.wrapping_add_signed(*offset as isize)))
+            }
+            AddrExpr::Value { name, offset } => {
+                let value = self
+                    .history
+                    .get(name)
+                    .ok_or_else(|| DebuggerError::UnknownValue(name.clone()))?;
+                Ok(Addr::from(value.wrapping_add_signed(*offset as isize)))
+            }
+            AddrExpr::Line { file, line } => {
+                let dbge = self.debuggee.as_ref().ok_or(DebuggerError::NoDebugee)?;
+                dbge.lines
+                    .addrs_of(file, *line)
+                    .first()
+                    .copied()
+                    .ok_or_else(|| DebuggerError::NoCodeAtLine(file.clone(), *line))
+            }
+        }
+    }
+
+    /// Records the value of `feedback` in the [`ValueHistory`]
+    ///
+    /// Words, addresses and variables that fit into a [`Word`] are recorded and wrapped into a
+    /// [`Feedback::Recorded`] with their number. Other feedback, like floating point variables,
+    /// structs, arrays or variables that were optimized out, is returned as it is.
+    fn record_value(&mut self, feedback: Feedback) -> Feedback {
+        let value = match &feedback {
+            Feedback::Word(w) => *w,
+            Feedback::Addr(addr) => addr.usize(),
+            Feedback::Variable(
+                VariableValue::Float(_)
+                | VariableValue::Struct { .. }
+                | VariableValue::Array(_)
+                | VariableValue::OptimizedOut,
+            ) => return feedback,
+            Feedback::Variable(VariableValue::Bytes(b)) if b.len() > WORD_BYTES => return feedback,
+            Feedback::Variable(v) => v.to_u64() as Word,
+            _ => return feedback,
+        };
+        let index = self.history.record(value);
+        Feedback::Recorded {
+            index,
+            feedback: Box::new(feedback),
+        }
+    }
+
+    /// Sets a convenience variable
+    ///
+    /// The expression is resolved once, the variable keeps its value when the registers or
+    /// values it was computed from change. Use it in later address expressions as `$name`.
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - The name of the variable, without the `$`
+    /// * `expr` - The address expression of the value
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Feedback::Addr)` - The value the variable was set to
+    /// * `Err(DebuggerError)` - If the variable could not be set
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if the name is not valid for a convenience variable or the
+    /// expression cannot be resolved, see [`Self::resolve_addr`].
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #[cfg(feature = "cli")]
+    /// # mod featguard { fn _do_thing() {
+    /// # use coreminer::debugger::Debugger;
+    /// # use coreminer::ui::cli::CliUi;
+    /// #
+    /// # let ui = CliUi::build(None).unwrap();
+    /// # let mut debugger = Debugger::build(ui).unwrap();
+    /// # // Assume debuggee is already running
+    /// #
+    /// debugger.set_convenience("frame", &"$rsp+0x20".parse().unwrap()).unwrap();
+    /// let slot = debugger.resolve_addr(&"$frame+8".parse().unwrap()).unwrap();
+    /// println!("the slot is at {slot}");
+    ///
+    /// # }}
+    /// ```
+    pub fn set_convenience(&mut self, name: &str, expr: &AddrExpr) -> Result<Feedback> {
+        let name = name.strip_prefix('$').unwrap_or(name);
+        let addr = self.resolve_addr(expr)?;
+        self.history.set_variable(name, addr.usize())?;
+        info!("${name} = {addr}");
+        Ok(Feedback::Addr(addr))
+    }
+
+    /// Sets the caps on expensive requests
+    ///
+    /// The [`Limits`] apply to the running debuggee and to every debuggee launched or attached
+    /// afterwards. Requests that would exceed them fail with [`DebuggerError::LimitExceeded`]
+    /// before anything is read, see the [limits module](crate::limits).
+    ///
+    /// # Parameters
+    ///
+    /// * `limits` - The new limits
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Feedback::Ok)` - The limits were applied
+    ///
+    /// # Errors
+    ///
+    /// Cannot fail.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #[cfg(feature = "cli")]
+    /// # mod featguard { fn _do_thing() {
+    /// # use coreminer::debugger::Debugger;
+    /// # use coreminer::ui::cli::CliUi;
+    /// # use coreminer::addr::Addr;
+    /// use coreminer::limits::Limits;
+    /// #
+    /// # let ui = CliUi::build(None).unwrap();
+    /// # let mut debugger = Debugger::build(ui).unwrap();
+    /// # // Assume debuggee is already running
+    /// #
+    /// debugger.set_limits(Limits {
+    ///     max_disassembly_len: 256,
+    ///     ..Default::default()
+    /// }).unwrap();
+    /// // fails with DebuggerError::LimitExceeded
+    /// assert!(debugger.disassemble_at(Addr::from(0x1000usize), 4096, false).is_err());
+    ///
+    /// # }}
+    /// ```
+    pub fn set_limits(&mut self, limits: Limits) -> Result<Feedback> {
+        info!("limits: {limits:?}");
+        self.limits = limits;
+        if let Some(dbge) = self.debuggee.as_mut() {
+            dbge.limits = limits;
+        }
+        Ok(Feedback::Ok)
+    }
+
+    /// Selects which writes into the memory of the debuggee are read back to check them
+    ///
+    /// By default, only the writes of software [Breakpoints](Breakpoint) are checked. The
+    /// setting applies to breakpoints that are set afterwards.
+    ///
+    /// # Parameters
+    ///
+    /// * `verification` - Which writes to check
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Feedback::Ok)` - The setting was applied
+    ///
+    /// # Errors
+    ///
+    /// Cannot fail.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #[cfg(feature = "cli")]
+    /// # mod featguard { fn _do_thing() {
+    /// # use coreminer::debugger::{Debugger, WriteVerification};
+    /// # use coreminer::ui::cli::CliUi;
+    /// # use coreminer::errors::DebuggerError;
+    /// # use coreminer::addr::Addr;
+    /// #
+    /// # let ui = CliUi::build(None).unwrap();
+    /// # let mut debugger = Debugger::build(ui).unwrap();
+    /// # // Assume debuggee is already running
+    /// #
+    /// debugger.set_write_verification(WriteVerification::all()).unwrap();
+    ///
+    /// if let Err(DebuggerError::WriteNotVerified(addr)) =
+    ///     debugger.write_mem(Addr::from(0x1000usize), 0x42)
+    /// {
+    ///     println!("the write was lost at {addr}");
+    /// }
+    ///
+    /// # }}
+    /// ```
+    pub fn set_write_verification(&mut self, verification: WriteVerification) -> Result<Feedback> {
+        info!("write verification: {verification:?}");
+        self.write_verification = verification;
+        if let Some(dbge) = self.debuggee.as_mut() {
+            dbge.verify_writes = verification.memory;
+        }
+        Ok(Feedback::Ok)
+    }
+
+    /// Detaches from the debuggee and leaves it running
+    ///
+    /// All [Breakpoints](Breakpoint) are removed first, so that the code of the debuggee is
+    /// restored. If the debuggee is stopped right after the `int3` of a software [`Breakpoint`],
+    /// the instruction pointer is moved back to the breakpoint, so that the original instruction
+    /// is executed. A signal the debuggee received last is delivered to it when it is detached.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Feedback::Ok)` - The debuggee was detached
+    /// * `Err(DebuggerError)` - If the debuggee could not be detached
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if:
+    /// - The debuggee is not running
+    /// - The breakpoints cannot be removed
+    /// - ptrace operations fail
+    ///
+    /// # Examples
+    ///
+    ///
+    /// ```no_run
+    /// #[cfg(feature = "cli")]
+    /// # mod featguard { fn _do_thing() {
+    /// # use coreminer::debugger::Debugger;
+    /// # use coreminer::ui::cli::CliUi;
+    /// #
+    /// # let ui = CliUi::build(None).unwrap();
+    /// # let mut debugger = Debugger::build(ui).unwrap();
+    /// # // Assume debuggee is already running
+    /// #
+    /// // Let the debuggee run on its own
+    /// debugger.detach().unwrap();
+    ///
+    /// # }}
+    /// ```
+    pub fn detach(&mut self) -> Result<Feedback> {
+        if self.is_post_mortem() {
+            info!("closing the core dump");
+            self.debuggee = None;
+            return Ok(Feedback::Ok);
+        }
+        let maybe_bp_addr = self
+            .get_current_addr()?
+            .usize()
+            .checked_sub(1)
+            .map(Addr::from);
+        let dbge = self.debuggee.as_mut().ok_or(DebuggerError::NoDebugee)?;
+        if let Some(bp_addr) = maybe_bp_addr.filter(|addr| {
+            dbge.breakpoints
+                .get(addr)
+                .is_some_and(|bp| bp.is_enabled() && !bp.is_hardware())
+        }) {
+            trace!("moving rip back to the breakpoint at {bp_addr}");
+            self.set_reg(Register::rip, bp_addr.into())?;
+        }
+        self.stop_threads()?;
+
+        let mut dbge = self.debuggee.take().ok_or(DebuggerError::NoDebugee)?;
+        // while the breakpoints are known, so they can be removed from the copies
+        dbge.release_held();
+        for bp in dbge.breakpoints.values_mut() {
+            if bp.is_enabled() {
+                bp.disable()?;
+            }
+        }
+        dbge.breakpoints.clear();
+        dbge.watchpoints.clear();
+        for guard in dbge.page_guards.values_mut() {
+            guard.disarm(dbge.pid)?;
+        }
+        dbge.page_guards.clear();
+
+        for (tid, thread) in &mut dbge.threads {
+            if *tid == dbge.pid {
+                continue;
+            }
+            trace!("detaching from thread {tid}");
+            match ptrace::detach(*tid, thread.pending_signal.take()) {
+                Ok(()) | Err(nix::errno::Errno::ESRCH) => (),
+                Err(e) => return Err(e.into()),
+            }
+        }
+        for t in std::fs::read_dir(format!("/proc/{}/task", dbge.pid))? {
+            let t = t?;
+            error!(
+                "DBG {:?} {}",
+                t.file_name(),
+                std::fs::read_to_string(t.path().join("stat")).unwrap_or_default()
+            );
+        }
+        info!("detaching from the debuggee {}", dbge.pid);
+        ptrace::detach(dbge.pid, self.take_last_status())?;
+        Ok(Feedback::Ok)
+    }
+
+    /// Stops all threads of the debuggee other than the main thread
+    ///
+    /// Running threads are stopped with a `SIGSTOP`. If a thread stopped at a software
+    /// [`Breakpoint`], its instruction pointer is moved back to the breakpoint. If a thread
+    /// stopped because of another signal, the signal is kept and delivered when the thread is
+    /// resumed or detached. Threads that exited are removed from the thread table.
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if a thread cannot be stopped or its registers cannot be accessed.
+    fn stop_threads(&mut self) -> Result<()> {
+        let dbge = self.debuggee.as_ref().ok_or(DebuggerError::NoDebugee)?;
+        let pid = dbge.pid;
+        let others: Vec<(Pid, ThreadState)> = dbge
+            .threads
+            .iter()
+            .filter(|(tid, _)| **tid != pid)
+            .map(|(tid, thread)| (*tid, thread.state))
+            .collect();
+
+        let mut outcomes = Vec::new();
+        for (tid, state) in others {
+            if state == ThreadState::Stopped {
+                continue;
+            }
+            let mut stop_sent = false;
+            // None if the thread is gone, else the signal that stopped it, if it should get it
+            let outcome: Option<Option<Signal>> = loop {
+                let flags = if stop_sent {
+                    WaitPidFlag::__WALL
+                } else {
+                    WaitPidFlag::WNOHANG | WaitPidFlag::__WALL
+                };
+                match waitpid(tid, Some(flags)) {
+                    Ok(WaitStatus::StillAlive) => {
+                        // SAFETY: tgkill only sends a signal to the thread
+                        let ret = unsafe {
+                            nix::libc::syscall(
+                                nix::libc::SYS_tgkill,
+                                pid.as_raw(),
+                                tid.as_raw(),
+                                nix::libc::SIGSTOP,
+                            )
+                        };
+                        nix::errno::Errno::result(ret)?;
+                        stop_sent = true;
+                    }
+                    Ok(WaitStatus::Stopped(_, Signal::SIGSTOP)) => break Some(None),
+                    Ok(WaitStatus::Stopped(_, Signal::SIGTRAP)) => {
+                        dbge.rewind_breakpoint_hit(tid)?;
+                        if !stop_sent {
+                            break Some(None);
+                        }
+                        // wait for the SIGSTOP, the breakpoint is hit again later
+                        ptrace::cont(tid, None)?;
+                    }
+                    Ok(WaitStatus::Stopped(_, sig)) => {
+                        if !stop_sent {
+                            break Some(Some(sig));
+                        }
+                        ptrace::cont(tid, sig)?;
+                    }
+                    Ok(WaitStatus::Exited(..) | WaitStatus::Signaled(..))
+                    | Err(nix::errno::Errno::ECHILD | nix::errno::Errno::ESRCH) => break None,
+                    Ok(other) => {
+                        trace!("thread {tid} stopped: {other:?}");
+                        if !stop_sent {
+                            break Some(None);
+                        }
+                        ptrace::cont(tid, None)?;
+                    }
+                    Err(e) => return Err(e.into()),
+                }
+            };
+            outcomes.push((tid, outcome));
+        }
+
+        let dbge = self.debuggee.as_mut().ok_or(DebuggerError::NoDebugee)?;
+        for (tid, outcome) in outcomes {
+            match (outcome, dbge.threads.get_mut(&tid)) {
+                (Some(sig), Some(thread)) => {
+                    thread.state = ThreadState::Stopped;
+                    thread.pending_signal = sig;
+                }
+                (None, _) => {
+                    trace!("thread {tid} is gone");
+                    dbge.threads.remove(&tid);
+                }
+                (Some(_), None) => (),
+            }
+        }
+        Ok(())
+    }
+
+    /// Sets a breakpoint at the specified address
+    ///
+    /// # Parameters
+    ///
+    /// * `addr` - The address to set the breakpoint at
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Feedback::Ok)` - If the breakpoint was set successfully
+    /// * `Err(DebuggerError)` - If there was an error setting the breakpoint
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if:
+    /// - The debuggee is not running
+    /// - The breakpoint could not be enabled
+    ///
+    /// # Examples
+    ///
+    ///
+    /// ```no_run
+    /// #[cfg(feature = "cli")]
+    /// # mod featguard { fn _do_thing() {
+    /// # use coreminer::debugger::Debugger;
+    /// # use coreminer::ui::cli::CliUi;
+    /// # use coreminer::addr::Addr;
+    /// #
+    /// # let ui = CliUi::build(None).unwrap();
+    /// # let mut debugger = Debugger::build(ui).unwrap();
+    /// # // Assume debuggee is already running
+    /// #
+    /// // Set a breakpoint at absolute address 0x1000
+    /// debugger.set_bp(Addr::from(0x1000usize)).unwrap();
+    ///
+    /// // Set a breakpoint at the program's entry point
+    /// let base = debugger.get_current_addr().unwrap();
+    /// debugger.set_bp(base).unwrap();
+    ///
+    /// // Set a breakpoint at the relative address 0x1000
+    /// let base = debugger.get_current_addr().unwrap();
+    /// debugger.set_bp(base + 0x1000).unwrap();
+    ///
+    /// # }}
+    /// ```
+    pub fn set_bp(&mut self, addr: Addr) -> Result<Feedback> {
+        let dbge = self.debuggee.as_mut().ok_or(DebuggerError::NoDebugee)?;
+        let mut bp = Breakpoint::new(dbge.pid, addr);
+        bp.set_verify(self.write_verification.breakpoints);
+        bp.enable()?;
+        dbge.breakpoints.insert(addr, bp);
+
+        Ok(Feedback::Ok)
+    }
+
+    /// Sets a hardware breakpoint at the specified address
+    ///
+    /// Unlike [`Self::set_bp`], this does not write `INT3` into the code of the debuggee, but
+    /// uses a free debug register of the processor. This is needed for code in memory that
+    /// cannot be written, or code that checks itself for modifications. Only
+    /// [`DEBUG_ADDR_REGISTERS`] hardware breakpoints can be set at the same time.
+    ///
+    /// When a hardware breakpoint is hit, the instruction pointer is at the breakpoint address,
+    /// and the instruction there was not executed yet. It is removed with [`Self::del_bp`], like
+    /// any other breakpoint.
+    ///
+    /// # Parameters
+    ///
+    /// * `addr` - The address to set the breakpoint at
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Feedback::Ok)` - If the breakpoint was set successfully
+    /// * `Err(DebuggerError)` - If there was an error setting the breakpoint
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if:
+    /// - The debuggee is not running
+    /// - All debug registers are used by other hardware breakpoints
+    /// - The debug registers could not be written
+    ///
+    /// # Examples
+    ///
+    ///
+    /// ```no_run
+    /// #[cfg(feature = "cli")]
+    /// # mod featguard { fn _do_thing() {
+    /// # use coreminer::debugger::Debugger;
+    /// # use coreminer::ui::cli::CliUi;
+    /// # use coreminer::addr::Addr;
+    /// #
+    /// # let ui = CliUi::build(None).unwrap();
+    /// # let mut debugger = Debugger::build(ui).unwrap();
+    /// # // Assume debuggee is already running
+    /// #
+    /// // Break at the relative address 0x1139 without modifying the code
+    /// let base = debugger.get_current_addr().unwrap();
+    /// debugger.set_hw_bp(base + 0x1139).unwrap();
+    ///
+    /// # }}
+    /// ```
+    pub fn set_hw_bp(&mut self, addr: Addr) -> Result<Feedback> {
+        let dbge = self.debuggee.as_mut().ok_or(DebuggerError::NoDebugee)?;
+        // replacing a breakpoint at the same address frees its debug register
+        if let Some(mut old) = dbge.breakpoints.remove(&addr) {
+            if old.is_enabled() {
+                old.disable()?;
+            }
+        }
+
+        let used: Vec<usize> = dbge
+            .breakpoints
+            .values()
+            .filter_map(Breakpoint::hardware_slot)
+            .collect();
+        let slot = (0..DEBUG_ADDR_REGISTERS)
+            .find(|slot| !used.contains(slot))
+            .ok_or(DebuggerError::NoFreeDebugRegister)?;
+
+        let mut bp = Breakpoint::new_hardware(dbge.pid, addr, slot);
+        bp.enable()?;
+        dbge.breakpoints.insert(addr, bp);
+        info!("set hardware breakpoint at {addr} in DR{slot}");
+
+        Ok(Feedback::Ok)
+    }
+
+    /// Removes a breakpoint at the specified address
+    ///
+    /// # Parameters
+    ///
+    /// * `addr` - The address to remove the breakpoint from
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Feedback::Ok)` - If the breakpoint was removed successfully
+    /// * `Err(DebuggerError)` - If there was an error removing the breakpoint
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if:
+    /// - The debuggee is not running
+    /// - The breakpoint could not be disabled
+    ///
+    /// # Examples
+    ///
+    ///
+    /// ```no_run
+    /// #[cfg(feature = "cli")]
+    /// # mod featguard { fn _do_thing() {
+    /// # use coreminer::debugger::Debugger;
+    /// # use coreminer::ui::cli::CliUi;
+    /// # use coreminer::addr::Addr;
+    /// #
+    /// # let ui = CliUi::build(None).unwrap();
+    /// # let mut debugger = Debugger::build(ui).unwrap();
+    /// # // Assume debuggee is already running
+    /// #
+    /// // Remove a breakpoint at address 0x1000
+    /// debugger.del_bp(Addr::from(0x1000usize)).unwrap();
+    ///
+    /// // Remove a breakpoint at the relative address 0x1000
+    /// let base = debugger.get_current_addr().unwrap();
+    /// debugger.del_bp(base + 0x1000).unwrap();
+    ///
+    /// # }}
+    /// ```
+    pub fn del_bp(&mut self, addr: Addr) -> Result<Feedback> {
+        let dbge = self.debuggee.as_mut().ok_or(DebuggerError::NoDebugee)?;
+
+        if let Some(_bp) = dbge.breakpoints.get_mut(&addr) {
+            dbge.breakpoints.remove(&addr); // gets disabled on dropping
+        } else {
+            warn!("removed a breakpoint at {addr:x?} that did not exist");
+        }
+
+        Ok(Feedback::Ok)
+    }
+
+    /// Enables a [`Breakpoint`] that was disabled with [`Self::disable_bp`] again
+    ///
+    /// A hardware breakpoint keeps its debug register while it is disabled, so enabling it
+    /// cannot fail because all debug registers are in use.
+    ///
+    /// # Parameters
+    ///
+    /// * `addr` - The address of the breakpoint
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Feedback::Ok)` - If the breakpoint was enabled
+    /// * `Err(DebuggerError)` - If there was an error enabling the breakpoint
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if:
+    /// - The debuggee is not running
+    /// - There is no breakpoint at `addr`
+    /// - The breakpoint is already enabled
+    /// - The breakpoint could not be written into the debuggee
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #[cfg(feature = "cli")]
+    /// # mod featguard { fn _do_thing() {
+    /// # use coreminer::debugger::Debugger;
+    /// # use coreminer::ui::cli::CliUi;
+    /// # use coreminer::addr::Addr;
+    /// #
+    /// # let ui = CliUi::build(None).unwrap();
+    /// # let mut debugger = Debugger::build(ui).unwrap();
+    /// # // Assume debuggee is already running
+    /// #
+    /// let addr = Addr::from(0x401136usize);
+    /// debugger.set_bp(addr).unwrap();
+    ///
+    /// // run past the breakpoint once
+    /// debugger.disable_bp(addr).unwrap();
+    /// debugger.cont().unwrap();
+    /// debugger.enable_bp(addr).unwrap();
+    ///
+    /// # }}
+    /// ```
+    pub fn enable_bp(&mut self, addr: Addr) -> Result<Feedback> {
+        let dbge = self.debuggee.as_mut().ok_or(DebuggerError::NoDebugee)?;
+        let bp = dbge
+            .breakpoints
+            .get_mut(&addr)
+            .ok_or(DebuggerError::NoBreakpoint(addr))?;
+        bp.enable()?;
+        info!("enabled breakpoint at {addr}");
+
+        Ok(Feedback::Ok)
+    }
+
+    /// Disables a [`Breakpoint`] without removing it
+    ///
+    /// A disabled breakpoint does not stop the debuggee, but it keeps its address and is listed
+    /// and saved with the other breakpoints. It can be enabled again with [`Self::enable_bp`].
+    ///
+    /// # Parameters
+    ///
+    /// * `addr` - The address of the breakpoint
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Feedback::Ok)` - If the breakpoint was disabled
+    /// * `Err(DebuggerError)` - If there was an error disabling the breakpoint
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if:
+    /// - The debuggee is not running
+    /// - There is no breakpoint at `addr`
+    /// - The breakpoint is already disabled
+    /// - The original code could not be written back into the debuggee
+    pub fn disable_bp(&mut self, addr: Addr) -> Result<Feedback> {
+        let dbge = self.debuggee.as_ref().ok_or(DebuggerError::NoDebugee)?;
+        let software = dbge
+            .breakpoints
+            .get(&addr)
+            .is_some_and(|bp| bp.is_enabled() && !bp.is_hardware());
+        if software && self.get_current_addr()?.checked_sub(1) == Some(addr) {
+            // the debuggee was stopped by the INT3, which is only gone back over while it is
+            // enabled, see Self::go_back_step_over_bp
+            self.set_reg(Register::rip, addr.into())?;
+        }
+
+        let dbge = self.debuggee.as_mut().ok_or(DebuggerError::NoDebugee)?;
+        let bp = dbge
+            .breakpoints
+            .get_mut(&addr)
+            .ok_or(DebuggerError::NoBreakpoint(addr))?;
+        bp.disable()?;
+        info!("disabled breakpoint at {addr}");
+
+        Ok(Feedback::Ok)
+    }
+
+    /// Makes the [`Breakpoint`] at `addr` a logging breakpoint, which does not stop the debuggee
+    ///
+    /// When a logging breakpoint is hit, the expressions of `log` are evaluated, the message is
+    /// sent to the UI as [`Feedback::Text`], and [`Self::cont`] continues the debuggee right away.
+    /// A [`Breakpoint`] is set first if there is none at `addr`. With `log` being [`None`], the
+    /// breakpoint stops the debuggee again.
+    ///
+    /// # Parameters
+    ///
+    /// * `addr` - The address of the breakpoint
+    /// * `log` - The message format, see [`crate::logpoint`]
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Feedback::Ok)` - If the message format was set
+    /// * `Err(DebuggerError)` - If there was an error setting the breakpoint
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if:
+    /// - The debuggee is not running
+    /// - A new breakpoint could not be set
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #[cfg(feature = "cli")]
+    /// # mod featguard { fn _do_thing() {
+    /// # use coreminer::debugger::Debugger;
+    /// # use coreminer::ui::cli::CliUi;
+    /// # use coreminer::addr::Addr;
+    /// #
+    /// # let ui = CliUi::build(None).unwrap();
+    /// # let mut debugger = Debugger::build(ui).unwrap();
+    /// # // Assume debuggee is already running
+    /// #
+    /// // Print the counter and the first argument every time the loop body runs
+    /// let addr = Addr::from(0x401136usize);
+    /// debugger
+    ///     .set_log_bp(addr, Some("i = {i}, rdi = {rdi}".parse().unwrap()))
+    ///     .unwrap();
+    /// debugger.cont().unwrap();
+    ///
+    /// # }}
+    /// ```
+    pub fn set_log_bp(&mut self, addr: Addr, log: Option<LogFormat>) -> Result<Feedback> {
+        let dbge = self.debuggee.as_ref().ok_or(DebuggerError::NoDebugee)?;
+        if !dbge.breakpoints.contains_key(&addr) {
+            if log.is_none() {
+                return Err(DebuggerError::NoBreakpoint(addr));
+            }
+            self.set_bp(addr)?;
+        }
+
+        let dbge = self.debuggee.as_mut().ok_or(DebuggerError::NoDebugee)?;
+        let bp = dbge
+            .breakpoints
+            .get_mut(&addr)
+            .ok_or(DebuggerError::NoBreakpoint(addr))?;
+        if let Some(log) = &log {
+            info!("breakpoint at {addr} logs \"{log}\"");
+        } else {
+            info!("breakpoint at {addr} stops again");
+        }
+        bp.set_log(log);
+
+        Ok(Feedback::Ok)
+    }
+
+    /// Saves all [Breakpoints](Breakpoint) into a file
+    ///
+    /// Each [`Breakpoint`] is saved with a [`BreakpointAnchor`] instead of its address, so that
+    /// it can be restored with [`Self::load_breakpoints`] in a later session, where the
+    /// executable and libraries are loaded at different addresses. The anchor is the function of
+    /// the debug information or the symbol of the executable that contains the [`Breakpoint`] if
+    /// there is one, and the offset into the mapped module otherwise.
+    ///
+    /// # Parameters
+    ///
+    /// * `path` - The file to write, see [`BreakpointFile`] for the format
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Feedback::Ok)` - If the breakpoints were saved
+    /// * `Err(DebuggerError)` - If the breakpoints could not be saved
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if:
+    /// - The debuggee is not running
+    /// - The memory map of the debuggee cannot be read
+    /// - The file cannot be written
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #[cfg(feature = "cli")]
+    /// # mod featguard { fn _do_thing() {
+    /// # use std::path::Path;
+    /// # use coreminer::debugger::Debugger;
+    /// # use coreminer::ui::cli::CliUi;
+    /// # use coreminer::addr::Addr;
+    /// #
+    /// # let ui = CliUi::build(None).unwrap();
+    /// # let mut debugger = Debugger::build(ui).unwrap();
+    /// # // Assume debuggee is already running
+    /// #
+    /// let rip = debugger.get_current_addr().unwrap();
+    /// debugger.set_bp(rip + 0x10).unwrap();
+    /// debugger.save_breakpoints(Path::new("breakpoints.json")).unwrap();
+    ///
+    /// # }}
+    /// ```
+    pub fn save_breakpoints(&self, path: &Path) -> Result<Feedback> {
+        let file = BreakpointFile {
+            breakpoints: self.saved_breakpoints()?,
+        };
+        file.write(path)?;
+        info!(
+            "saved {} breakpoints to {}",
+            file.breakpoints.len(),
+            path.display()
+        );
+        Ok(Feedback::Ok)
+    }
+
+    /// Sets the [Breakpoints](Breakpoint) saved in a file
+    ///
+    /// The [Breakpoint] anchors of the file written by [`Self::save_breakpoints`] are resolved
+    /// against the current load addresses of the debuggee. A [`BreakpointAnchor::Module`] can only
+    /// be resolved once the module is loaded, so breakpoints in shared libraries should be loaded
+    /// after the dynamic loader has run, for example at `main`. Anchors that cannot be resolved
+    /// and addresses that already have a [`Breakpoint`] are skipped with a warning.
+    ///
+    /// # Parameters
+    ///
+    /// * `path` - The file to read, see [`BreakpointFile`] for the format
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Feedback::Ok)` - If the file was loaded
+    /// * `Err(DebuggerError)` - If the file could not be loaded
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if:
+    /// - The debuggee is not running
+    /// - The file cannot be read or is not a valid breakpoint file
+    /// - A [`Breakpoint`] cannot be set, for example because all debug registers are in use
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #[cfg(feature = "cli")]
+    /// # mod featguard { fn _do_thing() {
+    /// # use std::path::Path;
+    /// # use coreminer::debugger::{Debugger, LaunchBreak};
+    /// # use coreminer::ui::cli::CliUi;
+    /// #
+    /// # let ui = CliUi::build(None).unwrap();
+    /// # let mut debugger = Debugger::build(ui).unwrap();
+    /// debugger.set_launch_break(Some(LaunchBreak::Main)).unwrap();
+    /// debugger.run(Path::new("./target/debug/dummy"), &[]).unwrap();
+    /// debugger.load_breakpoints(Path::new("breakpoints.json")).unwrap();
+    /// debugger.cont().unwrap();
+    ///
+    /// # }}
+    /// ```
+    pub fn load_breakpoints(&mut self, path: &Path) -> Result<Feedback> {
+        let file = BreakpointFile::read(path)?;
+        let loaded = self.restore_breakpoints(&file.breakpoints)?;
+        info!(
+            "loaded {loaded} of {} breakpoints from {}",
+            file.breakpoints.len(),
+            path.display()
+        );
+        Ok(Feedback::Ok)
+    }
+
+    /// Describes all [Breakpoints](Breakpoint) of the debuggee with [Anchors](BreakpointAnchor)
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if the debuggee is not running or its memory map cannot be read.
+    fn saved_breakpoints(&self) -> Result<Vec<SavedBreakpoint>> {
+        let dbge = self.debuggee.as_ref().ok_or(DebuggerError::NoDebugee)?;
+        let map = dbge.get_process_map()?;
+        let obj = object::File::parse(&*self.executable_data).ok();
+
+        let mut breakpoints: Vec<(&Addr, &Breakpoint)> = dbge.breakpoints.iter().collect();
+        breakpoints.sort_by_key(|(addr, _)| **addr);
+
+        let mut saved = Vec::with_capacity(breakpoints.len());
+        for (&addr, bp) in breakpoints {
+            let anchor = self.anchor_of(addr, &map, obj.as_ref())?;
+            debug!("saving breakpoint at {addr} as {anchor}");
+            saved.push(SavedBreakpoint {
+                anchor,
+                hardware: bp.is_hardware(),
+                disabled: !bp.is_enabled(),
+                log: bp.log().cloned(),
+            });
+        }
+        Ok(saved)
+    }
+
+    /// Sets saved [Breakpoints](Breakpoint) at the addresses their anchors resolve to
+    ///
+    /// # Returns
+    ///
+    /// The number of [Breakpoints](Breakpoint) that were set, see [`Self::load_breakpoints`] for
+    /// which are skipped.
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if the debuggee is not running or a [`Breakpoint`] cannot be set.
+    fn restore_breakpoints(&mut self, saved: &[SavedBreakpoint]) -> Result<usize> {
+        let addrs = self.resolve_anchors(saved.iter().map(|bp| &bp.anchor))?;
+
+        let mut loaded = 0;
+        for (addr, saved) in addrs.into_iter().zip(saved) {
+            let Some(addr) = addr else {
+                warn!(
+                    "could not find {} in the debuggee, skipping it",
+                    saved.anchor
+                );
+                continue;
+            };
+            let dbge = self.debuggee.as_ref().ok_or(DebuggerError::NoDebugee)?;
+            if dbge.breakpoints.contains_key(&addr) {
+                warn!(
+                    "there already is a breakpoint at {addr}, skipping {}",
+                    saved.anchor
+                );
+                continue;
+            }
+
+            debug!("restoring breakpoint {} at {addr}", saved.anchor);
+            if saved.hardware {
+                self.set_hw_bp(addr)?;
+            } else {
+                self.set_bp(addr)?;
+            }
+            if saved.disabled {
+                self.disable_bp(addr)?;
+            }
+            if saved.log.is_some() {
+                self.set_log_bp(addr, saved.log.clone())?;
+            }
+            loaded += 1;
+        }
+        Ok(loaded)
+    }
+
+    /// Creates a [`BreakpointAnchor`] for an address in the debuggee
+    ///
+    /// The function of the debug information or the code symbol of the executable containing
+    /// `addr` is preferred, then the mapped module containing it.
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if the debuggee is not running or its auxiliary vector cannot be
+    /// read.
+    fn anchor_of(
+        &self,
+        addr: Addr,
+        map: &ProcessMemoryMap,
+        obj: Option<&object::File<'_>>,
+    ) -> Result<BreakpointAnchor> {
+        let dbge = self.debuggee.as_ref().ok_or(DebuggerError::NoDebugee)?;
+        if let Some(function) = dbge.get_function_by_addr(addr)? {
+            if let (Some(name), Some(low)) = (function.name(), function.low_addr()) {
+                return Ok(BreakpointAnchor::Symbol {
+                    name: name.to_string(),
+                    offset: (addr - low).u64(),
+                });
+            }
+        }
+        if let Some(obj) = obj {
+            if let Some((name, offset)) = self
+                .executable()?
+                .to_module(addr)
+                .and_then(|file_addr| bpfile::symbol_containing(obj, file_addr.offset))
+            {
+                return Ok(BreakpointAnchor::Symbol { name, offset });
+            }
+        }
+        Ok(bpfile::module_anchor(map, addr).unwrap_or(BreakpointAnchor::Absolute(addr)))
+    }
+
+    /// Resolves [Anchors](BreakpointAnchor) to addresses in the debuggee
+    ///
+    /// Symbols are searched in the debug information first, in the symbol table of the
+    /// executable second and in the symbol tables of the
+    /// [added symbol files](Self::add_symbol_file) third.
+    ///
+    /// # Returns
+    ///
+    /// For each anchor, the address it describes, or [`None`] if the symbol or module was not
+    /// found.
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if the debuggee is not running or its memory map or auxiliary
+    /// vector cannot be read.
+    fn resolve_anchors<'a>(
+        &self,
+        anchors: impl Iterator<Item = &'a BreakpointAnchor>,
+    ) -> Result<Vec<Option<Addr>>> {
+        let dbge = self.debuggee.as_ref().ok_or(DebuggerError::NoDebugee)?;
+        let map = dbge.get_process_map()?;
+        let obj = object::File::parse(&*self.executable_data).ok();
+
+        // only built when a symbol is neither in the debug information nor the symbol table
+        let mut heuristics: Option<HeuristicSymbols> = None;
+
+        let mut addrs = Vec::new();
+        for anchor in anchors {
+            addrs.push(match anchor {
+                BreakpointAnchor::Symbol { name, offset } => {
+                    let function = dbge
+                        .get_symbol_by_name(name)?
+                        .into_iter()
+                        .filter(|s| s.kind() == SymbolKind::Function)
+                        .find_map(|s| s.low_addr());
+                    let file_addr = obj
+                        .as_ref()
+                        .and_then(|obj| bpfile::symbol_address(obj, name));
+                    match (function, file_addr) {
+                        (Some(low), _) => Some(low + *offset as usize),
+                        (None, Some(file_addr)) => {
+                            Some(self.executable()?.to_process(file_addr + *offset))
+                        }
+                        (None, None) => {
+                            if let Some(addr) = dbge.symbol_file_address(name) {
+                                Some(addr + *offset as usize)
+                            } else {
+                                if heuristics.is_none() {
+                                    heuristics = Some(self.heuristic_symbols()?);
+                                }
+                                heuristics
+                                    .as_ref()
+                                    .and_then(|h| h.by_name(name))
+                                    .map(|s| s.addr + *offset as usize)
+                            }
+                        }
+                    }
+                }
+                BreakpointAnchor::Module { path, offset } => {
+                    bpfile::module_base(&map, path).map(|base| base + *offset as usize)
+                }
+                BreakpointAnchor::Absolute(addr) => Some(*addr),
+            });
+        }
+        Ok(addrs)
+    }
+
+    /// Saves the configuration of the debugger into a session file
+    ///
+    /// The [`Session`] contains the [`Settings`], all [Breakpoints](Breakpoint) and
+    /// [Watchpoints](Watchpoint), the expressions of [`Self::add_auto_display`] and the status of
+    /// the plugins. It can be restored with
+    /// [`Self::restore_session`], also after restarting the debugger and the debuggee.
+    /// [Breakpoints](Breakpoint) and [Watchpoints](Watchpoint) are saved with
+    /// [Anchors](BreakpointAnchor) like in [`Self::save_breakpoints`].
+    ///
+    /// Without a debuggee, only the settings, the displayed expressions and the plugins are saved.
+    ///
+    /// The [`ValueHistory`] and its convenience variables are intentionally not saved. They hold
+    /// addresses and values of one run of the debuggee, which mean nothing in the next one, where
+    /// everything is loaded at different addresses.
+    ///
+    /// # Parameters
+    ///
+    /// * `path` - The file to write, see [`Session`] for the format
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Feedback::Ok)` - If the session was saved
+    /// * `Err(DebuggerError)` - If the session could not be saved
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if:
+    /// - The memory map of the debuggee cannot be read
+    /// - The file cannot be written
+    ///
+    /// # Panics
+    ///
+    /// This method will panic if it cannot acquire a lock on the plugin manager.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #[cfg(feature = "cli")]
+    /// # mod featguard { fn _do_thing() {
+    /// # use std::path::Path;
+    /// # use coreminer::debugger::Debugger;
+    /// # use coreminer::ui::cli::CliUi;
+    /// #
+    /// # let ui = CliUi::build(None).unwrap();
+    /// # let mut debugger = Debugger::build(ui).unwrap();
+    /// # // Assume debuggee is already running
+    /// debugger.catch_syscalls(true).unwrap();
+    /// debugger.save_session(Path::new("session.json")).unwrap();
+    ///
+    /// # }}
+    /// ```
+    pub fn save_session(&self, path: &Path) -> Result<Feedback> {
+        let mut session = Session {
+            settings: Settings {
+                anti_debug: self.anti_debug.config,
+                catch_syscalls: self.catch_syscalls,
+                syscall_filter: self.syscall_filter.as_ref().map(|filter| {
+                    filter
+                        .syscalls()
+                        .iter()
+                        .map(|nr| {
+                            syscall::syscall_name(*nr)
+                                .map_or_else(|| nr.to_string(), str::to_string)
+                        })
+                        .collect()
+                }),
+                catch_memory: self.catch_memory,
+                catch_panics: self.catch_panics,
+                launch_break: self.launch_break,
+                launch_env: self.launch_env.clone(),
+                write_verification: self.write_verification,
+                ptrace_options: self.ptrace_options,
+                quit_action: self.quit_action,
+                scheduler_locking: self.scheduler_locking,
+                follow_fork: self.follow_fork,
+                limits: self.limits,
+            },
+            displays: self.auto_displays.values().cloned().collect(),
+            ..Default::default()
+        };
+
+        if let Some(dbge) = &self.debuggee {
+            session.breakpoints = self.saved_breakpoints()?;
+
+            let map = dbge.get_process_map()?;
+            let obj = object::File::parse(&*self.executable_data).ok();
+            let mut watchpoints: Vec<&Watchpoint> = dbge.watchpoints.values().collect();
+            watchpoints.sort_by_key(|wp| wp.addr());
+            for wp in watchpoints {
+                session.watchpoints.push(SavedWatchpoint {
+                    anchor: self.anchor_of(wp.addr(), &map, obj.as_ref())?,
+                    len: wp.len(),
+                    condition: wp.condition().copied(),
+                });
+            }
+        } else if let Some(pending) = &self.pending_session {
+            // not restored yet, so they should not get lost
+            session.breakpoints.clone_from(&pending.breakpoints);
+            session.watchpoints.clone_from(&pending.watchpoints);
+        }
+
+        #[cfg(feature = "plugins")]
+        {
+            session.plugins = self
+                .plugins
+                .lock()
+                .expect("could not lock plugin_manager")
+                .plugins()
+                .iter()
+                .map(|plugin| (plugin.id().into(), plugin.is_enabled()))
+                .collect();
+        }
+
+        session.write(path)?;
+        info!("saved the session to {}", path.display());
+        Ok(Feedback::Ok)
+    }
+
+    /// Restores the configuration of the debugger from a session file
+    ///
+    /// The [`Settings`], the displayed expressions and the status of the plugins are applied
+    /// right away. The [Breakpoints](Breakpoint) and [Watchpoints](Watchpoint) are set in the
+    /// running debuggee, or, if there is none, in the next debuggee that is launched with
+    /// [`Self::run`], once it is stopped at the [`LaunchBreak`]. Their anchors are resolved like in
+    /// [`Self::load_breakpoints`], and those that cannot be resolved are skipped with a warning.
+    ///
+    /// # Parameters
+    ///
+    /// * `path` - The file to read, see [`Session`] for the format
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Feedback::Ok)` - If the session was restored
+    /// * `Err(DebuggerError)` - If the session could not be restored
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if:
+    /// - The file cannot be read or is not a valid session file
+    /// - A setting cannot be applied to the debuggee
+    /// - A [`Breakpoint`] or [`Watchpoint`] cannot be set
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #[cfg(feature = "cli")]
+    /// # mod featguard { fn _do_thing() {
+    /// # use std::path::Path;
+    /// # use coreminer::debugger::Debugger;
+    /// # use coreminer::ui::cli::CliUi;
+    /// #
+    /// # let ui = CliUi::build(None).unwrap();
+    /// # let mut debugger = Debugger::build(ui).unwrap();
+    /// debugger.restore_session(Path::new("session.json")).unwrap();
+    /// // the breakpoints of the session are set once the program was launched
+    /// debugger.run(Path::new("./target/debug/dummy"), &[]).unwrap();
+    /// debugger.cont().unwrap();
+    ///
+    /// # }}
+    /// ```
+    pub fn restore_session(&mut self, path: &Path) -> Result<Feedback> {
+        let session = Session::read(path)?;
+        let settings = session.settings.clone();
+
+        self.set_anti_debug(settings.anti_debug)?;
+        let syscall_filter = match &settings.syscall_filter {
+            Some(names) => Some(SyscallFilter::new(
+                names
+                    .iter()
+                    .map(|name| {
+                        syscall::parse_syscall(name)
+                            .ok_or_else(|| DebuggerError::UnknownSyscall(name.clone()))
+                    })
+                    .collect::<Result<Vec<u64>>>()?,
+            )),
+            None => None,
+        };
+        self.set_syscall_filter(syscall_filter)?;
+        self.catch_syscalls(settings.catch_syscalls)?;
+        self.catch_memory(settings.catch_memory)?;
+        self.catch_panics(settings.catch_panics)?;
+        self.set_launch_break(settings.launch_break)?;
+        self.launch_env = settings.launch_env;
+        self.set_write_verification(settings.write_verification)?;
+        self.set_ptrace_options(settings.ptrace_options)?;
+        self.set_quit_action(settings.quit_action)?;
+        self.set_scheduler_locking(settings.scheduler_locking)?;
+        self.set_follow_fork(settings.follow_fork)?;
+        self.set_limits(settings.limits)?;
+
+        for display in &session.displays {
+            if !self.auto_displays.values().any(|shown| shown == display) {
+                self.add_auto_display(display.clone())?;
+            }
+        }
+
+        #[cfg(feature = "plugins")]
+        for (id, enabled) in &session.plugins {
+            if let Err(e) = self.plugin_set_enable(id, *enabled) {
+                warn!("could not restore the status of the plugin {id}: {e}");
+            }
+        }
+
+        if self.debuggee.is_some() {
+            self.restore_points(&session)?;
+        } else {
+            debug!("no debuggee yet, restoring breakpoints and watchpoints after the next run");
+            self.pending_session = Some(session);
+        }
+        info!("restored the session from {}", path.display());
+        Ok(Feedback::Ok)
+    }
+
+    /// Sets the [Breakpoints](Breakpoint) and [Watchpoints](Watchpoint) of a [`Session`]
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if the debuggee is not running or a [`Breakpoint`] or
+    /// [`Watchpoint`] cannot be set.
+    fn restore_points(&mut self, session: &Session) -> Result<()> {
+        let breakpoints = self.restore_breakpoints(&session.breakpoints)?;
+
+        let addrs = self.resolve_anchors(session.watchpoints.iter().map(|wp| &wp.anchor))?;
+        let mut watchpoints = 0;
+        for (addr, saved) in addrs.into_iter().zip(&session.watchpoints) {
+            let Some(addr) = addr else {
+                warn!(
+                    "could not find {} in the debuggee, skipping it",
+                    saved.anchor
+                );
+                continue;
+            };
+            debug!("restoring watchpoint {} at {addr}", saved.anchor);
+            self.set_watchpoint(addr, saved.len)?;
+            if saved.condition.is_some() {
+                self.set_watch_condition(addr, saved.condition)?;
+            }
+            watchpoints += 1;
+        }
+
+        info!(
+            "restored {breakpoints} of {} breakpoints and {watchpoints} of {} watchpoints",
+            session.breakpoints.len(),
+            session.watchpoints.len()
+        );
+        Ok(())
+    }
+
+    /// Watches a memory range for changes
+    ///
+    /// Sets a [`Watchpoint`] (software data breakpoint) on the memory range starting at `addr`
+    /// with a length of `len` bytes. While at least one [`Watchpoint`] is set, [`Self::cont`]
+    /// single steps the debuggee and stops as soon as a watched memory range changes, returning
+    /// [`Feedback::WatchpointHit`] with the address of the responsible instruction and the old and
+    /// new contents of the range. [`Self::single_step`] reports changes in the same way.
+    ///
+    /// Setting a [`Watchpoint`] for an address that is already watched replaces the old one.
+    ///
+    /// Note: Single stepping is slow, so execution with [Watchpoints](Watchpoint) will be
+    /// noticeably slower.
+    ///
+    /// # Parameters
+    ///
+    /// * `addr` - The start address of the memory range
+    /// * `len` - The length of the memory range in bytes
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Feedback::Ok)` - If the watchpoint was set successfully
+    /// * `Err(DebuggerError)` - If there was an error setting the watchpoint
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if:
+    /// - The debuggee is not running
+    /// - The memory range cannot be read
+    ///
+    /// # Examples
+    ///
+    ///
+    /// ```no_run
+    /// #[cfg(feature = "cli")]
+    /// # mod featguard { fn _do_thing() {
+    /// # use coreminer::debugger::Debugger;
+    /// # use coreminer::ui::cli::CliUi;
+    /// # use coreminer::addr::Addr;
+    /// # use coreminer::feedback::Feedback;
+    /// #
+    /// # let ui = CliUi::build(None).unwrap();
+    /// # let mut debugger = Debugger::build(ui).unwrap();
+    /// # // Assume debuggee is already running
+    /// #
+    /// // Watch a 32 byte buffer at 0x7ffd2b4a1f30
+    /// debugger.set_watchpoint(Addr::from(0x7ffd2b4a1f30usize), 32).unwrap();
+    ///
+    /// if let Ok(Feedback::WatchpointHit(hit)) = debugger.cont() {
+    ///     println!("buffer was changed by the instruction at {}", hit.instruction);
+    /// }
+    ///
+    /// # }}
+    /// ```
+    pub fn set_watchpoint(&mut self, addr: Addr, len: usize) -> Result<Feedback> {
+        let dbge = self.debuggee.as_mut().ok_or(DebuggerError::NoDebugee)?;
+        let wp = Watchpoint::new(dbge.pid, addr, len)?;
+        dbge.watchpoints.insert(addr, wp);
+
+        Ok(Feedback::Ok)
+    }
+
+    /// Removes the watchpoint for the memory range starting at the specified address
+    ///
+    /// # Parameters
+    ///
+    /// * `addr` - The start address of the watched memory range
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Feedback::Ok)` - If the watchpoint was removed successfully
+    /// * `Err(DebuggerError)` - If there was an error removing the watchpoint
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if:
+    /// - The debuggee is not running
+    ///
+    /// # Examples
+    ///
+    ///
+    /// ```no_run
+    /// #[cfg(feature = "cli")]
+    /// # mod featguard { fn _do_thing() {
+    /// # use coreminer::debugger::Debugger;
+    /// # use coreminer::ui::cli::CliUi;
+    /// # use coreminer::addr::Addr;
+    /// #
+    /// # let ui = CliUi::build(None).unwrap();
+    /// # let mut debugger = Debugger::build(ui).unwrap();
+    /// # // Assume debuggee is already running
+    /// #
+    /// // Stop watching the buffer at 0x7ffd2b4a1f30
+    /// debugger.del_watchpoint(Addr::from(0x7ffd2b4a1f30usize)).unwrap();
+    ///
+    /// # }}
+    /// ```
+    pub fn del_watchpoint(&mut self, addr: Addr) -> Result<Feedback> {
+        let dbge = self.debuggee.as_mut().ok_or(DebuggerError::NoDebugee)?;
+
+        if dbge.watchpoints.remove(&addr).is_none() {
+            warn!("removed a watchpoint at {addr:x?} that did not exist");
+        }
+
+        Ok(Feedback::Ok)
+    }
+
+    /// Sets or removes the condition of the watchpoint or page guard at the specified address
+    ///
+    /// A change of the watched memory range is only reported as [`Feedback::WatchpointHit`] if
+    /// the [`WatchCondition`] holds for it. Otherwise, the debuggee silently continues, and the
+    /// change becomes the base for the next one. This makes it possible to stop only when, for
+    /// example, a counter reaches zero, without stopping for every other change.
+    ///
+    /// # Parameters
+    ///
+    /// * `addr` - The start address of the watched memory range
+    /// * `condition` - The condition, or `None` to report every change again
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Feedback::Ok)` - If the condition was set successfully
+    /// * `Err(DebuggerError)` - If there was an error setting the condition
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if:
+    /// - The debuggee is not running
+    /// - There is no [`Watchpoint`] or [`PageGuard`] at `addr`
+    /// - The watched memory range is too wide to be compared, see
+    ///   [`MAX_CONDITION_WIDTH`](crate::watchpoint::MAX_CONDITION_WIDTH)
+    ///
+    /// # Examples
+    ///
+    ///
+    /// ```no_run
+    /// #[cfg(feature = "cli")]
+    /// # mod featguard { fn _do_thing() {
+    /// # use coreminer::debugger::Debugger;
+    /// # use coreminer::ui::cli::CliUi;
+    /// # use coreminer::addr::Addr;
+    /// # use coreminer::feedback::Feedback;
+    /// #
+    /// # let ui = CliUi::build(None).unwrap();
+    /// # let mut debugger = Debugger::build(ui).unwrap();
+    /// # // Assume debuggee is already running
+    /// #
+    /// // Stop once the 4 byte counter at 0x7ffd2b4a1f30 drops to zero
+    /// let counter = Addr::from(0x7ffd2b4a1f30usize);
+    /// debugger.set_watchpoint(counter, 4).unwrap();
+    /// debugger
+    ///     .set_watch_condition(counter, Some("new == 0".parse().unwrap()))
+    ///     .unwrap();
+    ///
+    /// if let Ok(Feedback::WatchpointHit(hit)) = debugger.cont() {
+    ///     println!("the counter was zeroed by the instruction at {}", hit.instruction);
+    /// }
+    ///
+    /// # }}
+    /// ```
+    pub fn set_watch_condition(
+        &mut self,
+        addr: Addr,
+        condition: Option<WatchCondition>,
+    ) -> Result<Feedback> {
+        let dbge = self.debuggee.as_mut().ok_or(DebuggerError::NoDebugee)?;
+
+        if let Some(wp) = dbge.watchpoints.get_mut(&addr) {
+            wp.set_condition(condition)?;
+        } else if let Some(guard) = dbge.page_guards.get_mut(&addr) {
+            guard.set_condition(condition)?;
+        } else {
+            return Err(DebuggerError::NoWatchpoint(addr));
+        }
+        if let Some(cond) = condition {
+            info!("changes at {addr} are only reported if {cond}");
+        } else {
+            info!("all changes at {addr} are reported");
+        }
+
+        Ok(Feedback::Ok)
+    }
+
+    /// Watches a large memory range for changes by write protecting its pages
+    ///
+    /// Sets a [`PageGuard`] on the memory range starting at `addr` with a length of `len` bytes.
+    /// Unlike with a [`Watchpoint`], the debuggee keeps running at full speed with [`Self::cont`].
+    /// Writes into the pages of the range are caught, and if one of them changed the range,
+    /// [`Feedback::WatchpointHit`] is returned, like for a [`Watchpoint`]. See the
+    /// [pageguard module](crate::pageguard) for the limits of this approach.
+    ///
+    /// Setting a [`PageGuard`] for an address that is already guarded replaces the old one.
+    ///
+    /// # Parameters
+    ///
+    /// * `addr` - The start address of the memory range
+    /// * `len` - The length of the memory range in bytes
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Feedback::Ok)` - If the page guard was set successfully
+    /// * `Err(DebuggerError)` - If there was an error setting the page guard
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if:
+    /// - The debuggee is not running
+    /// - The memory range is empty or not writable
+    /// - The memory range cannot be read
+    /// - The protection of the pages cannot be changed
+    ///
+    /// # Examples
+    ///
+    ///
+    /// ```no_run
+    /// #[cfg(feature = "cli")]
+    /// # mod featguard { fn _do_thing() {
+    /// # use coreminer::debugger::Debugger;
+    /// # use coreminer::ui::cli::CliUi;
+    /// # use coreminer::addr::Addr;
+    /// # use coreminer::feedback::Feedback;
+    /// #
+    /// # let ui = CliUi::build(None).unwrap();
+    /// # let mut debugger = Debugger::build(ui).unwrap();
+    /// # // Assume debuggee is already running
+    /// #
+    /// // Watch a 4 MiB heap buffer at 0x7f3a1c000010
+    /// debugger.set_page_guard(Addr::from(0x7f3a1c000010usize), 4 << 20).unwrap();
+    ///
+    /// if let Ok(Feedback::WatchpointHit(hit)) = debugger.cont() {
+    ///     println!("buffer was changed by the instruction at {}", hit.instruction);
+    /// }
+    ///
+    /// # }}
+    /// ```
+    pub fn set_page_guard(&mut self, addr: Addr, len: usize) -> Result<Feedback> {
+        self.del_page_guard(addr)?;
+
+        let dbge = self.debuggee.as_mut().ok_or(DebuggerError::NoDebugee)?;
+        let map = dbge.get_process_map()?;
+        let mut guard = PageGuard::new(dbge.pid, addr, len, &map)?;
+        guard.arm(dbge.pid)?;
+        dbge.page_guards.insert(addr, guard);
+
+        Ok(Feedback::Ok)
+    }
+
+    /// Removes the page guard for the memory range starting at the specified address
+    ///
+    /// The original protection of the guarded pages is restored.
+    ///
+    /// # Parameters
+    ///
+    /// * `addr` - The start address of the guarded memory range
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Feedback::Ok)` - If the page guard was removed successfully
+    /// * `Err(DebuggerError)` - If there was an error removing the page guard
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if:
+    /// - The debuggee is not running
+    /// - The protection of the pages cannot be changed
+    pub fn del_page_guard(&mut self, addr: Addr) -> Result<Feedback> {
+        let dbge = self.debuggee.as_mut().ok_or(DebuggerError::NoDebugee)?;
+        let Some(mut guard) = dbge.page_guards.remove(&addr) else {
+            trace!("no page guard at {addr} to remove");
+            return Ok(Feedback::Ok);
+        };
+
+        // other guards might share pages with this one, so they are armed again afterwards
+        for other in dbge.page_guards.values_mut() {
+            other.disarm(dbge.pid)?;
+        }
+        guard.disarm(dbge.pid)?;
+        for other in dbge.page_guards.values_mut() {
+            other.arm(dbge.pid)?;
+        }
+
+        Ok(Feedback::Ok)
+    }
+
+    /// Configures which anti-debugging tricks of the debuggee should be defeated
+    ///
+    /// If any countermeasure is enabled, [`Self::cont`] intercepts the syscalls of the debuggee
+    /// to hide the debugger from it. See the [antidebug module](crate::antidebug) for what is
+    /// done and what the limits are. This makes continuing noticeably slower for programs that
+    /// perform many syscalls.
+    ///
+    /// The config can be set before a debuggee is started and is kept between debuggees.
+    ///
+    /// # Parameters
+    ///
+    /// * `config` - The countermeasures to enable
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Feedback::Ok)` - The config was applied
+    ///
+    /// # Errors
+    ///
+    /// Cannot fail.
+    ///
+    /// # Examples
+    ///
+    ///
+    /// ```no_run
+    /// #[cfg(feature = "cli")]
+    /// # mod featguard { fn _do_thing() {
+    /// # use coreminer::debugger::Debugger;
+    /// # use coreminer::ui::cli::CliUi;
+    /// use coreminer::antidebug::AntiDebugConfig;
+    /// #
+    /// # let ui = CliUi::build(None).unwrap();
+    /// # let mut debugger = Debugger::build(ui).unwrap();
+    /// #
+    /// // Hide the debugger from the debuggee as well as possible
+    /// debugger.set_anti_debug(AntiDebugConfig::all()).unwrap();
+    ///
+    /// // Only fake successful PTRACE_TRACEME calls
+    /// debugger.set_anti_debug(AntiDebugConfig {
+    ///     fake_traceme: true,
+    ///     ..Default::default()
+    /// }).unwrap();
+    ///
+    /// # }}
+    /// ```
+    pub fn set_anti_debug(&mut self, config: AntiDebugConfig) -> Result<Feedback> {
+        info!("anti debug config: {config:?}");
+        self.anti_debug.config = config;
+        Ok(Feedback::Ok)
+    }
+
+    /// Configures the ptrace options of the debuggee
+    ///
+    /// The options are applied to the running debuggee right away, and to every debuggee that is
+    /// launched with [`Self::run`] afterwards. See the [traceopts module](crate::traceopts) for
+    /// what can be configured.
+    ///
+    /// # Parameters
+    ///
+    /// * `options` - The ptrace options to use
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Feedback::Ok)` - The options were applied
+    /// * `Err(DebuggerError)` - If the options could not be applied to the running debuggee
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if `PTRACE_SETOPTIONS` fails for the running debuggee.
+    ///
+    /// # Examples
+    ///
+    ///
+    /// ```no_run
+    /// #[cfg(feature = "cli")]
+    /// # mod featguard { fn _do_thing() {
+    /// # use coreminer::debugger::Debugger;
+    /// # use coreminer::ui::cli::CliUi;
+    /// use coreminer::traceopts::PtraceOptions;
+    /// #
+    /// # let ui = CliUi::build(None).unwrap();
+    /// # let mut debugger = Debugger::build(ui).unwrap();
+    /// #
+    /// // Make sure the debuggee does not outlive the debugger
+    /// debugger.set_ptrace_options(PtraceOptions {
+    ///     exit_kill: true,
+    ///     ..Default::default()
+    /// }).unwrap();
+    ///
+    /// # }}
+    /// ```
+    pub fn set_ptrace_options(&mut self, options: PtraceOptions) -> Result<Feedback> {
+        info!("ptrace options: {options:?}");
+        self.ptrace_options = options;
+        if let Some(dbge) = &self.debuggee {
+            ptrace::setoptions(dbge.pid, self.ptrace_flags())?;
+        }
+        Ok(Feedback::Ok)
+    }
+
+    /// Returns the flags for `PTRACE_SETOPTIONS`
+    ///
+    /// These are the configured [`PtraceOptions`], plus `PTRACE_O_TRACESYSGOOD` if the syscalls of
+    /// the debuggee are intercepted, so that syscall stops can be told apart from other stops.
+    fn ptrace_flags(&self) -> ptrace::Options {
+        // new processes have the breakpoints of the debuggee, see Self::handle_fork
+        let mut flags = self.ptrace_options.flags()
+            | ptrace::Options::PTRACE_O_TRACEFORK
+            | ptrace::Options::PTRACE_O_TRACEVFORK
+            | ptrace::Options::PTRACE_O_TRACEVFORKDONE;
+        if self.traces_syscalls() {
+            flags |= ptrace::Options::PTRACE_O_TRACESYSGOOD;
+        }
+        if self.debuggee.as_ref().is_some_and(|d| d.seccomp.is_some()) {
+            // without it, the filtered syscalls fail with ENOSYS
+            flags |= ptrace::Options::PTRACE_O_TRACESECCOMP;
+        }
+        flags
+    }
+
+    /// Enables or disables stopping at every syscall entry and exit of the debuggee
+    ///
+    /// While enabled, [`Self::cont`] returns [`Feedback::SyscallStop`] whenever the debuggee
+    /// enters or exits a syscall. At these stops, the syscall can be tampered with using
+    /// [`Self::set_syscall_arg`] and [`Self::set_syscall_return`]. A syscall can be skipped
+    /// entirely by setting [`Register::orig_rax`] to an invalid syscall number at its entry.
+    ///
+    /// # Parameters
+    ///
+    /// * `catch` - Whether to stop at syscalls
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Feedback::Ok)` - The setting was applied
+    ///
+    /// # Errors
+    ///
+    /// Cannot fail.
+    ///
+    /// # Examples
+    ///
+    ///
+    /// ```no_run
+    /// #[cfg(feature = "cli")]
+    /// # mod featguard { fn _do_thing() {
+    /// # use coreminer::debugger::Debugger;
+    /// # use coreminer::ui::cli::CliUi;
+    /// # use coreminer::feedback::Feedback;
+    /// #
+    /// # let ui = CliUi::build(None).unwrap();
+    /// # let mut debugger = Debugger::build(ui).unwrap();
+    /// # // Assume debuggee is already running
+    /// #
+    /// debugger.catch_syscalls(true).unwrap();
+    ///
+    /// if let Ok(Feedback::SyscallStop(stop)) = debugger.cont() {
+    ///     println!("stopped at syscall {}", stop.number);
+    /// }
+    ///
+    /// # }}
+    /// ```
+    pub fn catch_syscalls(&mut self, catch: bool) -> Result<Feedback> {
+        self.catch_syscalls = catch;
+        Ok(Feedback::Ok)
+    }
+
+    /// Stops the debuggee at the entry and exit of the syscall `name`
+    ///
+    /// The name is translated to the number of the syscall, which is added to the
+    /// [`SyscallFilter`] (see [`Self::set_syscall_filter`]), and syscalls are caught (see
+    /// [`Self::catch_syscalls`]). If no syscalls were selected so far, only this syscall stops
+    /// the debuggee afterwards. Calling this again selects more syscalls.
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - The name of the syscall in the syscall table of the kernel, like `openat`
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Feedback::Ok)` - The syscall is caught
+    ///
+    /// # Errors
+    ///
+    /// This function fails if there is no syscall with that name, see
+    /// [`parse_syscall`](crate::syscall::parse_syscall), or the filter would select too many
+    /// syscalls.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #[cfg(feature = "cli")]
+    /// # mod featguard { fn _do_thing() {
+    /// # use coreminer::debugger::Debugger;
+    /// # use coreminer::ui::cli::CliUi;
+    /// # use coreminer::feedback::Feedback;
+    /// #
+    /// # let ui = CliUi::build(None).unwrap();
+    /// # let mut debugger = Debugger::build(ui).unwrap();
+    /// #
+    /// debugger.catch_syscall("openat").unwrap();
+    /// debugger.run("/bin/ls", &[]).unwrap();
+    ///
+    /// while let Ok(Feedback::SyscallStop(stop)) = debugger.cont() {
+    ///     println!("openat({:#x}, {:#x})", stop.args[0], stop.args[1]);
+    /// }
+    ///
+    /// # }}
+    /// ```
+    pub fn catch_syscall(&mut self, name: &str) -> Result<Feedback> {
+        self.catch_syscall_list(&[name])
+    }
+
+    /// Stops the debuggee at the entry and exit of all the syscalls in `names`
+    ///
+    /// This is [`Self::catch_syscall`] for several syscalls at once, like
+    /// `catch syscall openat write`. The syscalls can be given by name or by number. If one of
+    /// them is unknown, none of them are caught.
+    ///
+    /// # Parameters
+    ///
+    /// * `names` - The names or numbers of the syscalls, like `openat` or `257`
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Feedback::Ok)` - The syscalls are caught
+    ///
+    /// # Errors
+    ///
+    /// This function fails if one of the syscalls is unknown, see
+    /// [`parse_syscall`](crate::syscall::parse_syscall), or the filter would select too many
+    /// syscalls.
+    pub fn catch_syscall_list<S: AsRef<str>>(&mut self, names: &[S]) -> Result<Feedback> {
+        let nrs = names
+            .iter()
+            .map(|name| {
+                let name = name.as_ref();
+                syscall::parse_syscall(name)
+                    .ok_or_else(|| DebuggerError::UnknownSyscall(name.to_string()))
+            })
+            .collect::<Result<Vec<u64>>>()?;
+        let mut syscalls = self
+            .syscall_filter
+            .as_ref()
+            .map(|filter| filter.syscalls().to_vec())
+            .unwrap_or_default();
+        syscalls.extend_from_slice(&nrs);
+        self.set_syscall_filter(Some(SyscallFilter::new(syscalls)))?;
+        for nr in nrs {
+            info!(
+                "catching syscall {} ({nr})",
+                syscall::syscall_name(nr).unwrap_or("unknown")
+            );
+        }
+        self.catch_syscalls(true)
+    }
+
+    /// Selects the syscalls at which [`Self::catch_syscalls`] stops
+    ///
+    /// Debuggees launched afterwards get a [seccomp filter](crate::seccomp) for the selected
+    /// syscalls, so that the other syscalls do not stop them at all. Programs that make many
+    /// syscalls run much faster like that. A debuggee that was launched with the same filter is
+    /// continued with `PTRACE_CONT` until it makes a selected syscall, unless the syscalls are
+    /// needed for something else, like the [anti-anti-debugging](crate::antidebug).
+    ///
+    /// A debuggee that was attached to or launched before still stops at every syscall, but only
+    /// the selected ones are reported.
+    ///
+    /// # Parameters
+    ///
+    /// * `filter` - The syscalls to stop at, or `None` to stop at all syscalls
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Feedback::Ok)` - The filter was set
+    ///
+    /// # Errors
+    ///
+    /// This function fails if the filter selects more than
+    /// [`MAX_FILTERED_SYSCALLS`](crate::seccomp::MAX_FILTERED_SYSCALLS) syscalls.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #[cfg(feature = "cli")]
+    /// # mod featguard { fn _do_thing() {
+    /// # use coreminer::debugger::Debugger;
+    /// # use coreminer::ui::cli::CliUi;
+    /// # use coreminer::feedback::Feedback;
+    /// use coreminer::seccomp::SyscallFilter;
+    /// #
+    /// # let ui = CliUi::build(None).unwrap();
+    /// # let mut debugger = Debugger::build(ui).unwrap();
+    /// #
+    /// let openat = nix::libc::SYS_openat as u64;
+    /// debugger.set_syscall_filter(Some(SyscallFilter::new(vec![openat]))).unwrap();
+    /// debugger.catch_syscalls(true).unwrap();
+    /// debugger.run("/bin/ls", &[]).unwrap();
+    ///
+    /// while let Ok(Feedback::SyscallStop(stop)) = debugger.cont() {
+    ///     assert_eq!(stop.number, openat);
+    /// }
+    ///
+    /// # }}
+    /// ```
+    pub fn set_syscall_filter(&mut self, filter: Option<SyscallFilter>) -> Result<Feedback> {
+        if let Some(filter) = &filter {
+            filter.program()?;
+        }
+        self.syscall_filter = filter;
+        Ok(Feedback::Ok)
+    }
+
+    /// Configures at which memory management syscalls the debuggee should be stopped
+    ///
+    /// While a catchpoint is enabled, [`Self::cont`] returns [`Feedback::MemoryEvent`] whenever
+    /// the debuggee exits a matching syscall. See the [`catchpoint`](crate::catchpoint) module
+    /// for details. At these stops, the return value of the syscall can still be changed with
+    /// [`Self::set_syscall_return`].
+    ///
+    /// # Parameters
+    ///
+    /// * `config` - Which syscalls to catch, use [`MemoryCatchConfig::default`] to disable all
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Feedback::Ok)` - The config was applied
+    ///
+    /// # Errors
+    ///
+    /// Cannot fail.
+    ///
+    /// # Examples
+    ///
+    ///
+    /// ```no_run
+    /// #[cfg(feature = "cli")]
+    /// # mod featguard { fn _do_thing() {
+    /// # use coreminer::debugger::Debugger;
+    /// # use coreminer::ui::cli::CliUi;
+    /// # use coreminer::feedback::Feedback;
+    /// use coreminer::catchpoint::MemoryCatchConfig;
+    /// #
+    /// # let ui = CliUi::build(None).unwrap();
+    /// # let mut debugger = Debugger::build(ui).unwrap();
+    /// # // Assume debuggee is already running
+    /// #
+    /// // stop once the debuggee has new executable memory
+    /// debugger.catch_memory(MemoryCatchConfig {
+    ///     executable_only: true,
+    ///     ..MemoryCatchConfig::all()
+    /// }).unwrap();
+    ///
+    /// if let Ok(Feedback::MemoryEvent(event)) = debugger.cont() {
+    ///     println!("new code at {}", event.addr);
+    /// }
+    ///
+    /// # }}
+    /// ```
+    pub fn catch_memory(&mut self, config: MemoryCatchConfig) -> Result<Feedback> {
+        info!("memory catchpoints: {config:?}");
+        self.catch_memory = config;
+        Ok(Feedback::Ok)
+    }
+
+    /// Enables or disables stopping the debuggee when it panics, if it is a Rust program
+    ///
+    /// While the catchpoint is enabled, [`Self::cont`] returns [`Feedback::Panic`] when the
+    /// debuggee panics, with the message and location of the panic if they can be found. The
+    /// debuggee is stopped in `rust_panic` before the stack is unwound, so the frames of the
+    /// panicking code can still be inspected. See the [rustpanic module](crate::rustpanic) for
+    /// details.
+    ///
+    /// The catchpoint uses hardware [Breakpoints](Breakpoint), which are set when a debuggee is
+    /// launched or attached, or right away if there already is one.
+    ///
+    /// # Parameters
+    ///
+    /// * `catch` - Whether to stop at panics
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Feedback::Ok)` - The catchpoint was enabled or disabled
+    /// * `Err(DebuggerError)` - If the breakpoints could not be set or removed
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if the debuggee is running and the breakpoints of the catchpoint
+    /// cannot be set or removed.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #[cfg(feature = "cli")]
+    /// # mod featguard { fn _do_thing() {
+    /// # use coreminer::debugger::Debugger;
+    /// # use coreminer::ui::cli::CliUi;
+    /// # use coreminer::feedback::Feedback;
+    /// #
+    /// # let ui = CliUi::build(None).unwrap();
+    /// # let mut debugger = Debugger::build(ui).unwrap();
+    /// # // Assume debuggee is already running
+    /// #
+    /// debugger.catch_panics(true).unwrap();
+    ///
+    /// if let Ok(Feedback::Panic(panic)) = debugger.cont() {
+    ///     println!("the debuggee {panic}");
+    /// }
+    ///
+    /// # }}
+    /// ```
+    pub fn catch_panics(&mut self, catch: bool) -> Result<Feedback> {
+        info!("catch panics: {catch}");
+        self.catch_panics = catch;
+        if self.debuggee.is_none() {
+            return Ok(Feedback::Ok);
+        }
+        if !catch {
+            self.disarm_panic_catchpoint()?;
+        } else if self.panic_breakpoints.is_empty() {
+            self.arm_panic_catchpoint()?;
+        }
+        Ok(Feedback::Ok)
+    }
+
+    /// Sets the breakpoints of the panic catchpoint in a new debuggee, if it is enabled
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if the executable cannot be parsed.
+    fn arm_panic_catchpoint(&mut self) -> Result<()> {
+        self.panic_breakpoints.clear();
+        self.panic_location = None;
+        if !self.catch_panics {
+            return Ok(());
+        }
+
+        let obj = object::File::parse(&*self.executable_data)?;
+        let breakpoints = rustpanic::find_breakpoints(&obj, self.executable()?);
+        if breakpoints.is_empty() {
+            warn!(
+                "the executable has no symbols of the Rust panic functions, panics are not caught"
+            );
+        }
+        for (addr, kind) in breakpoints {
+            if let Err(e) = self.set_hw_bp(addr) {
+                warn!("could not set the panic breakpoint at {addr}: {e}");
+                continue;
+            }
+            debug!("catching panics at {addr} ({kind:?})");
+            self.panic_breakpoints.push((addr, kind));
+        }
+        Ok(())
+    }
+
+    /// Removes the breakpoints of the panic catchpoint
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if a breakpoint cannot be removed.
+    fn disarm_panic_catchpoint(&mut self) -> Result<()> {
+        self.panic_location = None;
+        for (addr, _) in std::mem::take(&mut self.panic_breakpoints) {
+            self.del_bp(addr)?;
+        }
+        Ok(())
+    }
+
+    /// Overwrites an argument of the syscall that the debuggee is currently entering
+    ///
+    /// This can be used to change what a syscall does before the kernel executes it, for
+    /// example to redirect an `open` to a different path.
+    ///
+    /// # Parameters
+    ///
+    /// * `index` - Index of the argument, starting at 0
+    /// * `value` - The new value of the argument
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Feedback::Ok)` - If the argument was overwritten
+    /// * `Err(DebuggerError)` - If the argument could not be overwritten
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if:
+    /// - The debuggee is not running
+    /// - The debuggee is not stopped at the entry of a syscall
+    /// - The index is not in `0..6`
+    /// - The registers of the debuggee cannot be written
+    ///
+    /// # Examples
+    ///
+    ///
+    /// ```no_run
+    /// #[cfg(feature = "cli")]
+    /// # mod featguard { fn _do_thing() {
+    /// # use coreminer::debugger::Debugger;
+    /// # use coreminer::ui::cli::CliUi;
+    /// #
+    /// # let ui = CliUi::build(None).unwrap();
+    /// # let mut debugger = Debugger::build(ui).unwrap();
+    /// # // Assume debuggee is stopped at the entry of a write syscall
+    /// #
+    /// // write only the first byte of the buffer
+    /// debugger.set_syscall_arg(2, 1).unwrap();
+    ///
+    /// # }}
+    /// ```
+    pub fn set_syscall_arg(&mut self, index: usize, value: u64) -> Result<Feedback> {
+        let pid = self.expect_syscall_stop(SyscallPhase::Entry)?;
+        syscall::set_arg(pid, index, value)?;
+        info!("set argument {index} of the current syscall to {value:#x}");
+        Ok(Feedback::Ok)
+    }
+
+    /// Overwrites the return value of the syscall that the debuggee is currently exiting
+    ///
+    /// This can be used to make syscalls fail, which is useful to test error paths of the
+    /// debuggee. Errors are returned as negative `errno` values, so `-2` would be `ENOENT`.
+    ///
+    /// # Parameters
+    ///
+    /// * `value` - The new return value
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Feedback::Ok)` - If the return value was overwritten
+    /// * `Err(DebuggerError)` - If the return value could not be overwritten
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if:
+    /// - The debuggee is not running
+    /// - The debuggee is not stopped at the exit of a syscall
+    /// - The registers of the debuggee cannot be written
+    ///
+    /// # Examples
+    ///
+    ///
+    /// ```no_run
+    /// #[cfg(feature = "cli")]
+    /// # mod featguard { fn _do_thing() {
+    /// # use coreminer::debugger::Debugger;
+    /// # use coreminer::ui::cli::CliUi;
+    /// #
+    /// # let ui = CliUi::build(None).unwrap();
+    /// # let mut debugger = Debugger::build(ui).unwrap();
+    /// # // Assume debuggee is stopped at the exit of an openat syscall
+    /// #
+    /// // pretend that the file does not exist
+    /// debugger.set_syscall_return(-(nix::libc::ENOENT as i64)).unwrap();
+    ///
+    /// # }}
+    /// ```
+    pub fn set_syscall_return(&mut self, value: i64) -> Result<Feedback> {
+        let pid = self.expect_syscall_stop(SyscallPhase::Exit)?;
+        syscall::set_return_value(pid, value)?;
+        info!("set return value of the current syscall to {value}");
+        Ok(Feedback::Ok)
+    }
+
+    /// Makes sure that the debuggee is stopped at a syscall in the given phase
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Pid)` - The process ID of the debuggee
+    /// * `Err(DebuggerError)` - If the debuggee is not stopped in the given phase of a syscall
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if:
+    /// - The debuggee is not running
+    /// - The debuggee is not stopped at a syscall
+    /// - The debuggee is stopped in the other phase of a syscall
+    fn expect_syscall_stop(&self, phase: SyscallPhase) -> Result<nix::unistd::Pid> {
+        let dbge = self.debuggee.as_ref().ok_or(DebuggerError::NoDebugee)?;
+        match dbge.syscall_stop {
+            None => Err(DebuggerError::NotInSyscallStop),
+            Some(stop) if stop.phase != phase => Err(DebuggerError::WrongSyscallPhase(phase)),
+            Some(_) => Ok(dbge.pid),
+        }
+    }
+
+    /// Performs a single, atomic step of exactly one instruction through the debuggee
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the step was successful
+    /// * `Err(DebuggerError)` - If there was an error during stepping
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if:
+    /// - The debuggee is not running
+    /// - ptrace's step operation fails
+    fn atomic_single_step(&mut self) -> Result<()> {
+        let pid = self.debuggee.as_ref().ok_or(DebuggerError::NoDebugee)?.pid;
+
+        // FIXME: this is probably noticeable
+        let sig = self.take_last_status();
+        if let Err(e) = self.resume_as(pid, Resume::Step, sig) {
+            error!("could not do atomic step: {e}");
+            return Err(e);
+        }
+
+        Ok(())
+    }
+
+    /// Steps a single instruction in the debuggee
+    ///
+    /// This function advances execution by a single instruction, taking
+    /// care to handle breakpoints appropriately.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Feedback::Ok)` - If the step was successful
+    /// * `Err(DebuggerError)` - If there was an error during stepping
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if:
+    /// - The debuggee is not running
+    /// - ptrace operations fail
+    ///
+    /// # Examples
+    ///
+    ///
+    /// ```no_run
+    /// #[cfg(feature = "cli")]
+    /// # mod featguard { fn _do_thing() {
+    /// # use coreminer::debugger::Debugger;
+    /// # use coreminer::ui::cli::CliUi;
+    /// #
+    /// # let ui = CliUi::build(None).unwrap();
+    /// # let mut debugger = Debugger::build(ui).unwrap();
+    /// # // Assume debuggee is already running
+    /// #
+    /// // Step through one instruction
+    /// debugger.single_step().unwrap();
+    ///
+    /// // Step through multiple instructions
+    /// for _ in 0..5 {
+    ///     debugger.single_step().unwrap();
+    /// }
+    ///
+    /// # }}
+    /// ```
+    pub fn single_step(&mut self) -> Result<Feedback> {
+        if self.go_back_step_over_bp()? {
+            info!("breakpoint before, caught up and continueing with single step");
+        }
+
+        let instruction: Addr = self.get_current_addr()?;
+        let feedback = self.step_instruction()?;
+        if let Feedback::Exit(_) = feedback {
+            return Ok(feedback);
+        }
+        trace!("now at {:018x}", self.get_reg(Register::rip)?);
+
+        if let Some(hit) = self.check_watchpoints(instruction)? {
+            return Ok(Feedback::WatchpointHit(hit));
+        }
+
+        Ok(Feedback::Ok)
+    }
+
+    /// Executes exactly the instruction at the current instruction pointer
+    ///
+    /// If there is a [`Breakpoint`] at the current instruction pointer, it is stepped over with
+    /// [`Self::dse`].
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Feedback::Ok)` - If the step was successful
+    /// * `Ok(Feedback::Exit)` - If the debuggee exited during the step
+    /// * `Err(DebuggerError)` - If there was an error during stepping
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if:
+    /// - The debuggee is not running
+    /// - ptrace operations fail
+    fn step_instruction(&mut self) -> Result<Feedback> {
+        let dbge = self.debuggee.as_ref().ok_or(DebuggerError::NoDebugee)?;
+
+        let maybe_bp_addr: Addr = self.get_current_addr()?;
+        if dbge.breakpoints.contains_key(&maybe_bp_addr) {
+            trace!("step over instruction with breakpoint");
+            self.dse(maybe_bp_addr)?;
+            Ok(Feedback::Ok)
+        } else {
+            trace!("step regular instruction");
+            self.atomic_single_step()?;
+            self.wait_signal()
+        }
+    }
+
+    /// Continues the debuggee by single stepping it while [Watchpoints](Watchpoint) are set
+    ///
+    /// After each instruction, all [Watchpoints](Watchpoint) are checked. Stepping stops when a
+    /// watched memory range was changed, the debuggee received a signal or exited, or an enabled
+    /// [`Breakpoint`] is reached. In the latter case, the `INT3` of the [`Breakpoint`] is
+    /// executed, so that the debuggee is in the same state as after a regular [`Self::cont`].
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Feedback::WatchpointHit)` - If a watched memory range was changed
+    /// * `Ok(Feedback::Exit)` - If the debuggee exited
+    /// * `Ok(Feedback::Ok)` - If the debuggee stopped for another reason
+    /// * `Err(DebuggerError)` - If there was an error during stepping
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if:
+    /// - The debuggee is not running
+    /// - ptrace operations fail
+    /// - The memory of a watched range cannot be read
+    fn cont_watched(&mut self) -> Result<Feedback> {
+        let start: Addr = self.get_current_addr()?;
+        if self.go_back_step_over_bp()? {
+            if let Some(hit) = self.check_watchpoints(start - 1)? {
+                return Ok(Feedback::WatchpointHit(hit));
+            }
+        }
+
+        loop {
+            let instruction: Addr = self.get_current_addr()?;
+            let feedback = self.step_instruction()?;
+            if let Feedback::Exit(_) | Feedback::Interrupted = feedback {
+                return Ok(feedback);
+            }
+            if let Some(hit) = self.check_watchpoints(instruction)? {
+                return Ok(Feedback::WatchpointHit(hit));
+            }
+            if self.last_signal.is_some() {
+                return Ok(Feedback::Ok);
+            }
+
+            let rip: Addr = self.get_current_addr()?;
+            let dbge = self.debuggee.as_ref().ok_or(DebuggerError::NoDebugee)?;
+            if dbge
+                .breakpoints
+                .get(&rip)
+                .is_some_and(Breakpoint::is_enabled)
+            {
+                trace!("watched stepping reached breakpoint at {rip}");
+                self.atomic_single_step()?;
+                return self.wait_signal();
+            }
+        }
+    }
+
+    /// Checks all [Watchpoints](Watchpoint) of the debuggee for changes
+    ///
+    /// # Parameters
+    ///
+    /// * `instruction` - Address of the instruction that was executed last
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Some(WatchpointHit))` - The first watched memory range that was changed
+    /// * `Ok(None)` - If no watched memory range was changed
+    /// * `Err(DebuggerError)` - If a watched memory range could not be read
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if:
+    /// - The debuggee is not running
+    /// - The memory of a watched range cannot be read
+    fn check_watchpoints(&mut self, instruction: Addr) -> Result<Option<WatchpointHit>> {
+        let dbge = self.debuggee.as_mut().ok_or(DebuggerError::NoDebugee)?;
+        for wp in dbge.watchpoints.values_mut() {
+            if let Some(hit) = wp.check(dbge.pid, instruction)? {
+                info!(
+                    "watched memory at {} was changed by the instruction at {instruction}",
+                    hit.addr
+                );
+                self.last_stop = Some(StopReason::Watchpoint { addr: hit.addr });
+                return Ok(Some(hit));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Continues the debuggee while stopping at each syscall
+    ///
+    /// This is used instead of a regular `PTRACE_CONT` when the syscalls of the debuggee need to
+    /// be intercepted, for example for the [anti-anti-debugging](crate::antidebug) countermeasures.
+    /// The debuggee is resumed with `PTRACE_SYSCALL`, and each syscall stop is handled by
+    /// [`Self::handle_syscall_stop`] before resuming again. Once the debuggee stops for any other
+    /// reason, that stop is processed like in [`Self::wait_signal`].
+    ///
+    /// If only the syscalls of a [`SyscallFilter`] are caught, the seccomp filter of the debuggee
+    /// does the selection: the debuggee is resumed with `PTRACE_CONT` and its
+    /// `PTRACE_EVENT_SECCOMP` stops are handled as syscall entries. Only after such an entry, it
+    /// is resumed with `PTRACE_SYSCALL` to stop at the exit of the syscall.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Feedback::Exit)` - If the debuggee exited
+    /// * `Ok(Feedback::Ok)` - If the debuggee stopped for another reason
+    /// * `Err(DebuggerError)` - If there was an error during execution
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if:
+    /// - The debuggee is not running
+    /// - ptrace operations fail
+    /// - waitpid fails
+    fn cont_syscalls(&mut self) -> Result<Feedback> {
+        let pid = self.debuggee.as_ref().ok_or(DebuggerError::NoDebugee)?.pid;
+        ptrace::setoptions(pid, self.ptrace_flags())?;
+        let seccomp = self.traces_with_seccomp();
+
+        loop {
+            let in_syscall = self.debuggee.as_ref().is_some_and(|d| d.in_syscall);
+            let how = if seccomp && !in_syscall {
+                Resume::Cont
+            } else {
+                Resume::Syscall
+            };
+            let sig = self.take_last_status();
+            self.resume_as(pid, how, sig)?;
+            match self.wait_interruptible()? {
+                WaitStatus::PtraceEvent(_, _, nix::libc::PTRACE_EVENT_SECCOMP) if !seccomp => {
+                    // the entry of this syscall was already a syscall stop
+                }
+                WaitStatus::PtraceSyscall(_)
+                | WaitStatus::PtraceEvent(_, _, nix::libc::PTRACE_EVENT_SECCOMP) => {
+                    if let Some(feedback) = self.handle_syscall_stop()? {
+                        return Ok(feedback);
+                    }
+                }
+                wait_status => return self.process_wait_status(wait_status),
+            }
+        }
+    }
+
+    /// Checks if the seccomp filter of the debuggee selects all syscall stops that are needed
+    ///
+    /// That is the case if the debuggee was launched with the current [`SyscallFilter`] and nothing but
+    /// [`Self::catch_syscalls`] needs the syscalls of the debuggee.
+    fn traces_with_seccomp(&self) -> bool {
+        self.catch_syscalls
+            && self.syscall_filter.is_some()
+            && self
+                .debuggee
+                .as_ref()
+                .is_some_and(|d| d.seccomp == self.syscall_filter)
+            && !self.catch_memory.is_active()
+            && !self.anti_debug.config.is_active()
+            && !self.has_syscall_hooks()
+    }
+
+    /// Handles a syscall stop of the debuggee
+    ///
+    /// Keeps track of whether the debuggee is entering or exiting a syscall, applies the
+    /// [anti-anti-debugging](crate::antidebug) countermeasures and runs the `EOnSyscall` hooks
+    /// of plugins.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Some(Feedback::SyscallStop))` - If the debugger should stop at this syscall
+    /// * `Ok(Some(Feedback::MemoryEvent))` - If a memory catchpoint was hit
+    /// * `Ok(None)` - If the debuggee should be resumed
+    /// * `Err(DebuggerError)` - If there was an error handling the syscall stop
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if:
+    /// - The debuggee is not running
+    /// - The registers or memory of the debuggee cannot be accessed
+    fn handle_syscall_stop(&mut self) -> Result<Option<Feedback>> {
+        let dbge = self.debuggee.as_mut().ok_or(DebuggerError::NoDebugee)?;
+        let phase = if dbge.in_syscall {
+            SyscallPhase::Exit
+        } else {
+            SyscallPhase::Entry
+        };
+        dbge.in_syscall = !dbge.in_syscall;
+
+        let stop = SyscallStop::from_pid(dbge.pid, phase)?;
+        dbge.syscall_stop = Some(stop);
+        trace!("syscall stop: {stop:?}");
+        self.anti_debug.on_syscall(dbge, &stop)?;
+
+        for_hooks!(
+            for hook[EOnSyscall] in self {
+                self.hook_feedback_loop(hook.name(), |f| {
+                    hook.inner_mut().on_syscall(f, &stop)
+                })?;
+            }
+        );
+
+        if self.catch_syscalls || self.catch_memory.is_active() {
+            // the hooks may have changed the registers
+            let dbge = self.debuggee.as_mut().ok_or(DebuggerError::NoDebugee)?;
+            let stop = SyscallStop::from_pid(dbge.pid, phase)?;
+            dbge.syscall_stop = Some(stop);
+
+            if let Some(event) = self.catch_memory.check(&stop) {
+                info!("caught memory event: {event}");
+                self.last_stop = Some(StopReason::Syscall(stop));
+                return Ok(Some(Feedback::MemoryEvent(event)));
+            }
+            if self.catch_syscalls
+                && self
+                    .syscall_filter
+                    .as_ref()
+                    .map_or(true, |f| f.contains(stop.number))
+            {
+                info!("stopped at syscall {}: {phase:?}", stop.number);
+                self.last_stop = Some(StopReason::Syscall(stop));
+                return Ok(Some(Feedback::SyscallStop(stop)));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Checks if the debuggee needs to be continued with `PTRACE_SYSCALL`
+    ///
+    /// This is the case if syscalls or memory events are caught, the
+    /// [anti-anti-debugging](crate::antidebug) countermeasures are active or a plugin has an
+    /// enabled `EOnSyscall` hook.
+    fn traces_syscalls(&self) -> bool {
+        self.catch_syscalls
+            || self.catch_memory.is_active()
+            || self.anti_debug.config.is_active()
+            || self.has_syscall_hooks()
+    }
+
+    /// Checks if a plugin has an enabled `EOnSyscall` hook
+    #[allow(unused_variables, clippy::unused_self, clippy::unnecessary_wraps)] // used for plugins
+    fn has_syscall_hooks(&self) -> bool {
+        #[cfg(feature = "plugins")]
+        {
+            let plugins = self.plugins();
+            let plugins_lock = plugins
+                .lock()
+                .expect("failed to lock the plugin manager of the coreminer debugger");
+            !plugins_lock
+                .get_enabled_hooks_by_ep::<EOnSyscall>()
+                .is_empty()
+        }
+        #[cfg(not(feature = "plugins"))]
+        false
+    }
+
+    /// Steps out of the current function
+    ///
+    /// This function sets a temporary breakpoint at the return address
+    /// and continues execution until that breakpoint is hit.
+    ///
+    /// If the function is known from the debug information and has a return type, the returned
+    /// value is read from the registers and interpreted with that type, see
+    /// [`Debuggee::return_value_read`].
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Feedback::Returned)` - If the function returned a value
+    /// * `Ok(Feedback::Ok)` - If the step-out was successful, but there is no value to show
+    /// * `Err(DebuggerError)` - If there was an error during step-out
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if:
+    /// - The debuggee is not running
+    /// - No valid return address of the current function can be found
+    /// - The current function is main (cannot step out)
+    /// - Could not read or write a [Register].
+    /// - Could not read from memory.
+    /// - Could not set or delete a [Breakpoint].
+    ///
+    /// # Examples
+    ///
+    ///
+    /// ```no_run
+    /// #[cfg(feature = "cli")]
+    /// # mod featguard { fn _do_thing() {
+    /// # use coreminer::debugger::Debugger;
+    /// # use coreminer::ui::cli::CliUi;
+    /// # use coreminer::feedback::Feedback;
+    /// #
+    /// # let ui = CliUi::build(None).unwrap();
+    /// # let mut debugger = Debugger::build(ui).unwrap();
+    /// # // Assume debuggee is already running and in a function
+    /// #
+    /// // Step out of the current function
+    /// if let Feedback::Returned { function, value } = debugger.step_out().unwrap() {
+    ///     println!("{function} returned {value}");
+    /// }
+    ///
+    /// # }}
+    /// ```
+    pub fn step_out(&mut self) -> Result<Feedback> {
+        let dbge = self.debuggee.as_ref().ok_or(DebuggerError::NoDebugee)?;
+        let function = dbge.get_function_by_addr(self.get_reg(Register::rip)?.into())?;
+        if let Some(s) = &function {
+            debug!("step out in following function: {s:#?}");
+            if s.name() == Some("main") {
+                error!("you're about to do something stupid: no stepping out of the earliest stack frame allowed");
+                return Err(DebuggerError::StepOutMain);
+            }
+        } else {
+            warn!("did not find debug symbol for current address");
+        }
+
+        let stack_frame_pointer: Addr = self.get_reg(Register::rbp)?.into();
+        let return_addr: Addr = mem_read_word(dbge.pid, stack_frame_pointer + 8)?.into();
+        trace!("rsb: {stack_frame_pointer}");
+        trace!("ret_addr: {return_addr}");
+
+        let should_remove_breakpoint = if dbge.breakpoints.contains_key(&return_addr) {
+            false
+        } else {
+            self.set_bp(return_addr)?;
+            true
+        };
+
+        self.cont()?;
+        let returned = matches!(
+            self.last_stop,
+            Some(StopReason::Breakpoint { addr, .. }) if addr == return_addr
+        );
+
+        if should_remove_breakpoint {
+            self.del_bp(return_addr)?;
+            self.set_reg(Register::rip, self.get_reg(Register::rip)? - 1)?; // we need to go back
+                                                                            // else we skip an instruction
+        }
+
+        let Some(function) = function.filter(|_| returned) else {
+            return Ok(Feedback::Ok);
+        };
+        let dbge = self.debuggee.as_ref().ok_or(DebuggerError::NoDebugee)?;
+        match dbge.return_value_read(&function) {
+            Ok(Some(value)) => Ok(Feedback::Returned {
+                function: function.name().unwrap_or("??").to_string(),
+                value,
+            }),
+            Ok(None) => Ok(Feedback::Ok),
+            Err(e) => {
+                warn!("could not read the return value: {e}");
+                Ok(Feedback::Ok)
+            }
+        }
+    }
+
+    /// Temporarily disables a breakpoint, steps over it, and then re-enables it
+    ///
+    /// # Parameters
+    ///
+    /// * `here` - The address of the breakpoint
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the operation was successful
+    /// * `Err(DebuggerError)` - If there was an error
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if:
+    /// - The debuggee is not running
+    /// - Breakpoint operations fail
+    /// - Step operations fail
+    fn dse(&mut self, here: Addr) -> Result<()> {
+        trace!("disabling the breakpoint");
+        self.debuggee
+            .as_mut()
+            .unwrap()
+            .breakpoints
+            .get_mut(&here)
+            .unwrap()
+            .disable()?;
+
+        trace!("atomic step");
+        self.atomic_single_step()?;
+        trace!("waiting");
+        self.wait_signal()
+            .inspect_err(|e| warn!("weird wait_signal error: {e}"))?;
+        trace!("enable stepped over bp again");
+        self.debuggee
+            .as_mut()
+            .unwrap()
+            .breakpoints
+            .get_mut(&here)
+            .unwrap()
+            .enable()?;
+        trace!("dse done");
+
+        Ok(())
+    }
+
+    /// Checks if we need to restore an instruction pointer after hitting a breakpoint
+    ///
+    /// When a breakpoint is hit, the instruction pointer is just after the INT3 instruction.
+    /// This function checks if we need to move the instruction pointer back to the breakpoint
+    /// address and execute the original instruction. If so, it does that.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(bool)` - True if the IP was adjusted and a breakpoint was stepped over
+    /// * `Err(DebuggerError)` - If there was an error
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if:
+    /// - The debuggee is not running
+    /// - Register operations fail
+    /// - Breakpoint operations fail
+    #[allow(clippy::missing_panics_doc)] // this function cant panic
+    pub fn go_back_step_over_bp(&mut self) -> Result<bool> {
+        if self.debuggee.is_none() {
+            return Err(DebuggerError::NoDebugee);
+        }
+
+        let Some(maybe_bp_addr) = self.get_current_addr()?.checked_sub(1) else {
+            return Ok(false);
+        };
+        trace!("Checkinf if {maybe_bp_addr} had a breakpoint");
+
+        if self
+            .debuggee
+            .as_mut()
+            // safe because we check earlier, needed because we use a mutable reference
+            // and can only drop in place this way
+            .unwrap()
+            .breakpoints
+            .get_mut(&maybe_bp_addr)
+            .is_some_and(|a| a.is_enabled() && !a.is_hardware())
+        {
+            let here = maybe_bp_addr;
+            trace!("set register to {here}");
+            self.set_reg(Register::rip, here.into())?;
+
+            self.dse(here)?;
+            Ok(true)
+        } else {
+            trace!("breakpoint is disabled or does not exist, doing nothing");
+            Ok(false)
+        }
+    }
+
+    /// Disassembles memory at the specified address
+    ///
+    /// # Parameters
+    ///
+    /// * `addr` - The starting address
+    /// * `len` - The number of bytes to disassemble
+    /// * `literal` - Whether to show literal bytes instead of original code (including breakpoint instructions)
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Feedback::Disassembly)` - The disassembly result
+    /// * `Err(DebuggerError)` - If there was an error during disassembly
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if:
+    /// - The debuggee is not running
+    /// - `len` is larger than [`Limits::max_disassembly_len`]
+    /// - Memory cannot be read
+    /// - Disassembly fails
+    ///
+    /// # Panics
+    ///
+    /// If a [Breakpoint] is enabled but has no saved data, this will panic.
+    /// If a [Breakpoint] was found before making the [Disassembly], but the same breakpoint does
+    /// not exist after the [Disassembly] was created, this will also panic.
+    ///
+    /// # Examples
+    ///
+    ///
+    /// ```no_run
+    /// #[cfg(feature = "cli")]
+    /// # mod featguard { fn _do_thing() {
+    /// # use coreminer::debugger::Debugger;
+    /// # use coreminer::ui::cli::CliUi;
+    /// # use coreminer::addr::Addr;
+    /// # use coreminer::feedback::Feedback;
+    /// #
+    /// # let ui = CliUi::build(None).unwrap();
+    /// # let debugger = Debugger::build(ui).unwrap();
+    /// # // Assume debuggee is already running
+    /// #
+    /// // Disassemble 16 bytes at address 0x1000
+    /// if let Ok(Feedback::Disassembly(disasm)) = debugger.disassemble_at(Addr::from(0x1000usize), 16, false) {
+    ///     for (addr, raw, content, has_bp) in disasm.inner() {
+    ///         println!("{}: {} {}", addr, if *has_bp { "*" } else { " " }, content[0].0);
+    ///     }
+    /// }
+    ///
+    /// # }}
+    /// ```
+    pub fn disassemble_at(&self, addr: Addr, len: usize, literal: bool) -> Result<Feedback> {
+        let dbge = self.debuggee.as_ref().ok_or(DebuggerError::NoDebugee)?;
+        self.limits.check(LimitKind::DisassemblyLen, len)?;
+
+        let t = dbge.disassemble(addr, len, literal)?;
+
+        Ok(Feedback::Disassembly(t))
+    }
+
+    /// Searches for symbols by name
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - The name to search for
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Feedback::Symbols)` - The matching symbols
+    /// * `Err(DebuggerError)` - If there was an error during search
+    ///
+    /// Note: If the executable that is being debugged has no DWARF information (was stripped), this will always
+    /// return no symbols.
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if:
+    /// - The debug information is not loaded
+    /// - Symbol information is not available
+    /// - More symbols match than [`Limits::max_symbol_results`]
+    ///
+    /// # Examples
+    ///
+    ///
+    /// ```no_run
+    /// #[cfg(feature = "cli")]
+    /// # mod featguard { fn _do_thing() {
+    /// # use coreminer::debugger::Debugger;
+    /// # use coreminer::ui::cli::CliUi;
+    /// # use coreminer::feedback::Feedback;
+    /// #
+    /// # let ui = CliUi::build(None).unwrap();
+    /// # let debugger = Debugger::build(ui).unwrap();
+    /// # // Assume debuggee is already running
+    /// #
+    /// // Find the "main" function
+    /// if let Ok(Feedback::Symbols(symbols)) = debugger.get_symbol_by_name("main") {
+    ///     for symbol in symbols {
+    ///         println!("Found main at: {:?}", symbol.low_addr());
+    ///     }
+    /// }
+    ///
+    /// # }}
+    /// ```
+    pub fn get_symbol_by_name(&self, name: impl Display) -> Result<Feedback> {
+        let dbge = self.debuggee.as_ref().ok_or(DebuggerError::NoDebugee)?;
+
+        let symbols: Vec<OwnedSymbol> = dbge.get_symbol_by_name(name)?;
+        self.limits.check(LimitKind::SymbolResults, symbols.len())?;
+        Ok(Feedback::Symbols(symbols))
+    }
+
+    /// Handles a SIGTRAP signal from the debuggee
+    ///
+    /// # Parameters
+    ///
+    /// * `sig` - The signal
+    /// * `siginfo` - The signal information
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the signal was handled successfully
+    /// * `Err(DebuggerError)` - If there was an error handling the signal
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if:
+    /// - The debuggee is not running
+    pub fn handle_sigtrap(
+        &mut self,
+        sig: nix::sys::signal::Signal,
+        siginfo: nix::libc::siginfo_t,
+    ) -> Result<()> {
+        #[allow(unused_mut)] // used for plugins
+        let mut return_early = false;
+
+        for_hooks!(
+            for hook[EPreSigtrap] in self {
+                trace!("process hook {}", hook.name());
+                self.hook_feedback_loop(hook.name(), |f| {
+                    match hook.inner_mut().pre_handle_sigtrap(f, &siginfo, &sig) {
+                        Ok((status, ret_e)) => {
+                            return_early |= ret_e;
+                            Ok(status)
+                        },
+                        Err(e) => Err(e)
+                    }
+                })?;
+            }
+        );
+
+        if return_early {
+            return Ok(());
+        }
+
+        info!("debugee received {}: {}", sig.as_str(), siginfo.si_code);
+
+        match siginfo.si_code {
+            SI_KERNEL => trace!("SI_KERNEL"), // we don't know what do do?
+            TRAP_BRKPT => {
+                trace!("TRAP_BRKPT");
+            }
+            TRAP_TRACE => trace!("TRAP_TRACE"), // single stepping
+            TRAP_HWBKPT => trace!("TRAP_HWBKPT"), // hardware breakpoint, rip is at the breakpoint
+            _ => warn!("Strange SIGTRAP code: {}", siginfo.si_code),
+        }
+
+        Ok(())
+    }
+
+    /// Determines the [`StopReason`] of a `SIGTRAP` stop
+    ///
+    /// A `SIGTRAP` that is delivered to the debuggee, for example because a plugin decided that
+    /// it was not caused by the debugger, is reported as [`StopReason::Signal`].
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if the debuggee is not running or its registers cannot be read.
+    fn sigtrap_stop_reason(&self, siginfo: &nix::libc::siginfo_t) -> Result<StopReason> {
+        if self.last_signal == Some(Signal::SIGTRAP) {
+            return Ok(StopReason::Signal(Signal::SIGTRAP as i32));
+        }
+        let dbge = self.debuggee.as_ref().ok_or(DebuggerError::NoDebugee)?;
+        let rip: Addr = self.get_current_addr()?;
+
+        let reason = match siginfo.si_code {
+            TRAP_TRACE => StopReason::StepDone,
+            TRAP_HWBKPT => StopReason::Breakpoint {
+                addr: rip,
+                hardware: true,
+            },
+            // the INT3 of a software breakpoint was executed, so rip is right behind it
+            _ => match rip.checked_sub(1).filter(|addr| {
+                dbge.breakpoints
+                    .get(addr)
+                    .is_some_and(|bp| bp.is_enabled() && !bp.is_hardware())
+            }) {
+                Some(addr) => StopReason::Breakpoint {
+                    addr,
+                    hardware: false,
+                },
+                None => StopReason::Signal(Signal::SIGTRAP as i32),
+            },
+        };
+        Ok(reason)
+    }
+
+    /// Handles important signals from the debuggee
+    ///
+    /// # Parameters
+    ///
+    /// * `sig` - The signal
+    /// * `siginfo` - The signal information
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the signal was handled successfully
+    /// * `Err(DebuggerError)` - If there was an error handling the signal
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if:
+    /// - The debuggee is not running
+    pub fn handle_important_signal(
+        &mut self,
+        sig: Signal,
+        siginfo: nix::libc::siginfo_t,
+    ) -> Result<()> {
+        info!("debugee received {}: {}", sig.as_str(), siginfo.si_code);
+        self.last_signal = Some(sig);
+        Ok(())
+    }
+
+    /// Handles other signals from the debuggee
+    ///
+    /// # Parameters
+    ///
+    /// * `sig` - The signal
+    /// * `siginfo` - The signal information
     ///
     /// # Returns
     ///
@@ -1236,419 +5789,3961 @@ impl<'executable, UI: DebuggerUI> Debugger<'executable, UI> {
     ///
     /// This function can fail if:
     /// - The debuggee is not running
-    pub fn handle_important_signal(
-        &mut self,
-        sig: Signal,
-        siginfo: nix::libc::siginfo_t,
-    ) -> Result<()> {
-        info!("debugee received {}: {}", sig.as_str(), siginfo.si_code);
-        self.last_signal = Some(sig);
-        Ok(())
+    pub fn handle_other_signal(
+        &mut self,
+        sig: Signal,
+        siginfo: nix::libc::siginfo_t,
+    ) -> Result<()> {
+        trace!("handle other");
+        info!("debugee received {}: {}", sig.as_str(), siginfo.si_code);
+        self.last_signal = Some(sig);
+        Ok(())
+    }
+
+    /// Logs information about the debugger state
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Feedback::Ok)` - If the information was logged successfully
+    /// * `Err(DebuggerError)` - If there was an error
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if:
+    /// - The debuggee is not running
+    fn infos(&self) -> std::result::Result<Feedback, DebuggerError> {
+        let dbge = self.debuggee.as_ref().ok_or(DebuggerError::NoDebugee)?;
+        info!("Breakpoints:\n{:#?}", dbge.breakpoints);
+        info!("Watchpoints:\n{:#?}", dbge.watchpoints);
+        Ok(Feedback::Ok)
+    }
+
+    /// Steps into a function call
+    ///
+    /// This function steps through instructions until a call instruction is found,
+    /// then steps into that function.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Feedback::Ok)` - If the step-into was successful
+    /// * `Err(DebuggerError)` - If there was an error during step-into
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if:
+    /// - The debuggee is not running
+    /// - No call instruction is found
+    /// - Step operations fail
+    ///
+    /// # Examples
+    ///
+    ///
+    /// ```no_run
+    /// #[cfg(feature = "cli")]
+    /// # mod featguard { fn _do_thing() {
+    /// # use coreminer::debugger::Debugger;
+    /// # use coreminer::ui::cli::CliUi;
+    /// #
+    /// # let ui = CliUi::build(None).unwrap();
+    /// # let mut debugger = Debugger::build(ui).unwrap();
+    /// # // Assume debuggee is already running
+    /// #
+    /// // Step into the next function call
+    /// debugger.step_into().unwrap();
+    ///
+    /// # }}
+    /// ```
+    #[allow(clippy::missing_panics_doc)] // this function cannot panic
+    pub fn step_into(&mut self) -> Result<Feedback> {
+        if self.debuggee.is_none() {
+            return Err(DebuggerError::NoDebugee);
+        }
+        self.go_back_step_over_bp()?;
+
+        loop {
+            let rip: Addr = (self.get_reg(Register::rip)?).into();
+            let disassembly: Disassembly =
+                // unwrap is safe because we check earlier, needed because we need the mutable
+                // reference
+                self.debuggee.as_ref().unwrap().disassemble(rip, 8, true)?;
+            let next_instruction = &disassembly.inner()[0];
+            let operator = next_instruction.2[0].clone();
+
+            if operator.1 != FormatterTextKind::Mnemonic {
+                error!("could not read operator from disassembly");
+            }
+            // PERF: this is very inefficient :/ maybe remove the autostepper or work with continue
+            // somehow
+            if operator.0.trim() == "call" {
+                self.single_step()?;
+                break;
+            }
+            self.single_step()?;
+        }
+
+        Ok(Feedback::Ok)
+    }
+
+    /// Steps over a function call
+    ///
+    /// This function combines [`Self::step_into`] and [`Self::step_out`] to step over a function call.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Feedback::Ok)` - If the step-over was successful
+    /// * `Err(DebuggerError)` - If there was an error during step-over
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if:
+    /// - The debuggee is not running
+    /// - Step operations fail
+    pub fn step_over(&mut self) -> Result<Feedback> {
+        self.go_back_step_over_bp()?;
+
+        self.step_into()?;
+        match self.step_out()? {
+            // step_into stops in the called function, so this is not the function being stepped
+            // over
+            Feedback::Returned { .. } => Ok(Feedback::Ok),
+            other => Ok(other),
+        }
+    }
+
+    /// Single steps the debuggee as long as the instruction pointer stays in an address range
+    ///
+    /// This is useful to execute all instructions that belong to a source line, without the UI
+    /// having to send a [`Status::StepSingle`] for each of them. At least one instruction is
+    /// executed, even if the instruction pointer is not in the range at the start.
+    ///
+    /// Stepping stops early if the debuggee receives a signal, exits, changes a watched memory
+    /// range, or reaches an enabled [`Breakpoint`] inside of the range. In the latter case, the
+    /// debuggee is in the same state as after hitting the [`Breakpoint`] with [`Self::cont`].
+    ///
+    /// # Parameters
+    ///
+    /// * `start` - The first address of the range
+    /// * `end` - The first address after the range
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Feedback::Ok)` - If the instruction pointer left the range or stepping stopped early
+    /// * `Ok(Feedback::WatchpointHit)` - If a watched memory range was changed
+    /// * `Ok(Feedback::Exit)` - If the debuggee exited
+    /// * `Err(DebuggerError)` - If there was an error during stepping
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if:
+    /// - The debuggee is not running
+    /// - The range is empty
+    /// - ptrace operations fail
+    ///
+    /// # Examples
+    ///
+    ///
+    /// ```no_run
+    /// #[cfg(feature = "cli")]
+    /// # mod featguard { fn _do_thing() {
+    /// # use coreminer::debugger::Debugger;
+    /// # use coreminer::ui::cli::CliUi;
+    /// #
+    /// # let ui = CliUi::build(None).unwrap();
+    /// # let mut debugger = Debugger::build(ui).unwrap();
+    /// # // Assume debuggee is already running
+    /// #
+    /// // Execute the rest of a loop body that spans 0x20 bytes
+    /// let rip = debugger.get_current_addr().unwrap();
+    /// debugger.step_range(rip, rip + 0x20).unwrap();
+    ///
+    /// # }}
+    /// ```
+    pub fn step_range(&mut self, start: Addr, end: Addr) -> Result<Feedback> {
+        let range = AddrRange::new(start, end)
+            .filter(|range| !range.is_empty())
+            .ok_or(DebuggerError::EmptyAddressRange(start, end))?;
+
+        let mut steps: usize = 0;
+        loop {
+            let feedback = self.single_step()?;
+            steps += 1;
+            if !matches!(feedback, Feedback::Ok) {
+                return Ok(feedback);
+            }
+            if self.last_signal.is_some() {
+                break;
+            }
+
+            let rip: Addr = self.get_current_addr()?;
+            if !range.contains(rip) {
+                break;
+            }
+
+            let dbge = self.debuggee.as_ref().ok_or(DebuggerError::NoDebugee)?;
+            if let Some(bp) = dbge.breakpoints.get(&rip).filter(|bp| bp.is_enabled()) {
+                trace!("range stepping reached breakpoint at {rip}");
+                if !bp.is_hardware() {
+                    self.atomic_single_step()?;
+                    return self.wait_signal();
+                }
+                break;
+            }
+        }
+
+        debug!("stepped {steps} instructions in {range}");
+        Ok(Feedback::Ok)
+    }
+
+    /// Steps to the next source line, using the line table of the debug information
+    ///
+    /// The debuggee is single stepped until it reaches code of another line or another source
+    /// file. Functions without line information, like the functions of libraries and their PLT
+    /// stubs, are executed as a whole when they are called. If `over` is true, this is done for
+    /// all called functions, like `next` in GDB. Otherwise, stepping stops in a called function
+    /// with line information, like `step` in GDB.
+    ///
+    /// Stepping stops early if the debuggee receives a signal, exits, changes a watched memory
+    /// range, or reaches an enabled [`Breakpoint`], also in a called function. It also stops when
+    /// the debuggee leaves the code with line information, for example when returning from `main`.
+    ///
+    /// # Parameters
+    ///
+    /// * `over` - Whether calls are stepped over
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Feedback::Ok)` - If the debuggee reached another line or stepping stopped early
+    /// * `Ok(Feedback::WatchpointHit)` - If a watched memory range was changed
+    /// * `Ok(Feedback::Exit)` - If the debuggee exited
+    /// * `Err(DebuggerError)` - If there was an error during stepping
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if:
+    /// - The debuggee is not running
+    /// - There is no line information for the current instruction
+    /// - ptrace operations fail
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #[cfg(feature = "cli")]
+    /// # mod featguard { fn _do_thing() {
+    /// # use coreminer::debugger::Debugger;
+    /// # use coreminer::ui::cli::CliUi;
+    /// #
+    /// # let ui = CliUi::build(None).unwrap();
+    /// # let mut debugger = Debugger::build(ui).unwrap();
+    /// # // Assume debuggee is already running
+    /// #
+    /// // Execute the current line, without stopping in the functions it calls
+    /// debugger.step_line(true).unwrap();
+    ///
+    /// // Step into the function called by the next line
+    /// debugger.step_line(false).unwrap();
+    ///
+    /// # }}
+    /// ```
+    pub fn step_line(&mut self, over: bool) -> Result<Feedback> {
+        let rip: Addr = self.get_current_addr()?;
+        // rip is behind the INT3 of a software breakpoint until it is stepped over
+        let mut here = match self.last_stop {
+            Some(StopReason::Breakpoint {
+                addr,
+                hardware: false,
+            }) if rip.checked_sub(1) == Some(addr) => addr,
+            _ => rip,
+        };
+        let start = self
+            .line_at(here)?
+            .ok_or(DebuggerError::NoLineAtAddr(here))?;
+        debug!("stepping from line {} of {}", start.line, start.file);
+
+        loop {
+            let call = self.is_call(here)?;
+            let feedback = self.single_step()?;
+            if !matches!(feedback, Feedback::Ok) || self.last_signal.is_some() {
+                return Ok(feedback);
+            }
+
+            let mut rip: Addr = self.get_current_addr()?;
+            if call && (over || self.line_at(rip)?.is_none()) {
+                trace!("stepping over the function at {rip}");
+                if !self.finish_call()? {
+                    return Ok(Feedback::Ok);
+                }
+                rip = self.get_current_addr()?;
+            }
+
+            let dbge = self.debuggee.as_ref().ok_or(DebuggerError::NoDebugee)?;
+            if let Some(bp) = dbge.breakpoints.get(&rip).filter(|bp| bp.is_enabled()) {
+                trace!("line stepping reached breakpoint at {rip}");
+                if !bp.is_hardware() {
+                    self.atomic_single_step()?;
+                    return self.wait_signal();
+                }
+                break;
+            }
+
+            match self.line_at(rip)? {
+                Some(line) if line.line == start.line && line.file == start.file => here = rip,
+                Some(line) => {
+                    debug!("reached line {} of {}", line.line, line.file);
+                    break;
+                }
+                None => {
+                    debug!("left the code with line information at {rip}");
+                    break;
+                }
+            }
+        }
+
+        Ok(Feedback::Ok)
+    }
+
+    /// Continues the debuggee until it reaches `addr` in the current stack frame
+    ///
+    /// A temporary [`Breakpoint`] is set at `addr` and removed again afterwards. The debuggee only
+    /// stops there if the Canonical Frame Address of its frame is the same as when it was resumed,
+    /// so reaching `addr` in a recursive call or in another function using the same code does
+    /// not count. Unlike the frame pointer `rbp`, the CFA does not change in the prologue of a
+    /// function, or if the function does not use a frame pointer at all.
+    /// Like [`Self::cont`], the debuggee stops early at other [Breakpoints](Breakpoint), signals
+    /// and watched memory ranges.
+    ///
+    /// # Parameters
+    ///
+    /// * `addr` - The address to run to
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Feedback::Ok)` - If the debuggee reached `addr` or stopped for another reason
+    /// * `Ok(Feedback::Exit)` - If the debuggee exited
+    /// * `Ok(Feedback)` - Other [`Feedback`] of [`Self::cont`], like [`Feedback::WatchpointHit`]
+    /// * `Err(DebuggerError)` - If there was an error
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if:
+    /// - The debuggee is not running
+    /// - The temporary breakpoint cannot be set or removed
+    /// - ptrace operations fail
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #[cfg(feature = "cli")]
+    /// # mod featguard { fn _do_thing() {
+    /// # use coreminer::debugger::Debugger;
+    /// # use coreminer::ui::cli::CliUi;
+    /// #
+    /// # let ui = CliUi::build(None).unwrap();
+    /// # let mut debugger = Debugger::build(ui).unwrap();
+    /// # // Assume debuggee is already running
+    /// #
+    /// // Run to the end of a loop 0x40 bytes ahead, without stopping in each iteration
+    /// let rip = debugger.get_current_addr().unwrap();
+    /// debugger.run_until(rip + 0x40).unwrap();
+    ///
+    /// # }}
+    /// ```
+    pub fn run_until(&mut self, addr: Addr) -> Result<Feedback> {
+        self.go_back_step_over_bp()?;
+        if self.get_current_addr()? == addr {
+            // the debuggee would stop right away
+            let feedback = self.single_step()?;
+            if !matches!(feedback, Feedback::Ok) || self.last_signal.is_some() {
+                return Ok(feedback);
+            }
+        }
+
+        let frame = self.current_cfa(self.get_current_addr()?)?;
+        let dbge = self.debuggee.as_ref().ok_or(DebuggerError::NoDebugee)?;
+        let temporary = !dbge.breakpoints.contains_key(&addr);
+        if temporary {
+            self.set_bp(addr)?;
+        }
+
+        let (feedback, reached) = loop {
+            let feedback = self.cont()?;
+            let at_addr = matches!(
+                self.last_stop,
+                Some(StopReason::Breakpoint { addr: stop, .. }) if stop == addr
+            );
+            if !matches!(feedback, Feedback::Ok) || !at_addr {
+                break (feedback, false);
+            }
+            // rip is already after the INT3 of the breakpoint
+            if self.current_cfa(addr)? == frame {
+                break (feedback, true);
+            }
+            trace!("{addr} was reached in another frame");
+            self.go_back_step_over_bp()?;
+        };
+
+        if temporary && self.debuggee.is_some() {
+            if reached {
+                // the temporary breakpoint is gone, so the INT3 is not gone back over later
+                self.set_reg(Register::rip, addr.into())?;
+            }
+            self.del_bp(addr)?;
+        }
+        if reached {
+            info!("ran until {addr}");
+        }
+        Ok(feedback)
+    }
+
+    /// Returns the row of the line table for `addr`, if `addr` is in a function of the debug
+    /// information
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if the debug information cannot be read.
+    fn line_at(&self, addr: Addr) -> Result<Option<LineEntry>> {
+        let dbge = self.debuggee.as_ref().ok_or(DebuggerError::NoDebugee)?;
+        // the line table only covers the code of the executable, like the functions
+        if dbge.get_function_by_addr(addr)?.is_none() {
+            return Ok(None);
+        }
+        Ok(dbge.lines.line_of(addr).cloned())
+    }
+
+    /// Checks if the instruction at `addr` is a call
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if the code of the debuggee cannot be read.
+    fn is_call(&self, addr: Addr) -> Result<bool> {
+        let dbge = self.debuggee.as_ref().ok_or(DebuggerError::NoDebugee)?;
+        let mut code = [0; MAX_INSTRUCTION_LEN];
+        dbge.read_bytes(&mut code, addr)?;
+        if let Some(saved) = dbge.breakpoints.get(&addr).and_then(Breakpoint::saved_data) {
+            code[0] = saved;
+        }
+        let instruction = Decoder::with_ip(64, &code, addr.u64(), DecoderOptions::NONE).decode();
+        Ok(instruction.flow_control() == FlowControl::Call)
+    }
+
+    /// Runs the function that was just called until it returns to its caller
+    ///
+    /// The debuggee must be at the first instruction of the function, so that the return address
+    /// is on top of the stack. A temporary [`Breakpoint`] at the return address is used, which is
+    /// only accepted once the stack is back in the frame of the caller, so that recursive calls
+    /// also work.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(true)` - If the function returned
+    /// * `Ok(false)` - If the debuggee stopped for another reason before, like a [`Breakpoint`]
+    ///   or a signal, or exited
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if:
+    /// - The debuggee is not running
+    /// - The return address cannot be read
+    /// - The temporary breakpoint cannot be set or removed
+    fn finish_call(&mut self) -> Result<bool> {
+        let dbge = self.debuggee.as_ref().ok_or(DebuggerError::NoDebugee)?;
+        let entry_rsp = self.get_reg(Register::rsp)?;
+        let return_addr: Addr = mem_read_word(dbge.pid, Addr::from(entry_rsp))?.into();
+        let temporary = !dbge.breakpoints.contains_key(&return_addr);
+        if temporary {
+            self.set_bp(return_addr)?;
+        }
+
+        let returned = loop {
+            let feedback = self.cont()?;
+            let at_return = self.last_stop
+                == Some(StopReason::Breakpoint {
+                    addr: return_addr,
+                    hardware: false,
+                });
+            if !matches!(feedback, Feedback::Ok) || !at_return {
+                break false;
+            }
+            // the ret instruction popped the return address
+            if self.get_reg(Register::rsp)? > entry_rsp {
+                break temporary;
+            }
+            trace!("the return address {return_addr} was reached by a recursive call");
+            self.go_back_step_over_bp()?;
+        };
+
+        if temporary && self.debuggee.is_some() {
+            if returned {
+                self.set_reg(Register::rip, return_addr.into())?;
+                self.last_stop = Some(StopReason::StepDone);
+            }
+            self.del_bp(return_addr)?;
+        }
+        Ok(returned)
+    }
+
+    /// Gets a backtrace of the current call stack
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Feedback::Backtrace)` - The backtrace
+    /// * `Err(DebuggerError)` - If there was an error generating the backtrace
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if:
+    /// - The debuggee is not running
+    /// - Stack unwinding fails
+    ///
+    /// # Examples
+    ///
+    ///
+    /// ```no_run
+    /// #[cfg(feature = "cli")]
+    /// # mod featguard { fn _do_thing() {
+    /// # use coreminer::debugger::Debugger;
+    /// # use coreminer::ui::cli::CliUi;
+    /// # use coreminer::feedback::Feedback;
+    /// #
+    /// # let ui = CliUi::build(None).unwrap();
+    /// # let mut debugger = Debugger::build(ui).unwrap();
+    /// # // Assume debuggee is already running
+    /// #
+    /// // Get a backtrace of the call stack
+    /// if let Ok(Feedback::Backtrace(bt)) = debugger.backtrace() {
+    ///     for (i, frame) in bt.frames.iter().enumerate() {
+    ///         println!("#{} {} at {:?}", i, frame.name.as_deref().unwrap_or("??"), frame.addr);
+    ///     }
+    /// }
+    ///
+    /// # }}
+    /// ```
+    pub fn backtrace(&mut self) -> Result<Feedback> {
+        let dbge = self.debuggee.as_ref().ok_or(DebuggerError::NoDebugee)?;
+
+        let mut backtrace = match &dbge.core {
+            Some(core) => core.backtrace(&dbge.modules()?)?,
+            None => unwind::unwind(dbge.pid)?,
+        };
+        if backtrace.frames.iter().any(|f| f.name.is_none()) {
+            let heuristics = self.heuristic_symbols()?;
+            for frame in backtrace.frames.iter_mut().filter(|f| f.name.is_none()) {
+                if let Some((symbol, _)) = heuristics.containing(frame.addr) {
+                    frame.name = Some(symbol.name.clone());
+                    frame.start_addr = Some(symbol.addr);
+                }
+            }
+        }
+        let dbge = self.debuggee.as_mut().ok_or(DebuggerError::NoDebugee)?;
+        dbge.backtraces.push(backtrace.clone());
+
+        Ok(Feedback::Backtrace(backtrace))
+    }
+
+    /// Selects the stack frame that variables are read from and written to
+    ///
+    /// Frame 0 is the innermost frame, where the debuggee is stopped, and the default. For outer
+    /// frames, the [Registers](Register) are recovered from the call frame information of the
+    /// executable, so that variables in registers and the frame base are correct for that frame.
+    /// Variables that are in registers that could not be recovered cannot be accessed, instead of
+    /// silently using the registers of the innermost frame.
+    ///
+    /// The innermost frame is selected again whenever the debuggee is resumed.
+    ///
+    /// # Parameters
+    ///
+    /// * `frame` - The index of the frame, as in the [`Backtrace`](crate::unwind::Backtrace)
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Feedback::Ok)` - If the frame was selected
+    /// * `Err(DebuggerError)` - If the frame could not be selected
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if:
+    /// - The debuggee is not running
+    /// - The frame does not exist or cannot be unwound with the call frame information of the
+    ///   executable, for example because an outer frame is in a shared library
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #[cfg(feature = "cli")]
+    /// # mod featguard { fn _do_thing() {
+    /// # use coreminer::debugger::Debugger;
+    /// # use coreminer::ui::cli::CliUi;
+    /// # use coreminer::feedback::Feedback;
+    /// #
+    /// # let ui = CliUi::build(None).unwrap();
+    /// # let mut debugger = Debugger::build(ui).unwrap();
+    /// # // Assume debuggee is already running
+    /// #
+    /// // Read a variable of the caller of the current function
+    /// debugger.select_frame(1).unwrap();
+    /// if let Ok(Feedback::Variable(value)) = debugger.read_variable(&"count".to_string()) {
+    ///     println!("count = {:?}", value);
+    /// }
+    ///
+    /// # }}
+    /// ```
+    pub fn select_frame(&mut self, frame: usize) -> Result<Feedback> {
+        let obj = object::File::parse(&*self.executable_data)?;
+        self.frame_registers(&obj, frame)?;
+        self.selected_frame = frame;
+        info!("selected frame {frame}");
+        Ok(Feedback::Ok)
+    }
+
+    /// Gets the current instruction pointer address
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Addr)` - The current address
+    /// * `Err(DebuggerError)` - If there was an error getting the address
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if:
+    /// - The debuggee is not running
+    /// - [Register] read fails
+    ///
+    /// # Examples
+    ///
+    ///
+    /// ```no_run
+    /// #[cfg(feature = "cli")]
+    /// # mod featguard { fn _do_thing() {
+    /// # use coreminer::debugger::Debugger;
+    /// # use coreminer::ui::cli::CliUi;
+    /// #
+    /// # let ui = CliUi::build(None).unwrap();
+    /// # let debugger = Debugger::build(ui).unwrap();
+    /// # // Assume debuggee is already running
+    /// #
+    /// // Get the current instruction pointer
+    /// let curr_addr = debugger.get_current_addr().unwrap();
+    /// println!("Current IP: {}", curr_addr);
+    ///
+    /// # }}
+    /// ```
+    pub fn get_current_addr(&self) -> Result<Addr> {
+        Ok((self.get_reg(Register::rip)?).into())
+    }
+
+    /// Prepares for variable access by gathering necessary context
+    ///
+    /// This uses the selected stack frame, see [`Self::select_frame`].
+    ///
+    /// # Parameters
+    ///
+    /// * `expression` - The variable expression to access
+    ///
+    /// # Returns
+    ///
+    /// * `Ok((OwnedSymbol, OwnedSymbol, FrameInfo))` - Function, variable symbols and frame info
+    /// * `Err(DebuggerError)` - If preparation failed
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if:
+    /// - The debuggee is not running
+    /// - The current location is not in a function
+    /// - The variable is not found
+    /// - Frame information cannot be constructed
+    pub fn prepare_variable_access(
+        &self,
+        expression: &VariableExpression,
+    ) -> Result<(OwnedSymbol, OwnedSymbol, FrameInfo)> {
+        self.prepare_frame_variable_access(self.selected_frame, expression)
+    }
+
+    /// Prepares for variable access in a stack frame by gathering necessary context
+    ///
+    /// The function and the local variables are looked up for the instruction of the frame, and
+    /// the frame base, the CFA and the [Registers](Register) in the returned [`FrameInfo`] are
+    /// those of the frame, so that locations in the debug information are evaluated in its
+    /// context.
+    ///
+    /// # Parameters
+    ///
+    /// * `frame` - The index of the frame, 0 is the innermost frame
+    /// * `expression` - The variable expression to access
+    ///
+    /// # Returns
+    ///
+    /// * `Ok((OwnedSymbol, OwnedSymbol, FrameInfo))` - Function, variable symbols and frame info
+    /// * `Err(DebuggerError)` - If preparation failed
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if:
+    /// - The debuggee is not running
+    /// - The frame does not exist or cannot be unwound
+    /// - The instruction of the frame is not in a function
+    /// - The variable is not found
+    /// - Frame information cannot be constructed
+    #[allow(clippy::missing_panics_doc)] // this function cant panic
+    pub fn prepare_frame_variable_access(
+        &self,
+        frame: usize,
+        expression: &VariableExpression,
+    ) -> Result<(OwnedSymbol, OwnedSymbol, FrameInfo)> {
+        let dbge = self.debuggee.as_ref().ok_or(DebuggerError::NoDebugee)?;
+        let obj = object::File::parse(&*self.executable_data)?;
+        let (current_function, mut frame_info) = self.frame_info(&obj, frame)?;
+        let rip = frame_info.pc.ok_or(DebuggerError::NoFrameInfo)?;
+
+        // Find variable
+        let locals = dbge.get_local_variables(rip)?;
+        let vars = dbge.filter_expressions(&locals, expression)?;
+        let var = match vars.len() {
+            0 => return Err(DebuggerError::VarExprReturnedNothing(expression.clone())),
+            1 => vars[0].clone(),
+            _ => return Err(DebuggerError::AmbiguousVarExpr(expression.clone())),
+        };
+
+        // The caller is needed for the values of parameters at the entry of the function
+        frame_info.caller = match self.frame_info(&obj, frame + 1) {
+            Ok((_, caller)) => Some(Box::new(caller)),
+            Err(e) => {
+                debug!("no frame info for the caller of frame {frame}: {e}");
+                None
+            }
+        };
+
+        Ok((current_function, var, frame_info))
+    }
+
+    /// Finds the function of a stack frame and builds the [`FrameInfo`] for it
+    ///
+    /// The returned [`FrameInfo`] has no caller set.
+    fn frame_info(&self, obj: &object::File<'_>, frame: usize) -> Result<(OwnedSymbol, FrameInfo)> {
+        let dbge = self.debuggee.as_ref().ok_or(DebuggerError::NoDebugee)?;
+        let executable = self.executable()?;
+        let regs = self.frame_registers(obj, frame)?;
+        let rip = Self::frame_pc(&regs, frame)?;
+
+        // Get current function
+        let current_function = match dbge.get_function_by_addr(rip)? {
+            Some(f) if f.frame_base().is_some() => f,
+            Some(_) => {
+                return Err(DebuggerError::AttributeDoesNotExist(
+                    gimli::DW_AT_frame_base,
+                ))
+            }
+            None => return Err(DebuggerError::NotInFunction),
+        };
+
+        // Build frame info
+        let pc = executable
+            .to_module(rip)
+            .ok_or(DebuggerError::NotInFunction)?;
+        let cfa = Self::frame_cfa(obj, pc.offset, &regs)?;
+        let mut frame_info = FrameInfo::new(None, Some(cfa));
+        frame_info.pc = Some(rip);
+        // there are no live registers in a core dump
+        if frame > 0 || dbge.core.is_some() {
+            frame_info.registers = Some(regs);
+        }
+
+        let frame_base = dbge.parse_location(
+            current_function.frame_base().unwrap(), // safe: we check above if this is some
+            &frame_info,
+            current_function.encoding(),
+        )?;
+
+        let frame_base: Addr = match frame_base {
+            gimli::Location::Address { address } => address.into(),
+            other => unimplemented!(
+                "frame base DWARF location was not an address as expected: is {other:?}"
+            ),
+        };
+
+        frame_info.frame_base = Some(frame_base);
+
+        Ok((current_function, frame_info))
+    }
+
+    /// Reads the value of a variable
+    ///
+    /// The variable is looked up in the selected stack frame, see [`Self::select_frame`].
+    ///
+    /// The expression can select data inside of the variable, like a member with
+    /// `config.flags.verbose`, an element with `arr[3]` or what a pointer points to with `*ptr` or
+    /// `node->next`, see the [varexpr module](crate::varexpr). With `&` at the start, like `&count`,
+    /// the address of the data is read instead. Bit-fields are extracted from the bits they occupy,
+    /// see [`Debuggee::member_read`](crate::debuggee::Debuggee::member_read).
+    ///
+    /// The value is interpreted with the type of the variable, for example as a signed integer,
+    /// the name of an enumerator or the members of a struct, see
+    /// [`Debuggee::typed_value`](crate::debuggee::Debuggee::typed_value). Pointers are followed
+    /// to the data they point to if the [`LimitKind::PointerDepth`] limit is set, see
+    /// [`Self::set_limits`].
+    ///
+    /// # Parameters
+    ///
+    /// * `expression` - The variable expression to read
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Feedback::Variable)` - The variable value
+    /// * `Err(DebuggerError)` - If the variable could not be read
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if:
+    /// - The debuggee is not running
+    /// - The expression is invalid
+    /// - The variable is not found
+    /// - The variable cannot be accessed
+    ///
+    /// # Examples
+    ///
+    ///
+    /// ```no_run
+    /// #[cfg(feature = "cli")]
+    /// # mod featguard { fn _do_thing() {
+    /// # use coreminer::debugger::Debugger;
+    /// # use coreminer::ui::cli::CliUi;
+    /// # use coreminer::feedback::Feedback;
+    /// #
+    /// # let ui = CliUi::build(None).unwrap();
+    /// # let debugger = Debugger::build(ui).unwrap();
+    /// # // Assume debuggee is already running
+    /// #
+    /// // Read the value of a variable named "count"
+    /// if let Ok(Feedback::Variable(value)) = debugger.read_variable(&"count".to_string()) {
+    ///     println!("count = {:?}", value);
+    /// }
+    ///
+    /// # }}
+    /// ```
+    pub fn read_variable(&self, expression: &VariableExpression) -> Result<Feedback> {
+        self.read_frame_variable(self.selected_frame, expression)
+    }
+
+    /// Reads the value of a variable in a stack frame
+    ///
+    /// Like [`Self::read_variable`], but in the frame with the index `frame` instead of the
+    /// selected one. The selected frame is not changed.
+    ///
+    /// # Parameters
+    ///
+    /// * `frame` - The index of the frame, 0 is the innermost frame
+    /// * `expression` - The variable expression to read
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Feedback::Variable)` - The variable value
+    /// * `Err(DebuggerError)` - If the variable could not be read
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if:
+    /// - The debuggee is not running
+    /// - The expression is invalid
+    /// - The frame does not exist or cannot be unwound
+    /// - The variable is not found in the function of the frame
+    /// - A member is not found in the type of the variable
+    /// - An element or pointee is selected from something that is no array or pointer, or a
+    ///   pointer cannot be followed
+    /// - The variable cannot be accessed, for example because it is in a register that could
+    ///   not be recovered for the frame
+    ///
+    /// # Examples
+    ///
+    ///
+    /// ```no_run
+    /// #[cfg(feature = "cli")]
+    /// # mod featguard { fn _do_thing() {
+    /// # use coreminer::debugger::Debugger;
+    /// # use coreminer::ui::cli::CliUi;
+    /// # use coreminer::feedback::Feedback;
+    /// #
+    /// # let ui = CliUi::build(None).unwrap();
+    /// # let debugger = Debugger::build(ui).unwrap();
+    /// # // Assume debuggee is already running
+    /// #
+    /// // Read the value of "count" in the caller of the current function
+    /// if let Ok(Feedback::Variable(value)) = debugger.read_frame_variable(1, &"count".to_string()) {
+    ///     println!("count = {:?}", value);
+    /// }
+    ///
+    /// # }}
+    /// ```
+    pub fn read_frame_variable(
+        &self,
+        frame: usize,
+        expression: &VariableExpression,
+    ) -> Result<Feedback> {
+        let dbge = self.debuggee.as_ref().ok_or(DebuggerError::NoDebugee)?;
+        let expr: VarExpr = expression.parse()?;
+
+        let (_, symbol, frame_info) =
+            self.prepare_frame_variable_access(frame, &expr.variable().to_string())?;
+
+        let val = match &expr {
+            VarExpr::AddressOf(inner) => {
+                let place = dbge.expr_place(&symbol, &frame_info, inner)?;
+                let Some(addr) = place.address() else {
+                    return Err(DebuggerError::NotAddressable(inner.to_string()));
+                };
+                VariableValue::Other(addr.into())
+            }
+            other => dbge.place_read(&dbge.expr_place(&symbol, &frame_info, other)?)?,
+        };
+
+        Ok(Feedback::Variable(val))
+    }
+
+    /// Writes a value to a variable
+    ///
+    /// The variable is looked up in the selected stack frame, see [`Self::select_frame`].
+    ///
+    /// Like with [`Self::read_variable`], the expression can select data inside of the variable,
+    /// like `config.flags` or `*ptr`. Such data is only written if it is in memory, variables in
+    /// registers can only be written as a whole.
+    ///
+    /// # Parameters
+    ///
+    /// * `expression` - The variable expression to write to
+    /// * `value` - The value to write
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Feedback::Ok)` - If the write was successful
+    /// * `Err(DebuggerError)` - If the variable could not be written
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if:
+    /// - The debuggee is not running
+    /// - The expression is invalid or takes an address
+    /// - The variable is not found
+    /// - The variable cannot be accessed, or the data selected inside of it is not in memory
+    /// - The value is incompatible with the variable type
+    ///
+    /// # Examples
+    ///
+    ///
+    /// ```no_run
+    /// #[cfg(feature = "cli")]
+    /// # mod featguard { fn _do_thing() {
+    /// # use coreminer::debugger::Debugger;
+    /// # use coreminer::ui::cli::CliUi;
+    /// #
+    /// # let ui = CliUi::build(None).unwrap();
+    /// # let debugger = Debugger::build(ui).unwrap();
+    /// # // Assume debuggee is already running
+    /// #
+    /// // Set the value of a variable named "count" to 42
+    /// debugger.write_variable(&"count".to_string(), 42).unwrap();
+    ///
+    /// # }}
+    /// ```
+    pub fn write_variable(
+        &self,
+        expression: &VariableExpression,
+        value: impl Into<VariableValue>,
+    ) -> Result<Feedback> {
+        self.write_frame_variable(self.selected_frame, expression, value)
+    }
+
+    /// Writes a value to a variable in a stack frame
+    ///
+    /// Like [`Self::write_variable`], but in the frame with the index `frame` instead of the
+    /// selected one. The selected frame is not changed.
+    ///
+    /// Variables of outer frames that are kept in registers cannot be written, as their values
+    /// are saved somewhere on the stack by a callee, see
+    /// [`DebuggerError::OuterFrameRegisterWrite`].
+    ///
+    /// # Parameters
+    ///
+    /// * `frame` - The index of the frame, 0 is the innermost frame
+    /// * `expression` - The variable expression to write to
+    /// * `value` - The value to write
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Feedback::Ok)` - If the write was successful
+    /// * `Err(DebuggerError)` - If the variable could not be written
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if:
+    /// - The debuggee is not running
+    /// - The frame does not exist or cannot be unwound
+    /// - The expression is invalid or takes an address
+    /// - The variable is not found in the function of the frame
+    /// - The variable cannot be accessed, or the data selected inside of it is not in memory
+    /// - The value is incompatible with the variable type
+    ///
+    /// # Examples
+    ///
+    ///
+    /// ```no_run
+    /// #[cfg(feature = "cli")]
+    /// # mod featguard { fn _do_thing() {
+    /// # use coreminer::debugger::Debugger;
+    /// # use coreminer::ui::cli::CliUi;
+    /// #
+    /// # let ui = CliUi::build(None).unwrap();
+    /// # let debugger = Debugger::build(ui).unwrap();
+    /// # // Assume debuggee is already running
+    /// #
+    /// // Set "count" in the caller of the current function to 42
+    /// debugger.write_frame_variable(1, &"count".to_string(), 42).unwrap();
+    ///
+    /// # }}
+    /// ```
+    pub fn write_frame_variable(
+        &self,
+        frame: usize,
+        expression: &VariableExpression,
+        value: impl Into<VariableValue>,
+    ) -> Result<Feedback> {
+        let dbge = self.debuggee.as_ref().ok_or(DebuggerError::NoDebugee)?;
+        let expr: VarExpr = expression.parse()?;
+
+        let (_, var, frame_info) =
+            self.prepare_frame_variable_access(frame, &expr.variable().to_string())?;
+
+        match &expr {
+            // variables can be in registers, data inside of them is written in memory
+            VarExpr::Variable(_) => dbge.var_write(&var, &frame_info, &value.into())?,
+            VarExpr::AddressOf(_) => return Err(DebuggerError::InvalidVarExpr(expr.to_string())),
+            other => {
+                let place = dbge.expr_place(&var, &frame_info, other)?;
+                dbge.place_write(&place, other, &value.into())?;
+            }
+        }
+
+        Ok(Feedback::Ok)
+    }
+
+    /// Reads a range of memory at the specified address
+    ///
+    /// Unlike [`Self::read_mem`], the read does not fail if a part of the range is not readable,
+    /// like when the range crosses into an unmapped page. The bytes before the first unreadable
+    /// byte are returned, and the rest of the range is described in the
+    /// [`MemoryRead`](crate::memread::MemoryRead), see the [memread module](crate::memread).
+    ///
+    /// # Parameters
+    ///
+    /// * `addr` - The start of the range
+    /// * `len` - The number of bytes to read
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Feedback::Memory)` - The bytes that could be read and the unreadable rest
+    /// * `Err(DebuggerError)` - If the memory could not be read at all
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if:
+    /// - The debuggee is not running
+    /// - `len` exceeds the [`LimitKind::ReadSize`] limit
+    /// - The memory of the debuggee cannot be opened
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #[cfg(feature = "cli")]
+    /// # mod featguard { fn _do_thing() {
+    /// # use coreminer::debugger::Debugger;
+    /// # use coreminer::ui::cli::CliUi;
+    /// # use coreminer::addr::Addr;
+    /// # use coreminer::feedback::Feedback;
+    /// #
+    /// # let ui = CliUi::build(None).unwrap();
+    /// # let debugger = Debugger::build(ui).unwrap();
+    /// # // Assume debuggee is already running
+    /// #
+    /// let rsp = debugger.resolve_addr(&"$rsp".parse().unwrap()).unwrap();
+    /// if let Ok(Feedback::Memory(read)) = debugger.read_mem_range(rsp, 0x100) {
+    ///     println!("read {} bytes", read.bytes.len());
+    ///     if let Some(rest) = read.unreadable {
+    ///         println!("{} bytes at {} are not readable", rest.len, rest.addr);
+    ///     }
+    /// }
+    ///
+    /// # }}
+    /// ```
+    pub fn read_mem_range(&self, addr: Addr, len: usize) -> Result<Feedback> {
+        let dbge = self.debuggee.as_ref().ok_or(DebuggerError::NoDebugee)?;
+        self.limits.check(LimitKind::ReadSize, len)?;
+
+        Ok(Feedback::Memory(match &dbge.core {
+            Some(core) => core.read_memory(addr, len),
+            None => crate::memread::read_memory(dbge.pid, addr, len)?,
+        }))
+    }
+
+    /// Reads a single [Word] from memory at the specified address
+    ///
+    /// # Parameters
+    ///
+    /// * `addr` - The address to read from
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Feedback::Word)` - The value read from memory
+    /// * `Err(DebuggerError)` - If the memory could not be read
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if:
+    /// - The debuggee is not running
+    /// - The memory address is invalid
+    /// - The memory cannot be accessed
+    ///
+    /// # Examples
+    ///
+    ///
+    /// ```no_run
+    /// #[cfg(feature = "cli")]
+    /// # mod featguard { fn _do_thing() {
+    /// # use coreminer::debugger::Debugger;
+    /// # use coreminer::ui::cli::CliUi;
+    /// # use coreminer::addr::Addr;
+    /// # use coreminer::feedback::Feedback;
+    /// #
+    /// # let ui = CliUi::build(None).unwrap();
+    /// # let debugger = Debugger::build(ui).unwrap();
+    /// # // Assume debuggee is already running
+    /// #
+    /// // Read a word from memory at address 0x1000
+    /// if let Ok(Feedback::Word(value)) = debugger.read_mem(Addr::from(0x1000usize)) {
+    ///     println!("Memory at 0x1000: {:#x}", value);
+    /// }
+    ///
+    /// # }}
+    /// ```
+    pub fn read_mem(&self, addr: Addr) -> Result<Feedback> {
+        let dbge = self.debuggee.as_ref().ok_or(DebuggerError::NoDebugee)?;
+
+        let w = dbge.read_word(addr)?;
+
+        Ok(Feedback::Word(w))
+    }
+
+    /// Writes a [Word] to memory at the specified address
+    ///
+    /// # Parameters
+    ///
+    /// * `addr` - The address to write to
+    /// * `value` - The value to write
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Feedback::Ok)` - If the write was successful
+    /// * `Err(DebuggerError)` - If the memory could not be written
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if:
+    /// - The debuggee is not running
+    /// - The memory address is invalid
+    /// - The memory cannot be accessed
+    ///
+    /// # Examples
+    ///
+    ///
+    /// ```no_run
+    /// #[cfg(feature = "cli")]
+    /// # mod featguard { fn _do_thing() {
+    /// # use coreminer::debugger::Debugger;
+    /// # use coreminer::ui::cli::CliUi;
+    /// # use coreminer::addr::Addr;
+    /// #
+    /// # let ui = CliUi::build(None).unwrap();
+    /// # let debugger = Debugger::build(ui).unwrap();
+    /// # // Assume debuggee is already running
+    /// #
+    /// // Write the value 0x42 to memory at address 0x1000
+    /// debugger.write_mem(Addr::from(0x1000usize), 0x42).unwrap();
+    ///
+    /// # }}
+    /// ```
+    pub fn write_mem(&self, addr: Addr, value: Word) -> Result<Feedback> {
+        let dbge = self.debuggee.as_ref().ok_or(DebuggerError::NoDebugee)?;
+
+        if self.write_verification.memory {
+            mem_write_word_verified(dbge.pid, addr, value)?;
+        } else {
+            mem_write_word(dbge.pid, addr, value)?;
+        }
+
+        Ok(Feedback::Ok)
+    }
+
+    /// Gets the value of a register
+    ///
+    /// # Parameters
+    ///
+    /// * `r` - The register to get
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(u64)` - The register value
+    /// * `Err(DebuggerError)` - If the register could not be read
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if:
+    /// - The debuggee is not running
+    /// - Register access fails
+    ///
+    /// # Examples
+    ///
+    ///
+    /// ```no_run
+    /// #[cfg(feature = "cli")]
+    /// # mod featguard { fn _do_thing() {
+    /// # use coreminer::debugger::Debugger;
+    /// # use coreminer::ui::cli::CliUi;
+    /// # use coreminer::Register;
+    /// #
+    /// # let ui = CliUi::build(None).unwrap();
+    /// # let debugger = Debugger::build(ui).unwrap();
+    /// # // Assume debuggee is already running
+    /// #
+    /// // Get the value of the rax register
+    /// let rax = debugger.get_reg(Register::rax).unwrap();
+    /// println!("RAX: {:#x}", rax);
+    ///
+    /// # }}
+    /// ```
+    pub fn get_reg(&self, r: Register) -> Result<u64> {
+        let dbge = self.debuggee.as_ref().ok_or(DebuggerError::NoDebugee)?;
+
+        match &dbge.core {
+            Some(core) => Ok(crate::reg_value(&core.registers(), r)),
+            None => crate::get_reg(dbge.pid, r),
+        }
+    }
+
+    /// Sets the value of a register
+    ///
+    /// # Parameters
+    ///
+    /// * `r` - The register to set
+    /// * `v` - The value to set
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Feedback::Ok)` - If the register was set successfully
+    /// * `Err(DebuggerError)` - If the register could not be set
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if:
+    /// - The debuggee is not running
+    /// - Register access fails
+    ///
+    /// # Examples
+    ///
+    ///
+    /// ```no_run
+    /// #[cfg(feature = "cli")]
+    /// # mod featguard { fn _do_thing() {
+    /// # use coreminer::debugger::Debugger;
+    /// # use coreminer::ui::cli::CliUi;
+    /// # use coreminer::Register;
+    /// #
+    /// # let ui = CliUi::build(None).unwrap();
+    /// # let debugger = Debugger::build(ui).unwrap();
+    /// # // Assume debuggee is already running
+    /// #
+    /// // Set the value of the rax register to 0x42
+    /// debugger.set_reg(Register::rax, 0x42).unwrap();
+    ///
+    /// # }}
+    /// ```
+    pub fn set_reg(&self, r: Register, v: u64) -> Result<Feedback> {
+        let dbge = self.debuggee.as_ref().ok_or(DebuggerError::NoDebugee)?;
+        crate::set_reg(dbge.pid, r, v)?;
+        Ok(Feedback::Ok)
+    }
+
+    /// Gets the current stack of the debugged process
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Feedback::Stack)` - The stack
+    /// * `Err(DebuggerError)` - If the stack could not be retrieved
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if:
+    /// - The debuggee is not running
+    /// - Stack memory cannot be accessed
+    ///
+    /// # Examples
+    ///
+    ///
+    /// ```no_run
+    /// #[cfg(feature = "cli")]
+    /// # mod featguard { fn _do_thing() {
+    /// # use coreminer::debugger::Debugger;
+    /// # use coreminer::ui::cli::CliUi;
+    /// # use coreminer::feedback::Feedback;
+    /// #
+    /// # let ui = CliUi::build(None).unwrap();
+    /// # let debugger = Debugger::build(ui).unwrap();
+    /// # // Assume debuggee is already running
+    /// #
+    /// // Get the current stack
+    /// if let Ok(Feedback::Stack(stack)) = debugger.get_stack() {
+    ///     println!("Stack: {}", stack);
+    /// }
+    ///
+    /// # }}
+    /// ```
+    pub fn get_stack(&self) -> Result<Feedback> {
+        let dbge = self.debuggee.as_ref().ok_or(DebuggerError::NoDebugee)?;
+
+        let stack = dbge.get_stack()?;
+        Ok(Feedback::Stack(stack))
+    }
+
+    /// Gets the process memory map
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Feedback::ProcessMap)` - The process memory map
+    /// * `Err(DebuggerError)` - If the memory map could not be retrieved
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if:
+    /// - The debuggee is not running
+    /// - The memory map cannot be accessed
+    ///
+    /// # Examples
+    ///
+    ///
+    /// ```no_run
+    /// #[cfg(feature = "cli")]
+    /// # mod featguard { fn _do_thing() {
+    /// # use coreminer::debugger::Debugger;
+    /// # use coreminer::ui::cli::CliUi;
+    /// # use coreminer::feedback::Feedback;
+    /// #
+    /// # let ui = CliUi::build(None).unwrap();
+    /// # let debugger = Debugger::build(ui).unwrap();
+    /// # // Assume debuggee is already running
+    /// #
+    /// // Get the process memory map
+    /// if let Ok(Feedback::ProcessMap(map)) = debugger.get_process_map() {
+    ///     println!("{:?}", map);
+    /// }
+    ///
+    /// # }}
+    /// ```
+    pub fn get_process_map(&self) -> Result<Feedback> {
+        let dbge = self.debuggee.as_ref().ok_or(DebuggerError::NoDebugee)?;
+
+        let pm = dbge.get_process_map()?;
+
+        Ok(Feedback::ProcessMap(pm))
+    }
+
+    /// Gets the modules loaded into the debuggee
+    ///
+    /// The modules are the ELF files mapped into the debuggee, with the address they are
+    /// loaded at and their load bias, see the [modules module](crate::modules).
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Feedback::Modules)` - The loaded modules, ordered by their base address
+    /// * `Err(DebuggerError)` - If the modules could not be determined
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if:
+    /// - The debuggee is not running
+    /// - The memory map cannot be accessed
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #[cfg(feature = "cli")]
+    /// # mod featguard { fn _do_thing() {
+    /// # use coreminer::debugger::Debugger;
+    /// # use coreminer::ui::cli::CliUi;
+    /// # use coreminer::feedback::Feedback;
+    /// #
+    /// # let ui = CliUi::build(None).unwrap();
+    /// # let debugger = Debugger::build(ui).unwrap();
+    /// # // Assume debuggee is already running
+    /// #
+    /// if let Ok(Feedback::Modules(modules)) = debugger.list_modules() {
+    ///     for module in modules {
+    ///         println!("{} is loaded at {}", module.path, module.base);
+    ///     }
+    /// }
+    ///
+    /// # }}
+    /// ```
+    pub fn list_modules(&self) -> Result<Feedback> {
+        let dbge = self.debuggee.as_ref().ok_or(DebuggerError::NoDebugee)?;
+
+        Ok(Feedback::Modules(dbge.modules()?))
+    }
+
+    /// Adds the symbols of a separate ELF file at a chosen address
+    ///
+    /// This is for code that has no symbols in the debuggee, like a firmware blob, JIT compiled
+    /// code or a module that was unpacked into anonymous memory, when its symbols are available
+    /// in a separate file. The symbols are merged with those of the executable, so they can be
+    /// used for breakpoints, backtraces and listings, see [`Debuggee::add_symbol_file`].
+    ///
+    /// # Parameters
+    ///
+    /// * `path` - The ELF file with the symbols
+    /// * `base` - The address that the lowest segment of the file is at in the debuggee
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Feedback::Modules)` - The added file with the load bias of its symbols
+    /// * `Err(DebuggerError)` - If the symbols could not be added
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if:
+    /// - The debuggee is not running
+    /// - The file cannot be read or is not an ELF file
+    /// - The debug information of the file cannot be parsed
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #[cfg(feature = "cli")]
+    /// # mod featguard { fn _do_thing() {
+    /// # use std::path::Path;
+    /// # use coreminer::addr::Addr;
+    /// # use coreminer::debugger::Debugger;
+    /// # use coreminer::ui::cli::CliUi;
+    /// #
+    /// # let ui = CliUi::build(None).unwrap();
+    /// # let mut debugger = Debugger::build(ui).unwrap();
+    /// # // Assume debuggee is already running
+    /// #
+    /// let firmware = Path::new("firmware.elf");
+    /// debugger
+    ///     .add_symbol_file(firmware, Addr::from(0x7f00_0000_0000usize))
+    ///     .unwrap();
+    /// let entry = debugger.resolve_addr(&"fw_entry".parse().unwrap()).unwrap();
+    ///
+    /// # }}
+    /// ```
+    pub fn add_symbol_file(&mut self, path: &Path, base: Addr) -> Result<Feedback> {
+        let dbge = self.debuggee.as_mut().ok_or(DebuggerError::NoDebugee)?;
+        let module = dbge.add_symbol_file(path, base)?;
+        info!(
+            "added the symbols of {} at {} (bias {:#x})",
+            module.path, module.base, module.bias
+        );
+
+        Ok(Feedback::Modules(vec![module]))
+    }
+
+    /// Lists the functions of the debuggee whose names match a pattern
+    ///
+    /// The functions are taken from the debug information, the symbol tables of the executable
+    /// and of the [added symbol files](Self::add_symbol_file) and, for stripped executables, the
+    /// heuristics, see the [symbol index module](crate::symindex).
+    ///
+    /// # Parameters
+    ///
+    /// * `pattern` - A [`NamePattern`], like `str*`, or a part of the names, empty for all
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Feedback::Functions)` - The matching functions, ordered by address
+    /// * `Err(DebuggerError)` - If the functions could not be listed
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if:
+    /// - The debuggee is not running
+    /// - The memory map or auxiliary vector cannot be read
+    /// - More functions match than [`Limits::max_symbol_results`]
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #[cfg(feature = "cli")]
+    /// # mod featguard { fn _do_thing() {
+    /// # use coreminer::debugger::Debugger;
+    /// # use coreminer::ui::cli::CliUi;
+    /// # use coreminer::feedback::Feedback;
+    /// #
+    /// # let ui = CliUi::build(None).unwrap();
+    /// # let debugger = Debugger::build(ui).unwrap();
+    /// # // Assume debuggee is already running
+    /// #
+    /// if let Ok(Feedback::Functions(functions)) = debugger.list_functions("init") {
+    ///     for function in functions {
+    ///         println!("{} at {}", function.name, function.low);
+    ///     }
+    /// }
+    ///
+    /// # }}
+    /// ```
+    pub fn list_functions(&self, pattern: &str) -> Result<Feedback> {
+        let dbge = self.debuggee.as_ref().ok_or(DebuggerError::NoDebugee)?;
+        let obj = object::File::parse(&*self.executable_data).ok();
+        let overlays = dbge
+            .symbol_files()
+            .iter()
+            .map(|f| Ok((f.info.object_info()?, &f.module)))
+            .collect::<Result<Vec<_>>>()?;
+        let mut symbol_tables: Vec<(&object::File<'_>, &LoadedModule)> = Vec::new();
+        if let Some(obj) = &obj {
+            symbol_tables.push((obj, dbge.get_executable()));
+        }
+        symbol_tables.extend(overlays.iter().map(|(obj, module)| (obj, *module)));
+
+        let functions = symindex::functions(
+            dbge.symbols(),
+            &symbol_tables,
+            &self.heuristic_symbols()?,
+            &NamePattern::new(pattern),
+        );
+        self.limits
+            .check(LimitKind::SymbolResults, functions.len())?;
+        Ok(Feedback::Functions(functions))
+    }
+
+    /// Lists the types of the debuggee whose names match a pattern
+    ///
+    /// The structs, unions, enums, typedefs and base types are taken from the debug information,
+    /// see [`symindex::types`].
+    ///
+    /// # Parameters
+    ///
+    /// * `pattern` - A [`NamePattern`], like `*_t`, or a part of the names, empty for all
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Feedback::Types)` - The matching types, ordered by name
+    /// * `Err(DebuggerError)` - If the types could not be listed
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if:
+    /// - The debuggee is not running
+    /// - More types match than [`Limits::max_symbol_results`]
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #[cfg(feature = "cli")]
+    /// # mod featguard { fn _do_thing() {
+    /// # use coreminer::debugger::Debugger;
+    /// # use coreminer::ui::cli::CliUi;
+    /// # use coreminer::feedback::Feedback;
+    /// #
+    /// # let ui = CliUi::build(None).unwrap();
+    /// # let debugger = Debugger::build(ui).unwrap();
+    /// # // Assume debuggee is already running
+    /// #
+    /// if let Ok(Feedback::Types(types)) = debugger.list_types("*_t") {
+    ///     for info in types {
+    ///         println!("{} has {:?} bytes", info.name, info.size);
+    ///     }
+    /// }
+    ///
+    /// # }}
+    /// ```
+    pub fn list_types(&self, pattern: &str) -> Result<Feedback> {
+        let dbge = self.debuggee.as_ref().ok_or(DebuggerError::NoDebugee)?;
+
+        let types = symindex::types(dbge.symbols(), &NamePattern::new(pattern));
+        self.limits.check(LimitKind::SymbolResults, types.len())?;
+        Ok(Feedback::Types(types))
+    }
+
+    /// Lists the source lines around an address expression
+    ///
+    /// A source line like `hello.c:12` is listed as it is, other expressions are resolved to an
+    /// address and the line of its instruction is listed. Without an expression, the line of the
+    /// instruction of the selected frame is listed. The source is taken from the debug
+    /// information if it is embedded there, or read from disk otherwise, see the
+    /// [source module](crate::source).
+    ///
+    /// # Parameters
+    ///
+    /// * `expr` - The address expression, or [`None`] for the selected frame
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Feedback::Source)` - The lines around the line, which is marked
+    /// * `Err(DebuggerError)` - If the source could not be listed
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if:
+    /// - The debuggee is not running
+    /// - The expression cannot be resolved
+    /// - No source line is known for the address
+    /// - The source is neither embedded nor readable from disk
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #[cfg(feature = "cli")]
+    /// # mod featguard { fn _do_thing() {
+    /// # use coreminer::debugger::Debugger;
+    /// # use coreminer::ui::cli::CliUi;
+    /// # use coreminer::feedback::Feedback;
+    /// #
+    /// # let ui = CliUi::build(None).unwrap();
+    /// # let debugger = Debugger::build(ui).unwrap();
+    /// # // Assume debuggee is already running
+    /// #
+    /// if let Ok(Feedback::Source(listing)) = debugger.list_source(Some(&"main".parse().unwrap())) {
+    ///     println!("{listing}");
+    /// }
+    ///
+    /// # }}
+    /// ```
+    pub fn list_source(&self, expr: Option<&AddrExpr>) -> Result<Feedback> {
+        let dbge = self.debuggee.as_ref().ok_or(DebuggerError::NoDebugee)?;
+
+        let (file, line) = if let Some(AddrExpr::Line { file, line }) = expr {
+            (file.clone(), *line)
+        } else {
+            let addr = if let Some(expr) = expr {
+                self.resolve_addr(expr)?
+            } else {
+                let obj = object::File::parse(&*self.executable_data)?;
+                let regs = self.frame_registers(&obj, self.selected_frame)?;
+                Self::frame_pc(&regs, self.selected_frame)?
+            };
+            let entry = dbge
+                .lines
+                .line_of(addr)
+                .ok_or(DebuggerError::NoLineAtAddr(addr))?;
+            (entry.file.clone(), entry.line)
+        };
+
+        Ok(Feedback::Source(source::listing(
+            &dbge.lines,
+            &file,
+            line,
+            source::DEFAULT_CONTEXT_LINES,
+        )?))
+    }
+
+    /// Lists the global and static variables of the debuggee whose names match a pattern
+    ///
+    /// The variables of the executable are taken from its debug information, which knows their
+    /// types, and its symbol table. For the shared libraries, their exported data objects are
+    /// listed. See the [symbol index module](crate::symindex).
+    ///
+    /// # Parameters
+    ///
+    /// * `pattern` - A [`NamePattern`], like `g_*`, or a part of the names, empty for all
+    /// * `module` - A [`NamePattern`] for the file names of the modules, like `libc`, or [`None`]
+    ///   for all modules
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Feedback::Variables)` - The matching variables, ordered by address
+    /// * `Err(DebuggerError)` - If the variables could not be listed
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if:
+    /// - The debuggee is not running
+    /// - The memory map cannot be read
+    /// - More variables match than [`Limits::max_symbol_results`]
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #[cfg(feature = "cli")]
+    /// # mod featguard { fn _do_thing() {
+    /// # use coreminer::debugger::Debugger;
+    /// # use coreminer::ui::cli::CliUi;
+    /// # use coreminer::feedback::Feedback;
+    /// #
+    /// # let ui = CliUi::build(None).unwrap();
+    /// # let debugger = Debugger::build(ui).unwrap();
+    /// # // Assume debuggee is already running
+    /// #
+    /// if let Ok(Feedback::Variables(variables)) = debugger.list_variables("std", Some("libc")) {
+    ///     for variable in variables {
+    ///         println!("{} at {}", variable.name, variable.addr);
+    ///     }
+    /// }
+    ///
+    /// # }}
+    /// ```
+    pub fn list_variables(&self, pattern: &str, module: Option<&str>) -> Result<Feedback> {
+        let dbge = self.debuggee.as_ref().ok_or(DebuggerError::NoDebugee)?;
+        let executable = dbge.get_executable();
+        let pattern = NamePattern::new(pattern);
+        let module = module.map(NamePattern::new);
+        let selected = |path: &str| {
+            module
+                .as_ref()
+                .map_or(true, |m| symindex::module_matches(path, m))
+        };
+
+        let mut variables = Vec::new();
+        if selected(&executable.path) {
+            let obj = object::File::parse(&*self.executable_data).ok();
+            variables = symindex::variables(dbge.symbols(), obj.as_ref(), executable, &pattern);
+        }
+        for loaded in dbge.modules()? {
+            if loaded.base == executable.base || !selected(&loaded.path) {
+                continue;
+            }
+            match symindex::exported_variables(&loaded, &pattern) {
+                Ok(exports) => variables.extend(exports),
+                Err(e) => debug!("could not read the exports of {}: {e}", loaded.path),
+            }
+        }
+        variables.sort_by(|a, b| a.addr.cmp(&b.addr).then_with(|| a.name.cmp(&b.name)));
+        self.limits
+            .check(LimitKind::SymbolResults, variables.len())?;
+        Ok(Feedback::Variables(variables))
+    }
+
+    /// Gets the environment of the debuggee
+    ///
+    /// The environment is read from `/proc/<pid>/environ`, so it is the environment the debuggee
+    /// was started with.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Feedback::Environment)` - The environment variables of the debuggee
+    /// * `Err(DebuggerError)` - If the environment could not be read
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if:
+    /// - The debuggee is not running
+    /// - `/proc/<pid>/environ` cannot be read
+    ///
+    /// # Examples
+    ///
+    ///
+    /// ```no_run
+    /// #[cfg(feature = "cli")]
+    /// # mod featguard { fn _do_thing() {
+    /// # use coreminer::debugger::Debugger;
+    /// # use coreminer::ui::cli::CliUi;
+    /// # use coreminer::feedback::Feedback;
+    /// #
+    /// # let ui = CliUi::build(None).unwrap();
+    /// # let debugger = Debugger::build(ui).unwrap();
+    /// # // Assume debuggee is already running
+    /// #
+    /// if let Ok(Feedback::Environment(env)) = debugger.get_environment() {
+    ///     println!("LD_PRELOAD: {:?}", env.get("LD_PRELOAD"));
+    /// }
+    ///
+    /// # }}
+    /// ```
+    pub fn get_environment(&self) -> Result<Feedback> {
+        let dbge = self.debuggee.as_ref().ok_or(DebuggerError::NoDebugee)?;
+
+        let env = dbge.get_environment()?;
+
+        Ok(Feedback::Environment(env))
+    }
+
+    /// Gets the open file descriptors of the debuggee
+    ///
+    /// The targets of the file descriptors are resolved: files have their path, pipes their
+    /// inode and sockets their protocol and addresses.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Feedback::FileDescriptors)` - The open file descriptors of the debuggee
+    /// * `Err(DebuggerError)` - If the file descriptors could not be read
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if:
+    /// - The debuggee is not running
+    /// - `/proc/<pid>/fd` cannot be read
+    ///
+    /// # Examples
+    ///
+    ///
+    /// ```no_run
+    /// #[cfg(feature = "cli")]
+    /// # mod featguard { fn _do_thing() {
+    /// # use coreminer::debugger::Debugger;
+    /// # use coreminer::ui::cli::CliUi;
+    /// # use coreminer::feedback::Feedback;
+    /// #
+    /// # let ui = CliUi::build(None).unwrap();
+    /// # let debugger = Debugger::build(ui).unwrap();
+    /// # // Assume debuggee is already running
+    /// #
+    /// if let Ok(Feedback::FileDescriptors(fds)) = debugger.get_file_descriptors() {
+    ///     for fd in fds {
+    ///         println!("{fd}");
+    ///     }
+    /// }
+    ///
+    /// # }}
+    /// ```
+    pub fn get_file_descriptors(&self) -> Result<Feedback> {
+        let dbge = self.debuggee.as_ref().ok_or(DebuggerError::NoDebugee)?;
+
+        let fds = dbge.get_file_descriptors()?;
+
+        Ok(Feedback::FileDescriptors(fds))
+    }
+
+    /// Gets the status and resource usage of the debuggee
+    ///
+    /// This includes the state of the process, the number of threads, the memory usage, context
+    /// switches, page faults and CPU times.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Feedback::ProcessInfo)` - The status of the debuggee
+    /// * `Err(DebuggerError)` - If the status could not be read
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if:
+    /// - The debuggee is not running
+    /// - `/proc/<pid>/status` or `/proc/<pid>/stat` cannot be read or parsed
+    ///
+    /// # Examples
+    ///
+    ///
+    /// ```no_run
+    /// #[cfg(feature = "cli")]
+    /// # mod featguard { fn _do_thing() {
+    /// # use coreminer::debugger::Debugger;
+    /// # use coreminer::ui::cli::CliUi;
+    /// # use coreminer::feedback::Feedback;
+    /// #
+    /// # let ui = CliUi::build(None).unwrap();
+    /// # let debugger = Debugger::build(ui).unwrap();
+    /// # // Assume debuggee is already running
+    /// #
+    /// if let Ok(Feedback::ProcessInfo(info)) = debugger.get_process_info() {
+    ///     println!("the debuggee has {} threads", info.threads);
+    /// }
+    ///
+    /// # }}
+    /// ```
+    pub fn get_process_info(&self) -> Result<Feedback> {
+        let dbge = self.debuggee.as_ref().ok_or(DebuggerError::NoDebugee)?;
+
+        let info = dbge.get_process_info()?;
+
+        Ok(Feedback::ProcessInfo(info))
+    }
+
+    /// Gets the auxiliary vector of the debuggee
+    ///
+    /// The auxiliary vector contains, among other things, the entry point of the executable
+    /// (`AT_ENTRY`), which is the most reliable way to find it for position independent
+    /// executables.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Feedback::AuxVector)` - The auxiliary vector of the debuggee
+    /// * `Err(DebuggerError)` - If the auxiliary vector could not be read
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if:
+    /// - The debuggee is not running
+    /// - `/proc/<pid>/auxv` cannot be read
+    ///
+    /// # Examples
+    ///
+    ///
+    /// ```no_run
+    /// #[cfg(feature = "cli")]
+    /// # mod featguard { fn _do_thing() {
+    /// # use coreminer::debugger::Debugger;
+    /// # use coreminer::ui::cli::CliUi;
+    /// # use coreminer::feedback::Feedback;
+    /// #
+    /// # let ui = CliUi::build(None).unwrap();
+    /// # let mut debugger = Debugger::build(ui).unwrap();
+    /// # // Assume debuggee is already running
+    /// #
+    /// if let Ok(Feedback::AuxVector(auxv)) = debugger.get_auxv() {
+    ///     if let Some(entry) = auxv.entry {
+    ///         debugger.set_bp(entry).unwrap();
+    ///     }
+    /// }
+    ///
+    /// # }}
+    /// ```
+    pub fn get_auxv(&self) -> Result<Feedback> {
+        let dbge = self.debuggee.as_ref().ok_or(DebuggerError::NoDebugee)?;
+
+        let auxv = dbge.get_auxv()?;
+
+        Ok(Feedback::AuxVector(auxv))
+    }
+
+    /// Lists the GOT slots of the executable with the addresses they currently point to
+    ///
+    /// See the [`got`](crate::got) module for details about the Global Offset Table.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Feedback::Got)` - The GOT entries of the executable
+    /// * `Err(DebuggerError)` - If the GOT could not be read
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if:
+    /// - The debuggee is not running
+    /// - The executable cannot be parsed
+    /// - The memory of the debuggee cannot be read
+    ///
+    /// # Examples
+    ///
+    ///
+    /// ```no_run
+    /// #[cfg(feature = "cli")]
+    /// # mod featguard { fn _do_thing() {
+    /// # use coreminer::debugger::Debugger;
+    /// # use coreminer::ui::cli::CliUi;
+    /// # use coreminer::feedback::Feedback;
+    /// #
+    /// # let ui = CliUi::build(None).unwrap();
+    /// # let debugger = Debugger::build(ui).unwrap();
+    /// # // Assume debuggee is already running
+    /// #
+    /// if let Ok(Feedback::Got(entries)) = debugger.get_got() {
+    ///     for entry in entries {
+    ///         println!("{} is resolved to {}", entry.symbol, entry.target);
+    ///     }
+    /// }
+    ///
+    /// # }}
+    /// ```
+    pub fn get_got(&self) -> Result<Feedback> {
+        let dbge = self.debuggee.as_ref().ok_or(DebuggerError::NoDebugee)?;
+        let (load_bias, slots) = self.got_slots()?;
+        let map = dbge.get_process_map()?;
+
+        let entries = slots
+            .iter()
+            .map(|slot| slot.read(dbge.pid, load_bias, &map))
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Feedback::Got(entries))
+    }
+
+    /// Overwrites the GOT slot of a symbol, so that the executable uses `target` instead
+    ///
+    /// After this, calls from the executable to the function `symbol` of a shared library jump to
+    /// `target`. All slots for `symbol` are overwritten.
+    ///
+    /// # Parameters
+    ///
+    /// * `symbol` - The name of the imported symbol, for example `puts`
+    /// * `target` - The address the slot should point to
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Feedback::Addr)` - The address that was stored in the slot before the hook
+    /// * `Err(DebuggerError)` - If the slot could not be overwritten
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if:
+    /// - The debuggee is not running
+    /// - The executable has no GOT slot for `symbol`
+    /// - The memory of the debuggee cannot be written
+    ///
+    /// # Examples
+    ///
+    ///
+    /// ```no_run
+    /// #[cfg(feature = "cli")]
+    /// # mod featguard { fn _do_thing() {
+    /// # use coreminer::debugger::Debugger;
+    /// # use coreminer::ui::cli::CliUi;
+    /// # use coreminer::feedback::Feedback;
+    /// # use coreminer::addr::Addr;
+    /// #
+    /// # let ui = CliUi::build(None).unwrap();
+    /// # let debugger = Debugger::build(ui).unwrap();
+    /// # // Assume debuggee is already running
+    /// #
+    /// // redirect all calls to puts to our own code
+    /// if let Ok(Feedback::Addr(original)) = debugger.hook_got("puts", Addr::from(0x1337000usize)) {
+    ///     // ... and later restore the original function
+    ///     debugger.hook_got("puts", original).unwrap();
+    /// }
+    ///
+    /// # }}
+    /// ```
+    pub fn hook_got(&self, symbol: &str, target: Addr) -> Result<Feedback> {
+        let dbge = self.debuggee.as_ref().ok_or(DebuggerError::NoDebugee)?;
+        let (load_bias, slots) = self.got_slots()?;
+
+        let mut original = None;
+        for slot in slots.iter().filter(|s| s.symbol == symbol) {
+            let old = slot.hook(dbge.pid, load_bias, target)?;
+            debug!(
+                "hooked GOT slot of {symbol} at {}: {old} -> {target}",
+                slot.slot_addr(load_bias)
+            );
+            original.get_or_insert(old);
+        }
+
+        original
+            .map(Feedback::Addr)
+            .ok_or_else(|| DebuggerError::GotEntryNotFound(symbol.to_string()))
+    }
+
+    /// Injects machine code into newly allocated executable memory of the debuggee
+    ///
+    /// The memory is allocated by making the debuggee execute an `mmap` syscall. If `run` is
+    /// `true`, the code is executed right away and the debuggee is then put back to where it was
+    /// stopped before, with its original registers. See the [`inject`] module for details.
+    ///
+    /// # Parameters
+    ///
+    /// * `code` - The machine code to inject
+    /// * `run` - Whether the injected code should be executed immediately
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Feedback::Injection)` - Where the code was injected, and the value of `rax` after
+    ///   it ran, if it was run
+    /// * `Err(DebuggerError)` - If the code could not be injected or run
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if:
+    /// - The debuggee is not running
+    /// - The debuggee is stopped at the entry of a syscall, where the registers cannot be
+    ///   repurposed for another syscall
+    /// - The `mmap` syscall fails
+    /// - The injected code does not reach its end, for example because it crashed
+    ///
+    /// # Examples
+    ///
+    ///
+    /// ```no_run
+    /// #[cfg(feature = "cli")]
+    /// # mod featguard { fn _do_thing() {
+    /// # use coreminer::debugger::Debugger;
+    /// # use coreminer::ui::cli::CliUi;
+    /// # use coreminer::feedback::Feedback;
+    /// #
+    /// # let ui = CliUi::build(None).unwrap();
+    /// # let debugger = Debugger::build(ui).unwrap();
+    /// # // Assume debuggee is already running
+    /// #
+    /// // mov eax, 42
+    /// if let Ok(Feedback::Injection(injection)) = debugger.inject_code(&[0xb8, 0x2a, 0, 0, 0], true) {
+    ///     assert_eq!(injection.result, Some(42));
+    /// }
+    ///
+    /// # }}
+    /// ```
+    pub fn inject_code(&self, code: &[u8], run: bool) -> Result<Feedback> {
+        let dbge = self.debuggee.as_ref().ok_or(DebuggerError::NoDebugee)?;
+        if dbge.syscall_stop.is_some_and(|s| s.is_entry()) {
+            return Err(DebuggerError::InjectionFailed(
+                "the debuggee is stopped at the entry of a syscall".to_string(),
+            ));
+        }
+
+        let mut injection = inject::inject_code(dbge.pid, code)?;
+        info!("injected {} bytes at {}", injection.len, injection.addr);
+        if run {
+            let regs = inject::run_injected(dbge.pid, &injection)?;
+            injection.result = Some(regs.rax);
+        }
+
+        Ok(Feedback::Injection(injection))
+    }
+
+    /// Calls a function in the debuggee and returns its return value
+    ///
+    /// The arguments are passed in registers and on the stack like for a call from C, so only
+    /// integer and pointer arguments are supported. The function runs until it returns, then the
+    /// debuggee is put back to where it was stopped before, with its original registers. See
+    /// [`inject::call_function`] for details. The value of `rax` is returned and recorded in the
+    /// value history.
+    ///
+    /// # Parameters
+    ///
+    /// * `target` - The function, as name or address expression like `main` or `0x401126`
+    /// * `args` - The arguments of the function
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Feedback::Recorded)` - The value of `rax` after the function returned
+    /// * `Err(DebuggerError)` - If the function could not be called
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if:
+    /// - The debuggee is not running
+    /// - `target` is not a valid address expression or cannot be resolved
+    /// - The debuggee is stopped at the entry of a syscall, which would be skipped
+    /// - The function does not return, for example because it crashed or hit a breakpoint
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #[cfg(feature = "cli")]
+    /// # mod featguard { fn _do_thing() {
+    /// # use coreminer::debugger::Debugger;
+    /// # use coreminer::ui::cli::CliUi;
+    /// #
+    /// # let ui = CliUi::build(None).unwrap();
+    /// # let mut debugger = Debugger::build(ui).unwrap();
+    /// # // Assume debuggee is already running
+    /// #
+    /// // let the debuggee compute fib(10)
+    /// let feedback = debugger.call_function("fib", &[10]).unwrap();
+    /// println!("{feedback}");
+    ///
+    /// # }}
+    /// ```
+    pub fn call_function(&mut self, target: &str, args: &[u64]) -> Result<Feedback> {
+        let addr = self.resolve_addr(&target.parse()?)?;
+        let dbge = self.debuggee.as_ref().ok_or(DebuggerError::NoDebugee)?;
+        if dbge.syscall_stop.is_some_and(|s| s.is_entry()) {
+            return Err(DebuggerError::InjectionFailed(
+                "the debuggee is stopped at the entry of a syscall".to_string(),
+            ));
+        }
+
+        let regs = inject::call_function(dbge.pid, addr, args)?;
+        info!("called {target} at {addr}, it returned {:#x}", regs.rax);
+        Ok(self.record_value(Feedback::Word(regs.rax as Word)))
+    }
+
+    /// Assembles instructions and overwrites the code at `addr` with them
+    ///
+    /// See the [`assemble`](crate::assemble) module for the supported syntax. Breakpoints in the
+    /// patched range stay in place and will restore the patched code when they are removed.
+    ///
+    /// If the assembled code does not exactly replace whole instructions, a warning is logged and
+    /// [`Patch::len_matches`] is `false`. Nothing is padded automatically, add `nop`s to the
+    /// instructions if needed.
+    ///
+    /// # Parameters
+    ///
+    /// * `addr` - Address of the first instruction to overwrite
+    /// * `text` - The instructions to assemble, separated by `;`
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Feedback::Patch)` - The written bytes and the bytes of the replaced instructions
+    /// * `Err(DebuggerError)` - If the instructions could not be assembled or written
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if:
+    /// - The debuggee is not running
+    /// - The instructions cannot be assembled
+    /// - The memory of the debuggee cannot be read or written
+    ///
+    /// # Examples
+    ///
+    ///
+    /// ```no_run
+    /// #[cfg(feature = "cli")]
+    /// # mod featguard { fn _do_thing() {
+    /// # use coreminer::debugger::Debugger;
+    /// # use coreminer::ui::cli::CliUi;
+    /// # use coreminer::feedback::Feedback;
+    /// # use coreminer::addr::Addr;
+    /// #
+    /// # let ui = CliUi::build(None).unwrap();
+    /// # let mut debugger = Debugger::build(ui).unwrap();
+    /// # // Assume debuggee is already running
+    /// #
+    /// // skip a check by replacing a conditional jump with an unconditional one
+    /// if let Ok(Feedback::Patch(patch)) = debugger.patch_asm(Addr::from(0x401040usize), "jmp 0x401050") {
+    ///     assert!(patch.len_matches());
+    /// }
+    ///
+    /// # }}
+    /// ```
+    pub fn patch_asm(&mut self, addr: Addr, text: &str) -> Result<Feedback> {
+        let dbge = self.debuggee.as_mut().ok_or(DebuggerError::NoDebugee)?;
+        let bytes = assemble(text, addr)?;
+
+        // find the whole instructions that are overwritten
+        let original = dbge.disassemble(addr, bytes.len() + MAX_INSTRUCTION_LEN, false)?;
+        let mut replaced = Vec::new();
+        for (_, instruction, _, _) in original.inner() {
+            if replaced.len() >= bytes.len() {
+                break;
+            }
+            replaced.extend_from_slice(instruction);
+        }
+        if replaced.len() != bytes.len() {
+            warn!(
+                "the patch at {addr} is {} bytes long, but replaces {} bytes of instructions",
+                bytes.len(),
+                replaced.len()
+            );
+        }
+
+        // breakpoints would restore the old code when removed, so they are set again afterwards
+        let range = AddrRange::with_len(addr, bytes.len())
+            .ok_or(DebuggerError::AddressOverflow(addr, bytes.len()))?;
+        let bp_addrs: Vec<Addr> = dbge
+            .breakpoints
+            .iter()
+            .filter(|(a, bp)| range.contains(**a) && bp.is_enabled())
+            .map(|(a, _)| *a)
+            .collect();
+        for bp_addr in &bp_addrs {
+            if let Some(bp) = dbge.breakpoints.get_mut(bp_addr) {
+                bp.disable()?;
+            }
+        }
+        let written = if self.write_verification.memory {
+            mem_write_verified(&bytes, dbge.pid, addr)
+        } else {
+            mem_write(&bytes, dbge.pid, addr)
+        };
+        for bp_addr in &bp_addrs {
+            if let Some(bp) = dbge.breakpoints.get_mut(bp_addr) {
+                bp.enable()?;
+            }
+        }
+        written?;
+
+        Ok(Feedback::Patch(Patch {
+            addr,
+            bytes,
+            replaced,
+        }))
+    }
+
+    /// Dumps a loaded module from the memory of the debuggee into an ELF file
+    ///
+    /// See the [`dump`](crate::dump) module for details on how the file is reconstructed.
+    ///
+    /// # Parameters
+    ///
+    /// * `module` - The module to dump, as a path, file name or the start of a file name (like
+    ///   `libc`). If [`None`], the executable is dumped.
+    /// * `output` - Path of the ELF file to write
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Feedback::ModuleDump)` - Information about the dumped module
+    /// * `Err(DebuggerError)` - If the module could not be dumped
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if:
+    /// - The debuggee is not running
+    /// - The module is not loaded or has no valid ELF header in memory
+    /// - The output file cannot be written
+    ///
+    /// # Examples
+    ///
+    ///
+    /// ```no_run
+    /// #[cfg(feature = "cli")]
+    /// # mod featguard { fn _do_thing() {
+    /// # use coreminer::debugger::Debugger;
+    /// # use coreminer::ui::cli::CliUi;
+    /// # use coreminer::feedback::Feedback;
+    /// # use std::path::Path;
+    /// #
+    /// # let ui = CliUi::build(None).unwrap();
+    /// # let mut debugger = Debugger::build(ui).unwrap();
+    /// # // Assume debuggee is already running and has unpacked itself
+    /// #
+    /// debugger.dump_module(None, Path::new("/tmp/unpacked")).unwrap();
+    /// debugger.dump_module(Some("libc"), Path::new("/tmp/libc.so")).unwrap();
+    ///
+    /// # }}
+    /// ```
+    pub fn dump_module(&mut self, module: Option<&str>, output: &Path) -> Result<Feedback> {
+        let dbge = self.debuggee.as_mut().ok_or(DebuggerError::NoDebugee)?;
+        let map = dbge.get_process_map()?;
+
+        // the dump should contain the original code, not our int3 instructions
+        let enabled: Vec<Addr> = dbge
+            .breakpoints
+            .iter()
+            .filter(|(_, bp)| bp.is_enabled())
+            .map(|(addr, _)| *addr)
+            .collect();
+        for addr in &enabled {
+            if let Some(bp) = dbge.breakpoints.get_mut(addr) {
+                bp.disable()?;
+            }
+        }
+        let dump = dump::dump_module(dbge.pid, &map, module, output);
+        for addr in &enabled {
+            if let Some(bp) = dbge.breakpoints.get_mut(addr) {
+                bp.enable()?;
+            }
+        }
+        let dump = dump?;
+        info!("dumped {} to {}", dump.module, dump.output.display());
+
+        Ok(Feedback::ModuleDump(dump))
+    }
+
+    /// Records the execution of a program with rr
+    ///
+    /// The program runs until it exits, independently of the debuggee, see [`rr::record`]. The
+    /// recording can be replayed with [`Self::rr_replay`].
+    ///
+    /// # Parameters
+    ///
+    /// * `program` - The program to record
+    /// * `args` - The arguments of the program, without the program itself
+    /// * `trace_dir` - The directory that rr writes the trace to, which must not exist
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Feedback::RrRecording)` - The trace directory and how the program exited
+    ///
+    /// # Errors
+    ///
+    /// Fails with [`DebuggerError::RrNotInstalled`] if rr is not installed, or if it cannot be
+    /// started.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #[cfg(feature = "cli")]
+    /// # mod featguard { fn _do_thing() {
+    /// # use coreminer::debugger::Debugger;
+    /// # use coreminer::gdbremote::Resume;
+    /// # use coreminer::ui::cli::CliUi;
+    /// # use std::path::Path;
+    /// #
+    /// # let ui = CliUi::build(None).unwrap();
+    /// # let mut debugger = Debugger::build(ui).unwrap();
+    /// #
+    /// let trace = Path::new("/tmp/ls-trace");
+    /// debugger.rr_record(Path::new("/bin/ls"), &[c"-la".into()], trace).unwrap();
+    /// debugger.rr_replay(trace, 1234).unwrap();
+    /// debugger.replay_resume(Resume::Continue).unwrap();
+    /// debugger.replay_resume(Resume::ReverseStep).unwrap();
+    ///
+    /// # }}
+    /// ```
+    pub fn rr_record(
+        &mut self,
+        program: &Path,
+        args: &[CString],
+        trace_dir: &Path,
+    ) -> Result<Feedback> {
+        let recording = rr::record(program, args, trace_dir)?;
+        info!(
+            "recorded {} into {}: {}",
+            program.display(),
+            recording.trace_dir.display(),
+            recording.exit
+        );
+        Ok(Feedback::RrRecording(recording))
+    }
+
+    /// Starts replaying an rr trace, which is then controlled with the `replay_*` functions
+    ///
+    /// The replay is driven through the gdbserver of `rr replay`, which listens on `port`, see
+    /// [`rr::Replay`]. A replay that was started before is stopped first, and the replay is
+    /// stopped when the debugger quits, see [`Self::cleanup`]. The replay is independent of the
+    /// debuggee.
+    ///
+    /// # Parameters
+    ///
+    /// * `trace_dir` - The trace directory of a recording, see [`Self::rr_record`]
+    /// * `port` - The TCP port for the gdbserver
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Feedback::ReplayStopped)` - The replay was started and stopped at the beginning of
+    ///   the recording
+    ///
+    /// # Errors
+    ///
+    /// Fails with [`DebuggerError::RrNotInstalled`] if rr is not installed, or if it cannot be
+    /// started or connected to.
+    pub fn rr_replay(&mut self, trace_dir: &Path, port: u16) -> Result<Feedback> {
+        self.replay = None;
+        let mut replay = rr::Replay::start(trace_dir, port)?;
+        let stop = replay.remote().stop_reason()?;
+        self.replay = Some(replay);
+        Ok(Feedback::ReplayStopped(stop))
+    }
+
+    /// Resumes the rr replay until it stops again
+    ///
+    /// Running backwards stops at breakpoints just like running forwards, and at the start of the
+    /// recording.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Feedback::ReplayStopped)` - Where and why the replay stopped
+    ///
+    /// # Errors
+    ///
+    /// Fails with [`DebuggerError::NoReplay`] if there is no replay, see [`Self::rr_replay`], or
+    /// if the gdbserver fails.
+    pub fn replay_resume(&mut self, how: gdbremote::Resume) -> Result<Feedback> {
+        let stop = self.replay_remote()?.resume(how)?;
+        info!("replay {stop}");
+        Ok(Feedback::ReplayStopped(stop))
+    }
+
+    /// Reads the registers of the rr replay
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Feedback::Registers)` - The general purpose registers
+    ///
+    /// # Errors
+    ///
+    /// Fails with [`DebuggerError::NoReplay`] if there is no replay, see [`Self::rr_replay`], or
+    /// if the gdbserver fails.
+    pub fn replay_registers(&mut self) -> Result<Feedback> {
+        let regs = self.replay_remote()?.read_registers()?;
+        Ok(Feedback::Registers(regs.into()))
+    }
+
+    /// Reads `len` bytes at `addr` of the rr replay
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Feedback::Variable)` - The bytes as [`VariableValue::Bytes`]
+    ///
+    /// # Errors
+    ///
+    /// Fails with [`DebuggerError::NoReplay`] if there is no replay, see [`Self::rr_replay`], or
+    /// if the memory cannot be read.
+    pub fn replay_read_mem(&mut self, addr: Addr, len: usize) -> Result<Feedback> {
+        let bytes = self.replay_remote()?.read_memory(addr, len)?;
+        Ok(Feedback::Variable(VariableValue::Bytes(bytes)))
+    }
+
+    /// Sets a breakpoint at `addr` in the rr replay
+    ///
+    /// # Errors
+    ///
+    /// Fails with [`DebuggerError::NoReplay`] if there is no replay, see [`Self::rr_replay`], or
+    /// if the gdbserver fails.
+    pub fn replay_set_breakpoint(&mut self, addr: Addr) -> Result<Feedback> {
+        self.replay_remote()?.set_breakpoint(addr)?;
+        Ok(Feedback::Ok)
+    }
+
+    /// Removes the breakpoint at `addr` from the rr replay
+    ///
+    /// # Errors
+    ///
+    /// Fails with [`DebuggerError::NoReplay`] if there is no replay, see [`Self::rr_replay`], or
+    /// if the gdbserver fails.
+    pub fn replay_del_breakpoint(&mut self, addr: Addr) -> Result<Feedback> {
+        self.replay_remote()?.remove_breakpoint(addr)?;
+        Ok(Feedback::Ok)
+    }
+
+    /// Stops the rr replay, see [`Self::rr_replay`]
+    ///
+    /// # Errors
+    ///
+    /// Fails with [`DebuggerError::NoReplay`] if there is no replay.
+    pub fn stop_replay(&mut self) -> Result<Feedback> {
+        self.replay.take().ok_or(DebuggerError::NoReplay)?;
+        Ok(Feedback::Ok)
+    }
+
+    /// Gets the connection to the gdbserver of the rr replay
+    fn replay_remote(&mut self) -> Result<&mut GdbRemote> {
+        Ok(self
+            .replay
+            .as_mut()
+            .ok_or(DebuggerError::NoReplay)?
+            .remote())
+    }
+
+    /// Writes a core dump of the stopped debuggee
+    ///
+    /// The core dump has the registers of all threads and all readable memory of the debuggee, so
+    /// an interesting state can be analyzed later, for example with [`Self::open_core`] or with
+    /// other debuggers. The thread that was stopped by a signal is saved with that signal. See
+    /// [`generate_core`](coredump::generate_core) for the layout of the file.
+    ///
+    /// # Parameters
+    ///
+    /// * `output` - Path of the core dump to write
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Feedback::CoreGenerated)` - Information about the written core dump
+    /// * `Err(DebuggerError)` - If the core dump could not be written
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if:
+    /// - The debuggee is not running
+    /// - The memory of the debuggee cannot be read
+    /// - The output file cannot be written
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #[cfg(feature = "cli")]
+    /// # mod featguard { fn _do_thing() {
+    /// # use coreminer::debugger::Debugger;
+    /// # use coreminer::ui::cli::CliUi;
+    /// # use std::path::Path;
+    /// #
+    /// # let ui = CliUi::build(None).unwrap();
+    /// # let mut debugger = Debugger::build(ui).unwrap();
+    /// # // Assume debuggee is already running and stopped
+    /// #
+    /// debugger.generate_core(Path::new("/tmp/core.snapshot")).unwrap();
+    ///
+    /// # }}
+    /// ```
+    pub fn generate_core(&mut self, output: &Path) -> Result<Feedback> {
+        let signal = match self.last_stop {
+            Some(StopReason::Signal(signal)) => signal,
+            _ => 0,
+        };
+        let dbge = self.debuggee.as_mut().ok_or(DebuggerError::NoDebugee)?;
+        let map = dbge.get_process_map()?;
+
+        let mut threads = Vec::new();
+        for tid in dbge.threads.keys() {
+            match ptrace::getregs(*tid) {
+                Ok(regs) => threads.push(CoreThread {
+                    tid: tid.as_raw(),
+                    signal: if *tid == dbge.pid { signal } else { 0 },
+                    regs,
+                }),
+                Err(e) => warn!("could not save the registers of thread {tid}: {e}"),
+            }
+        }
+        // the first thread is the one that is shown when the core dump is opened
+        threads.sort_by_key(|thread| thread.tid != dbge.pid.as_raw());
+
+        // the core dump should contain the original code, not our int3 instructions
+        let enabled: Vec<Addr> = dbge
+            .breakpoints
+            .iter()
+            .filter(|(_, bp)| bp.is_enabled())
+            .map(|(addr, _)| *addr)
+            .collect();
+        for addr in &enabled {
+            if let Some(bp) = dbge.breakpoints.get_mut(addr) {
+                bp.disable()?;
+            }
+        }
+        let core = coredump::generate_core(dbge.pid, &threads, &map, output);
+        for addr in &enabled {
+            if let Some(bp) = dbge.breakpoints.get_mut(addr) {
+                bp.enable()?;
+            }
+        }
+        let core = core?;
+        info!(
+            "saved a core dump of {} to {}",
+            core.pid,
+            core.output.display()
+        );
+
+        Ok(Feedback::CoreGenerated(core))
+    }
+
+    /// Finds all instructions in the executable memory of the debuggee that reference an address
+    ///
+    /// This is useful to find out where a function is called from or where a global variable is
+    /// used. See the [`xref`](crate::xref) module for what counts as a reference.
+    ///
+    /// # Parameters
+    ///
+    /// * `target` - The address to find references to
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Feedback::Xrefs)` - The referencing instructions
+    /// * `Err(DebuggerError)` - If the search failed
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if the debuggee is not running or its memory map cannot be read.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #[cfg(feature = "cli")]
+    /// # mod featguard { fn _do_thing() {
+    /// # use coreminer::debugger::Debugger;
+    /// # use coreminer::ui::cli::CliUi;
+    /// # use coreminer::feedback::Feedback;
+    /// # use coreminer::addr::Addr;
+    /// #
+    /// # let ui = CliUi::build(None).unwrap();
+    /// # let mut debugger = Debugger::build(ui).unwrap();
+    /// # // Assume debuggee is already running
+    /// #
+    /// if let Ok(Feedback::Xrefs(xrefs)) = debugger.find_xrefs(Addr::from(0x401136usize)) {
+    ///     for xref in xrefs {
+    ///         println!("{xref}");
+    ///     }
+    /// }
+    ///
+    /// # }}
+    /// ```
+    pub fn find_xrefs(&self, target: Addr) -> Result<Feedback> {
+        let dbge = self.debuggee.as_ref().ok_or(DebuggerError::NoDebugee)?;
+        let xrefs = dbge.find_xrefs(target)?;
+        info!("found {} references to {target}", xrefs.len());
+        Ok(Feedback::Xrefs(xrefs))
+    }
+
+    /// Exports the call graph of the executable in the DOT language of graphviz
+    ///
+    /// The graph has an edge from each function of the executable to each function it calls
+    /// directly, see [`Debuggee::call_graph`] and the [`dot`](crate::dot) module.
+    ///
+    /// # Parameters
+    ///
+    /// * `output` - Path of the DOT file to write, or [`None`] to return the DOT text
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Feedback::Dot)` - The DOT text, if no `output` was given
+    /// * `Ok(Feedback::Ok)` - If the DOT file was written
+    /// * `Err(DebuggerError)` - If the graph could not be exported
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if:
+    /// - The debuggee is not running
+    /// - The memory map of the debuggee cannot be read
+    /// - The output file cannot be written
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #[cfg(feature = "cli")]
+    /// # mod featguard { fn _do_thing() {
+    /// # use coreminer::debugger::Debugger;
+    /// # use coreminer::ui::cli::CliUi;
+    /// # use std::path::Path;
+    /// #
+    /// # let ui = CliUi::build(None).unwrap();
+    /// # let mut debugger = Debugger::build(ui).unwrap();
+    /// # // Assume debuggee is already running
+    /// #
+    /// // Render it with: dot -Tsvg /tmp/calls.dot > /tmp/calls.svg
+    /// debugger.export_call_graph(Some(Path::new("/tmp/calls.dot"))).unwrap();
+    ///
+    /// # }}
+    /// ```
+    pub fn export_call_graph(&self, output: Option<&Path>) -> Result<Feedback> {
+        let dbge = self.debuggee.as_ref().ok_or(DebuggerError::NoDebugee)?;
+        let graph = dbge.call_graph()?;
+        info!(
+            "the call graph has {} functions and {} edges",
+            graph.nodes().count(),
+            graph.edges().count()
+        );
+        Self::export_dot(&graph, output)
+    }
+
+    /// Exports the backtraces taken so far as a call graph in the DOT language of graphviz
+    ///
+    /// Every [`Self::backtrace`] of the current debuggee is recorded. The recorded backtraces
+    /// are merged into one graph with an edge from each caller to its callee, see
+    /// [`CallGraph::from_backtraces`]. Taking backtraces at breakpoints in interesting places
+    /// shows how the debuggee got there.
+    ///
+    /// # Parameters
+    ///
+    /// * `output` - Path of the DOT file to write, or [`None`] to return the DOT text
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Feedback::Dot)` - The DOT text, if no `output` was given
+    /// * `Ok(Feedback::Ok)` - If the DOT file was written
+    /// * `Err(DebuggerError)` - If the graph could not be exported
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if:
+    /// - The debuggee is not running
+    /// - The output file cannot be written
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #[cfg(feature = "cli")]
+    /// # mod featguard { fn _do_thing() {
+    /// # use coreminer::debugger::Debugger;
+    /// # use coreminer::ui::cli::CliUi;
+    /// # use coreminer::feedback::Feedback;
+    /// #
+    /// # let ui = CliUi::build(None).unwrap();
+    /// # let mut debugger = Debugger::build(ui).unwrap();
+    /// # // Assume debuggee is already running
+    /// #
+    /// for _ in 0..10 {
+    ///     debugger.cont().unwrap();
+    ///     debugger.backtrace().unwrap();
+    /// }
+    /// if let Ok(Feedback::Dot(dot)) = debugger.export_backtrace_graph(None) {
+    ///     println!("{dot}");
+    /// }
+    ///
+    /// # }}
+    /// ```
+    pub fn export_backtrace_graph(&self, output: Option<&Path>) -> Result<Feedback> {
+        let dbge = self.debuggee.as_ref().ok_or(DebuggerError::NoDebugee)?;
+        let graph = CallGraph::from_backtraces(&dbge.backtraces);
+        info!(
+            "merged {} backtraces into a graph with {} edges",
+            dbge.backtraces.len(),
+            graph.edges().count()
+        );
+        Self::export_dot(&graph, output)
+    }
+
+    /// Writes `graph` as DOT into `output`, or returns the DOT text if there is no `output`
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if the output file cannot be written.
+    fn export_dot(graph: &CallGraph, output: Option<&Path>) -> Result<Feedback> {
+        let dot = graph.to_string();
+        let Some(output) = output else {
+            return Ok(Feedback::Dot(dot));
+        };
+        std::fs::write(output, dot + "\n")?;
+        info!("wrote the graph to {}", output.display());
+        Ok(Feedback::Ok)
+    }
+
+    /// Installs a [`Uprobe`] at an address
+    ///
+    /// The uprobe counts how often the instruction at `addr` is executed, without stopping the
+    /// debuggee. If `capture` is set, the argument registers are captured at each hit, so `addr`
+    /// should be the first instruction of a function. The results are read with
+    /// [`Self::read_uprobes`]. See the [uprobe module](crate::uprobe) for the requirements and
+    /// limits.
+    ///
+    /// Installing a [`Uprobe`] at an address that already has one replaces the old one.
+    ///
+    /// # Parameters
+    ///
+    /// * `addr` - The address of the instruction to probe
+    /// * `capture` - Whether to capture the argument registers of each hit
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Feedback::Ok)` - If the uprobe was installed
+    /// * `Err(DebuggerError)` - If the uprobe could not be installed
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if:
+    /// - The debuggee is not running
+    /// - `addr` is not mapped from a file
+    /// - The kernel does not support uprobes or the debugger may not use them
+    ///
+    /// # Examples
+    ///
+    ///
+    /// ```no_run
+    /// #[cfg(feature = "cli")]
+    /// # mod featguard { fn _do_thing() {
+    /// # use coreminer::debugger::Debugger;
+    /// # use coreminer::ui::cli::CliUi;
+    /// # use coreminer::addr::Addr;
+    /// # use coreminer::feedback::Feedback;
+    /// #
+    /// # let ui = CliUi::build(None).unwrap();
+    /// # let mut debugger = Debugger::build(ui).unwrap();
+    /// # // Assume debuggee is already running
+    /// #
+    /// // Count the calls of the function at 0x401136 and capture their arguments
+    /// debugger.set_uprobe(Addr::from(0x401136usize), true).unwrap();
+    /// debugger.cont().unwrap();
+    ///
+    /// if let Ok(Feedback::Uprobes(probes)) = debugger.read_uprobes() {
+    ///     println!("called {} times", probes[0].count);
+    /// }
+    ///
+    /// # }}
+    /// ```
+    pub fn set_uprobe(&mut self, addr: Addr, capture: bool) -> Result<Feedback> {
+        let dbge = self.debuggee.as_mut().ok_or(DebuggerError::NoDebugee)?;
+        let target = UprobeTarget::from_addr(&dbge.get_process_map()?, addr)?;
+        // the old uprobe must be gone before the new one is installed at the same place
+        dbge.uprobes.remove(&addr);
+        let probe = Uprobe::open(dbge.pid, addr, target, capture)?;
+        info!("installed uprobe at {addr} ({})", probe.target());
+        dbge.uprobes.insert(addr, probe);
+
+        Ok(Feedback::Ok)
+    }
+
+    /// Removes the [`Uprobe`] at an address
+    ///
+    /// # Parameters
+    ///
+    /// * `addr` - The probed address
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Feedback::Ok)` - If the uprobe was removed
+    /// * `Err(DebuggerError)` - If there was an error removing the uprobe
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if the debuggee is not running.
+    pub fn del_uprobe(&mut self, addr: Addr) -> Result<Feedback> {
+        let dbge = self.debuggee.as_mut().ok_or(DebuggerError::NoDebugee)?;
+
+        if dbge.uprobes.remove(&addr).is_none() {
+            warn!("removed a uprobe at {addr} that did not exist");
+        }
+
+        Ok(Feedback::Ok)
+    }
+
+    /// Reads what the [Uprobes](Uprobe) have seen
+    ///
+    /// The captured hits are only reported once, the counts are kept.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Feedback::Uprobes)` - The counts and captured hits of all uprobes, ordered by
+    ///   address
+    /// * `Err(DebuggerError)` - If the uprobes could not be read
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if:
+    /// - The debuggee is not running
+    /// - The counter of a uprobe cannot be read
+    pub fn read_uprobes(&mut self) -> Result<Feedback> {
+        let dbge = self.debuggee.as_mut().ok_or(DebuggerError::NoDebugee)?;
+        let mut stats = dbge
+            .uprobes
+            .values_mut()
+            .map(Uprobe::stats)
+            .collect::<Result<Vec<_>>>()?;
+        stats.sort_by_key(|s| s.addr);
+
+        Ok(Feedback::Uprobes(stats))
     }
 
-    /// Handles other signals from the debuggee
+    /// Resets and starts the hardware [performance counters](crate::perf) of the debuggee
+    ///
+    /// The counters are opened the first time they are started. They count the instructions,
+    /// cycles and cache misses of the debuggee until [`Self::stop_perf_counters`] is called, so
+    /// starting them at one breakpoint and stopping them at the next one measures the work in
+    /// between. See the [perf module](crate::perf) for the requirements and limits.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Feedback::Ok)` - If the counters were started
+    /// * `Err(DebuggerError)` - If the counters could not be started
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if:
+    /// - The debuggee is not running
+    /// - The CPU has no supported performance counters or the debugger may not use them
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #[cfg(feature = "cli")]
+    /// # mod featguard { fn _do_thing() {
+    /// # use coreminer::debugger::Debugger;
+    /// # use coreminer::ui::cli::CliUi;
+    /// # use coreminer::feedback::Feedback;
+    /// #
+    /// # let ui = CliUi::build(None).unwrap();
+    /// # let mut debugger = Debugger::build(ui).unwrap();
+    /// # // Assume debuggee is already running and stopped at a breakpoint
+    /// #
+    /// debugger.start_perf_counters().unwrap();
+    /// debugger.cont().unwrap(); // until the next breakpoint
+    /// debugger.stop_perf_counters().unwrap();
+    ///
+    /// if let Ok(Feedback::PerfCounters(values)) = debugger.read_perf_counters() {
+    ///     for value in values {
+    ///         println!("{value}");
+    ///     }
+    /// }
+    ///
+    /// # }}
+    /// ```
+    pub fn start_perf_counters(&mut self) -> Result<Feedback> {
+        let dbge = self.debuggee.as_mut().ok_or(DebuggerError::NoDebugee)?;
+        let counters = match dbge.perf_counters.take() {
+            Some(counters) => counters,
+            None => PerfCounters::open(dbge.pid)?,
+        };
+        dbge.perf_counters.insert(counters).start()?;
+        info!("started the performance counters");
+
+        Ok(Feedback::Ok)
+    }
+
+    /// Stops the hardware [performance counters](crate::perf) of the debuggee
+    ///
+    /// The values are kept until the counters are started again.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Feedback::Ok)` - If the counters were stopped
+    /// * `Err(DebuggerError)` - If the counters could not be stopped
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if:
+    /// - The debuggee is not running
+    /// - The counters were never started
+    pub fn stop_perf_counters(&mut self) -> Result<Feedback> {
+        let dbge = self.debuggee.as_mut().ok_or(DebuggerError::NoDebugee)?;
+        let counters = dbge
+            .perf_counters
+            .as_mut()
+            .ok_or(DebuggerError::PerfCountersNotStarted)?;
+        counters.stop()?;
+        info!("stopped the performance counters");
+
+        Ok(Feedback::Ok)
+    }
+
+    /// Reads the hardware [performance counters](crate::perf) of the debuggee
+    ///
+    /// The counters can be read while they are running or after they were stopped.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Feedback::PerfCounters)` - The values of the supported counters
+    /// * `Err(DebuggerError)` - If the counters could not be read
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if:
+    /// - The debuggee is not running
+    /// - The counters were never started
+    pub fn read_perf_counters(&self) -> Result<Feedback> {
+        let dbge = self.debuggee.as_ref().ok_or(DebuggerError::NoDebugee)?;
+        let counters = dbge
+            .perf_counters
+            .as_ref()
+            .ok_or(DebuggerError::PerfCountersNotStarted)?;
+
+        Ok(Feedback::PerfCounters(counters.read()?))
+    }
+
+    /// Parses the GOT slots of the executable and determines its load bias
+    fn got_slots(&self) -> Result<(usize, Vec<GotRelocation>)> {
+        let obj = object::File::parse(&*self.executable_data)?;
+        Ok((self.executable()?.bias, got_relocations(&obj)))
+    }
+
+    /// Computes the Canonical Frame Address of a frame
+    ///
+    /// The CFA is computed from the call frame information of the executable, see
+    /// [`canonical_frame_address`]. If there is none for `pc`, the frame is assumed to use a
+    /// frame pointer, which makes the CFA `rbp + 16`.
+    fn frame_cfa(obj: &object::File<'_>, pc: u64, regs: &FrameRegisters) -> Result<Addr> {
+        let get_reg = |r| {
+            regs.get(&r)
+                .copied()
+                .ok_or(DebuggerError::RegisterNotRecovered(r))
+        };
+        if let Some(cfa) = canonical_frame_address(obj, pc, get_reg)? {
+            return Ok(cfa);
+        }
+        debug!("no call frame information for {pc:#x}, assuming a frame pointer");
+        Ok(Addr::from(get_reg(Register::rbp)?) + 16usize)
+    }
+
+    /// Computes the Canonical Frame Address of the innermost frame, with `pc` as its program
+    /// counter
+    ///
+    /// `pc` is where the debuggee is stopped, which is not `rip` right after a [`Breakpoint`] was
+    /// hit. See [`Self::frame_cfa`].
+    fn current_cfa(&self, pc: Addr) -> Result<Addr> {
+        let obj = object::File::parse(&*self.executable_data)?;
+        let regs = self.frame_registers(&obj, 0)?;
+        let pc = self
+            .executable()?
+            .to_module(pc)
+            .ok_or(DebuggerError::NotInFunction)?;
+        Self::frame_cfa(&obj, pc.offset, &regs)
+    }
+
+    /// Recovers the [Registers](Register) of the stack frame with the index `frame`
+    ///
+    /// Frame 0 has the live registers of the debuggee. The registers of the outer frames are
+    /// recovered one frame after the other with [`unwind_registers`], using the call frame
+    /// information of the module that contains the program counter of each frame.
+    ///
+    /// # Errors
+    ///
+    /// This function fails if the debuggee is not running, or the frame cannot be reached, which
+    /// is [`DebuggerError::NoSuchFrame`].
+    fn frame_registers(&self, obj: &object::File<'_>, frame: usize) -> Result<FrameRegisters> {
+        let dbge = self.debuggee.as_ref().ok_or(DebuggerError::NoDebugee)?;
+        let executable = self.executable()?;
+
+        // all registers that can be used in DWARF expressions
+        let mut regs = FrameRegisters::new();
+        for dwarf_reg in 0..=16 {
+            let reg = Register::try_from(gimli::Register(dwarf_reg))?;
+            regs.insert(reg, self.get_reg(reg)?);
+        }
+
+        let modules = if frame > 0 {
+            dbge.modules()?
+        } else {
+            Vec::new()
+        };
+        let read_word = |addr| Ok(dbge.read_word(addr)? as u64);
+        for depth in 0..frame {
+            let pc = Self::frame_pc(&regs, depth)?;
+            let module = module_at(&modules, pc).ok_or(DebuggerError::NoSuchFrame(frame))?;
+            let offset = module
+                .to_module(pc)
+                .ok_or(DebuggerError::NoSuchFrame(frame))?
+                .offset;
+            // frames in shared libraries, like after an abort, are unwound with their own call
+            // frame information
+            let caller = if module.base == executable.base {
+                unwind_registers(obj, offset, &regs, read_word)?
+            } else {
+                let data = std::fs::read(&module.path)?;
+                unwind_registers(&object::File::parse(&*data)?, offset, &regs, read_word)?
+            };
+            regs = caller.ok_or(DebuggerError::NoSuchFrame(frame))?;
+        }
+        Ok(regs)
+    }
+
+    /// Gets the address to look up the function and call frame information of a frame with
+    ///
+    /// For outer frames, `rip` is the return address, which can already be in the next function
+    /// if the call was the last instruction. The address of the call instruction is used
+    /// instead.
+    fn frame_pc(regs: &FrameRegisters, frame: usize) -> Result<Addr> {
+        let rip = regs
+            .get(&Register::rip)
+            .ok_or(DebuggerError::RegisterNotRecovered(Register::rip))?;
+        Ok(Addr::from(if frame == 0 { *rip } else { rip - 1 }))
+    }
+
+    /// Gets the executable of the debuggee, which translates between the addresses of the ELF file
+    /// and the addresses in the debuggee, see [`Debuggee::get_executable`]
+    fn executable(&self) -> Result<&LoadedModule> {
+        let dbge = self.debuggee.as_ref().ok_or(DebuggerError::NoDebugee)?;
+        Ok(dbge.get_executable())
+    }
+
+    /// Finds the functions of the executable and the loaded shared libraries with heuristics
+    ///
+    /// These are the last resort for executables without debug information and symbol table, see
+    /// the [heuristics module](crate::heuristics). Shared libraries that cannot be read are left
+    /// out.
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if the debuggee is not running or its memory map or auxiliary
+    /// vector cannot be read.
+    fn heuristic_symbols(&self) -> Result<HeuristicSymbols> {
+        let dbge = self.debuggee.as_ref().ok_or(DebuggerError::NoDebugee)?;
+        let executable = dbge.get_executable();
+        let mut symbols = match object::File::parse(&*self.executable_data) {
+            Ok(obj) => heuristics::executable_symbols(&obj, executable, dbge.get_auxv()?.entry),
+            Err(e) => {
+                warn!("could not parse the executable for heuristic symbols: {e}");
+                Vec::new()
+            }
+        };
+        for module in dbge.modules()? {
+            if module.base == executable.base {
+                continue;
+            }
+            match heuristics::export_symbols(&module) {
+                Ok(exports) => symbols.extend(exports),
+                Err(e) => debug!("could not read the exports of {}: {e}", module.path),
+            }
+        }
+        Ok(HeuristicSymbols::new(symbols))
+    }
+
+    /// Gets a [`Breakpoint`] at the specified address
+    ///
+    /// This method retrieves a [`Breakpoint`] object at the given address, if one exists.
+    /// It's primarily used by plugins to determine whether a [`Breakpoint`] exists at a
+    /// specific location.
     ///
     /// # Parameters
     ///
-    /// * `sig` - The signal
-    /// * `siginfo` - The signal information
+    /// * `addr` - The memory [`Addr`] to check for a [`Breakpoint`]
     ///
     /// # Returns
     ///
-    /// * `Ok(())` - If the signal was handled successfully
-    /// * `Err(DebuggerError)` - If there was an error handling the signal
+    /// * `Ok(Feedback::Breakpoint)` - Feedback containing either `Some(Breakpoint)` if a
+    ///   [`Breakpoint`] exists at the address, or [`None`] if there is no [`Breakpoint`]
+    /// * `Err(DebuggerError)` - If there was an error accessing the debuggee
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if:
+    /// - The debuggee is not running (`DebuggerError::NoDebugee`)
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #[cfg(feature = "cli")]
+    /// # mod featguard { fn _do_thing() {
+    /// # use coreminer::debugger::Debugger;
+    /// # use coreminer::ui::cli::CliUi;
+    /// # use coreminer::addr::Addr;
+    /// # use coreminer::feedback::Feedback;
+    /// #
+    /// # let ui = CliUi::build(None).unwrap();
+    /// # let mut debugger = Debugger::build(ui).unwrap();
+    /// # // Set a breakpoint first
+    /// # debugger.set_bp(Addr::from(0x1000usize)).unwrap();
+    ///
+    /// // Check if a breakpoint exists at address 0x1000
+    /// if let Ok(Feedback::Breakpoint(maybe_bp)) = debugger.get_bp(Addr::from(0x1000usize)) {
+    ///     match maybe_bp {
+    ///         Some(bp) => println!("Found breakpoint at address 0x1000"),
+    ///         None => println!("No breakpoint at address 0x1000"),
+    ///     }
+    /// }
+    /// # }}
+    /// ```
+    pub fn get_bp(&self, addr: Addr) -> Result<Feedback> {
+        let dbge = self.debuggee.as_ref().ok_or(DebuggerError::NoDebugee)?;
+
+        let bp = dbge.breakpoints.get(&addr);
+
+        Ok(Feedback::Breakpoint(bp.cloned()))
+    }
+
+    /// Lists all [Breakpoints](Breakpoint) of the debuggee
+    ///
+    /// Each breakpoint is described with a [`BreakpointInfo`], which has its state, how often
+    /// the debuggee stopped at it, and the function and source line of its address, if the debug
+    /// information knows them.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Feedback::Breakpoints)` - The breakpoints, ordered by address
+    /// * `Err(DebuggerError)` - If the breakpoints could not be listed
     ///
     /// # Errors
     ///
     /// This function can fail if:
     /// - The debuggee is not running
-    pub fn handle_other_signal(
-        &mut self,
-        sig: Signal,
-        siginfo: nix::libc::siginfo_t,
-    ) -> Result<()> {
-        trace!("handle other");
-        info!("debugee received {}: {}", sig.as_str(), siginfo.si_code);
+    /// - The debug information cannot be read
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #[cfg(feature = "cli")]
+    /// # mod featguard { fn _do_thing() {
+    /// # use coreminer::debugger::Debugger;
+    /// # use coreminer::ui::cli::CliUi;
+    /// # use coreminer::feedback::Feedback;
+    /// #
+    /// # let ui = CliUi::build(None).unwrap();
+    /// # let debugger = Debugger::build(ui).unwrap();
+    /// # // Assume debuggee is already running
+    /// #
+    /// if let Ok(Feedback::Breakpoints(breakpoints)) = debugger.list_breakpoints() {
+    ///     for info in breakpoints {
+    ///         println!("{} was hit {} times", info.addr, info.hits);
+    ///     }
+    /// }
+    /// # }}
+    /// ```
+    pub fn list_breakpoints(&self) -> Result<Feedback> {
+        let dbge = self.debuggee.as_ref().ok_or(DebuggerError::NoDebugee)?;
+
+        let mut breakpoints: Vec<(&Addr, &Breakpoint)> = dbge.breakpoints.iter().collect();
+        breakpoints.sort_by_key(|(addr, _)| **addr);
+
+        let mut infos = Vec::with_capacity(breakpoints.len());
+        for (&addr, bp) in breakpoints {
+            let function = dbge
+                .get_function_by_addr(addr)?
+                .and_then(|function| function.name().map(ToString::to_string));
+            // the line table only covers the code of the executable, like the functions
+            let line = function.as_ref().and_then(|_| dbge.lines.line_of(addr));
+            infos.push(BreakpointInfo {
+                addr,
+                enabled: bp.is_enabled(),
+                hardware: bp.is_hardware(),
+                hits: bp.hits(),
+                function,
+                file: line.map(|entry| entry.file.clone()),
+                line: line.map(|entry| entry.line),
+                log: bp.log().cloned(),
+            });
+        }
+        Ok(Feedback::Breakpoints(infos))
+    }
+
+    /// Sets the last signal received from the [`Debuggee`]
+    ///
+    /// This method allows the [`Debugger`] or a plugin to set or modify the `last_signal`
+    /// that will be delivered to the [`Debuggee`].
+    ///
+    /// The set signal will be used the next time the debugger continues execution with
+    /// [`Self::cont()`] or one of the stepping methods.
+    ///
+    /// # Parameters
+    ///
+    /// * `sig` - The signal number to set as the last signal
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Feedback::Ok)` - If the signal was successfully set
+    /// * `Err(DebuggerError)` - If the signal number could not be converted to a valid signal
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if:
+    /// - The signal number cannot be converted to a valid [`Signal`] type
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #[cfg(feature = "cli")]
+    /// # mod featguard { fn _do_thing() {
+    /// # use coreminer::debugger::Debugger;
+    /// # use coreminer::ui::cli::CliUi;
+    /// # use nix::sys::signal::Signal;
+    /// #
+    /// # let ui = CliUi::build(None).unwrap();
+    /// # let mut debugger = Debugger::build(ui).unwrap();
+    ///
+    /// // Set SIGTRAP as the last signal
+    /// debugger.set_last_signal(Signal::SIGTRAP as i32).unwrap();
+    ///
+    /// // When continuing execution, this signal will be passed to the debuggee
+    /// debugger.cont().unwrap();
+    /// # }}
+    /// ```
+    pub fn set_last_signal(&mut self, sig: i32) -> Result<Feedback> {
+        let sig = Signal::try_from(sig)?;
+
+        info!("set last signal to {sig}");
         self.last_signal = Some(sig);
-        Ok(())
+
+        Ok(Feedback::Ok)
     }
 
-    /// Logs information about the debugger state
+    /// Continues execution of the debuggee and delivers a signal to it
+    ///
+    /// The signal replaces any signal that would otherwise be delivered on continuing. This can
+    /// be used to test the signal handlers of the debuggee.
+    ///
+    /// # Parameters
+    ///
+    /// * `sig` - The number of the signal to deliver
     ///
     /// # Returns
     ///
-    /// * `Ok(Feedback::Ok)` - If the information was logged successfully
-    /// * `Err(DebuggerError)` - If there was an error
+    /// * `Ok(Feedback)` - The result of continuing, see [`Self::cont`]
+    /// * `Err(DebuggerError)` - If there was an error continuing
     ///
     /// # Errors
     ///
     /// This function can fail if:
-    /// - The debuggee is not running
-    fn infos(&self) -> std::result::Result<Feedback, DebuggerError> {
-        let dbge = self.debuggee.as_ref().ok_or(DebuggerError::NoDebugee)?;
-        info!("Breakpoints:\n{:#?}", dbge.breakpoints);
-        Ok(Feedback::Ok)
+    /// - The signal number cannot be converted to a valid [`Signal`] type
+    /// - [`Self::cont`] fails
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #[cfg(feature = "cli")]
+    /// # mod featguard { fn _do_thing() {
+    /// # use coreminer::debugger::Debugger;
+    /// # use coreminer::ui::cli::CliUi;
+    /// # use nix::sys::signal::Signal;
+    /// #
+    /// # let ui = CliUi::build(None).unwrap();
+    /// # let mut debugger = Debugger::build(ui).unwrap();
+    /// # // Assume debuggee is already running
+    ///
+    /// // Continue and let the SIGUSR1 handler of the debuggee run
+    /// debugger.cont_with_signal(Signal::SIGUSR1 as i32).unwrap();
+    /// # }}
+    /// ```
+    pub fn cont_with_signal(&mut self, sig: i32) -> Result<Feedback> {
+        self.set_last_signal(sig)?;
+        self.cont()
     }
 
-    /// Steps into a function call
+    /// Returns why the debuggee stopped the last time
     ///
-    /// This function steps through instructions until a call instruction is found,
-    /// then steps into that function.
+    /// The reason is kept until the debuggee stops again, so a frontend can ask for it at any
+    /// time, for example after a plugin resumed the debuggee.
     ///
     /// # Returns
     ///
-    /// * `Ok(Feedback::Ok)` - If the step-into was successful
-    /// * `Err(DebuggerError)` - If there was an error during step-into
+    /// * `Ok(Feedback::Stopped)` - The [`StopReason`] of the last stop
+    /// * `Err(DebuggerError)` - If the debuggee has not stopped yet
     ///
     /// # Errors
     ///
-    /// This function can fail if:
-    /// - The debuggee is not running
-    /// - No call instruction is found
-    /// - Step operations fail
+    /// This function fails if no debuggee was launched or if it has not stopped since it was
+    /// launched.
     ///
     /// # Examples
     ///
-    ///
     /// ```no_run
     /// #[cfg(feature = "cli")]
     /// # mod featguard { fn _do_thing() {
     /// # use coreminer::debugger::Debugger;
+    /// # use coreminer::feedback::{Feedback, StopReason};
     /// # use coreminer::ui::cli::CliUi;
     /// #
     /// # let ui = CliUi::build(None).unwrap();
     /// # let mut debugger = Debugger::build(ui).unwrap();
     /// # // Assume debuggee is already running
-    /// #
-    /// // Step into the next function call
-    /// debugger.step_into().unwrap();
+    /// debugger.cont().unwrap();
     ///
+    /// if let Ok(Feedback::Stopped(info)) = debugger.why_stopped() {
+    ///     if let StopReason::Breakpoint { addr, .. } = info.reason {
+    ///         println!("hit the breakpoint at {addr}");
+    ///     }
+    /// }
     /// # }}
     /// ```
-    #[allow(clippy::missing_panics_doc)] // this function cannot panic
-    pub fn step_into(&mut self) -> Result<Feedback> {
-        if self.debuggee.is_none() {
-            return Err(DebuggerError::NoDebugee);
+    pub fn why_stopped(&self) -> Result<Feedback> {
+        match self.last_stop {
+            Some(reason) => Ok(Feedback::Stopped(self.stop_info(reason))),
+            None if self.debuggee.is_none() => Err(DebuggerError::NoDebugee),
+            None => Err(DebuggerError::NoStopReason),
         }
-        self.go_back_step_over_bp()?;
+    }
 
-        loop {
-            let rip: Addr = (self.get_reg(Register::rip)?).into();
-            let disassembly: Disassembly =
-                // unwrap is safe because we check earlier, needed because we need the mutable
-                // reference
-                self.debuggee.as_ref().unwrap().disassemble(rip, 8, true)?;
-            let next_instruction = &disassembly.inner()[0];
-            let operator = next_instruction.2[0].clone();
+    /// Resumes the debuggee with `resume` and attaches the [`StopReason`] to the [`Feedback`]
+    ///
+    /// A plain [`Feedback::Ok`] is replaced with [`Feedback::Stopped`], other [`Feedback`]
+    /// already tells why the debuggee stopped and is returned as is.
+    ///
+    /// Stepping uses temporary [Breakpoints](Breakpoint) and single steps internally. If
+    /// `stepping` is set, stops at those are reported as [`StopReason::StepDone`], so that only
+    /// [Breakpoints](Breakpoint) that were set by the user are reported as such.
+    ///
+    /// The run and the stop are recorded in the [`Journal`], with `command` as name of the run.
+    /// While recording, the stop is also added to the [`Recorder`], with `status` as the request
+    /// that resumed the debuggee.
+    fn track_stop(
+        &mut self,
+        status: &Status,
+        command: &'static str,
+        stepping: bool,
+        resume: impl FnOnce(&mut Self) -> Result<Feedback>,
+    ) -> Result<Feedback> {
+        self.last_stop = None;
+        // the stack is different after the debuggee ran
+        self.selected_frame = 0;
+        let pid = self.debuggee.as_ref().map(|dbge| dbge.pid);
+        let resumed = Instant::now();
+        let result = resume(self);
+        if let Some(pid) = pid {
+            let duration = resumed.elapsed();
+            self.journal
+                .record(pid, resumed, JournalEvent::Run { command, duration });
+        }
+        let feedback = result?;
 
-            if operator.1 != FormatterTextKind::Mnemonic {
-                error!("could not read operator from disassembly");
+        if stepping {
+            let internal_bp = match self.last_stop {
+                Some(StopReason::Breakpoint { addr, .. }) => self
+                    .debuggee
+                    .as_ref()
+                    .is_some_and(|dbge| !dbge.breakpoints.contains_key(&addr)),
+                _ => false,
+            };
+            if internal_bp {
+                self.last_stop = Some(StopReason::StepDone);
             }
-            // PERF: this is very inefficient :/ maybe remove the autostepper or work with continue
-            // somehow
-            if operator.0.trim() == "call" {
-                self.single_step()?;
-                break;
+        }
+
+        if let (Some(pid), Some(reason)) = (pid, self.last_stop) {
+            self.journal
+                .record(pid, Instant::now(), JournalEvent::Stop(reason));
+            if !self.replaying {
+                self.push_event(Event::Stopped(reason));
+                self.run_stop_hooks(reason)?;
+                self.record_stop(status);
             }
-            self.single_step()?;
         }
 
-        Ok(Feedback::Ok)
+        match (feedback, self.last_stop) {
+            (Feedback::Ok, Some(reason)) => Ok(Feedback::Stopped(self.stop_info(reason))),
+            (feedback, _) => Ok(feedback),
+        }
     }
 
-    /// Steps over a function call
-    ///
-    /// This function combines [`Self::step_into`] and [`Self::step_out`] to step over a function call.
-    ///
-    /// # Returns
+    /// Creates the [`StopInfo`] of a stop, reading the expressions added with
+    /// [`Self::add_auto_display`]
+    ///
+    /// Expressions that cannot be read at this stop, for example because they are not in scope,
+    /// have the error as value. Nothing is read if the debuggee exited.
+    fn stop_info(&self, reason: StopReason) -> StopInfo {
+        if matches!(reason, StopReason::Exited(_)) {
+            return StopInfo::from(reason);
+        }
+        let displays = self
+            .auto_displays
+            .iter()
+            .map(|(&number, expression)| AutoDisplay {
+                number,
+                expression: expression.clone(),
+                value: match self.read_variable(expression) {
+                    Ok(Feedback::Variable(value)) => Ok(value),
+                    Ok(other) => Err(other.to_string()),
+                    Err(e) => Err(e.to_string()),
+                },
+            })
+            .collect();
+        StopInfo { reason, displays }
+    }
+
+    /// Starts recording the stops of the debuggee, so that the debugger can go back to them
     ///
-    /// * `Ok(Feedback::Ok)` - If the step-over was successful
-    /// * `Err(DebuggerError)` - If there was an error during step-over
+    /// A [`Checkpoint`] of the debuggee is taken now and then at every `interval`th stop. See
+    /// [`crate::reverse`] for how the recording is used and what it cannot bring back. A recording
+    /// that was running before is discarded. The recording ends when the debuggee exits.
     ///
-    /// # Errors
+    /// # Parameters
     ///
-    /// This function can fail if:
-    /// - The debuggee is not running
-    /// - Step operations fail
-    pub fn step_over(&mut self) -> Result<Feedback> {
-        self.go_back_step_over_bp()?;
-
-        self.step_into()?;
-        self.step_out()
-    }
-
-    /// Gets a backtrace of the current call stack
+    /// * `interval` - The number of stops between two checkpoints
     ///
     /// # Returns
     ///
-    /// * `Ok(Feedback::Backtrace)` - The backtrace
-    /// * `Err(DebuggerError)` - If there was an error generating the backtrace
+    /// * `Ok(Feedback::Ok)` - If the recording was started
+    /// * `Err(DebuggerError)` - If the recording could not be started
     ///
     /// # Errors
     ///
     /// This function can fail if:
     /// - The debuggee is not running
-    /// - Stack unwinding fails
+    /// - The registers or the memory of the debuggee could not be read
     ///
     /// # Examples
     ///
-    ///
     /// ```no_run
     /// #[cfg(feature = "cli")]
     /// # mod featguard { fn _do_thing() {
     /// # use coreminer::debugger::Debugger;
+    /// # use coreminer::feedback::Status;
     /// # use coreminer::ui::cli::CliUi;
-    /// # use coreminer::feedback::Feedback;
     /// #
     /// # let ui = CliUi::build(None).unwrap();
-    /// # let debugger = Debugger::build(ui).unwrap();
+    /// # let mut debugger = Debugger::build(ui).unwrap();
     /// # // Assume debuggee is already running
     /// #
-    /// // Get a backtrace of the call stack
-    /// if let Ok(Feedback::Backtrace(bt)) = debugger.backtrace() {
-    ///     for (i, frame) in bt.frames.iter().enumerate() {
-    ///         println!("#{} {} at {:?}", i, frame.name.as_deref().unwrap_or("??"), frame.addr);
-    ///     }
-    /// }
+    /// // Record with a checkpoint at every fifth stop
+    /// debugger.start_recording(5).unwrap();
+    /// debugger.process_status(&Status::Continue).unwrap();
+    /// debugger.process_status(&Status::StepOver).unwrap();
+    ///
+    /// // Go back to the stop after the continue
+    /// debugger.reverse_step().unwrap();
     ///
     /// # }}
     /// ```
-    pub fn backtrace(&self) -> Result<Feedback> {
+    pub fn start_recording(&mut self, interval: usize) -> Result<Feedback> {
         let dbge = self.debuggee.as_ref().ok_or(DebuggerError::NoDebugee)?;
+        let checkpoint = Checkpoint::take(dbge.pid)?;
+        info!(
+            "recording with a checkpoint every {interval} stops, {} bytes per checkpoint",
+            checkpoint.size()
+        );
+        let first = RecordedStop {
+            resumed_by: None,
+            reason: self.last_stop,
+            signal: self.last_signal,
+            pc: self.get_current_addr()?,
+            checkpoint: Some(checkpoint),
+        };
+        self.recorder = Some(Recorder::new(interval, first));
+        Ok(Feedback::Ok)
+    }
 
-        let backtrace = unwind::unwind(dbge.pid)?;
+    /// Stops recording the stops of the debuggee and discards the recorded stops
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Feedback::Ok)` - If the recording was stopped
+    ///
+    /// # Errors
+    ///
+    /// Fails with [`DebuggerError::NotRecording`] if there is no recording.
+    pub fn stop_recording(&mut self) -> Result<Feedback> {
+        self.recorder
+            .take()
+            .ok_or(DebuggerError::NotRecording)
+            .map(|_| Feedback::Ok)
+    }
 
-        Ok(Feedback::Backtrace(backtrace))
+    /// Adds the current stop to the [`Recorder`], if there is a recording
+    ///
+    /// The recording ends if the debuggee exited or no checkpoint could be taken.
+    fn record_stop(&mut self, status: &Status) {
+        let Some(take_checkpoint) = self.recorder.as_ref().map(Recorder::takes_checkpoint) else {
+            return;
+        };
+        let pid = match &self.debuggee {
+            Some(dbge) if !matches!(self.last_stop, Some(StopReason::Exited(_))) => dbge.pid,
+            _ => {
+                info!("the debuggee exited, recording stopped");
+                self.recorder = None;
+                return;
+            }
+        };
+
+        match self.recorded_stop(pid, status, take_checkpoint) {
+            Ok(stop) => {
+                if let Some(recorder) = &mut self.recorder {
+                    recorder.push(stop);
+                }
+            }
+            Err(e) => {
+                warn!("could not record the stop, recording stopped: {e}");
+                self.recorder = None;
+            }
+        }
     }
 
-    /// Gets the current instruction pointer address
+    /// Describes the current stop for the [`Recorder`], with `status` as the request that
+    /// resumed the debuggee before it
+    fn recorded_stop(
+        &self,
+        pid: Pid,
+        status: &Status,
+        take_checkpoint: bool,
+    ) -> Result<RecordedStop> {
+        let checkpoint = if take_checkpoint {
+            Some(Checkpoint::take(pid)?)
+        } else {
+            None
+        };
+        Ok(RecordedStop {
+            resumed_by: Some(status.clone()),
+            reason: self.last_stop,
+            signal: self.last_signal,
+            pc: self.get_current_addr()?,
+            checkpoint,
+        })
+    }
+
+    /// Goes back to the previous recorded stop
+    ///
+    /// The nearest [`Checkpoint`] at or before the stop is restored, and the requests that
+    /// resumed the debuggee after it are repeated. The UI is not told about the stops in between.
     ///
     /// # Returns
     ///
-    /// * `Ok(Addr)` - The current address
-    /// * `Err(DebuggerError)` - If there was an error getting the address
+    /// * `Ok(Feedback::Stopped)` - The debuggee is at the previous stop
+    /// * `Err(DebuggerError)` - If the debuggee could not be brought back
     ///
     /// # Errors
     ///
     /// This function can fail if:
     /// - The debuggee is not running
-    /// - [Register] read fails
+    /// - The stops are not being recorded, see [`Self::start_recording`]
+    /// - The debuggee is at the first recorded stop
+    /// - The checkpoint could not be restored
+    /// - The debuggee did not reach the same stops again, which ends the recording
+    pub fn reverse_step(&mut self) -> Result<Feedback> {
+        let recorder = self.recorder.as_ref().ok_or(DebuggerError::NotRecording)?;
+        let target = recorder.previous().ok_or(DebuggerError::NoEarlierStop)?;
+        self.reverse_to(target)
+    }
+
+    /// Goes back to the last recorded stop that was not the end of a step, like a breakpoint or
+    /// a signal, or to the first recorded stop if there is none
     ///
-    /// # Examples
+    /// This works like [`Self::reverse_step`] otherwise.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Feedback::Stopped)` - The debuggee is at the earlier stop
+    /// * `Err(DebuggerError)` - If the debuggee could not be brought back
+    ///
+    /// # Errors
+    ///
+    /// This function can fail for the same reasons as [`Self::reverse_step`].
+    pub fn reverse_continue(&mut self) -> Result<Feedback> {
+        let recorder = self.recorder.as_ref().ok_or(DebuggerError::NotRecording)?;
+        let target = recorder
+            .previous_event()
+            .ok_or(DebuggerError::NoEarlierStop)?;
+        self.reverse_to(target)
+    }
+
+    /// Restores the nearest [`Checkpoint`] at or before the recorded stop `target` and repeats
+    /// the recorded requests until the debuggee is at `target`
+    fn reverse_to(&mut self, target: usize) -> Result<Feedback> {
+        let pid = self.debuggee.as_ref().ok_or(DebuggerError::NoDebugee)?.pid;
+        let recorder = self.recorder.as_ref().ok_or(DebuggerError::NotRecording)?;
+        let (start, checkpoint) = recorder
+            .checkpoint_before(target)
+            .ok_or(DebuggerError::NoEarlierStop)?;
+        checkpoint.restore(pid)?;
+
+        let stops = &recorder.stops()[start..=target];
+        let replay: Vec<(Status, Addr)> = stops[1..]
+            .iter()
+            .filter_map(|stop| Some((stop.resumed_by.clone()?, stop.pc)))
+            .collect();
+        debug!(
+            "restored the checkpoint of stop {start}, repeating {} requests",
+            replay.len()
+        );
+        self.last_stop = stops[0].reason;
+        self.last_signal = stops[0].signal;
+        self.selected_frame = 0;
+
+        self.replaying = true;
+        let result = self.replay_stops(start, &replay);
+        self.replaying = false;
+        if let Err(e) = result {
+            self.recorder = None;
+            return Err(e);
+        }
+
+        if let Some(recorder) = &mut self.recorder {
+            recorder.set_position(target);
+        }
+        info!("went back to recorded stop {target}");
+        Ok(self.restored_stop())
+    }
+
+    /// Tells the UI about the stop that the debuggee is at again after a [`Checkpoint`] was
+    /// restored
+    fn restored_stop(&mut self) -> Feedback {
+        match self.last_stop {
+            Some(reason) => {
+                self.push_event(Event::Stopped(reason));
+                Feedback::Stopped(self.stop_info(reason))
+            }
+            None => Feedback::Ok,
+        }
+    }
+
+    /// Repeats the recorded requests after the stop `start`, checking that each of them stops at
+    /// the recorded address again
+    fn replay_stops(&mut self, start: usize, replay: &[(Status, Addr)]) -> Result<()> {
+        for (index, (status, pc)) in (start + 1..).zip(replay) {
+            self.process_status(status)?;
+            if self.get_current_addr().ok() != Some(*pc) {
+                return Err(DebuggerError::ReplayDiverged(index, *pc));
+            }
+        }
+        Ok(())
+    }
+
+    /// Saves the registers and the writable memory of the stopped debuggee in a new
+    /// [`Checkpoint`]
+    ///
+    /// The debuggee can be brought back to this state later with [`Self::restore_checkpoint`],
+    /// for example to run the same code again after changing a variable. See
+    /// [`crate::checkpoint`] for the state that is not saved. The checkpoints are deleted when a
+    /// new debuggee is started.
     ///
+    /// # Returns
+    ///
+    /// * `Ok(Feedback::Checkpoint)` - The number, address and size of the new checkpoint
+    /// * `Err(DebuggerError)` - If the checkpoint could not be taken
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if:
+    /// - The debuggee is not running
+    /// - The registers or the memory of the debuggee could not be read
+    ///
+    /// # Examples
     ///
     /// ```no_run
     /// #[cfg(feature = "cli")]
     /// # mod featguard { fn _do_thing() {
     /// # use coreminer::debugger::Debugger;
+    /// # use coreminer::feedback::{Feedback, Status};
+    /// # use coreminer::Register;
     /// # use coreminer::ui::cli::CliUi;
     /// #
     /// # let ui = CliUi::build(None).unwrap();
-    /// # let debugger = Debugger::build(ui).unwrap();
+    /// # let mut debugger = Debugger::build(ui).unwrap();
     /// # // Assume debuggee is already running
     /// #
-    /// // Get the current instruction pointer
-    /// let curr_addr = debugger.get_current_addr().unwrap();
-    /// println!("Current IP: {}", curr_addr);
+    /// let Feedback::Checkpoint { id, .. } = debugger.take_checkpoint().unwrap() else {
+    ///     unreachable!()
+    /// };
+    /// debugger.process_status(&Status::StepOut).unwrap();
+    ///
+    /// // Run the function again, with a different first argument
+    /// debugger.restore_checkpoint(id).unwrap();
+    /// debugger.set_reg(Register::rdi, 2).unwrap();
+    /// debugger.process_status(&Status::StepOut).unwrap();
     ///
     /// # }}
     /// ```
-    pub fn get_current_addr(&self) -> Result<Addr> {
-        Ok((self.get_reg(Register::rip)?).into())
+    pub fn take_checkpoint(&mut self) -> Result<Feedback> {
+        let dbge = self.debuggee.as_ref().ok_or(DebuggerError::NoDebugee)?;
+        let checkpoint = Checkpoint::take(dbge.pid)?;
+        let id = self
+            .checkpoints
+            .last_key_value()
+            .map_or(1, |(id, _)| id + 1);
+        let (addr, size) = (checkpoint.pc(), checkpoint.size());
+        info!("checkpoint {id} at {addr}, {size} bytes");
+        self.checkpoints.insert(
+            id,
+            SavedCheckpoint {
+                checkpoint,
+                reason: self.last_stop,
+                signal: self.last_signal,
+            },
+        );
+        Ok(Feedback::Checkpoint { id, addr, size })
     }
 
-    /// Prepares for variable access by gathering necessary context
+    /// Brings the debuggee back to the state saved with [`Self::take_checkpoint`]
+    ///
+    /// The checkpoint is kept, so that it can be restored again. A recording of the stops is
+    /// ended, as the debuggee is not at a recorded stop anymore.
     ///
     /// # Parameters
     ///
-    /// * `expression` - The variable expression to access
+    /// * `id` - The number of the checkpoint
     ///
     /// # Returns
     ///
-    /// * `Ok((OwnedSymbol, OwnedSymbol, FrameInfo))` - Function, variable symbols and frame info
-    /// * `Err(DebuggerError)` - If preparation failed
+    /// * `Ok(Feedback::Stopped)` - The debuggee is at the stop where the checkpoint was taken
+    /// * `Err(DebuggerError)` - If the checkpoint could not be restored
     ///
     /// # Errors
     ///
     /// This function can fail if:
     /// - The debuggee is not running
-    /// - The current location is not in a function
-    /// - The variable is not found
-    /// - Frame information cannot be constructed
-    #[allow(clippy::missing_panics_doc)] // this function cant panic
-    pub fn prepare_variable_access(
-        &self,
-        expression: &VariableExpression,
-    ) -> Result<(OwnedSymbol, OwnedSymbol, FrameInfo)> {
+    /// - There is no checkpoint with the number `id`
+    /// - Memory of the checkpoint was unmapped since it was taken
+    /// - The registers or the memory of the debuggee could not be written
+    pub fn restore_checkpoint(&mut self, id: CheckpointId) -> Result<Feedback> {
         let dbge = self.debuggee.as_ref().ok_or(DebuggerError::NoDebugee)?;
-        let rip: Addr = self.get_current_addr()?;
-
-        // Get current function
-        let current_function = match dbge.get_function_by_addr(rip)? {
-            Some(f) if f.frame_base().is_some() => f,
-            Some(_) => {
-                return Err(DebuggerError::AttributeDoesNotExist(
-                    gimli::DW_AT_frame_base,
-                ))
-            }
-            None => return Err(DebuggerError::NotInFunction),
-        };
-
-        // Find variable
-        let locals = dbge.get_local_variables(rip)?;
-        let vars = dbge.filter_expressions(&locals, expression)?;
-        let var = match vars.len() {
-            0 => {
-                return Err(DebuggerError::VarExprReturnedNothing(
-                    expression.to_string(),
-                ))
-            }
-            1 => vars[0].clone(),
-            _ => return Err(DebuggerError::AmbiguousVarExpr(expression.to_string())),
-        };
-
-        // Build frame info
-        let mut frame_info = FrameInfo::new(
-            None,
-            Some(Into::<Addr>::into(self.get_reg(Register::rbp)?) + 16usize),
-        );
-
-        let frame_base = dbge.parse_location(
-            current_function.frame_base().unwrap(), // safe: we check above if this is some
-            &frame_info,
-            current_function.encoding(),
-        )?;
-
-        let frame_base: Addr = match frame_base {
-            gimli::Location::Address { address } => address.into(),
-            other => unimplemented!(
-                "frame base DWARF location was not an address as expected: is {other:?}"
-            ),
-        };
-
-        frame_info.frame_base = Some(frame_base);
+        let saved = self
+            .checkpoints
+            .get(&id)
+            .ok_or(DebuggerError::NoCheckpoint(id))?;
+        saved.checkpoint.restore(dbge.pid)?;
+        info!("restored checkpoint {id}");
+        self.last_stop = saved.reason;
+        self.last_signal = saved.signal;
+        self.selected_frame = 0;
+        if self.recorder.take().is_some() {
+            info!("restored a checkpoint, recording stopped");
+        }
+        Ok(self.restored_stop())
+    }
 
-        Ok((current_function, var, frame_info))
+    /// Deletes the checkpoint with the number `id`, see [`Self::take_checkpoint`]
+    ///
+    /// # Errors
+    ///
+    /// Fails with [`DebuggerError::NoCheckpoint`] if there is no checkpoint with that number.
+    pub fn del_checkpoint(&mut self, id: CheckpointId) -> Result<Feedback> {
+        self.checkpoints
+            .remove(&id)
+            .ok_or(DebuggerError::NoCheckpoint(id))?;
+        Ok(Feedback::Ok)
     }
 
-    /// Reads the value of a variable
+    /// Displays the value of a variable expression every time the debuggee stops
+    ///
+    /// The value is read each time the debuggee stops after [`Status::Continue`] or a stepping
+    /// status, and by [`Self::why_stopped`]. It is part of the [`StopInfo`] in the returned
+    /// [`Feedback::Stopped`].
     ///
     /// # Parameters
     ///
-    /// * `expression` - The variable name to read
+    /// * `expression` - The variable expression, see [`Self::read_variable`]
     ///
     /// # Returns
     ///
-    /// * `Ok(Feedback::Variable)` - The variable value
-    /// * `Err(DebuggerError)` - If the variable could not be read
+    /// * `Ok(Feedback::Word)` - The number of the expression, to remove it with
+    ///   [`Self::del_auto_display`]
     ///
     /// # Errors
     ///
-    /// This function can fail if:
-    /// - The debuggee is not running
-    /// - The variable is not found
-    /// - The variable cannot be accessed
+    /// Cannot fail, expressions that cannot be read are only reported at the stops.
     ///
     /// # Examples
     ///
-    ///
     /// ```no_run
     /// #[cfg(feature = "cli")]
     /// # mod featguard { fn _do_thing() {
     /// # use coreminer::debugger::Debugger;
-    /// # use coreminer::ui::cli::CliUi;
     /// # use coreminer::feedback::Feedback;
+    /// # use coreminer::ui::cli::CliUi;
     /// #
     /// # let ui = CliUi::build(None).unwrap();
-    /// # let debugger = Debugger::build(ui).unwrap();
+    /// # let mut debugger = Debugger::build(ui).unwrap();
     /// # // Assume debuggee is already running
     /// #
-    /// // Read the value of a variable named "count"
-    /// if let Ok(Feedback::Variable(value)) = debugger.read_variable(&"count".to_string()) {
-    ///     println!("count = {:?}", value);
+    /// debugger.add_auto_display("i".to_string()).unwrap();
+    ///
+    /// debugger.single_step().unwrap();
+    /// if let Ok(Feedback::Stopped(info)) = debugger.why_stopped() {
+    ///     for display in info.displays {
+    ///         println!("{display}");
+    ///     }
     /// }
     ///
     /// # }}
     /// ```
-    pub fn read_variable(&self, expression: &VariableExpression) -> Result<Feedback> {
-        let dbge = self.debuggee.as_ref().ok_or(DebuggerError::NoDebugee)?;
-
-        let (_, symbol, frame_info) = self.prepare_variable_access(expression)?;
+    pub fn add_auto_display(&mut self, expression: VariableExpression) -> Result<Feedback> {
+        let number = self
+            .auto_displays
+            .last_key_value()
+            .map_or(1, |(number, _)| number + 1);
+        info!("displaying {expression} as {number}");
+        self.auto_displays.insert(number, expression);
+        Ok(Feedback::Word(number))
+    }
 
-        let val = dbge.var_read(&symbol, &frame_info)?;
+    /// Stops displaying the expression with the number `number`, see [`Self::add_auto_display`]
+    ///
+    /// # Errors
+    ///
+    /// Fails with [`DebuggerError::NoAutoDisplay`] if there is no expression with that number.
+    pub fn del_auto_display(&mut self, number: usize) -> Result<Feedback> {
+        self.auto_displays
+            .remove(&number)
+            .ok_or(DebuggerError::NoAutoDisplay(number))?;
+        Ok(Feedback::Ok)
+    }
 
-        Ok(Feedback::Variable(val))
+    /// Pushes an [`Event`] to the [`EventSink`](crate::ui::EventSink) of the UI
+    ///
+    /// Events happen on their own, so a sink that fails is only logged, instead of
+    /// failing whatever the debugger was doing.
+    fn push_event(&mut self, event: Event) {
+        // the UI has seen everything that happens while going back to a recorded stop
+        if self.replaying {
+            return;
+        }
+        if let Err(e) = self.ui.push_event(event) {
+            warn!("could not push an event to the UI: {e}");
+        }
     }
 
-    /// Writes a value to a variable
+    /// Sends a signal to the debuggee with `kill`
+    ///
+    /// Unlike [`Self::cont_with_signal`], this does not resume the debuggee. The kernel queues
+    /// the signal, and the debuggee stops with it once it is resumed, just like with a signal
+    /// from any other process. The signal is then delivered when the debuggee is continued again.
     ///
     /// # Parameters
     ///
-    /// * `expression` - The variable name to write to
-    /// * `value` - The value to write
+    /// * `sig` - The number of the signal to send
     ///
     /// # Returns
     ///
-    /// * `Ok(Feedback::Ok)` - If the write was successful
-    /// * `Err(DebuggerError)` - If the variable could not be written
+    /// * `Ok(Feedback::Ok)` - If the signal was sent
+    /// * `Err(DebuggerError)` - If the signal could not be sent
     ///
     /// # Errors
     ///
     /// This function can fail if:
     /// - The debuggee is not running
-    /// - The variable is not found
-    /// - The variable cannot be accessed
-    /// - The value is incompatible with the variable type
+    /// - The signal number cannot be converted to a valid [`Signal`] type
+    /// - `kill` fails
     ///
     /// # Examples
     ///
-    ///
     /// ```no_run
     /// #[cfg(feature = "cli")]
     /// # mod featguard { fn _do_thing() {
     /// # use coreminer::debugger::Debugger;
     /// # use coreminer::ui::cli::CliUi;
+    /// # use nix::sys::signal::Signal;
     /// #
     /// # let ui = CliUi::build(None).unwrap();
-    /// # let debugger = Debugger::build(ui).unwrap();
+    /// # let mut debugger = Debugger::build(ui).unwrap();
     /// # // Assume debuggee is already running
-    /// #
-    /// // Set the value of a variable named "count" to 42
-    /// debugger.write_variable(&"count".to_string(), 42).unwrap();
     ///
+    /// debugger.send_signal(Signal::SIGTERM as i32).unwrap();
+    ///
+    /// // the debuggee stops with the SIGTERM
+    /// debugger.cont().unwrap();
+    /// // the SIGTERM is delivered
+    /// debugger.cont().unwrap();
     /// # }}
     /// ```
-    pub fn write_variable(
-        &self,
-        expression: &VariableExpression,
-        value: impl Into<VariableValue>,
-    ) -> Result<Feedback> {
+    pub fn send_signal(&mut self, sig: i32) -> Result<Feedback> {
         let dbge = self.debuggee.as_ref().ok_or(DebuggerError::NoDebugee)?;
+        let sig = Signal::try_from(sig)?;
 
-        let (_, var, frame_info) = self.prepare_variable_access(expression)?;
-
-        dbge.var_write(&var, &frame_info, &value.into())?;
+        info!("sending {sig} to the debuggee");
+        nix::sys::signal::kill(dbge.pid, sig)?;
 
         Ok(Feedback::Ok)
     }
 
-    /// Reads a single [Word] from memory at the specified address
+    /// Runs a program for debugging
+    ///
+    /// This function loads an executable, parses its debug information, and
+    /// launches it under debugger control.
     ///
     /// # Parameters
     ///
-    /// * `addr` - The address to read from
+    /// * `executable_path` - Path to the executable
+    /// * `arguments` - Command-line arguments for the executable
     ///
     /// # Returns
     ///
-    /// * `Ok(Feedback::Word)` - The value read from memory
-    /// * `Err(DebuggerError)` - If the memory could not be read
+    /// * `Ok(Feedback::Ok)` - If the program was launched successfully
+    /// * `Err(DebuggerError)` - If the program could not be launched
     ///
     /// # Errors
     ///
     /// This function can fail if:
-    /// - The debuggee is not running
-    /// - The memory address is invalid
-    /// - The memory cannot be accessed
+    /// - A debuggee is already running
+    /// - The executable does not exist
+    /// - The executable is not a valid file
+    /// - Debug information cannot be parsed
+    /// - The process cannot be forked
     ///
     /// # Examples
     ///
@@ -1658,172 +9753,395 @@ impl<'executable, UI: DebuggerUI> Debugger<'executable, UI> {
     /// # mod featguard { fn _do_thing() {
     /// # use coreminer::debugger::Debugger;
     /// # use coreminer::ui::cli::CliUi;
-    /// # use coreminer::addr::Addr;
-    /// # use coreminer::feedback::Feedback;
+    /// # use std::path::Path;
+    /// # use std::ffi::CString;
     /// #
     /// # let ui = CliUi::build(None).unwrap();
-    /// # let debugger = Debugger::build(ui).unwrap();
-    /// # // Assume debuggee is already running
+    /// # let mut debugger = Debugger::build(ui).unwrap();
     /// #
-    /// // Read a word from memory at address 0x1000
-    /// if let Ok(Feedback::Word(value)) = debugger.read_mem(Addr::from(0x1000usize)) {
-    ///     println!("Memory at 0x1000: {:#x}", value);
-    /// }
+    /// // Run a program with arguments
+    /// let program = Path::new("./target/debug/my_program");
+    /// let args = vec![
+    ///     CString::new("my_program").unwrap(),
+    ///     CString::new("--arg1").unwrap(),
+    ///     CString::new("value1").unwrap()
+    /// ];
+    ///
+    /// debugger.run(program, &args).unwrap();
     ///
     /// # }}
     /// ```
-    pub fn read_mem(&self, addr: Addr) -> Result<Feedback> {
-        let dbge = self.debuggee.as_ref().ok_or(DebuggerError::NoDebugee)?;
+    pub fn run(
+        &mut self,
+        executable_path: impl AsRef<Path>,
+        arguments: &[CString],
+    ) -> Result<Feedback> {
+        if self.debuggee.is_some() {
+            return Err(DebuggerError::AlreadyRunning);
+        }
+
+        debug!(
+            "exe to run are: {}",
+            executable_path.as_ref().to_string_lossy()
+        );
+        debug!("arguments to run are: {arguments:?}");
 
-        let w = mem_read_word(dbge.pid, addr)?;
+        let exe: &Path = executable_path.as_ref();
+        let exe: PathBuf = which(exe).unwrap_or(exe.into());
+        info!("using executable path '{}'", exe.to_string_lossy());
+        self.load_executable(&exe)?;
 
-        Ok(Feedback::Word(w))
+        // Now launch the debuggee
+        // the debuggee is stopped after the execve and can be configured from there on
+        if let Some(status) = self.launch_debuggee(&exe, arguments)? {
+            return Ok(Feedback::Exit(status));
+        }
+        let pid = self.debuggee.as_ref().ok_or(DebuggerError::NoDebugee)?.pid;
+        ptrace::setoptions(pid, self.ptrace_flags())?;
+        self.journal.record(
+            pid,
+            Instant::now(),
+            JournalEvent::Launch {
+                exe: exe.to_string_lossy().to_string(),
+            },
+        );
+
+        let feedback = match self.launch_break {
+            Some(launch_break) => self.run_to_launch_break(launch_break)?,
+            None => Feedback::Ok,
+        };
+
+        if let Feedback::Ok = feedback {
+            self.arm_panic_catchpoint()?;
+            if let Some(session) = self.pending_session.take() {
+                self.restore_points(&session)?;
+            }
+        }
+        Ok(feedback)
     }
 
-    /// Writes a [Word] to memory at the specified address
+    /// Sets up the [`Debuggee`] for the stopped process `pid` and resets the state of the
+    /// previous one
+    ///
+    /// This is shared by all ways to get a debuggee, so that they handle the symbols and the
+    /// settings of the debugger the same way.
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if the debug information cannot be processed or the modules of the
+    /// process cannot be found.
+    fn init_debuggee(
+        &mut self,
+        pid: Pid,
+        dbginfo: &CMDebugInfo,
+        modules: Option<Vec<LoadedModule>>,
+    ) -> Result<()> {
+        let mut dbge = Debuggee::build(pid, dbginfo, HashMap::new(), modules)?;
+        dbge.limits = self.limits;
+        dbge.verify_writes = self.write_verification.memory;
+        self.debuggee = Some(dbge);
+        self.resumed_with = Resume::Cont;
+        self.anti_debug.reset();
+        self.interrupt_pending = false;
+        self.last_stop = None;
+        self.selected_frame = 0;
+        self.recorder = None;
+        self.checkpoints.clear();
+        Ok(())
+    }
+
+    /// Reads and parses the executable of the next debuggee
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if the executable cannot be read or is not a valid object file.
+    fn load_executable(&mut self, exe: &Path) -> Result<()> {
+        // the data is owned by the debugger, so that a different program can be debugged in the
+        // same session
+        let data: Rc<[u8]> = std::fs::read(exe)?.into();
+        object::File::parse(&*data)?;
+        self.executable_data = data;
+        Ok(())
+    }
+
+    /// Attaches to a running process and all of its threads
+    ///
+    /// The executable is read from `/proc/<pid>/exe`. Every thread in `/proc/<pid>/task` is
+    /// attached and stopped, and registered in the thread table of the debuggee, see the
+    /// [thread module](crate::thread). Afterwards, the process can be debugged like one that was
+    /// launched with [`Self::run`].
     ///
     /// # Parameters
     ///
-    /// * `addr` - The address to write to
-    /// * `value` - The value to write
+    /// * `pid` - The process ID of the process
     ///
     /// # Returns
     ///
-    /// * `Ok(Feedback::Ok)` - If the write was successful
-    /// * `Err(DebuggerError)` - If the memory could not be written
+    /// * `Ok(Feedback::Ok)` - If the process was attached and is stopped
+    /// * `Err(DebuggerError)` - If the process could not be attached
     ///
     /// # Errors
     ///
     /// This function can fail if:
-    /// - The debuggee is not running
-    /// - The memory address is invalid
-    /// - The memory cannot be accessed
+    /// - A debuggee is already running
+    /// - The executable of the process cannot be read or parsed
+    /// - The debugger may not trace the process, see `/proc/sys/kernel/yama/ptrace_scope`
     ///
     /// # Examples
     ///
-    ///
     /// ```no_run
     /// #[cfg(feature = "cli")]
     /// # mod featguard { fn _do_thing() {
     /// # use coreminer::debugger::Debugger;
     /// # use coreminer::ui::cli::CliUi;
-    /// # use coreminer::addr::Addr;
+    /// # use coreminer::feedback::Feedback;
+    /// use nix::unistd::Pid;
     /// #
     /// # let ui = CliUi::build(None).unwrap();
-    /// # let debugger = Debugger::build(ui).unwrap();
-    /// # // Assume debuggee is already running
+    /// # let mut debugger = Debugger::build(ui).unwrap();
     /// #
-    /// // Write the value 0x42 to memory at address 0x1000
-    /// debugger.write_mem(Addr::from(0x1000usize), 0x42).unwrap();
+    /// debugger.attach(Pid::from_raw(1234)).unwrap();
+    ///
+    /// if let Ok(Feedback::Threads(threads)) = debugger.list_threads() {
+    ///     println!("attached to {} threads", threads.len());
+    /// }
     ///
     /// # }}
     /// ```
-    pub fn write_mem(&self, addr: Addr, value: Word) -> Result<Feedback> {
-        let dbge = self.debuggee.as_ref().ok_or(DebuggerError::NoDebugee)?;
+    pub fn attach(&mut self, pid: Pid) -> Result<Feedback> {
+        self.attach_with_modules(pid, None)
+    }
+
+    /// Attaches to a running process like [`Self::attach`], with the modules of the process
+    /// given by `modules` instead of read from its memory map
+    ///
+    /// The executable is the first of the `modules`, or the one at `/proc/<pid>/exe` without
+    /// `modules`.
+    fn attach_with_modules(
+        &mut self,
+        pid: Pid,
+        modules: Option<Vec<LoadedModule>>,
+    ) -> Result<Feedback> {
+        if self.debuggee.is_some() {
+            return Err(DebuggerError::AlreadyRunning);
+        }
+
+        let exe = match modules.as_ref().map(|modules| modules.first()) {
+            Some(Some(executable)) => PathBuf::from(&executable.path),
+            Some(None) => return Err(DebuggerError::NoExecutableModule),
+            None => std::fs::read_link(format!("/proc/{pid}/exe"))?,
+        };
+        info!("attaching to {pid} running '{}'", exe.to_string_lossy());
+        self.load_executable(&exe)?;
+        let dbginfo: CMDebugInfo = CMDebugInfo::build(Rc::clone(&self.executable_data))?;
+        self.init_debuggee(pid, &dbginfo, modules)?;
+
+        let dbge = self.debuggee.as_mut().ok_or(DebuggerError::NoDebugee)?;
+        dbge.threads = thread::attach_all(pid)?;
+        info!("attached to {} threads", dbge.threads.len());
 
-        mem_write_word(dbge.pid, addr, value)?;
+        let flags = self.ptrace_flags();
+        let dbge = self.debuggee.as_ref().ok_or(DebuggerError::NoDebugee)?;
+        for tid in dbge.threads.keys() {
+            ptrace::setoptions(*tid, flags)?;
+        }
+        self.journal.record(
+            pid,
+            Instant::now(),
+            JournalEvent::Attach {
+                exe: exe.to_string_lossy().to_string(),
+            },
+        );
 
+        self.arm_panic_catchpoint()?;
+        if let Some(session) = self.pending_session.take() {
+            self.restore_points(&session)?;
+        }
         Ok(Feedback::Ok)
     }
 
-    /// Gets the value of a register
+    /// Opens a core dump of a crashed process for post-mortem debugging
+    ///
+    /// The core dump takes the place of the debuggee: registers, memory, backtraces, disassembly
+    /// and variables are read from it, with the debug information of the executable. The
+    /// process is gone, so nothing that would run or change it is possible until the core dump
+    /// is closed with [`Self::detach`]. Those requests fail with [`DebuggerError::PostMortem`].
+    ///
+    /// The reason of the stop is the signal that terminated the process, see
+    /// [`Self::why_stopped`].
     ///
     /// # Parameters
     ///
-    /// * `r` - The register to get
+    /// * `core` - Path of the core dump
+    /// * `executable` - Path of the executable of the process, or [`None`] to use the path it was
+    ///   run from, as recorded in the core dump
     ///
     /// # Returns
     ///
-    /// * `Ok(u64)` - The register value
-    /// * `Err(DebuggerError)` - If the register could not be read
+    /// * `Ok(Feedback::Ok)` - If the core dump was opened
+    /// * `Err(DebuggerError)` - If the core dump could not be opened
     ///
     /// # Errors
     ///
     /// This function can fail if:
-    /// - The debuggee is not running
-    /// - Register access fails
+    /// - A debuggee is already running
+    /// - The core dump cannot be read or parsed, see [`CoreDump::open`]
+    /// - The executable is not known or cannot be read
     ///
     /// # Examples
     ///
-    ///
     /// ```no_run
     /// #[cfg(feature = "cli")]
     /// # mod featguard { fn _do_thing() {
     /// # use coreminer::debugger::Debugger;
     /// # use coreminer::ui::cli::CliUi;
-    /// # use coreminer::Register;
+    /// # use coreminer::feedback::Feedback;
+    /// # use std::path::Path;
     /// #
     /// # let ui = CliUi::build(None).unwrap();
-    /// # let debugger = Debugger::build(ui).unwrap();
-    /// # // Assume debuggee is already running
+    /// # let mut debugger = Debugger::build(ui).unwrap();
     /// #
-    /// // Get the value of the rax register
-    /// let rax = debugger.get_reg(Register::rax).unwrap();
-    /// println!("RAX: {:#x}", rax);
+    /// debugger.open_core(Path::new("core.1234"), Some(Path::new("./crashed"))).unwrap();
+    ///
+    /// if let Ok(Feedback::Backtrace(bt)) = debugger.backtrace() {
+    ///     for (i, frame) in bt.frames.iter().enumerate() {
+    ///         println!("#{} {} at {}", i, frame.name.as_deref().unwrap_or("??"), frame.addr);
+    ///     }
+    /// }
     ///
     /// # }}
     /// ```
-    pub fn get_reg(&self, r: Register) -> Result<u64> {
-        let dbge = self.debuggee.as_ref().ok_or(DebuggerError::NoDebugee)?;
+    pub fn open_core(&mut self, core: &Path, executable: Option<&Path>) -> Result<Feedback> {
+        if self.debuggee.is_some() {
+            return Err(DebuggerError::AlreadyRunning);
+        }
+
+        let core = CoreDump::open(core, executable)?;
+        let modules = core.modules()?;
+        let exe = PathBuf::from(&modules[0].path); // modules starts with the executable
+        info!(
+            "opening the core dump of {} running '{}'",
+            core.pid(),
+            exe.to_string_lossy()
+        );
+        self.load_executable(&exe)?;
+        let dbginfo: CMDebugInfo = CMDebugInfo::build(Rc::clone(&self.executable_data))?;
+        let pid = Pid::from_raw(core.pid());
+        self.init_debuggee(pid, &dbginfo, Some(modules))?;
 
-        crate::get_reg(dbge.pid, r)
+        let dbge = self.debuggee.as_mut().ok_or(DebuggerError::NoDebugee)?;
+        dbge.threads = core
+            .threads()
+            .iter()
+            .map(|t| {
+                (
+                    Pid::from_raw(t.tid),
+                    Thread::stopped(Pid::from_raw(t.tid), t.tid == core.pid()),
+                )
+            })
+            .collect();
+        if core.signal() != 0 {
+            self.last_stop = Some(StopReason::Signal(core.signal()));
+        }
+        dbge.core = Some(core);
+        Ok(Feedback::Ok)
     }
 
-    /// Sets the value of a register
-    ///
-    /// # Parameters
+    /// Returns `true` if the debuggee is a core dump, see [`Self::open_core`]
+    #[must_use]
+    pub fn is_post_mortem(&self) -> bool {
+        self.debuggee.as_ref().is_some_and(|d| d.core.is_some())
+    }
+
+    /// Returns `true` if `status` can be processed while the debuggee is a core dump
+    ///
+    /// These are the requests that only read the state of the process, and the settings of the
+    /// debugger.
+    fn works_post_mortem(status: &Status) -> bool {
+        matches!(
+            status,
+            Status::Infos
+                | Status::DebuggerQuit
+                | Status::Detach
+                | Status::SetQuitAction(_)
+                | Status::SetFollowFork(_)
+                | Status::SetSchedulerLocking(_)
+                | Status::SetLimits(_)
+                | Status::SetLaunchBreak(_)
+                | Status::SetWriteVerification(_)
+                | Status::SetStdinPipe(_)
+                | Status::DumpRegisters
+                | Status::ReadMem(_)
+                | Status::ReadMemRange(..)
+                | Status::DisassembleAt(..)
+                | Status::GetSymbolsByName(_)
+                | Status::ResolveAddr(_)
+                | Status::SetConvenience(..)
+                | Status::ShowValues
+                | Status::Backtrace
+                | Status::SelectFrame(_)
+                | Status::ReadVariable(_)
+                | Status::ReadFrameVariable(..)
+                | Status::AddAutoDisplay(_)
+                | Status::DelAutoDisplay(_)
+                | Status::ListModules
+                | Status::ListFunctions(_)
+                | Status::ListTypes(_)
+                | Status::ListSource(_)
+                | Status::ListVariables(..)
+                | Status::ListThreads
+                | Status::ShowAuxv
+                | Status::WhyStopped
+        )
+    }
+
+    /// Lists the threads in the thread table of the debuggee
     ///
-    /// * `r` - The register to set
-    /// * `v` - The value to set
+    /// Threads that have exited since are removed from the table first. The names of the threads
+    /// are read again, as a thread can rename itself at any time.
     ///
     /// # Returns
     ///
-    /// * `Ok(Feedback::Ok)` - If the register was set successfully
-    /// * `Err(DebuggerError)` - If the register could not be set
+    /// * `Ok(Feedback::Threads)` - The threads, ordered by thread ID
+    /// * `Err(DebuggerError)` - If the threads could not be listed
     ///
     /// # Errors
     ///
     /// This function can fail if:
     /// - The debuggee is not running
-    /// - Register access fails
-    ///
-    /// # Examples
-    ///
-    ///
-    /// ```no_run
-    /// #[cfg(feature = "cli")]
-    /// # mod featguard { fn _do_thing() {
-    /// # use coreminer::debugger::Debugger;
-    /// # use coreminer::ui::cli::CliUi;
-    /// # use coreminer::Register;
-    /// #
-    /// # let ui = CliUi::build(None).unwrap();
-    /// # let debugger = Debugger::build(ui).unwrap();
-    /// # // Assume debuggee is already running
-    /// #
-    /// // Set the value of the rax register to 0x42
-    /// debugger.set_reg(Register::rax, 0x42).unwrap();
-    ///
-    /// # }}
-    /// ```
-    pub fn set_reg(&self, r: Register, v: u64) -> Result<Feedback> {
-        let dbge = self.debuggee.as_ref().ok_or(DebuggerError::NoDebugee)?;
-        crate::set_reg(dbge.pid, r, v)?;
-        Ok(Feedback::Ok)
+    /// - `/proc/<pid>/task` cannot be read
+    pub fn list_threads(&mut self) -> Result<Feedback> {
+        let dbge = self.debuggee.as_mut().ok_or(DebuggerError::NoDebugee)?;
+        if dbge.core.is_some() {
+            return Ok(Feedback::Threads(dbge.threads.values().cloned().collect()));
+        }
+        let alive = thread::task_ids(dbge.pid)?;
+        dbge.threads.retain(|tid, _| alive.contains(tid));
+        for (tid, thread) in &mut dbge.threads {
+            thread.name = thread::thread_name(dbge.pid, *tid).ok();
+        }
+
+        Ok(Feedback::Threads(dbge.threads.values().cloned().collect()))
     }
 
-    /// Gets the current stack of the debugged process
+    /// Selects where a debuggee should be stopped after it was launched with [`Self::run`]
+    ///
+    /// Without this, the debuggee is stopped right after the `execve`, in the dynamic loader. With
+    /// a [`LaunchBreak`], [`Self::run`] sets a temporary hardware [`Breakpoint`] at the entry
+    /// point or `main` and continues the debuggee until it is reached, so the first stop the UI
+    /// sees is in the code of the executable.
+    ///
+    /// # Parameters
+    ///
+    /// * `launch_break` - Where to stop, or [`None`] to stop right after the `execve`
     ///
     /// # Returns
     ///
-    /// * `Ok(Feedback::Stack)` - The stack
-    /// * `Err(DebuggerError)` - If the stack could not be retrieved
+    /// * `Ok(Feedback::Ok)` - The setting was applied
     ///
     /// # Errors
     ///
-    /// This function can fail if:
-    /// - The debuggee is not running
-    /// - Stack memory cannot be accessed
+    /// Cannot fail.
     ///
     /// # Examples
     ///
@@ -1831,264 +10149,318 @@ impl<'executable, UI: DebuggerUI> Debugger<'executable, UI> {
     /// ```no_run
     /// #[cfg(feature = "cli")]
     /// # mod featguard { fn _do_thing() {
-    /// # use coreminer::debugger::Debugger;
+    /// # use coreminer::debugger::{Debugger, LaunchBreak};
     /// # use coreminer::ui::cli::CliUi;
-    /// # use coreminer::feedback::Feedback;
+    /// # use std::path::Path;
     /// #
     /// # let ui = CliUi::build(None).unwrap();
-    /// # let debugger = Debugger::build(ui).unwrap();
-    /// # // Assume debuggee is already running
+    /// # let mut debugger = Debugger::build(ui).unwrap();
     /// #
-    /// // Get the current stack
-    /// if let Ok(Feedback::Stack(stack)) = debugger.get_stack() {
-    ///     println!("Stack: {}", stack);
-    /// }
+    /// debugger.set_launch_break(Some(LaunchBreak::Main)).unwrap();
+    ///
+    /// // the debuggee is now stopped at the start of main
+    /// debugger.run(Path::new("/bin/ls"), &[]).unwrap();
     ///
     /// # }}
     /// ```
-    pub fn get_stack(&self) -> Result<Feedback> {
-        let dbge = self.debuggee.as_ref().ok_or(DebuggerError::NoDebugee)?;
-
-        let stack = dbge.get_stack()?;
-        Ok(Feedback::Stack(stack))
+    pub fn set_launch_break(&mut self, launch_break: Option<LaunchBreak>) -> Result<Feedback> {
+        info!("launch break: {launch_break:?}");
+        self.launch_break = launch_break;
+        Ok(Feedback::Ok)
     }
 
-    /// Gets the process memory map
+    /// Sets or removes a variable in the environment of debuggees launched later
+    ///
+    /// The environment of the debugger is not changed. See the
+    /// [launchenv module](crate::launchenv) for details.
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - Name of the variable
+    /// * `value` - The new value, or [`None`] to remove the variable
     ///
     /// # Returns
     ///
-    /// * `Ok(Feedback::ProcessMap)` - The process memory map
-    /// * `Err(DebuggerError)` - If the memory map could not be retrieved
+    /// * `Ok(Feedback::Ok)` - The variable was changed
+    /// * `Err(DebuggerError)` - If `name` is not a valid name
     ///
     /// # Errors
     ///
-    /// This function can fail if:
-    /// - The debuggee is not running
-    /// - The memory map cannot be accessed
+    /// This function fails if `name` is empty or contains a `=` or a null byte.
     ///
     /// # Examples
     ///
-    ///
     /// ```no_run
     /// #[cfg(feature = "cli")]
     /// # mod featguard { fn _do_thing() {
     /// # use coreminer::debugger::Debugger;
     /// # use coreminer::ui::cli::CliUi;
-    /// # use coreminer::feedback::Feedback;
     /// #
     /// # let ui = CliUi::build(None).unwrap();
-    /// # let debugger = Debugger::build(ui).unwrap();
-    /// # // Assume debuggee is already running
+    /// # let mut debugger = Debugger::build(ui).unwrap();
     /// #
-    /// // Get the process memory map
-    /// if let Ok(Feedback::ProcessMap(map)) = debugger.get_process_map() {
-    ///     println!("{:?}", map);
-    /// }
+    /// debugger.set_launch_env("MALLOC_CHECK_", Some("3")).unwrap();
+    /// debugger.set_launch_env("DISPLAY", None).unwrap();
+    /// debugger.run("/bin/ls", &[]).unwrap();
     ///
     /// # }}
     /// ```
-    pub fn get_process_map(&self) -> Result<Feedback> {
-        let dbge = self.debuggee.as_ref().ok_or(DebuggerError::NoDebugee)?;
-
-        let pm = dbge.get_process_map()?;
-
-        Ok(Feedback::ProcessMap(pm))
+    pub fn set_launch_env(&mut self, name: &str, value: Option<&str>) -> Result<Feedback> {
+        info!("launch environment: {name}={value:?}");
+        match value {
+            Some(value) => self.launch_env.set(name, value)?,
+            None => self.launch_env.unset(name)?,
+        }
+        Ok(Feedback::Ok)
     }
 
-    /// Gets a [`Breakpoint`] at the specified address
+    /// Loads a library before all others in debuggees launched later, with `LD_PRELOAD`
     ///
-    /// This method retrieves a [`Breakpoint`] object at the given address, if one exists.
-    /// It's primarily used by plugins to determine whether a [`Breakpoint`] exists at a
-    /// specific location.
+    /// Functions of the library take precedence over the functions with the same name in the
+    /// executable and its other libraries, which makes it possible to interpose them. The
+    /// library is added after the libraries that are already preloaded.
     ///
     /// # Parameters
     ///
-    /// * `addr` - The memory [`Addr`] to check for a [`Breakpoint`]
+    /// * `library` - Path of the library, relative paths are made absolute
     ///
     /// # Returns
     ///
-    /// * `Ok(Feedback::Breakpoint)` - Feedback containing either `Some(Breakpoint)` if a
-    ///   [`Breakpoint`] exists at the address, or [`None`] if there is no [`Breakpoint`]
-    /// * `Err(DebuggerError)` - If there was an error accessing the debuggee
+    /// * `Ok(Feedback::Ok)` - The library will be loaded
+    /// * `Err(DebuggerError)` - If the library does not exist
     ///
     /// # Errors
     ///
-    /// This function can fail if:
-    /// - The debuggee is not running (`DebuggerError::NoDebugee`)
+    /// This function fails if the path of the library cannot be resolved.
     ///
     /// # Examples
     ///
     /// ```no_run
     /// #[cfg(feature = "cli")]
     /// # mod featguard { fn _do_thing() {
+    /// # use std::path::Path;
     /// # use coreminer::debugger::Debugger;
     /// # use coreminer::ui::cli::CliUi;
-    /// # use coreminer::addr::Addr;
-    /// # use coreminer::feedback::Feedback;
     /// #
     /// # let ui = CliUi::build(None).unwrap();
     /// # let mut debugger = Debugger::build(ui).unwrap();
-    /// # // Set a breakpoint first
-    /// # debugger.set_bp(Addr::from(0x1000usize)).unwrap();
+    /// #
+    /// // a library that interposes malloc and free
+    /// debugger.preload_library(Path::new("./libtrace.so")).unwrap();
+    /// debugger.run("/bin/ls", &[]).unwrap();
     ///
-    /// // Check if a breakpoint exists at address 0x1000
-    /// if let Ok(Feedback::Breakpoint(maybe_bp)) = debugger.get_bp(Addr::from(0x1000usize)) {
-    ///     match maybe_bp {
-    ///         Some(bp) => println!("Found breakpoint at address 0x1000"),
-    ///         None => println!("No breakpoint at address 0x1000"),
-    ///     }
-    /// }
     /// # }}
     /// ```
-    pub fn get_bp(&self, addr: Addr) -> Result<Feedback> {
-        let dbge = self.debuggee.as_ref().ok_or(DebuggerError::NoDebugee)?;
-
-        let bp = dbge.breakpoints.get(&addr);
-
-        Ok(Feedback::Breakpoint(bp.cloned()))
+    pub fn preload_library(&mut self, library: &Path) -> Result<Feedback> {
+        let library = std::fs::canonicalize(library)?;
+        info!("preloading '{}'", library.to_string_lossy());
+        self.launch_env.preload(&library.to_string_lossy());
+        Ok(Feedback::Ok)
     }
 
-    /// Sets the last signal received from the [`Debuggee`]
-    ///
-    /// This method allows the [`Debugger`] or a plugin to set or modify the `last_signal`
-    /// that will be delivered to the [`Debuggee`].
+    /// Searches a directory for libraries first in debuggees launched later, with
+    /// `LD_LIBRARY_PATH`
     ///
-    /// The set signal will be used the next time the debugger continues execution with
-    /// [`Self::cont()`] or one of the stepping methods.
+    /// The directory is searched before the directories that are already in the search path.
     ///
     /// # Parameters
     ///
-    /// * `sig` - The signal number to set as the last signal
+    /// * `dir` - Path of the directory, relative paths are made absolute
     ///
     /// # Returns
     ///
-    /// * `Ok(Feedback::Ok)` - If the signal was successfully set
-    /// * `Err(DebuggerError)` - If the signal number could not be converted to a valid signal
+    /// * `Ok(Feedback::Ok)` - The directory will be searched
+    /// * `Err(DebuggerError)` - If the directory does not exist
     ///
     /// # Errors
     ///
-    /// This function can fail if:
-    /// - The signal number cannot be converted to a valid [`Signal`] type
+    /// This function fails if the path of the directory cannot be resolved.
     ///
     /// # Examples
     ///
     /// ```no_run
     /// #[cfg(feature = "cli")]
     /// # mod featguard { fn _do_thing() {
+    /// # use std::path::Path;
     /// # use coreminer::debugger::Debugger;
     /// # use coreminer::ui::cli::CliUi;
-    /// # use nix::sys::signal::Signal;
     /// #
     /// # let ui = CliUi::build(None).unwrap();
     /// # let mut debugger = Debugger::build(ui).unwrap();
+    /// #
+    /// // use a debug build of libc
+    /// debugger.add_library_path(Path::new("/opt/glibc-debug/lib")).unwrap();
+    /// debugger.run("/bin/ls", &[]).unwrap();
     ///
-    /// // Set SIGTRAP as the last signal
-    /// debugger.set_last_signal(Signal::SIGTRAP as i32).unwrap();
-    ///
-    /// // When continuing execution, this signal will be passed to the debuggee
-    /// debugger.cont().unwrap();
     /// # }}
     /// ```
-    pub fn set_last_signal(&mut self, sig: i32) -> Result<Feedback> {
-        let sig = Signal::try_from(sig)?;
-
-        info!("set last signal to {sig}");
-        self.last_signal = Some(sig);
+    pub fn add_library_path(&mut self, dir: &Path) -> Result<Feedback> {
+        let dir = std::fs::canonicalize(dir)?;
+        info!("searching libraries in '{}'", dir.to_string_lossy());
+        self.launch_env.add_library_path(&dir.to_string_lossy());
+        Ok(Feedback::Ok)
+    }
 
+    /// Selects if debuggees launched later get a pipe as their stdin
+    ///
+    /// Without the pipe, the debuggee shares the stdin of the debugger, which is a problem if
+    /// the debugger reads its commands from it, like `cmserve` does. With the pipe, the input of
+    /// the debuggee is sent with [`Self::send_stdin`].
+    ///
+    /// # Parameters
+    ///
+    /// * `enabled` - Whether to launch debuggees with a stdin pipe
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Feedback::Ok)` - The setting was applied
+    ///
+    /// # Errors
+    ///
+    /// Cannot fail.
+    pub fn set_stdin_pipe(&mut self, enabled: bool) -> Result<Feedback> {
+        info!("stdin pipe: {enabled}");
+        self.pipe_stdin = enabled;
         Ok(Feedback::Ok)
     }
 
-    /// Runs a program for debugging
+    /// Writes `bytes` into the stdin pipe of the debuggee
     ///
-    /// This function loads an executable, parses its debug information, and
-    /// launches it under debugger control.
+    /// The bytes are buffered by the pipe until the debuggee reads them, so they can be sent
+    /// while it is stopped. A pipe holds 64 KiB on Linux.
     ///
     /// # Parameters
     ///
-    /// * `executable_path` - Path to the executable
-    /// * `arguments` - Command-line arguments for the executable
+    /// * `bytes` - The input for the debuggee
     ///
     /// # Returns
     ///
-    /// * `Ok(Feedback::Ok)` - If the program was launched successfully
-    /// * `Err(DebuggerError)` - If the program could not be launched
+    /// * `Ok(Feedback::Ok)` - All bytes were written into the pipe
+    /// * `Err(DebuggerError)` - If the bytes could not be written
     ///
     /// # Errors
     ///
-    /// This function can fail if:
-    /// - A debuggee is already running
-    /// - The executable does not exist
-    /// - The executable is not a valid file
-    /// - Debug information cannot be parsed
-    /// - The process cannot be forked
+    /// This function fails if:
+    /// - There is no debuggee
+    /// - The debuggee was launched without a stdin pipe, or it was closed already
+    /// - The pipe is full, then the bytes that fit were written
+    /// - The debuggee closed its stdin
     ///
     /// # Examples
     ///
-    ///
     /// ```no_run
     /// #[cfg(feature = "cli")]
     /// # mod featguard { fn _do_thing() {
     /// # use coreminer::debugger::Debugger;
     /// # use coreminer::ui::cli::CliUi;
-    /// # use std::path::Path;
-    /// # use std::ffi::CString;
     /// #
     /// # let ui = CliUi::build(None).unwrap();
     /// # let mut debugger = Debugger::build(ui).unwrap();
     /// #
-    /// // Run a program with arguments
-    /// let program = Path::new("./target/debug/my_program");
-    /// let args = vec![
-    ///     CString::new("my_program").unwrap(),
-    ///     CString::new("--arg1").unwrap(),
-    ///     CString::new("value1").unwrap()
-    /// ];
+    /// debugger.set_stdin_pipe(true).unwrap();
+    /// debugger.run("/bin/cat", &[]).unwrap();
     ///
-    /// debugger.run(program, &args).unwrap();
+    /// // answer the read of cat, and let it exit after that
+    /// debugger.send_stdin(b"hello\n").unwrap();
+    /// debugger.close_stdin().unwrap();
     ///
     /// # }}
     /// ```
-    pub fn run(
-        &mut self,
-        executable_path: impl AsRef<Path>,
-        arguments: &[CString],
-    ) -> Result<Feedback> {
-        if self.debuggee.is_some() {
-            return Err(DebuggerError::AlreadyRunning);
+    pub fn send_stdin(&mut self, bytes: &[u8]) -> Result<Feedback> {
+        let dbge = self.debuggee.as_mut().ok_or(DebuggerError::NoDebugee)?;
+        let stdin = dbge.stdin.as_mut().ok_or(DebuggerError::NoStdinPipe)?;
+        let mut written = 0;
+        while written < bytes.len() {
+            match stdin.write(&bytes[written..]) {
+                Ok(n) => written += n,
+                Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {
+                    return Err(DebuggerError::StdinPipeFull(written, bytes.len()));
+                }
+                Err(e) => return Err(e.into()),
+            }
         }
+        debug!("sent {written} bytes to the stdin of the debuggee");
+        Ok(Feedback::Ok)
+    }
 
-        debug!(
-            "exe to run are: {}",
-            executable_path.as_ref().to_string_lossy()
-        );
-        debug!("arguments to run are: {arguments:?}");
-
-        // NOTE: the lifetimes of the raw object data have given us many problems. It would be
-        // possible to read the object data out in the main function and passing it to the
-        // constructor of Debugger, but that would mean that we cannot debug a different program in
-        // the same session.
-        let exe: &Path = executable_path.as_ref();
-        let exe: PathBuf = which(exe).unwrap_or(exe.into());
-        info!("using executable path '{}'", exe.to_string_lossy());
-
-        // First, read the file data
-        self.stored_obj_data_raw = std::fs::read(&exe)?;
+    /// Closes the stdin pipe of the debuggee
+    ///
+    /// Once it read all bytes that were sent, the debuggee reads the end of the file.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Feedback::Ok)` - The pipe was closed
+    /// * `Err(DebuggerError)` - If there is no stdin pipe to close
+    ///
+    /// # Errors
+    ///
+    /// This function fails if there is no debuggee, or if it has no stdin pipe.
+    pub fn close_stdin(&mut self) -> Result<Feedback> {
+        let dbge = self.debuggee.as_mut().ok_or(DebuggerError::NoDebugee)?;
+        dbge.stdin.take().ok_or(DebuggerError::NoStdinPipe)?;
+        info!("closed the stdin of the debuggee");
+        Ok(Feedback::Ok)
+    }
 
-        // Create a new scope to handle the borrow checker
-        {
-            // Create a reference to the raw data that matches the 'executable lifetime
-            let raw_data: &'executable [u8] = unsafe {
-                std::mem::transmute::<&[u8], &'executable [u8]>(&self.stored_obj_data_raw)
-            };
+    /// Continues a freshly launched debuggee until it reaches the [`LaunchBreak`]
+    ///
+    /// A hardware [`Breakpoint`] is used, so that the code of the debuggee is not modified, and
+    /// the instruction pointer is right at the target afterwards.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Feedback::Ok)` - The debuggee is stopped at the target
+    /// * `Ok(Feedback::Exit)` - The debuggee exited before reaching the target
+    /// * `Err(DebuggerError)` - If the target could not be determined or reached
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if:
+    /// - The auxiliary vector of the debuggee cannot be read
+    /// - The breakpoint cannot be set
+    /// - The debuggee stopped somewhere else, for example because of a signal
+    fn run_to_launch_break(&mut self, launch_break: LaunchBreak) -> Result<Feedback> {
+        let dbge = self.debuggee.as_ref().ok_or(DebuggerError::NoDebugee)?;
+        let entry = dbge
+            .get_auxv()?
+            .entry
+            .ok_or(DebuggerError::LaunchBreakNotFound(launch_break))?;
+        let target = match launch_break {
+            LaunchBreak::Entry => entry,
+            LaunchBreak::Main => {
+                let obj = object::File::parse(&*self.executable_data)?;
+                if let Some(main) = obj
+                    .symbols()
+                    .find(|s| s.name() == Ok("main") && s.address() != 0)
+                {
+                    self.executable()?.to_process(main.address())
+                } else if let Some(main) = self.heuristic_symbols()?.by_name("main") {
+                    info!(
+                        "the executable has no symbol for main, found it at {}",
+                        main.addr
+                    );
+                    main.addr
+                } else {
+                    warn!("the executable has no symbol for main, stopping at the entry point");
+                    entry
+                }
+            }
+        };
 
-            // Parse the object file
-            let obj_data = object::File::parse(raw_data)?;
-            self.stored_obj_data = Some(obj_data);
+        info!("running to {launch_break:?} at {target}");
+        self.set_hw_bp(target)?;
+        let feedback = self.cont();
+        if self.debuggee.is_some() {
+            self.del_bp(target)?;
+        }
+        let feedback = feedback?;
+        if let Feedback::Exit(_) = feedback {
+            return Ok(feedback);
         }
 
-        // Now launch the debuggee
-        self.launch_debuggee(&exe, arguments)?;
-
+        if self.get_current_addr()? != target {
+            return Err(DebuggerError::LaunchBreakNotFound(launch_break));
+        }
         Ok(Feedback::Ok)
     }
 
@@ -2144,7 +10516,7 @@ impl<'executable, UI: DebuggerUI> Debugger<'executable, UI> {
     ///                 println!("got word {w}");
     ///                 Ok(Status::PluginContinue)
     ///             } else {
-    ///                 Ok(Status::ReadMem(Addr::from(0xdeadbeef_usize)))
+    ///                 Ok(Status::ReadMem(Addr::from(0xdeadbeef_usize).into()))
     ///             }
     ///         }).unwrap();
     ///     }
@@ -2160,14 +10532,13 @@ impl<'executable, UI: DebuggerUI> Debugger<'executable, UI> {
         F: FnMut(&Feedback) -> Result<Status>,
     {
         let mut feedback = Feedback::Ok;
-        let mut status;
         let mut guard = 0;
         loop {
             if guard > 10 {
                 return Err(DebuggerError::TooManyPluginIterations);
             }
             guard += 1;
-            status = match f(&feedback) {
+            let status = match f(&feedback) {
                 Ok(s) => s,
                 Err(e) => {
                     error!("Error in Hook '{}': {e}", hook_name);
@@ -2178,11 +10549,75 @@ impl<'executable, UI: DebuggerUI> Debugger<'executable, UI> {
                 break;
             }
             feedback = self.process_status(&status)?;
+            // meant for the user, the hook gets them back to know that they were shown
+            match &feedback {
+                Feedback::Text(text) => {
+                    self.push_event(Event::Feedback(Feedback::Text(text.clone())));
+                }
+                Feedback::Custom(value) => {
+                    self.push_event(Event::Feedback(Feedback::Custom(value.clone())));
+                }
+                _ => (),
+            }
         }
 
         Ok(())
     }
 
+    /// Runs the `EOnCommand` hooks of plugins for a command of the UI
+    ///
+    /// # Errors
+    ///
+    /// This function fails if a hook runs for too long, see [`Self::hook_feedback_loop`].
+    #[allow(unused_variables, clippy::unused_self, clippy::unnecessary_wraps)] // used for plugins
+    fn run_command_hooks(&mut self, command: &Status) -> Result<()> {
+        for_hooks!(
+            for hook[EOnCommand] in self {
+                self.hook_feedback_loop(hook.name(), |f| {
+                    hook.inner_mut().on_command(f, command)
+                })?;
+            }
+        );
+        Ok(())
+    }
+
+    /// Runs the `EOnStop` hooks of plugins, and the `EOnBreakpointHit` or `EOnExit` hooks if the
+    /// debuggee stopped at a [`Breakpoint`] or terminated
+    ///
+    /// # Errors
+    ///
+    /// This function fails if a hook runs for too long, see [`Self::hook_feedback_loop`].
+    #[allow(unused_variables, clippy::unused_self, clippy::unnecessary_wraps)] // used for plugins
+    fn run_stop_hooks(&mut self, reason: StopReason) -> Result<()> {
+        for_hooks!(
+            for hook[EOnStop] in self {
+                self.hook_feedback_loop(hook.name(), |f| hook.inner_mut().on_stop(f, &reason))?;
+            }
+        );
+        match reason {
+            StopReason::Breakpoint { addr, hardware } => {
+                for_hooks!(
+                    for hook[EOnBreakpointHit] in self {
+                        self.hook_feedback_loop(hook.name(), |f| {
+                            hook.inner_mut().on_breakpoint_hit(f, addr, hardware)
+                        })?;
+                    }
+                );
+            }
+            StopReason::Exited(status) => {
+                for_hooks!(
+                    for hook[EOnExit] in self {
+                        self.hook_feedback_loop(hook.name(), |f| {
+                            hook.inner_mut().on_exit(f, &status)
+                        })?;
+                    }
+                );
+            }
+            _ => (),
+        }
+        Ok(())
+    }
+
     // NOTE: this is used in the for_hooks macro
     //
     /// Get a reference to the [`PluginManager`] of this [`Debugger`]
@@ -2191,6 +10626,58 @@ impl<'executable, UI: DebuggerUI> Debugger<'executable, UI> {
         self.plugins.clone()
     }
 
+    /// Loads and enables a [`Plugin`] of another crate
+    ///
+    /// The hooks of the plugin are called at the
+    /// [extension points](crate::plugins::extension_points) from then on. Loading a plugin
+    /// with the id of an already loaded plugin fails, which is only logged, just like with the
+    /// default plugins, see [`crate::plugins::load_plugin`].
+    ///
+    /// # Panics
+    ///
+    /// This method will panic if it cannot acquire a lock on the plugin manager.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use coreminer::debugger::Debugger;
+    /// # use coreminer::ui::DebuggerUI;
+    /// # use coreminer::errors::Result;
+    /// # use coreminer::feedback::{Feedback, Status, StopReason};
+    /// # use coreminer::plugins::extension_points::{EOnStop, EOnStopF};
+    /// use steckrs::simple_plugin;
+    ///
+    /// struct StopCounter(usize);
+    /// impl EOnStopF for StopCounter {
+    ///     fn on_stop(&mut self, feedback: &Feedback, _reason: &StopReason) -> Result<Status> {
+    ///         match feedback {
+    ///             // the text was shown to the user
+    ///             Feedback::Text(_) => Ok(Status::PluginContinue),
+    ///             _ => {
+    ///                 self.0 += 1;
+    ///                 Ok(Status::PluginText(format!("stop number {}", self.0)))
+    ///             }
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// simple_plugin!(
+    ///     StopCounterPlugin,
+    ///     "stop_counter",
+    ///     "Counts the stops of the debuggee",
+    ///     hooks: [(EOnStop, StopCounter(0))]
+    /// );
+    ///
+    /// # fn helper<UI: DebuggerUI>(debugger: &mut Debugger<UI>) {
+    /// debugger.load_plugin(StopCounterPlugin::new());
+    /// # }
+    /// ```
+    #[cfg(feature = "plugins")]
+    pub fn load_plugin<P: Plugin>(&mut self, plugin: P) {
+        let mut plugins = self.plugins.lock().expect("could not lock plugin_manager");
+        crate::plugins::load_plugin(&mut plugins, plugin);
+    }
+
     /// Enables or disables a plugin by its ID
     ///
     /// This method modifies the enabled status of a plugin in the plugin manager.
@@ -0,0 +1,172 @@
+//! # Debug Register Module
+//!
+//! Provides access to the hardware debug registers of a debugged process.
+//!
+//! `x86_64` processors have four debug address registers (`DR0` to `DR3`), each of which can hold
+//! the address of a hardware breakpoint, a status register (`DR6`), which tells which breakpoint
+//! was hit, and a control register (`DR7`), which enables the breakpoints and selects what they
+//! react to. The registers of a tracee are accessed through the `u_debugreg` field of its user
+//! area with `PTRACE_PEEKUSER` and `PTRACE_POKEUSER`.
+//!
+//! Hardware execute breakpoints do not modify the code of the debuggee, so they work for code
+//! that cannot be written or that checks itself for modifications. The debuggee is stopped
+//! *before* the instruction at the breakpoint is executed, with the instruction pointer at the
+//! breakpoint address.
+
+use nix::libc::user;
+use nix::sys::ptrace;
+use nix::unistd::Pid;
+
+use crate::errors::{DebuggerError, Result};
+use crate::Addr;
+
+/// Number of debug address registers (`DR0` to `DR3`), and therefore of hardware breakpoints
+pub const DEBUG_ADDR_REGISTERS: usize = 4;
+/// Index of the debug status register
+pub const DR6: usize = 6;
+/// Index of the debug control register
+pub const DR7: usize = 7;
+
+/// Offset of the debug registers in the user area of a process
+const DEBUGREG_OFFSET: usize = std::mem::offset_of!(user, u_debugreg);
+/// Size of one debug register in the user area
+const DEBUGREG_SIZE: usize = std::mem::size_of::<u64>();
+
+/// Reads a debug register of a process
+///
+/// # Parameters
+///
+/// * `pid` - The process ID of the debuggee
+/// * `index` - The number of the register, for example [`DR7`]
+///
+/// # Errors
+///
+/// This function will fail if the register cannot be read.
+pub fn read_debugreg(pid: Pid, index: usize) -> Result<u64> {
+    let offset = DEBUGREG_OFFSET + index * DEBUGREG_SIZE;
+    Ok(ptrace::read_user(pid, offset as ptrace::AddressType)? as u64)
+}
+
+/// Writes a debug register of a process
+///
+/// The kernel validates the written values, for example, a breakpoint address must be in user
+/// space.
+///
+/// # Parameters
+///
+/// * `pid` - The process ID of the debuggee
+/// * `index` - The number of the register, for example [`DR7`]
+/// * `value` - The new value of the register
+///
+/// # Errors
+///
+/// This function will fail if the register cannot be written.
+pub fn write_debugreg(pid: Pid, index: usize, value: u64) -> Result<()> {
+    let offset = DEBUGREG_OFFSET + index * DEBUGREG_SIZE;
+    ptrace::write_user(pid, offset as ptrace::AddressType, value as i64)?;
+    Ok(())
+}
+
+/// Computes a new value of [`DR7`] with the execute breakpoint in `slot` enabled or disabled
+///
+/// An execute breakpoint uses the condition `00` (instruction execution) and the length `00`
+/// (one byte), so both fields are cleared. Only the local enable bit is used, which is what the
+/// kernel expects for breakpoints of a single process.
+///
+/// # Examples
+///
+/// ```
+/// use coreminer::debugreg::dr7_with_execute_breakpoint;
+///
+/// let dr7 = dr7_with_execute_breakpoint(0, 1, true);
+/// assert_eq!(dr7, 0b100);
+/// assert_eq!(dr7_with_execute_breakpoint(dr7, 1, false), 0);
+/// ```
+#[must_use]
+pub fn dr7_with_execute_breakpoint(dr7: u64, slot: usize, enabled: bool) -> u64 {
+    let local_enable = 1 << (slot * 2);
+    let condition_and_len = 0b1111 << (16 + slot * 4);
+
+    let dr7 = dr7 & !condition_and_len;
+    if enabled {
+        dr7 | local_enable
+    } else {
+        dr7 & !local_enable
+    }
+}
+
+/// Sets a hardware execute breakpoint in a debug address register
+///
+/// # Parameters
+///
+/// * `pid` - The process ID of the debuggee
+/// * `slot` - The debug address register to use, `0..4`
+/// * `addr` - The address of the instruction to break at
+///
+/// # Errors
+///
+/// This function will fail if `slot` is not a debug address register or if the debug registers
+/// cannot be accessed.
+///
+/// # Examples
+///
+/// ```no_run
+/// use coreminer::debugreg::set_execute_breakpoint;
+/// use coreminer::addr::Addr;
+/// use nix::unistd::Pid;
+///
+/// set_execute_breakpoint(Pid::from_raw(1234), 0, Addr::from(0x401136usize)).unwrap();
+/// ```
+pub fn set_execute_breakpoint(pid: Pid, slot: usize, addr: Addr) -> Result<()> {
+    if slot >= DEBUG_ADDR_REGISTERS {
+        return Err(DebuggerError::InvalidDebugRegister(slot));
+    }
+    // the address must be set before the breakpoint is enabled, the kernel refuses otherwise
+    write_debugreg(pid, slot, addr.u64())?;
+    let dr7 = read_debugreg(pid, DR7)?;
+    write_debugreg(pid, DR7, dr7_with_execute_breakpoint(dr7, slot, true))
+}
+
+/// Removes the hardware breakpoint in a debug address register
+///
+/// # Parameters
+///
+/// * `pid` - The process ID of the debuggee
+/// * `slot` - The debug address register to clear, `0..4`
+///
+/// # Errors
+///
+/// This function will fail if `slot` is not a debug address register or if the debug registers
+/// cannot be accessed.
+pub fn clear_breakpoint(pid: Pid, slot: usize) -> Result<()> {
+    if slot >= DEBUG_ADDR_REGISTERS {
+        return Err(DebuggerError::InvalidDebugRegister(slot));
+    }
+    let dr7 = read_debugreg(pid, DR7)?;
+    write_debugreg(pid, DR7, dr7_with_execute_breakpoint(dr7, slot, false))?;
+    write_debugreg(pid, slot, 0)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_dr7_with_execute_breakpoint() {
+        let mut dr7 = 0;
+        for slot in 0..DEBUG_ADDR_REGISTERS {
+            dr7 = dr7_with_execute_breakpoint(dr7, slot, true);
+        }
+        assert_eq!(dr7, 0b0101_0101);
+
+        // a write watchpoint of 8 bytes in slot 3 is turned into an execute breakpoint
+        let dr7 = dr7_with_execute_breakpoint(0b1011 << 28, 3, true);
+        assert_eq!(dr7, 1 << 6);
+
+        assert_eq!(
+            dr7_with_execute_breakpoint(0b0101_0101, 2, false),
+            0b0100_0101
+        );
+        assert_eq!(DEBUGREG_OFFSET, 848);
+    }
+}
@@ -16,9 +16,11 @@
 use std::fmt::{Display, Write};
 
 use crate::errors::{DebuggerError, Result};
+use crate::feedback::ChunkItems;
 use crate::Addr;
 
-const CODE_BITNESS: u32 = 64;
+/// Bitness of the code of the debuggee
+pub(crate) const CODE_BITNESS: u32 = 64;
 
 use iced_x86::{
     Decoder, DecoderOptions, Formatter, FormatterOutput, FormatterTextKind, Instruction,
@@ -191,28 +193,7 @@ impl Disassembly {
     pub fn disassemble(data: &[u8], first_addr: Addr, bp_indexes: &[usize]) -> Result<Self> {
         let mut decoder =
             Decoder::with_ip(CODE_BITNESS, data, first_addr.into(), DecoderOptions::NONE);
-        let mut formatter = NasmFormatter::new();
-
-        // padding
-        formatter.options_mut().set_first_operand_char_index(16);
-
-        // numbers stuff
-        formatter.options_mut().set_hex_suffix("");
-        formatter.options_mut().set_hex_prefix("");
-        formatter.options_mut().set_uppercase_hex(false);
-        formatter.options_mut().set_decimal_suffix("");
-        formatter.options_mut().set_decimal_prefix("0d");
-        formatter.options_mut().set_octal_suffix("");
-        formatter.options_mut().set_octal_prefix("0o");
-        formatter.options_mut().set_binary_suffix("");
-        formatter.options_mut().set_binary_prefix("0b");
-
-        // memory stuff
-        formatter.options_mut().set_show_symbol_address(true);
-        formatter.options_mut().set_rip_relative_addresses(false);
-        formatter
-            .options_mut()
-            .set_memory_size_options(iced_x86::MemorySizeOptions::Always);
+        let mut formatter = formatter();
 
         let mut disassembly = Self::empty();
         let mut instruction = Instruction::default();
@@ -222,17 +203,19 @@ impl Disassembly {
             text_contents.clear();
             formatter.format(&instruction, &mut text_contents);
 
-            let start_index = (instruction.ip() - Into::<u64>::into(first_addr)) as usize;
+            let ip = Addr::from(instruction.ip());
+            // the decoder only decodes instructions in data, so they are after first_addr
+            let start_index = ip.offset_from(first_addr).unwrap_or_default();
             let instr_bytes = &data[start_index..start_index + instruction.len()];
 
             if let Err(e) = disassembly.write_to_line(
-                instruction.ip().into(),
+                ip,
                 instr_bytes,
                 text_contents.inner(),
-                bp_indexes.contains(&(instruction.ip() as usize - first_addr.usize())),
+                bp_indexes.contains(&start_index),
             ) {
                 warn!("Error while disassembling, skipping: {e}");
-            };
+            }
         }
 
         Ok(disassembly)
@@ -305,6 +288,34 @@ impl Disassembly {
     }
 }
 
+/// Creates the formatter that is used for all disassembly shown by the debugger
+pub(crate) fn formatter() -> NasmFormatter {
+    let mut formatter = NasmFormatter::new();
+
+    // padding
+    formatter.options_mut().set_first_operand_char_index(16);
+
+    // numbers stuff
+    formatter.options_mut().set_hex_suffix("");
+    formatter.options_mut().set_hex_prefix("");
+    formatter.options_mut().set_uppercase_hex(false);
+    formatter.options_mut().set_decimal_suffix("");
+    formatter.options_mut().set_decimal_prefix("0d");
+    formatter.options_mut().set_octal_suffix("");
+    formatter.options_mut().set_octal_prefix("0o");
+    formatter.options_mut().set_binary_suffix("");
+    formatter.options_mut().set_binary_prefix("0b");
+
+    // memory stuff
+    formatter.options_mut().set_show_symbol_address(true);
+    formatter.options_mut().set_rip_relative_addresses(false);
+    formatter
+        .options_mut()
+        .set_memory_size_options(iced_x86::MemorySizeOptions::Always);
+
+    formatter
+}
+
 impl FormatterOutput for DisassemblyOutput {
     /// Writes a piece of text with its kind to this output
     ///
@@ -372,6 +383,19 @@ impl From<&TextContent> for SerializableTextContent {
     }
 }
 
+impl ChunkItems for &Disassembly {
+    fn len(&self) -> usize {
+        self.vec.len()
+    }
+
+    fn write_item(&self, index: usize, out: &mut dyn std::io::Write) -> serde_json::Result<()> {
+        let (addr, raw, content, has_bp) = &self.vec[index];
+        let content: Vec<SerializableTextContent> =
+            content.iter().map(SerializableTextContent::from).collect();
+        serde_json::to_writer(out, &(addr, raw, content, has_bp))
+    }
+}
+
 fn serialize_disassembly_vec<S>(
     data: &[(Addr, Vec<u8>, Vec<TextContent>, bool)],
     serializer: S,
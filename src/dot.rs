@@ -0,0 +1,283 @@
+//! # DOT Module
+//!
+//! Provides exporting call graphs and backtraces of a debugged process in the DOT language of
+//! graphviz.
+//!
+//! A [`CallGraph`] has the functions of the debuggee as nodes and an edge from each caller to
+//! each function it calls. It can be built in two ways:
+//!
+//! - [`CallGraph::from_calls`] uses the direct calls that were found by disassembling the code
+//!   of the debuggee, see [`find_calls`](crate::xref::find_calls). This shows what could be
+//!   called, but misses indirect calls like `call rax`. The edges are labeled with the number of
+//!   call sites.
+//! - [`CallGraph::from_backtraces`] merges backtraces that were recorded while debugging. This
+//!   shows what was actually called, but only for the places where the debuggee stopped. The
+//!   edges are labeled with the number of backtraces they appear in.
+//!
+//! The DOT text can be rendered with external tools, for example `dot -Tsvg graph.dot`.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::{self, Display};
+
+use serde::Serialize;
+
+use crate::unwind::{Backtrace, BacktraceFrame};
+use crate::xref::{Xref, XrefKind};
+use crate::Addr;
+
+/// A function of the debuggee, which calls are attributed to when building a [`CallGraph`]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+pub struct FunctionRange {
+    /// Address of the first instruction of the function
+    pub start: Addr,
+    /// Address after the last instruction of the function
+    pub end: Addr,
+    /// Name of the function
+    pub name: Option<String>,
+}
+
+/// Functions and the calls between them
+///
+/// Nodes are identified by the start address of their function, which is also their id in the
+/// DOT language. Functions without a name are labeled with their address.
+///
+/// # Examples
+///
+/// ```
+/// use coreminer::dot::CallGraph;
+/// use coreminer::unwind::{Backtrace, BacktraceFrame};
+/// use coreminer::addr::Addr;
+///
+/// let frame = |addr: usize, name: &str| BacktraceFrame {
+///     addr: Addr::from(addr + 0x10),
+///     start_addr: Some(Addr::from(addr)),
+///     name: Some(name.to_string()),
+/// };
+/// let backtrace = Backtrace {
+///     frames: vec![frame(0x1200, "compute"), frame(0x1100, "main")],
+/// };
+///
+/// let graph = CallGraph::from_backtraces(&[backtrace]);
+/// assert_eq!(graph.edges().count(), 1);
+/// assert!(graph.to_string().contains("[label=\"compute\"]"));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct CallGraph {
+    nodes: BTreeMap<Addr, Option<String>>,
+    edges: BTreeMap<(Addr, Addr), usize>,
+}
+
+impl CallGraph {
+    /// Builds the call graph of direct calls
+    ///
+    /// Each call is attributed to the function in `functions` that contains the calling
+    /// instruction. Calls from outside of all functions are left out, called addresses that are
+    /// not the start of a function become nodes of their own, like the PLT stubs of shared
+    /// library functions. Xrefs that are not calls are ignored.
+    ///
+    /// # Parameters
+    ///
+    /// * `calls` - The call instructions, with the called address as target
+    /// * `functions` - The functions of the debuggee
+    #[must_use]
+    pub fn from_calls(calls: &[Xref], functions: &[FunctionRange]) -> Self {
+        let mut graph = Self::default();
+        let containing = |addr: Addr| functions.iter().find(|f| f.start <= addr && addr < f.end);
+
+        for call in calls.iter().filter(|x| x.kind == XrefKind::Call) {
+            let Some(caller) = containing(call.addr) else {
+                continue;
+            };
+            let callee_name = functions
+                .iter()
+                .find(|f| f.start == call.target)
+                .and_then(|f| f.name.clone());
+            graph.add_node(caller.start, caller.name.clone());
+            graph.add_node(call.target, callee_name);
+            *graph.edges.entry((caller.start, call.target)).or_default() += 1;
+        }
+
+        graph
+    }
+
+    /// Builds the call graph of recorded backtraces
+    ///
+    /// Each frame of a backtrace is called by the frame after it. Frames without a known
+    /// function start are identified by their address. A call that appears more than once in
+    /// the same backtrace, like in a recursion, is only counted once for it.
+    ///
+    /// # Parameters
+    ///
+    /// * `backtraces` - The backtraces, with the innermost frame first
+    #[must_use]
+    pub fn from_backtraces(backtraces: &[Backtrace]) -> Self {
+        let mut graph = Self::default();
+
+        for backtrace in backtraces {
+            let mut seen = BTreeSet::new();
+            let node = |frame: &BacktraceFrame| frame.start_addr.unwrap_or(frame.addr);
+            for frame in &backtrace.frames {
+                graph.add_node(node(frame), frame.name.clone());
+            }
+            for pair in backtrace.frames.windows(2) {
+                let edge = (node(&pair[1]), node(&pair[0]));
+                if seen.insert(edge) {
+                    *graph.edges.entry(edge).or_default() += 1;
+                }
+            }
+        }
+
+        graph
+    }
+
+    /// Adds a node, keeping the name it already has if `name` is [`None`]
+    fn add_node(&mut self, addr: Addr, name: Option<String>) {
+        let node = self.nodes.entry(addr).or_default();
+        if name.is_some() {
+            *node = name;
+        }
+    }
+
+    /// Returns the nodes as start address and name of their function, ordered by address
+    pub fn nodes(&self) -> impl Iterator<Item = (Addr, Option<&str>)> {
+        self.nodes
+            .iter()
+            .map(|(addr, name)| (*addr, name.as_deref()))
+    }
+
+    /// Returns the edges as caller, callee and count, ordered by caller and callee
+    pub fn edges(&self) -> impl Iterator<Item = (Addr, Addr, usize)> + '_ {
+        self.edges
+            .iter()
+            .map(|((caller, callee), count)| (*caller, *callee, *count))
+    }
+
+    /// Returns the label of the node at `addr`
+    fn label(&self, addr: Addr) -> String {
+        match self.nodes.get(&addr) {
+            Some(Some(name)) => name.clone(),
+            _ => addr.to_string(),
+        }
+    }
+}
+
+impl Display for CallGraph {
+    /// Writes the graph in the DOT language
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "digraph calls {{")?;
+        writeln!(f, "    node [shape=box];")?;
+        for (addr, _) in self.nodes() {
+            writeln!(f, "    \"{addr}\" [label={}];", quote(&self.label(addr)))?;
+        }
+        for (caller, callee, count) in self.edges() {
+            write!(f, "    \"{caller}\" -> \"{callee}\"")?;
+            if count > 1 {
+                write!(f, " [label=\"{count}\"]")?;
+            }
+            writeln!(f, ";")?;
+        }
+        write!(f, "}}")
+    }
+}
+
+/// Quotes `s` as a DOT identifier
+fn quote(s: &str) -> String {
+    let mut quoted = String::with_capacity(s.len() + 2);
+    quoted.push('"');
+    for c in s.chars() {
+        if matches!(c, '"' | '\\') {
+            quoted.push('\\');
+        }
+        quoted.push(c);
+    }
+    quoted.push('"');
+    quoted
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn call(addr: usize, target: usize) -> Xref {
+        Xref {
+            addr: Addr::from(addr),
+            target: Addr::from(target),
+            kind: XrefKind::Call,
+            instruction: String::new(),
+        }
+    }
+
+    fn function(start: usize, end: usize, name: &str) -> FunctionRange {
+        FunctionRange {
+            start: Addr::from(start),
+            end: Addr::from(end),
+            name: Some(name.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_from_calls() {
+        let functions = [
+            function(0x1100, 0x1180, "main"),
+            function(0x1180, 0x11c0, "helper"),
+        ];
+        let calls = [
+            call(0x1110, 0x1180),
+            call(0x1120, 0x1180),
+            call(0x1130, 0x1030),
+            call(0x1190, 0x1030),
+            // outside of all functions
+            call(0x1010, 0x1100),
+        ];
+
+        let graph = CallGraph::from_calls(&calls, &functions);
+        assert_eq!(graph.nodes().count(), 3);
+        assert_eq!(
+            graph.edges().collect::<Vec<_>>(),
+            vec![
+                (Addr::from(0x1100usize), Addr::from(0x1030usize), 1),
+                (Addr::from(0x1100usize), Addr::from(0x1180usize), 2),
+                (Addr::from(0x1180usize), Addr::from(0x1030usize), 1),
+            ]
+        );
+
+        let dot = graph.to_string();
+        assert!(dot.starts_with("digraph calls {"));
+        let [main, helper, plt] = [0x1100usize, 0x1180, 0x1030].map(Addr::from);
+        assert!(dot.contains(&format!("    \"{main}\" [label=\"main\"];\n")));
+        assert!(dot.contains(&format!("    \"{plt}\" [label=\"{plt}\"];\n")));
+        assert!(dot.contains(&format!("    \"{main}\" -> \"{helper}\" [label=\"2\"];\n")));
+        assert!(dot.contains(&format!("    \"{helper}\" -> \"{plt}\";\n")));
+    }
+
+    #[test]
+    fn test_from_backtraces() {
+        let frame = |start: usize, name: Option<&str>| BacktraceFrame {
+            addr: Addr::from(start + 4),
+            start_addr: Some(Addr::from(start)),
+            name: name.map(str::to_string),
+        };
+        let recursive = Backtrace {
+            frames: vec![
+                frame(0x1200, Some("fib")),
+                frame(0x1200, Some("fib")),
+                frame(0x1100, Some("main")),
+            ],
+        };
+        let other = Backtrace {
+            frames: vec![frame(0x1200, None), frame(0x1100, Some("main"))],
+        };
+
+        let graph = CallGraph::from_backtraces(&[recursive, other]);
+        assert_eq!(
+            graph.edges().collect::<Vec<_>>(),
+            vec![
+                (Addr::from(0x1100usize), Addr::from(0x1200usize), 2),
+                (Addr::from(0x1200usize), Addr::from(0x1200usize), 1),
+            ]
+        );
+        // a frame without a name does not remove the name of the node
+        assert_eq!(graph.label(Addr::from(0x1200usize)), "fib");
+        assert_eq!(quote("a\"b\\c"), "\"a\\\"b\\\\c\"");
+    }
+}
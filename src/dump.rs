@@ -0,0 +1,524 @@
+//! # Dump Module
+//!
+//! Provides dumping of loaded modules (the executable or a shared library) from the memory of a
+//! debugged process into an ELF file.
+//!
+//! This is useful for packed or self-modifying programs: after the program has unpacked itself,
+//! the code in memory differs from the code in the file on disk. The dump contains the memory
+//! image of all loadable segments of the module, with the program headers fixed up so that the
+//! file offset of each segment matches its place in the image. Since section headers are usually
+//! not loaded into memory, they are taken from the file on disk, if it is still available, and
+//! adjusted to the new layout.
+//!
+//! Note that the dumped image contains the state of the process at the time of the dump, like
+//! the resolved entries of the GOT and modified global variables.
+
+use std::path::{Path, PathBuf};
+
+use nix::unistd::Pid;
+use object::elf::{Dyn64, FileHeader64, ProgramHeader64, SectionHeader64};
+use object::LittleEndian as LE;
+use serde::Serialize;
+use tracing::{debug, warn};
+
+use crate::errors::{DebuggerError, Result};
+use crate::memorymap::ProcessMemoryMap;
+use crate::{mem_read, Addr};
+
+/// Size of a memory page
+const PAGE_SIZE: u64 = 0x1000;
+/// Size of the ELF header
+const EHDR_SIZE: usize = size_of::<FileHeader64<LE>>();
+/// Size of a program header
+const PHDR_SIZE: usize = size_of::<ProgramHeader64<LE>>();
+/// `DT_RELR`: address of the packed relative relocations, not yet known to [`object`]
+const DT_RELR: u32 = 36;
+/// Size of a section header
+const SHDR_SIZE: usize = size_of::<SectionHeader64<LE>>();
+
+/// Describes a module that was dumped into a file
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+pub struct ModuleDump {
+    /// Path of the module that was dumped, as shown in the memory map
+    pub module: String,
+    /// Address at which the module is loaded
+    pub base: Addr,
+    /// Path of the written ELF file
+    pub output: PathBuf,
+    /// Size of the written ELF file in bytes
+    pub size: usize,
+    /// Number of section headers that could be restored from the file on disk
+    pub sections: usize,
+}
+
+/// Dumps a loaded module of a process into an ELF file
+///
+/// # Parameters
+///
+/// * `pid` - The process ID of the debuggee
+/// * `map` - The memory map of the process
+/// * `module` - The module to dump: a full path, a file name or the start of a file name (like
+///   `libc`). If [`None`], the executable of the process is dumped.
+/// * `output` - Path of the ELF file to write
+///
+/// # Errors
+///
+/// This function fails if the module is not loaded, if it does not start with a valid ELF header
+/// or if the output file cannot be written.
+///
+/// # Examples
+///
+/// ```no_run
+/// use coreminer::dump::dump_module;
+/// use coreminer::memorymap::ProcessMemoryMap;
+/// use nix::unistd::Pid;
+///
+/// let pid = Pid::from_raw(1234);
+/// let map: ProcessMemoryMap = proc_maps::get_process_maps(pid.into()).unwrap().into();
+/// let dump = dump_module(pid, &map, None, "/tmp/unpacked".as_ref()).unwrap();
+/// println!("wrote {} bytes to {}", dump.size, dump.output.display());
+/// ```
+pub fn dump_module(
+    pid: Pid,
+    map: &ProcessMemoryMap,
+    module: Option<&str>,
+    output: &Path,
+) -> Result<ModuleDump> {
+    let exe = std::fs::read_link(format!("/proc/{pid}/exe"))?;
+    let path = find_module(map, module.unwrap_or(&exe.to_string_lossy()))?;
+    let base = map
+        .lowest_address_of(&path)
+        .ok_or_else(|| DebuggerError::ModuleNotFound(path.clone()))?;
+
+    let mut header = [0; EHDR_SIZE];
+    mem_read(&mut header, pid, base)?;
+    let (ehdr, _) = pod::<FileHeader64<LE>>(&header)?;
+    let mut phdrs = vec![0; usize::from(ehdr.e_phnum.get(LE)) * PHDR_SIZE];
+    mem_read(&mut phdrs, pid, base + ehdr.e_phoff.get(LE) as usize)?;
+    let phdrs = pod_slice::<ProgramHeader64<LE>>(&phdrs, usize::from(ehdr.e_phnum.get(LE)))?;
+
+    let (lo, hi) = load_range(phdrs)?;
+    let bias = if ehdr.e_type.get(LE) == object::elf::ET_DYN {
+        base.u64() - lo
+    } else {
+        0
+    };
+    debug!("dumping {path} loaded at {base} (bias {bias:#x}), image {lo:#x}..{hi:#x}");
+
+    let mut image = vec![0; (hi - lo) as usize];
+    for phdr in phdrs
+        .iter()
+        .filter(|p| p.p_type.get(LE) == object::elf::PT_LOAD)
+    {
+        let start = page_down(phdr.p_vaddr.get(LE));
+        let end = phdr.p_vaddr.get(LE) + phdr.p_memsz.get(LE);
+        read_lenient(
+            pid,
+            Addr::from(start + bias),
+            &mut image[(start - lo) as usize..(end - lo) as usize],
+        );
+    }
+
+    // the file on disk may be gone or replaced, so only use it if it looks like the same file
+    let disk = std::fs::read(&path)
+        .ok()
+        .filter(|d| d.get(..EHDR_SIZE) == Some(&header[..]));
+    if disk.is_none() {
+        warn!("could not read {path} from disk, the dump will have no section headers");
+    }
+    let sections = rebuild_elf(&mut image, lo, bias, disk.as_deref())?;
+
+    std::fs::write(output, &image)?;
+    Ok(ModuleDump {
+        module: path,
+        base,
+        output: output.to_path_buf(),
+        size: image.len(),
+        sections,
+    })
+}
+
+/// Fixes up the headers of an ELF memory image, so that it can be written as a file
+///
+/// The image must start at the page aligned address of the first loadable segment (`lo`) and
+/// contain all loadable segments at their virtual addresses relative to `lo`. The program headers
+/// are changed so that the file offsets match the virtual addresses. If the original file is
+/// given as `disk`, the section headers of all sections that are loaded into memory are appended
+/// to the image, otherwise the image will have no section headers.
+///
+/// The dynamic loader adds the load bias to some pointers in the dynamic section and to lazily
+/// bound GOT entries. The pointers in the dynamic section are reverted with `bias`, and the
+/// image is marked to be bound immediately, so that the GOT entries are overwritten when the
+/// dump is loaded.
+///
+/// # Returns
+///
+/// * `Ok(usize)` - The number of section headers that were restored, without the null section
+///   and the section name table
+/// * `Err(DebuggerError)` - If the image is not a valid ELF image
+///
+/// # Errors
+///
+/// This function fails if the image does not start with a valid 64 bit ELF header or if the
+/// program headers are not contained in the image.
+pub fn rebuild_elf(image: &mut Vec<u8>, lo: u64, bias: u64, disk: Option<&[u8]>) -> Result<usize> {
+    let (ehdr, _) = pod::<FileHeader64<LE>>(image)?;
+    if ehdr.e_ident.magic != object::elf::ELFMAG || ehdr.e_ident.class != object::elf::ELFCLASS64 {
+        return Err(DebuggerError::InvalidElf(
+            "no 64 bit ELF header".to_string(),
+        ));
+    }
+    let phnum = usize::from(ehdr.e_phnum.get(LE));
+    let phoff = ehdr.e_phoff.get(LE);
+
+    // the first page of the image is the start of the file, which contains the program headers
+    // in practice, their real place in the image is determined with them afterwards
+    let phdrs =
+        pod_slice::<ProgramHeader64<LE>>(image.get(phoff as usize..).unwrap_or_default(), phnum)?
+            .to_vec();
+    let new_phoff = file_offset_to_vaddr(&phdrs, phoff)
+        .map(|vaddr| vaddr - lo)
+        .ok_or_else(|| {
+            DebuggerError::InvalidElf("the program headers are not loaded".to_string())
+        })?;
+
+    let phdrs_image = image
+        .get_mut(new_phoff as usize..)
+        .ok_or_else(|| DebuggerError::InvalidElf("program headers out of range".to_string()))?;
+    let (phdrs_mut, _) =
+        object::pod::slice_from_bytes_mut::<ProgramHeader64<LE>>(phdrs_image, phnum)
+            .map_err(|()| DebuggerError::InvalidElf("program headers out of range".to_string()))?;
+    for phdr in phdrs_mut.iter_mut() {
+        let vaddr = phdr.p_vaddr.get(LE);
+        if phdr.p_type.get(LE) == object::elf::PT_LOAD {
+            phdr.p_offset.set(LE, vaddr - lo);
+            phdr.p_filesz.set(LE, phdr.p_memsz.get(LE));
+        } else if vaddr >= lo {
+            phdr.p_offset.set(LE, vaddr - lo);
+        }
+    }
+    if let Some(dynamic) = phdrs
+        .iter()
+        .find(|p| p.p_type.get(LE) == object::elf::PT_DYNAMIC)
+    {
+        let start = (dynamic.p_vaddr.get(LE) - lo) as usize;
+        let end = start + dynamic.p_memsz.get(LE) as usize;
+        if let Some(raw) = image.get_mut(start..end) {
+            fix_dynamic(raw, bias);
+        }
+    }
+
+    let (sections, shstrtab, shdrs) = match disk.map(|d| loaded_sections(d, lo)) {
+        Some(Ok(restored)) => restored,
+        Some(Err(e)) => {
+            warn!("could not restore the section headers: {e}");
+            (0, Vec::new(), Vec::new())
+        }
+        None => (0, Vec::new(), Vec::new()),
+    };
+
+    let (shoff, shnum, shstrndx) = if shdrs.is_empty() {
+        (0, 0, 0)
+    } else {
+        let name_offset = align_up(image.len(), 8);
+        image.resize(name_offset, 0);
+        image.extend_from_slice(&shstrtab);
+
+        let shoff = align_up(image.len(), 8);
+        image.resize(shoff, 0);
+        let shnum = shdrs.len();
+        for mut shdr in shdrs {
+            if shdr.sh_type.get(LE) == object::elf::SHT_STRTAB && shdr.sh_addr.get(LE) == 0 {
+                // the section name table is the only one that is not loaded
+                shdr.sh_offset.set(LE, name_offset as u64);
+            }
+            image.extend_from_slice(object::pod::bytes_of(&shdr));
+        }
+        (shoff as u64, shnum as u16, shnum as u16 - 1)
+    };
+
+    let (ehdr, _) = object::pod::from_bytes_mut::<FileHeader64<LE>>(image)
+        .map_err(|()| DebuggerError::InvalidElf("image too small".to_string()))?;
+    ehdr.e_phoff.set(LE, new_phoff);
+    ehdr.e_shoff.set(LE, shoff);
+    ehdr.e_shnum.set(LE, shnum);
+    ehdr.e_shstrndx.set(LE, shstrndx);
+    ehdr.e_shentsize.set(LE, SHDR_SIZE as u16);
+
+    Ok(sections)
+}
+
+/// Reverts the changes of the dynamic loader to the dynamic section
+fn fix_dynamic(raw: &mut [u8], bias: u64) {
+    let count = raw.len() / size_of::<Dyn64<LE>>();
+    let Ok((entries, _)) = object::pod::slice_from_bytes_mut::<Dyn64<LE>>(raw, count) else {
+        return;
+    };
+
+    let mut now_flag_set = false;
+    for entry in entries.iter_mut() {
+        let Ok(tag) = u32::try_from(entry.d_tag.get(LE)) else {
+            continue;
+        };
+        match tag {
+            object::elf::DT_NULL => break,
+            object::elf::DT_PLTGOT
+            | object::elf::DT_HASH
+            | object::elf::DT_STRTAB
+            | object::elf::DT_SYMTAB
+            | object::elf::DT_RELA
+            | object::elf::DT_REL
+            | object::elf::DT_JMPREL
+            | object::elf::DT_VERSYM
+            | object::elf::DT_GNU_HASH
+            | DT_RELR => entry.d_val.set(LE, entry.d_val.get(LE).wrapping_sub(bias)),
+            object::elf::DT_DEBUG => entry.d_val.set(LE, 0),
+            object::elf::DT_FLAGS => {
+                let flags = entry.d_val.get(LE) | u64::from(object::elf::DF_BIND_NOW);
+                entry.d_val.set(LE, flags);
+                now_flag_set = true;
+            }
+            object::elf::DT_FLAGS_1 => {
+                let flags = entry.d_val.get(LE) | u64::from(object::elf::DF_1_NOW);
+                entry.d_val.set(LE, flags);
+                now_flag_set = true;
+            }
+            _ => (),
+        }
+    }
+
+    if !now_flag_set {
+        // DT_DEBUG is only used at runtime, so it can be replaced with DT_BIND_NOW
+        if let Some(entry) = entries
+            .iter_mut()
+            .find(|e| e.d_tag.get(LE) == u64::from(object::elf::DT_DEBUG))
+        {
+            entry.d_tag.set(LE, u64::from(object::elf::DT_BIND_NOW));
+        } else {
+            warn!(
+                "could not mark the dump to be bound immediately, lazy GOT entries may be broken"
+            );
+        }
+    }
+}
+
+/// Collects the section headers of all loaded sections of an ELF file
+///
+/// The offsets of the sections are changed to match a memory image starting at `lo`. A null
+/// section is put first and a new section name table last.
+///
+/// Returns the number of restored sections, the section name table and the section headers.
+fn loaded_sections(disk: &[u8], lo: u64) -> Result<(usize, Vec<u8>, Vec<SectionHeader64<LE>>)> {
+    let (ehdr, _) = pod::<FileHeader64<LE>>(disk)?;
+    let shnum = usize::from(ehdr.e_shnum.get(LE));
+    let shdrs_raw = disk
+        .get(ehdr.e_shoff.get(LE) as usize..)
+        .ok_or_else(|| DebuggerError::InvalidElf("section headers out of range".to_string()))?;
+    let shdrs = pod_slice::<SectionHeader64<LE>>(shdrs_raw, shnum)?;
+    let names = shdrs
+        .get(usize::from(ehdr.e_shstrndx.get(LE)))
+        .and_then(|s| {
+            let start = s.sh_offset.get(LE) as usize;
+            disk.get(start..start + s.sh_size.get(LE) as usize)
+        })
+        .ok_or_else(|| DebuggerError::InvalidElf("no section name table".to_string()))?;
+
+    let loaded: Vec<usize> = (1..shnum)
+        .filter(|&i| {
+            shdrs[i].sh_flags.get(LE) & u64::from(object::elf::SHF_ALLOC) != 0
+                && shdrs[i].sh_addr.get(LE) >= lo
+        })
+        .collect();
+    // index of a section in the new section header table
+    let new_index = |old: u32| -> u32 {
+        loaded
+            .iter()
+            .position(|&i| i == old as usize)
+            .map_or(0, |p| p as u32 + 1)
+    };
+
+    let mut shstrtab = vec![0];
+    let mut new_shdrs = vec![empty_section_header()];
+    for &i in &loaded {
+        let mut shdr = shdrs[i];
+        let name = names
+            .get(shdr.sh_name.get(LE) as usize..)
+            .and_then(|n| n.split(|b| *b == 0).next())
+            .unwrap_or_default();
+        shdr.sh_name.set(LE, shstrtab.len() as u32);
+        shstrtab.extend_from_slice(name);
+        shstrtab.push(0);
+
+        shdr.sh_offset.set(LE, shdr.sh_addr.get(LE) - lo);
+        shdr.sh_link.set(LE, new_index(shdr.sh_link.get(LE)));
+        if shdr.sh_flags.get(LE) & u64::from(object::elf::SHF_INFO_LINK) != 0 {
+            shdr.sh_info.set(LE, new_index(shdr.sh_info.get(LE)));
+        }
+        new_shdrs.push(shdr);
+    }
+
+    let mut names_shdr = empty_section_header();
+    names_shdr.sh_name.set(LE, shstrtab.len() as u32);
+    shstrtab.extend_from_slice(b".shstrtab\0");
+    names_shdr.sh_type.set(LE, object::elf::SHT_STRTAB);
+    names_shdr.sh_size.set(LE, shstrtab.len() as u64);
+    names_shdr.sh_addralign.set(LE, 1);
+    new_shdrs.push(names_shdr);
+
+    Ok((loaded.len(), shstrtab, new_shdrs))
+}
+
+/// Finds the path of a module in the memory map
+fn find_module(map: &ProcessMemoryMap, module: &str) -> Result<String> {
+    let paths = || map.regions.iter().filter_map(|r| r.path.as_deref());
+    let file_name = |p: &str| {
+        Path::new(p)
+            .file_name()
+            .map(|f| f.to_string_lossy().to_string())
+            .unwrap_or_default()
+    };
+
+    paths()
+        .find(|p| *p == module)
+        .or_else(|| paths().find(|p| file_name(p) == module))
+        .or_else(|| paths().find(|p| p.starts_with('/') && file_name(p).starts_with(module)))
+        .map(str::to_string)
+        .ok_or_else(|| DebuggerError::ModuleNotFound(module.to_string()))
+}
+
+/// Returns the page aligned start and the end of the loadable segments
+pub(crate) fn load_range(phdrs: &[ProgramHeader64<LE>]) -> Result<(u64, u64)> {
+    let loads = || {
+        phdrs
+            .iter()
+            .filter(|p| p.p_type.get(LE) == object::elf::PT_LOAD)
+    };
+    let lo = loads().map(|p| page_down(p.p_vaddr.get(LE))).min();
+    let hi = loads().map(|p| p.p_vaddr.get(LE) + p.p_memsz.get(LE)).max();
+    lo.zip(hi)
+        .ok_or_else(|| DebuggerError::InvalidElf("no loadable segments".to_string()))
+}
+
+/// Translates an offset in the ELF file to the virtual address it is loaded at
+fn file_offset_to_vaddr(phdrs: &[ProgramHeader64<LE>], offset: u64) -> Option<u64> {
+    phdrs
+        .iter()
+        .filter(|p| p.p_type.get(LE) == object::elf::PT_LOAD)
+        .find(|p| (p.p_offset.get(LE)..p.p_offset.get(LE) + p.p_filesz.get(LE)).contains(&offset))
+        .map(|p| p.p_vaddr.get(LE) + (offset - p.p_offset.get(LE)))
+}
+
+/// Reads memory page by page, leaving pages that cannot be read zeroed
+fn read_lenient(pid: Pid, addr: Addr, buf: &mut [u8]) {
+    for (i, chunk) in buf.chunks_mut(PAGE_SIZE as usize).enumerate() {
+        let page = addr + i * PAGE_SIZE as usize;
+        if let Err(e) = mem_read(chunk, pid, page) {
+            warn!("could not read page at {page}, leaving it empty: {e}");
+        }
+    }
+}
+
+/// Interprets the start of `data` as `T`
+pub(crate) fn pod<T: object::pod::Pod>(data: &[u8]) -> Result<(&T, &[u8])> {
+    object::pod::from_bytes(data)
+        .map_err(|()| DebuggerError::InvalidElf("unexpected end of data".to_string()))
+}
+
+/// Interprets the start of `data` as `count` values of `T`
+pub(crate) fn pod_slice<T: object::pod::Pod>(data: &[u8], count: usize) -> Result<&[T]> {
+    object::pod::slice_from_bytes(data, count)
+        .map(|(slice, _)| slice)
+        .map_err(|()| DebuggerError::InvalidElf("unexpected end of data".to_string()))
+}
+
+/// Returns a section header with all fields set to zero, like the null section
+fn empty_section_header() -> SectionHeader64<LE> {
+    let (shdr, _) = object::pod::from_bytes::<SectionHeader64<LE>>(&[0; SHDR_SIZE])
+        .expect("a zeroed buffer of the right size is a valid section header");
+    *shdr
+}
+
+/// Rounds an address down to the start of its page
+fn page_down(addr: u64) -> u64 {
+    addr & !(PAGE_SIZE - 1)
+}
+
+/// Rounds `value` up to a multiple of `align`
+pub(crate) fn align_up(value: usize, align: usize) -> usize {
+    value.div_ceil(align) * align
+}
+
+#[cfg(test)]
+mod test {
+    use object::{Object, ObjectSection};
+
+    use super::*;
+
+    #[test]
+    fn test_rebuild_elf() {
+        let disk = std::fs::read("/proc/self/exe").expect("could not read the test binary");
+        let (ehdr, _) = pod::<FileHeader64<LE>>(&disk).unwrap();
+        let phdrs_raw = &disk[ehdr.e_phoff.get(LE) as usize..];
+        let phdrs =
+            pod_slice::<ProgramHeader64<LE>>(phdrs_raw, ehdr.e_phnum.get(LE).into()).unwrap();
+
+        // build the memory image like the loader would
+        let (lo, hi) = load_range(phdrs).unwrap();
+        let mut image = vec![0; (hi - lo) as usize];
+        for p in phdrs
+            .iter()
+            .filter(|p| p.p_type.get(LE) == object::elf::PT_LOAD)
+        {
+            let offset = p.p_offset.get(LE) as usize;
+            let filesz = p.p_filesz.get(LE) as usize;
+            let start = (p.p_vaddr.get(LE) - lo) as usize;
+            image[start..start + filesz].copy_from_slice(&disk[offset..offset + filesz]);
+        }
+
+        // the loader adds the bias to some entries of the dynamic section
+        let bias = 0x5555_0000_0000;
+        let dynamic = phdrs
+            .iter()
+            .find(|p| p.p_type.get(LE) == object::elf::PT_DYNAMIC)
+            .expect("test binary is not dynamically linked");
+        let dyn_range = (dynamic.p_vaddr.get(LE) - lo) as usize
+            ..(dynamic.p_vaddr.get(LE) - lo + dynamic.p_memsz.get(LE)) as usize;
+        let strtab = |image: &[u8]| {
+            let raw = &image[dyn_range.clone()];
+            pod_slice::<Dyn64<LE>>(raw, raw.len() / size_of::<Dyn64<LE>>())
+                .unwrap()
+                .iter()
+                .find(|d| d.d_tag.get(LE) == u64::from(object::elf::DT_STRTAB))
+                .map(|d| d.d_val.get(LE))
+        };
+        let original_strtab = strtab(&image);
+        let raw = &mut image[dyn_range.clone()];
+        let count = raw.len() / size_of::<Dyn64<LE>>();
+        for d in object::pod::slice_from_bytes_mut::<Dyn64<LE>>(raw, count)
+            .unwrap()
+            .0
+            .iter_mut()
+            .filter(|d| d.d_tag.get(LE) == u64::from(object::elf::DT_STRTAB))
+        {
+            d.d_val.set(LE, d.d_val.get(LE) + bias);
+        }
+
+        let sections = rebuild_elf(&mut image, lo, bias, Some(&disk)).unwrap();
+        assert!(sections > 0);
+        assert_eq!(strtab(&image), original_strtab);
+
+        let original = object::File::parse(disk.as_slice()).unwrap();
+        let dumped = object::File::parse(image.as_slice()).expect("dump is no valid ELF");
+        assert_eq!(dumped.entry(), original.entry());
+        let text = |f: &object::File<'_>| {
+            let s = f.section_by_name(".text").expect("no .text section");
+            (s.address(), s.data().unwrap().to_vec())
+        };
+        assert_eq!(text(&dumped), text(&original));
+
+        // without the file on disk, there are just no sections
+        let mut image_without = image[..(hi - lo) as usize].to_vec();
+        assert_eq!(rebuild_elf(&mut image_without, lo, 0, None).unwrap(), 0);
+        assert!(object::File::parse(image_without.as_slice()).is_ok());
+    }
+}
@@ -12,18 +12,31 @@
 //! - Evaluating DWARF location descriptions
 //! - Extracting type and scope information
 //! - Managing frame information for stack unwinding and variable access
+//! - Computing the Canonical Frame Address from the call frame information in `.eh_frame` and
+//!   `.debug_frame`
+//! - Recovering the registers of outer stack frames from the same call frame information
 //!
 //! DWARF is a standardized debugging data format used by many compilers and
 //! debugging tools. This module leverages the `gimli` crate to parse and interpret
 //! DWARF sections from executable files.
 
-use gimli::{Encoding, Expression, Reader, Unit};
+use std::collections::HashMap;
+
+use gimli::{
+    BaseAddresses, CfaRule, DebugFrame, EhFrame, Encoding, Expression, LittleEndian, Reader,
+    RegisterRule, Unit, UnwindContext, UnwindSection, UnwindTableRow,
+};
+use object::{Object, ObjectSection};
 use tracing::{trace, warn};
 
-use crate::dbginfo::GimliLocation;
+use crate::dbginfo::{
+    search_through_symbols, GimliLocation, LocationListEntry, OwnedSymbol, SymbolKind,
+};
 use crate::debuggee::Debuggee;
+use crate::dwarfreg::DwarfRegister;
 use crate::errors::{DebuggerError, Result};
-use crate::{mem_read, Addr};
+use crate::modules::LoadedModule;
+use crate::{Addr, Register};
 
 /// Type alias for the Gimli reader used throughout the module
 ///
@@ -31,6 +44,12 @@ use crate::{mem_read, Addr};
 /// with the correct endianness.
 pub(crate) type GimliReaderThing = gimli::EndianReader<gimli::LittleEndian, std::rc::Rc<[u8]>>;
 
+/// The values of the [Registers](Register) in a stack frame
+///
+/// For outer frames, only the registers that could be recovered from the call frame information
+/// are known, see [`unwind_registers`].
+pub type FrameRegisters = HashMap<Register, u64>;
+
 /// Represents stack frame information needed for variable access
 ///
 /// In order to locate variables correctly, a debugger needs information about
@@ -42,6 +61,20 @@ pub struct FrameInfo {
 
     /// Canonical Frame Address (CFA) for the current frame
     pub canonical_frame_address: Option<Addr>,
+
+    /// The [Registers](Register) of the frame, if it is not the innermost one
+    ///
+    /// If this is [None], the live registers of the debuggee are used.
+    pub registers: Option<FrameRegisters>,
+
+    /// The address of the instruction in the frame, used to select from location lists
+    ///
+    /// For outer frames, this is in the call instruction rather than the return address.
+    pub pc: Option<Addr>,
+
+    /// The frame of the caller, used to find the values that parameters had when the function
+    /// was entered (`DW_OP_entry_value`)
+    pub caller: Option<Box<FrameInfo>>,
 }
 
 impl FrameInfo {
@@ -65,6 +98,9 @@ impl FrameInfo {
         FrameInfo {
             frame_base,
             canonical_frame_address,
+            registers: None,
+            pc: None,
+            caller: None,
         }
     }
 
@@ -93,6 +129,42 @@ impl FrameInfo {
     pub fn frame_base(&self) -> Option<Addr> {
         self.frame_base
     }
+
+    /// Gets the value of a [Register] in the frame
+    ///
+    /// # Errors
+    ///
+    /// Fails if the register was not recovered for an outer frame, or cannot be read from the
+    /// debuggee.
+    pub(crate) fn register(&self, pid: nix::unistd::Pid, reg: Register) -> Result<u64> {
+        match &self.registers {
+            Some(registers) => registers
+                .get(&reg)
+                .copied()
+                .ok_or(DebuggerError::RegisterNotRecovered(reg)),
+            None => crate::get_reg(pid, reg),
+        }
+    }
+
+    /// Gets the value of a [`DwarfRegister`] in the frame, as little endian bytes
+    ///
+    /// Only general purpose registers are recovered for outer frames, the others are only known
+    /// in the innermost frame.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the register is not known in the frame, or cannot be read from the debuggee.
+    pub(crate) fn dwarf_register(
+        &self,
+        pid: nix::unistd::Pid,
+        reg: DwarfRegister,
+    ) -> Result<Vec<u8>> {
+        match reg {
+            DwarfRegister::General(reg) => Ok(self.register(pid, reg)?.to_le_bytes().to_vec()),
+            other if self.registers.is_some() => Err(DebuggerError::RegisterUnavailable(other)),
+            other => other.read(pid),
+        }
+    }
 }
 
 impl Debuggee {
@@ -103,11 +175,11 @@ impl Debuggee {
     /// * `dwarf` - The DWARF information
     /// * `unit` - The compilation unit
     /// * `attribute` - The attribute to parse
-    /// * `base_addr` - The base address of the loaded executable
+    /// * `module` - The loaded module the debug information belongs to
     ///
     /// # Returns
     ///
-    /// * `Ok(Some(Addr))` - The parsed address
+    /// * `Ok(Some(Addr))` - The parsed address, rebased with the load bias of `module`
     /// * `Ok(None)` - If the attribute is not present
     /// * `Err(DebuggerError)` - If parsing failed
     ///
@@ -119,7 +191,7 @@ impl Debuggee {
         dwarf: &gimli::Dwarf<GimliReaderThing>,
         unit: &Unit<GimliReaderThing>,
         attribute: Option<gimli::Attribute<GimliReaderThing>>,
-        base_addr: Addr,
+        module: &LoadedModule,
     ) -> Result<Option<Addr>> {
         Ok(if let Some(a) = attribute {
             let a: u64 = match dwarf.attr_address(unit, a.value())? {
@@ -129,7 +201,7 @@ impl Debuggee {
                 }
                 Some(a) => a,
             };
-            Some(base_addr + a as usize)
+            Some(module.to_process(a))
         } else {
             None
         })
@@ -177,6 +249,46 @@ impl Debuggee {
         })
     }
 
+    /// Parses a DWARF location list attribute (`DW_AT_location`)
+    ///
+    /// # Parameters
+    ///
+    /// * `dwarf` - The DWARF information
+    /// * `unit` - The compilation unit
+    /// * `attribute` - The location attribute
+    /// * `module` - The loaded module the debug information belongs to
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Vec<LocationListEntry>)` - The entries of the location list, with their ranges
+    ///   rebased with the load bias of `module`, which is empty if the location is a single
+    ///   expression
+    /// * `Err(DebuggerError)` - If parsing failed
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if:
+    /// - The location list cannot be read
+    pub(crate) fn parse_location_list(
+        dwarf: &gimli::Dwarf<GimliReaderThing>,
+        unit: &Unit<GimliReaderThing>,
+        attribute: &gimli::Attribute<GimliReaderThing>,
+        module: &LoadedModule,
+    ) -> Result<Vec<LocationListEntry>> {
+        let Some(mut locations) = dwarf.attr_locations(unit, attribute.value())? else {
+            return Ok(Vec::new());
+        };
+        let mut entries = Vec::new();
+        while let Some(entry) = locations.next()? {
+            entries.push(LocationListEntry {
+                low_addr: module.to_process(entry.range.begin),
+                high_addr: module.to_process(entry.range.end),
+                expression: entry.data,
+            });
+        }
+        Ok(entries)
+    }
+
     /// Parses a DWARF string attribute, like `DW_AT_name`
     ///
     /// # Parameters
@@ -245,6 +357,83 @@ impl Debuggee {
         }
     }
 
+    /// Parses the offset of a struct member (`DW_AT_data_member_location`)
+    ///
+    /// The offset is usually a constant, but older DWARF versions describe it with an expression
+    /// that adds it to the address of the struct (`DW_OP_plus_uconst`).
+    ///
+    /// # Parameters
+    ///
+    /// * `attribute` - The attribute to parse
+    /// * `encoding` - The encoding of the compilation unit
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Some(usize))` - The offset in bytes from the start of the struct
+    /// * `Ok(None)` - If the attribute is not present or not a simple offset
+    /// * `Err(DebuggerError)` - If the expression could not be read
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if:
+    /// - The expression of the attribute is malformed
+    pub(crate) fn parse_member_offset(
+        attribute: Option<gimli::Attribute<GimliReaderThing>>,
+        encoding: Encoding,
+    ) -> Result<Option<usize>> {
+        let Some(a) = attribute else {
+            return Ok(None);
+        };
+        if let Some(offset) = a.udata_value() {
+            return Ok(Some(offset as usize));
+        }
+        if let Some(expression) = a.exprloc_value() {
+            let mut ops = expression.operations(encoding);
+            if let (Some(gimli::Operation::PlusConstant { value }), None) =
+                (ops.next()?, ops.next()?)
+            {
+                return Ok(Some(value as usize));
+            }
+        }
+        warn!("unsupported member offset: {a:?}");
+        Ok(None)
+    }
+
+    /// Computes where a bit-field member is in its struct
+    ///
+    /// DWARF 4 and later directly give the offset of the member in bits from the start of the
+    /// struct (`DW_AT_data_bit_offset`). Older versions give the offset of the member in its
+    /// storage unit instead (`DW_AT_bit_offset`), which is located by the member offset and byte
+    /// size and counted from the most significant bit of it.
+    ///
+    /// # Parameters
+    ///
+    /// * `data_bit_offset` - The value of `DW_AT_data_bit_offset`
+    /// * `bit_offset` - The value of `DW_AT_bit_offset`
+    /// * `member_offset` - The offset of the storage unit in bytes
+    /// * `byte_size` - The size of the storage unit in bytes
+    /// * `bit_size` - The size of the member in bits
+    ///
+    /// # Returns
+    ///
+    /// * `Some(usize)` - The offset of the lowest bit of the member from the start of the struct
+    /// * `None` - If the member is not a bit-field, or its position cannot be computed
+    pub(crate) fn parse_data_bit_offset(
+        data_bit_offset: Option<u64>,
+        bit_offset: Option<u64>,
+        member_offset: Option<usize>,
+        byte_size: Option<usize>,
+        bit_size: Option<usize>,
+    ) -> Option<usize> {
+        let bit_size = bit_size?;
+        if let Some(offset) = data_bit_offset {
+            return Some(offset as usize);
+        }
+        // x86_64 is little endian, so the most significant bit is the last one
+        let storage_bits = byte_size? * 8;
+        (member_offset.unwrap_or(0) * 8 + storage_bits).checked_sub(bit_offset? as usize + bit_size)
+    }
+
     /// Parses a DWARF location attribute
     ///
     /// Location attributes describe where a variable or parameter is stored,
@@ -332,14 +521,19 @@ impl Debuggee {
                 } => {
                     let mut buff = vec![0; size as usize];
                     let addr: Addr = address.into(); // NOTE: may be relative?
-                    let read_this_many_bytes = mem_read(&mut buff, self.pid, addr)?;
+                    let read_this_many_bytes = self.read_bytes(&mut buff, addr)?;
                     assert_eq!(size as usize, read_this_many_bytes);
                     let value = to_value(size, &buff);
                     res = evaluation.resume_with_memory(value)?;
                 }
                 gimli::EvaluationResult::RequiresRegister { register, .. /* ignore the actual type and give as word */ } => {
-                    let reg_kind= crate::Register::try_from(register)?;
-                    let reg_value = crate::get_reg(self.pid, reg_kind)?;
+                    let reg_kind = DwarfRegister::try_from(register)?;
+                    // registers larger than a word are truncated to their lowest bytes
+                    let raw = frame_info.dwarf_register(self.pid, reg_kind)?;
+                    let mut bytes = [0; 8];
+                    let len = raw.len().min(bytes.len());
+                    bytes[..len].copy_from_slice(&raw[..len]);
+                    let reg_value = u64::from_le_bytes(bytes);
                     res = evaluation.resume_with_register(gimli::Value::from_u64(gimli::ValueType::Generic, reg_value)?)?;
                 }
                 gimli::EvaluationResult::RequiresFrameBase =>{
@@ -350,6 +544,16 @@ impl Debuggee {
                         frame_base.u64()
                     )?;
                 }
+                gimli::EvaluationResult::RequiresEntryValue(entry_expression) => {
+                    let value = self.entry_value(entry_expression, frame_info, encoding)?;
+                    trace!("entry value: {value:#x}");
+                    res = evaluation.resume_with_entry_value(gimli::Value::Generic(value))?;
+                }
+                gimli::EvaluationResult::RequiresRelocatedAddress(address) => {
+                    // addresses of statics are virtual addresses of the executable
+                    let addr = self.executable.to_process(address);
+                    res = evaluation.resume_with_relocated_address(addr.u64())?;
+                }
                 gimli::EvaluationResult::RequiresCallFrameCfa => {
                     let cfa: Addr = frame_info.canonical_frame_address.expect("no cfa was given");
                     trace!("cfa: {cfa}");
@@ -375,6 +579,361 @@ impl Debuggee {
     }
 }
 
+impl Debuggee {
+    /// Evaluates the location of a variable or parameter
+    ///
+    /// If the location is a location list, the entry for the instruction of the frame is used.
+    ///
+    /// # Parameters
+    ///
+    /// * `sym` - The symbol of the variable
+    /// * `frame_info` - Stack frame information for context
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(GimliLocation)` - The location of the variable
+    /// * `Err(DebuggerError)` - If evaluation failed
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if:
+    /// - The symbol has no location
+    /// - The location list has no entry for the instruction of the frame, which usually means
+    ///   that the variable was optimized out there
+    /// - Evaluation of the location expression fails
+    pub(crate) fn parse_symbol_location(
+        &self,
+        sym: &OwnedSymbol,
+        frame_info: &FrameInfo,
+    ) -> Result<GimliLocation> {
+        if sym.location_list().is_empty() {
+            let Some(loc_attr) = sym.location() else {
+                return Err(DebuggerError::SymbolHasNoLocation);
+            };
+            return self.parse_location(loc_attr, frame_info, sym.encoding());
+        }
+
+        let pc = frame_info.pc.ok_or(DebuggerError::NoFrameInfo)?;
+        match sym
+            .location_list()
+            .iter()
+            .find(|entry| entry.low_addr <= pc && pc < entry.high_addr)
+        {
+            Some(entry) => {
+                self.eval_expression(entry.expression.clone(), frame_info, sym.encoding())
+            }
+            None => Err(DebuggerError::NoLocationAtAddr(pc)),
+        }
+    }
+
+    /// Computes the value that a register had when the function of a frame was entered
+    ///
+    /// This is needed for `DW_OP_entry_value`, which optimized code uses for parameters that are
+    /// not kept anywhere after they were used. The value is taken from the call site in the
+    /// caller if it describes the parameter (`DW_AT_call_value`). Otherwise, registers that are
+    /// preserved across calls still have the value in the caller.
+    ///
+    /// # Errors
+    ///
+    /// This function fails with [`DebuggerError::EntryValueUnavailable`] if the value cannot be
+    /// found, or if the frame of the caller is not known.
+    fn entry_value(
+        &self,
+        expression: Expression<GimliReaderThing>,
+        frame_info: &FrameInfo,
+        encoding: Encoding,
+    ) -> Result<u64> {
+        let caller = frame_info
+            .caller
+            .as_deref()
+            .ok_or(DebuggerError::EntryValueUnavailable)?;
+
+        // only entry values of registers are produced by compilers
+        let mut ops = expression.operations(encoding);
+        let register = match (ops.next()?, ops.next()?) {
+            (Some(gimli::Operation::Register { register }), None) => register,
+            (op, _) => {
+                warn!("entry value of an expression other than a register: {op:?}");
+                return Err(DebuggerError::EntryValueUnavailable);
+            }
+        };
+
+        if let Some(value) = self.call_site_value(caller, register)? {
+            return Ok(value);
+        }
+        match Register::try_from(register) {
+            Ok(reg) if is_callee_saved(reg) => caller.register(self.pid, reg),
+            _ => Err(DebuggerError::EntryValueUnavailable),
+        }
+    }
+
+    /// Finds the value that the caller passed in `register` with the call site information
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Some(u64))` - The value that was passed
+    /// * `Ok(None)` - If there is no call site information for the call or the register
+    fn call_site_value(
+        &self,
+        caller: &FrameInfo,
+        register: gimli::Register,
+    ) -> Result<Option<u64>> {
+        let (Some(pc), Some(return_addr)) = (
+            caller.pc,
+            caller
+                .registers
+                .as_ref()
+                .and_then(|r| r.get(&Register::rip)),
+        ) else {
+            return Ok(None);
+        };
+        let return_addr = Addr::from(*return_addr);
+        let Some(function) = self.get_function_by_addr(pc)? else {
+            return Ok(None);
+        };
+
+        let call_sites = search_through_symbols(function.children(), |s| {
+            s.kind() == SymbolKind::CallSite && s.low_addr() == Some(return_addr)
+        });
+        let Some(call_site) = call_sites.first() else {
+            trace!("no call site information for the call returning to {return_addr}");
+            return Ok(None);
+        };
+
+        for param in call_site.children() {
+            let (Some(location), Some(value)) = (param.location(), param.call_value()) else {
+                continue;
+            };
+            let gimli::AttributeValue::Exprloc(location) = location.value() else {
+                continue;
+            };
+            let mut ops = location.operations(param.encoding());
+            if !matches!(ops.next()?, Some(gimli::Operation::Register { register: r }) if r == register)
+            {
+                continue;
+            }
+
+            let value = match self.parse_location(value, caller, param.encoding())? {
+                // without DW_OP_stack_value, the value is left as the address
+                gimli::Location::Address { address } => address,
+                gimli::Location::Value { value } => value.to_u64(u64::MAX)?,
+                gimli::Location::Register { register } => {
+                    caller.register(self.pid, Register::try_from(register)?)?
+                }
+                other => {
+                    warn!("call site value is not a value: {other:?}");
+                    return Ok(None);
+                }
+            };
+            return Ok(Some(value));
+        }
+        Ok(None)
+    }
+}
+
+/// Computes the Canonical Frame Address (CFA) from the call frame information of an ELF file
+///
+/// The CFA is the value of the stack pointer in the calling frame, right before the call
+/// instruction. Frame bases are often expressed relative to it with `DW_OP_call_frame_cfa`. The
+/// rule to compute it is looked up in `.eh_frame` first, then in `.debug_frame`.
+///
+/// # Parameters
+///
+/// * `obj` - The ELF file
+/// * `pc` - The address of the current instruction, as in the ELF file without the load bias
+/// * `get_reg` - Gets the value of a register in the current frame
+///
+/// # Returns
+///
+/// * `Ok(Some(Addr))` - The CFA
+/// * `Ok(None)` - If there is no call frame information for `pc`, or the CFA is computed with a
+///   DWARF expression, which is not supported
+///
+/// # Errors
+///
+/// This function fails if the sections cannot be read, or the register of the rule cannot be
+/// read.
+pub(crate) fn canonical_frame_address(
+    obj: &object::File<'_>,
+    pc: u64,
+    get_reg: impl Fn(Register) -> Result<u64>,
+) -> Result<Option<Addr>> {
+    match unwind_row(obj, pc)? {
+        Some(row) => cfa_value(row.cfa(), pc, get_reg),
+        None => Ok(None),
+    }
+}
+
+/// Recovers the [Registers](Register) of the calling frame from the call frame information of an
+/// ELF file
+///
+/// The callee-saved registers are restored with the rules in `.eh_frame` or `.debug_frame`, `rsp`
+/// is set to the CFA and `rip` to the return address. Registers without a rule keep their value
+/// if they are callee-saved in the System V ABI. All other registers were possibly clobbered by
+/// the callee, so they are missing from the result instead of holding a wrong value.
+///
+/// # Parameters
+///
+/// * `obj` - The ELF file
+/// * `pc` - The address of the instruction in the frame, as in the ELF file without the load
+///   bias. For outer frames, this should be inside of the call instruction and not the return
+///   address, as a call can be the last instruction of a function.
+/// * `regs` - The registers of the frame
+/// * `read_word` - Reads a [Word](crate::Word) from the stack of the debuggee
+///
+/// # Returns
+///
+/// * `Ok(Some(FrameRegisters))` - The registers of the calling frame
+/// * `Ok(None)` - If there is no call frame information for `pc`, or it does not say where the
+///   return address is, which is the case for the outermost frame
+///
+/// # Errors
+///
+/// This function fails if the sections cannot be read, a register that is needed for a rule is
+/// not known or the stack cannot be read.
+pub(crate) fn unwind_registers(
+    obj: &object::File<'_>,
+    pc: u64,
+    regs: &FrameRegisters,
+    read_word: impl Fn(Addr) -> Result<u64>,
+) -> Result<Option<FrameRegisters>> {
+    let Some(row) = unwind_row(obj, pc)? else {
+        return Ok(None);
+    };
+    let get_reg = |r: Register| {
+        regs.get(&r)
+            .copied()
+            .ok_or(DebuggerError::RegisterNotRecovered(r))
+    };
+    let Some(cfa) = cfa_value(row.cfa(), pc, get_reg)? else {
+        return Ok(None);
+    };
+
+    let mut caller: FrameRegisters = regs
+        .iter()
+        .filter(|(r, _)| is_callee_saved(**r))
+        .map(|(r, v)| (*r, *v))
+        .collect();
+    caller.remove(&Register::rip);
+
+    for (dwarf_reg, rule) in row.registers() {
+        let Ok(reg) = Register::try_from(*dwarf_reg) else {
+            trace!("ignoring the unwind rule for unsupported register {dwarf_reg:?}");
+            continue;
+        };
+        let value = match rule {
+            RegisterRule::Undefined => None,
+            RegisterRule::SameValue => regs.get(&reg).copied(),
+            RegisterRule::Offset(offset) => {
+                Some(read_word(cfa.u64().wrapping_add_signed(*offset).into())?)
+            }
+            RegisterRule::ValOffset(offset) => Some(cfa.u64().wrapping_add_signed(*offset)),
+            RegisterRule::Register(other) => regs.get(&Register::try_from(*other)?).copied(),
+            RegisterRule::Constant(value) => Some(*value),
+            other => {
+                warn!("the unwind rule for {reg:?} at {pc:#x} is not supported: {other:?}");
+                None
+            }
+        };
+        match value {
+            Some(value) => caller.insert(reg, value),
+            None => caller.remove(&reg),
+        };
+    }
+
+    if !caller.contains_key(&Register::rip) {
+        trace!("no return address for {pc:#x}, this is the outermost frame");
+        return Ok(None);
+    }
+    caller.insert(Register::rsp, cfa.u64());
+    Ok(Some(caller))
+}
+
+/// Checks if `reg` keeps its value across calls in the System V ABI
+///
+/// The segment registers are included, since they are the same for the whole thread.
+fn is_callee_saved(reg: Register) -> bool {
+    !matches!(
+        reg,
+        Register::rax
+            | Register::rcx
+            | Register::rdx
+            | Register::rsi
+            | Register::rdi
+            | Register::r8
+            | Register::r9
+            | Register::r10
+            | Register::r11
+            | Register::orig_rax
+            | Register::eflags
+    )
+}
+
+/// Computes the value of the CFA with the rule from the call frame information
+fn cfa_value(
+    rule: &CfaRule<usize>,
+    pc: u64,
+    get_reg: impl Fn(Register) -> Result<u64>,
+) -> Result<Option<Addr>> {
+    match rule {
+        CfaRule::RegisterAndOffset { register, offset } => {
+            let base = get_reg(Register::try_from(*register)?)?;
+            let cfa = base.wrapping_add_signed(*offset);
+            trace!("cfa for {pc:#x}: {register:?} + {offset} = {cfa:#x}");
+            Ok(Some(Addr::from(cfa as usize)))
+        }
+        CfaRule::Expression(_) => {
+            warn!(
+                "the CFA for {pc:#x} is computed with a DWARF expression, which is not supported"
+            );
+            Ok(None)
+        }
+    }
+}
+
+/// Looks up the row of the unwind table for `pc`, in `.eh_frame` first and `.debug_frame` second
+fn unwind_row(obj: &object::File<'_>, pc: u64) -> Result<Option<UnwindTableRow<usize>>> {
+    let address = |name: &str| obj.section_by_name(name).map(|s| s.address());
+    let mut bases = BaseAddresses::default();
+    if let Some(text) = address(".text") {
+        bases = bases.set_text(text);
+    }
+    if let Some(got) = address(".got") {
+        bases = bases.set_got(got);
+    }
+
+    let mut row = None;
+    if let Some(section) = obj.section_by_name(".eh_frame") {
+        bases = bases.set_eh_frame(section.address());
+        let eh_frame = EhFrame::new(section.data()?, LittleEndian);
+        row = section_row(&eh_frame, &bases, pc);
+    }
+    if row.is_none() {
+        if let Some(section) = obj.section_by_name(".debug_frame") {
+            let mut debug_frame = DebugFrame::new(section.data()?, LittleEndian);
+            debug_frame.set_address_size(8);
+            row = section_row(&debug_frame, &bases, pc);
+        }
+    }
+    Ok(row)
+}
+
+/// Looks up the row of the unwind table for `pc` in an unwind section
+fn section_row<R: Reader<Offset = usize>, U: UnwindSection<R>>(
+    section: &U,
+    bases: &BaseAddresses,
+    pc: u64,
+) -> Option<UnwindTableRow<usize>> {
+    let mut ctx = UnwindContext::new();
+    match section.unwind_info_for_address(bases, &mut ctx, pc, U::cie_from_offset) {
+        Ok(row) => Some(row.clone()),
+        Err(e) => {
+            trace!("no call frame information for {pc:#x}: {e}");
+            None
+        }
+    }
+}
+
 /// Converts bytes to a DWARF value based on size
 ///
 /// # Parameters
@@ -398,3 +957,79 @@ fn to_value(size: u8, buff: &[u8]) -> gimli::Value {
         x => unimplemented!("Requested memory with size {x}, which is not supported yet."),
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_canonical_frame_address() {
+        let data = std::fs::read("/proc/self/exe").unwrap();
+        let obj = object::File::parse(data.as_slice()).unwrap();
+        let main = crate::bpfile::symbol_address(&obj, "main").expect("no main symbol");
+
+        // at the first instruction of a function, the return address was just pushed
+        let get_reg = |r| match r {
+            Register::rsp => Ok(0x1000),
+            other => Err(DebuggerError::UnimplementedRegister(other as u16)),
+        };
+        assert_eq!(
+            canonical_frame_address(&obj, main, get_reg).unwrap(),
+            Some(Addr::from(0x1008usize))
+        );
+        assert_eq!(canonical_frame_address(&obj, 0, get_reg).unwrap(), None);
+    }
+
+    #[test]
+    fn test_parse_data_bit_offset() {
+        // struct { unsigned a : 3; unsigned b : 5; int c : 4; }, as described by DWARF 4 and 5
+        assert_eq!(
+            Debuggee::parse_data_bit_offset(None, Some(29), Some(0), Some(4), Some(3)),
+            Some(0)
+        );
+        assert_eq!(
+            Debuggee::parse_data_bit_offset(None, Some(24), Some(0), Some(4), Some(5)),
+            Some(3)
+        );
+        assert_eq!(
+            Debuggee::parse_data_bit_offset(None, Some(20), Some(0), Some(4), Some(4)),
+            Some(8)
+        );
+        assert_eq!(
+            Debuggee::parse_data_bit_offset(Some(8), None, None, None, Some(4)),
+            Some(8)
+        );
+        // not a bit-field
+        assert_eq!(
+            Debuggee::parse_data_bit_offset(None, None, Some(4), Some(4), None),
+            None
+        );
+    }
+
+    #[test]
+    fn test_unwind_registers() {
+        let data = std::fs::read("/proc/self/exe").unwrap();
+        let obj = object::File::parse(data.as_slice()).unwrap();
+        let main = crate::bpfile::symbol_address(&obj, "main").expect("no main symbol");
+
+        let regs = FrameRegisters::from([
+            (Register::rip, main),
+            (Register::rsp, 0x1000),
+            (Register::rbx, 5),
+            (Register::rax, 7),
+        ]);
+        let read_word = |addr: Addr| match addr.u64() {
+            0x1000 => Ok(0xdead),
+            _ => Err(DebuggerError::NoSuchFrame(0)),
+        };
+        let caller = unwind_registers(&obj, main, &regs, read_word)
+            .unwrap()
+            .expect("could not unwind main");
+
+        assert_eq!(caller.get(&Register::rip), Some(&0xdead));
+        assert_eq!(caller.get(&Register::rsp), Some(&0x1008));
+        assert_eq!(caller.get(&Register::rbx), Some(&5));
+        // rax is not preserved across calls
+        assert_eq!(caller.get(&Register::rax), None);
+    }
+}
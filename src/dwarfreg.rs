@@ -0,0 +1,145 @@
+//! # DWARF Register Module
+//!
+//! Maps the DWARF register numbers of `x86_64` to the registers of the debuggee.
+//!
+//! DWARF location expressions and call frame information name registers by the numbers from the
+//! amd64 ABI, see <https://refspecs.linuxbase.org/elf/x86_64-abi-0.99.pdf#figure.3.36>. Besides
+//! the general purpose registers, these include the vector registers, the x87 and MMX registers
+//! and the floating point control registers. A [`DwarfRegister`] can be any of them, while a
+//! [`Register`] is only a general purpose register.
+//!
+//! The AVX-512 registers (`xmm16` to `xmm31` and `k0` to `k7`) are not supported.
+
+use std::fmt::{self, Display};
+
+use nix::unistd::Pid;
+use serde::{Deserialize, Serialize};
+
+use crate::errors::{DebuggerError, Result};
+use crate::fpregs::{get_fp_control, get_mm, get_st, get_xmm, FpControlRegister, FpRegister};
+use crate::{get_reg, Register};
+
+/// A register that DWARF information can refer to
+///
+/// # Examples
+///
+/// ```
+/// use coreminer::dwarfreg::DwarfRegister;
+/// use coreminer::fpregs::FpRegister;
+/// use coreminer::Register;
+///
+/// let reg = |n| DwarfRegister::try_from(gimli::Register(n)).unwrap();
+/// assert_eq!(reg(7), DwarfRegister::General(Register::rsp));
+/// assert_eq!(reg(17), DwarfRegister::Vector(FpRegister::xmm0));
+/// assert_eq!(reg(35), DwarfRegister::X87(2));
+/// assert_eq!(reg(58).to_string(), "fs_base");
+/// assert!(DwarfRegister::try_from(gimli::Register(67)).is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum DwarfRegister {
+    /// A general purpose register, including the flags and the segment registers
+    General(Register),
+    /// An SSE or AVX vector register
+    Vector(FpRegister),
+    /// The x87 register `st(n)`, relative to the top of the x87 register stack
+    X87(u8),
+    /// The MMX register `mm{n}`
+    Mmx(u8),
+    /// A floating point control or status register
+    FpControl(FpControlRegister),
+    /// The task register, which ptrace cannot read
+    Tr,
+    /// The local descriptor table register, which ptrace cannot read
+    Ldtr,
+}
+
+impl DwarfRegister {
+    /// Reads the register of the process `pid`, as little endian bytes
+    ///
+    /// General purpose registers are 8 bytes, the others have their natural size, so
+    /// [`X87`](Self::X87) registers have the 10 bytes of an extended precision number.
+    ///
+    /// # Errors
+    ///
+    /// Fails with [`DebuggerError::RegisterUnavailable`] for [`Tr`](Self::Tr) and
+    /// [`Ldtr`](Self::Ldtr), or if the register cannot be read with ptrace.
+    pub fn read(self, pid: Pid) -> Result<Vec<u8>> {
+        Ok(match self {
+            Self::General(r) => get_reg(pid, r)?.to_le_bytes().to_vec(),
+            Self::Vector(r) => get_xmm(pid, r)?.to_vec(),
+            Self::X87(n) => get_st(pid, n.into())?.to_vec(),
+            Self::Mmx(n) => get_mm(pid, n.into())?.to_vec(),
+            Self::FpControl(r) => get_fp_control(pid, r)?.to_le_bytes().to_vec(),
+            Self::Tr | Self::Ldtr => return Err(DebuggerError::RegisterUnavailable(self)),
+        })
+    }
+}
+
+impl Display for DwarfRegister {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::General(r) => write!(f, "{r:?}"),
+            Self::Vector(r) => write!(f, "{r}"),
+            Self::X87(n) => write!(f, "st{n}"),
+            Self::Mmx(n) => write!(f, "mm{n}"),
+            Self::FpControl(r) => write!(f, "{r:?}"),
+            Self::Tr => write!(f, "tr"),
+            Self::Ldtr => write!(f, "ldtr"),
+        }
+    }
+}
+
+impl TryFrom<gimli::Register> for DwarfRegister {
+    type Error = DebuggerError;
+    /// Converts a DWARF register number to the corresponding register
+    ///
+    /// The DWARF Register Number Mapping is defined in the amd64 ABI here:
+    /// <https://refspecs.linuxbase.org/elf/x86_64-abi-0.99.pdf#figure.3.36>
+    fn try_from(value: gimli::Register) -> Result<Self> {
+        let general = |r| Ok(Self::General(r));
+        match value.0 {
+            0 => general(Register::rax),
+            1 => general(Register::rdx),
+            2 => general(Register::rcx),
+            3 => general(Register::rbx),
+            4 => general(Register::rsi),
+            5 => general(Register::rdi),
+            6 => general(Register::rbp),
+            7 => general(Register::rsp),
+            8 => general(Register::r8),
+            9 => general(Register::r9),
+            10 => general(Register::r10),
+            11 => general(Register::r11),
+            12 => general(Register::r12),
+            13 => general(Register::r13),
+            14 => general(Register::r14),
+            15 => general(Register::r15),
+            // the return address, which is where rip points after returning
+            16 => general(Register::rip),
+
+            n @ 17..=32 => Ok(Self::Vector(FpRegister::ALL[usize::from(n - 17)])),
+            n @ 33..=40 => Ok(Self::X87((n - 33) as u8)),
+            n @ 41..=48 => Ok(Self::Mmx((n - 41) as u8)),
+
+            49 => general(Register::eflags),
+            50 => general(Register::es),
+            51 => general(Register::cs),
+            52 => general(Register::ss),
+            53 => general(Register::ds),
+            54 => general(Register::fs),
+            55 => general(Register::gs),
+
+            58 => general(Register::fs_base),
+            59 => general(Register::gs_base),
+
+            62 => Ok(Self::Tr),
+            63 => Ok(Self::Ldtr),
+            64 => Ok(Self::FpControl(FpControlRegister::mxcsr)),
+            65 => Ok(Self::FpControl(FpControlRegister::fcw)),
+            66 => Ok(Self::FpControl(FpControlRegister::fsw)),
+
+            // 56, 57, 60 and 61 are reserved, the numbers after 66 are AVX-512 registers
+            x => Err(DebuggerError::UnimplementedRegister(x)),
+        }
+    }
+}
@@ -13,6 +13,11 @@ use thiserror::Error;
 
 use crate::addr::Addr;
 use crate::dbginfo::SymbolKind;
+use crate::dwarfreg::DwarfRegister;
+use crate::fpregs::FpRegister;
+use crate::limits::LimitKind;
+use crate::syscall::SyscallPhase;
+use crate::Register;
 
 /// Type alias for Results returned by coreminer functions
 ///
@@ -58,6 +63,16 @@ pub enum DebuggerError {
         #[from]
         std::io::Error,
     ),
+    #[error("rr is not installed, it is needed for recording and replaying")]
+    RrNotInstalled,
+    #[error("rr replay {0} before its gdbserver accepted a connection")]
+    RrReplayExited(crate::feedback::ExitStatus),
+    #[error("No rr replay is running")]
+    NoReplay,
+    #[error("Invalid reply from the GDB remote server: {0:?}")]
+    InvalidRemoteReply(String),
+    #[error("The GDB remote server rejected {0:?} with {1:?}")]
+    RemoteRejected(String, String),
     #[error("Given Executable does not exist")]
     ExecutableDoesNotExist,
     #[error("Given Executable is not a file")]
@@ -76,6 +91,87 @@ pub enum DebuggerError {
     BreakpointIsAlreadyEnabled,
     #[error("Tried to disable breakpoint again")]
     BreakpointIsAlreadyDisabled,
+    #[error("There is no breakpoint at {0}")]
+    NoBreakpoint(Addr),
+    #[error(
+        "All {} debug registers for hardware breakpoints are in use",
+        crate::debugreg::DEBUG_ADDR_REGISTERS
+    )]
+    NoFreeDebugRegister,
+    #[error("Not a debug address register: DR{0}")]
+    InvalidDebugRegister(usize),
+    #[error("The address range {0}..{1} is empty")]
+    EmptyAddressRange(Addr, Addr),
+    #[error("The {1} bytes at {0} wrap around the end of the address space")]
+    AddressOverflow(Addr, usize),
+    #[error("The debuggee cannot be resumed without blocking while watchpoints are set")]
+    ResumeWithWatchpoints,
+    #[error("There is no watchpoint or page guard at {0}")]
+    NoWatchpoint(Addr),
+    #[error("Invalid watch condition: {0}, expected something like new == 0")]
+    InvalidWatchCondition(String),
+    #[error("Invalid log format: {0}, expected text with expressions in braces like x = {{x}}")]
+    InvalidLogFormat(String),
+    #[error("A watch condition needs a range of 1 to 8 bytes, not {0}")]
+    WatchConditionTooWide(usize),
+    #[error("There is no writable memory at {0} that could be guarded")]
+    NotWritableMemory(Addr),
+    #[error("The memory at {0} is not mapped from a file")]
+    NotFileBacked(Addr),
+    #[error("The kernel does not support uprobes")]
+    UprobesUnsupported,
+    #[error("A syscall filter can select at most 254 syscalls, not {0}")]
+    SyscallFilterTooLarge(usize),
+    #[error("The CPU or kernel does not support hardware performance counters")]
+    PerfCountersUnsupported,
+    #[error("The performance counters were not started")]
+    PerfCountersNotStarted,
+    #[error("The sampling frequency of the profiler must not be zero")]
+    InvalidProfileFrequency,
+    #[error("The debuggee was not profiled yet")]
+    NoProfile,
+    #[error("The chunk size of chunked responses must not be zero")]
+    InvalidChunkSize,
+    #[error("The request exceeds the {0} limit: {1} > {2}")]
+    LimitExceeded(LimitKind, usize, usize),
+    #[error("Unknown limit: {0}, expected read, disas or symbols")]
+    InvalidLimit(String),
+    #[error("Invalid address expression: {0}, expected ADDR, SYMBOL[+OFFSET], $NAME[+OFFSET] or FILE:LINE")]
+    InvalidAddrExpr(String),
+    #[error("No function or symbol named {0}")]
+    UnknownSymbol(String),
+    #[error("No code at or after line {1} of {0}")]
+    NoCodeAtLine(String, u64),
+    #[error("No source line is known for {0}")]
+    NoLineAtAddr(Addr),
+    #[error("The source of {0} is neither embedded in the debug information nor readable: {1}")]
+    SourceUnavailable(String, String),
+    #[error("Line {1} is past the end of {0}")]
+    LineOutOfRange(String, u64),
+    #[error("The memory at {0} does not contain the bytes that were written to it")]
+    WriteNotVerified(Addr),
+    #[error("Not a valid name for an environment variable: {0:?}")]
+    InvalidEnvName(String),
+    #[error("No value or convenience variable ${0}")]
+    UnknownValue(String),
+    #[error("Invalid name for a convenience variable: {0}")]
+    InvalidVariableName(String),
+    #[error("Unknown quit action: {0}, expected kill or detach")]
+    InvalidQuitAction(String),
+    #[error("Unknown launch break: {0}, expected entry or main")]
+    InvalidLaunchBreak(String),
+    #[error("Unknown scheduler locking: {0}, expected off, step or on")]
+    InvalidSchedulerLocking(String),
+    #[error("Unknown follow-fork mode: {0}, expected parent, child or both")]
+    InvalidFollowFork(String),
+    #[error("Unknown syscall: {0}")]
+    UnknownSyscall(String),
+    #[error("The debuggee did not reach the launch break {0:?}")]
+    LaunchBreakNotFound(crate::debugger::LaunchBreak),
+    #[error("The debuggee has not stopped since it was launched")]
+    NoStopReason,
+    #[error("There is no displayed expression with the number {0}")]
+    NoAutoDisplay(usize),
     #[error("Could not parse integer: {0}")]
     ParseInt(
         #[serde(serialize_with = "ser_err")]
@@ -125,6 +221,20 @@ pub enum DebuggerError {
     HighAddrExistsButNotLowAddr,
     #[error("Register with index {0} is not supported by this debugger")]
     UnimplementedRegister(u16),
+    #[error("The register set has no register named {0}")]
+    MissingRegister(String),
+    #[error("The list of modules is empty, it needs to contain at least the executable")]
+    NoExecutableModule,
+    #[error("The check of step {0} of the script failed, the feedback was: {1}")]
+    ScriptCheckFailed(usize, String),
+    #[error("The stdin of the debuggee is not a pipe, enable the stdin pipe before running it")]
+    NoStdinPipe,
+    #[error("The stdin pipe of the debuggee is full, only {0} of {1} bytes were sent")]
+    StdinPipeFull(usize, usize),
+    #[error("{0} is not a general purpose register")]
+    NotAGeneralRegister(DwarfRegister),
+    #[error("The value of {0} cannot be read in the selected frame")]
+    RegisterUnavailable(DwarfRegister),
     #[error("Wrong Symbol kind for this operation: {0:?}")]
     WrongSymbolKind(SymbolKind),
     #[error("Symbol has no datatype (but needed it)")]
@@ -139,12 +249,44 @@ pub enum DebuggerError {
     VarExprReturnedNothing(String),
     #[error("No datatype found for symbol which needed one")]
     NoDatatypeFound,
+    #[error("Invalid variable expression: {0}, expected something like a.b, a[3], *a, a->b or &a")]
+    InvalidVarExpr(String),
+    #[error("{0} is neither an array nor a pointer")]
+    NotIndexable(String),
+    #[error("{0} is not in memory, so it has no address")]
+    NotAddressable(String),
+    #[error("The type has no member named {0:?}")]
+    NoSuchMember(String),
+    #[error("The value does not fit into the {0} bytes that are written")]
+    ValueDoesNotFit(usize),
+    #[error("The member {0:?} is static, it is not stored in the value")]
+    StaticMember(String),
+    #[error("The member {0:?} is not inside of the value that was read")]
+    MemberNotInValue(String),
     #[error("The debuggee is currently not in a known function")]
     NotInFunction,
     #[error("A required attribute did not exist: {0:?}")]
     AttributeDoesNotExist(#[serde(serialize_with = "ser_dwat")] gimli::DwAt),
     #[error("While parsing a DWARF location: no frame information was provided")]
     NoFrameInfo,
+    #[error("The value of {0:?} in the selected frame could not be recovered")]
+    RegisterNotRecovered(Register),
+    #[error("The variable has no location at {0}, it was probably optimized out")]
+    NoLocationAtAddr(Addr),
+    #[error("The variable was optimized out and has no storage that could be written")]
+    VariableOptimizedOut,
+    #[error("The value of the parameter at the entry of the function could not be recovered")]
+    EntryValueUnavailable,
+    #[error("Writing {0:?} in an outer frame is not supported")]
+    OuterFrameRegisterWrite(Register),
+    #[error("{0} is not preserved across calls, so it is not known in outer frames")]
+    OuterFrameVectorRegister(FpRegister),
+    #[error("A value of {0} bytes does not fit into a vector register")]
+    VectorValueTooLarge(usize),
+    #[error("The processor does not support AVX")]
+    AvxUnsupported,
+    #[error("Stack frame {0} does not exist or could not be unwound")]
+    NoSuchFrame(usize),
     #[error("Tried to run a program while one was already running")]
     AlreadyRunning,
     #[error("Found multiple DWARF entries for an operation that was supposed to only find one")]
@@ -159,9 +301,56 @@ pub enum DebuggerError {
         "Tried to disassemble a line that we had already disassembled for this iteration: {0}"
     )]
     AlreadyDisassembled(Addr),
+    #[error("The debuggee is not stopped at a syscall")]
+    NotInSyscallStop,
+    #[error("The debuggee is not stopped at the {0:?} of a syscall")]
+    WrongSyscallPhase(SyscallPhase),
+    #[error("Syscalls have at most 6 arguments, but argument {0} was requested")]
+    SyscallArgOutOfRange(usize),
+    #[error("The executable has no GOT entry for the symbol: {0}")]
+    GotEntryNotFound(String),
+    #[error("A syscall injected into the debuggee failed: {0}")]
+    InjectedSyscallFailed(i64),
+    #[error("Could not inject into the debuggee: {0}")]
+    InjectionFailed(String),
+    #[error("Could not assemble: {0}")]
+    AssemblyFailed(String),
+    #[error("No loaded module matches: {0}")]
+    ModuleNotFound(String),
+    #[error("Invalid ELF data: {0}")]
+    InvalidElf(String),
+    #[error("Invalid core dump: {0}")]
+    InvalidCore(String),
+    #[error("The memory at {0} is neither in the core dump nor in a file mapped there")]
+    NotInCore(Addr),
+    #[error("The debuggee is a core dump, it cannot be run or changed")]
+    PostMortem,
+    #[error("The memory at {0} was unmapped or made read-only since the checkpoint was taken")]
+    CheckpointRegionUnmapped(Addr),
+    #[error("There is no checkpoint with the number {0}")]
+    NoCheckpoint(usize),
+    #[error("The stops of the debuggee are not being recorded")]
+    NotRecording,
+    #[error("There is no earlier stop in the recording")]
+    NoEarlierStop,
+    #[error("The debuggee did not reach recorded stop {0} at {1} again, recording stopped")]
+    ReplayDiverged(usize, Addr),
     #[error("The UI used {:?}", crate::feedback::Status::PluginContinue)]
     #[cfg(feature = "plugins")]
     UiUsedPluginContinue,
+    #[error("A null pointer was given for {0}")]
+    #[cfg(feature = "ffi")]
+    NullPointer(&'static str),
+    #[error("String is not valid UTF-8: {0}")]
+    #[cfg(feature = "ffi")]
+    Utf8(
+        #[serde(serialize_with = "ser_err")]
+        #[from]
+        std::str::Utf8Error,
+    ),
+    #[error("Coreminer panicked: {0}")]
+    #[cfg(feature = "ffi")]
+    Panicked(String),
     #[error("A plugin had too many iterations (this is an error in the plugin)")]
     #[cfg(feature = "plugins")]
     TooManyPluginIterations,
@@ -20,18 +20,52 @@ use std::ffi::CString;
 use std::fmt::Display;
 use std::path::PathBuf;
 
-use nix::libc::user_regs_struct;
+use base64::prelude::{Engine, BASE64_STANDARD};
+use nix::sys::signal::Signal;
+use nix::sys::wait::WaitStatus;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 #[cfg(feature = "plugins")]
 use steckrs::PluginIDOwned;
 
-use crate::breakpoint::Breakpoint;
+use crate::addrexpr::AddrExpr;
+use crate::antidebug::AntiDebugConfig;
+use crate::assemble::Patch;
+use crate::breakpoint::{Breakpoint, BreakpointInfo};
+use crate::catchpoint::{MemoryCatchConfig, MemoryEvent};
+use crate::checkpoint::CheckpointId;
+use crate::coredump::GeneratedCore;
 use crate::dbginfo::OwnedSymbol;
+use crate::debugger::{FollowFork, LaunchBreak, QuitAction, SchedulerLocking, WriteVerification};
 use crate::disassemble::Disassembly;
+use crate::dump::ModuleDump;
 use crate::errors::DebuggerError;
+use crate::gdbremote::{Resume, StopReply};
+use crate::got::GotEntry;
+use crate::history::ValueHistory;
+use crate::inject::Injection;
+use crate::launchenv::LaunchEnv;
+use crate::limits::Limits;
+use crate::logpoint::LogFormat;
 use crate::memorymap::ProcessMemoryMap;
+use crate::memread::MemoryRead;
+use crate::modules::LoadedModule;
+use crate::perf::CounterValue;
+use crate::procfs::{AuxVector, Environment, FileDescriptor, ProcessInfo};
+use crate::profile::Profile;
+use crate::regset::RegisterSet;
+use crate::rr::Recording;
+use crate::rustpanic::RustPanic;
+use crate::seccomp::SyscallFilter;
+use crate::source::SourceListing;
+use crate::symindex::{FunctionInfo, TypeInfo, VariableInfo};
+use crate::syscall::SyscallStop;
+use crate::thread::Thread;
+use crate::traceopts::PtraceOptions;
 use crate::unwind::Backtrace;
-use crate::variable::VariableValue;
+use crate::uprobe::UprobeStats;
+use crate::variable::{VariableExpression, VariableValue};
+use crate::watchpoint::{WatchCondition, WatchpointHit};
+use crate::xref::Xref;
 use crate::{Addr, Register, Word};
 
 /// Represents a command from the UI to the debugger
@@ -49,7 +83,10 @@ use crate::{Addr, Register, Word};
 /// use std::path::Path;
 ///
 /// // Command to set a breakpoint at address 0x1000
-/// let status = Status::SetBreakpoint(Addr::from(0x1000usize));
+/// let status = Status::SetBreakpoint(Addr::from(0x1000usize).into());
+///
+/// // Command to set a breakpoint at the start of main
+/// let status = Status::SetBreakpoint("main".parse().unwrap());
 ///
 /// // Command to continue execution
 /// let status = Status::Continue;
@@ -67,6 +104,9 @@ pub enum Status {
     /// Generate a backtrace of the call stack
     Backtrace,
 
+    /// Select the stack frame with this index for variable access, 0 is the innermost frame
+    SelectFrame(usize),
+
     /// Step over the current function call
     StepOver,
 
@@ -79,29 +119,183 @@ pub enum Status {
     /// Step a single instruction
     StepSingle,
 
+    /// Single step while the instruction pointer is in the range from the first (inclusive) to
+    /// the second address (exclusive)
+    StepRange(Addr, Addr),
+
+    /// Step to the next source line, stopping in called functions with line information
+    StepLine,
+
+    /// Step to the next source line, stepping over called functions
+    NextLine,
+
+    /// Continue until the address is reached in the current stack frame
+    RunUntil(Addr),
+
+    /// Record the stops of the debuggee from now on, with a checkpoint every this many stops, see
+    /// [`crate::reverse`]
+    StartRecording(usize),
+
+    /// Stop recording and discard the recorded stops and their checkpoints
+    StopRecording,
+
+    /// Go back to the previous recorded stop
+    ReverseStep,
+
+    /// Go back to the last recorded stop at a breakpoint, watchpoint, signal or other event, or
+    /// to the start of the recording if there is none
+    ReverseContinue,
+
+    /// Save the registers and the writable memory of the stopped debuggee in a new checkpoint
+    Checkpoint,
+
+    /// Restore the registers and memory saved in the checkpoint with this number
+    Restore(CheckpointId),
+
+    /// Delete the checkpoint with this number
+    DelCheckpoint(CheckpointId),
+
     /// Look up symbols by name
     GetSymbolsByName(String),
 
+    /// List the functions whose names match a [`NamePattern`](crate::symindex::NamePattern)
+    ListFunctions(String),
+
+    /// List the types whose names match a [`NamePattern`](crate::symindex::NamePattern)
+    ListTypes(String),
+
+    /// List the source lines around an address expression, or around the instruction of the
+    /// selected frame if it is [`None`]
+    ListSource(Option<AddrExpr>),
+
+    /// List the global and static variables whose names match a
+    /// [`NamePattern`](crate::symindex::NamePattern), optionally only of the modules whose file
+    /// names match the second pattern
+    ListVariables(String, Option<String>),
+
+    /// Resolve an address expression, like `main+0x10` or `hot.c:12`, to an address
+    ResolveAddr(AddrExpr),
+
+    /// Set a convenience variable, like `$base`, to the address of an address expression
+    SetConvenience(String, AddrExpr),
+
+    /// Show the value history and the convenience variables
+    ShowValues,
+
     /// Disassemble memory at the specified address
     ///
     /// The boolean parameter indicates whether to show the literal bytes
     /// (including breakpoint instructions) instead of the original code.
-    DisassembleAt(Addr, usize, bool),
+    DisassembleAt(AddrExpr, usize, bool),
 
     /// Exit the debugger
     DebuggerQuit,
 
+    /// Select whether the debuggee is killed or detached when the debugger quits
+    SetQuitAction(QuitAction),
+
+    /// Select which threads run while the main thread of the debuggee is resumed
+    SetSchedulerLocking(SchedulerLocking),
+
+    /// Select which process is debugged when the debuggee forks
+    SetFollowFork(FollowFork),
+
+    /// Set the caps on expensive requests
+    SetLimits(Limits),
+
+    /// Detach from the debuggee and leave it running
+    Detach,
+
     /// Continue execution
     Continue,
 
     /// Set a breakpoint at the specified address
-    SetBreakpoint(Addr),
+    SetBreakpoint(AddrExpr),
+
+    /// Set a hardware breakpoint, using a debug register instead of `INT3`, at the specified
+    /// address
+    SetHardwareBreakpoint(AddrExpr),
 
     /// Get a breakpoint at the specified address
     GetBreakpoint(Addr),
 
+    /// List all breakpoints, with their state and location
+    ListBreakpoints,
+
     /// Remove a breakpoint at the specified address
-    DelBreakpoint(Addr),
+    DelBreakpoint(AddrExpr),
+
+    /// Enable the disabled breakpoint at the specified address again
+    EnableBreakpoint(AddrExpr),
+
+    /// Disable the breakpoint at the specified address, without removing it
+    DisableBreakpoint(AddrExpr),
+
+    /// Make the breakpoint at the specified address log a message instead of stopping, setting
+    /// it if needed, or make it stop again with [`None`]
+    SetLogBreakpoint(AddrExpr, Option<LogFormat>),
+
+    /// Save all breakpoints into a file
+    SaveBreakpoints(PathBuf),
+
+    /// Set the breakpoints saved in a file, rebased to the current load addresses
+    LoadBreakpoints(PathBuf),
+
+    /// Save the configuration of the debugger into a session file
+    SaveSession(PathBuf),
+
+    /// Restore the configuration of the debugger from a session file
+    RestoreSession(PathBuf),
+
+    /// Watch a memory range, starting at the specified address and with the specified length in
+    /// bytes, for changes
+    SetWatchpoint(AddrExpr, usize),
+
+    /// Remove the watchpoint for the memory range starting at the specified address
+    DelWatchpoint(AddrExpr),
+
+    /// Only report changes of the watchpoint or page guard at the specified address for which
+    /// the condition holds, or all changes again with `None`
+    SetWatchCondition(Addr, Option<WatchCondition>),
+
+    /// Watch a large memory range, starting at the specified address and with the specified
+    /// length in bytes, for changes by write protecting its pages
+    SetPageGuard(Addr, usize),
+
+    /// Remove the page guard for the memory range starting at the specified address
+    DelPageGuard(Addr),
+
+    /// Configure which anti-debugging tricks of the debuggee should be defeated
+    SetAntiDebug(AntiDebugConfig),
+
+    /// Set the ptrace options of the debuggee and of debuggees launched later
+    SetPtraceOptions(PtraceOptions),
+
+    /// Enable or disable stopping at every syscall entry and exit of the debuggee
+    CatchSyscalls(bool),
+
+    /// Stop at the entry and exit of the syscalls with these names or numbers, like `openat`, in
+    /// addition to the syscalls that are already selected
+    CatchSyscall(Vec<String>),
+
+    /// Only stop at the selected syscalls, which are selected with a seccomp filter in debuggees
+    /// launched later, or stop at all syscalls again with `None`
+    SetSyscallFilter(Option<SyscallFilter>),
+
+    /// Configure at which memory management syscalls (`mmap`, `mprotect`, `brk`...) the debuggee
+    /// should be stopped
+    CatchMemory(MemoryCatchConfig),
+
+    /// Enable or disable stopping the debuggee when it panics, if it is a Rust program
+    CatchPanics(bool),
+
+    /// Overwrite an argument of the syscall that the debuggee is entering
+    ///
+    /// The first parameter is the index of the argument (`0..6`), the second is the new value.
+    SetSyscallArg(usize, u64),
+
+    /// Overwrite the return value of the syscall that the debuggee is exiting
+    SetSyscallReturn(i64),
 
     /// Get all register values
     DumpRegisters,
@@ -110,10 +304,13 @@ pub enum Status {
     SetRegister(Register, u64),
 
     /// Write a value to memory
-    WriteMem(Addr, Word),
+    WriteMem(AddrExpr, Word),
 
     /// Read a value from memory
-    ReadMem(Addr),
+    ReadMem(AddrExpr),
+
+    /// Read a number of bytes from memory, as far as they are readable
+    ReadMemRange(AddrExpr, usize),
 
     /// Show debugger information
     Infos,
@@ -121,15 +318,128 @@ pub enum Status {
     /// Read a variable's value
     ReadVariable(String),
 
+    /// Display the value of a variable expression every time the debuggee stops, see
+    /// [`StopInfo`]
+    AddAutoDisplay(VariableExpression),
+
+    /// Stop displaying the expression with the given number
+    DelAutoDisplay(usize),
+
     /// Write a value to a variable
     WriteVariable(String, usize),
 
+    /// Read a variable's value in the stack frame with this index
+    ReadFrameVariable(usize, String),
+
+    /// Write a value to a variable in the stack frame with this index
+    WriteFrameVariable(usize, String, usize),
+
     /// Show the current stack
     GetStack,
 
     /// Show the process memory map
     ProcMap,
 
+    /// Show the loaded modules with their base addresses and load biases
+    ListModules,
+
+    /// Add the symbols of an ELF file, as if its lowest segment was loaded at the address
+    AddSymbolFile(PathBuf, Addr),
+
+    /// Show the environment of the debuggee
+    ShowEnviron,
+
+    /// List the open file descriptors of the debuggee
+    ListFileDescriptors,
+
+    /// Show the status and resource usage of the debuggee
+    ProcessInfo,
+
+    /// Show the auxiliary vector of the debuggee
+    ShowAuxv,
+
+    /// List the threads in the thread table of the debuggee
+    ListThreads,
+
+    /// List the GOT entries of the executable and where they point to
+    ListGot,
+
+    /// Overwrite the GOT entry of a symbol (name) to point to an address
+    HookGot(String, Addr),
+
+    /// Inject machine code into new executable memory and optionally run it (code, run)
+    InjectCode(Vec<u8>, bool),
+
+    /// Call a function, given by name or address expression, with integer arguments
+    CallFunction(String, Vec<u64>),
+
+    /// Assemble instructions (text) and write them at an address
+    PatchAsm(Addr, String),
+
+    /// Dump a loaded module (the executable if [`None`]) into an ELF file at the path
+    DumpModule(Option<String>, PathBuf),
+
+    /// Write a core dump of the stopped debuggee to the path
+    GenerateCore(PathBuf),
+
+    /// Find all instructions that reference an address
+    FindXrefs(AddrExpr),
+
+    /// Record a program with its arguments with rr into the trace directory, see [`crate::rr`]
+    Record(
+        PathBuf,
+        #[serde(serialize_with = "serialize_cstring_vec")]
+        #[serde(deserialize_with = "deserialize_cstring_vec")]
+        Vec<CString>,
+        PathBuf,
+    ),
+
+    /// Replay the rr trace in the directory, with the gdbserver of rr on the port
+    Replay(PathBuf, u16),
+
+    /// Resume the rr replay, forwards or backwards
+    ReplayResume(Resume),
+
+    /// Read the registers of the rr replay
+    ReplayRegisters,
+
+    /// Read a number of bytes at an address of the rr replay
+    ReplayReadMem(Addr, usize),
+
+    /// Set a breakpoint at an address of the rr replay
+    ReplaySetBreakpoint(Addr),
+
+    /// Remove the breakpoint at an address of the rr replay
+    ReplayDelBreakpoint(Addr),
+
+    /// Stop the rr replay
+    StopReplay,
+
+    /// Export the call graph of the executable as DOT into a file, or return it without a file
+    ExportCallGraph(Option<PathBuf>),
+
+    /// Export the backtraces taken so far as a DOT graph into a file, or return it without a
+    /// file
+    ExportBacktraceGraph(Option<PathBuf>),
+
+    /// Install a uprobe at an address, capturing the arguments of each hit if the flag is set
+    SetUprobe(Addr, bool),
+
+    /// Remove the uprobe at an address
+    DelUprobe(Addr),
+
+    /// Read the hits of all uprobes
+    ReadUprobes,
+
+    /// Reset and start the hardware performance counters of the debuggee
+    StartPerfCounters,
+
+    /// Stop the hardware performance counters of the debuggee, keeping their values
+    StopPerfCounters,
+
+    /// Read the hardware performance counters of the debuggee
+    ReadPerfCounters,
+
     /// Run a new program
     Run(
         PathBuf,
@@ -138,9 +448,72 @@ pub enum Status {
         Vec<CString>,
     ),
 
+    /// Attach to the running process with the process ID and all of its threads
+    Attach(i32),
+
+    /// Open a core dump for post-mortem debugging, optionally with the path of the executable
+    OpenCore(PathBuf, Option<PathBuf>),
+
+    /// Select where a debuggee should be stopped after it was launched with [`Status::Run`]
+    SetLaunchBreak(Option<LaunchBreak>),
+
+    /// Set a variable in the environment of programs launched later, or remove it with `None`
+    SetLaunchEnv(String, Option<String>),
+
+    /// Load the library at this path before all others in programs launched later, with
+    /// `LD_PRELOAD`
+    PreloadLibrary(String),
+
+    /// Search this directory for libraries first in programs launched later, with
+    /// `LD_LIBRARY_PATH`
+    AddLibraryPath(String),
+
+    /// Show the changes to the environment of programs launched later
+    GetLaunchEnv,
+
+    /// Connect the stdin of programs launched later to a pipe, which is written with
+    /// [`Status::SendStdin`], instead of the stdin of the debugger
+    SetStdinPipe(bool),
+
+    /// Write bytes into the stdin pipe of the debuggee, sent as base64 over JSON
+    SendStdin(
+        #[serde(serialize_with = "serialize_base64")]
+        #[serde(deserialize_with = "deserialize_base64")]
+        Vec<u8>,
+    ),
+
+    /// Close the stdin pipe of the debuggee, so that it reads the end of the file
+    CloseStdin,
+
+    /// Select which writes into the memory of the debuggee are read back to check them
+    SetWriteVerification(WriteVerification),
+
     /// Set the last signal with the number of the signal
     SetLastSignal(i32),
 
+    /// Continue execution and deliver the signal with the given number to the debuggee
+    ContinueWithSignal(i32),
+
+    /// Continue execution, but stop the debuggee again after the given number of milliseconds
+    ContinueTimeout(u64),
+
+    /// Profile the debuggee for the given number of milliseconds, taking the given number of
+    /// samples per second
+    Profile(u64, u64),
+
+    /// Write the last profile into a file, as SVG flamegraph if the flag is set, else as folded
+    /// stacks
+    ExportFlamegraph(PathBuf, bool),
+
+    /// Write the journal of the debug session into a file in the Chrome trace event format
+    ExportTrace(PathBuf),
+
+    /// Get the [`StopReason`] of the last stop of the debuggee
+    WhyStopped,
+
+    /// Send the signal with the given number to the debuggee with `kill`
+    SendSignal(i32),
+
     /// To be used by plugin hooks if the hook is done
     #[serde(skip)]
     #[cfg(feature = "plugins")]
@@ -157,6 +530,16 @@ pub enum Status {
     #[cfg(feature = "plugins")]
     /// Get a list of all loaded plugins
     PluginGetList,
+
+    /// To be used by plugin hooks to show a text to the user, see [`Feedback::Text`]
+    #[serde(skip)]
+    #[cfg(feature = "plugins")]
+    PluginText(String),
+
+    /// To be used by plugin hooks to send their own data to the UI, see [`Feedback::Custom`]
+    #[serde(skip)]
+    #[cfg(feature = "plugins")]
+    PluginCustom(serde_json::Value),
 }
 
 /// Represents the result of a debugging operation
@@ -201,8 +584,22 @@ pub enum Feedback {
     /// Memory address
     Addr(Addr),
 
+    /// A value that was recorded in the value history
+    Recorded {
+        /// Number of the value, it is `$1` for the first value
+        index: usize,
+        /// The feedback with the value
+        feedback: Box<Feedback>,
+    },
+
+    /// The value history and the convenience variables
+    Values(ValueHistory),
+
+    /// Bytes read from memory, and the part of the range that could not be read
+    Memory(MemoryRead),
+
     /// Register values
-    Registers(UserRegs),
+    Registers(RegisterSet),
 
     /// Error condition
     Error(DebuggerError),
@@ -222,18 +619,139 @@ pub enum Feedback {
     /// Variable value
     Variable(VariableValue),
 
+    /// A checkpoint was taken
+    Checkpoint {
+        /// Number of the checkpoint, to restore it with [`Status::Restore`]
+        id: CheckpointId,
+        /// The instruction pointer that is restored with the checkpoint
+        addr: Addr,
+        /// Number of bytes of memory in the checkpoint
+        size: usize,
+    },
+
+    /// The value a function returned, after stepping out of it
+    Returned {
+        /// Name of the function
+        function: String,
+        /// The returned value, interpreted with the return type of the function
+        value: VariableValue,
+    },
+
     /// Stack contents
     Stack(crate::stack::Stack),
 
     /// Process memory map
     ProcessMap(ProcessMemoryMap),
 
+    /// Modules loaded into the debuggee
+    Modules(Vec<LoadedModule>),
+
+    /// Functions of the debuggee, ordered by address
+    Functions(Vec<FunctionInfo>),
+
+    /// Named types of the debuggee, ordered by name
+    Types(Vec<TypeInfo>),
+
+    /// Global and static variables of the debuggee, ordered by address
+    Variables(Vec<VariableInfo>),
+
+    /// Lines of a source file
+    Source(SourceListing),
+
+    /// Environment of the debuggee
+    Environment(Environment),
+
+    /// Changes to the environment of programs launched later
+    LaunchEnv(LaunchEnv),
+
+    /// Open file descriptors of the debuggee
+    FileDescriptors(Vec<FileDescriptor>),
+
+    /// Status and resource usage of the debuggee
+    ProcessInfo(ProcessInfo),
+
+    /// Auxiliary vector of the debuggee
+    AuxVector(AuxVector),
+
+    /// Threads in the thread table of the debuggee
+    Threads(Vec<Thread>),
+
+    /// GOT entries of the executable
+    Got(Vec<GotEntry>),
+
+    /// Code that was injected into the debuggee
+    Injection(Injection),
+
+    /// Code that was overwritten with assembled instructions
+    Patch(Patch),
+
+    /// A module that was dumped into a file
+    ModuleDump(ModuleDump),
+
+    /// A program that was recorded with rr
+    RrRecording(Recording),
+
+    /// Where the rr replay stopped
+    ReplayStopped(StopReply),
+
+    /// A core dump that was written of the debuggee
+    CoreGenerated(GeneratedCore),
+
+    /// Instructions that reference an address
+    Xrefs(Vec<Xref>),
+
+    /// A graph in the DOT language of graphviz
+    Dot(String),
+
+    /// What the uprobes have seen
+    Uprobes(Vec<UprobeStats>),
+
+    /// Values of the hardware performance counters
+    PerfCounters(Vec<CounterValue>),
+
+    /// Samples of the sampling profiler
+    Profile(Profile),
+
     /// Debuggee process exit
-    Exit(i32),
+    Exit(ExitStatus),
+
+    /// The debuggee was resumed and stopped again, for the given reason and with the values of
+    /// the displayed expressions
+    Stopped(StopInfo),
+
+    /// The debuggee was stopped by the debugger, because a timeout was reached or Ctrl-C was
+    /// pressed
+    Interrupted,
 
     /// Returns a requested [`Breakpoint`]
     Breakpoint(Option<Breakpoint>),
 
+    /// All breakpoints, ordered by address
+    Breakpoints(Vec<BreakpointInfo>),
+
+    /// A watched memory range was changed by an instruction
+    WatchpointHit(WatchpointHit),
+
+    /// The debuggee stopped at the entry or exit of a syscall
+    SyscallStop(SyscallStop),
+
+    /// The debuggee exited a memory management syscall that is caught
+    MemoryEvent(MemoryEvent),
+
+    /// The debuggee panicked and is stopped before unwinding, see [`crate::rustpanic`]
+    Panic(RustPanic),
+
+    /// The debuggee forked and is stopped at the fork, see [`Debugger::set_follow_fork`]
+    ///
+    /// [`Debugger::set_follow_fork`]: crate::debugger::Debugger::set_follow_fork
+    ForkEvent(ForkEvent),
+
+    /// A text for the user, like the message of a logging breakpoint or a text from a plugin
+    Text(String),
+
+    /// Data in a format only known to its producer, usually a plugin
+    Custom(serde_json::Value),
+
     #[cfg(feature = "plugins")]
     /// Information on if a plugin is enabled
     ///
@@ -260,21 +778,186 @@ pub(crate) enum InternalFeedback {
 }
 
 impl Display for Feedback {
+    #[allow(clippy::too_many_lines)] // one arm per variant
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Feedback::Ok => write!(f, "Ok")?,
             Feedback::Error(e) => write!(f, "Error: {e}")?,
-            Feedback::Registers(regs) => write!(f, "Registers: {regs:#x?}")?,
+            Feedback::Registers(regs) => write!(f, "Registers:\n{regs}")?,
             Feedback::Word(w) => write!(f, "Word: {w:#018x?}")?,
             Feedback::Addr(w) => write!(f, "Address: {w}")?,
+            Feedback::Memory(read) => write!(f, "Memory:\n{read}")?,
+            Feedback::Recorded { index, feedback } => write!(f, "${index} = {feedback}")?,
+            Feedback::Values(history) => {
+                write!(f, "Values:")?;
+                for (index, value) in history.values().iter().enumerate() {
+                    write!(f, "\n  ${} = {value:#x}", index + 1)?;
+                }
+                for (name, value) in history.variables() {
+                    write!(f, "\n  ${name} = {value:#x}")?;
+                }
+            }
             Feedback::Disassembly(t) => write!(f, "{t:#?}")?,
             Feedback::Symbols(t) => write!(f, "Symbols: {t:#?}")?,
             Feedback::Backtrace(t) => write!(f, "Backtrace: {t:#?}")?,
-            Feedback::Variable(t) => write!(f, "Variable: {t:#?}")?,
+            Feedback::Variable(t) => write!(f, "Variable: {t}")?,
+            Feedback::Returned { function, value } => write!(f, "{function} returned {value}")?,
+            Feedback::Checkpoint { id, addr, size } => {
+                write!(f, "Checkpoint {id} at {addr}, {size} bytes of memory saved")?;
+            }
             Feedback::Stack(t) => write!(f, "Stack:\n{t}")?,
             Feedback::ProcessMap(pm) => write!(f, "Process Map:\n{pm:#x?}")?,
-            Feedback::Exit(code) => write!(f, "Debugee exited with code {code}")?,
+            Feedback::Modules(modules) => {
+                write!(f, "Modules:")?;
+                for module in modules {
+                    write!(
+                        f,
+                        "\n  {} bias {:#x} {}",
+                        module.base, module.bias, module.path
+                    )?;
+                }
+            }
+            Feedback::Functions(functions) => {
+                write!(f, "Functions:")?;
+                for function in functions {
+                    write!(f, "\n  {function}")?;
+                }
+            }
+            Feedback::Types(types) => {
+                write!(f, "Types:")?;
+                for info in types {
+                    write!(f, "\n  {info}")?;
+                }
+            }
+            Feedback::Source(listing) => write!(f, "{listing}")?,
+            Feedback::Variables(variables) => {
+                write!(f, "Variables:")?;
+                for variable in variables {
+                    write!(f, "\n  {variable}")?;
+                }
+            }
+            Feedback::Environment(env) => write!(f, "Environment:\n{env}")?,
+            Feedback::LaunchEnv(env) => write!(f, "Launch environment:\n{env}")?,
+            Feedback::ProcessInfo(info) => write!(f, "Process Info:\n{info}")?,
+            Feedback::AuxVector(auxv) => write!(f, "Auxiliary Vector:\n{auxv}")?,
+            Feedback::ModuleDump(dump) => write!(
+                f,
+                "Dumped {} (loaded at {}) to {}: {} bytes, {} sections restored",
+                dump.module,
+                dump.base,
+                dump.output.display(),
+                dump.size,
+                dump.sections
+            )?,
+            Feedback::CoreGenerated(core) => write!(
+                f,
+                "Saved a core dump of {} to {}: {} bytes, {} threads, {} memory regions",
+                core.pid,
+                core.output.display(),
+                core.size,
+                core.threads,
+                core.segments
+            )?,
+            Feedback::Patch(patch) => {
+                write!(
+                    f,
+                    "Patched {} bytes at {}, replacing {} bytes of instructions",
+                    patch.bytes.len(),
+                    patch.addr,
+                    patch.replaced.len()
+                )?;
+                if !patch.len_matches() {
+                    write!(f, " (length mismatch!)")?;
+                }
+            }
+            Feedback::Injection(injection) => {
+                write!(
+                    f,
+                    "Injected {} bytes at {}",
+                    injection.len, injection.addr
+                )?;
+                if let Some(result) = injection.result {
+                    write!(f, ", rax after running: {result:#x}")?;
+                }
+            }
+            Feedback::Got(entries) => {
+                writeln!(f, "GOT:")?;
+                for entry in entries {
+                    writeln!(f, "{entry}")?;
+                }
+            }
+            Feedback::RrRecording(recording) => write!(
+                f,
+                "Recorded into {}, the program {}",
+                recording.trace_dir.display(),
+                recording.exit
+            )?,
+            Feedback::ReplayStopped(stop) => write!(f, "Replay {stop}")?,
+            Feedback::Xrefs(xrefs) => {
+                writeln!(f, "References:")?;
+                for xref in xrefs {
+                    writeln!(f, "{xref}")?;
+                }
+            }
+            Feedback::Dot(dot) => writeln!(f, "{dot}")?,
+            Feedback::Uprobes(probes) => {
+                writeln!(f, "Uprobes:")?;
+                for probe in probes {
+                    writeln!(f, "{probe}")?;
+                }
+            }
+            Feedback::PerfCounters(values) => {
+                writeln!(f, "Performance Counters:")?;
+                for value in values {
+                    writeln!(f, "{value}")?;
+                }
+            }
+            Feedback::Profile(profile) => writeln!(f, "Profile: {profile}")?,
+            Feedback::Threads(threads) => {
+                writeln!(f, "Threads:")?;
+                for thread in threads {
+                    writeln!(f, "{thread}")?;
+                }
+            }
+            Feedback::FileDescriptors(fds) => {
+                writeln!(f, "File Descriptors:")?;
+                for fd in fds {
+                    writeln!(f, "{fd}")?;
+                }
+            }
+            Feedback::Exit(status) => write!(f, "Debugee {status}")?,
+            Feedback::Interrupted => write!(f, "Debuggee was interrupted")?,
+            Feedback::Stopped(info) => write!(f, "Stopped: {info}")?,
             Feedback::Breakpoint(bp) => write!(f, "Breakpoint: {bp:?}")?,
+            Feedback::Breakpoints(breakpoints) => {
+                write!(f, "Breakpoints:")?;
+                for info in breakpoints {
+                    write!(f, "\n  {info}")?;
+                }
+            }
+            Feedback::WatchpointHit(hit) => write!(
+                f,
+                "Watchpoint: {} ({} bytes) was changed by the instruction at {}\n  old: {:02x?}\n  new: {:02x?}",
+                hit.addr, hit.len, hit.instruction, hit.old, hit.new
+            )?,
+            Feedback::SyscallStop(stop) => match stop.ret {
+                None => write!(
+                    f,
+                    "Syscall entry: {} with arguments {:#x?}",
+                    stop.name(),
+                    stop.args
+                )?,
+                Some(ret) => write!(
+                    f,
+                    "Syscall exit: {} returned {ret}",
+                    stop.name()
+                )?,
+            },
+            Feedback::MemoryEvent(event) => write!(f, "Memory event: {event}")?,
+            Feedback::Panic(panic) => write!(f, "The debuggee {panic}")?,
+            Feedback::ForkEvent(fork) => write!(f, "{fork}")?,
+            Feedback::Text(text) => write!(f, "{text}")?,
+            Feedback::Custom(value) => write!(f, "Custom: {value}")?,
             Feedback::Internal(_) => write!(f, "Internal Feedback")?,
             #[cfg(feature = "plugins")]
             Feedback::PluginStatus(ps) => write!(f, "Plugin Status: {ps:?}")?,
@@ -300,72 +983,441 @@ impl From<Result<Feedback, DebuggerError>> for Feedback {
     }
 }
 
-/// A Datastructure with all the registers
+impl Feedback {
+    /// Gets the list of this feedback that can be sent in chunks
+    ///
+    /// Some feedback can get very large, like the disassembly of a whole function or a long list
+    /// of symbols. Interfaces like [`JsonUI`](crate::ui::json::JsonUI) use this to stream the
+    /// items of the list one at a time instead of serializing everything at once.
+    ///
+    /// # Returns
+    ///
+    /// * `Some(ChunkableList)` - The list and where it is in the serialized feedback
+    /// * `None` - If this kind of feedback has no list that can be chunked
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use coreminer::feedback::Feedback;
+    /// use coreminer::variable::VariableValue;
+    ///
+    /// let feedback = Feedback::Variable(VariableValue::Bytes(vec![1, 2, 3]));
+    /// let list = feedback.chunkable().unwrap();
+    /// assert_eq!(list.path, "/Variable/Bytes");
+    /// assert_eq!(list.items.len(), 3);
+    ///
+    /// let mut item = Vec::new();
+    /// list.items.write_item(2, &mut item).unwrap();
+    /// assert_eq!(item, b"3");
+    ///
+    /// assert!(Feedback::Ok.chunkable().is_none());
+    /// ```
+    #[must_use]
+    #[allow(clippy::too_many_lines)] // one arm per list
+    pub fn chunkable(&self) -> Option<ChunkableList<'_>> {
+        let (path, items, rest): (_, Box<dyn ChunkItems + '_>, _) = match self {
+            Feedback::Disassembly(d) => (
+                "/Disassembly/vec",
+                Box::new(d),
+                Feedback::Disassembly(Disassembly::empty()),
+            ),
+            Feedback::Backtrace(bt) => (
+                "/Backtrace/frames",
+                Box::new(bt.frames.as_slice()),
+                Feedback::Backtrace(Backtrace { frames: Vec::new() }),
+            ),
+            Feedback::Symbols(syms) => (
+                "/Symbols",
+                Box::new(syms.as_slice()),
+                Feedback::Symbols(Vec::new()),
+            ),
+            Feedback::Variable(VariableValue::Bytes(bytes)) => (
+                "/Variable/Bytes",
+                Box::new(bytes.as_slice()),
+                Feedback::Variable(VariableValue::Bytes(Vec::new())),
+            ),
+            Feedback::Stack(stack) => (
+                "/Stack/words",
+                Box::new(stack.words()),
+                Feedback::Stack(crate::stack::Stack::new(stack.start_addr())),
+            ),
+            Feedback::ProcessMap(map) => (
+                "/ProcessMap/regions",
+                Box::new(map.regions.as_slice()),
+                Feedback::ProcessMap(ProcessMemoryMap {
+                    regions: Vec::new(),
+                    ..*map
+                }),
+            ),
+            Feedback::FileDescriptors(fds) => (
+                "/FileDescriptors",
+                Box::new(fds.as_slice()),
+                Feedback::FileDescriptors(Vec::new()),
+            ),
+            Feedback::Threads(threads) => (
+                "/Threads",
+                Box::new(threads.as_slice()),
+                Feedback::Threads(Vec::new()),
+            ),
+            Feedback::Got(entries) => (
+                "/Got",
+                Box::new(entries.as_slice()),
+                Feedback::Got(Vec::new()),
+            ),
+            Feedback::Xrefs(xrefs) => (
+                "/Xrefs",
+                Box::new(xrefs.as_slice()),
+                Feedback::Xrefs(Vec::new()),
+            ),
+            Feedback::Uprobes(stats) => (
+                "/Uprobes",
+                Box::new(stats.as_slice()),
+                Feedback::Uprobes(Vec::new()),
+            ),
+            Feedback::PerfCounters(values) => (
+                "/PerfCounters",
+                Box::new(values.as_slice()),
+                Feedback::PerfCounters(Vec::new()),
+            ),
+            Feedback::Memory(read) => (
+                "/Memory/bytes",
+                Box::new(read.bytes.as_slice()),
+                Feedback::Memory(MemoryRead {
+                    addr: read.addr,
+                    bytes: Vec::new(),
+                    unreadable: read.unreadable.clone(),
+                }),
+            ),
+            Feedback::Modules(modules) => (
+                "/Modules",
+                Box::new(modules.as_slice()),
+                Feedback::Modules(Vec::new()),
+            ),
+            Feedback::Functions(functions) => (
+                "/Functions",
+                Box::new(functions.as_slice()),
+                Feedback::Functions(Vec::new()),
+            ),
+            Feedback::Types(types) => (
+                "/Types",
+                Box::new(types.as_slice()),
+                Feedback::Types(Vec::new()),
+            ),
+            Feedback::Variables(variables) => (
+                "/Variables",
+                Box::new(variables.as_slice()),
+                Feedback::Variables(Vec::new()),
+            ),
+            Feedback::Breakpoints(breakpoints) => (
+                "/Breakpoints",
+                Box::new(breakpoints.as_slice()),
+                Feedback::Breakpoints(Vec::new()),
+            ),
+            _ => return None,
+        };
+        Some(ChunkableList { path, items, rest })
+    }
+}
+
+/// A list in a [`Feedback`] that can be sent in chunks, see [`Feedback::chunkable`]
+pub struct ChunkableList<'a> {
+    /// JSON pointer of the list in the serialized feedback, like `/Disassembly/vec`
+    pub path: &'static str,
+    /// The items of the list
+    pub items: Box<dyn ChunkItems + 'a>,
+    /// The same feedback, but with the list left empty
+    pub rest: Feedback,
+}
+
+/// A list whose items can be serialized one at a time, see [`Feedback::chunkable`]
+pub trait ChunkItems {
+    /// Returns the number of items in the list
+    fn len(&self) -> usize;
+
+    /// Returns `true` if the list has no items
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Writes the item at `index` as JSON to `out`
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if serializing the item or writing to `out` fails.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `index` is out of bounds.
+    fn write_item(&self, index: usize, out: &mut dyn std::io::Write) -> serde_json::Result<()>;
+}
+
+impl<T: Serialize> ChunkItems for &[T] {
+    fn len(&self) -> usize {
+        <[T]>::len(self)
+    }
+
+    fn write_item(&self, index: usize, out: &mut dyn std::io::Write) -> serde_json::Result<()> {
+        serde_json::to_writer(out, &self[index])
+    }
+}
+
+/// How the debuggee terminated
+///
+/// # Examples
+///
+/// ```
+/// use coreminer::feedback::ExitStatus;
+/// use nix::sys::signal::Signal;
+/// use nix::sys::wait::WaitStatus;
+/// use nix::unistd::Pid;
+///
+/// let status = WaitStatus::Signaled(Pid::from_raw(1337), Signal::SIGSEGV, true);
+/// let status = ExitStatus::from_wait_status(status).unwrap();
+/// assert_eq!(status.code(), None);
+/// assert_eq!(status.to_string(), "was terminated by SIGSEGV (core dumped)");
+///
+/// let status = ExitStatus::from_wait_status(WaitStatus::Exited(Pid::from_raw(1337), 2));
+/// assert_eq!(status, Some(ExitStatus::Exited(2)));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ExitStatus {
+    /// The debuggee exited with the exit code
+    Exited(i32),
+    /// The debuggee was terminated by a signal
+    Signaled {
+        /// Number of the terminating signal
+        signal: i32,
+        /// Whether a core dump was written
+        core_dumped: bool,
+    },
+}
+
+impl ExitStatus {
+    /// Creates an [`ExitStatus`] from the [`WaitStatus`] of a terminated process
+    ///
+    /// # Returns
+    ///
+    /// [`None`] if the [`WaitStatus`] does not describe a terminated process.
+    #[must_use]
+    pub fn from_wait_status(wait_status: WaitStatus) -> Option<Self> {
+        match wait_status {
+            WaitStatus::Exited(_, code) => Some(Self::Exited(code)),
+            WaitStatus::Signaled(_, signal, core_dumped) => Some(Self::Signaled {
+                signal: signal as i32,
+                core_dumped,
+            }),
+            _ => None,
+        }
+    }
+
+    /// Returns the exit code, or [`None`] if the debuggee was terminated by a signal
+    #[must_use]
+    pub fn code(&self) -> Option<i32> {
+        match self {
+            Self::Exited(code) => Some(*code),
+            Self::Signaled { .. } => None,
+        }
+    }
+}
+
+/// Describes a `fork` or `vfork` of the debuggee
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+pub struct ForkEvent {
+    /// Process ID of the process that forked
+    pub parent: i32,
+    /// Process ID of the new process
+    pub child: i32,
+    /// Whether the new process was created with `vfork` and shares the memory of the parent
+    pub vfork: bool,
+    /// Process ID of the process that is debugged from now on
+    pub followed: i32,
+}
+
+impl Display for ForkEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let call = if self.vfork { "vfork" } else { "fork" };
+        write!(
+            f,
+            "Process {} created process {} with {call}, following process {}",
+            self.parent, self.child, self.followed
+        )
+    }
+}
+
+impl Display for ExitStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Exited(code) => write!(f, "exited with code {code}"),
+            Self::Signaled {
+                signal,
+                core_dumped,
+            } => {
+                match Signal::try_from(*signal) {
+                    Ok(sig) => write!(f, "was terminated by {sig}")?,
+                    Err(_) => write!(f, "was terminated by signal {signal}")?,
+                }
+                if *core_dumped {
+                    write!(f, " (core dumped)")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// A stop of the debuggee, with the values of the expressions that are displayed at every stop
+///
+/// This is the content of [`Feedback::Stopped`]. The expressions are added with
+/// [`Status::AddAutoDisplay`] and read again each time the debuggee stops.
+///
+/// # Examples
+///
+/// ```
+/// use coreminer::feedback::{AutoDisplay, StopInfo, StopReason};
 ///
-/// This is more or less the same as [`nix::libc::user_regs_struct`], but can be serialized with
-/// [`serde`].
+/// let info = StopInfo {
+///     reason: StopReason::StepDone,
+///     displays: vec![AutoDisplay {
+///         number: 1,
+///         expression: "i".to_string(),
+///         value: Err("No variable named i".to_string()),
+///     }],
+/// };
+/// assert_eq!(info.to_string(), "step done\n1: i = <error: No variable named i>");
+/// assert_eq!(StopInfo::from(StopReason::StepDone).to_string(), "step done");
+/// ```
 #[derive(Debug, Clone, Serialize)]
-#[allow(missing_docs)] // name of the reg and their values, the fields are self explanatory
-pub struct UserRegs {
-    pub r15: u64,
-    pub r14: u64,
-    pub r13: u64,
-    pub r12: u64,
-    pub rbp: u64,
-    pub rbx: u64,
-    pub r11: u64,
-    pub r10: u64,
-    pub r9: u64,
-    pub r8: u64,
-    pub rax: u64,
-    pub rcx: u64,
-    pub rdx: u64,
-    pub rsi: u64,
-    pub rdi: u64,
-    pub orig_rax: u64,
-    pub rip: u64,
-    pub cs: u64,
-    pub eflags: u64,
-    pub rsp: u64,
-    pub ss: u64,
-    pub fs_base: u64,
-    pub gs_base: u64,
-    pub ds: u64,
-    pub es: u64,
-    pub fs: u64,
-    pub gs: u64,
+pub struct StopInfo {
+    /// Why the debuggee stopped
+    pub reason: StopReason,
+    /// The values of the displayed expressions, by their number
+    pub displays: Vec<AutoDisplay>,
 }
 
-impl From<user_regs_struct> for UserRegs {
-    fn from(regs: user_regs_struct) -> Self {
+impl From<StopReason> for StopInfo {
+    fn from(reason: StopReason) -> Self {
         Self {
-            r15: regs.r15,
-            r14: regs.r14,
-            r13: regs.r13,
-            r12: regs.r12,
-            rbp: regs.rbp,
-            rbx: regs.rbx,
-            r11: regs.r11,
-            r10: regs.r10,
-            r9: regs.r9,
-            r8: regs.r8,
-            rax: regs.rax,
-            rcx: regs.rcx,
-            rdx: regs.rdx,
-            rsi: regs.rsi,
-            rdi: regs.rdi,
-            orig_rax: regs.orig_rax,
-            rip: regs.rip,
-            cs: regs.cs,
-            eflags: regs.eflags,
-            rsp: regs.rsp,
-            ss: regs.ss,
-            fs_base: regs.fs_base,
-            gs_base: regs.gs_base,
-            ds: regs.ds,
-            es: regs.es,
-            fs: regs.fs,
-            gs: regs.gs,
+            reason,
+            displays: Vec::new(),
+        }
+    }
+}
+
+impl Display for StopInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.reason)?;
+        for display in &self.displays {
+            write!(f, "\n{display}")?;
+        }
+        Ok(())
+    }
+}
+
+/// The value of an expression that is displayed at every stop, see [`StopInfo`]
+#[derive(Debug, Clone, Serialize)]
+pub struct AutoDisplay {
+    /// The number of the expression, to remove it with [`Status::DelAutoDisplay`]
+    pub number: usize,
+    /// The variable expression
+    pub expression: VariableExpression,
+    /// The value of the expression, or why it could not be read at this stop
+    pub value: std::result::Result<VariableValue, String>,
+}
+
+impl Display for AutoDisplay {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.value {
+            Ok(value) => write!(f, "{}: {} = {value}", self.number, self.expression),
+            Err(e) => write!(f, "{}: {} = <error: {e}>", self.number, self.expression),
+        }
+    }
+}
+
+/// Why the debuggee stopped the last time it was resumed
+///
+/// After [`Status::Continue`] or one of the stepping statuses, the debugger returns
+/// [`Feedback::Stopped`] with the reason, see [`StopInfo`], instead of a plain [`Feedback::Ok`].
+/// Stops that already have their own [`Feedback`], such as [`Feedback::Exit`] or
+/// [`Feedback::WatchpointHit`], are returned as before. The reason of the last stop can be queried again at any time with
+/// [`Status::WhyStopped`].
+///
+/// [Breakpoints](Breakpoint) are identified by their address.
+///
+/// # Examples
+///
+/// ```
+/// use coreminer::addr::Addr;
+/// use coreminer::feedback::{ExitStatus, StopReason};
+///
+/// let reason = StopReason::Breakpoint {
+///     addr: Addr::from(0x401136usize),
+///     hardware: false,
+/// };
+/// assert_eq!(reason.to_string(), format!("breakpoint at {}", Addr::from(0x401136usize)));
+/// assert_eq!(StopReason::Signal(11).to_string(), "received SIGSEGV");
+/// assert_eq!(
+///     StopReason::Exited(ExitStatus::Exited(0)).to_string(),
+///     "debuggee exited with code 0"
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+pub enum StopReason {
+    /// An enabled [`Breakpoint`] at `addr` was hit
+    Breakpoint {
+        /// Address of the [`Breakpoint`]
+        addr: Addr,
+        /// Whether it is a hardware breakpoint
+        hardware: bool,
+    },
+    /// A watched memory range starting at `addr` was changed
+    Watchpoint {
+        /// Start of the watched memory range
+        addr: Addr,
+    },
+    /// A single step or a stepping operation was completed
+    StepDone,
+    /// The debuggee received a signal, which is delivered when it is resumed
+    Signal(i32),
+    /// The debuggee stopped at the entry or exit of a caught syscall
+    Syscall(SyscallStop),
+    /// The debuggee stopped at a ptrace event, see [`PtraceOptions`]
+    PtraceEvent(i32),
+    /// The debuggee was stopped by the debugger, see [`Feedback::Interrupted`]
+    Interrupted,
+    /// The debuggee panicked and is stopped in `rust_panic` at `addr`
+    Panic {
+        /// Address of `rust_panic`
+        addr: Addr,
+    },
+    /// The debuggee terminated
+    Exited(ExitStatus),
+}
+
+impl Display for StopReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Breakpoint {
+                addr,
+                hardware: false,
+            } => write!(f, "breakpoint at {addr}"),
+            Self::Breakpoint {
+                addr,
+                hardware: true,
+            } => write!(f, "hardware breakpoint at {addr}"),
+            Self::Watchpoint { addr } => write!(f, "watched memory at {addr} was changed"),
+            Self::StepDone => write!(f, "step done"),
+            Self::Signal(signal) => match Signal::try_from(*signal) {
+                Ok(sig) => write!(f, "received {sig}"),
+                Err(_) => write!(f, "received signal {signal}"),
+            },
+            Self::Syscall(stop) => match stop.ret {
+                None => write!(f, "entry of syscall {}", stop.name()),
+                Some(ret) => write!(f, "exit of syscall {} returned {ret}", stop.name()),
+            },
+            Self::PtraceEvent(event) => write!(f, "ptrace event {event}"),
+            Self::Interrupted => write!(f, "interrupted by the debugger"),
+            Self::Panic { addr } => write!(f, "panic in rust_panic at {addr}"),
+            Self::Exited(status) => write!(f, "debuggee {status}"),
         }
     }
 }
@@ -381,6 +1433,23 @@ where
     strings.serialize(serializer)
 }
 
+fn serialize_base64<S>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.serialize_str(&BASE64_STANDARD.encode(bytes))
+}
+
+fn deserialize_base64<'de, D>(deserializer: D) -> Result<Vec<u8>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let encoded = String::deserialize(deserializer)?;
+    BASE64_STANDARD
+        .decode(encoded)
+        .map_err(serde::de::Error::custom)
+}
+
 fn deserialize_cstring_vec<'de, D>(deserializer: D) -> Result<Vec<CString>, D::Error>
 where
     D: Deserializer<'de>,
@@ -0,0 +1,686 @@
+//! # C Interface
+//!
+//! Provides a stable `extern "C"` API to embed the debugger into programs that are not written in
+//! Rust, like IDEs and other tools.
+//!
+//! The API is built around the opaque [`CmDebugger`] handle, which is created with
+//! [`cm_debugger_new`] and destroyed with [`cm_debugger_free`]. Functions return `0` (or the
+//! number of bytes for memory access) on success and `-1` on failure, in which case
+//! [`cm_last_error`] describes what went wrong. Whenever the debuggee stops or terminates, the
+//! callback set with [`cm_set_event_callback`] is called with a [`CmEvent`] that says why.
+//!
+//! A handle must only be used by one thread at a time, and only by the thread that launched or
+//! attached to the debuggee, as ptrace requires that.
+//!
+//! This module is only available with the `ffi` feature. The shared library is then built with
+//! `cargo build --release --features ffi`, and the header `include/coreminer.h` is generated from
+//! this module with [cbindgen](https://github.com/mozilla/cbindgen):
+//!
+//! ```bash
+//! cbindgen --config cbindgen.toml --output include/coreminer.h
+//! ```
+//!
+//! # Examples
+//!
+//! ```c
+//! #include <stdio.h>
+//! #include "coreminer.h"
+//!
+//! static void on_event(const CmEvent *event, void *user_data) {
+//!     printf("stopped: %s\n", event->description);
+//! }
+//!
+//! int main(void) {
+//!     CmDebugger *dbg = cm_debugger_new();
+//!     cm_set_event_callback(dbg, on_event, NULL);
+//!
+//!     if (cm_launch(dbg, "./target/debug/dummy", NULL, 0) != 0
+//!         || cm_set_breakpoint(dbg, 0x401136, false) != 0
+//!         || cm_continue(dbg) != 0) {
+//!         fprintf(stderr, "error: %s\n", cm_last_error(dbg));
+//!     }
+//!
+//!     cm_debugger_free(dbg);
+//!     return 0;
+//! }
+//! ```
+
+use std::ffi::{c_char, c_int, c_void, CStr, CString, OsStr};
+use std::os::unix::ffi::OsStrExt;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use tracing::warn;
+
+use crate::debugger::Debugger;
+use crate::errors::{DebuggerError, Result};
+use crate::feedback::{ExitStatus, Feedback, Status, StopReason};
+use crate::ui::{CommandSource, Event, EventSink};
+use crate::{mem_read, mem_write, Addr, Register};
+
+/// The [`DebuggerUI`](crate::ui::DebuggerUI) of a [`CmDebugger`]
+///
+/// The debugger loop is never run for the C interface, the caller drives the debugger directly.
+/// Stops are pushed to the sink by the debugger and passed on to the callback.
+struct FfiUi {
+    callback: CmEventCallback,
+    user_data: *mut c_void,
+}
+
+impl CommandSource for FfiUi {
+    fn next_status(&mut self) -> Result<Status> {
+        Ok(Status::DebuggerQuit)
+    }
+}
+
+impl EventSink for FfiUi {
+    fn push_event(&mut self, event: Event) -> Result<()> {
+        if let Event::Stopped(reason) = event {
+            self.emit(reason);
+        }
+        Ok(())
+    }
+}
+
+impl FfiUi {
+    fn emit(&self, reason: StopReason) {
+        let Some(callback) = self.callback else {
+            return;
+        };
+        // the description comes from Display, which does not contain nul bytes
+        let description = CString::new(reason.to_string()).unwrap_or_default();
+        let event = CmEvent::new(reason, &description);
+        callback(&event, self.user_data);
+    }
+}
+
+/// Opaque handle to a debugger, created with [`cm_debugger_new`]
+pub struct CmDebugger {
+    debugger: Debugger<FfiUi>,
+    last_error: Option<CString>,
+}
+
+/// Called for every [`CmEvent`], with the `user_data` given to [`cm_set_event_callback`]
+///
+/// The event and its description are only valid during the call.
+pub type CmEventCallback = Option<extern "C" fn(event: *const CmEvent, user_data: *mut c_void)>;
+
+/// The kind of a [`CmEvent`], see [`StopReason`]
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CmEventKind {
+    /// A breakpoint at [`CmEvent::addr`] was hit
+    Breakpoint,
+    /// The watched memory range starting at [`CmEvent::addr`] was changed
+    Watchpoint,
+    /// A single step or a stepping operation was completed
+    StepDone,
+    /// The debuggee received the signal [`CmEvent::value`]
+    Signal,
+    /// The debuggee entered the syscall [`CmEvent::value`]
+    SyscallEntry,
+    /// The syscall [`CmEvent::value`] returned [`CmEvent::ret`]
+    SyscallExit,
+    /// The debuggee stopped at the ptrace event [`CmEvent::value`]
+    PtraceEvent,
+    /// The debuggee was stopped by the debugger
+    Interrupted,
+    /// The debuggee exited with the exit code [`CmEvent::value`]
+    Exited,
+    /// The debuggee was terminated by the signal [`CmEvent::value`]
+    Signaled,
+    /// The debuggee panicked and is stopped in `rust_panic` at [`CmEvent::addr`]
+    Panic,
+}
+
+/// Describes why the debuggee stopped or terminated
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct CmEvent {
+    /// What happened
+    pub kind: CmEventKind,
+    /// Address of the breakpoint, watchpoint or `rust_panic`, `0` for other kinds
+    pub addr: u64,
+    /// Whether the breakpoint is a hardware breakpoint
+    pub hardware: bool,
+    /// Signal, syscall or ptrace event number, or exit code, depending on the kind
+    pub value: i64,
+    /// Return value of the syscall for [`CmEventKind::SyscallExit`], `0` for other kinds
+    pub ret: i64,
+    /// Human readable description of the event
+    pub description: *const c_char,
+}
+
+impl CmEvent {
+    fn new(reason: StopReason, description: &CStr) -> Self {
+        let mut event = CmEvent {
+            kind: CmEventKind::StepDone,
+            addr: 0,
+            hardware: false,
+            value: 0,
+            ret: 0,
+            description: description.as_ptr(),
+        };
+        match reason {
+            StopReason::Breakpoint { addr, hardware } => {
+                event.kind = CmEventKind::Breakpoint;
+                event.addr = addr.u64();
+                event.hardware = hardware;
+            }
+            StopReason::Watchpoint { addr } => {
+                event.kind = CmEventKind::Watchpoint;
+                event.addr = addr.u64();
+            }
+            StopReason::StepDone => (),
+            StopReason::Signal(signal) => {
+                event.kind = CmEventKind::Signal;
+                event.value = signal.into();
+            }
+            StopReason::Syscall(stop) => {
+                event.value = stop.number as i64;
+                match stop.ret {
+                    None => event.kind = CmEventKind::SyscallEntry,
+                    Some(ret) => {
+                        event.kind = CmEventKind::SyscallExit;
+                        event.ret = ret;
+                    }
+                }
+            }
+            StopReason::PtraceEvent(ptrace_event) => {
+                event.kind = CmEventKind::PtraceEvent;
+                event.value = ptrace_event.into();
+            }
+            StopReason::Interrupted => event.kind = CmEventKind::Interrupted,
+            StopReason::Panic { addr } => {
+                event.kind = CmEventKind::Panic;
+                event.addr = addr.u64();
+            }
+            StopReason::Exited(ExitStatus::Exited(code)) => {
+                event.kind = CmEventKind::Exited;
+                event.value = code.into();
+            }
+            StopReason::Exited(ExitStatus::Signaled { signal, .. }) => {
+                event.kind = CmEventKind::Signaled;
+                event.value = signal.into();
+            }
+        }
+        event
+    }
+}
+
+impl CmDebugger {
+    /// Processes a [`Status`] like the debugger loop does, stops are reported to the callback by
+    /// the [`FfiUi`]
+    fn execute(&mut self, status: &Status) -> Result<Feedback> {
+        let feedback = self.debugger.process_status(status)?;
+        if let Feedback::Exit(_) = feedback {
+            self.debugger.debuggee = None;
+        }
+        Ok(feedback)
+    }
+}
+
+/// Calls `f` with the [`CmDebugger`] behind `handle`
+///
+/// Returns `failed` if `handle` is null or `f` failed or panicked. Errors and panics are stored
+/// for [`cm_last_error`].
+///
+/// # Safety
+///
+/// `handle` must be null or a valid handle from [`cm_debugger_new`].
+unsafe fn call<T>(
+    handle: *mut CmDebugger,
+    failed: T,
+    f: impl FnOnce(&mut CmDebugger) -> Result<T>,
+) -> T {
+    // SAFETY: the caller guarantees that the handle is null or valid
+    let Some(handle) = (unsafe { handle.as_mut() }) else {
+        return failed;
+    };
+    handle.last_error = None;
+
+    // unwinding into C is undefined behavior
+    let outcome = catch_unwind(AssertUnwindSafe(|| f(handle))).unwrap_or_else(|panic| {
+        let message = panic
+            .downcast_ref::<&str>()
+            .map(ToString::to_string)
+            .or_else(|| panic.downcast_ref::<String>().cloned())
+            .unwrap_or_default();
+        Err(DebuggerError::Panicked(message))
+    });
+    match outcome {
+        Ok(value) => value,
+        Err(e) => {
+            handle.last_error = CString::new(e.to_string()).ok();
+            failed
+        }
+    }
+}
+
+/// Gets the string behind `ptr`, or [`DebuggerError::NullPointer`] with `what` if it is null
+///
+/// # Safety
+///
+/// `ptr` must be null or point to a nul terminated string.
+unsafe fn c_str<'a>(ptr: *const c_char, what: &'static str) -> Result<&'a CStr> {
+    if ptr.is_null() {
+        return Err(DebuggerError::NullPointer(what));
+    }
+    // SAFETY: the caller guarantees that the string is nul terminated
+    Ok(unsafe { CStr::from_ptr(ptr) })
+}
+
+/// Creates a new debugger
+///
+/// # Returns
+///
+/// A handle that must be destroyed with [`cm_debugger_free`], or null if the debugger could not
+/// be created.
+#[no_mangle]
+pub extern "C" fn cm_debugger_new() -> *mut CmDebugger {
+    let ui = FfiUi {
+        callback: None,
+        user_data: std::ptr::null_mut(),
+    };
+    match Debugger::build(ui) {
+        Ok(debugger) => Box::into_raw(Box::new(CmDebugger {
+            debugger,
+            last_error: None,
+        })),
+        Err(e) => {
+            warn!("could not create the debugger: {e}");
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// Destroys a debugger, killing or detaching from the debuggee like when quitting
+///
+/// # Safety
+///
+/// `handle` must be null or a valid handle from [`cm_debugger_new`], which is invalid afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn cm_debugger_free(handle: *mut CmDebugger) {
+    if handle.is_null() {
+        return;
+    }
+    // SAFETY: the caller guarantees that the handle is valid, and gives up ownership of it
+    let mut handle = unsafe { Box::from_raw(handle) };
+    if let Err(e) = handle.debugger.cleanup() {
+        warn!("could not clean up the debugger: {e}");
+    }
+}
+
+/// Gets the error of the last failed function
+///
+/// # Returns
+///
+/// A description of the error, or null if the last function did not fail. It is valid until the
+/// next function is called with the handle.
+///
+/// # Safety
+///
+/// `handle` must be null or a valid handle from [`cm_debugger_new`].
+#[no_mangle]
+pub unsafe extern "C" fn cm_last_error(handle: *const CmDebugger) -> *const c_char {
+    // SAFETY: the caller guarantees that the handle is null or valid
+    match unsafe { handle.as_ref() }.and_then(|h| h.last_error.as_ref()) {
+        Some(error) => error.as_ptr(),
+        None => std::ptr::null(),
+    }
+}
+
+/// Sets the function that is called whenever the debuggee stops or terminates
+///
+/// The callback is called before the function that resumed the debuggee returns. A null
+/// `callback` removes it.
+///
+/// # Safety
+///
+/// `handle` must be a valid handle from [`cm_debugger_new`]. `user_data` is passed to the
+/// callback as is and must be valid for as long as the callback is set.
+#[no_mangle]
+pub unsafe extern "C" fn cm_set_event_callback(
+    handle: *mut CmDebugger,
+    callback: CmEventCallback,
+    user_data: *mut c_void,
+) -> c_int {
+    call(handle, -1, |h| {
+        h.debugger.ui.callback = callback;
+        h.debugger.ui.user_data = user_data;
+        Ok(0)
+    })
+}
+
+/// Launches a program as the debuggee, see [`Debugger::run`]
+///
+/// # Parameters
+///
+/// * `path` - The program, which is searched in `PATH` if it is not a path
+/// * `argv` - The arguments for the program, without the program name, which is set to `path`
+/// * `argc` - The number of arguments in `argv`
+///
+/// # Safety
+///
+/// `handle` must be a valid handle from [`cm_debugger_new`] and `path` a nul terminated string.
+/// `argv` must point to `argc` nul terminated strings, or be null if `argc` is `0`.
+#[no_mangle]
+#[allow(clippy::similar_names)] // argc and argv are the usual names in C
+pub unsafe extern "C" fn cm_launch(
+    handle: *mut CmDebugger,
+    path: *const c_char,
+    argv: *const *const c_char,
+    argc: usize,
+) -> c_int {
+    call(handle, -1, |h| {
+        let path = PathBuf::from(OsStr::from_bytes(c_str(path, "path")?.to_bytes()));
+        let mut arguments = Vec::with_capacity(argc);
+        if argc > 0 {
+            if argv.is_null() {
+                return Err(DebuggerError::NullPointer("argv"));
+            }
+            for i in 0..argc {
+                // SAFETY: the caller guarantees that argv has argc elements
+                arguments.push(c_str(unsafe { *argv.add(i) }, "argument")?.to_owned());
+            }
+        }
+        h.execute(&Status::Run(path, arguments))?;
+        Ok(0)
+    })
+}
+
+/// Attaches to the running process `pid` as the debuggee, see [`Debugger::attach`]
+///
+/// The process is stopped when this returns.
+///
+/// # Safety
+///
+/// `handle` must be a valid handle from [`cm_debugger_new`].
+#[no_mangle]
+pub unsafe extern "C" fn cm_attach(handle: *mut CmDebugger, pid: c_int) -> c_int {
+    call(handle, -1, |h| h.execute(&Status::Attach(pid)).map(|_| 0))
+}
+
+/// Continues the debuggee until it stops again
+///
+/// # Safety
+///
+/// `handle` must be a valid handle from [`cm_debugger_new`].
+#[no_mangle]
+pub unsafe extern "C" fn cm_continue(handle: *mut CmDebugger) -> c_int {
+    call(handle, -1, |h| h.execute(&Status::Continue).map(|_| 0))
+}
+
+/// Executes a single instruction of the debuggee
+///
+/// # Safety
+///
+/// `handle` must be a valid handle from [`cm_debugger_new`].
+#[no_mangle]
+pub unsafe extern "C" fn cm_step(handle: *mut CmDebugger) -> c_int {
+    call(handle, -1, |h| h.execute(&Status::StepSingle).map(|_| 0))
+}
+
+/// Executes the next instruction of the debuggee, stepping over function calls
+///
+/// # Safety
+///
+/// `handle` must be a valid handle from [`cm_debugger_new`].
+#[no_mangle]
+pub unsafe extern "C" fn cm_step_over(handle: *mut CmDebugger) -> c_int {
+    call(handle, -1, |h| h.execute(&Status::StepOver).map(|_| 0))
+}
+
+/// Executes the debuggee until it reaches the next source line, stepping into function calls
+///
+/// # Safety
+///
+/// `handle` must be a valid handle from [`cm_debugger_new`].
+#[no_mangle]
+pub unsafe extern "C" fn cm_step_line(handle: *mut CmDebugger) -> c_int {
+    call(handle, -1, |h| h.execute(&Status::StepLine).map(|_| 0))
+}
+
+/// Executes the debuggee until it reaches the next source line, stepping over function calls
+///
+/// # Safety
+///
+/// `handle` must be a valid handle from [`cm_debugger_new`].
+#[no_mangle]
+pub unsafe extern "C" fn cm_next_line(handle: *mut CmDebugger) -> c_int {
+    call(handle, -1, |h| h.execute(&Status::NextLine).map(|_| 0))
+}
+
+/// Continues the debuggee until the current function returns
+///
+/// # Safety
+///
+/// `handle` must be a valid handle from [`cm_debugger_new`].
+#[no_mangle]
+pub unsafe extern "C" fn cm_step_out(handle: *mut CmDebugger) -> c_int {
+    call(handle, -1, |h| h.execute(&Status::StepOut).map(|_| 0))
+}
+
+/// Sets a breakpoint at `addr`, using a debug register if `hardware` is true
+///
+/// # Safety
+///
+/// `handle` must be a valid handle from [`cm_debugger_new`].
+#[no_mangle]
+pub unsafe extern "C" fn cm_set_breakpoint(
+    handle: *mut CmDebugger,
+    addr: u64,
+    hardware: bool,
+) -> c_int {
+    let addr = Addr::from(addr as usize).into();
+    let status = if hardware {
+        Status::SetHardwareBreakpoint(addr)
+    } else {
+        Status::SetBreakpoint(addr)
+    };
+    call(handle, -1, |h| h.execute(&status).map(|_| 0))
+}
+
+/// Deletes the breakpoint at `addr`
+///
+/// # Safety
+///
+/// `handle` must be a valid handle from [`cm_debugger_new`].
+#[no_mangle]
+pub unsafe extern "C" fn cm_delete_breakpoint(handle: *mut CmDebugger, addr: u64) -> c_int {
+    let status = Status::DelBreakpoint(Addr::from(addr as usize).into());
+    call(handle, -1, |h| h.execute(&status).map(|_| 0))
+}
+
+/// Reads up to `len` bytes of the memory of the debuggee at `addr` into `buf`
+///
+/// Software breakpoints are visible as `int3` instructions in the read memory.
+///
+/// # Returns
+///
+/// The number of bytes read, which can be less than `len` at the end of mapped memory, or `-1`.
+///
+/// # Safety
+///
+/// `handle` must be a valid handle from [`cm_debugger_new`] and `buf` must be valid for writing
+/// `len` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn cm_read_memory(
+    handle: *mut CmDebugger,
+    addr: u64,
+    buf: *mut u8,
+    len: usize,
+) -> isize {
+    call(handle, -1, |h| {
+        if buf.is_null() {
+            return Err(DebuggerError::NullPointer("buf"));
+        }
+        let pid = h
+            .debugger
+            .debuggee
+            .as_ref()
+            .ok_or(DebuggerError::NoDebugee)?
+            .pid;
+        // SAFETY: the caller guarantees that buf is valid for len bytes
+        let buf = unsafe { std::slice::from_raw_parts_mut(buf, len) };
+        Ok(mem_read(buf, pid, Addr::from(addr as usize))? as isize)
+    })
+}
+
+/// Writes `len` bytes from `buf` into the memory of the debuggee at `addr`
+///
+/// Writing over software breakpoints removes them from the code without deleting them.
+///
+/// # Returns
+///
+/// The number of bytes written, or `-1`.
+///
+/// # Safety
+///
+/// `handle` must be a valid handle from [`cm_debugger_new`] and `buf` must be valid for reading
+/// `len` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn cm_write_memory(
+    handle: *mut CmDebugger,
+    addr: u64,
+    buf: *const u8,
+    len: usize,
+) -> isize {
+    call(handle, -1, |h| {
+        if buf.is_null() {
+            return Err(DebuggerError::NullPointer("buf"));
+        }
+        let pid = h
+            .debugger
+            .debuggee
+            .as_ref()
+            .ok_or(DebuggerError::NoDebugee)?
+            .pid;
+        // SAFETY: the caller guarantees that buf is valid for len bytes
+        let buf = unsafe { std::slice::from_raw_parts(buf, len) };
+        Ok(mem_write(buf, pid, Addr::from(addr as usize))? as isize)
+    })
+}
+
+/// Reads the register `name` (like `"rip"`) of the debuggee into `value`
+///
+/// # Safety
+///
+/// `handle` must be a valid handle from [`cm_debugger_new`], `name` a nul terminated string and
+/// `value` must be valid for writing.
+#[no_mangle]
+pub unsafe extern "C" fn cm_get_register(
+    handle: *mut CmDebugger,
+    name: *const c_char,
+    value: *mut u64,
+) -> c_int {
+    call(handle, -1, |h| {
+        let register = Register::from_str(c_str(name, "name")?.to_str()?)?;
+        if value.is_null() {
+            return Err(DebuggerError::NullPointer("value"));
+        }
+        // SAFETY: the caller guarantees that value is valid
+        unsafe { *value = h.debugger.get_reg(register)? };
+        Ok(0)
+    })
+}
+
+/// Sets the register `name` (like `"rip"`) of the debuggee to `value`
+///
+/// # Safety
+///
+/// `handle` must be a valid handle from [`cm_debugger_new`] and `name` a nul terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn cm_set_register(
+    handle: *mut CmDebugger,
+    name: *const c_char,
+    value: u64,
+) -> c_int {
+    call(handle, -1, |h| {
+        let register = Register::from_str(c_str(name, "name")?.to_str()?)?;
+        h.execute(&Status::SetRegister(register, value)).map(|_| 0)
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    extern "C" fn count_events(event: *const CmEvent, user_data: *mut c_void) {
+        // SAFETY: the tests pass a pointer to a Vec as user data
+        let events = unsafe { &mut *user_data.cast::<Vec<CmEventKind>>() };
+        events.push(unsafe { (*event).kind });
+    }
+
+    #[test]
+    fn test_errors() {
+        let handle = cm_debugger_new();
+        assert!(!handle.is_null());
+
+        unsafe {
+            assert!(cm_last_error(handle).is_null());
+            assert_eq!(cm_continue(handle), -1);
+            let error = CStr::from_ptr(cm_last_error(handle));
+            assert_eq!(
+                error.to_str().unwrap(),
+                DebuggerError::NoDebugee.to_string()
+            );
+
+            let mut value = 0;
+            assert_eq!(cm_get_register(handle, c"nope".as_ptr(), &mut value), -1);
+            assert_eq!(cm_launch(handle, std::ptr::null(), std::ptr::null(), 0), -1);
+            assert!(CStr::from_ptr(cm_last_error(handle))
+                .to_str()
+                .unwrap()
+                .contains("path"));
+
+            // there is no process with the largest pid
+            assert_eq!(cm_attach(handle, c_int::MAX), -1);
+            assert!(!cm_last_error(handle).is_null());
+
+            assert_eq!(cm_continue(std::ptr::null_mut()), -1);
+            cm_debugger_free(handle);
+        }
+    }
+
+    #[test]
+    fn test_event() {
+        let description = c"breakpoint at 0x401136";
+        let event = CmEvent::new(
+            StopReason::Breakpoint {
+                addr: Addr::from(0x0040_1136_usize),
+                hardware: true,
+            },
+            description,
+        );
+        assert_eq!(event.kind, CmEventKind::Breakpoint);
+        assert_eq!(event.addr, 0x0040_1136);
+        assert!(event.hardware);
+
+        let event = CmEvent::new(
+            StopReason::Exited(ExitStatus::Signaled {
+                signal: 11,
+                core_dumped: false,
+            }),
+            description,
+        );
+        assert_eq!(event.kind, CmEventKind::Signaled);
+        assert_eq!(event.value, 11);
+
+        let mut events: Vec<CmEventKind> = Vec::new();
+        let handle = cm_debugger_new();
+        unsafe {
+            let user_data = std::ptr::from_mut(&mut events).cast();
+            assert_eq!(
+                cm_set_event_callback(handle, Some(count_events), user_data),
+                0
+            );
+            (*handle).debugger.ui.emit(StopReason::StepDone);
+            assert_eq!(cm_set_event_callback(handle, None, user_data), 0);
+            (*handle).debugger.ui.emit(StopReason::StepDone);
+            cm_debugger_free(handle);
+        }
+        assert_eq!(events, [CmEventKind::StepDone]);
+    }
+}
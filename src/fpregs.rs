@@ -0,0 +1,378 @@
+//! # FP Register Module
+//!
+//! Provides access to the SSE and AVX vector registers of a debugged process.
+//!
+//! Floating point values are kept in the vector registers `xmm0` to `xmm15` on `x86_64`, so
+//! compilers often put `float` and `double` variables there, especially in optimized code. The
+//! 128-bit `xmm` registers are read and written through the FXSAVE area of the debuggee with
+//! `PTRACE_GETREGSET` and `NT_PRFPREG`.
+//!
+//! With AVX, each `xmm` register is the lower half of a 256-bit `ymm` register. The upper halves
+//! are only in the XSAVE area, which is accessed with `NT_X86_XSTATE` instead. DWARF uses the same
+//! register numbers for `xmm` and `ymm` registers, the size of the value decides which one is
+//! meant.
+
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+use nix::libc;
+use nix::sys::ptrace::{self, regset::NT_PRFPREG};
+use nix::unistd::Pid;
+use serde::{Deserialize, Serialize};
+
+use crate::errors::{DebuggerError, Result};
+
+/// Number of bytes in an `xmm` register
+pub const XMM_BYTES: usize = 16;
+/// Number of bytes in a `ymm` register
+pub const YMM_BYTES: usize = 32;
+/// Number of bytes in an x87 register, which holds an extended precision number
+pub const X87_BYTES: usize = 10;
+/// Number of bytes in an `mm` register
+pub const MMX_BYTES: usize = 8;
+
+/// The regset of the XSAVE area, which is missing in [`nix::sys::ptrace::regset`]
+const NT_X86_XSTATE: libc::c_int = 0x202;
+/// Offset of `xmm0` in the FXSAVE and XSAVE areas
+const XMM_OFFSET: usize = 160;
+/// Offset of the bitmap of the saved state components in the XSAVE area
+const XSTATE_BV_OFFSET: usize = 512;
+/// Offset of the upper half of `ymm0` in the standard format of the XSAVE area
+const YMM_HI_OFFSET: usize = 576;
+/// The bit of the SSE state component in the bitmap of the XSAVE area
+const XSTATE_SSE: u64 = 1 << 1;
+/// The bit of the AVX state component in the bitmap of the XSAVE area
+const XSTATE_AVX: u64 = 1 << 2;
+/// Large enough for the XSAVE area of every current processor
+const XSTATE_MAX_BYTES: usize = 0x4000;
+
+/// The vector registers of `x86_64`
+///
+/// The registers are named by their `xmm` names, the `ymm` registers are the same registers
+/// with the upper halves included.
+///
+/// # Examples
+///
+/// ```
+/// use coreminer::fpregs::FpRegister;
+///
+/// let reg: FpRegister = "ymm3".parse().unwrap();
+/// assert_eq!(reg, FpRegister::xmm3);
+/// assert_eq!(FpRegister::try_from(gimli::Register(20)).unwrap(), reg);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[allow(non_camel_case_types)]
+#[allow(missing_docs)] // just register names, self explanatory
+pub enum FpRegister {
+    xmm0,
+    xmm1,
+    xmm2,
+    xmm3,
+    xmm4,
+    xmm5,
+    xmm6,
+    xmm7,
+    xmm8,
+    xmm9,
+    xmm10,
+    xmm11,
+    xmm12,
+    xmm13,
+    xmm14,
+    xmm15,
+}
+
+impl FpRegister {
+    /// All vector registers, ordered by their number
+    pub const ALL: [FpRegister; 16] = [
+        Self::xmm0,
+        Self::xmm1,
+        Self::xmm2,
+        Self::xmm3,
+        Self::xmm4,
+        Self::xmm5,
+        Self::xmm6,
+        Self::xmm7,
+        Self::xmm8,
+        Self::xmm9,
+        Self::xmm10,
+        Self::xmm11,
+        Self::xmm12,
+        Self::xmm13,
+        Self::xmm14,
+        Self::xmm15,
+    ];
+
+    /// Returns the number of the register, like 3 for `xmm3`
+    #[must_use]
+    pub fn index(self) -> usize {
+        self as usize
+    }
+}
+
+impl Display for FpRegister {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "xmm{}", self.index())
+    }
+}
+
+impl FromStr for FpRegister {
+    type Err = DebuggerError;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let s = s.to_lowercase();
+        s.strip_prefix("xmm")
+            .or_else(|| s.strip_prefix("ymm"))
+            .and_then(|n| n.parse::<usize>().ok())
+            .and_then(|n| Self::ALL.get(n).copied())
+            .ok_or(DebuggerError::ParseStr(s))
+    }
+}
+
+impl TryFrom<gimli::Register> for FpRegister {
+    type Error = DebuggerError;
+    /// Converts a DWARF register number to the corresponding vector register
+    ///
+    /// The vector registers have the numbers 17 to 32 in the amd64 ABI, see
+    /// <https://refspecs.linuxbase.org/elf/x86_64-abi-0.99.pdf#figure.3.36>
+    fn try_from(value: gimli::Register) -> Result<Self> {
+        match value.0 {
+            n @ 17..=32 => Ok(Self::ALL[n as usize - 17]),
+            x => Err(DebuggerError::UnimplementedRegister(x)),
+        }
+    }
+}
+
+/// Reads the `xmm` register `r` of the process `pid`
+///
+/// # Errors
+///
+/// This function will return an error if [`ptrace::getregset`] fails.
+pub fn get_xmm(pid: Pid, r: FpRegister) -> Result<[u8; XMM_BYTES]> {
+    let fpregs = ptrace::getregset::<NT_PRFPREG>(pid)?;
+    let words = &fpregs.xmm_space[r.index() * 4..r.index() * 4 + 4];
+
+    let mut value = [0; XMM_BYTES];
+    for (chunk, word) in value.chunks_exact_mut(4).zip(words) {
+        chunk.copy_from_slice(&word.to_le_bytes());
+    }
+    Ok(value)
+}
+
+/// Writes `value` to the lower bytes of the `xmm` register `r` of the process `pid`
+///
+/// The remaining bytes of the register keep their value, so that a `float` can be written
+/// without changing anything else.
+///
+/// # Errors
+///
+/// This function will return an error if `value` is larger than an `xmm` register, or
+/// [`ptrace::getregset`] or [`ptrace::setregset`] fails.
+pub fn set_xmm(pid: Pid, r: FpRegister, value: &[u8]) -> Result<()> {
+    if value.len() > XMM_BYTES {
+        return Err(DebuggerError::VectorValueTooLarge(value.len()));
+    }
+    let mut fpregs = ptrace::getregset::<NT_PRFPREG>(pid)?;
+    let words = &mut fpregs.xmm_space[r.index() * 4..r.index() * 4 + 4];
+
+    let mut bytes = [0; XMM_BYTES];
+    for (chunk, word) in bytes.chunks_exact_mut(4).zip(words.iter()) {
+        chunk.copy_from_slice(&word.to_le_bytes());
+    }
+    bytes[..value.len()].copy_from_slice(value);
+    for (word, chunk) in words.iter_mut().zip(bytes.chunks_exact(4)) {
+        *word = u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+    }
+
+    ptrace::setregset::<NT_PRFPREG>(pid, fpregs)?;
+    Ok(())
+}
+
+/// Reads the x87 register `st(index)` of the process `pid`
+///
+/// The `st` registers are numbered relative to the top of the x87 register stack, like in
+/// instructions and in DWARF.
+///
+/// # Errors
+///
+/// This function will return an error if `index` is not below 8 or [`ptrace::getregset`] fails.
+pub fn get_st(pid: Pid, index: usize) -> Result<[u8; X87_BYTES]> {
+    if index >= 8 {
+        return Err(DebuggerError::MissingRegister(format!("st{index}")));
+    }
+    let fpregs = ptrace::getregset::<NT_PRFPREG>(pid)?;
+    let mut value = [0; X87_BYTES];
+    value.copy_from_slice(&st_slot(&fpregs, index)[..X87_BYTES]);
+    Ok(value)
+}
+
+/// Reads the MMX register `mm{index}` of the process `pid`
+///
+/// The `mm` registers are the lower 64 bits of the physical x87 registers, which do not move with
+/// the top of the x87 register stack.
+///
+/// # Errors
+///
+/// This function will return an error if `index` is not below 8 or [`ptrace::getregset`] fails.
+pub fn get_mm(pid: Pid, index: usize) -> Result<[u8; MMX_BYTES]> {
+    if index >= 8 {
+        return Err(DebuggerError::MissingRegister(format!("mm{index}")));
+    }
+    let fpregs = ptrace::getregset::<NT_PRFPREG>(pid)?;
+    let top = usize::from(fpregs.swd >> 11) & 7;
+    let mut value = [0; MMX_BYTES];
+    value.copy_from_slice(&st_slot(&fpregs, (index + 8 - top) % 8)[..MMX_BYTES]);
+    Ok(value)
+}
+
+/// The floating point control and status registers of `x86_64`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[allow(non_camel_case_types)]
+#[allow(missing_docs)] // just register names, self explanatory
+pub enum FpControlRegister {
+    fcw,
+    fsw,
+    mxcsr,
+}
+
+/// Reads the floating point control or status register `r` of the process `pid`
+///
+/// # Errors
+///
+/// This function will return an error if [`ptrace::getregset`] fails.
+pub fn get_fp_control(pid: Pid, r: FpControlRegister) -> Result<u32> {
+    let fpregs = ptrace::getregset::<NT_PRFPREG>(pid)?;
+    Ok(match r {
+        FpControlRegister::fcw => u32::from(fpregs.cwd),
+        FpControlRegister::fsw => u32::from(fpregs.swd),
+        FpControlRegister::mxcsr => fpregs.mxcsr,
+    })
+}
+
+/// Gets the bytes of `st(index)` from the FXSAVE area, each register has a 16 byte slot
+fn st_slot(fpregs: &libc::user_fpregs_struct, index: usize) -> [u8; XMM_BYTES] {
+    let mut slot = [0; XMM_BYTES];
+    for (chunk, word) in slot
+        .chunks_exact_mut(4)
+        .zip(&fpregs.st_space[index * 4..index * 4 + 4])
+    {
+        chunk.copy_from_slice(&word.to_le_bytes());
+    }
+    slot
+}
+
+/// Reads the `ymm` register `r` of the process `pid`
+///
+/// # Errors
+///
+/// This function will return an error if the XSAVE area cannot be read, or the processor does
+/// not support AVX.
+pub fn get_ymm(pid: Pid, r: FpRegister) -> Result<[u8; YMM_BYTES]> {
+    let xstate = get_xstate(pid)?;
+
+    // state components in their initial state, which is zero, are not saved
+    let bv = xstate_bv(&xstate);
+    let mut value = [0; YMM_BYTES];
+    if bv & XSTATE_SSE != 0 {
+        value[..XMM_BYTES].copy_from_slice(&xstate[xmm_range(r)]);
+    }
+    if bv & XSTATE_AVX != 0 {
+        value[XMM_BYTES..].copy_from_slice(&xstate[ymm_hi_range(r)]);
+    }
+    Ok(value)
+}
+
+/// Writes `value` to the lower bytes of the `ymm` register `r` of the process `pid`
+///
+/// Like with [`set_xmm`], the remaining bytes of the register keep their value.
+///
+/// # Errors
+///
+/// This function will return an error if `value` is larger than a `ymm` register, the XSAVE area
+/// cannot be read or written, or the processor does not support AVX.
+pub fn set_ymm(pid: Pid, r: FpRegister, value: &[u8]) -> Result<()> {
+    if value.len() > YMM_BYTES {
+        return Err(DebuggerError::VectorValueTooLarge(value.len()));
+    }
+    let mut bytes = get_ymm(pid, r)?;
+    bytes[..value.len()].copy_from_slice(value);
+
+    let mut xstate = get_xstate(pid)?;
+    xstate[xmm_range(r)].copy_from_slice(&bytes[..XMM_BYTES]);
+    xstate[ymm_hi_range(r)].copy_from_slice(&bytes[XMM_BYTES..]);
+    let bv = xstate_bv(&xstate) | XSTATE_SSE | XSTATE_AVX;
+    xstate[XSTATE_BV_OFFSET..XSTATE_BV_OFFSET + 8].copy_from_slice(&bv.to_le_bytes());
+
+    xstate_regset(pid, ptrace::Request::PTRACE_SETREGSET, &mut xstate)?;
+    Ok(())
+}
+
+/// Reads the XSAVE area of the process `pid` in its standard format
+fn get_xstate(pid: Pid) -> Result<Vec<u8>> {
+    let mut xstate = vec![0; XSTATE_MAX_BYTES];
+    let len = xstate_regset(pid, ptrace::Request::PTRACE_GETREGSET, &mut xstate)?;
+    if len < YMM_HI_OFFSET + FpRegister::ALL.len() * XMM_BYTES {
+        return Err(DebuggerError::AvxUnsupported);
+    }
+    xstate.truncate(len);
+    Ok(xstate)
+}
+
+/// Gets or sets the XSAVE area with `request`, returning the number of bytes that were
+/// transferred
+fn xstate_regset(pid: Pid, request: ptrace::Request, buf: &mut [u8]) -> Result<usize> {
+    let mut iov = libc::iovec {
+        iov_base: buf.as_mut_ptr().cast(),
+        iov_len: buf.len(),
+    };
+    // SAFETY: the kernel reads or writes at most iov_len bytes of buf, which outlives the call
+    let res = unsafe {
+        libc::ptrace(
+            request as libc::c_uint,
+            libc::pid_t::from(pid),
+            NT_X86_XSTATE,
+            &raw mut iov,
+        )
+    };
+    nix::errno::Errno::result(res)?;
+    Ok(iov.iov_len)
+}
+
+/// Gets the bitmap of the state components that are saved in the XSAVE area
+fn xstate_bv(xstate: &[u8]) -> u64 {
+    let mut bv = [0; 8];
+    bv.copy_from_slice(&xstate[XSTATE_BV_OFFSET..XSTATE_BV_OFFSET + 8]);
+    u64::from_le_bytes(bv)
+}
+
+/// Where the `xmm` register `r` is in the FXSAVE and XSAVE areas
+fn xmm_range(r: FpRegister) -> std::ops::Range<usize> {
+    let start = XMM_OFFSET + r.index() * XMM_BYTES;
+    start..start + XMM_BYTES
+}
+
+/// Where the upper half of the `ymm` register `r` is in the XSAVE area
+fn ymm_hi_range(r: FpRegister) -> std::ops::Range<usize> {
+    let start = YMM_HI_OFFSET + r.index() * XMM_BYTES;
+    start..start + XMM_BYTES
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_fp_register_numbers() {
+        assert_eq!("XMM15".parse::<FpRegister>().unwrap(), FpRegister::xmm15);
+        assert!("xmm16".parse::<FpRegister>().is_err());
+        assert!("rax".parse::<FpRegister>().is_err());
+
+        assert_eq!(
+            FpRegister::try_from(gimli::Register(17)).unwrap(),
+            FpRegister::xmm0
+        );
+        assert!(FpRegister::try_from(gimli::Register(16)).is_err());
+        assert!(FpRegister::try_from(gimli::Register(33)).is_err());
+        assert_eq!(ymm_hi_range(FpRegister::xmm1), 592..608);
+    }
+}
@@ -0,0 +1,512 @@
+//! # GDB Remote Module
+//!
+//! Provides a client for the GDB Remote Serial Protocol, which is spoken by debug servers like
+//! `gdbserver` and the replay server of [rr](crate::rr).
+//!
+//! The protocol exchanges packets of the form `$data#checksum` over a TCP connection, each of
+//! which is acknowledged with `+`. The client sends a request and the server answers with one
+//! reply, for example `g` to read the registers or `c` to continue, which is answered with a
+//! stop reply once the program stops again. rr additionally supports running backwards with `bc`
+//! and `bs`.
+//!
+//! Only the requests that are needed to control an x86-64 program are implemented, see
+//! [`GdbRemote`].
+
+use std::fmt::Display;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+
+use nix::libc::user_regs_struct;
+use nix::sys::signal::Signal;
+use serde::{Deserialize, Serialize};
+use tracing::trace;
+
+use crate::errors::{DebuggerError, Result};
+use crate::Addr;
+
+/// Maximum number of bytes that are requested with one `m` packet
+///
+/// Servers limit the size of their packets, and each byte takes two hex digits in the reply.
+pub const MAX_READ_SIZE: usize = 0x800;
+
+/// Number of the `rip` register in the stop replies of x86-64 targets
+const RIP_REGNUM: u8 = 0x10;
+
+/// Why the program of a remote server stopped, see [`GdbRemote::resume`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum StopReply {
+    /// The program stopped because of the signal, with the program counter if the server told it
+    ///
+    /// Breakpoints and finished steps are reported as `SIGTRAP`.
+    Signal {
+        /// Number of the signal
+        signal: i32,
+        /// Address of the next instruction
+        pc: Option<Addr>,
+    },
+    /// The program exited with the exit code
+    Exited(i32),
+    /// The program was terminated by the signal
+    Terminated(i32),
+}
+
+impl StopReply {
+    /// Parses the stop reply packet `reply`, like `T05thread:p1.1;` or `W00`
+    ///
+    /// # Errors
+    ///
+    /// This function fails with [`DebuggerError::InvalidRemoteReply`] if `reply` is not a stop
+    /// reply.
+    pub fn parse(reply: &str) -> Result<Self> {
+        let invalid = || DebuggerError::InvalidRemoteReply(reply.to_string());
+        let kind = reply.chars().next().ok_or_else(invalid)?;
+        let number = reply
+            .get(1..3)
+            .and_then(|n| i32::from_str_radix(n, 16).ok())
+            .ok_or_else(invalid)?;
+        match kind {
+            'S' => Ok(Self::Signal {
+                signal: number,
+                pc: None,
+            }),
+            'T' => {
+                let pc = reply[3..]
+                    .split(';')
+                    .filter_map(|pair| pair.split_once(':'))
+                    .find(|(reg, _)| u8::from_str_radix(reg, 16) == Ok(RIP_REGNUM))
+                    .map(|(_, value)| decode_hex(value))
+                    .transpose()?
+                    .and_then(|bytes| Some(u64::from_le_bytes(bytes.try_into().ok()?)))
+                    .map(Addr::from);
+                Ok(Self::Signal { signal: number, pc })
+            }
+            'W' => Ok(Self::Exited(number)),
+            'X' => Ok(Self::Terminated(number)),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+impl Display for StopReply {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Signal { signal, pc } => {
+                match Signal::try_from(*signal) {
+                    Ok(sig) => write!(f, "stopped by {sig}")?,
+                    Err(_) => write!(f, "stopped by signal {signal}")?,
+                }
+                if let Some(pc) = pc {
+                    write!(f, " at {pc}")?;
+                }
+                Ok(())
+            }
+            Self::Exited(code) => write!(f, "exited with code {code}"),
+            Self::Terminated(signal) => match Signal::try_from(*signal) {
+                Ok(sig) => write!(f, "was terminated by {sig}"),
+                Err(_) => write!(f, "was terminated by signal {signal}"),
+            },
+        }
+    }
+}
+
+/// How to resume the program of a remote server, see [`GdbRemote::resume`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Resume {
+    /// Continue until a breakpoint, a signal or the exit (`c`)
+    Continue,
+    /// Execute one instruction (`s`)
+    Step,
+    /// Run backwards until a breakpoint or the start of the recording (`bc`)
+    ReverseContinue,
+    /// Go back one instruction (`bs`)
+    ReverseStep,
+}
+
+impl Resume {
+    /// The request packet for resuming like this
+    fn request(self) -> &'static str {
+        match self {
+            Self::Continue => "c",
+            Self::Step => "s",
+            Self::ReverseContinue => "bc",
+            Self::ReverseStep => "bs",
+        }
+    }
+}
+
+/// A connection to a server of the GDB Remote Serial Protocol
+///
+/// # Examples
+///
+/// ```no_run
+/// use coreminer::gdbremote::{GdbRemote, Resume};
+///
+/// let mut remote = GdbRemote::connect("127.0.0.1:1234").unwrap();
+/// println!("the program {}", remote.stop_reason().unwrap());
+///
+/// let stop = remote.resume(Resume::Step).unwrap();
+/// let regs = remote.read_registers().unwrap();
+/// println!("{stop}, rip is {:#x}", regs.rip);
+/// ```
+pub struct GdbRemote {
+    reader: BufReader<TcpStream>,
+    writer: TcpStream,
+}
+
+impl GdbRemote {
+    /// Connects to the server listening at `addr`
+    ///
+    /// # Errors
+    ///
+    /// This function fails if the connection cannot be established.
+    pub fn connect(addr: impl ToSocketAddrs) -> Result<Self> {
+        Self::from_stream(TcpStream::connect(addr)?)
+    }
+
+    /// Uses an established connection to a server
+    ///
+    /// # Errors
+    ///
+    /// This function fails if the connection cannot be cloned for reading and writing.
+    pub fn from_stream(stream: TcpStream) -> Result<Self> {
+        stream.set_nodelay(true)?;
+        Ok(Self {
+            reader: BufReader::new(stream.try_clone()?),
+            writer: stream,
+        })
+    }
+
+    /// Sends the request packet `request` and returns the reply of the server
+    ///
+    /// # Errors
+    ///
+    /// This function fails if the connection breaks, with [`DebuggerError::RemoteRejected`] if
+    /// the server replies with an error (`Exx`) or does not support the request (empty reply),
+    /// and with [`DebuggerError::InvalidRemoteReply`] if the reply is malformed.
+    pub fn request(&mut self, request: &str) -> Result<String> {
+        self.send(request)?;
+        let reply = self.receive()?;
+        let is_error = reply.len() == 3
+            && reply.starts_with('E')
+            && reply[1..].chars().all(|c| c.is_ascii_hexdigit());
+        if reply.is_empty() || is_error {
+            return Err(DebuggerError::RemoteRejected(request.to_string(), reply));
+        }
+        Ok(reply)
+    }
+
+    /// Asks the server why the program is stopped (`?`)
+    ///
+    /// # Errors
+    ///
+    /// This function fails like [`Self::request`].
+    pub fn stop_reason(&mut self) -> Result<StopReply> {
+        StopReply::parse(&self.request("?")?)
+    }
+
+    /// Resumes the program and waits until it stops again
+    ///
+    /// # Errors
+    ///
+    /// This function fails like [`Self::request`], for example if the server cannot run
+    /// backwards.
+    pub fn resume(&mut self, how: Resume) -> Result<StopReply> {
+        StopReply::parse(&self.request(how.request())?)
+    }
+
+    /// Reads the general purpose registers of the program (`g`)
+    ///
+    /// Registers that are not part of the reply, like `orig_rax`, are zero.
+    ///
+    /// # Errors
+    ///
+    /// This function fails like [`Self::request`], or with [`DebuggerError::InvalidRemoteReply`]
+    /// if the reply is too short for the x86-64 registers.
+    pub fn read_registers(&mut self) -> Result<user_regs_struct> {
+        let reply = self.request("g")?;
+        parse_registers(&decode_hex(&reply)?).ok_or(DebuggerError::InvalidRemoteReply(reply))
+    }
+
+    /// Reads `len` bytes of the memory of the program at `addr` (`m`)
+    ///
+    /// # Errors
+    ///
+    /// This function fails like [`Self::request`], for example if the memory is not mapped.
+    pub fn read_memory(&mut self, addr: Addr, len: usize) -> Result<Vec<u8>> {
+        let mut bytes = Vec::with_capacity(len);
+        while bytes.len() < len {
+            let size = (len - bytes.len()).min(MAX_READ_SIZE);
+            let reply = self.request(&format!("m{:x},{size:x}", addr.usize() + bytes.len()))?;
+            let part = decode_hex(&reply)?;
+            if part.is_empty() {
+                return Err(DebuggerError::InvalidRemoteReply(reply));
+            }
+            bytes.extend(part);
+        }
+        Ok(bytes)
+    }
+
+    /// Sets a software breakpoint at `addr` (`Z0`)
+    ///
+    /// # Errors
+    ///
+    /// This function fails like [`Self::request`].
+    pub fn set_breakpoint(&mut self, addr: Addr) -> Result<()> {
+        self.request_ok(&format!("Z0,{:x},1", addr.usize()))
+    }
+
+    /// Removes the software breakpoint at `addr` (`z0`)
+    ///
+    /// # Errors
+    ///
+    /// This function fails like [`Self::request`].
+    pub fn remove_breakpoint(&mut self, addr: Addr) -> Result<()> {
+        self.request_ok(&format!("z0,{:x},1", addr.usize()))
+    }
+
+    /// Detaches from the program, which ends the session with the server (`D`)
+    ///
+    /// # Errors
+    ///
+    /// This function fails like [`Self::request`].
+    pub fn detach(&mut self) -> Result<()> {
+        self.request_ok("D")
+    }
+
+    /// Sends `request` and makes sure that the server replies with `OK`
+    fn request_ok(&mut self, request: &str) -> Result<()> {
+        let reply = self.request(request)?;
+        if reply != "OK" {
+            return Err(DebuggerError::InvalidRemoteReply(reply));
+        }
+        Ok(())
+    }
+
+    /// Sends a packet and waits until the server acknowledged it, sending it again if the server
+    /// asks for that
+    fn send(&mut self, data: &str) -> Result<()> {
+        trace!("gdb remote <- {data}");
+        let packet = format!("${data}#{:02x}", checksum(data.as_bytes()));
+        loop {
+            self.writer.write_all(packet.as_bytes())?;
+            let mut ack = [0];
+            self.reader.read_exact(&mut ack)?;
+            match ack[0] {
+                b'+' => return Ok(()),
+                b'-' => trace!("gdb remote asked to resend the packet"),
+                other => {
+                    return Err(DebuggerError::InvalidRemoteReply(
+                        char::from(other).to_string(),
+                    ))
+                }
+            }
+        }
+    }
+
+    /// Receives the next packet and acknowledges it, asking the server to send it again if the
+    /// checksum is wrong
+    fn receive(&mut self) -> Result<String> {
+        loop {
+            let mut garbage = Vec::new();
+            self.reader.read_until(b'$', &mut garbage)?;
+            if garbage.last() != Some(&b'$') {
+                return Err(std::io::Error::from(std::io::ErrorKind::UnexpectedEof).into());
+            }
+            let mut raw = Vec::new();
+            self.reader.read_until(b'#', &mut raw)?;
+            if raw.pop() != Some(b'#') {
+                return Err(std::io::Error::from(std::io::ErrorKind::UnexpectedEof).into());
+            }
+            let mut sum = [0; 2];
+            self.reader.read_exact(&mut sum)?;
+
+            let expected = std::str::from_utf8(&sum)
+                .ok()
+                .and_then(|s| u8::from_str_radix(s, 16).ok());
+            if expected != Some(checksum(&raw)) {
+                trace!("gdb remote sent a packet with a wrong checksum");
+                self.writer.write_all(b"-")?;
+                continue;
+            }
+            self.writer.write_all(b"+")?;
+
+            let data = String::from_utf8(unescape(&raw))
+                .map_err(|e| DebuggerError::InvalidRemoteReply(e.to_string()))?;
+            trace!("gdb remote -> {data}");
+            return Ok(data);
+        }
+    }
+}
+
+/// The checksum of a packet, which is the sum of its bytes modulo 256
+fn checksum(data: &[u8]) -> u8 {
+    data.iter().fold(0, |sum, b| sum.wrapping_add(*b))
+}
+
+/// Undoes the escaping (`}`) and run-length encoding (`*`) of the data of a packet
+fn unescape(raw: &[u8]) -> Vec<u8> {
+    let mut data = Vec::with_capacity(raw.len());
+    let mut bytes = raw.iter();
+    while let Some(&byte) = bytes.next() {
+        match byte {
+            b'}' => data.extend(bytes.next().map(|b| b ^ 0x20)),
+            b'*' => {
+                // the count is printable, starting at 29 for no repetitions
+                let count = bytes.next().map_or(0, |n| n.saturating_sub(29));
+                let last = data.last().copied().unwrap_or_default();
+                data.extend(std::iter::repeat(last).take(count.into()));
+            }
+            _ => data.push(byte),
+        }
+    }
+    data
+}
+
+/// Decodes pairs of hex digits into bytes
+fn decode_hex(hex: &str) -> Result<Vec<u8>> {
+    let invalid = || DebuggerError::InvalidRemoteReply(hex.to_string());
+    if hex.len() % 2 != 0 {
+        return Err(invalid());
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).map_err(|_| invalid()))
+        .collect()
+}
+
+/// Reads the registers of the `g` reply of an x86-64 target
+///
+/// The reply starts with `rax`, `rbx`, `rcx`, `rdx`, `rsi`, `rdi`, `rbp`, `rsp`, `r8` to `r15`
+/// and `rip` with 8 bytes each, followed by `eflags`, `cs`, `ss`, `ds`, `es`, `fs` and `gs` with
+/// 4 bytes each. Registers that are not available are sent as `x`, which is read as zero.
+fn parse_registers(bytes: &[u8]) -> Option<user_regs_struct> {
+    let word = |i: usize| {
+        Some(u64::from_le_bytes(
+            bytes.get(i * 8..i * 8 + 8)?.try_into().ok()?,
+        ))
+    };
+    let segment = |i: usize| {
+        let at = 17 * 8 + i * 4;
+        Some(u32::from_le_bytes(bytes.get(at..at + 4)?.try_into().ok()?).into())
+    };
+    // SAFETY: user_regs_struct only contains integers
+    let mut regs: user_regs_struct = unsafe { std::mem::zeroed() };
+    regs.rax = word(0)?;
+    regs.rbx = word(1)?;
+    regs.rcx = word(2)?;
+    regs.rdx = word(3)?;
+    regs.rsi = word(4)?;
+    regs.rdi = word(5)?;
+    regs.rbp = word(6)?;
+    regs.rsp = word(7)?;
+    regs.r8 = word(8)?;
+    regs.r9 = word(9)?;
+    regs.r10 = word(10)?;
+    regs.r11 = word(11)?;
+    regs.r12 = word(12)?;
+    regs.r13 = word(13)?;
+    regs.r14 = word(14)?;
+    regs.r15 = word(15)?;
+    regs.rip = word(16)?;
+    regs.eflags = segment(0)?;
+    regs.cs = segment(1)?;
+    regs.ss = segment(2)?;
+    regs.ds = segment(3)?;
+    regs.es = segment(4)?;
+    regs.fs = segment(5)?;
+    regs.gs = segment(6)?;
+    Some(regs)
+}
+
+#[cfg(test)]
+mod test {
+    use std::net::TcpListener;
+    use std::thread;
+
+    use super::*;
+
+    /// Answers the requests of one client with `replies`, returning the requests it got
+    fn serve(replies: Vec<String>) -> (u16, thread::JoinHandle<Vec<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut server = GdbRemote::from_stream(stream).unwrap();
+            let mut requests = Vec::new();
+            for reply in replies {
+                requests.push(server.receive().unwrap());
+                server.send(&reply).unwrap();
+            }
+            requests
+        });
+        (port, server)
+    }
+
+    #[test]
+    fn test_packets() {
+        assert_eq!(checksum(b"OK"), 0x9a);
+        assert_eq!(unescape(b"0* }]"), b"0000}");
+        assert_eq!(decode_hex("00ff10").unwrap(), [0, 0xff, 0x10]);
+        assert!(decode_hex("0").is_err());
+    }
+
+    #[test]
+    fn test_stop_reply() {
+        assert_eq!(
+            StopReply::parse("T0510:3412000000000000;thread:p1.1;").unwrap(),
+            StopReply::Signal {
+                signal: 5,
+                pc: Some(Addr::from(0x1234usize))
+            }
+        );
+        assert_eq!(
+            StopReply::parse("S0b").unwrap(),
+            StopReply::Signal {
+                signal: 11,
+                pc: None
+            }
+        );
+        assert_eq!(StopReply::parse("W2a").unwrap(), StopReply::Exited(42));
+        assert_eq!(StopReply::parse("X09").unwrap(), StopReply::Terminated(9));
+        assert!(StopReply::parse("OK").is_err());
+        assert_eq!(
+            StopReply::parse("T0510:3412000000000000;")
+                .unwrap()
+                .to_string(),
+            "stopped by SIGTRAP at 0x0000000000001234"
+        );
+    }
+
+    #[test]
+    fn test_requests() {
+        let mut regs = "00".repeat(17 * 8 + 7 * 4);
+        regs.replace_range(16 * 16..16 * 16 + 4, "3412");
+        let replies = ["T0510:3412000000000000;", &regs, "c3cc", "OK", "E01", ""];
+        let (port, server) = serve(replies.map(str::to_string).to_vec());
+
+        let mut remote = GdbRemote::connect(("127.0.0.1", port)).unwrap();
+        assert_eq!(
+            remote.resume(Resume::ReverseStep).unwrap(),
+            StopReply::Signal {
+                signal: 5,
+                pc: Some(Addr::from(0x1234usize))
+            }
+        );
+        assert_eq!(remote.read_registers().unwrap().rip, 0x1234);
+        assert_eq!(
+            remote.read_memory(Addr::from(0x1000usize), 2).unwrap(),
+            [0xc3, 0xcc]
+        );
+        remote.set_breakpoint(Addr::from(0x1000usize)).unwrap();
+        assert!(matches!(
+            remote.remove_breakpoint(Addr::from(0x1000usize)),
+            Err(DebuggerError::RemoteRejected(_, reply)) if reply == "E01"
+        ));
+        assert!(remote.detach().is_err());
+
+        assert_eq!(
+            server.join().unwrap(),
+            ["bs", "g", "m1000,2", "Z0,1000,1", "z0,1000,1", "D"]
+        );
+    }
+}
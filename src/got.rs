@@ -0,0 +1,239 @@
+//! # GOT Module
+//!
+//! Provides inspection and hooking of the Global Offset Table (GOT) of a debugged process.
+//!
+//! Dynamically linked executables do not call functions from shared libraries directly. Instead,
+//! the dynamic loader writes the address of each imported symbol into a slot of the GOT, and the
+//! code of the executable (usually through a stub in the PLT) jumps to whatever address is stored
+//! in that slot. Which slot belongs to which symbol is described by the dynamic relocations of
+//! the executable.
+//!
+//! By reading a slot, the debugger can see where an imported function was resolved to. By
+//! overwriting a slot, every call of the executable to that function can be redirected to an
+//! arbitrary address, which is a common technique for hooking functions at runtime.
+//!
+//! Slots of the type [`GotSlotKind::JumpSlot`] may be resolved lazily. Until the function is
+//! called for the first time, such a slot points back into the PLT of the executable.
+
+use std::fmt::{self, Display};
+
+use nix::unistd::Pid;
+use object::{Object, ObjectSymbol, ObjectSymbolTable, RelocationFlags, RelocationTarget};
+use serde::Serialize;
+
+use crate::errors::Result;
+use crate::memorymap::ProcessMemoryMap;
+use crate::{mem_read_word, mem_write_word, Addr};
+
+/// The kind of a GOT slot, derived from the type of its relocation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+pub enum GotSlotKind {
+    /// `R_X86_64_JUMP_SLOT`: a slot used by a PLT stub to call a function
+    JumpSlot,
+    /// `R_X86_64_GLOB_DAT`: a slot holding the address of a function or variable
+    GlobDat,
+}
+
+impl GotSlotKind {
+    /// Gets the [`GotSlotKind`] of an ELF relocation type, if it describes a GOT slot
+    #[must_use]
+    pub fn from_r_type(r_type: u32) -> Option<Self> {
+        match r_type {
+            object::elf::R_X86_64_JUMP_SLOT => Some(Self::JumpSlot),
+            object::elf::R_X86_64_GLOB_DAT => Some(Self::GlobDat),
+            _ => None,
+        }
+    }
+}
+
+/// A GOT slot as described by the dynamic relocations of an executable
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+pub struct GotRelocation {
+    /// Offset of the slot, relative to the load bias of the executable
+    pub offset: u64,
+    /// Name of the symbol the slot is resolved to
+    pub symbol: String,
+    /// Kind of the slot
+    pub kind: GotSlotKind,
+}
+
+/// A GOT slot of a running process
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+pub struct GotEntry {
+    /// Name of the symbol the slot is resolved to
+    pub symbol: String,
+    /// Kind of the slot
+    pub kind: GotSlotKind,
+    /// Address of the slot in the memory of the process
+    pub slot: Addr,
+    /// Address currently stored in the slot
+    pub target: Addr,
+    /// Path of the mapping that contains the target, if the target is mapped from a file
+    pub target_mapping: Option<String>,
+}
+
+/// Lists the GOT slots of an executable
+///
+/// Only relocations of the types `R_X86_64_JUMP_SLOT` and `R_X86_64_GLOB_DAT` that refer to a
+/// named symbol are included. Statically linked executables have no dynamic relocations, so the
+/// returned list is empty for them.
+///
+/// # Examples
+///
+/// ```no_run
+/// use coreminer::got::got_relocations;
+///
+/// let raw = std::fs::read("/bin/ls").unwrap();
+/// let obj = object::File::parse(raw.as_slice()).unwrap();
+/// for reloc in got_relocations(&obj) {
+///     println!("{:#x}: {}", reloc.offset, reloc.symbol);
+/// }
+/// ```
+#[must_use]
+pub fn got_relocations(obj: &object::File<'_>) -> Vec<GotRelocation> {
+    let (Some(relocations), Some(symbols)) =
+        (obj.dynamic_relocations(), obj.dynamic_symbol_table())
+    else {
+        return Vec::new();
+    };
+
+    let mut slots = Vec::new();
+    for (offset, reloc) in relocations {
+        let RelocationFlags::Elf { r_type } = reloc.flags() else {
+            continue;
+        };
+        let Some(kind) = GotSlotKind::from_r_type(r_type) else {
+            continue;
+        };
+        let RelocationTarget::Symbol(index) = reloc.target() else {
+            continue;
+        };
+        let Some(symbol) = symbols
+            .symbol_by_index(index)
+            .ok()
+            .and_then(|s| s.name().ok().map(str::to_string))
+            .filter(|name| !name.is_empty())
+        else {
+            continue;
+        };
+
+        slots.push(GotRelocation {
+            offset,
+            symbol,
+            kind,
+        });
+    }
+
+    slots
+}
+
+impl GotRelocation {
+    /// Reads the current contents of the slot from the memory of a process
+    ///
+    /// # Parameters
+    ///
+    /// * `pid` - The process ID of the debuggee
+    /// * `load_bias` - Difference between the addresses at which the executable was loaded and
+    ///   the addresses in the ELF file (0 for executables that are not position independent)
+    /// * `map` - The memory map of the process, used to find out where the target lies
+    ///
+    /// # Errors
+    ///
+    /// This function will fail if the slot cannot be read.
+    pub fn read(&self, pid: Pid, load_bias: usize, map: &ProcessMemoryMap) -> Result<GotEntry> {
+        let slot = self.slot_addr(load_bias);
+        let target = Addr::from(mem_read_word(pid, slot)?);
+        let target_mapping = map
+            .regions
+            .iter()
+            .find(|r| r.start_address <= target && target < r.end_address)
+            .and_then(|r| r.path.clone());
+
+        Ok(GotEntry {
+            symbol: self.symbol.clone(),
+            kind: self.kind,
+            slot,
+            target,
+            target_mapping,
+        })
+    }
+
+    /// Overwrites the slot in the memory of a process so that it points to `target`
+    ///
+    /// # Parameters
+    ///
+    /// * `pid` - The process ID of the debuggee
+    /// * `load_bias` - Difference between the addresses at which the executable was loaded and
+    ///   the addresses in the ELF file
+    /// * `target` - The new address stored in the slot
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Addr)` - The address that was stored in the slot before, useful to undo the hook
+    /// * `Err(DebuggerError)` - If the slot could not be read or written
+    ///
+    /// # Errors
+    ///
+    /// This function will fail if the slot cannot be read or written.
+    pub fn hook(&self, pid: Pid, load_bias: usize, target: Addr) -> Result<Addr> {
+        let slot = self.slot_addr(load_bias);
+        let old = Addr::from(mem_read_word(pid, slot)?);
+        // ptrace writes ignore the page protection, so this works with full RELRO too
+        mem_write_word(pid, slot, target.usize())?;
+        Ok(old)
+    }
+
+    /// Returns the address of the slot in the memory of the process
+    #[inline]
+    #[must_use]
+    pub fn slot_addr(&self, load_bias: usize) -> Addr {
+        Addr::from(self.offset) + load_bias
+    }
+}
+
+impl Display for GotEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kind = match self.kind {
+            GotSlotKind::JumpSlot => "JUMP_SLOT",
+            GotSlotKind::GlobDat => "GLOB_DAT",
+        };
+        write!(
+            f,
+            "{}  {:<9}  {} -> {}",
+            self.slot, kind, self.symbol, self.target
+        )?;
+        if let Some(path) = &self.target_mapping {
+            write!(f, " ({path})")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_got_relocations_of_self() {
+        // the test binary is dynamically linked against libc, so it must import something
+        let raw = std::fs::read("/proc/self/exe").expect("could not read the test binary");
+        let obj = object::File::parse(raw.as_slice()).expect("could not parse the test binary");
+
+        let slots = got_relocations(&obj);
+        assert!(!slots.is_empty());
+        assert!(slots.iter().all(|s| !s.symbol.is_empty()));
+        assert_eq!(
+            slots[0].slot_addr(0x1000),
+            Addr::from(slots[0].offset) + 0x1000
+        );
+
+        assert_eq!(
+            GotSlotKind::from_r_type(object::elf::R_X86_64_JUMP_SLOT),
+            Some(GotSlotKind::JumpSlot)
+        );
+        assert_eq!(
+            GotSlotKind::from_r_type(object::elf::R_X86_64_RELATIVE),
+            None
+        );
+    }
+}
@@ -0,0 +1,342 @@
+//! # Heuristic Symbols Module
+//!
+//! Provides symbols for executables that have neither debug information nor a symbol table.
+//!
+//! Fully stripped executables still contain enough to name the most important places in them:
+//!
+//! - The entry point from the auxiliary vector is `_start`.
+//! - The code at the entry point of a C runtime passes `main` as first argument to
+//!   `__libc_start_main`, so the address loaded into `rdi` before the first call is `main`.
+//! - The stubs in the PLT jump through GOT slots, and the dynamic relocations, which are never
+//!   stripped, say which function each slot is for. A stub is named like `puts@plt`.
+//! - Shared libraries export their functions in their dynamic symbol table, so `puts` can be
+//!   found in libc even if the executable only knows it by its GOT slot.
+//! - Every target of a direct call in the code of the executable is the start of a function. It
+//!   is named after its address in the ELF file, like `sub_401136`, which does not change between
+//!   runs.
+//!
+//! These [`HeuristicSymbols`] are the last resort of the
+//! [`Debugger`](crate::debugger::Debugger): they are only used for names that are neither in
+//! the debug information nor the symbol table, so that breakpoints, address expressions and
+//! backtraces stay at least partially symbolic.
+
+use std::collections::{BTreeMap, HashMap};
+
+use iced_x86::{Decoder, DecoderOptions, FlowControl, Instruction, Mnemonic, OpKind, Register};
+use object::{Object, ObjectSection, ObjectSymbol, SymbolKind};
+use serde::Serialize;
+
+use crate::disassemble::CODE_BITNESS;
+use crate::errors::Result;
+use crate::got::{got_relocations, GotSlotKind};
+use crate::modules::LoadedModule;
+use crate::Addr;
+
+/// How many instructions at the entry point are searched for the address of `main`
+const MAX_ENTRY_INSTRUCTIONS: usize = 64;
+
+/// Where a [`HeuristicSymbol`] was found, from the most to the least reliable source
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+pub enum HeuristicSource {
+    /// The entry point of the executable, named `_start`
+    Entry,
+    /// The first argument of the first call at the entry point, named `main`
+    Main,
+    /// A stub in the PLT of the executable, named like `puts@plt`
+    Plt,
+    /// A function exported by a loaded shared library
+    Export,
+    /// The target of a direct call in the executable, named like `sub_401136`
+    CallTarget,
+}
+
+/// A function found by a heuristic
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+pub struct HeuristicSymbol {
+    /// Name of the function
+    pub name: String,
+    /// Address of the first instruction of the function in the process
+    pub addr: Addr,
+    /// Size of the function in bytes, up to the next known function if it is not known
+    pub size: u64,
+    /// Where the function was found
+    pub source: HeuristicSource,
+}
+
+/// The functions found by the heuristics, ordered by address
+///
+/// # Examples
+///
+/// ```
+/// use coreminer::heuristics::{HeuristicSource, HeuristicSymbol, HeuristicSymbols};
+/// use coreminer::addr::Addr;
+///
+/// let symbols = HeuristicSymbols::new(vec![
+///     HeuristicSymbol {
+///         name: "sub_1139".to_string(),
+///         addr: Addr::from(0x1139usize),
+///         size: 0x20,
+///         source: HeuristicSource::CallTarget,
+///     },
+///     HeuristicSymbol {
+///         name: "puts@plt".to_string(),
+///         addr: Addr::from(0x1030usize),
+///         size: 0x10,
+///         source: HeuristicSource::Plt,
+///     },
+/// ]);
+///
+/// let (symbol, offset) = symbols.containing(Addr::from(0x1144usize)).unwrap();
+/// assert_eq!((symbol.name.as_str(), offset), ("sub_1139", 0xb));
+/// assert_eq!(symbols.by_name("puts@plt").unwrap().addr, Addr::from(0x1030usize));
+/// assert!(symbols.containing(Addr::from(0x1100usize)).is_none());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct HeuristicSymbols {
+    symbols: Vec<HeuristicSymbol>,
+}
+
+impl HeuristicSymbols {
+    /// Creates [`HeuristicSymbols`] from functions in any order
+    #[must_use]
+    pub fn new(mut symbols: Vec<HeuristicSymbol>) -> Self {
+        // of the functions at the same address, the one from the most reliable source comes last,
+        // where `containing` finds it
+        symbols.sort_by(|a, b| a.addr.cmp(&b.addr).then(b.source.cmp(&a.source)));
+        Self { symbols }
+    }
+
+    /// Returns the functions, ordered by address
+    #[must_use]
+    pub fn symbols(&self) -> &[HeuristicSymbol] {
+        &self.symbols
+    }
+
+    /// Returns the function named `name`, preferring the most reliable source
+    #[must_use]
+    pub fn by_name(&self, name: &str) -> Option<&HeuristicSymbol> {
+        self.symbols
+            .iter()
+            .filter(|s| s.name == name)
+            .min_by_key(|s| s.source)
+    }
+
+    /// Returns the function that contains `addr` and the offset of `addr` in it
+    #[must_use]
+    pub fn containing(&self, addr: Addr) -> Option<(&HeuristicSymbol, u64)> {
+        let index = self.symbols.partition_point(|s| s.addr <= addr);
+        let symbol = self.symbols.get(index.checked_sub(1)?)?;
+        let offset = (addr - symbol.addr).u64();
+        (offset < symbol.size).then_some((symbol, offset))
+    }
+}
+
+/// Finds the functions of an executable with the heuristics of the [module](self)
+///
+/// # Parameters
+///
+/// * `obj` - The ELF file of the executable
+/// * `executable` - The executable as loaded into the process, for its load bias
+/// * `entry` - The entry point from the auxiliary vector, if known
+///
+/// # Returns
+///
+/// The entry point, `main`, the PLT stubs and the targets of direct calls, with their addresses
+/// in the process.
+#[must_use]
+pub fn executable_symbols(
+    obj: &object::File<'_>,
+    executable: &LoadedModule,
+    entry: Option<Addr>,
+) -> Vec<HeuristicSymbol> {
+    let mut symbols = plt_symbols(obj, executable);
+
+    let Some(text) = obj.section_by_name(".text") else {
+        return symbols;
+    };
+    let Ok(code) = text.data() else {
+        return symbols;
+    };
+    let text_range = text.address()..text.address() + text.size();
+
+    // function starts in the file, by the most reliable source
+    let mut starts: BTreeMap<u64, (String, HeuristicSource)> = BTreeMap::new();
+    for target in call_targets(code, text.address()) {
+        if text_range.contains(&target) {
+            starts.insert(
+                target,
+                (format!("sub_{target:x}"), HeuristicSource::CallTarget),
+            );
+        }
+    }
+    if let Some(entry) = entry.and_then(|e| executable.to_module(e)) {
+        if let Some(main) = main_from_entry(obj, entry.offset) {
+            starts.insert(main, ("main".to_string(), HeuristicSource::Main));
+        }
+        starts.insert(entry.offset, ("_start".to_string(), HeuristicSource::Entry));
+    }
+
+    let ends: Vec<u64> = starts
+        .keys()
+        .skip(1)
+        .copied()
+        .chain(std::iter::once(text_range.end))
+        .collect();
+    for ((start, (name, source)), end) in starts.into_iter().zip(ends) {
+        symbols.push(HeuristicSymbol {
+            name,
+            addr: executable.to_process(start),
+            size: end.saturating_sub(start).max(1),
+            source,
+        });
+    }
+    symbols
+}
+
+/// Reads the functions exported by a shared library from its file
+///
+/// # Errors
+///
+/// This function fails if the file of the module cannot be read or is not an ELF file.
+pub fn export_symbols(module: &LoadedModule) -> Result<Vec<HeuristicSymbol>> {
+    let data = std::fs::read(&module.path)?;
+    let obj = object::File::parse(data.as_slice())?;
+    Ok(obj
+        .dynamic_symbols()
+        .filter(|s| s.is_definition() && s.kind() == SymbolKind::Text && s.address() != 0)
+        .filter_map(|s| {
+            Some(HeuristicSymbol {
+                name: s.name().ok().filter(|n| !n.is_empty())?.to_string(),
+                addr: module.to_process(s.address()),
+                size: s.size().max(1),
+                source: HeuristicSource::Export,
+            })
+        })
+        .collect())
+}
+
+/// Finds the targets of all direct calls in `code`, which starts at the file address `first_addr`
+fn call_targets(code: &[u8], first_addr: u64) -> Vec<u64> {
+    let mut decoder = Decoder::with_ip(CODE_BITNESS, code, first_addr, DecoderOptions::NONE);
+    let mut instruction = Instruction::default();
+    let mut targets = Vec::new();
+    while decoder.can_decode() {
+        decoder.decode_out(&mut instruction);
+        if instruction.flow_control() == FlowControl::Call && instruction.near_branch_target() != 0
+        {
+            targets.push(instruction.near_branch_target());
+        }
+    }
+    targets
+}
+
+/// Finds the address of `main` in the code at the entry point
+///
+/// The C runtime passes `main` in `rdi` to `__libc_start_main`, which is the first call of
+/// `_start`. The address is loaded with `lea rdi, [rip+main]` in position independent
+/// executables and with `mov rdi, main` otherwise.
+fn main_from_entry(obj: &object::File<'_>, entry: u64) -> Option<u64> {
+    let section = obj
+        .sections()
+        .find(|s| (s.address()..s.address() + s.size()).contains(&entry))?;
+    let data = section.data().ok()?;
+    let code = data.get(usize::try_from(entry - section.address()).ok()?..)?;
+
+    let mut decoder = Decoder::with_ip(CODE_BITNESS, code, entry, DecoderOptions::NONE);
+    let mut instruction = Instruction::default();
+    let mut main = None;
+    for _ in 0..MAX_ENTRY_INSTRUCTIONS {
+        if !decoder.can_decode() {
+            break;
+        }
+        decoder.decode_out(&mut instruction);
+        match instruction.flow_control() {
+            FlowControl::Call | FlowControl::IndirectCall => return main,
+            FlowControl::Next => (),
+            _ => return None,
+        }
+        if !matches!(instruction.op0_register(), Register::RDI | Register::EDI) {
+            continue;
+        }
+        main = match (instruction.mnemonic(), instruction.op1_kind()) {
+            (Mnemonic::Lea, OpKind::Memory) if instruction.is_ip_rel_memory_operand() => {
+                Some(instruction.ip_rel_memory_address())
+            }
+            (
+                Mnemonic::Mov,
+                OpKind::Immediate32 | OpKind::Immediate32to64 | OpKind::Immediate64,
+            ) => Some(instruction.immediate(1)),
+            _ => None,
+        };
+    }
+    None
+}
+
+/// Names the stubs in the PLT sections of an executable after the GOT slots they jump through
+fn plt_symbols(obj: &object::File<'_>, executable: &LoadedModule) -> Vec<HeuristicSymbol> {
+    let slots: HashMap<u64, String> = got_relocations(obj)
+        .into_iter()
+        .filter(|r| r.kind == GotSlotKind::JumpSlot || r.kind == GotSlotKind::GlobDat)
+        .map(|r| (r.offset, r.symbol))
+        .collect();
+
+    let mut symbols = Vec::new();
+    for section in obj.sections() {
+        if !matches!(section.name(), Ok(".plt" | ".plt.sec" | ".plt.got")) {
+            continue;
+        }
+        let Ok(code) = section.data() else {
+            continue;
+        };
+        // a stub starts at the alignment of the section, before the endbr64 of IBT stubs
+        let stub_size = section.align().max(1);
+        let mut decoder =
+            Decoder::with_ip(CODE_BITNESS, code, section.address(), DecoderOptions::NONE);
+        let mut instruction = Instruction::default();
+        while decoder.can_decode() {
+            decoder.decode_out(&mut instruction);
+            if instruction.flow_control() != FlowControl::IndirectBranch
+                || !instruction.is_ip_rel_memory_operand()
+            {
+                continue;
+            }
+            if let Some(name) = slots.get(&instruction.ip_rel_memory_address()) {
+                let start = instruction.ip() - instruction.ip() % stub_size;
+                symbols.push(HeuristicSymbol {
+                    name: format!("{name}@plt"),
+                    addr: executable.to_process(start),
+                    size: stub_size,
+                    source: HeuristicSource::Plt,
+                });
+            }
+        }
+    }
+    symbols
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_executable_symbols() {
+        let pid = nix::unistd::getpid();
+        let data = std::fs::read("/proc/self/exe").unwrap();
+        let obj = object::File::parse(data.as_slice()).unwrap();
+        let executable = crate::modules::executable_module(pid).unwrap();
+        let entry = crate::procfs::AuxVector::read(pid).unwrap().entry;
+
+        let symbols = HeuristicSymbols::new(executable_symbols(&obj, &executable, entry));
+        assert_eq!(symbols.by_name("_start").map(|s| s.addr), entry);
+
+        // the heuristic finds the same main as the symbol table of the test binary
+        let main = obj
+            .symbols()
+            .find(|s| s.name() == Ok("main"))
+            .expect("the test binary has no main");
+        let (found, offset) = symbols
+            .containing(executable.to_process(main.address()))
+            .unwrap();
+        assert_eq!((found.name.as_str(), offset), ("main", 0));
+    }
+}
@@ -0,0 +1,132 @@
+//! # Value History Module
+//!
+//! Provides the value history and the convenience variables of the debugger.
+//!
+//! Every value that a read command shows the user, like a word read from memory, a resolved
+//! address or the value of a variable, is recorded in the [`ValueHistory`] and numbered from 1.
+//! Convenience variables are values that the user names, like `$base`. Both can be used in later
+//! [address expressions](crate::addrexpr), so that values never have to be copied by hand:
+//!
+//! - `$1`, `$2`, ...: the recorded value with that number
+//! - `$`: the most recently recorded value
+//! - `$base`: the convenience variable `base`
+//!
+//! Convenience variables cannot be named like a register, `$rsp` is always the stack pointer.
+
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+use crate::errors::{DebuggerError, Result};
+use crate::{Register, Word};
+
+/// Recorded values and convenience variables
+///
+/// # Examples
+///
+/// ```
+/// use coreminer::history::ValueHistory;
+///
+/// let mut history = ValueHistory::default();
+/// assert_eq!(history.record(0x1000), 1);
+/// assert_eq!(history.record(0x2000), 2);
+/// history.set_variable("base", 0x7f00).unwrap();
+///
+/// assert_eq!(history.get("1"), Some(0x1000));
+/// assert_eq!(history.get(""), Some(0x2000));
+/// assert_eq!(history.get("base"), Some(0x7f00));
+/// assert_eq!(history.get("3"), None);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ValueHistory {
+    values: Vec<Word>,
+    variables: BTreeMap<String, Word>,
+}
+
+impl ValueHistory {
+    /// Records `value` as the newest value of the history
+    ///
+    /// # Returns
+    ///
+    /// The number of the value, which is `$1` for the first value.
+    pub fn record(&mut self, value: Word) -> usize {
+        self.values.push(value);
+        self.values.len()
+    }
+
+    /// Sets the convenience variable `name` to `value`
+    ///
+    /// # Errors
+    ///
+    /// Fails with [`DebuggerError::InvalidVariableName`] if `name` is empty, a number, the name of
+    /// a [`Register`] or contains characters other than ASCII letters, digits and `_`.
+    pub fn set_variable(&mut self, name: &str, value: Word) -> Result<()> {
+        if !is_variable_name(name) {
+            return Err(DebuggerError::InvalidVariableName(name.to_string()));
+        }
+        self.variables.insert(name.to_string(), value);
+        Ok(())
+    }
+
+    /// Returns the value that `$name` refers to
+    ///
+    /// `name` is a number for a recorded value, empty for the most recent value, and the name of
+    /// a convenience variable otherwise.
+    #[must_use]
+    pub fn get(&self, name: &str) -> Option<Word> {
+        if name.is_empty() {
+            return self.values.last().copied();
+        }
+        match name.parse::<usize>() {
+            Ok(index) => index
+                .checked_sub(1)
+                .and_then(|i| self.values.get(i))
+                .copied(),
+            Err(_) => self.variables.get(name).copied(),
+        }
+    }
+
+    /// Returns the recorded values, `$1` first
+    #[must_use]
+    pub fn values(&self) -> &[Word] {
+        &self.values
+    }
+
+    /// Returns the convenience variables, ordered by name
+    #[must_use]
+    pub fn variables(&self) -> &BTreeMap<String, Word> {
+        &self.variables
+    }
+}
+
+/// Checks if `name` can be the name of a convenience variable
+fn is_variable_name(name: &str) -> bool {
+    !name.is_empty()
+        && !name.chars().all(|c| c.is_ascii_digit())
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        && name.parse::<Register>().is_err()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_value_history() {
+        let mut history = ValueHistory::default();
+        assert_eq!(history.get(""), None);
+        assert_eq!(history.get("0"), None);
+        history.record(7);
+        history.set_variable("x_1", 9).unwrap();
+        history.set_variable("x_1", 10).unwrap();
+
+        assert_eq!(history.get("1"), Some(7));
+        assert_eq!(history.get("0"), None);
+        assert_eq!(history.get("x_1"), Some(10));
+        assert!(history.set_variable("12", 1).is_err());
+        assert!(history.set_variable("a-b", 1).is_err());
+        assert!(history.set_variable("rip", 1).is_err());
+        assert!(history.set_variable("", 1).is_err());
+        assert_eq!(history.variables().len(), 1);
+    }
+}
@@ -0,0 +1,371 @@
+//! # Injection Module
+//!
+//! Provides code injection into a debugged process.
+//!
+//! The debugger can make the debuggee execute syscalls that it never intended to execute: the
+//! registers are set up for the syscall, a `syscall` instruction is written at the current
+//! instruction pointer, the debuggee is single stepped over it and afterwards the original
+//! memory and registers are restored. With an injected `mmap`, this allocates executable memory
+//! in the debuggee, into which arbitrary machine code can be written.
+//!
+//! Injected code can also be executed right away with [`run_injected`]. For that, the code is
+//! followed by an `int3` instruction, so the debugger gets control back once the code has run.
+//!
+//! Functions that are already in the debuggee are called with [`call_function`], which sets up
+//! the arguments like the System V ABI wants them and returns to an `int3` at the instruction
+//! pointer, so no memory has to be allocated.
+
+use nix::libc::user_regs_struct;
+use nix::sys::ptrace::{self, regset::NT_PRFPREG};
+use nix::sys::signal::Signal;
+use nix::sys::wait::{waitpid, WaitStatus};
+use nix::unistd::Pid;
+use serde::Serialize;
+use tracing::debug;
+
+use crate::breakpoint::INT3_BYTE;
+use crate::errors::{DebuggerError, Result};
+use crate::{mem_read, mem_write, Addr, WORD_BYTES};
+
+/// Machine code of the `syscall` instruction
+const SYSCALL_INSTRUCTION: [u8; 2] = [0x0f, 0x05];
+/// Size of the red zone below the stack pointer, which the injected code must not touch
+const RED_ZONE: u64 = 128;
+/// Highest value of `rax` after a syscall that does not indicate an error
+const MAX_ERRNO: i64 = 4095;
+/// Number of integer arguments that are passed in registers, the others are passed on the stack
+const REGISTER_ARGS: usize = 6;
+
+/// Describes code that was injected into the debuggee
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+pub struct Injection {
+    /// Address at which the code was written
+    pub addr: Addr,
+    /// Length of the injected code in bytes, without the trailing `int3`
+    pub len: usize,
+    /// The value of `rax` after the code ran, if it was run
+    pub result: Option<u64>,
+}
+
+/// Makes the debuggee execute a syscall and returns its return value
+///
+/// The registers and the memory at the instruction pointer are restored afterwards, so the
+/// debuggee does not notice anything, apart from the effects of the syscall itself.
+///
+/// # Parameters
+///
+/// * `pid` - The process ID of the debuggee, which must be stopped
+/// * `number` - The number of the syscall, for example [`nix::libc::SYS_mmap`]
+/// * `args` - The six arguments of the syscall
+///
+/// # Returns
+///
+/// * `Ok(i64)` - The raw return value of the syscall (negative errno on failure)
+/// * `Err(DebuggerError)` - If the syscall could not be injected
+///
+/// # Errors
+///
+/// This function will fail if the registers or memory of the debuggee cannot be accessed, or if
+/// the debuggee does not stop after executing the syscall.
+///
+/// # Examples
+///
+/// ```no_run
+/// use coreminer::inject::inject_syscall;
+/// use nix::unistd::Pid;
+///
+/// // let the stopped process with PID 1234 ask for its own PID
+/// let pid = inject_syscall(Pid::from_raw(1234), nix::libc::SYS_getpid as u64, [0; 6]).unwrap();
+/// assert_eq!(pid, 1234);
+/// ```
+pub fn inject_syscall(pid: Pid, number: u64, args: [u64; 6]) -> Result<i64> {
+    let saved_regs = ptrace::getregs(pid)?;
+    let rip = Addr::from(saved_regs.rip);
+
+    let mut saved_code = [0; SYSCALL_INSTRUCTION.len()];
+    mem_read(&mut saved_code, pid, rip)?;
+    mem_write(&SYSCALL_INSTRUCTION, pid, rip)?;
+
+    let mut regs = saved_regs;
+    regs.rax = number;
+    regs.orig_rax = number;
+    regs.rdi = args[0];
+    regs.rsi = args[1];
+    regs.rdx = args[2];
+    regs.r10 = args[3];
+    regs.r8 = args[4];
+    regs.r9 = args[5];
+    ptrace::setregs(pid, regs)?;
+
+    let stepped = ptrace::step(pid, None)
+        .map_err(DebuggerError::from)
+        .and_then(|()| expect_sigtrap(pid, ptrace::step));
+    let ret = ptrace::getregs(pid).map(|r| r.rax as i64);
+
+    // restore the debuggee even if something went wrong
+    mem_write(&saved_code, pid, rip)?;
+    ptrace::setregs(pid, saved_regs)?;
+    stepped?;
+    let ret = ret?;
+
+    debug!("injected syscall {number} returned {ret}");
+    Ok(ret)
+}
+
+/// Allocates memory in the debuggee that is readable, writable and executable
+///
+/// # Parameters
+///
+/// * `pid` - The process ID of the debuggee, which must be stopped
+/// * `len` - The minimum size of the memory in bytes, it is rounded up to whole pages
+///
+/// # Errors
+///
+/// This function will fail if the `mmap` syscall cannot be injected or if it fails.
+pub fn alloc_executable(pid: Pid, len: usize) -> Result<Addr> {
+    let prot = nix::libc::PROT_READ | nix::libc::PROT_WRITE | nix::libc::PROT_EXEC;
+    let flags = nix::libc::MAP_PRIVATE | nix::libc::MAP_ANONYMOUS;
+    let ret = inject_syscall(
+        pid,
+        nix::libc::SYS_mmap as u64,
+        [0, len as u64, prot as u64, flags as u64, u64::MAX, 0],
+    )?;
+
+    if (-MAX_ERRNO..0).contains(&ret) {
+        return Err(DebuggerError::InjectedSyscallFailed(ret));
+    }
+    Ok(Addr::from(ret as u64))
+}
+
+/// Changes the protection of memory in the debuggee with an injected `mprotect`
+///
+/// # Parameters
+///
+/// * `pid` - The process ID of the debuggee, which must be stopped
+/// * `addr` - The start of the memory, it must be aligned to a page
+/// * `len` - The length of the memory in bytes
+/// * `prot` - The new protection as `PROT_*` flags
+///
+/// # Errors
+///
+/// This function will fail if the `mprotect` syscall cannot be injected or if it fails.
+pub fn change_protection(pid: Pid, addr: Addr, len: usize, prot: i32) -> Result<()> {
+    let ret = inject_syscall(
+        pid,
+        nix::libc::SYS_mprotect as u64,
+        [addr.u64(), len as u64, prot as u64, 0, 0, 0],
+    )?;
+
+    if (-MAX_ERRNO..0).contains(&ret) {
+        return Err(DebuggerError::InjectedSyscallFailed(ret));
+    }
+    Ok(())
+}
+
+/// Allocates executable memory in the debuggee and writes `code` into it
+///
+/// The code is followed by an `int3` instruction, so that [`run_injected`] can get control back
+/// after it ran.
+///
+/// # Parameters
+///
+/// * `pid` - The process ID of the debuggee, which must be stopped
+/// * `code` - The machine code to inject
+///
+/// # Errors
+///
+/// This function will fail if the memory cannot be allocated or written.
+///
+/// # Examples
+///
+/// ```no_run
+/// use coreminer::inject::{inject_code, run_injected};
+/// use nix::unistd::Pid;
+///
+/// let pid = Pid::from_raw(1234);
+/// // mov eax, 42
+/// let injection = inject_code(pid, &[0xb8, 0x2a, 0x00, 0x00, 0x00]).unwrap();
+/// let regs = run_injected(pid, &injection).unwrap();
+/// assert_eq!(regs.rax, 42);
+/// ```
+pub fn inject_code(pid: Pid, code: &[u8]) -> Result<Injection> {
+    let addr = alloc_executable(pid, code.len() + 1)?;
+
+    let mut buf = code.to_vec();
+    buf.push(INT3_BYTE);
+    mem_write(&buf, pid, addr)?;
+
+    Ok(Injection {
+        addr,
+        len: code.len(),
+        result: None,
+    })
+}
+
+/// Executes injected code and returns to where the debuggee was stopped before
+///
+/// The code runs on the stack of the debuggee, below the red zone. Once it reaches the `int3`
+/// behind it, the registers of the debuggee are restored.
+///
+/// # Parameters
+///
+/// * `pid` - The process ID of the debuggee, which must be stopped
+/// * `injection` - Code that was injected with [`inject_code`]
+///
+/// # Returns
+///
+/// * `Ok(user_regs_struct)` - The registers of the debuggee after the injected code ran
+/// * `Err(DebuggerError)` - If the code could not be run
+///
+/// # Errors
+///
+/// This function will fail if the registers of the debuggee cannot be accessed, or if the
+/// debuggee stops anywhere else than at the end of the injected code, for example because it
+/// crashed.
+pub fn run_injected(pid: Pid, injection: &Injection) -> Result<user_regs_struct> {
+    let saved_regs = ptrace::getregs(pid)?;
+
+    let mut regs = saved_regs;
+    regs.rip = injection.addr.u64();
+    // keep the red zone intact and align the stack like at a function entry
+    regs.rsp = ((saved_regs.rsp - RED_ZONE) & !0xf) - 8;
+    ptrace::setregs(pid, regs)?;
+
+    let ran = ptrace::cont(pid, None)
+        .map_err(DebuggerError::from)
+        .and_then(|()| expect_sigtrap(pid, ptrace::cont))
+        .and_then(|()| Ok(ptrace::getregs(pid)?));
+
+    ptrace::setregs(pid, saved_regs)?;
+    let regs = ran?;
+
+    // after the int3, rip points right behind it
+    let expected = injection.addr + injection.len + 1;
+    if regs.rip != expected.u64() {
+        return Err(DebuggerError::InjectionFailed(format!(
+            "the injected code stopped at {} instead of {expected}",
+            Addr::from(regs.rip)
+        )));
+    }
+
+    Ok(regs)
+}
+
+/// Calls the function at `addr` in the debuggee with integer arguments and returns the registers
+/// after it returned
+///
+/// The arguments are passed like the System V ABI for `x86_64` wants them: the first six in
+/// `rdi`, `rsi`, `rdx`, `rcx`, `r8` and `r9`, the others on the stack. The function runs on the
+/// stack of the debuggee, below the red zone, and returns to an `int3` that is written at the
+/// instruction pointer for the duration of the call. Afterwards, the registers, including the
+/// vector registers, and the memory at the instruction pointer are restored, so the debuggee
+/// does not notice anything, apart from the effects of the function itself.
+///
+/// # Parameters
+///
+/// * `pid` - The process ID of the debuggee, which must be stopped
+/// * `addr` - The address of the function
+/// * `args` - The integer and pointer arguments of the function
+///
+/// # Returns
+///
+/// * `Ok(user_regs_struct)` - The registers after the function returned, with the return value
+///   in `rax` and `rdx`
+/// * `Err(DebuggerError)` - If the function could not be called
+///
+/// # Errors
+///
+/// This function will fail if the registers or memory of the debuggee cannot be accessed, or if
+/// the debuggee stops anywhere else than at the return address, for example because the
+/// function crashed or hit a breakpoint.
+///
+/// # Examples
+///
+/// ```no_run
+/// use coreminer::addr::Addr;
+/// use coreminer::inject::call_function;
+/// use nix::unistd::Pid;
+///
+/// // call a function like `int add(int a, int b)` in the stopped process with PID 1234
+/// let regs = call_function(Pid::from_raw(1234), Addr::from(0x401126usize), &[40, 2]).unwrap();
+/// assert_eq!(regs.rax as i32, 42);
+/// ```
+pub fn call_function(pid: Pid, addr: Addr, args: &[u64]) -> Result<user_regs_struct> {
+    let saved_regs = ptrace::getregs(pid)?;
+    let saved_fpregs = ptrace::getregset::<NT_PRFPREG>(pid)?;
+    let ret_addr = Addr::from(saved_regs.rip);
+
+    // the return address and the stack arguments, the stack must be aligned to 16 bytes before
+    // the return address is pushed
+    let stack_args = args.get(REGISTER_ARGS..).unwrap_or_default();
+    let mut rsp = (saved_regs.rsp - RED_ZONE) & !0xf;
+    if stack_args.len() % 2 == 1 {
+        rsp -= WORD_BYTES as u64;
+    }
+    rsp -= ((stack_args.len() + 1) * WORD_BYTES) as u64;
+    let stack: Vec<u8> = std::iter::once(ret_addr.u64())
+        .chain(stack_args.iter().copied())
+        .flat_map(u64::to_le_bytes)
+        .collect();
+    mem_write(&stack, pid, Addr::from(rsp))?;
+
+    let mut saved_code = [0; 1];
+    mem_read(&mut saved_code, pid, ret_addr)?;
+    mem_write(&[INT3_BYTE], pid, ret_addr)?;
+
+    let mut regs = saved_regs;
+    let arg = |i: usize| args.get(i).copied().unwrap_or(0);
+    regs.rdi = arg(0);
+    regs.rsi = arg(1);
+    regs.rdx = arg(2);
+    regs.rcx = arg(3);
+    regs.r8 = arg(4);
+    regs.r9 = arg(5);
+    // no vector registers are used by a variadic function
+    regs.rax = 0;
+    // the kernel must not restart an interrupted syscall when the function starts
+    regs.orig_rax = u64::MAX;
+    regs.rsp = rsp;
+    regs.rip = addr.u64();
+
+    let ran = ptrace::setregs(pid, regs)
+        .and_then(|()| ptrace::cont(pid, None))
+        .map_err(DebuggerError::from)
+        .and_then(|()| expect_sigtrap(pid, ptrace::cont))
+        .and_then(|()| Ok(ptrace::getregs(pid)?));
+
+    // restore the debuggee even if something went wrong
+    mem_write(&saved_code, pid, ret_addr)?;
+    ptrace::setregset::<NT_PRFPREG>(pid, saved_fpregs)?;
+    ptrace::setregs(pid, saved_regs)?;
+    let regs = ran?;
+
+    // after the int3, rip points right behind it
+    if regs.rip != ret_addr.u64() + 1 {
+        return Err(DebuggerError::InjectionFailed(format!(
+            "the called function stopped at {} instead of returning to {ret_addr}",
+            Addr::from(regs.rip)
+        )));
+    }
+
+    debug!("function at {addr} returned {:#x}", regs.rax);
+    Ok(regs)
+}
+
+/// Waits for the debuggee and makes sure it stopped because of a `SIGTRAP`
+///
+/// The injected code may make syscalls that the [seccomp filter](crate::seccomp) of the debuggee
+/// selects, their stops are skipped by resuming the debuggee with `resume` again.
+fn expect_sigtrap(pid: Pid, resume: fn(Pid, Option<Signal>) -> nix::Result<()>) -> Result<()> {
+    loop {
+        match waitpid(pid, None)? {
+            WaitStatus::Stopped(_, Signal::SIGTRAP) => return Ok(()),
+            WaitStatus::PtraceEvent(_, _, nix::libc::PTRACE_EVENT_SECCOMP) => resume(pid, None)?,
+            other => {
+                return Err(DebuggerError::InjectionFailed(format!(
+                    "the debuggee did not stop as expected: {other:?}"
+                )))
+            }
+        }
+    }
+}
@@ -0,0 +1,264 @@
+//! # Journal Module
+//!
+//! Provides a journal of what happened in a debug session, with timestamps, and exporting it in
+//! the Chrome trace event format.
+//!
+//! The debugger records an entry in its [`Journal`] whenever a debuggee is launched or attached,
+//! and whenever a debuggee is resumed by a command like continue or step, together with the time
+//! it ran and why it stopped. Breakpoint hits, watchpoints, caught syscalls, signals and exits
+//! all show up as stops.
+//!
+//! [`Journal::chrome_trace`] turns the journal into the JSON of the Chrome trace event format,
+//! which `chrome://tracing` and the Perfetto UI (<https://ui.perfetto.dev>) can show on a
+//! timeline. Each run of the debuggee is a slice as long as the debuggee ran, followed by an
+//! instant event for the stop. Timing relationships of a long session, like which continue took
+//! surprisingly long, are easy to spot like that.
+
+use std::time::{Duration, Instant};
+
+use nix::unistd::Pid;
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use crate::feedback::StopReason;
+use crate::syscall::{syscall_name, SyscallPhase};
+
+/// Something that happened in a debug session
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub enum JournalEvent {
+    /// A new debuggee was launched from an executable
+    Launch {
+        /// Path of the executable
+        exe: String,
+    },
+    /// The debugger attached to a running process
+    Attach {
+        /// Path of the executable of the process
+        exe: String,
+    },
+    /// The debuggee was resumed and ran until it stopped again
+    Run {
+        /// The command that resumed the debuggee, like `continue` or `step over`
+        command: &'static str,
+        /// How long the command took
+        duration: Duration,
+    },
+    /// The debuggee stopped
+    Stop(StopReason),
+}
+
+/// An entry of the [`Journal`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct JournalEntry {
+    /// When the event started, relative to the creation of the [`Journal`]
+    pub at: Duration,
+    /// The process ID of the debuggee
+    pub pid: i32,
+    /// What happened
+    pub event: JournalEvent,
+}
+
+/// The events of a debug session, in the order they were recorded
+///
+/// # Examples
+///
+/// ```
+/// use coreminer::journal::{Journal, JournalEvent};
+/// use coreminer::feedback::StopReason;
+/// use nix::unistd::Pid;
+/// use std::time::{Duration, Instant};
+///
+/// let mut journal = Journal::default();
+/// let pid = Pid::from_raw(1337);
+/// let resumed = Instant::now();
+/// journal.record(pid, resumed, JournalEvent::Run {
+///     command: "continue",
+///     duration: Duration::from_millis(20),
+/// });
+/// journal.record(pid, Instant::now(), JournalEvent::Stop(StopReason::StepDone));
+///
+/// let trace = journal.chrome_trace();
+/// assert_eq!(trace["traceEvents"][0]["ph"], "X");
+/// assert_eq!(trace["traceEvents"][0]["dur"], 20000);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Journal {
+    start: Instant,
+    entries: Vec<JournalEntry>,
+}
+
+impl Default for Journal {
+    fn default() -> Self {
+        Self {
+            start: Instant::now(),
+            entries: Vec::new(),
+        }
+    }
+}
+
+impl Journal {
+    /// Records that `event` happened at `at` for the debuggee `pid`
+    pub fn record(&mut self, pid: Pid, at: Instant, event: JournalEvent) {
+        self.entries.push(JournalEntry {
+            at: at.saturating_duration_since(self.start),
+            pid: pid.as_raw(),
+            event,
+        });
+    }
+
+    /// Returns the recorded entries, from the oldest to the newest
+    #[must_use]
+    pub fn entries(&self) -> &[JournalEntry] {
+        &self.entries
+    }
+
+    /// Returns the journal as JSON in the Chrome trace event format
+    ///
+    /// Runs become complete events (`"ph": "X"`) in the category `run`, stops become thread
+    /// scoped instant events (`"ph": "i"`) in a category by their reason, like `breakpoint` or
+    /// `syscall`. Launches and attaches name the process after its executable and are instant
+    /// events in the category `session`. Timestamps are in microseconds.
+    #[must_use]
+    pub fn chrome_trace(&self) -> Value {
+        let mut events = Vec::with_capacity(self.entries.len());
+        for entry in &self.entries {
+            let ts = micros(entry.at);
+            let pid = entry.pid;
+            match &entry.event {
+                JournalEvent::Launch { exe } | JournalEvent::Attach { exe } => {
+                    let name = if matches!(entry.event, JournalEvent::Launch { .. }) {
+                        "launch"
+                    } else {
+                        "attach"
+                    };
+                    events.push(json!({
+                        "name": "process_name", "ph": "M", "pid": pid, "tid": pid,
+                        "args": { "name": exe },
+                    }));
+                    events.push(json!({
+                        "name": name, "cat": "session", "ph": "i", "s": "p",
+                        "ts": ts, "pid": pid, "tid": pid, "args": { "exe": exe },
+                    }));
+                }
+                JournalEvent::Run { command, duration } => {
+                    events.push(json!({
+                        "name": command, "cat": "run", "ph": "X",
+                        "ts": ts, "dur": micros(*duration), "pid": pid, "tid": pid,
+                    }));
+                }
+                JournalEvent::Stop(reason) => {
+                    events.push(json!({
+                        "name": stop_name(reason), "cat": stop_category(reason), "ph": "i",
+                        "s": "t", "ts": ts, "pid": pid, "tid": pid,
+                        "args": { "reason": reason.to_string() },
+                    }));
+                }
+            }
+        }
+
+        json!({
+            "traceEvents": events,
+            "displayTimeUnit": "ms",
+        })
+    }
+}
+
+/// Converts `duration` into the microseconds of the Chrome trace event format
+fn micros(duration: Duration) -> u64 {
+    u64::try_from(duration.as_micros()).unwrap_or(u64::MAX)
+}
+
+/// Returns the category of the instant event for a stop
+fn stop_category(reason: &StopReason) -> &'static str {
+    match reason {
+        StopReason::Breakpoint { .. } => "breakpoint",
+        StopReason::Watchpoint { .. } => "watchpoint",
+        StopReason::StepDone => "step",
+        StopReason::Signal(_) => "signal",
+        StopReason::Syscall(_) => "syscall",
+        StopReason::PtraceEvent(_) => "ptrace",
+        StopReason::Interrupted => "interrupt",
+        StopReason::Panic { .. } => "panic",
+        StopReason::Exited(_) => "exit",
+    }
+}
+
+/// Returns the name of the instant event for a stop
+///
+/// Syscalls are named after the syscall, so that they can be told apart on the timeline.
+fn stop_name(reason: &StopReason) -> String {
+    match reason {
+        StopReason::Syscall(stop) => {
+            let name = syscall_name(stop.number)
+                .map_or_else(|| format!("syscall {}", stop.number), ToString::to_string);
+            match stop.phase {
+                SyscallPhase::Entry => name,
+                SyscallPhase::Exit => format!("{name} returned"),
+            }
+        }
+        other => stop_category(other).to_string(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::syscall::SyscallStop;
+    use crate::Addr;
+
+    #[test]
+    fn test_chrome_trace() {
+        let mut journal = Journal::default();
+        let pid = Pid::from_raw(42);
+        let start = journal.start;
+        journal.record(
+            pid,
+            start,
+            JournalEvent::Launch {
+                exe: "/bin/true".to_string(),
+            },
+        );
+        journal.record(
+            pid,
+            start + Duration::from_micros(10),
+            JournalEvent::Run {
+                command: "continue",
+                duration: Duration::from_micros(1500),
+            },
+        );
+        journal.record(
+            pid,
+            start + Duration::from_micros(1510),
+            JournalEvent::Stop(StopReason::Breakpoint {
+                addr: Addr::from(0x1139usize),
+                hardware: false,
+            }),
+        );
+        journal.record(
+            pid,
+            start + Duration::from_millis(2),
+            JournalEvent::Stop(StopReason::Syscall(SyscallStop {
+                phase: SyscallPhase::Exit,
+                number: 257,
+                args: [0; 6],
+                ret: Some(3),
+            })),
+        );
+        assert_eq!(journal.entries().len(), 4);
+
+        let trace = journal.chrome_trace();
+        let events = trace["traceEvents"].as_array().unwrap();
+        assert_eq!(events.len(), 5);
+        assert_eq!(events[0]["ph"], "M");
+        assert_eq!(events[0]["args"]["name"], "/bin/true");
+        assert_eq!(events[1]["name"], "launch");
+        assert_eq!(events[2]["name"], "continue");
+        assert_eq!(events[2]["ts"], 10);
+        assert_eq!(events[2]["dur"], 1500);
+        assert_eq!(events[3]["cat"], "breakpoint");
+        assert_eq!(events[3]["ts"], 1510);
+        assert_eq!(events[4]["cat"], "syscall");
+        assert_eq!(events[4]["name"], "openat returned");
+        assert!(events.iter().all(|e| e["pid"] == 42));
+    }
+}
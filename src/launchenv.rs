@@ -0,0 +1,203 @@
+//! # Launch Environment Module
+//!
+//! Provides changes to the environment of programs launched by the debugger.
+//!
+//! Programs run by [`Debugger::run`](crate::debugger::Debugger::run) inherit the environment of
+//! the debugger. A [`LaunchEnv`] sets or removes variables in the environment of the launched
+//! program only, so that the debugger itself is not affected. This is mostly useful for the
+//! variables of the dynamic linker: a library in `LD_PRELOAD` is loaded before all others and
+//! can interpose functions of the program, and `LD_LIBRARY_PATH` makes the program use different
+//! versions of its libraries. Setting these for the whole debugger would load the libraries into
+//! the debugger as well.
+
+use std::collections::BTreeMap;
+use std::ffi::CString;
+use std::fmt::{self, Display};
+
+use serde::{Deserialize, Serialize};
+
+use crate::errors::{DebuggerError, Result};
+
+/// The variable of the dynamic linker with the libraries to load before all others
+pub const PRELOAD_VAR: &str = "LD_PRELOAD";
+/// The variable of the dynamic linker with directories to search for libraries first
+pub const LIBRARY_PATH_VAR: &str = "LD_LIBRARY_PATH";
+
+/// Changes to the environment of launched programs
+///
+/// # Examples
+///
+/// ```
+/// use coreminer::launchenv::LaunchEnv;
+///
+/// let mut env = LaunchEnv::default();
+/// env.set("LANG", "C").unwrap();
+/// env.unset("DISPLAY").unwrap();
+/// env.preload("/tmp/hook.so");
+///
+/// let envp = env.envp_from([("DISPLAY".to_string(), ":0".to_string())]).unwrap();
+/// assert_eq!(envp.len(), 2);
+/// assert!(envp.iter().any(|v| v.to_bytes() == b"LD_PRELOAD=/tmp/hook.so"));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct LaunchEnv {
+    /// The changed variables, with `None` for variables that are removed
+    pub vars: BTreeMap<String, Option<String>>,
+}
+
+impl LaunchEnv {
+    /// Sets the variable `name` to `value`
+    ///
+    /// # Errors
+    ///
+    /// This function fails if `name` is empty or contains a `=` or a null byte.
+    pub fn set(&mut self, name: impl Into<String>, value: impl Into<String>) -> Result<()> {
+        let name = checked_name(name.into())?;
+        self.vars.insert(name, Some(value.into()));
+        Ok(())
+    }
+
+    /// Removes the variable `name` from the environment
+    ///
+    /// # Errors
+    ///
+    /// This function fails if `name` is empty or contains a `=` or a null byte.
+    pub fn unset(&mut self, name: impl Into<String>) -> Result<()> {
+        let name = checked_name(name.into())?;
+        self.vars.insert(name, None);
+        Ok(())
+    }
+
+    /// Adds `library` to the libraries in `LD_PRELOAD`, after the ones that are already in it
+    pub fn preload(&mut self, library: &str) {
+        let value = match self.value(PRELOAD_VAR) {
+            Some(libraries) if !libraries.is_empty() => format!("{libraries}:{library}"),
+            _ => library.to_string(),
+        };
+        self.vars.insert(PRELOAD_VAR.to_string(), Some(value));
+    }
+
+    /// Adds `dir` to the directories in `LD_LIBRARY_PATH`, before the ones that are already in it
+    pub fn add_library_path(&mut self, dir: &str) {
+        let value = match self.value(LIBRARY_PATH_VAR) {
+            Some(dirs) if !dirs.is_empty() => format!("{dir}:{dirs}"),
+            _ => dir.to_string(),
+        };
+        self.vars.insert(LIBRARY_PATH_VAR.to_string(), Some(value));
+    }
+
+    /// Returns the value of `name` that a launched program gets, which is inherited from the
+    /// debugger if it is not changed
+    #[must_use]
+    pub fn value(&self, name: &str) -> Option<String> {
+        match self.vars.get(name) {
+            Some(value) => value.clone(),
+            None => std::env::var(name).ok(),
+        }
+    }
+
+    /// Returns `true` if no variables are changed
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.vars.is_empty()
+    }
+
+    /// Builds the environment of a launched program from the environment of the debugger
+    ///
+    /// # Errors
+    ///
+    /// This function fails if a variable contains a null byte.
+    pub fn envp(&self) -> Result<Vec<CString>> {
+        self.envp_from(std::env::vars_os().map(|(name, value)| {
+            (
+                name.to_string_lossy().into_owned(),
+                value.to_string_lossy().into_owned(),
+            )
+        }))
+    }
+
+    /// Builds the environment of a launched program from the variables in `base`
+    ///
+    /// The result is a list of `NAME=VALUE` strings, as `execve` takes it. The variables of
+    /// `base` keep their order, new variables are added at the end.
+    ///
+    /// # Errors
+    ///
+    /// This function fails if a variable contains a null byte.
+    pub fn envp_from(
+        &self,
+        base: impl IntoIterator<Item = (String, String)>,
+    ) -> Result<Vec<CString>> {
+        let mut vars: Vec<(String, String)> = base
+            .into_iter()
+            .filter(|(name, _)| !self.vars.contains_key(name))
+            .collect();
+        vars.extend(
+            self.vars
+                .iter()
+                .filter_map(|(name, value)| Some((name.clone(), value.clone()?))),
+        );
+        vars.into_iter()
+            .map(|(name, value)| Ok(CString::new(format!("{name}={value}"))?))
+            .collect()
+    }
+}
+
+impl Display for LaunchEnv {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return write!(f, "the environment of the debugger is used unchanged");
+        }
+        for (i, (name, value)) in self.vars.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            match value {
+                Some(value) => write!(f, "{name}={value}")?,
+                None => write!(f, "unset {name}")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Checks that `name` can be the name of an environment variable
+fn checked_name(name: String) -> Result<String> {
+    if name.is_empty() || name.contains(['=', '\0']) {
+        return Err(DebuggerError::InvalidEnvName(name));
+    }
+    Ok(name)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_envp_from() {
+        let mut env = LaunchEnv::default();
+        env.set("LD_LIBRARY_PATH", "/opt/lib").unwrap();
+        env.add_library_path("/tmp/lib");
+        env.preload("/tmp/a.so");
+        env.preload("/tmp/b.so");
+        env.unset("HOME").unwrap();
+        assert!(env.set("A=B", "C").is_err());
+
+        let envp = env
+            .envp_from([
+                ("HOME".to_string(), "/root".to_string()),
+                ("PATH".to_string(), "/bin".to_string()),
+                ("LD_PRELOAD".to_string(), "/ignored.so".to_string()),
+            ])
+            .unwrap();
+        let envp: Vec<&str> = envp.iter().map(|v| v.to_str().unwrap()).collect();
+        assert_eq!(
+            envp,
+            [
+                "PATH=/bin",
+                "LD_LIBRARY_PATH=/tmp/lib:/opt/lib",
+                "LD_PRELOAD=/tmp/a.so:/tmp/b.so"
+            ]
+        );
+    }
+}
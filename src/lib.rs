@@ -7,13 +7,77 @@
 //! ## Core Features
 //!
 //! - **Memory Access**: Read and write process memory
-//! - **Register Control**: Access and modify CPU registers
-//! - **Breakpoint Management**: Set, enable, disable, and remove breakpoints
-//! - **Execution Control**: Step by step execution, continue execution, step in/out/over functions
-//! - **Symbol Resolution**: Parse and use DWARF debug information for symbol lookup
-//! - **Variable Inspection**: Access application variables through debug information
+//! - **Register Control**: Access and modify CPU registers, including the SSE and AVX vector
+//!   registers
+//! - **Breakpoint Management**: Set, enable, disable, and remove software and hardware breakpoints
+//! - **Logging Breakpoints**: Print variables and registers when a breakpoint is hit, without
+//!   stopping the process
+//! - **Uprobes**: Count the calls of functions and capture their arguments without stopping the
+//!   process
+//! - **Performance Counters**: Count the instructions, cycles and cache misses of the process
+//!   between two stops
+//! - **Profiling**: Sample the call stack of the running process and export the samples as
+//!   folded stacks or SVG flamegraph
+//! - **Session Traces**: Export the runs and stops of a debug session with their timestamps as
+//!   Chrome trace, to be viewed in `chrome://tracing` or Perfetto
+//! - **Breakpoint Files**: Save breakpoints relative to symbols and modules and restore them in
+//!   a later session
+//! - **Limits**: Cap the size of expensive requests like large reads, long disassemblies and
+//!   symbol searches with many results
+//! - **Sessions**: Save the settings, breakpoints, watchpoints and plugins of the debugger and
+//!   restore them after restarting the debugger and the process
+//! - **Watchpoints**: Watch memory ranges and find the instruction that changes them, with
+//!   write protected pages for ranges that are too large to be single stepped, optionally only
+//!   stopping for changes that fulfill a condition
+//! - **Catchpoints**: Stop when the process maps new memory or makes memory executable
+//! - **Rust Panics**: Stop Rust programs at the panic site with the message and location of the
+//!   panic, before the stack is unwound
+//! - **Syscall Filters**: Stop only at selected syscalls, chosen by name or number, which a seccomp
+//!   filter in the process selects without slowing down the other syscalls
+//! - **Launch Environment**: Set `LD_PRELOAD`, `LD_LIBRARY_PATH` and other variables in the
+//!   environment of launched programs without changing the environment of the debugger
+//! - **Attaching**: Attach to running processes and all of their threads, optionally keeping the
+//!   other threads stopped while stepping
+//! - **Execution Control**: Step by step execution, continue execution, step in/out/over functions,
+//!   step by source lines, optionally stopping new processes at their entry point or `main`, with
+//!   the reason of each stop reported to the UI
+//! - **Symbol Resolution**: Parse and use DWARF debug information for symbol lookup, with
+//!   demangled names of C++ and Rust programs
+//! - **Source Listings**: List the source around lines and addresses, from the source embedded in
+//!   DWARF 5 debug information or from disk, with a check for changed files
+//! - **Stripped Executables**: Find `main`, PLT stubs, functions exported by shared libraries and
+//!   call targets with heuristics when there is neither debug information nor a symbol table
+//! - **Address Expressions**: Give addresses as symbols or registers with an offset, like
+//!   `main+0x10` or `$rsp+8*3`, or as lines of source files, like `hello.c:12`
+//! - **Value History**: Refer to earlier results as `$1`, `$2`, ... and to convenience variables
+//!   like `$base` in later addresses
+//! - **Variable Inspection**: Access application variables through debug information, including
+//!   parameters of optimized code whose values are only known at the entry of the function, and
+//!   show expressions at every stop
+//! - **Variable Expressions**: Select members, array elements and pointees of variables, like
+//!   `node->items[2].len`, or their addresses, like `&config`, including members of C++ base
+//!   classes and data behind references
 //! - **Stack Analysis**: Generate and inspect backtraces and stack frames
+//! - **Process Inspection**: Inspect the environment, open files and resource usage of the process
+//! - **GOT Hooking**: List the GOT entries of the executable and redirect them
+//! - **Code Injection**: Allocate executable memory in the process and run custom code in it, or
+//!   call the functions of the process with integer arguments
 //! - **Disassembly**: Disassemble machine code to human readable assembly
+//! - **Patching**: Assemble instructions and write them into the code of the process
+//! - **Module Dumping**: Dump loaded modules from memory into ELF files
+//! - **Cross-References**: Find the instructions that reference an address
+//! - **Graph Export**: Export the call graph of the executable and the backtraces taken while
+//!   debugging in the DOT language of graphviz
+//! - **Ptrace Options**: Kill the process with the debugger and trace its exits, forks and execs
+//! - **Anti-Anti-Debugging**: Hide the debugger from common debugger detection tricks
+//! - **Record and Replay**: Record programs with rr and step through the replays, even backwards
+//! - **Checkpoints**: Save the writable memory and registers of the process and restore them
+//!   later to run the same code again
+//! - **Reverse Debugging**: Record the stops of the process with periodic checkpoints of its
+//!   memory and registers, and go back to earlier stops
+//! - **Core Dumps**: Inspect the registers, memory, backtrace and variables of a crashed process
+//!   from its core dump, after it is long gone
+//! - **C Interface**: Embed the debugger into programs written in other languages (`ffi` feature)
 //!
 //! ## Architecture
 //!
@@ -72,24 +136,70 @@ use serde::{Deserialize, Serialize};
 use crate::errors::Result;
 
 use self::addr::Addr;
+use self::dwarfreg::DwarfRegister;
 use self::errors::DebuggerError;
 
 pub mod addr;
+pub mod addrexpr;
+pub mod antidebug;
+pub mod assemble;
+pub mod bpfile;
 pub mod breakpoint;
+pub mod catchpoint;
+pub mod checkpoint;
 pub mod consts;
+pub mod coredump;
 pub mod dbginfo;
 pub mod debuggee;
 pub mod debugger;
+pub mod debugreg;
 pub mod disassemble;
+pub mod dot;
+pub mod dump;
 pub mod dwarf_parse;
+pub mod dwarfreg;
 pub mod errors;
 pub mod feedback;
+pub mod fpregs;
+pub mod gdbremote;
+pub mod got;
+pub mod heuristics;
+pub mod history;
+pub mod inject;
+pub mod journal;
+pub mod launchenv;
+pub mod limits;
+pub mod lines;
+pub mod logpoint;
 pub mod memorymap;
+pub mod memread;
+pub mod modules;
+pub mod pageguard;
+pub mod perf;
+pub mod procfs;
+pub mod profile;
+pub mod regset;
+pub mod reverse;
+pub mod rr;
+pub mod rustpanic;
+pub mod seccomp;
+pub mod session;
+pub mod source;
 pub mod stack;
+pub mod symindex;
+pub mod syscall;
+pub mod thread;
+pub mod traceopts;
 pub mod ui;
 pub mod unwind;
+pub mod uprobe;
+pub mod varexpr;
 pub mod variable;
+pub mod watchpoint;
+pub mod xref;
 
+#[cfg(feature = "ffi")]
+pub mod ffi;
 #[cfg(feature = "plugins")]
 pub mod plugins;
 
@@ -99,7 +209,7 @@ pub type Word = usize;
 pub const WORD_BYTES: usize = Word::BITS as usize / 8;
 
 /// CPU register names for `x86_64` architecture
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[allow(non_camel_case_types)]
 #[allow(missing_docs)] // just register names, self explanatory
 pub enum Register {
@@ -174,46 +284,16 @@ impl TryFrom<gimli::Register> for Register {
     type Error = DebuggerError;
     /// Converts a DWARF register number to the corresponding Register enum value.
     ///
-    /// The DWARF Register Number Mapping is defined in the amd64 ABI here:
-    /// <https://refspecs.linuxbase.org/elf/x86_64-abi-0.99.pdf#figure.3.36>
+    /// Only general purpose registers are a [Register], see [`DwarfRegister`] for all registers
+    /// that DWARF information can refer to.
+    ///
+    /// # Errors
+    ///
+    /// Fails with [`DebuggerError::NotAGeneralRegister`] if the number is of another register.
     fn try_from(value: gimli::Register) -> Result<Self> {
-        match value.0 {
-            0 => Ok(Register::rax),
-            1 => Ok(Register::rdx),
-            2 => Ok(Register::rcx),
-            3 => Ok(Register::rbx),
-            4 => Ok(Register::rsi),
-            5 => Ok(Register::rdi),
-            6 => Ok(Register::rbp),
-            7 => Ok(Register::rsp),
-            8 => Ok(Register::r8),
-            9 => Ok(Register::r9),
-            10 => Ok(Register::r10),
-            11 => Ok(Register::r11),
-            12 => Ok(Register::r12),
-            13 => Ok(Register::r13),
-            14 => Ok(Register::r14),
-            15 => Ok(Register::r15),
-            16 => Ok(Register::rip),
-
-            49 => Ok(Register::eflags),
-
-            50 => Ok(Register::es),
-            51 => Ok(Register::cs),
-            52 => Ok(Register::ss),
-            53 => Ok(Register::ds),
-            54 => Ok(Register::fs),
-            55 => Ok(Register::gs),
-
-            56 => Ok(Register::fs_base),
-            57 => Ok(Register::gs_base),
-
-            // We skip 58..=62 because they correspond to tr, ldtr, mxcsr, fcw, fsw, etc.
-            // which aren't in our enum.
-
-            // No standard mapping for 63 or `orig_rax`
-            // So we return None for those or anything else unrecognized.
-            x => Err(DebuggerError::UnimplementedRegister(x)),
+        match DwarfRegister::try_from(value)? {
+            DwarfRegister::General(reg) => Ok(reg),
+            other => Err(DebuggerError::NotAGeneralRegister(other)),
         }
     }
 }
@@ -223,6 +303,23 @@ pub(crate) fn mem_write_word(pid: Pid, addr: Addr, value: Word) -> Result<()> {
     Ok(ptrace::write(pid, addr.into(), value as i64)?)
 }
 
+/// Writes a word-sized value like [`mem_write_word`] and reads it back to check that the memory
+/// was changed
+///
+/// # Errors
+///
+/// Fails with [`DebuggerError::WriteNotVerified`] if the memory does not contain `value`
+/// afterwards, for example because another thread of the process wrote to it at the same time.
+pub(crate) fn mem_write_word_verified(pid: Pid, addr: Addr, value: Word) -> Result<()> {
+    mem_write_word(pid, addr, value)?;
+    let found = mem_read_word(pid, addr)?;
+    if found != value {
+        let first = (found ^ value).trailing_zeros() as usize / 8;
+        return Err(DebuggerError::WriteNotVerified(addr + first));
+    }
+    Ok(())
+}
+
 /// Reads a word-sized value from the specified process memory address
 pub(crate) fn mem_read_word(pid: Pid, addr: Addr) -> Result<Word> {
     Ok(ptrace::read(pid, addr.into())? as Word)
@@ -252,15 +349,38 @@ pub(crate) fn mem_write(data_raw: &[u8], pid: Pid, addr: Addr) -> Result<usize>
     Ok(len)
 }
 
+/// Writes a slice of bytes like [`mem_write`] and reads it back to check that the memory was
+/// changed
+///
+/// # Errors
+///
+/// Fails with [`DebuggerError::WriteNotVerified`] if not all bytes were written or the memory
+/// does not contain them afterwards.
+pub(crate) fn mem_write_verified(data_raw: &[u8], pid: Pid, addr: Addr) -> Result<usize> {
+    let written = mem_write(data_raw, pid, addr)?;
+    let mut found = vec![0; data_raw.len()];
+    let read = mem_read(&mut found, pid, addr)?;
+    if let Some(first) =
+        (0..data_raw.len()).find(|i| *i >= written || *i >= read || found[*i] != data_raw[*i])
+    {
+        return Err(DebuggerError::WriteNotVerified(addr + first));
+    }
+    Ok(written)
+}
+
 /// Gets the value of a specified register for the target process
 ///
 /// # Errors
 ///
 /// This function will return an error if [`ptrace::getregs`] fails.
 pub fn get_reg(pid: Pid, r: Register) -> Result<u64> {
-    let regs = ptrace::getregs(pid)?;
+    Ok(reg_value(&ptrace::getregs(pid)?, r))
+}
 
-    let v = match r {
+/// Gets the value of a specified register from the registers of a thread
+#[must_use]
+pub fn reg_value(regs: &nix::libc::user_regs_struct, r: Register) -> u64 {
+    match r {
         Register::r9 => regs.r9,
         Register::r8 => regs.r8,
         Register::r10 => regs.r10,
@@ -288,9 +408,7 @@ pub fn get_reg(pid: Pid, r: Register) -> Result<u64> {
         Register::gs_base => regs.gs_base,
         Register::gs => regs.gs,
         Register::ds => regs.ds,
-    };
-
-    Ok(v)
+    }
 }
 
 /// Sets the value of a specified register for the target process
@@ -369,7 +487,32 @@ mod test {
                 .expect("could not make register from valid num"),
             Register::r15
         );
+        assert_eq!(
+            Register::try_from(gimli::Register(58))
+                .expect("could not make register from valid num"),
+            Register::fs_base
+        );
         Register::try_from(gimli::Register(666)).expect_err("could make register from invalid num");
+        // xmm0 is known, but not a general purpose register
+        assert!(matches!(
+            Register::try_from(gimli::Register(17)),
+            Err(super::DebuggerError::NotAGeneralRegister(_))
+        ));
+    }
+
+    #[test]
+    fn test_mem_write_verified() {
+        let pid = nix::unistd::getpid();
+        let mut buf = vec![0u8; 16];
+        let addr = super::Addr::from(buf.as_mut_ptr() as usize);
+        assert_eq!(super::mem_write_verified(&[1, 2, 3], pid, addr).unwrap(), 3);
+        // the compiler does not know about the write, so read the memory again
+        let written: [u8; 4] = unsafe { std::ptr::read_volatile(buf.as_ptr().cast()) };
+        assert_eq!(written, [1, 2, 3, 0]);
+        // a write past the end of the readable memory is not complete
+        assert!(
+            super::mem_write_verified(&[1, 2], pid, super::Addr::from(usize::MAX - 1)).is_err()
+        );
     }
 }
 
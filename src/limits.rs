@@ -0,0 +1,172 @@
+//! # Limits Module
+//!
+//! Provides caps on the size of expensive requests to the debugger.
+//!
+//! Some requests make the debugger allocate and transfer as much data as they ask for: reading
+//! a variable of a huge type, dumping the stack while the frame pointer is garbage, disassembling
+//! a long range or searching symbols with a lot of matches. A frontend with a bug could ask for
+//! gigabytes that way and wedge the debugger. The [`Limits`] cap these requests, which fail with
+//! [`DebuggerError::LimitExceeded`](crate::errors::DebuggerError::LimitExceeded) before anything
+//! is read when they would exceed a limit.
+//!
+//! The [`LimitKind::PointerDepth`] is different: it says how far pointers are followed when a
+//! variable is read, and pointers beyond it are shown as their address instead of failing.
+//!
+//! The limits are set with [`Debugger::set_limits`](crate::debugger::Debugger::set_limits).
+
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::errors::{DebuggerError, Result};
+
+/// Default for [`Limits::max_read_size`], 16 MiB
+pub const DEFAULT_MAX_READ_SIZE: usize = 16 * 1024 * 1024;
+/// Default for [`Limits::max_disassembly_len`], 64 KiB
+pub const DEFAULT_MAX_DISASSEMBLY_LEN: usize = 64 * 1024;
+/// Default for [`Limits::max_symbol_results`]
+pub const DEFAULT_MAX_SYMBOL_RESULTS: usize = 10_000;
+/// Default for [`Limits::max_pointer_depth`], pointers are not followed
+pub const DEFAULT_MAX_POINTER_DEPTH: usize = 0;
+
+/// Which of the [`Limits`] was exceeded
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum LimitKind {
+    /// [`Limits::max_read_size`]
+    ReadSize,
+    /// [`Limits::max_disassembly_len`]
+    DisassemblyLen,
+    /// [`Limits::max_symbol_results`]
+    SymbolResults,
+    /// [`Limits::max_pointer_depth`]
+    PointerDepth,
+}
+
+impl Display for LimitKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ReadSize => write!(f, "read size"),
+            Self::DisassemblyLen => write!(f, "disassembly length"),
+            Self::SymbolResults => write!(f, "symbol results"),
+            Self::PointerDepth => write!(f, "pointer depth"),
+        }
+    }
+}
+
+impl FromStr for LimitKind {
+    type Err = DebuggerError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "read" => Ok(Self::ReadSize),
+            "disas" => Ok(Self::DisassemblyLen),
+            "symbols" => Ok(Self::SymbolResults),
+            "pointers" => Ok(Self::PointerDepth),
+            _ => Err(DebuggerError::InvalidLimit(s.to_string())),
+        }
+    }
+}
+
+/// Caps on the size of expensive requests
+///
+/// # Examples
+///
+/// ```
+/// use coreminer::limits::{LimitKind, Limits};
+///
+/// let mut limits = Limits::default();
+/// assert!(limits.check(LimitKind::ReadSize, 4096).is_ok());
+///
+/// limits.set(LimitKind::ReadSize, 1024);
+/// assert!(limits.check(LimitKind::ReadSize, 4096).is_err());
+/// assert_eq!(limits.max_read_size, 1024);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Limits {
+    /// Maximum number of bytes that are read from the debuggee for one variable or stack dump
+    pub max_read_size: usize,
+    /// Maximum number of bytes that are disassembled at once
+    pub max_disassembly_len: usize,
+    /// Maximum number of symbols that a symbol search returns
+    pub max_symbol_results: usize,
+    /// Maximum number of pointers that are followed when a variable is read
+    ///
+    /// This limit does not make reads fail, pointers beyond it are shown as addresses. See
+    /// [`Debuggee::follow_pointer`](crate::debuggee::Debuggee::follow_pointer).
+    pub max_pointer_depth: usize,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self {
+            max_read_size: DEFAULT_MAX_READ_SIZE,
+            max_disassembly_len: DEFAULT_MAX_DISASSEMBLY_LEN,
+            max_symbol_results: DEFAULT_MAX_SYMBOL_RESULTS,
+            max_pointer_depth: DEFAULT_MAX_POINTER_DEPTH,
+        }
+    }
+}
+
+impl Limits {
+    /// Returns the maximum of the limit `kind`
+    #[must_use]
+    pub fn get(&self, kind: LimitKind) -> usize {
+        match kind {
+            LimitKind::ReadSize => self.max_read_size,
+            LimitKind::DisassemblyLen => self.max_disassembly_len,
+            LimitKind::SymbolResults => self.max_symbol_results,
+            LimitKind::PointerDepth => self.max_pointer_depth,
+        }
+    }
+
+    /// Sets the maximum of the limit `kind`
+    pub fn set(&mut self, kind: LimitKind, max: usize) {
+        match kind {
+            LimitKind::ReadSize => self.max_read_size = max,
+            LimitKind::DisassemblyLen => self.max_disassembly_len = max,
+            LimitKind::SymbolResults => self.max_symbol_results = max,
+            LimitKind::PointerDepth => self.max_pointer_depth = max,
+        }
+    }
+
+    /// Checks that `requested` does not exceed the limit `kind`
+    ///
+    /// # Errors
+    ///
+    /// Fails with [`DebuggerError::LimitExceeded`] if `requested` is larger than the maximum.
+    pub fn check(&self, kind: LimitKind, requested: usize) -> Result<()> {
+        let max = self.get(kind);
+        if requested > max {
+            return Err(DebuggerError::LimitExceeded(kind, requested, max));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_limits() {
+        let mut limits = Limits::default();
+        limits.set("symbols".parse().unwrap(), 2);
+        assert!(limits.check(LimitKind::SymbolResults, 2).is_ok());
+        assert!(matches!(
+            limits.check(LimitKind::SymbolResults, 3),
+            Err(DebuggerError::LimitExceeded(LimitKind::SymbolResults, 3, 2))
+        ));
+        assert!(limits
+            .check(LimitKind::DisassemblyLen, DEFAULT_MAX_DISASSEMBLY_LEN)
+            .is_ok());
+        assert!("bogus".parse::<LimitKind>().is_err());
+        assert_eq!(Limits::default().get("pointers".parse().unwrap()), 0);
+
+        // missing limits keep their default
+        let limits: Limits = serde_json::from_str(r#"{"max_read_size": 64}"#).unwrap();
+        assert_eq!(limits.max_read_size, 64);
+        assert_eq!(limits.max_symbol_results, DEFAULT_MAX_SYMBOL_RESULTS);
+    }
+}
@@ -0,0 +1,303 @@
+//! # Line Table Module
+//!
+//! Provides the mapping between source lines and addresses of the debuggee.
+//!
+//! The DWARF line programs of the executable describe which instructions were generated from
+//! which line of which source file. The [`LineTable`] holds the rows of all line programs that
+//! start a statement, rebased to the load address of the debuggee, so that source locations like
+//! `main.c:12` can be resolved to addresses and addresses can be mapped back to their line.
+//!
+//! DWARF 5 line programs can also record an MD5 digest of each source file and even embed its
+//! text. These are kept as [`SourceFile`]s, so that [source listings](crate::source) do not
+//! depend on the files on disk.
+
+use std::path::PathBuf;
+
+use gimli::Reader;
+use serde::Serialize;
+
+use crate::dwarf_parse::GimliReaderThing;
+use crate::errors::Result;
+use crate::modules::LoadedModule;
+use crate::Addr;
+
+/// A row of the [`LineTable`]: the first instruction of a statement in a source line
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+pub struct LineEntry {
+    /// Address of the instruction
+    pub addr: Addr,
+    /// Path of the source file, as recorded by the compiler
+    pub file: String,
+    /// Line in the source file, starting at 1
+    pub line: u64,
+}
+
+/// A source file of the line programs, with what DWARF 5 recorded about it
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+pub struct SourceFile {
+    /// Path of the source file, as recorded by the compiler
+    pub path: String,
+    /// MD5 digest of the file when it was compiled
+    pub md5: Option<[u8; 16]>,
+    /// The text of the file, if it is embedded in the debug information
+    pub source: Option<String>,
+}
+
+/// The source lines of the debuggee and their addresses
+///
+/// # Examples
+///
+/// ```
+/// use coreminer::lines::{LineEntry, LineTable};
+/// use coreminer::addr::Addr;
+///
+/// let entry = |addr: usize, line: u64| LineEntry {
+///     addr: Addr::from(addr),
+///     file: "/src/hot.c".to_string(),
+///     line,
+/// };
+/// let table = LineTable::new(vec![entry(0x1149, 4), entry(0x1151, 5), entry(0x1160, 7)]);
+///
+/// assert_eq!(table.addrs_of("hot.c", 5), vec![Addr::from(0x1151usize)]);
+/// // line 6 has no code, so the next line with code is used
+/// assert_eq!(table.addrs_of("hot.c", 6), vec![Addr::from(0x1160usize)]);
+/// assert_eq!(table.line_of(Addr::from(0x1155usize)).unwrap().line, 5);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LineTable {
+    entries: Vec<LineEntry>,
+    files: Vec<SourceFile>,
+}
+
+impl LineTable {
+    /// Creates a [`LineTable`] from its rows, in any order
+    #[must_use]
+    pub fn new(mut entries: Vec<LineEntry>) -> Self {
+        entries.sort_by_key(|e| e.addr);
+        Self {
+            entries,
+            files: Vec::new(),
+        }
+    }
+
+    /// Sets the [`SourceFile`]s that have an MD5 digest or embedded text
+    #[must_use]
+    pub fn with_files(mut self, files: Vec<SourceFile>) -> Self {
+        self.files = files;
+        self
+    }
+
+    /// Reads the line programs of all units in `dwarf`
+    ///
+    /// # Parameters
+    ///
+    /// * `dwarf` - The DWARF information of the executable
+    /// * `executable` - The loaded executable, to rebase the addresses of the rows
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if the units or a line program cannot be parsed.
+    pub(crate) fn build(
+        dwarf: &gimli::Dwarf<GimliReaderThing>,
+        executable: &LoadedModule,
+    ) -> Result<Self> {
+        let mut entries = Vec::new();
+        let mut files: Vec<SourceFile> = Vec::new();
+        let mut units = dwarf.units();
+        while let Some(header) = units.next()? {
+            let unit = dwarf.unit(header)?;
+            let Some(program) = unit.line_program.clone() else {
+                continue;
+            };
+            let header = program.header();
+            for file in header.file_names() {
+                let md5 = header.file_has_md5().then(|| *file.md5());
+                let source = match file.source() {
+                    Some(source) => Some(
+                        dwarf
+                            .attr_string(&unit, source)?
+                            .to_string_lossy()?
+                            .to_string(),
+                    ),
+                    None => None,
+                }
+                // compilers write an empty string for files without embedded source
+                .filter(|s| !s.is_empty());
+                if md5.is_none() && source.is_none() {
+                    continue;
+                }
+                let path = file_path(dwarf, &unit, header, file)?;
+                if files.iter().all(|f| f.path != path) {
+                    files.push(SourceFile { path, md5, source });
+                }
+            }
+
+            let mut rows = program.rows();
+            while let Some((header, row)) = rows.next_row()? {
+                if row.end_sequence() || !row.is_stmt() {
+                    continue;
+                }
+                let (Some(line), Some(file)) = (row.line(), row.file(header)) else {
+                    continue;
+                };
+
+                entries.push(LineEntry {
+                    addr: executable.to_process(row.address()),
+                    file: file_path(dwarf, &unit, header, file)?,
+                    line: line.get(),
+                });
+            }
+        }
+        Ok(Self::new(entries).with_files(files))
+    }
+
+    /// Adds the rows and source files of `other`, like the line table of another file
+    pub(crate) fn merge(&mut self, other: Self) {
+        self.entries.extend(other.entries);
+        self.entries.sort_by_key(|e| e.addr);
+        for file in other.files {
+            if self.files.iter().all(|f| f.path != file.path) {
+                self.files.push(file);
+            }
+        }
+    }
+
+    /// Returns the rows, ordered by address
+    #[must_use]
+    pub fn entries(&self) -> &[LineEntry] {
+        &self.entries
+    }
+
+    /// Returns the [`SourceFile`] of `file`, if its digest or text was recorded
+    ///
+    /// `file` is matched like in [`Self::addrs_of`].
+    #[must_use]
+    pub fn source_file(&self, file: &str) -> Option<&SourceFile> {
+        self.files.iter().find(|f| file_matches(&f.path, file))
+    }
+
+    /// Returns the recorded path of `file`, like `/home/user/src/hot.c` for `hot.c`
+    ///
+    /// `file` is matched like in [`Self::addrs_of`].
+    #[must_use]
+    pub fn path_of(&self, file: &str) -> Option<&str> {
+        self.files
+            .iter()
+            .map(|f| f.path.as_str())
+            .chain(self.entries.iter().map(|e| e.file.as_str()))
+            .find(|path| file_matches(path, file))
+    }
+
+    /// Returns the addresses of the statements in `line` of `file`
+    ///
+    /// `file` matches a source file if it is its path or the end of its path, like `hot.c` or
+    /// `src/hot.c` for `/home/user/src/hot.c`. If `line` has no code, like a comment or an empty
+    /// line, the next line with code in the same file is used instead.
+    ///
+    /// # Returns
+    ///
+    /// The addresses ordered ascending, or an empty list if there is no code at or after `line`.
+    #[must_use]
+    pub fn addrs_of(&self, file: &str, line: u64) -> Vec<Addr> {
+        let in_file: Vec<&LineEntry> = self
+            .entries
+            .iter()
+            .filter(|e| file_matches(&e.file, file))
+            .collect();
+        let Some(best) = in_file.iter().map(|e| e.line).filter(|l| *l >= line).min() else {
+            return Vec::new();
+        };
+
+        let mut addrs: Vec<Addr> = in_file
+            .iter()
+            .filter(|e| e.line == best)
+            .map(|e| e.addr)
+            .collect();
+        addrs.dedup();
+        addrs
+    }
+
+    /// Returns the row of the statement that contains `addr`
+    ///
+    /// That is the last row at or before `addr`. It is only meaningful if `addr` is in the code
+    /// of the executable.
+    #[must_use]
+    pub fn line_of(&self, addr: Addr) -> Option<&LineEntry> {
+        let index = self.entries.partition_point(|e| e.addr <= addr);
+        index.checked_sub(1).map(|i| &self.entries[i])
+    }
+}
+
+/// Builds the path of a `file` of a line program, as recorded by the compiler
+///
+/// Relative paths are joined with the directory of the file and the compilation directory of
+/// `unit`, as far as they are known.
+///
+/// # Errors
+///
+/// This function can fail if the names cannot be read from the string sections.
+pub(crate) fn file_path(
+    dwarf: &gimli::Dwarf<GimliReaderThing>,
+    unit: &gimli::Unit<GimliReaderThing>,
+    header: &gimli::LineProgramHeader<GimliReaderThing>,
+    file: &gimli::FileEntry<GimliReaderThing>,
+) -> Result<String> {
+    let mut path = PathBuf::new();
+    if let Some(comp_dir) = &unit.comp_dir {
+        path.push(comp_dir.to_string_lossy()?.as_ref());
+    }
+    if let Some(dir) = file.directory(header) {
+        path.push(dwarf.attr_string(unit, dir)?.to_string_lossy()?.as_ref());
+    }
+    path.push(
+        dwarf
+            .attr_string(unit, file.path_name())?
+            .to_string_lossy()?
+            .as_ref(),
+    );
+    Ok(path.to_string_lossy().to_string())
+}
+
+/// Checks if the recorded source path `path` is the file `file` is referring to
+fn file_matches(path: &str, file: &str) -> bool {
+    path == file
+        || path
+            .strip_suffix(file)
+            .is_some_and(|prefix| prefix.ends_with('/'))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_addrs_of() {
+        let entry = |addr: usize, file: &str, line: u64| LineEntry {
+            addr: Addr::from(addr),
+            file: file.to_string(),
+            line,
+        };
+        let table = LineTable::new(vec![
+            entry(0x1200, "/src/other.c", 10),
+            entry(0x1180, "/src/hot.c", 10),
+            entry(0x1100, "/src/hot.c", 10),
+            entry(0x1110, "/src/hot.c", 12),
+        ]);
+
+        assert_eq!(
+            table.addrs_of("hot.c", 10),
+            vec![Addr::from(0x1100usize), Addr::from(0x1180usize)]
+        );
+        assert_eq!(
+            table.addrs_of("src/hot.c", 11),
+            vec![Addr::from(0x1110usize)]
+        );
+        assert!(table.addrs_of("hot.c", 13).is_empty());
+        assert!(table.addrs_of("t.c", 10).is_empty());
+        assert_eq!(
+            table.line_of(Addr::from(0x1210usize)).unwrap().file,
+            "/src/other.c"
+        );
+        assert!(table.line_of(Addr::from(0x10ffusize)).is_none());
+    }
+}
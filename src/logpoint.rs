@@ -0,0 +1,191 @@
+//! # Logging Breakpoints
+//!
+//! Provides the message formats of logging breakpoints, which are like `dprintf` in GDB.
+//!
+//! A [`Breakpoint`](crate::breakpoint::Breakpoint) with a [`LogFormat`] does not stop the
+//! debuggee. When it is hit, the debugger fills in the format, shows the message to the user as
+//! [`Feedback::Text`](crate::feedback::Feedback::Text) and continues the debuggee right away.
+//! This is useful for tracing code that runs often, where stopping for every hit would change the
+//! timing of the program too much.
+//!
+//! A format is text with expressions in braces, like `x = {x}, rax = {rax}`. An expression is the
+//! name of a register or a variable expression, see
+//! [`Debugger::read_variable`](crate::debugger::Debugger::read_variable). Registers are shown in
+//! hexadecimal. Braces are written as `{{` and `}}`.
+
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::errors::DebuggerError;
+
+/// A part of a [`LogFormat`]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum LogPart {
+    /// Text that is shown as it is
+    Text(String),
+    /// A register or variable expression, whose value is filled in
+    Expr(String),
+}
+
+/// The message format of a logging breakpoint
+///
+/// # Examples
+///
+/// ```
+/// use coreminer::logpoint::{LogFormat, LogPart};
+///
+/// let format: LogFormat = "i = {i}, rdi = {rdi}".parse().unwrap();
+/// assert_eq!(
+///     format.parts(),
+///     [
+///         LogPart::Text("i = ".to_string()),
+///         LogPart::Expr("i".to_string()),
+///         LogPart::Text(", rdi = ".to_string()),
+///         LogPart::Expr("rdi".to_string()),
+///     ]
+/// );
+///
+/// let message = format.render(|expr| match expr {
+///     "i" => "3".to_string(),
+///     _ => "0x10".to_string(),
+/// });
+/// assert_eq!(message, "i = 3, rdi = 0x10");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct LogFormat {
+    parts: Vec<LogPart>,
+}
+
+impl LogFormat {
+    /// Returns the parts of the format, in order
+    #[must_use]
+    pub fn parts(&self) -> &[LogPart] {
+        &self.parts
+    }
+
+    /// Fills in the format, with `value` giving the value of each expression
+    pub fn render(&self, mut value: impl FnMut(&str) -> String) -> String {
+        let mut message = String::new();
+        for part in &self.parts {
+            match part {
+                LogPart::Text(text) => message.push_str(text),
+                LogPart::Expr(expr) => message.push_str(&value(expr)),
+            }
+        }
+        message
+    }
+}
+
+impl FromStr for LogFormat {
+    type Err = DebuggerError;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let invalid = || DebuggerError::InvalidLogFormat(s.to_string());
+        let mut parts = Vec::new();
+        let mut text = String::new();
+        let mut chars = s.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    text.push('{');
+                }
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                    text.push('}');
+                }
+                '{' => {
+                    let mut expr = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some('{') | None => return Err(invalid()),
+                            Some(c) => expr.push(c),
+                        }
+                    }
+                    let expr = expr.trim();
+                    if expr.is_empty() {
+                        return Err(invalid());
+                    }
+                    if !text.is_empty() {
+                        parts.push(LogPart::Text(std::mem::take(&mut text)));
+                    }
+                    parts.push(LogPart::Expr(expr.to_string()));
+                }
+                '}' => return Err(invalid()),
+                c => text.push(c),
+            }
+        }
+        if !text.is_empty() {
+            parts.push(LogPart::Text(text));
+        }
+        Ok(Self { parts })
+    }
+}
+
+impl TryFrom<String> for LogFormat {
+    type Error = DebuggerError;
+
+    fn try_from(value: String) -> std::result::Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<LogFormat> for String {
+    fn from(value: LogFormat) -> Self {
+        value.to_string()
+    }
+}
+
+impl Display for LogFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for part in &self.parts {
+            match part {
+                LogPart::Text(text) => write!(f, "{}", text.replace('{', "{{").replace('}', "}}"))?,
+                LogPart::Expr(expr) => write!(f, "{{{expr}}}")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_log_format() {
+        let format: LogFormat = "hit {{main}} with { argc } args".parse().unwrap();
+        assert_eq!(
+            format.parts(),
+            [
+                LogPart::Text("hit {main} with ".to_string()),
+                LogPart::Expr("argc".to_string()),
+                LogPart::Text(" args".to_string()),
+            ]
+        );
+        assert_eq!(format.to_string(), "hit {{main}} with {argc} args");
+        assert_eq!(format.to_string().parse::<LogFormat>().unwrap(), format);
+
+        assert!("".parse::<LogFormat>().unwrap().parts().is_empty());
+        assert!("{unclosed".parse::<LogFormat>().is_err());
+        assert!("stray }".parse::<LogFormat>().is_err());
+        assert!("empty {}".parse::<LogFormat>().is_err());
+        assert!("nested {a{b}}".parse::<LogFormat>().is_err());
+    }
+
+    #[test]
+    fn test_render_log_format() {
+        let format: LogFormat = "{a}{b}!".parse().unwrap();
+        let mut asked = Vec::new();
+        let message = format.render(|expr| {
+            asked.push(expr.to_string());
+            expr.to_uppercase()
+        });
+        assert_eq!(message, "AB!");
+        assert_eq!(asked, ["a", "b"]);
+    }
+}
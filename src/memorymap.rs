@@ -199,6 +199,21 @@ impl From<Vec<proc_maps::MapRange>> for ProcessMemoryMap {
     }
 }
 
+impl ProcessMemoryMap {
+    /// Returns the lowest start address of the regions that map the file `path`
+    ///
+    /// This is where a module is loaded, since the first region of a module maps the start of
+    /// its file with the ELF header.
+    #[must_use]
+    pub fn lowest_address_of(&self, path: &str) -> Option<Addr> {
+        self.regions
+            .iter()
+            .filter(|r| r.path.as_deref() == Some(path))
+            .map(|r| r.start_address)
+            .min()
+    }
+}
+
 impl Display for ProcessMemoryMap {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         writeln!(f, "Process Memory Map:")?;
@@ -0,0 +1,197 @@
+//! # Memory Read Module
+//!
+//! Provides reading ranges of memory from a debugged process that may be partially unreadable.
+//!
+//! A range that starts in mapped memory can cross into a page that is unmapped or not readable,
+//! which happens all the time when dumping the memory around the stack pointer near the top of
+//! the stack. Instead of failing the whole read, [`read_memory`] returns the bytes up to the
+//! first unreadable byte and describes the rest of the range in an [`UnreadableRange`].
+
+use std::fmt::{self, Display};
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+
+use nix::unistd::Pid;
+use serde::Serialize;
+
+use crate::errors::Result;
+use crate::Addr;
+
+/// Number of bytes in a line of the hexdump of a [`MemoryRead`]
+const HEXDUMP_WIDTH: usize = 16;
+
+/// The part of a [`MemoryRead`] that could not be read
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+pub struct UnreadableRange {
+    /// Address of the first byte that could not be read
+    pub addr: Addr,
+    /// Number of bytes from `addr` to the end of the requested range
+    pub len: usize,
+    /// Why the memory could not be read, like `Input/output error (os error 5)` for memory that
+    /// is not mapped
+    pub reason: String,
+}
+
+/// The result of reading a range of memory
+///
+/// # Examples
+///
+/// ```
+/// use coreminer::memread::{MemoryRead, UnreadableRange};
+/// use coreminer::addr::Addr;
+///
+/// let read = MemoryRead {
+///     addr: Addr::from(0x1000usize),
+///     bytes: vec![0xde, 0xad],
+///     unreadable: Some(UnreadableRange {
+///         addr: Addr::from(0x1002usize),
+///         len: 2,
+///         reason: "Input/output error (os error 5)".to_string(),
+///     }),
+/// };
+/// assert_eq!(read.requested_len(), 4);
+/// assert!(!read.is_complete());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+pub struct MemoryRead {
+    /// Start of the requested range
+    pub addr: Addr,
+    /// The bytes that could be read, from the start of the range
+    pub bytes: Vec<u8>,
+    /// The rest of the range, if not all of it could be read
+    pub unreadable: Option<UnreadableRange>,
+}
+
+impl MemoryRead {
+    /// Returns if the whole requested range could be read
+    #[must_use]
+    pub fn is_complete(&self) -> bool {
+        self.unreadable.is_none()
+    }
+
+    /// Returns the length of the requested range
+    #[must_use]
+    pub fn requested_len(&self) -> usize {
+        self.bytes.len() + self.unreadable.as_ref().map_or(0, |u| u.len)
+    }
+}
+
+impl Display for MemoryRead {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, line) in self.bytes.chunks(HEXDUMP_WIDTH).enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{}:", self.addr + i * HEXDUMP_WIDTH)?;
+            for byte in line {
+                write!(f, " {byte:02x}")?;
+            }
+        }
+        if let Some(unreadable) = &self.unreadable {
+            if !self.bytes.is_empty() {
+                writeln!(f)?;
+            }
+            write!(
+                f,
+                "{}: {} bytes could not be read: {}",
+                unreadable.addr, unreadable.len, unreadable.reason
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Reads `len` bytes at `addr` from the memory of the process `pid`
+///
+/// Reading stops at the first byte that cannot be read, the rest of the range is described in
+/// [`MemoryRead::unreadable`]. If not even the first byte can be read, the read still succeeds,
+/// with no bytes and the whole range unreadable.
+///
+/// # Errors
+///
+/// This function fails if the memory of the process cannot be opened at all, for example
+/// because the process does not exist anymore.
+pub fn read_memory(pid: Pid, addr: Addr, len: usize) -> Result<MemoryRead> {
+    let mut file = File::options()
+        .read(true)
+        .write(false)
+        .open(format!("/proc/{pid}/mem"))?;
+
+    let mut bytes = vec![0; len];
+    let mut done = 0;
+    let mut failure = None;
+    while done < len {
+        // a read that crosses into an unreadable page stops short before it, the next read then
+        // fails with the reason
+        let result = file
+            .seek(SeekFrom::Start((addr + done).into()))
+            .and_then(|_| file.read(&mut bytes[done..]));
+        match result {
+            Ok(0) => {
+                failure = Some("end of the address space".to_string());
+                break;
+            }
+            Ok(n) => done += n,
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+            Err(e) => {
+                failure = Some(e.to_string());
+                break;
+            }
+        }
+    }
+    bytes.truncate(done);
+
+    Ok(MemoryRead {
+        addr,
+        bytes,
+        unreadable: failure.map(|reason| UnreadableRange {
+            addr: addr + done,
+            len: len - done,
+            reason,
+        }),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_read_memory_partial() {
+        use nix::libc;
+
+        // map two pages and unmap the second one, then read across the boundary
+        // SAFETY: sysconf has no preconditions
+        let page = usize::try_from(unsafe { libc::sysconf(libc::_SC_PAGESIZE) }).unwrap();
+        // SAFETY: a fresh anonymous mapping, only used by this test
+        let base = unsafe {
+            libc::mmap(
+                std::ptr::null_mut(),
+                page * 2,
+                libc::PROT_READ | libc::PROT_WRITE,
+                libc::MAP_PRIVATE | libc::MAP_ANONYMOUS,
+                -1,
+                0,
+            )
+        };
+        assert_ne!(base, libc::MAP_FAILED);
+        let start = base as usize;
+        // SAFETY: both addresses are inside of the mapping
+        unsafe {
+            *((start + page - 1) as *mut u8) = 0xab;
+            assert_eq!(libc::munmap((start + page) as *mut libc::c_void, page), 0);
+        }
+
+        let read = read_memory(nix::unistd::getpid(), Addr::from(start + page - 4), 8).unwrap();
+        assert_eq!(read.bytes, vec![0, 0, 0, 0xab]);
+        let unreadable = read.unreadable.as_ref().unwrap();
+        assert_eq!(unreadable.addr, Addr::from(start + page));
+        assert_eq!(unreadable.len, 4);
+        assert_eq!(read.requested_len(), 8);
+
+        // SAFETY: the first page is still mapped and not used anymore
+        unsafe {
+            libc::munmap(base, page);
+        }
+    }
+}
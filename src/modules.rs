@@ -0,0 +1,268 @@
+//! # Modules Module
+//!
+//! Provides the modules loaded into a debugged process and where they were loaded.
+//!
+//! A module is an ELF file mapped into the process: the executable, the dynamic loader and the
+//! shared libraries. Each module has a base address, the lowest address it is mapped at, and a
+//! load bias, the difference between the addresses in the process and the virtual addresses in
+//! the ELF file. The bias is what has to be added to an address from the symbols or the debug
+//! information of a module. Position independent modules have a bias equal to their base, other
+//! executables are loaded at the addresses of their file and have a bias of zero.
+//!
+//! The executable is found by the path of `/proc/<pid>/exe`, not by assuming that it is the first
+//! mapping of the process, which is not true for every process, for example when an anonymous
+//! mapping was placed below the executable.
+
+use std::path::Path;
+
+use nix::unistd::Pid;
+use object::elf::{FileHeader64, ProgramHeader64};
+use object::LittleEndian as LE;
+use serde::Serialize;
+use tracing::{debug, warn};
+
+use crate::addr::ModuleAddr;
+use crate::dump::{load_range, pod, pod_slice};
+use crate::errors::{DebuggerError, Result};
+use crate::memorymap::ProcessMemoryMap;
+use crate::procfs::AuxVector;
+use crate::{mem_read, Addr};
+
+/// Size of a memory page
+const PAGE_SIZE: usize = 0x1000;
+/// Size of the ELF header
+const EHDR_SIZE: usize = size_of::<FileHeader64<LE>>();
+/// Size of a program header
+const PHDR_SIZE: usize = size_of::<ProgramHeader64<LE>>();
+
+/// A module loaded into the process
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+pub struct LoadedModule {
+    /// Path of the mapped file
+    pub path: String,
+    /// Lowest address the module is mapped at
+    pub base: Addr,
+    /// Difference between the addresses in the process and the virtual addresses of the file
+    pub bias: usize,
+}
+
+impl LoadedModule {
+    /// Translates a virtual address of the ELF file to the address in the process
+    #[must_use]
+    pub fn to_process(&self, file_addr: u64) -> Addr {
+        self.at(file_addr).rebase(self.bias)
+    }
+
+    /// Returns the [`ModuleAddr`] of the virtual address `file_addr` of the ELF file
+    #[must_use]
+    pub fn at(&self, file_addr: u64) -> ModuleAddr {
+        ModuleAddr::new(self.path.as_str(), file_addr)
+    }
+
+    /// Translates an address in the process to the virtual address of the ELF file
+    ///
+    /// # Returns
+    ///
+    /// [`None`] if `addr` is below the load bias and cannot be in the module.
+    #[must_use]
+    pub fn to_module(&self, addr: Addr) -> Option<ModuleAddr> {
+        let file_addr = addr.usize().checked_sub(self.bias)?;
+        Some(self.at(file_addr as u64))
+    }
+}
+
+/// Finds the module that contains `addr`, which is the one with the highest base below it
+pub(crate) fn module_at(modules: &[LoadedModule], addr: Addr) -> Option<&LoadedModule> {
+    modules
+        .iter()
+        .filter(|m| m.base <= addr)
+        .max_by_key(|m| m.base)
+}
+
+/// Returns the ELF modules mapped into the process `pid`, ordered by their base address
+///
+/// Mapped files that are not ELF files, like locale archives, are left out.
+///
+/// # Errors
+///
+/// This function fails if the memory map of the process cannot be read.
+///
+/// # Examples
+///
+/// ```no_run
+/// use coreminer::modules::loaded_modules;
+/// use nix::unistd::Pid;
+///
+/// for module in loaded_modules(Pid::from_raw(1234)).unwrap() {
+///     println!("{} at {} (bias {:#x})", module.path, module.base, module.bias);
+/// }
+/// ```
+pub fn loaded_modules(pid: Pid) -> Result<Vec<LoadedModule>> {
+    let map: ProcessMemoryMap = proc_maps::get_process_maps(pid.into())?.into();
+    let mut modules = Vec::new();
+    for region in &map.regions {
+        let Some(path) = region.path.as_deref() else {
+            continue;
+        };
+        if !path.starts_with('/') || modules.iter().any(|m: &LoadedModule| m.path == path) {
+            continue;
+        }
+        let Some(base) = map.lowest_address_of(path) else {
+            continue;
+        };
+        match module_bias(pid, base) {
+            Ok(bias) => modules.push(LoadedModule {
+                path: path.to_string(),
+                base,
+                bias,
+            }),
+            Err(e) => debug!("{path} at {base} is not an ELF module: {e}"),
+        }
+    }
+    modules.sort_by_key(|m| m.base);
+    Ok(modules)
+}
+
+/// Returns the base address of the executable of the process `pid`
+///
+/// This is the lowest mapping of the file `/proc/<pid>/exe` points to. If the executable cannot
+/// be found in the memory map, like when it was replaced on disk, the page of its program
+/// headers from the auxiliary vector is used, and the first mapping as the last resort.
+///
+/// # Errors
+///
+/// This function fails if the memory map of the process cannot be read or is empty.
+pub fn executable_base(pid: Pid) -> Result<Addr> {
+    let map: ProcessMemoryMap = proc_maps::get_process_maps(pid.into())?.into();
+    if let Ok(exe) = std::fs::read_link(format!("/proc/{pid}/exe")) {
+        if let Some(base) = exe_path_candidates(&exe)
+            .iter()
+            .find_map(|path| map.lowest_address_of(path))
+        {
+            return Ok(base);
+        }
+        warn!("{} is not in the memory map", exe.display());
+    }
+
+    if let Some(phdr) = AuxVector::read(pid).ok().and_then(|auxv| auxv.phdr) {
+        let page = Addr::from(phdr.usize() & !(PAGE_SIZE - 1));
+        if map
+            .regions
+            .iter()
+            .any(|r| r.start_address <= page && page < r.end_address)
+        {
+            return Ok(page);
+        }
+    }
+
+    map.regions
+        .first()
+        .map(|r| r.start_address)
+        .ok_or(DebuggerError::NoDebugee)
+}
+
+/// Returns the executable of the process `pid` as a [`LoadedModule`]
+///
+/// The executable is found like in [`executable_base`], its load bias is read from its ELF header
+/// in the memory of the process. This is the bias for the symbols, the debug information and the
+/// line table of the executable, and zero for executables that are not position independent.
+///
+/// # Errors
+///
+/// This function fails if the memory map of the process cannot be read or the ELF header of the
+/// executable cannot be read from its memory.
+///
+/// # Examples
+///
+/// ```no_run
+/// use coreminer::modules::executable_module;
+/// use nix::unistd::Pid;
+///
+/// let exe = executable_module(Pid::from_raw(1234)).unwrap();
+/// // the address of `main` in the process, if it is at 0x1139 in the file
+/// println!("{}", exe.to_process(0x1139));
+/// ```
+pub fn executable_module(pid: Pid) -> Result<LoadedModule> {
+    let base = executable_base(pid)?;
+    let path = std::fs::read_link(format!("/proc/{pid}/exe"))
+        .map(|p| p.to_string_lossy().to_string())
+        .unwrap_or_default();
+    Ok(LoadedModule {
+        bias: module_bias(pid, base)?,
+        path,
+        base,
+    })
+}
+
+/// Returns the paths under which the executable `exe` can be in the memory map
+///
+/// The kernel appends ` (deleted)` to both the link and the mapped path of a deleted file.
+fn exe_path_candidates(exe: &Path) -> Vec<String> {
+    let exe = exe.to_string_lossy();
+    let mut paths = vec![exe.to_string()];
+    if let Some(stripped) = exe.strip_suffix(" (deleted)") {
+        paths.push(stripped.to_string());
+    }
+    paths
+}
+
+/// Reads the ELF headers of a module at `base` and calculates its load bias
+fn module_bias(pid: Pid, base: Addr) -> Result<usize> {
+    elf_bias(base, |buf, addr| mem_read(buf, pid, addr))
+}
+
+/// Calculates the load bias of a module at `base` from its ELF headers, which are read with
+/// `read` like with [`mem_read`]
+///
+/// # Errors
+///
+/// This function fails if the headers cannot be read or are not valid ELF headers.
+pub(crate) fn elf_bias(
+    base: Addr,
+    read: impl Fn(&mut [u8], Addr) -> Result<usize>,
+) -> Result<usize> {
+    let mut header = [0; EHDR_SIZE];
+    read(&mut header, base)?;
+    let (ehdr, _) = pod::<FileHeader64<LE>>(&header)?;
+    if ehdr.e_ident.magic != object::elf::ELFMAG {
+        return Err(DebuggerError::InvalidElf("no ELF magic".to_string()));
+    }
+    if ehdr.e_type.get(LE) != object::elf::ET_DYN {
+        return Ok(0);
+    }
+
+    let count = usize::from(ehdr.e_phnum.get(LE));
+    let mut phdrs = vec![0; count * PHDR_SIZE];
+    read(&mut phdrs, base + ehdr.e_phoff.get(LE) as usize)?;
+    let phdrs = pod_slice::<ProgramHeader64<LE>>(&phdrs, count)?;
+    let (lo, _) = load_range(phdrs)?;
+    Ok(base.usize().wrapping_sub(lo as usize))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_loaded_modules() {
+        let pid = nix::unistd::getpid();
+        let exe = std::fs::read_link("/proc/self/exe").unwrap();
+        let modules = loaded_modules(pid).unwrap();
+
+        let own = modules
+            .iter()
+            .find(|m| Path::new(&m.path) == exe)
+            .expect("the test binary is not a loaded module");
+        assert_eq!(executable_base(pid).unwrap(), own.base);
+        assert_eq!(executable_module(pid).unwrap().bias, own.bias);
+
+        // the address of a function in the process is its address in the file plus the bias
+        let file = std::fs::read(&exe).unwrap();
+        let elf = object::File::parse(&*file).unwrap();
+        let entry = object::Object::entry(&elf);
+        let auxv = AuxVector::read(pid).unwrap();
+        assert_eq!(Some(own.to_process(entry)), auxv.entry);
+        assert_eq!(own.to_module(auxv.entry.unwrap()), Some(own.at(entry)));
+        assert!(modules.windows(2).all(|w| w[0].base <= w[1].base));
+    }
+}
@@ -0,0 +1,310 @@
+//! # Page Guard Module
+//!
+//! Provides watchpoints on large memory ranges of a debugged process, implemented with the
+//! memory protection of the pages that contain them.
+//!
+//! A [`Watchpoint`] needs the debuggee to be single stepped, and hardware watchpoints in the
+//! debug registers only cover a few bytes. A [`PageGuard`] instead removes the write permission
+//! from the pages of the watched range by injecting `mprotect` into the debuggee, which can then
+//! run at full speed. Each write into those pages causes a `SIGSEGV`, at which the debugger
+//! restores the original protection, single steps the faulting instruction, compares the watched
+//! range with its snapshot and removes the write permission again. The `SIGSEGV` never reaches
+//! the debuggee.
+//!
+//! Writes to other data on the guarded pages are caught as well and stepped over silently, which
+//! makes them slow. There are some more limits:
+//!
+//! - Syscalls that write into the guarded pages, like `read`, fail with `EFAULT` instead of
+//!   causing a `SIGSEGV`.
+//! - If the debuggee changes the protection of the guarded pages itself, the guard is lost or
+//!   the changed protection is overwritten when the guard is removed.
+//! - Only writes of the traced thread are caught correctly.
+
+use nix::libc::{PROT_EXEC, PROT_READ, PROT_WRITE};
+use nix::unistd::Pid;
+use serde::Serialize;
+use tracing::trace;
+
+use crate::addr::AddrRange;
+use crate::errors::{DebuggerError, Result};
+use crate::inject::change_protection;
+use crate::memorymap::ProcessMemoryMap;
+use crate::watchpoint::{WatchCondition, Watchpoint, WatchpointHit};
+use crate::Addr;
+
+/// Size of a memory page
+pub const PAGE_SIZE: usize = 0x1000;
+
+/// Watches a large range of memory in the debugged process for changes by write protecting it
+///
+/// A [`PageGuard`] is set up with [`PageGuard::new`] and armed with [`PageGuard::arm`]. Once
+/// the debuggee receives a `SIGSEGV` for an address that [`PageGuard::guards`], the guard is
+/// disarmed, the faulting instruction is executed and [`PageGuard::check`] tells if the watched
+/// range was changed. Afterwards, the guard is armed again.
+///
+/// # Examples
+///
+/// ```no_run
+/// use coreminer::pageguard::PageGuard;
+/// use coreminer::memorymap::ProcessMemoryMap;
+/// use coreminer::addr::Addr;
+/// use nix::unistd::Pid;
+///
+/// // Watch 1 MiB at 0x00007f3a1c000000 of the process with PID 1234
+/// let pid = Pid::from_raw(1234);
+/// let map = ProcessMemoryMap::from(proc_maps::get_process_maps(pid.as_raw()).unwrap());
+/// let mut guard = PageGuard::new(pid, Addr::from(0x00007f3a1c000000usize), 1 << 20, &map).unwrap();
+/// guard.arm(pid).unwrap();
+/// ```
+#[derive(Debug, Clone, Hash, Serialize)]
+pub struct PageGuard {
+    watchpoint: Watchpoint,
+    pages: Vec<GuardedPages>,
+    armed: bool,
+}
+
+/// Pages of a [`PageGuard`] that are in the same memory region and have the same protection
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+struct GuardedPages {
+    start: Addr,
+    len: usize,
+    /// The original protection as `PROT_*` flags
+    prot: i32,
+}
+
+impl PageGuard {
+    /// Creates a new [`PageGuard`] and takes the initial snapshot of the memory range
+    ///
+    /// The guard is not armed yet, see [`PageGuard::arm`].
+    ///
+    /// # Parameters
+    ///
+    /// * `pid` - Process ID of the target process
+    /// * `addr` - Start address of the memory range to watch
+    /// * `len` - Length of the memory range to watch in bytes
+    /// * `map` - The current memory map of the process
+    ///
+    /// # Errors
+    ///
+    /// This function will fail if the memory range is empty, if any page of it is not mapped
+    /// writable, or if the memory of the debuggee cannot be read.
+    pub fn new(pid: Pid, addr: Addr, len: usize, map: &ProcessMemoryMap) -> Result<Self> {
+        let pages = guarded_pages(addr, len, map)?;
+        Ok(Self {
+            watchpoint: Watchpoint::new(pid, addr, len)?,
+            pages,
+            armed: false,
+        })
+    }
+
+    /// Removes the write permission from the guarded pages
+    ///
+    /// Does nothing if the guard is already armed.
+    ///
+    /// # Errors
+    ///
+    /// This function will fail if `mprotect` cannot be injected into the debuggee or fails.
+    pub fn arm(&mut self, pid: Pid) -> Result<()> {
+        if self.armed {
+            return Ok(());
+        }
+        for pages in &self.pages {
+            trace!("write protecting {} bytes at {}", pages.len, pages.start);
+            change_protection(pid, pages.start, pages.len, pages.prot & !PROT_WRITE)?;
+        }
+        self.armed = true;
+        Ok(())
+    }
+
+    /// Restores the original protection of the guarded pages
+    ///
+    /// Does nothing if the guard is not armed.
+    ///
+    /// # Errors
+    ///
+    /// This function will fail if `mprotect` cannot be injected into the debuggee or fails.
+    pub fn disarm(&mut self, pid: Pid) -> Result<()> {
+        if !self.armed {
+            return Ok(());
+        }
+        for pages in &self.pages {
+            trace!(
+                "restoring the protection of {} bytes at {}",
+                pages.len,
+                pages.start
+            );
+            change_protection(pid, pages.start, pages.len, pages.prot)?;
+        }
+        self.armed = false;
+        Ok(())
+    }
+
+    /// Returns `true` if `addr` is on one of the guarded pages
+    ///
+    /// A write to such an address causes a `SIGSEGV` while the guard is armed.
+    #[must_use]
+    pub fn guards(&self, addr: Addr) -> bool {
+        self.pages
+            .iter()
+            .any(|p| p.start <= addr && addr < p.start + p.len)
+    }
+
+    /// Checks if the watched memory range has changed since the last snapshot
+    ///
+    /// See [`Watchpoint::check`].
+    ///
+    /// # Errors
+    ///
+    /// This function will fail if the memory of the debuggee cannot be read.
+    pub fn check(&mut self, pid: Pid, instruction: Addr) -> Result<Option<WatchpointHit>> {
+        self.watchpoint.check(pid, instruction)
+    }
+
+    /// Sets or removes the [`WatchCondition`] that a change must fulfill to be reported
+    ///
+    /// See [`Watchpoint::set_condition`].
+    ///
+    /// # Errors
+    ///
+    /// This function will fail if a condition is given and the guarded memory range is wider
+    /// than [`MAX_CONDITION_WIDTH`](crate::watchpoint::MAX_CONDITION_WIDTH).
+    pub fn set_condition(&mut self, condition: Option<WatchCondition>) -> Result<()> {
+        self.watchpoint.set_condition(condition)
+    }
+
+    /// Returns the [`WatchCondition`] that a change must fulfill to be reported, if there is one
+    #[inline]
+    #[must_use]
+    pub fn condition(&self) -> Option<&WatchCondition> {
+        self.watchpoint.condition()
+    }
+
+    /// Returns the start address of the watched memory range
+    #[inline]
+    #[must_use]
+    pub fn addr(&self) -> Addr {
+        self.watchpoint.addr()
+    }
+
+    /// Returns the length of the watched memory range in bytes
+    #[inline]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.watchpoint.len()
+    }
+
+    /// Returns `true` if the watched memory range has a length of zero
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.watchpoint.is_empty()
+    }
+
+    /// Returns `true` if the guarded pages are currently write protected
+    #[inline]
+    #[must_use]
+    pub fn is_armed(&self) -> bool {
+        self.armed
+    }
+}
+
+/// Splits the pages containing `len` bytes at `addr` by the memory regions of `map`
+fn guarded_pages(addr: Addr, len: usize, map: &ProcessMemoryMap) -> Result<Vec<GuardedPages>> {
+    if len == 0 {
+        return Err(DebuggerError::EmptyAddressRange(addr, addr));
+    }
+    let first = addr.align_down(PAGE_SIZE).usize();
+    let end = AddrRange::with_len(addr, len)
+        .and_then(|range| range.end().align_up(PAGE_SIZE))
+        .ok_or(DebuggerError::AddressOverflow(addr, len))?
+        .usize();
+
+    let mut pages = Vec::new();
+    let mut at = first;
+    while at < end {
+        let region = map
+            .regions
+            .iter()
+            .find(|r| r.start_address.usize() <= at && at < r.end_address.usize())
+            .filter(|r| r.permissions.write)
+            .ok_or(DebuggerError::NotWritableMemory(Addr::from(at)))?;
+
+        let until = region.end_address.usize().min(end);
+        let mut prot = PROT_WRITE;
+        if region.permissions.read {
+            prot |= PROT_READ;
+        }
+        if region.permissions.execute {
+            prot |= PROT_EXEC;
+        }
+        pages.push(GuardedPages {
+            start: Addr::from(at),
+            len: until - at,
+            prot,
+        });
+        at = until;
+    }
+    Ok(pages)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::memorymap::{MemoryPermissions, MemoryRegion};
+
+    fn region(start: usize, end: usize, write: bool, execute: bool) -> MemoryRegion {
+        MemoryRegion {
+            start_address: Addr::from(start),
+            end_address: Addr::from(end),
+            size: end - start,
+            permissions: MemoryPermissions {
+                read: true,
+                write,
+                execute,
+                shared: false,
+                private: true,
+            },
+            offset: 0,
+            device: "00:00".to_string(),
+            inode: 0,
+            path: None,
+        }
+    }
+
+    #[test]
+    fn test_guarded_pages() {
+        let map = ProcessMemoryMap::from(Vec::new());
+        let map = ProcessMemoryMap {
+            regions: vec![
+                region(0x1000, 0x3000, true, false),
+                region(0x3000, 0x5000, true, true),
+                region(0x5000, 0x6000, false, false),
+            ],
+            ..map
+        };
+
+        let pages = guarded_pages(Addr::from(0x1ff0usize), 0x1020, &map).unwrap();
+        assert_eq!(
+            pages,
+            [
+                GuardedPages {
+                    start: Addr::from(0x1000usize),
+                    len: 0x2000,
+                    prot: PROT_READ | PROT_WRITE,
+                },
+                GuardedPages {
+                    start: Addr::from(0x3000usize),
+                    len: 0x1000,
+                    prot: PROT_READ | PROT_WRITE | PROT_EXEC,
+                },
+            ]
+        );
+
+        assert!(matches!(
+            guarded_pages(Addr::from(0x4ff0usize), 0x20, &map),
+            Err(DebuggerError::NotWritableMemory(addr)) if addr == Addr::from(0x5000usize)
+        ));
+        assert!(guarded_pages(Addr::from(0x8000usize), 1, &map).is_err());
+        assert!(guarded_pages(Addr::from(0x1000usize), 0, &map).is_err());
+    }
+}
@@ -0,0 +1,332 @@
+//! # Perf Module
+//!
+//! Provides hardware performance counters for a debugged process, and the `perf_event_open`
+//! interface that they share with [uprobes](crate::uprobe).
+//!
+//! [`PerfCounters`] count the instructions, CPU cycles and cache misses of the debuggee, so that
+//! the work between two breakpoints can be measured. The counters are scoped to the debuggee and
+//! only count while it runs in user space, the time the debuggee spends stopped by the debugger
+//! is not counted. Some limits apply:
+//!
+//! - The CPU needs a performance monitoring unit that the kernel supports. Virtual machines often
+//!   have none.
+//! - The debugger needs to be allowed to use `perf_event_open` for the debuggee, see
+//!   `/proc/sys/kernel/perf_event_paranoid`.
+//! - Only the traced thread and threads and processes it creates after the counters were opened
+//!   are counted.
+//! - Code that the debugger [injects](crate::inject) into the debuggee is counted as well.
+//! - If there are more counters than the CPU has, the kernel multiplexes them and the values are
+//!   estimated from the time each counter could count.
+
+use std::fmt::{self, Display};
+use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+
+use nix::libc;
+use nix::unistd::Pid;
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use crate::errors::{DebuggerError, Result};
+
+/// Size of the [`PerfEventAttr`] in its third version, which has `sample_regs_user`
+pub(crate) const PERF_ATTR_SIZE_VER3: u32 = 96;
+/// Open the perf event file descriptor with `O_CLOEXEC`
+const PERF_FLAG_FD_CLOEXEC: libc::c_ulong = 1 << 3;
+/// Perf event type of the generalized hardware events
+const PERF_TYPE_HARDWARE: u32 = 0;
+/// The counter starts disabled
+const ATTR_DISABLED: u64 = 1 << 0;
+/// Threads and processes created later are counted too
+const ATTR_INHERIT: u64 = 1 << 1;
+/// Do not count in the kernel
+const ATTR_EXCLUDE_KERNEL: u64 = 1 << 5;
+/// Do not count in the hypervisor
+const ATTR_EXCLUDE_HV: u64 = 1 << 6;
+/// Read the time in which the counter was enabled
+const PERF_FORMAT_TOTAL_TIME_ENABLED: u64 = 1 << 0;
+/// Read the time in which the counter was actually counting
+const PERF_FORMAT_TOTAL_TIME_RUNNING: u64 = 1 << 1;
+/// `ioctl` request to enable a counter
+const PERF_EVENT_IOC_ENABLE: libc::c_ulong = 0x2400;
+/// `ioctl` request to disable a counter
+const PERF_EVENT_IOC_DISABLE: libc::c_ulong = 0x2401;
+/// `ioctl` request to reset a counter to zero
+const PERF_EVENT_IOC_RESET: libc::c_ulong = 0x2403;
+
+/// The `perf_event_attr` of the kernel, up to `sample_regs_user`
+#[repr(C)]
+#[derive(Debug, Default)]
+pub(crate) struct PerfEventAttr {
+    pub(crate) kind: u32,
+    pub(crate) size: u32,
+    pub(crate) config: u64,
+    pub(crate) sample_period: u64,
+    pub(crate) sample_type: u64,
+    pub(crate) read_format: u64,
+    /// The bit-fields of the kernel struct, like [`ATTR_DISABLED`]
+    pub(crate) flags: u64,
+    pub(crate) wakeup_events: u32,
+    pub(crate) bp_type: u32,
+    /// The path of the probed file for uprobes
+    pub(crate) config1: u64,
+    /// The offset in the probed file for uprobes
+    pub(crate) config2: u64,
+    pub(crate) branch_sample_type: u64,
+    pub(crate) sample_regs_user: u64,
+    pub(crate) sample_stack_user: u32,
+    pub(crate) clockid: i32,
+}
+
+/// Opens a perf event for the thread `pid` on any CPU
+///
+/// # Errors
+///
+/// This function fails if the kernel rejects `attr`, for example because the event is not
+/// supported or the debugger lacks the permission.
+pub(crate) fn perf_event_open(attr: &PerfEventAttr, pid: Pid) -> Result<OwnedFd> {
+    // SAFETY: attr is a valid perf_event_attr of the given size, and anything it points to lives
+    // until the syscall returned
+    let ret = unsafe {
+        libc::syscall(
+            libc::SYS_perf_event_open,
+            std::ptr::from_ref(attr),
+            libc::pid_t::from(pid),
+            -1,
+            -1,
+            PERF_FLAG_FD_CLOEXEC,
+        )
+    };
+    nix::errno::Errno::result(ret)?;
+    // SAFETY: perf_event_open returned a new file descriptor, which is owned by no one else
+    Ok(unsafe { OwnedFd::from_raw_fd(ret as i32) })
+}
+
+/// A hardware event that [`PerfCounters`] count
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum CounterKind {
+    /// Retired instructions
+    Instructions,
+    /// CPU cycles
+    Cycles,
+    /// Cache misses, usually of the last level cache
+    CacheMisses,
+}
+
+impl CounterKind {
+    /// All kinds of counters
+    pub const ALL: [Self; 3] = [Self::Instructions, Self::Cycles, Self::CacheMisses];
+
+    /// Returns the `config` of the generalized hardware event
+    fn config(self) -> u64 {
+        match self {
+            Self::Cycles => 0,
+            Self::Instructions => 1,
+            Self::CacheMisses => 3,
+        }
+    }
+}
+
+impl Display for CounterKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Instructions => "instructions",
+            Self::Cycles => "cycles",
+            Self::CacheMisses => "cache misses",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// The value that a counter of [`PerfCounters`] has read
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+pub struct CounterValue {
+    /// What was counted
+    pub kind: CounterKind,
+    /// How often it happened
+    pub value: u64,
+    /// Whether the counter was multiplexed and the value is an estimate
+    pub estimated: bool,
+}
+
+impl CounterValue {
+    /// Creates the value of a counter that was enabled for `enabled` and counting for `running`
+    /// nanoseconds
+    ///
+    /// If the counter was not counting the whole time, the raw value is scaled up accordingly.
+    fn scaled(kind: CounterKind, raw: u64, enabled: u64, running: u64) -> Self {
+        if running == 0 || running >= enabled {
+            return Self {
+                kind,
+                value: raw,
+                estimated: false,
+            };
+        }
+        let value = u128::from(raw) * u128::from(enabled) / u128::from(running);
+        Self {
+            kind,
+            value: u64::try_from(value).unwrap_or(u64::MAX),
+            estimated: true,
+        }
+    }
+}
+
+impl Display for CounterValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.kind, self.value)?;
+        if self.estimated {
+            write!(f, " (estimated)")?;
+        }
+        Ok(())
+    }
+}
+
+/// Hardware performance counters of the debuggee
+///
+/// The counters are opened stopped. [`PerfCounters::start`] resets and starts them,
+/// [`PerfCounters::stop`] stops them and [`PerfCounters::read`] can be called at any time.
+///
+/// # Examples
+///
+/// ```no_run
+/// use coreminer::perf::PerfCounters;
+/// use nix::unistd::Pid;
+///
+/// let mut counters = PerfCounters::open(Pid::from_raw(1234)).unwrap();
+/// counters.start().unwrap();
+///
+/// // ... let the debuggee run until the next breakpoint ...
+///
+/// counters.stop().unwrap();
+/// for value in counters.read().unwrap() {
+///     println!("{value}");
+/// }
+/// ```
+#[derive(Debug)]
+pub struct PerfCounters {
+    counters: Vec<(CounterKind, OwnedFd)>,
+    running: bool,
+}
+
+impl PerfCounters {
+    /// Opens the counters for the thread `pid`
+    ///
+    /// Kinds of counters that the CPU does not support are left out.
+    ///
+    /// # Errors
+    ///
+    /// This function fails if none of the counters is supported, or if `perf_event_open` fails
+    /// for another reason, for example because the debugger lacks the permission.
+    pub fn open(pid: Pid) -> Result<Self> {
+        let mut counters = Vec::new();
+        for kind in CounterKind::ALL {
+            let attr = PerfEventAttr {
+                kind: PERF_TYPE_HARDWARE,
+                size: PERF_ATTR_SIZE_VER3,
+                config: kind.config(),
+                read_format: PERF_FORMAT_TOTAL_TIME_ENABLED | PERF_FORMAT_TOTAL_TIME_RUNNING,
+                flags: ATTR_DISABLED | ATTR_INHERIT | ATTR_EXCLUDE_KERNEL | ATTR_EXCLUDE_HV,
+                ..Default::default()
+            };
+            match perf_event_open(&attr, pid) {
+                Ok(fd) => counters.push((kind, fd)),
+                Err(DebuggerError::Os(
+                    nix::errno::Errno::ENOENT | nix::errno::Errno::EOPNOTSUPP,
+                )) => {
+                    debug!("the {kind} counter is not supported");
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        if counters.is_empty() {
+            return Err(DebuggerError::PerfCountersUnsupported);
+        }
+        debug!("opened {} performance counters for {pid}", counters.len());
+        Ok(Self {
+            counters,
+            running: false,
+        })
+    }
+
+    /// Resets the counters to zero and starts counting
+    ///
+    /// # Errors
+    ///
+    /// This function fails if a counter cannot be reset or enabled.
+    pub fn start(&mut self) -> Result<()> {
+        self.ioctl_all(PERF_EVENT_IOC_RESET)?;
+        self.ioctl_all(PERF_EVENT_IOC_ENABLE)?;
+        self.running = true;
+        Ok(())
+    }
+
+    /// Stops counting, the values are kept
+    ///
+    /// # Errors
+    ///
+    /// This function fails if a counter cannot be disabled.
+    pub fn stop(&mut self) -> Result<()> {
+        self.ioctl_all(PERF_EVENT_IOC_DISABLE)?;
+        self.running = false;
+        Ok(())
+    }
+
+    /// Reads the current values of the counters
+    ///
+    /// # Errors
+    ///
+    /// This function fails if a counter cannot be read.
+    pub fn read(&self) -> Result<Vec<CounterValue>> {
+        let mut values = Vec::with_capacity(self.counters.len());
+        for (kind, fd) in &self.counters {
+            let mut buf = [0; 24];
+            let read = nix::unistd::read(fd.as_raw_fd(), &mut buf)?;
+            if read != buf.len() {
+                return Err(DebuggerError::PerfCountersUnsupported);
+            }
+            let [raw, enabled, running] = [0, 1, 2].map(|i| {
+                let mut word = [0; 8];
+                word.copy_from_slice(&buf[i * 8..(i + 1) * 8]);
+                u64::from_ne_bytes(word)
+            });
+            values.push(CounterValue::scaled(*kind, raw, enabled, running));
+        }
+        Ok(values)
+    }
+
+    /// Returns `true` if the counters are counting
+    #[inline]
+    #[must_use]
+    pub fn is_running(&self) -> bool {
+        self.running
+    }
+
+    /// Applies the argumentless `ioctl` `request` to all counters
+    fn ioctl_all(&self, request: libc::c_ulong) -> Result<()> {
+        for (_, fd) in &self.counters {
+            // SAFETY: the request takes no argument and fd is an open perf event
+            let ret = unsafe { libc::ioctl(fd.as_raw_fd(), request, 0) };
+            nix::errno::Errno::result(ret)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_counter_scaling() {
+        let exact = CounterValue::scaled(CounterKind::Instructions, 1000, 50, 50);
+        assert_eq!(exact.value, 1000);
+        assert!(!exact.estimated);
+
+        let multiplexed = CounterValue::scaled(CounterKind::Cycles, 1000, 300, 100);
+        assert_eq!(multiplexed.value, 3000);
+        assert!(multiplexed.estimated);
+
+        let never_ran = CounterValue::scaled(CounterKind::CacheMisses, 0, 300, 0);
+        assert_eq!(never_ran.value, 0);
+        assert!(!never_ran.estimated);
+    }
+}
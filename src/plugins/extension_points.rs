@@ -13,6 +13,14 @@
 //! ## Available Extension Points
 //!
 //! - [`EPreSignalHandler`]: Called before the debugger processes signals from the debuggee
+//! - [`EOnSyscall`]: Called when the debuggee enters or exits a syscall
+//! - [`EOnStop`]: Called when the debuggee stopped after it was resumed
+//! - [`EOnBreakpointHit`]: Called when the debuggee stopped at a breakpoint
+//! - [`EOnExit`]: Called when the debuggee terminated
+//! - [`EOnCommand`]: Called for every command of the user interface, before it is processed
+//!
+//! Hooks can show text to the user with [`Status::PluginText`] or send their own data to the
+//! user interface with [`Status::PluginCustom`].
 //!
 //! ## Usage
 //!
@@ -59,9 +67,13 @@ use steckrs::extension_point;
 use nix::libc::siginfo_t;
 use nix::sys::signal::Signal;
 
+use crate::addr::Addr;
 use crate::errors::Result;
+use crate::feedback::ExitStatus;
 use crate::feedback::Feedback;
 use crate::feedback::Status;
+use crate::feedback::StopReason;
+use crate::syscall::SyscallStop;
 
 extension_point!(
     /// Extension point for handling signals before the debugger processes them
@@ -77,7 +89,7 @@ extension_point!(
     ///
     /// This function runs in a feedback loop, allowing the hook to execute debugger
     /// commands by returning Status values and receiving Feedback from those commands.
-    /// The loop continues until the hook returns Status::PluginContinue.
+    /// The loop continues until the hook returns `Status::PluginContinue`.
     ///
     /// # Parameters
     ///
@@ -114,7 +126,7 @@ extension_point!(
     ///
     /// This function runs in a feedback loop, allowing the hook to execute debugger
     /// commands by returning Status values and receiving Feedback from those commands.
-    /// The loop continues until the hook returns Status::PluginContinue.
+    /// The loop continues until the hook returns `Status::PluginContinue`.
     ///
     /// # Parameters
     ///
@@ -135,3 +147,168 @@ extension_point!(
     fn pre_handle_sigtrap(&mut self, feedback: &Feedback, siginfo: &siginfo_t,
         sig: &Signal) -> Result<(Status, bool)>;
 );
+
+extension_point!(
+    /// Extension point for observing and tampering with the syscalls of the debuggee
+    ///
+    /// This extension point is called at every syscall entry and exit of the debuggee. While a
+    /// hook for it is enabled, the debugger continues the debuggee with `PTRACE_SYSCALL`, which is
+    /// considerably slower than a regular continue.
+    ///
+    /// Hooks can rewrite the arguments of a syscall at its entry with
+    /// [`Status::SetSyscallArg`] and the return value at its exit with
+    /// [`Status::SetSyscallReturn`].
+    EOnSyscall:
+    /// Functions that must be implemented by hooks for the [`EOnSyscall`] extension point
+    EOnSyscallF;
+    /// Processes a syscall stop of the debuggee
+    ///
+    /// This function runs in a feedback loop, allowing the hook to execute debugger
+    /// commands by returning Status values and receiving Feedback from those commands.
+    /// The loop continues until the hook returns `Status::PluginContinue`.
+    ///
+    /// # Parameters
+    ///
+    /// * `self` - The hook instance
+    /// * `feedback` - The current feedback from the debugger
+    /// * `stop` - The syscall stop, including the syscall number and arguments
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Status)` - The next command for the debugger to execute
+    /// * `Err(DebuggerError)` - If an error occurs during handling of the syscall
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the hook implementation fails.
+    fn on_syscall(&mut self, feedback: &Feedback, stop: &SyscallStop) -> Result<Status>;
+);
+
+extension_point!(
+    /// Extension point for reacting to the debuggee stopping
+    ///
+    /// This extension point is called whenever the debuggee stopped after it was continued or
+    /// stepped, for any [`StopReason`], including the termination of the debuggee. The UI has
+    /// already been told about the stop when the hooks run.
+    EOnStop:
+    /// Functions that must be implemented by hooks for the [`EOnStop`] extension point
+    EOnStopF;
+    /// Processes a stop of the debuggee
+    ///
+    /// This function runs in a feedback loop, allowing the hook to execute debugger
+    /// commands by returning Status values and receiving Feedback from those commands.
+    /// The loop continues until the hook returns `Status::PluginContinue`.
+    ///
+    /// # Parameters
+    ///
+    /// * `self` - The hook instance
+    /// * `feedback` - The current feedback from the debugger
+    /// * `reason` - Why the debuggee stopped
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Status)` - The next command for the debugger to execute
+    /// * `Err(DebuggerError)` - If an error occurs during handling of the stop
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the hook implementation fails.
+    fn on_stop(&mut self, feedback: &Feedback, reason: &StopReason) -> Result<Status>;
+);
+
+extension_point!(
+    /// Extension point for reacting to breakpoints
+    ///
+    /// This extension point is called whenever the debuggee stopped at a software or hardware
+    /// breakpoint, after the [`EOnStop`] hooks. Breakpoints that the debugger sets internally
+    /// for stepping are not reported.
+    EOnBreakpointHit:
+    /// Functions that must be implemented by hooks for the [`EOnBreakpointHit`] extension point
+    EOnBreakpointHitF;
+    /// Processes a breakpoint hit of the debuggee
+    ///
+    /// This function runs in a feedback loop, allowing the hook to execute debugger
+    /// commands by returning Status values and receiving Feedback from those commands.
+    /// The loop continues until the hook returns `Status::PluginContinue`.
+    ///
+    /// # Parameters
+    ///
+    /// * `self` - The hook instance
+    /// * `feedback` - The current feedback from the debugger
+    /// * `addr` - Address of the breakpoint
+    /// * `hardware` - Whether it is a hardware breakpoint
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Status)` - The next command for the debugger to execute
+    /// * `Err(DebuggerError)` - If an error occurs during handling of the breakpoint
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the hook implementation fails.
+    fn on_breakpoint_hit(&mut self, feedback: &Feedback, addr: Addr, hardware: bool)
+        -> Result<Status>;
+);
+
+extension_point!(
+    /// Extension point for reacting to the termination of the debuggee
+    ///
+    /// This extension point is called when the debuggee exited or was killed by a signal, after
+    /// the [`EOnStop`] hooks. The debuggee is gone at this point, so only commands that work
+    /// without a debuggee can be executed.
+    EOnExit:
+    /// Functions that must be implemented by hooks for the [`EOnExit`] extension point
+    EOnExitF;
+    /// Processes the termination of the debuggee
+    ///
+    /// This function runs in a feedback loop, allowing the hook to execute debugger
+    /// commands by returning Status values and receiving Feedback from those commands.
+    /// The loop continues until the hook returns `Status::PluginContinue`.
+    ///
+    /// # Parameters
+    ///
+    /// * `self` - The hook instance
+    /// * `feedback` - The current feedback from the debugger
+    /// * `status` - How the debuggee terminated
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Status)` - The next command for the debugger to execute
+    /// * `Err(DebuggerError)` - If an error occurs during handling of the termination
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the hook implementation fails.
+    fn on_exit(&mut self, feedback: &Feedback, status: &ExitStatus) -> Result<Status>;
+);
+
+extension_point!(
+    /// Extension point for observing the commands of the user interface
+    ///
+    /// This extension point is called for every [`Status`] the user interface sends, before the
+    /// debugger processes it. Commands that hooks execute themselves are not reported.
+    EOnCommand:
+    /// Functions that must be implemented by hooks for the [`EOnCommand`] extension point
+    EOnCommandF;
+    /// Processes a command of the user interface
+    ///
+    /// This function runs in a feedback loop, allowing the hook to execute debugger
+    /// commands by returning Status values and receiving Feedback from those commands.
+    /// The loop continues until the hook returns `Status::PluginContinue`.
+    ///
+    /// # Parameters
+    ///
+    /// * `self` - The hook instance
+    /// * `feedback` - The current feedback from the debugger
+    /// * `command` - The command that the user interface sent
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Status)` - The next command for the debugger to execute
+    /// * `Err(DebuggerError)` - If an error occurs during handling of the command
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the hook implementation fails.
+    fn on_command(&mut self, feedback: &Feedback, command: &Status) -> Result<Status>;
+);
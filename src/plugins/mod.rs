@@ -21,7 +21,7 @@
 //!
 //! The module includes several built-in plugins:
 //! - [`SigtrapGuardPlugin`]: A plugin that prevents the detection of the coreminer debugger with
-//!                           a signal handler for SIGTRAP
+//!   a signal handler for SIGTRAP
 //!
 //! ## Usage
 //!
@@ -165,7 +165,7 @@ pub fn load_plugin<P: Plugin>(manager: &mut PluginManager, plugin: P) {
 ///                 println!("got word {w}");
 ///                 Ok(Status::PluginContinue)
 ///             } else {
-///                 Ok(Status::ReadMem(Addr::from(0xdeadbeef_usize)))
+///                 Ok(Status::ReadMem(Addr::from(0xdeadbeef_usize).into()))
 ///             }
 ///         }).unwrap();
 ///     }
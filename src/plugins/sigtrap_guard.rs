@@ -74,6 +74,7 @@ use tracing::{info, trace, warn};
 
 use crate::addr::Addr;
 use crate::breakpoint::Breakpoint;
+use crate::errors::DebuggerError;
 use crate::feedback::{Feedback, Status};
 
 use super::extension_points::{EPreSigtrap, EPreSigtrapF};
@@ -166,7 +167,10 @@ impl EPreSigtrapF for SigtrapInjectionGuard {
                 addr
             }
             (crate::feedback::Feedback::Registers(regs), None) => {
-                self.rip = Some(regs.rip.into());
+                let rip = regs
+                    .get("rip")
+                    .ok_or_else(|| DebuggerError::MissingRegister("rip".to_string()))?;
+                self.rip = Some(rip.into());
                 self.rip.unwrap()
             }
             (_, _) => return Ok((Status::DumpRegisters, false)),
@@ -0,0 +1,743 @@
+//! # Procfs Module
+//!
+//! Provides functionality for inspecting a debugged process through the `/proc` filesystem.
+//!
+//! Besides the memory map (see [`crate::memorymap`]), Linux exposes a lot of information about a
+//! running process under `/proc/<pid>/`. This module reads and parses the parts of it that are
+//! useful while debugging, like the environment, the open file descriptors, the status and the
+//! auxiliary vector of the process.
+
+use std::fmt::{self, Display};
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+use nix::unistd::Pid;
+use serde::Serialize;
+use tracing::warn;
+
+use crate::addr::Addr;
+use crate::errors::{DebuggerError, Result};
+
+/// The environment of a process, as it was when the process was started
+///
+/// The variables are kept in the order in which they appear in the environment block of the
+/// process.
+///
+/// # Examples
+///
+/// ```no_run
+/// use coreminer::procfs::Environment;
+/// use nix::unistd::Pid;
+///
+/// let env = Environment::read(Pid::from_raw(1234)).unwrap();
+/// if let Some(path) = env.get("PATH") {
+///     println!("the debuggee searches for programs in {path}");
+/// }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize)]
+pub struct Environment {
+    /// The variables of the environment as key value pairs
+    pub vars: Vec<(String, String)>,
+}
+
+impl Environment {
+    /// Reads the environment of a process from `/proc/<pid>/environ`
+    ///
+    /// Note that this is the environment the process was started with. Changes the process made
+    /// to its own environment with `setenv` and similar functions are not included.
+    ///
+    /// # Parameters
+    ///
+    /// * `pid` - The process ID of the process
+    ///
+    /// # Errors
+    ///
+    /// This function will fail if `/proc/<pid>/environ` cannot be read.
+    pub fn read(pid: Pid) -> Result<Self> {
+        let raw = std::fs::read(format!("/proc/{pid}/environ"))?;
+        Ok(Self::parse(&raw))
+    }
+
+    /// Parses the contents of an `environ` file
+    ///
+    /// The file contains `KEY=VALUE` strings, each terminated by a null byte. Entries without a
+    /// `=` are kept with an empty value.
+    #[must_use]
+    pub fn parse(raw: &[u8]) -> Self {
+        let vars = raw
+            .split(|b| *b == 0)
+            .filter(|entry| !entry.is_empty())
+            .map(|entry| {
+                let entry = String::from_utf8_lossy(entry);
+                match entry.split_once('=') {
+                    Some((key, value)) => (key.to_string(), value.to_string()),
+                    None => (entry.to_string(), String::new()),
+                }
+            })
+            .collect();
+        Self { vars }
+    }
+
+    /// Gets the value of a variable by its name
+    #[must_use]
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.vars
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+impl Display for Environment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (key, value) in &self.vars {
+            writeln!(f, "{key}={value}")?;
+        }
+        Ok(())
+    }
+}
+
+/// An open file descriptor of a process
+///
+/// # Examples
+///
+/// ```no_run
+/// use coreminer::procfs::{FileDescriptor, FdTarget};
+/// use nix::unistd::Pid;
+///
+/// for fd in FileDescriptor::read_all(Pid::from_raw(1234)).unwrap() {
+///     if let FdTarget::Socket(sock) = &fd.target {
+///         println!("fd {} is a {:?} socket connected to {:?}", fd.fd, sock.protocol, sock.remote);
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+pub struct FileDescriptor {
+    /// The number of the file descriptor
+    pub fd: u32,
+    /// What the file descriptor refers to
+    pub target: FdTarget,
+}
+
+/// What a [`FileDescriptor`] refers to
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+pub enum FdTarget {
+    /// A file or directory with its path
+    File(String),
+    /// A pipe with its inode
+    Pipe(u64),
+    /// A socket
+    Socket(SocketInfo),
+    /// An anonymous inode, like an `eventfd` or `epoll` instance, with its kind
+    AnonInode(String),
+    /// Anything else, with the raw target of the `/proc/<pid>/fd` link
+    Other(String),
+}
+
+/// Information about a socket that is referred to by a [`FileDescriptor`]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+pub struct SocketInfo {
+    /// The inode of the socket
+    pub inode: u64,
+    /// The protocol of the socket (`tcp`, `tcp6`, `udp`, `udp6` or `unix`), if it could be found
+    pub protocol: Option<String>,
+    /// The local address of the socket, or the path of a unix socket
+    pub local: Option<String>,
+    /// The address of the peer for connected internet sockets
+    pub remote: Option<String>,
+}
+
+impl FileDescriptor {
+    /// Reads all open file descriptors of a process from `/proc/<pid>/fd`
+    ///
+    /// Sockets are resolved with the socket tables in `/proc/<pid>/net/`, which contain the
+    /// addresses of internet sockets and the paths of unix sockets.
+    ///
+    /// # Parameters
+    ///
+    /// * `pid` - The process ID of the process
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Vec<FileDescriptor>)` - The open file descriptors, sorted by their number
+    /// * `Err(DebuggerError)` - If the file descriptors could not be read
+    ///
+    /// # Errors
+    ///
+    /// This function will fail if `/proc/<pid>/fd` cannot be read.
+    pub fn read_all(pid: Pid) -> Result<Vec<Self>> {
+        let mut sockets: Option<Vec<SocketInfo>> = None;
+        let mut fds = Vec::new();
+
+        for entry in std::fs::read_dir(format!("/proc/{pid}/fd"))? {
+            let entry = entry?;
+            let Ok(fd) = entry.file_name().to_string_lossy().parse::<u32>() else {
+                continue;
+            };
+            // the fd might have been closed in the meantime
+            let link = match std::fs::read_link(entry.path()) {
+                Ok(link) => link.to_string_lossy().to_string(),
+                Err(e) => {
+                    warn!("could not read link of fd {fd}: {e}");
+                    continue;
+                }
+            };
+
+            let mut target = FdTarget::from_link(&link);
+            if let FdTarget::Socket(sock) = &mut target {
+                let table = sockets.get_or_insert_with(|| read_socket_tables(pid));
+                if let Some(found) = table.iter().find(|s| s.inode == sock.inode) {
+                    *sock = found.clone();
+                }
+            }
+            fds.push(Self { fd, target });
+        }
+
+        fds.sort_by_key(|fd| fd.fd);
+        Ok(fds)
+    }
+}
+
+impl FdTarget {
+    /// Classifies the target of a `/proc/<pid>/fd` link
+    ///
+    /// Sockets are not resolved here, only their inode is stored.
+    #[must_use]
+    pub fn from_link(link: &str) -> Self {
+        let inode_of = |rest: &str| {
+            rest.strip_prefix('[')
+                .and_then(|r| r.strip_suffix(']'))
+                .and_then(|r| r.parse::<u64>().ok())
+        };
+
+        if let Some(inode) = link.strip_prefix("socket:").and_then(inode_of) {
+            FdTarget::Socket(SocketInfo {
+                inode,
+                protocol: None,
+                local: None,
+                remote: None,
+            })
+        } else if let Some(inode) = link.strip_prefix("pipe:").and_then(inode_of) {
+            FdTarget::Pipe(inode)
+        } else if let Some(kind) = link.strip_prefix("anon_inode:") {
+            FdTarget::AnonInode(kind.to_string())
+        } else if link.starts_with('/') {
+            FdTarget::File(link.to_string())
+        } else {
+            FdTarget::Other(link.to_string())
+        }
+    }
+}
+
+impl Display for FileDescriptor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:>4}: ", self.fd)?;
+        match &self.target {
+            FdTarget::File(path) => write!(f, "{path}"),
+            FdTarget::Pipe(inode) => write!(f, "pipe [{inode}]"),
+            FdTarget::AnonInode(kind) => write!(f, "anon_inode {kind}"),
+            FdTarget::Other(raw) => write!(f, "{raw}"),
+            FdTarget::Socket(sock) => {
+                write!(
+                    f,
+                    "{} socket [{}]",
+                    sock.protocol.as_deref().unwrap_or("unknown"),
+                    sock.inode
+                )?;
+                if let Some(local) = &sock.local {
+                    write!(f, " {local}")?;
+                }
+                if let Some(remote) = &sock.remote {
+                    write!(f, " -> {remote}")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Status and resource usage of a process
+///
+/// Combines the most useful fields of `/proc/<pid>/status` and `/proc/<pid>/stat`. Memory sizes
+/// are in kibibytes, CPU times are in clock ticks (see [`ProcessInfo::clock_ticks_per_second`]).
+///
+/// # Examples
+///
+/// ```no_run
+/// use coreminer::procfs::ProcessInfo;
+/// use nix::unistd::Pid;
+///
+/// let info = ProcessInfo::read(Pid::from_raw(1234)).unwrap();
+/// println!("{} is {} and uses {:?} kB of memory", info.name, info.state, info.vm_rss_kb);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize)]
+pub struct ProcessInfo {
+    /// The process ID
+    pub pid: i32,
+    /// The process ID of the parent process
+    pub ppid: i32,
+    /// The process ID of the tracer, 0 if the process is not traced
+    pub tracer_pid: i32,
+    /// The name of the executable, possibly truncated
+    pub name: String,
+    /// The state of the process, like `t (tracing stop)`
+    pub state: String,
+    /// The number of threads of the process
+    pub threads: u64,
+    /// Peak virtual memory size
+    pub vm_peak_kb: Option<u64>,
+    /// Virtual memory size
+    pub vm_size_kb: Option<u64>,
+    /// Resident set size, the memory that is actually in RAM
+    pub vm_rss_kb: Option<u64>,
+    /// Swapped out memory
+    pub vm_swap_kb: Option<u64>,
+    /// Context switches because the process waited for a resource
+    pub voluntary_ctxt_switches: u64,
+    /// Context switches because the process was preempted
+    pub nonvoluntary_ctxt_switches: u64,
+    /// Page faults that did not need to load a page from disk
+    pub minor_faults: u64,
+    /// Page faults that needed to load a page from disk
+    pub major_faults: u64,
+    /// Time spent in user mode, in clock ticks
+    pub utime: u64,
+    /// Time spent in kernel mode, in clock ticks
+    pub stime: u64,
+    /// Time the process started after system boot, in clock ticks
+    pub start_time: u64,
+    /// The CPU the process last ran on
+    pub processor: u32,
+    /// The number of clock ticks per second, to convert the times to seconds
+    pub clock_ticks_per_second: u64,
+}
+
+impl ProcessInfo {
+    /// Reads the status and resource usage of a process
+    ///
+    /// # Parameters
+    ///
+    /// * `pid` - The process ID of the process
+    ///
+    /// # Errors
+    ///
+    /// This function will fail if `/proc/<pid>/status` or `/proc/<pid>/stat` cannot be read or
+    /// parsed.
+    pub fn read(pid: Pid) -> Result<Self> {
+        let status = std::fs::read_to_string(format!("/proc/{pid}/status"))?;
+        let stat = std::fs::read_to_string(format!("/proc/{pid}/stat"))?;
+        let mut info = Self::parse(&status, &stat)?;
+        // SAFETY: sysconf has no preconditions
+        info.clock_ticks_per_second = unsafe { nix::libc::sysconf(nix::libc::_SC_CLK_TCK) } as u64;
+        Ok(info)
+    }
+
+    /// Parses the contents of the `status` and `stat` files of a process
+    ///
+    /// [`ProcessInfo::clock_ticks_per_second`] is left at 0.
+    ///
+    /// # Errors
+    ///
+    /// This function will fail if a required field is missing or malformed.
+    pub fn parse(status: &str, stat: &str) -> Result<Self> {
+        let mut info = Self::default();
+
+        for line in status.lines() {
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+            let value = value.trim();
+            let kb = || value.trim_end_matches("kB").trim().parse::<u64>().ok();
+            match key {
+                "Name" => info.name = value.to_string(),
+                "State" => info.state = value.to_string(),
+                "Pid" => info.pid = value.parse()?,
+                "PPid" => info.ppid = value.parse()?,
+                "TracerPid" => info.tracer_pid = value.parse()?,
+                "Threads" => info.threads = value.parse()?,
+                "VmPeak" => info.vm_peak_kb = kb(),
+                "VmSize" => info.vm_size_kb = kb(),
+                "VmRSS" => info.vm_rss_kb = kb(),
+                "VmSwap" => info.vm_swap_kb = kb(),
+                "voluntary_ctxt_switches" => info.voluntary_ctxt_switches = value.parse()?,
+                "nonvoluntary_ctxt_switches" => info.nonvoluntary_ctxt_switches = value.parse()?,
+                _ => (),
+            }
+        }
+
+        // the name in the stat file may contain spaces and parentheses, so start after the last
+        // closing parenthesis, with the state as the first field
+        let fields: Vec<&str> = stat
+            .rsplit_once(')')
+            .ok_or_else(|| DebuggerError::ParseStr(stat.to_string()))?
+            .1
+            .split_whitespace()
+            .collect();
+        let field = |n: usize| -> Result<&str> {
+            // n is the number of the field as documented in proc(5)
+            fields
+                .get(n - 3)
+                .copied()
+                .ok_or_else(|| DebuggerError::ParseStr(format!("stat field {n} is missing")))
+        };
+        info.minor_faults = field(10)?.parse()?;
+        info.major_faults = field(12)?.parse()?;
+        info.utime = field(14)?.parse()?;
+        info.stime = field(15)?.parse()?;
+        info.start_time = field(22)?.parse()?;
+        info.processor = field(39)?.parse()?;
+
+        Ok(info)
+    }
+}
+
+impl Display for ProcessInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kb = |v: Option<u64>| v.map_or("?".to_string(), |v| format!("{v} kB"));
+        #[allow(clippy::cast_precision_loss)] // nobody has that much CPU time
+        let secs = |ticks: u64| ticks as f64 / self.clock_ticks_per_second.max(1) as f64;
+        writeln!(f, "name:          {}", self.name)?;
+        writeln!(f, "pid:           {} (parent {})", self.pid, self.ppid)?;
+        writeln!(f, "state:         {}", self.state)?;
+        writeln!(f, "threads:       {}", self.threads)?;
+        writeln!(f, "vm rss:        {}", kb(self.vm_rss_kb))?;
+        writeln!(
+            f,
+            "vm size:       {} (peak {})",
+            kb(self.vm_size_kb),
+            kb(self.vm_peak_kb)
+        )?;
+        writeln!(f, "vm swap:       {}", kb(self.vm_swap_kb))?;
+        writeln!(
+            f,
+            "ctxt switches: {} voluntary, {} involuntary",
+            self.voluntary_ctxt_switches, self.nonvoluntary_ctxt_switches
+        )?;
+        writeln!(
+            f,
+            "page faults:   {} minor, {} major",
+            self.minor_faults, self.major_faults
+        )?;
+        writeln!(
+            f,
+            "cpu time:      {:.2}s user, {:.2}s system",
+            secs(self.utime),
+            secs(self.stime)
+        )?;
+        write!(f, "last cpu:      {}", self.processor)
+    }
+}
+
+/// The auxiliary vector of a process
+///
+/// The auxiliary vector is passed by the kernel to a new process and contains information about
+/// the process and the system, for example where the program headers of the executable were
+/// loaded and where its entry point is. Unlike guessing from the memory map, this also works
+/// reliably for position independent executables.
+///
+/// # Examples
+///
+/// ```no_run
+/// use coreminer::procfs::AuxVector;
+/// use nix::unistd::Pid;
+///
+/// let auxv = AuxVector::read(Pid::from_raw(1234)).unwrap();
+/// if let Some(entry) = auxv.entry {
+///     println!("the entry point of the executable is at {entry}");
+/// }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize)]
+pub struct AuxVector {
+    /// `AT_ENTRY`: the entry point of the executable
+    pub entry: Option<Addr>,
+    /// `AT_PHDR`: the address of the program headers of the executable
+    pub phdr: Option<Addr>,
+    /// `AT_PHENT`: the size of a program header entry
+    pub phent: Option<u64>,
+    /// `AT_PHNUM`: the number of program headers
+    pub phnum: Option<u64>,
+    /// `AT_BASE`: the base address of the dynamic loader, if there is one
+    pub base: Option<Addr>,
+    /// `AT_RANDOM`: the address of 16 random bytes, used for example for stack canaries
+    pub random: Option<Addr>,
+    /// `AT_SYSINFO_EHDR`: the address of the vDSO
+    pub sysinfo_ehdr: Option<Addr>,
+    /// `AT_PAGESZ`: the page size of the system
+    pub page_size: Option<u64>,
+    /// `AT_EXECFN`: the address of the path the executable was started with
+    pub execfn: Option<Addr>,
+    /// All entries of the auxiliary vector as `(type, value)` pairs, without the terminating
+    /// `AT_NULL`
+    pub raw: Vec<(u64, u64)>,
+}
+
+impl AuxVector {
+    /// Reads the auxiliary vector of a process from `/proc/<pid>/auxv`
+    ///
+    /// # Parameters
+    ///
+    /// * `pid` - The process ID of the process
+    ///
+    /// # Errors
+    ///
+    /// This function will fail if `/proc/<pid>/auxv` cannot be read.
+    pub fn read(pid: Pid) -> Result<Self> {
+        let raw = std::fs::read(format!("/proc/{pid}/auxv"))?;
+        Ok(Self::parse(&raw))
+    }
+
+    /// Parses the contents of an `auxv` file
+    ///
+    /// The file consists of pairs of machine words, the type and the value of an entry. It is
+    /// terminated by an entry of the type `AT_NULL`.
+    #[must_use]
+    pub fn parse(raw: &[u8]) -> Self {
+        const WORD: usize = std::mem::size_of::<u64>();
+        let mut auxv = Self::default();
+
+        for pair in raw.chunks_exact(2 * WORD) {
+            // chunks_exact makes sure that both halves have the right size
+            let mut key = [0; WORD];
+            let mut value = [0; WORD];
+            key.copy_from_slice(&pair[..WORD]);
+            value.copy_from_slice(&pair[WORD..]);
+            let (key, value) = (u64::from_ne_bytes(key), u64::from_ne_bytes(value));
+            if key == nix::libc::AT_NULL {
+                break;
+            }
+
+            match key {
+                nix::libc::AT_ENTRY => auxv.entry = Some(Addr::from(value)),
+                nix::libc::AT_PHDR => auxv.phdr = Some(Addr::from(value)),
+                nix::libc::AT_PHENT => auxv.phent = Some(value),
+                nix::libc::AT_PHNUM => auxv.phnum = Some(value),
+                nix::libc::AT_BASE => auxv.base = Some(Addr::from(value)),
+                nix::libc::AT_RANDOM => auxv.random = Some(Addr::from(value)),
+                nix::libc::AT_SYSINFO_EHDR => auxv.sysinfo_ehdr = Some(Addr::from(value)),
+                nix::libc::AT_PAGESZ => auxv.page_size = Some(value),
+                nix::libc::AT_EXECFN => auxv.execfn = Some(Addr::from(value)),
+                _ => (),
+            }
+            auxv.raw.push((key, value));
+        }
+
+        auxv
+    }
+}
+
+impl Display for AuxVector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let addr = |a: Option<Addr>| a.map_or("-".to_string(), |a| a.to_string());
+        let num = |n: Option<u64>| n.map_or("-".to_string(), |n| n.to_string());
+        writeln!(f, "AT_ENTRY:        {}", addr(self.entry))?;
+        writeln!(f, "AT_PHDR:         {}", addr(self.phdr))?;
+        writeln!(f, "AT_PHENT:        {}", num(self.phent))?;
+        writeln!(f, "AT_PHNUM:        {}", num(self.phnum))?;
+        writeln!(f, "AT_BASE:         {}", addr(self.base))?;
+        writeln!(f, "AT_RANDOM:       {}", addr(self.random))?;
+        writeln!(f, "AT_SYSINFO_EHDR: {}", addr(self.sysinfo_ehdr))?;
+        writeln!(f, "AT_PAGESZ:       {}", num(self.page_size))?;
+        write!(f, "AT_EXECFN:       {}", addr(self.execfn))
+    }
+}
+
+/// Reads all sockets that are visible in the network namespace of a process
+///
+/// Tables that cannot be read are skipped.
+fn read_socket_tables(pid: Pid) -> Vec<SocketInfo> {
+    let mut sockets = Vec::new();
+    for protocol in ["tcp", "tcp6", "udp", "udp6", "unix"] {
+        let table = match std::fs::read_to_string(format!("/proc/{pid}/net/{protocol}")) {
+            Ok(t) => t,
+            Err(e) => {
+                warn!("could not read the {protocol} socket table: {e}");
+                continue;
+            }
+        };
+        if protocol == "unix" {
+            sockets.extend(table.lines().skip(1).filter_map(parse_unix_socket_line));
+        } else {
+            sockets.extend(
+                table
+                    .lines()
+                    .skip(1)
+                    .filter_map(|l| parse_inet_socket_line(l, protocol)),
+            );
+        }
+    }
+    sockets
+}
+
+/// Parses a line of `/proc/net/{tcp,tcp6,udp,udp6}`
+fn parse_inet_socket_line(line: &str, protocol: &str) -> Option<SocketInfo> {
+    let fields: Vec<&str> = line.split_whitespace().collect();
+    let local = parse_inet_addr(fields.get(1)?)?;
+    let remote = parse_inet_addr(fields.get(2)?)?;
+    let inode = fields.get(9)?.parse().ok()?;
+    let is_unconnected = remote.starts_with("0.0.0.0:") || remote.starts_with("[::]:");
+    Some(SocketInfo {
+        inode,
+        protocol: Some(protocol.to_string()),
+        local: Some(local),
+        remote: if is_unconnected { None } else { Some(remote) },
+    })
+}
+
+/// Parses an address like `0100007F:1F90` from the inet socket tables into `127.0.0.1:8080`
+///
+/// The address is stored as hexadecimal 32 bit words in host byte order, the port in big endian.
+fn parse_inet_addr(raw: &str) -> Option<String> {
+    let (addr, port) = raw.split_once(':')?;
+    let port = u16::from_str_radix(port, 16).ok()?;
+    match addr.len() {
+        8 => {
+            let addr = u32::from_str_radix(addr, 16).ok()?;
+            Some(format!("{}:{port}", Ipv4Addr::from(addr.to_ne_bytes())))
+        }
+        32 => {
+            let mut bytes = [0u8; 16];
+            for (i, chunk) in bytes.chunks_exact_mut(4).enumerate() {
+                let word = u32::from_str_radix(addr.get(i * 8..i * 8 + 8)?, 16).ok()?;
+                chunk.copy_from_slice(&word.to_ne_bytes());
+            }
+            Some(format!("[{}]:{port}", Ipv6Addr::from(bytes)))
+        }
+        _ => None,
+    }
+}
+
+/// Parses a line of `/proc/net/unix`
+fn parse_unix_socket_line(line: &str) -> Option<SocketInfo> {
+    let fields: Vec<&str> = line.split_whitespace().collect();
+    let inode = fields.get(6)?.parse().ok()?;
+    Some(SocketInfo {
+        inode,
+        protocol: Some("unix".to_string()),
+        local: fields.get(7).map(ToString::to_string),
+        remote: None,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_environ() {
+        let env = Environment::parse(b"HOME=/root\0PATH=/usr/bin:/bin\0EMPTY=\0WEIRD\0A=b=c\0");
+        assert_eq!(
+            env.vars,
+            vec![
+                ("HOME".to_string(), "/root".to_string()),
+                ("PATH".to_string(), "/usr/bin:/bin".to_string()),
+                ("EMPTY".to_string(), String::new()),
+                ("WEIRD".to_string(), String::new()),
+                ("A".to_string(), "b=c".to_string()),
+            ]
+        );
+        assert_eq!(env.get("PATH"), Some("/usr/bin:/bin"));
+        assert_eq!(env.get("NOPE"), None);
+        assert!(Environment::parse(b"").vars.is_empty());
+    }
+
+    #[test]
+    fn test_fd_target_from_link() {
+        assert_eq!(
+            FdTarget::from_link("/dev/null"),
+            FdTarget::File("/dev/null".to_string())
+        );
+        assert_eq!(FdTarget::from_link("pipe:[1337]"), FdTarget::Pipe(1337));
+        assert_eq!(
+            FdTarget::from_link("anon_inode:[eventfd]"),
+            FdTarget::AnonInode("[eventfd]".to_string())
+        );
+        assert_eq!(
+            FdTarget::from_link("socket:[42]"),
+            FdTarget::Socket(SocketInfo {
+                inode: 42,
+                protocol: None,
+                local: None,
+                remote: None
+            })
+        );
+        assert_eq!(
+            FdTarget::from_link("net:[4026531840]"),
+            FdTarget::Other("net:[4026531840]".to_string())
+        );
+    }
+
+    #[test]
+    fn test_parse_socket_lines() {
+        let tcp = "   0: 0100007F:1F90 0200007F:C350 01 00000000:00000000 00:00000000 00000000  1000        0 969 1 000000004d5de418 100 0 0 10 0";
+        let sock = parse_inet_socket_line(tcp, "tcp").expect("could not parse tcp line");
+        assert_eq!(sock.inode, 969);
+        assert_eq!(sock.local.as_deref(), Some("127.0.0.1:8080"));
+        assert_eq!(sock.remote.as_deref(), Some("127.0.0.2:50000"));
+
+        let listening = "   1: 00000000:07E8 00000000:0000 0A 00000000:00000000 00:00000000 00000000     0        0 662 1 00000000b5d39ecd 100 0 0 10 0";
+        let sock = parse_inet_socket_line(listening, "tcp").expect("could not parse tcp line");
+        assert_eq!(sock.local.as_deref(), Some("0.0.0.0:2024"));
+        assert_eq!(sock.remote, None);
+
+        assert_eq!(
+            parse_inet_addr("00000000000000000000000001000000:0050").as_deref(),
+            Some("[::1]:80")
+        );
+
+        let unix = "000000006b1680f3: 00000002 00000000 00010000 0001 01 56775 /run/foo.sock";
+        let sock = parse_unix_socket_line(unix).expect("could not parse unix line");
+        assert_eq!(sock.inode, 56775);
+        assert_eq!(sock.local.as_deref(), Some("/run/foo.sock"));
+    }
+
+    #[test]
+    fn test_parse_process_info() {
+        let status = "Name:\tdummy\nState:\tt (tracing stop)\nTgid:\t6138\nPid:\t6138\nPPid:\t6028\nTracerPid:\t6028\nVmPeak:\t    2640 kB\nVmSize:\t    2600 kB\nVmRSS:\t    1420 kB\nVmSwap:\t       0 kB\nThreads:\t1\nvoluntary_ctxt_switches:\t3\nnonvoluntary_ctxt_switches:\t4\n";
+        let stat = "6138 (dum my) t) t 6028 6028 6028 0 -1 4194304 82 0 5 0 7 8 0 0 20 0 1 0 205746 2703360 321 18446744073709551615 94641880313856 94641880333737 140722616359888 0 0 0 0 0 0 0 0 0 17 3 0 0 0 0 0";
+        let info = ProcessInfo::parse(status, stat).expect("could not parse process info");
+
+        assert_eq!(info.name, "dummy");
+        assert_eq!(info.state, "t (tracing stop)");
+        assert_eq!(info.pid, 6138);
+        assert_eq!(info.ppid, 6028);
+        assert_eq!(info.tracer_pid, 6028);
+        assert_eq!(info.threads, 1);
+        assert_eq!(info.vm_peak_kb, Some(2640));
+        assert_eq!(info.vm_size_kb, Some(2600));
+        assert_eq!(info.vm_rss_kb, Some(1420));
+        assert_eq!(info.vm_swap_kb, Some(0));
+        assert_eq!(info.voluntary_ctxt_switches, 3);
+        assert_eq!(info.nonvoluntary_ctxt_switches, 4);
+        assert_eq!(info.minor_faults, 82);
+        assert_eq!(info.major_faults, 5);
+        assert_eq!(info.utime, 7);
+        assert_eq!(info.stime, 8);
+        assert_eq!(info.start_time, 205_746);
+        assert_eq!(info.processor, 3);
+
+        ProcessInfo::parse(status, "garbage").expect_err("could parse garbage stat");
+    }
+
+    #[test]
+    fn test_parse_auxv() {
+        let mut raw = Vec::new();
+        for (key, value) in [
+            (nix::libc::AT_PHDR, 0x5555_5555_4040u64),
+            (nix::libc::AT_PAGESZ, 4096),
+            (nix::libc::AT_ENTRY, 0x5555_5555_63d0),
+            (nix::libc::AT_RANDOM, 0x7fff_8bb4_be59),
+            (nix::libc::AT_NULL, 0),
+            (nix::libc::AT_BASE, 0xdead_beef), // after AT_NULL, must be ignored
+        ] {
+            raw.extend_from_slice(&key.to_ne_bytes());
+            raw.extend_from_slice(&value.to_ne_bytes());
+        }
+
+        let auxv = AuxVector::parse(&raw);
+        assert_eq!(auxv.phdr, Some(Addr::from(0x5555_5555_4040usize)));
+        assert_eq!(auxv.page_size, Some(4096));
+        assert_eq!(auxv.entry, Some(Addr::from(0x5555_5555_63d0usize)));
+        assert_eq!(auxv.random, Some(Addr::from(0x7fff_8bb4_be59usize)));
+        assert_eq!(auxv.base, None);
+        assert_eq!(auxv.raw.len(), 4);
+    }
+}
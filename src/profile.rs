@@ -0,0 +1,384 @@
+//! # Profile Module
+//!
+//! Provides a sampling profiler for a debugged process and exporting its results for flamegraph
+//! tools.
+//!
+//! While profiling, the debugger lets the debuggee run and stops it at a fixed frequency. At each
+//! stop, the call stack is unwound and counted as one sample. Call stacks that were sampled more
+//! often are where the debuggee spends more of its time.
+//!
+//! A [`Profile`] aggregates the samples by call stack. It can be exported in two formats:
+//!
+//! - [`Profile::folded`] writes the folded stack format, one line per call stack with the frames
+//!   from the outermost to the innermost, separated by `;`, followed by the number of samples.
+//!   This is what `flamegraph.pl` and `inferno-flamegraph` read.
+//! - [`Profile::flamegraph`] renders a simple SVG flamegraph directly, without external tools.
+//!
+//! Stopping the debuggee with `SIGSTOP` for each sample takes some time, so high frequencies slow
+//! the debuggee down noticeably. Only the main thread is sampled.
+
+use std::collections::BTreeMap;
+use std::fmt::{self, Display};
+
+use serde::Serialize;
+
+use crate::unwind::Backtrace;
+
+/// How many samples per second are taken if nothing else is configured
+pub const DEFAULT_PROFILE_FREQUENCY: u64 = 100;
+/// Name of frames without a known function
+pub const UNKNOWN_FRAME: &str = "[unknown]";
+/// How many call stacks the [`Display`] of a [`Profile`] shows
+const SHOWN_STACKS: usize = 10;
+/// Width of a rendered flamegraph in pixels
+const SVG_WIDTH: f64 = 1200.0;
+/// Height of a frame in a rendered flamegraph in pixels
+const SVG_FRAME_HEIGHT: f64 = 16.0;
+/// Space around the frames of a rendered flamegraph in pixels
+const SVG_PADDING: f64 = 10.0;
+/// Space above the frames of a rendered flamegraph for the title in pixels
+const SVG_TITLE_HEIGHT: f64 = 24.0;
+/// Approximate width of a character of the 12px font in pixels
+const SVG_CHAR_WIDTH: f64 = 7.0;
+
+/// The samples of the sampling profiler, aggregated by call stack
+///
+/// # Examples
+///
+/// ```
+/// use coreminer::profile::Profile;
+/// use coreminer::unwind::{Backtrace, BacktraceFrame};
+/// use coreminer::addr::Addr;
+///
+/// let frame = |name: &str| BacktraceFrame {
+///     addr: Addr::from(0x1000usize),
+///     start_addr: Some(Addr::from(0x1000usize)),
+///     name: Some(name.to_string()),
+/// };
+///
+/// let mut profile = Profile::default();
+/// profile.add(&Backtrace { frames: vec![frame("compute"), frame("main")] });
+/// profile.add(&Backtrace { frames: vec![frame("compute"), frame("main")] });
+/// profile.add(&Backtrace { frames: vec![frame("main")] });
+///
+/// assert_eq!(profile.samples(), 3);
+/// assert_eq!(profile.folded(), "main 1\nmain;compute 2\n");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct Profile {
+    /// Number of samples by folded call stack
+    stacks: BTreeMap<String, u64>,
+    /// Number of samples that were taken
+    samples: u64,
+    /// Number of samples for which the call stack could not be unwound
+    lost: u64,
+}
+
+impl Profile {
+    /// Adds a sample with the call stack `backtrace`
+    ///
+    /// Frames without a function name are counted as [`UNKNOWN_FRAME`].
+    pub fn add(&mut self, backtrace: &Backtrace) {
+        let stack: Vec<String> = backtrace
+            .frames
+            .iter()
+            .rev()
+            .map(|frame| {
+                // the frames are separated by ; in the folded format
+                frame
+                    .name
+                    .as_deref()
+                    .unwrap_or(UNKNOWN_FRAME)
+                    .replace(';', ":")
+            })
+            .collect();
+        *self.stacks.entry(stack.join(";")).or_default() += 1;
+        self.samples += 1;
+    }
+
+    /// Counts a sample for which the call stack could not be unwound
+    pub fn add_lost(&mut self) {
+        self.samples += 1;
+        self.lost += 1;
+    }
+
+    /// Returns the number of samples that were taken
+    #[inline]
+    #[must_use]
+    pub fn samples(&self) -> u64 {
+        self.samples
+    }
+
+    /// Returns the number of samples for which the call stack could not be unwound
+    #[inline]
+    #[must_use]
+    pub fn lost(&self) -> u64 {
+        self.lost
+    }
+
+    /// Returns the folded call stacks and their number of samples, ordered by call stack
+    pub fn stacks(&self) -> impl Iterator<Item = (&str, u64)> {
+        self.stacks.iter().map(|(stack, n)| (stack.as_str(), *n))
+    }
+
+    /// Returns the samples in the folded stack format of flamegraph tools
+    ///
+    /// Each line is a call stack, from the outermost to the innermost frame and separated by
+    /// `;`, followed by a space and the number of samples. Lost samples are left out.
+    #[must_use]
+    pub fn folded(&self) -> String {
+        let mut folded = String::new();
+        for (stack, n) in self.stacks() {
+            folded.push_str(stack);
+            folded.push(' ');
+            folded.push_str(&n.to_string());
+            folded.push('\n');
+        }
+        folded
+    }
+
+    /// Returns a [`Flamegraph`] of the samples, which renders as SVG with [`Display`]
+    #[must_use]
+    pub fn flamegraph<'p>(&'p self, title: &'p str) -> Flamegraph<'p> {
+        Flamegraph {
+            profile: self,
+            title,
+        }
+    }
+}
+
+impl Display for Profile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} samples in {} call stacks",
+            self.samples,
+            self.stacks.len()
+        )?;
+        if self.lost > 0 {
+            write!(f, ", {} could not be unwound", self.lost)?;
+        }
+
+        let mut hottest: Vec<(&str, u64)> = self.stacks().collect();
+        hottest.sort_by_key(|(_, n)| std::cmp::Reverse(*n));
+        for (stack, n) in hottest.into_iter().take(SHOWN_STACKS) {
+            let percent = float(n) * 100.0 / float(self.samples);
+            write!(f, "\n  {percent:5.1}%  {stack}")?;
+        }
+        Ok(())
+    }
+}
+
+/// A flamegraph of a [`Profile`], which renders as SVG with [`Display`]
+///
+/// Each frame is a box that is as wide as the share of samples it appears in, with its callees
+/// stacked on top of it. The outermost frames are at the bottom. Hovering over a box shows its
+/// full name and its number of samples.
+#[derive(Debug, Clone, Copy)]
+pub struct Flamegraph<'p> {
+    profile: &'p Profile,
+    title: &'p str,
+}
+
+/// A frame of a [`Flamegraph`] with all its callees
+#[derive(Debug, Default)]
+struct FlameNode<'p> {
+    samples: u64,
+    children: BTreeMap<&'p str, FlameNode<'p>>,
+}
+
+impl FlameNode<'_> {
+    /// Returns how many frames are stacked on top of this one at most
+    fn depth(&self) -> usize {
+        self.children
+            .values()
+            .map(|c| c.depth() + 1)
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+impl<'p> Flamegraph<'p> {
+    /// Builds the tree of frames from the folded call stacks
+    fn tree(&self) -> FlameNode<'p> {
+        let mut root = FlameNode::default();
+        for (stack, n) in self.profile.stacks() {
+            root.samples += n;
+            let mut node = &mut root;
+            for frame in stack.split(';') {
+                node = node.children.entry(frame).or_default();
+                node.samples += n;
+            }
+        }
+        root
+    }
+
+    /// Writes the box of `node` and the boxes of its callees
+    fn write_node(
+        f: &mut fmt::Formatter<'_>,
+        name: &str,
+        node: &FlameNode<'_>,
+        (x, y): (f64, f64),
+        scale: f64,
+        total: u64,
+    ) -> fmt::Result {
+        let width = float(node.samples) * scale;
+        if width < 0.1 {
+            return Ok(());
+        }
+        let percent = float(node.samples) * 100.0 / float(total);
+        writeln!(
+            f,
+            "<g><title>{} ({} samples, {percent:.2}%)</title>",
+            escape(name),
+            node.samples
+        )?;
+        writeln!(
+            f,
+            r#"<rect x="{x:.1}" y="{y:.1}" width="{width:.1}" height="{:.1}" fill="{}" rx="2"/>"#,
+            SVG_FRAME_HEIGHT - 1.0,
+            color(name)
+        )?;
+        let fits = ((width - 6.0) / SVG_CHAR_WIDTH) as usize;
+        if fits >= 3 {
+            let label: String = if name.chars().count() > fits {
+                name.chars()
+                    .take(fits - 2)
+                    .chain("..".chars())
+                    .collect::<String>()
+            } else {
+                name.to_string()
+            };
+            writeln!(
+                f,
+                r#"<text x="{:.1}" y="{:.1}">{}</text>"#,
+                x + 3.0,
+                y + SVG_FRAME_HEIGHT - 4.5,
+                escape(&label)
+            )?;
+        }
+        writeln!(f, "</g>")?;
+
+        let mut child_x = x;
+        for (child_name, child) in &node.children {
+            Self::write_node(
+                f,
+                child_name,
+                child,
+                (child_x, y - SVG_FRAME_HEIGHT),
+                scale,
+                total,
+            )?;
+            child_x += float(child.samples) * scale;
+        }
+        Ok(())
+    }
+}
+
+impl Display for Flamegraph<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let root = self.tree();
+        let height = float(root.depth() as u64 + 1) * SVG_FRAME_HEIGHT
+            + 2.0 * SVG_PADDING
+            + SVG_TITLE_HEIGHT;
+        writeln!(
+            f,
+            r##"<?xml version="1.0" standalone="no"?>
+<svg version="1.1" width="{SVG_WIDTH}" height="{height}" viewBox="0 0 {SVG_WIDTH} {height}" xmlns="http://www.w3.org/2000/svg">
+<style>text {{ font-family: monospace; font-size: 12px; fill: #000; }} rect:hover {{ stroke: #000; }}</style>
+<rect x="0" y="0" width="100%" height="100%" fill="#f8f8f8"/>
+<text x="{:.1}" y="{:.1}" text-anchor="middle" style="font-size: 16px">{}</text>"##,
+            SVG_WIDTH / 2.0,
+            SVG_PADDING + 14.0,
+            escape(self.title)
+        )?;
+
+        if root.samples > 0 {
+            let scale = (SVG_WIDTH - 2.0 * SVG_PADDING) / float(root.samples);
+            let y = height - SVG_PADDING - SVG_FRAME_HEIGHT;
+            Self::write_node(f, "all", &root, (SVG_PADDING, y), scale, root.samples)?;
+        }
+        write!(f, "</svg>")
+    }
+}
+
+/// Converts a number of samples to compute shares and widths with it
+#[allow(clippy::cast_precision_loss)] // nobody takes 2^52 samples
+fn float(samples: u64) -> f64 {
+    samples as f64
+}
+
+/// Escapes `s` for the text of an XML element
+fn escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Picks a warm color for the frame `name`, the same name always gets the same color
+fn color(name: &str) -> String {
+    // FNV-1a, so that the colors do not change between runs
+    let hash = name.bytes().fold(0xcbf2_9ce4_8422_2325_u64, |h, b| {
+        (h ^ u64::from(b)).wrapping_mul(0x0100_0000_01b3)
+    });
+    let r = 205 + hash % 50;
+    let g = (hash >> 8) % 230;
+    let b = (hash >> 16) % 55;
+    format!("rgb({r},{g},{b})")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::unwind::BacktraceFrame;
+    use crate::Addr;
+
+    fn backtrace(names: &[Option<&str>]) -> Backtrace {
+        Backtrace {
+            frames: names
+                .iter()
+                .map(|name| BacktraceFrame {
+                    addr: Addr::from(0x1000usize),
+                    start_addr: None,
+                    name: name.map(str::to_string),
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_profile() {
+        let mut profile = Profile::default();
+        profile.add(&backtrace(&[Some("leaf"), Some("mid"), Some("main")]));
+        profile.add(&backtrace(&[Some("leaf"), Some("mid"), Some("main")]));
+        profile.add(&backtrace(&[None, Some("main")]));
+        profile.add(&backtrace(&[Some("operator;"), Some("main")]));
+        profile.add_lost();
+
+        assert_eq!(profile.samples(), 5);
+        assert_eq!(profile.lost(), 1);
+        assert_eq!(
+            profile.folded(),
+            "main;[unknown] 1\nmain;mid;leaf 2\nmain;operator: 1\n"
+        );
+        assert!(profile.to_string().starts_with(
+            "5 samples in 3 call stacks, 1 could not be unwound\n   40.0%  main;mid;leaf"
+        ));
+
+        let svg = profile.flamegraph("a <b>").to_string();
+        assert!(svg.starts_with("<?xml"));
+        assert!(svg.ends_with("</svg>"));
+        assert!(svg.contains("a &lt;b&gt;"));
+        assert!(svg.contains("<title>main (4 samples, 100.00%)</title>"));
+        assert!(svg.contains("<title>leaf (2 samples, 50.00%)</title>"));
+        assert_eq!(svg.matches("<rect").count(), 1 + 6);
+    }
+}
@@ -0,0 +1,174 @@
+//! # Register Set Module
+//!
+//! Provides an architecture independent representation of the registers of a debuggee.
+//!
+//! The raw register structs of ptrace, like [`user_regs_struct`] on `x86_64`, have a different
+//! layout on every architecture and can not be serialized. A [`RegisterSet`] is a list of named
+//! registers with their values and sizes instead, in the order of the architecture's register
+//! file. UIs can show and serialize it without knowing the architecture, and support for other
+//! architectures only needs another conversion from their raw struct.
+
+use std::fmt::{self, Display};
+
+use nix::libc::user_regs_struct;
+use serde::{Deserialize, Serialize};
+
+/// A register and its value
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct RegisterValue {
+    /// Name of the register, like `rip`
+    pub name: String,
+    /// Value of the register, zero extended if it is smaller than 64 bits
+    pub value: u64,
+    /// Size of the register in bytes
+    pub size: usize,
+}
+
+/// The registers of a debuggee, by name
+///
+/// # Examples
+///
+/// ```
+/// use coreminer::regset::RegisterSet;
+///
+/// // SAFETY: user_regs_struct only contains integers
+/// let mut raw: nix::libc::user_regs_struct = unsafe { std::mem::zeroed() };
+/// raw.rip = 0x401136;
+/// raw.eflags = 0x246;
+///
+/// let regs = RegisterSet::from(raw);
+/// assert_eq!(regs.arch(), "x86_64");
+/// assert_eq!(regs.get("rip"), Some(0x401136));
+/// assert_eq!(regs.register("eflags").unwrap().size, 4);
+/// assert_eq!(regs.get("pc"), None);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct RegisterSet {
+    arch: String,
+    registers: Vec<RegisterValue>,
+}
+
+impl RegisterSet {
+    /// Creates a [`RegisterSet`] of the architecture `arch` from its registers
+    #[must_use]
+    pub fn new(arch: impl Into<String>, registers: Vec<RegisterValue>) -> Self {
+        Self {
+            arch: arch.into(),
+            registers,
+        }
+    }
+
+    /// Returns the name of the architecture, like `x86_64`
+    #[must_use]
+    pub fn arch(&self) -> &str {
+        &self.arch
+    }
+
+    /// Returns the registers, in the order of the register file
+    #[must_use]
+    pub fn registers(&self) -> &[RegisterValue] {
+        &self.registers
+    }
+
+    /// Returns the register named `name`
+    #[must_use]
+    pub fn register(&self, name: &str) -> Option<&RegisterValue> {
+        self.registers.iter().find(|r| r.name == name)
+    }
+
+    /// Returns the value of the register named `name`
+    #[must_use]
+    pub fn get(&self, name: &str) -> Option<u64> {
+        self.register(name).map(|r| r.value)
+    }
+}
+
+impl From<user_regs_struct> for RegisterSet {
+    fn from(regs: user_regs_struct) -> Self {
+        let registers = [
+            ("rax", regs.rax, 8),
+            ("rbx", regs.rbx, 8),
+            ("rcx", regs.rcx, 8),
+            ("rdx", regs.rdx, 8),
+            ("rsi", regs.rsi, 8),
+            ("rdi", regs.rdi, 8),
+            ("rbp", regs.rbp, 8),
+            ("rsp", regs.rsp, 8),
+            ("r8", regs.r8, 8),
+            ("r9", regs.r9, 8),
+            ("r10", regs.r10, 8),
+            ("r11", regs.r11, 8),
+            ("r12", regs.r12, 8),
+            ("r13", regs.r13, 8),
+            ("r14", regs.r14, 8),
+            ("r15", regs.r15, 8),
+            ("rip", regs.rip, 8),
+            ("eflags", regs.eflags, 4),
+            ("orig_rax", regs.orig_rax, 8),
+            ("cs", regs.cs, 2),
+            ("ss", regs.ss, 2),
+            ("ds", regs.ds, 2),
+            ("es", regs.es, 2),
+            ("fs", regs.fs, 2),
+            ("gs", regs.gs, 2),
+            ("fs_base", regs.fs_base, 8),
+            ("gs_base", regs.gs_base, 8),
+        ]
+        .into_iter()
+        .map(|(name, value, size)| RegisterValue {
+            name: name.to_string(),
+            value,
+            size,
+        })
+        .collect();
+        Self::new("x86_64", registers)
+    }
+}
+
+impl Display for RegisterSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name_width = self
+            .registers
+            .iter()
+            .map(|r| r.name.len())
+            .max()
+            .unwrap_or(0);
+        for (i, reg) in self.registers.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            write!(
+                f,
+                "{:<name_width$} {:#0value_width$x}",
+                reg.name,
+                reg.value,
+                value_width = reg.size * 2 + 2
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_register_set() {
+        // SAFETY: user_regs_struct only contains integers
+        let mut raw: user_regs_struct = unsafe { std::mem::zeroed() };
+        raw.rsp = 0x7ffd_0000_1000;
+        raw.cs = 0x33;
+        let regs = RegisterSet::from(raw);
+
+        assert_eq!(regs.registers().len(), 27);
+        assert_eq!(regs.get("rsp"), Some(0x7ffd_0000_1000));
+        let text = regs.to_string();
+        assert!(text.contains("rsp      0x00007ffd00001000"));
+        assert!(text.contains("cs       0x0033"));
+
+        let json = serde_json::to_string(&regs).unwrap();
+        assert!(json.starts_with(r#"{"arch":"x86_64","registers":[{"name":"rax""#));
+        assert_eq!(serde_json::from_str::<RegisterSet>(&json).unwrap(), regs);
+    }
+}
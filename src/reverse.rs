@@ -0,0 +1,185 @@
+//! # Reverse Debugging Module
+//!
+//! Provides the recording of the stops of a debuggee, so that the debugger can go back to
+//! earlier stops.
+//!
+//! While recording, the [`Debugger`](crate::debugger::Debugger) remembers every stop of the
+//! debuggee together with the request that resumed it, and takes a [`Checkpoint`] at the first
+//! stop and then every [`Recorder::interval`] stops. To go back to an earlier stop, it restores
+//! the nearest checkpoint at or before that stop and repeats the recorded requests from there,
+//! until the debuggee is at the earlier stop again. Frequent checkpoints make going back faster,
+//! but need more memory, as each of them holds the writable memory of the debuggee.
+//!
+//! Repeating the requests only leads to the same stops if the debuggee does the same things
+//! again. This is not the case for programs that depend on the time, random numbers, input that
+//! changed or the scheduling of their threads, and for requests that stop after a timeout. Changes
+//! of memory, registers and breakpoints made between the stops are not repeated either. The
+//! debugger checks that each repeated stop is at the recorded address and stops recording if it
+//! is not.
+
+use nix::sys::signal::Signal;
+
+use crate::checkpoint::Checkpoint;
+use crate::feedback::{Status, StopReason};
+use crate::Addr;
+
+/// Number of stops between two checkpoints if no interval is given
+pub const DEFAULT_CHECKPOINT_INTERVAL: usize = 10;
+
+/// A stop of the debuggee in a [`Recorder`]
+#[derive(Debug, Clone)]
+pub struct RecordedStop {
+    /// The request that resumed the debuggee before this stop, [`None`] for the first stop
+    pub resumed_by: Option<Status>,
+    /// Why the debuggee stopped
+    pub reason: Option<StopReason>,
+    /// The signal that is delivered when the debuggee is resumed from this stop
+    pub signal: Option<Signal>,
+    /// The instruction pointer at this stop
+    pub pc: Addr,
+    /// The state of the debuggee at this stop, if a checkpoint was taken
+    pub checkpoint: Option<Checkpoint>,
+}
+
+/// The recorded stops of a debuggee
+///
+/// The recorder has a position, which is the stop that the debuggee is at. It is the last stop,
+/// unless the debugger went back to an earlier one. Recording a stop after going back discards the
+/// stops after the position, as the debuggee took a different path from there.
+#[derive(Debug, Clone)]
+pub struct Recorder {
+    interval: usize,
+    stops: Vec<RecordedStop>,
+    position: usize,
+}
+
+impl Recorder {
+    /// Starts a recording at the stop `first`, which should have a checkpoint
+    ///
+    /// An `interval` of zero is treated as one, so that every stop has a checkpoint.
+    #[must_use]
+    pub fn new(interval: usize, first: RecordedStop) -> Self {
+        Self {
+            interval: interval.max(1),
+            stops: vec![first],
+            position: 0,
+        }
+    }
+
+    /// Returns the number of stops between two checkpoints
+    #[must_use]
+    pub fn interval(&self) -> usize {
+        self.interval
+    }
+
+    /// Returns the recorded stops, in order
+    #[must_use]
+    pub fn stops(&self) -> &[RecordedStop] {
+        &self.stops
+    }
+
+    /// Returns the index of the stop that the debuggee is at
+    #[must_use]
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Sets the stop that the debuggee is at, after the debugger went back to it
+    ///
+    /// Indices after the last stop select the last stop.
+    pub fn set_position(&mut self, index: usize) {
+        self.position = index.min(self.stops.len() - 1);
+    }
+
+    /// Returns `true` if the next recorded stop should have a checkpoint
+    #[must_use]
+    pub fn takes_checkpoint(&self) -> bool {
+        (self.position + 1) % self.interval == 0
+    }
+
+    /// Records the stop after the current position and moves there
+    ///
+    /// The stops that were after the position are discarded.
+    pub fn push(&mut self, stop: RecordedStop) {
+        self.stops.truncate(self.position + 1);
+        self.stops.push(stop);
+        self.position = self.stops.len() - 1;
+    }
+
+    /// Returns the index of the stop before the current position
+    #[must_use]
+    pub fn previous(&self) -> Option<usize> {
+        self.position.checked_sub(1)
+    }
+
+    /// Returns the index of the last stop before the current position that a continue would have
+    /// stopped at, like a breakpoint or a signal
+    ///
+    /// If there is no such stop, this is the first stop.
+    #[must_use]
+    pub fn previous_event(&self) -> Option<usize> {
+        let previous = self.previous()?;
+        Some(
+            (1..=previous)
+                .rev()
+                .find(|&i| {
+                    self.stops[i]
+                        .reason
+                        .is_some_and(|r| !matches!(r, StopReason::StepDone))
+                })
+                .unwrap_or(0),
+        )
+    }
+
+    /// Returns the index and checkpoint of the last stop with a checkpoint at or before `index`
+    #[must_use]
+    pub fn checkpoint_before(&self, index: usize) -> Option<(usize, &Checkpoint)> {
+        self.stops[..=index.min(self.stops.len() - 1)]
+            .iter()
+            .enumerate()
+            .rev()
+            .find_map(|(i, stop)| stop.checkpoint.as_ref().map(|c| (i, c)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn stop(reason: Option<StopReason>) -> RecordedStop {
+        RecordedStop {
+            resumed_by: Some(Status::Continue),
+            reason,
+            signal: None,
+            pc: Addr::from(0x1000usize),
+            checkpoint: None,
+        }
+    }
+
+    #[test]
+    fn test_recorder_positions() {
+        let mut recorder = Recorder::new(2, stop(None));
+        assert_eq!(recorder.previous(), None);
+        assert_eq!(recorder.previous_event(), None);
+        assert!(!recorder.takes_checkpoint());
+
+        recorder.push(stop(Some(StopReason::StepDone)));
+        assert!(recorder.takes_checkpoint());
+        recorder.push(stop(Some(StopReason::Signal(11))));
+        recorder.push(stop(Some(StopReason::StepDone)));
+        recorder.push(stop(Some(StopReason::StepDone)));
+        assert_eq!(recorder.position(), 4);
+        assert_eq!(recorder.previous(), Some(3));
+        assert_eq!(recorder.previous_event(), Some(2));
+
+        recorder.set_position(2);
+        assert_eq!(recorder.previous_event(), Some(0));
+        recorder.push(stop(Some(StopReason::Interrupted)));
+        assert_eq!(recorder.stops().len(), 4);
+        assert_eq!(recorder.position(), 3);
+
+        recorder.set_position(100);
+        assert_eq!(recorder.position(), 3);
+        assert!(recorder.checkpoint_before(3).is_none());
+    }
+}
@@ -0,0 +1,232 @@
+//! # rr Module
+//!
+//! Provides recording the execution of a program with [rr](https://rr-project.org) and
+//! replaying the recording.
+//!
+//! rr records everything that a program does, so that the execution can later be replayed
+//! exactly, as often as needed and even backwards. Coreminer does not record programs itself,
+//! instead it launches `rr record` and `rr replay`, which must be installed.
+//!
+//! A replay is debugged through the gdbserver of `rr replay`, which speaks the GDB remote serial
+//! protocol. [`Replay::start`] launches it and connects to it with the client of the
+//! [`gdbremote`](crate::gdbremote) module, which can then step and continue through the replay,
+//! also backwards, set breakpoints and read registers and memory. The replay is separate from the
+//! debuggee of the [`Debugger`](crate::debugger::Debugger), which controls its debuggee directly
+//! with ptrace.
+
+use std::ffi::{CString, OsStr};
+use std::net::Ipv4Addr;
+use std::os::unix::ffi::OsStrExt;
+use std::os::unix::process::ExitStatusExt;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use tracing::{debug, info, warn};
+
+use crate::errors::{DebuggerError, Result};
+use crate::feedback::ExitStatus;
+use crate::gdbremote::GdbRemote;
+
+/// Name of the rr executable, which is looked up in `PATH`
+pub const RR_BINARY: &str = "rr";
+
+/// How long [`Replay::start`] waits for the gdbserver of `rr replay` to accept connections
+///
+/// rr has to load the trace first, which can take a while for long recordings.
+pub const REPLAY_CONNECT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// A finished recording of a program
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+pub struct Recording {
+    /// The directory that rr wrote the trace to
+    pub trace_dir: PathBuf,
+    /// How the recorded program exited
+    pub exit: ExitStatus,
+}
+
+/// Builds the command that records `program` with `args` into `trace_dir`
+///
+/// The arguments do not include the program itself, like in
+/// [`Status::Run`](crate::feedback::Status::Run).
+#[must_use]
+pub fn record_command(program: &Path, args: &[CString], trace_dir: &Path) -> Command {
+    let mut cmd = Command::new(RR_BINARY);
+    cmd.arg("record")
+        .arg("--output-trace-dir")
+        .arg(trace_dir)
+        .arg(program)
+        .args(args.iter().map(|a| OsStr::from_bytes(a.as_bytes())));
+    cmd
+}
+
+/// Builds the command that replays the trace in `trace_dir` with a gdbserver on `port`
+#[must_use]
+pub fn replay_command(trace_dir: &Path, port: u16) -> Command {
+    let mut cmd = Command::new(RR_BINARY);
+    cmd.arg("replay")
+        .arg(format!("--dbgport={port}"))
+        .arg(trace_dir);
+    cmd
+}
+
+/// Records the execution of `program` with rr
+///
+/// The program runs until it exits, with the standard streams of the debugger. rr refuses to
+/// write into an existing `trace_dir`.
+///
+/// # Errors
+///
+/// This function fails if rr is not installed or could not be started.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::path::Path;
+/// use coreminer::rr;
+///
+/// let recording = rr::record(Path::new("/bin/ls"), &[c"-la".into()], Path::new("/tmp/ls-trace"))
+///     .unwrap();
+/// println!("recorded into {}: {:?}", recording.trace_dir.display(), recording.exit);
+/// ```
+pub fn record(program: &Path, args: &[CString], trace_dir: &Path) -> Result<Recording> {
+    info!(
+        "recording {} into {}",
+        program.display(),
+        trace_dir.display()
+    );
+    let status = record_command(program, args, trace_dir)
+        .status()
+        .map_err(rr_error)?;
+
+    // rr exits like the recorded program
+    Ok(Recording {
+        trace_dir: trace_dir.to_path_buf(),
+        exit: exit_status(status),
+    })
+}
+
+/// A running replay of a recording, driven through the gdbserver of `rr replay`
+///
+/// The replay starts at the beginning of the recording and is stopped when it is dropped.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::path::Path;
+/// use coreminer::gdbremote::Resume;
+/// use coreminer::rr::Replay;
+///
+/// let mut replay = Replay::start(Path::new("/tmp/ls-trace"), 1234).unwrap();
+/// let remote = replay.remote();
+/// remote.resume(Resume::Continue).unwrap();
+/// // go back to the last instruction before the program exited
+/// remote.resume(Resume::ReverseStep).unwrap();
+/// println!("rip: {:#x}", remote.read_registers().unwrap().rip);
+/// ```
+pub struct Replay {
+    server: Child,
+    remote: GdbRemote,
+}
+
+impl Replay {
+    /// Starts replaying the trace in `trace_dir` and connects to its gdbserver on `port`
+    ///
+    /// # Errors
+    ///
+    /// This function fails if rr is not installed or could not be started, with
+    /// [`DebuggerError::RrReplayExited`] if `rr replay` exited before accepting the connection,
+    /// for example because the trace does not exist, or if it did not accept the connection
+    /// within [`REPLAY_CONNECT_TIMEOUT`].
+    pub fn start(trace_dir: &Path, port: u16) -> Result<Self> {
+        info!("replaying {} on port {port}", trace_dir.display());
+        let mut server = replay_command(trace_dir, port).spawn().map_err(rr_error)?;
+
+        let started = Instant::now();
+        let remote = loop {
+            match GdbRemote::connect((Ipv4Addr::LOCALHOST, port)) {
+                Ok(remote) => break remote,
+                Err(e) => {
+                    if let Some(status) = server.try_wait()? {
+                        return Err(DebuggerError::RrReplayExited(exit_status(status)));
+                    }
+                    if started.elapsed() > REPLAY_CONNECT_TIMEOUT {
+                        warn!("rr replay did not accept a connection in time");
+                        server.kill()?;
+                        server.wait()?;
+                        return Err(e);
+                    }
+                    thread::sleep(Duration::from_millis(100));
+                }
+            }
+        };
+        Ok(Self { server, remote })
+    }
+
+    /// Gets the connection to the gdbserver, to control the replay
+    pub fn remote(&mut self) -> &mut GdbRemote {
+        &mut self.remote
+    }
+}
+
+impl Drop for Replay {
+    fn drop(&mut self) {
+        debug!("stopping the rr replay {}", self.server.id());
+        if let Err(e) = self.server.kill().and_then(|()| self.server.wait()) {
+            warn!("could not stop the rr replay: {e}");
+        }
+    }
+}
+
+/// Converts how rr exited, which is how the recorded program exited for `rr record`
+fn exit_status(status: std::process::ExitStatus) -> ExitStatus {
+    match (status.code(), status.signal()) {
+        (Some(code), _) => ExitStatus::Exited(code),
+        (None, signal) => ExitStatus::Signaled {
+            signal: signal.unwrap_or_default(),
+            core_dumped: status.core_dumped(),
+        },
+    }
+}
+
+/// Tells apart a missing rr from other errors when starting it
+fn rr_error(err: std::io::Error) -> DebuggerError {
+    if err.kind() == std::io::ErrorKind::NotFound {
+        DebuggerError::RrNotInstalled
+    } else {
+        err.into()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_rr_commands() {
+        let cmd = record_command(
+            Path::new("/bin/ls"),
+            &[c"-la".into(), c"/etc".into()],
+            Path::new("/tmp/trace"),
+        );
+        assert_eq!(cmd.get_program(), RR_BINARY);
+        let args: Vec<_> = cmd.get_args().collect();
+        assert_eq!(
+            args,
+            [
+                "record",
+                "--output-trace-dir",
+                "/tmp/trace",
+                "/bin/ls",
+                "-la",
+                "/etc"
+            ]
+        );
+
+        let cmd = replay_command(Path::new("/tmp/trace"), 1337);
+        let args: Vec<_> = cmd.get_args().collect();
+        assert_eq!(args, ["replay", "--dbgport=1337", "/tmp/trace"]);
+    }
+}
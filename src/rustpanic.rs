@@ -0,0 +1,244 @@
+//! # Rust Panic Module
+//!
+//! Provides a catchpoint that stops Rust programs when they panic.
+//!
+//! A panicking Rust program unwinds its stack or aborts right after printing the panic message,
+//! so by the time the debugger sees a signal or an exit, the state at the panic site is gone.
+//! The catchpoint instead stops at `rust_panic`, the function of the standard library that every
+//! panic goes through right before unwinding starts, after the panic hook printed the message.
+//! At that point, the frames of the panicking code are still on the stack.
+//!
+//! The location of the panic is taken from `core::panicking::panic_fmt`, which formatted panics
+//! call with the [`Location`](https://doc.rust-lang.org/core/panic/struct.Location.html) of the
+//! caller. The message is taken from the payload that is passed to `rust_panic`. Neither is
+//! available in every case: panics with a payload that is not a string, like the ones of
+//! `std::panic::panic_any`, have no message, and panics that do not go through `panic_fmt`, like
+//! `panic!` with a single argument in Rust 2018, have no location.
+//!
+//! Both functions are found in the symbol table of the executable by their demangled names, so
+//! the catchpoint works with the legacy and the v0 mangling scheme, but not with stripped
+//! executables. Enable it with
+//! [`Debugger::catch_panics`](crate::debugger::Debugger::catch_panics).
+
+use std::fmt::{self, Display};
+
+use object::{Object, ObjectSymbol, SymbolKind};
+use serde::Serialize;
+
+use crate::modules::LoadedModule;
+use crate::Addr;
+
+/// Longest file path of a [`PanicLocation`] and longest message of a [`RustPanic`] that is read
+const MAX_STRING_LEN: usize = 4096;
+
+/// Demangled paths of the functions that report the location of a panic
+const LOCATION_FUNCTIONS: &[&str] = &["core::panicking::panic_fmt"];
+
+/// Demangled paths of the functions that start the unwinding of a panic
+const PANIC_FUNCTIONS: &[&str] = &[
+    "rust_panic",
+    "__rustc::rust_panic",
+    "std::panicking::rust_panic",
+];
+
+/// Why the debuggee stopped at a breakpoint of the panic catchpoint
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum PanicBreakpoint {
+    /// At `panic_fmt`, where the location of the panic is known
+    Location,
+    /// At `rust_panic`, where the panic starts unwinding
+    Panic,
+}
+
+/// The place in the source code where a panic happened
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+pub struct PanicLocation {
+    /// Path of the source file, as recorded by the compiler
+    pub file: String,
+    /// Line in the source file, starting at 1
+    pub line: u32,
+    /// Column in the line, starting at 1
+    pub column: u32,
+}
+
+/// A panic of a Rust debuggee, which stopped it at `rust_panic`
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+pub struct RustPanic {
+    /// Address of `rust_panic`, where the debuggee is stopped
+    pub addr: Addr,
+    /// The panic message, if the payload of the panic is a string
+    pub message: Option<String>,
+    /// Where the panic happened, if it is known
+    pub location: Option<PanicLocation>,
+}
+
+impl Display for PanicLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}:{}", self.file, self.line, self.column)
+    }
+}
+
+impl Display for RustPanic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "panicked")?;
+        if let Some(location) = &self.location {
+            write!(f, " at {location}")?;
+        }
+        match &self.message {
+            Some(message) => write!(f, ": {message}"),
+            None => write!(f, " with a payload that is not a string"),
+        }
+    }
+}
+
+/// Finds the functions of the panic catchpoint in the symbol table of the executable
+///
+/// # Returns
+///
+/// The addresses of the functions in the debuggee, with the reason to stop at each of them.
+pub(crate) fn find_breakpoints(
+    obj: &object::File<'_>,
+    executable: &LoadedModule,
+) -> Vec<(Addr, PanicBreakpoint)> {
+    let mut breakpoints: Vec<(Addr, PanicBreakpoint)> = obj
+        .symbols()
+        .filter(|s| s.kind() == SymbolKind::Text && s.address() != 0)
+        .filter_map(|s| {
+            let name = format!("{:#}", rustc_demangle::demangle(s.name().ok()?));
+            let kind = if LOCATION_FUNCTIONS.contains(&name.as_str()) {
+                PanicBreakpoint::Location
+            } else if PANIC_FUNCTIONS.contains(&name.as_str()) {
+                PanicBreakpoint::Panic
+            } else {
+                return None;
+            };
+            Some((executable.to_process(s.address()), kind))
+        })
+        .collect();
+    breakpoints.sort_unstable_by_key(|(addr, _)| *addr);
+    breakpoints.dedup();
+    breakpoints
+}
+
+/// Reads a `core::panic::Location` at `addr`
+///
+/// A `Location` is a `&str` with the file followed by the line and column as `u32`. Since the
+/// layout of Rust structs is not stable and the register that holds the location depends on the
+/// version of the compiler, the result is checked to be plausible, so that `None` is returned
+/// instead of garbage when `addr` is something else.
+///
+/// # Parameters
+///
+/// * `read` - Reads a number of bytes at an address of the debuggee, `None` if it cannot
+/// * `addr` - Where the `Location` might be
+pub(crate) fn read_location(
+    read: impl Fn(Addr, usize) -> Option<Vec<u8>>,
+    addr: Addr,
+) -> Option<PanicLocation> {
+    let raw = read(addr, 24)?;
+    let word = |i: usize| u64::from_ne_bytes(raw[i * 8..i * 8 + 8].try_into().unwrap_or_default());
+    let half = |i: usize| u32::from_ne_bytes(raw[i * 4..i * 4 + 4].try_into().unwrap_or_default());
+    let (line, column) = (half(4), half(5));
+    if line == 0 {
+        return None;
+    }
+    let file = read_str(&read, word(0), word(1))?;
+    Some(PanicLocation { file, line, column })
+}
+
+/// Reads the message of a panic from its payload, a `&mut dyn PanicPayload`
+///
+/// The payload is a struct of the standard library, which holds the message either as a `&str`
+/// or, once the panic hook has formatted it, as a `String`. Both contain a pointer that is
+/// directly followed by a length, so the payload is searched for such a pair that points to a
+/// string.
+///
+/// # Parameters
+///
+/// * `read` - Reads a number of bytes at an address of the debuggee, `None` if it cannot
+/// * `data` - The data pointer of the payload
+/// * `vtable` - The vtable pointer of the payload, which has the size of the payload
+pub(crate) fn read_message(
+    read: impl Fn(Addr, usize) -> Option<Vec<u8>>,
+    data: Addr,
+    vtable: Addr,
+) -> Option<String> {
+    // the vtable starts with the drop function, the size and the alignment
+    let size = read(vtable + 8usize, 8)?;
+    let size = usize::try_from(u64::from_ne_bytes(size.try_into().ok()?)).ok()?;
+    if !(16..=256).contains(&size) {
+        return None;
+    }
+    let payload = read(data, size)?;
+    let words: Vec<u64> = payload
+        .chunks_exact(8)
+        .map(|w| u64::from_ne_bytes(w.try_into().unwrap_or_default()))
+        .collect();
+    words
+        .windows(2)
+        .find_map(|pair| read_str(&read, pair[0], pair[1]))
+}
+
+/// Reads a `&str` with the pointer `ptr` and the length `len`, if it is plausible text
+fn read_str(read: &impl Fn(Addr, usize) -> Option<Vec<u8>>, ptr: u64, len: u64) -> Option<String> {
+    let len = usize::try_from(len).ok()?;
+    if ptr == 0 || len == 0 || len > MAX_STRING_LEN {
+        return None;
+    }
+    let bytes = read(Addr::from(usize::try_from(ptr).ok()?), len)?;
+    let text = String::from_utf8(bytes).ok()?;
+    if text
+        .chars()
+        .any(|c| c.is_control() && c != '\n' && c != '\t')
+    {
+        return None;
+    }
+    Some(text)
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    /// Builds a reader of the memory regions in `memory`, like a debuggee would have them
+    fn reader(memory: HashMap<usize, Vec<u8>>) -> impl Fn(Addr, usize) -> Option<Vec<u8>> {
+        move |addr, len| {
+            memory.iter().find_map(|(start, bytes)| {
+                let offset = addr.usize().checked_sub(*start)?;
+                bytes.get(offset..offset + len).map(<[u8]>::to_vec)
+            })
+        }
+    }
+
+    fn words(words: &[u64]) -> Vec<u8> {
+        words.iter().flat_map(|w| w.to_ne_bytes()).collect()
+    }
+
+    #[test]
+    fn test_read_panic() {
+        let mut location = words(&[0x1000, 8]);
+        location.extend(3u32.to_ne_bytes());
+        location.extend(9u32.to_ne_bytes());
+        let memory = HashMap::from([
+            (0x1000, b"src/p.rs".to_vec()),
+            (0x2000, location),
+            (0x3000, b"index 7 is too large".to_vec()),
+            // a formatted payload: the arguments and the String with capacity, pointer, length
+            (0x4000, words(&[0x2000, 20, 0x3000, 20])),
+            // drop function, size, alignment
+            (0x5000, words(&[0xdead, 32, 8])),
+        ]);
+        let read = reader(memory);
+
+        let location = read_location(&read, Addr::from(0x2000usize)).unwrap();
+        assert_eq!(location.to_string(), "src/p.rs:3:9");
+        assert_eq!(read_location(&read, Addr::from(0x4000usize)), None);
+
+        assert_eq!(
+            read_message(&read, Addr::from(0x4000usize), Addr::from(0x5000usize)).as_deref(),
+            Some("index 7 is too large")
+        );
+    }
+}
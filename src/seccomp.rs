@@ -0,0 +1,210 @@
+//! # Seccomp Module
+//!
+//! Provides tracing only selected syscalls of a debugged process with a seccomp filter.
+//!
+//! With `PTRACE_SYSCALL`, the debuggee stops at the entry and exit of every syscall, even if the
+//! debugger is only interested in a few of them. Programs that make many syscalls become very
+//! slow like that. A [`SyscallFilter`] is compiled into a seccomp-BPF program that is installed
+//! in the debuggee right before it executes the program. The filter returns `SECCOMP_RET_TRACE`
+//! for the selected syscalls, so only those stop the debuggee with `PTRACE_EVENT_SECCOMP`, and
+//! lets all other syscalls pass without involving the debugger.
+//!
+//! A seccomp filter cannot be removed again, so it stays for the whole life of the debuggee and
+//! is inherited by its children. Some consequences:
+//!
+//! - The filter can only be installed when the debuggee is launched, not when attaching.
+//! - While the debugger traces the debuggee, it needs `PTRACE_O_TRACESECCOMP`, or the selected
+//!   syscalls fail with `ENOSYS`. The same happens after the debugger detached.
+//! - The debuggee gets the `no_new_privs` attribute, so that the filter can be installed without
+//!   privileges.
+
+use nix::libc;
+use serde::{Deserialize, Serialize};
+
+use crate::errors::{DebuggerError, Result};
+
+/// `AUDIT_ARCH_X86_64`, the architecture of syscalls with the `x86_64` ABI
+const AUDIT_ARCH_X86_64: u32 = 0xc000_003e;
+/// Offset of the syscall number in `struct seccomp_data`
+const SECCOMP_DATA_NR: u32 = 0;
+/// Offset of the architecture in `struct seccomp_data`
+const SECCOMP_DATA_ARCH: u32 = 4;
+/// `BPF_LD | BPF_W | BPF_ABS`: load a word of the `seccomp_data`
+const BPF_LOAD_WORD: u16 = 0x20;
+/// `BPF_JMP | BPF_JEQ | BPF_K`: jump if the accumulator equals a constant
+const BPF_JUMP_EQ: u16 = 0x15;
+/// `BPF_RET | BPF_K`: return a constant
+const BPF_RETURN: u16 = 0x06;
+/// Most syscalls a filter can select, as the BPF jumps have 8 bit offsets
+pub const MAX_FILTERED_SYSCALLS: usize = 254;
+
+/// A `struct sock_filter`, one instruction of a BPF program
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BpfInstruction {
+    code: u16,
+    jt: u8,
+    jf: u8,
+    k: u32,
+}
+
+impl BpfInstruction {
+    /// Creates an instruction that does not jump
+    const fn stmt(code: u16, k: u32) -> Self {
+        Self {
+            code,
+            jt: 0,
+            jf: 0,
+            k,
+        }
+    }
+
+    /// Creates a conditional jump, `jt` and `jf` are relative to the next instruction
+    const fn jump(code: u16, k: u32, jt: u8, jf: u8) -> Self {
+        Self { code, jt, jf, k }
+    }
+}
+
+/// A `struct sock_fprog`, which points to a BPF program
+#[repr(C)]
+struct BpfProgram {
+    len: libc::c_ushort,
+    filter: *const BpfInstruction,
+}
+
+/// The syscalls that should stop the debuggee
+///
+/// # Examples
+///
+/// ```
+/// use coreminer::seccomp::SyscallFilter;
+///
+/// let filter = SyscallFilter::new(vec![nix::libc::SYS_openat as u64, nix::libc::SYS_write as u64]);
+/// assert!(filter.contains(nix::libc::SYS_openat as u64));
+/// assert!(!filter.contains(nix::libc::SYS_read as u64));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(from = "Vec<u64>", into = "Vec<u64>")]
+pub struct SyscallFilter {
+    syscalls: Vec<u64>,
+}
+
+impl SyscallFilter {
+    /// Creates a filter that selects the given syscall numbers
+    #[must_use]
+    pub fn new(mut syscalls: Vec<u64>) -> Self {
+        syscalls.sort_unstable();
+        syscalls.dedup();
+        Self { syscalls }
+    }
+
+    /// Returns `true` if the syscall with the number `nr` is selected
+    #[must_use]
+    pub fn contains(&self, nr: u64) -> bool {
+        self.syscalls.binary_search(&nr).is_ok()
+    }
+
+    /// Returns the selected syscall numbers in ascending order
+    #[must_use]
+    pub fn syscalls(&self) -> &[u64] {
+        &self.syscalls
+    }
+
+    /// Compiles the filter into a seccomp-BPF program
+    ///
+    /// The program returns `SECCOMP_RET_TRACE` for the selected syscalls of the `x86_64` ABI and
+    /// `SECCOMP_RET_ALLOW` for everything else.
+    ///
+    /// # Errors
+    ///
+    /// This function fails if more than [`MAX_FILTERED_SYSCALLS`] syscalls are selected.
+    pub fn program(&self) -> Result<Vec<BpfInstruction>> {
+        let n = self.syscalls.len();
+        if n > MAX_FILTERED_SYSCALLS {
+            return Err(DebuggerError::SyscallFilterTooLarge(n));
+        }
+
+        // the program is: check arch, load nr, one comparison per syscall, allow, trace
+        let mut program = vec![
+            BpfInstruction::stmt(BPF_LOAD_WORD, SECCOMP_DATA_ARCH),
+            BpfInstruction::jump(BPF_JUMP_EQ, AUDIT_ARCH_X86_64, 0, n as u8 + 1),
+            BpfInstruction::stmt(BPF_LOAD_WORD, SECCOMP_DATA_NR),
+        ];
+        for (i, nr) in self.syscalls.iter().enumerate() {
+            program.push(BpfInstruction::jump(
+                BPF_JUMP_EQ,
+                *nr as u32,
+                (n - i) as u8,
+                0,
+            ));
+        }
+        program.push(BpfInstruction::stmt(BPF_RETURN, libc::SECCOMP_RET_ALLOW));
+        program.push(BpfInstruction::stmt(BPF_RETURN, libc::SECCOMP_RET_TRACE));
+        Ok(program)
+    }
+}
+
+impl From<Vec<u64>> for SyscallFilter {
+    fn from(syscalls: Vec<u64>) -> Self {
+        Self::new(syscalls)
+    }
+}
+
+impl From<SyscallFilter> for Vec<u64> {
+    fn from(filter: SyscallFilter) -> Self {
+        filter.syscalls
+    }
+}
+
+/// Installs a seccomp-BPF `program` for the calling thread
+///
+/// This is meant to be called in the child process between `fork` and `exec`, so it does not
+/// allocate.
+///
+/// # Errors
+///
+/// This function fails if `no_new_privs` cannot be set or the program is rejected by the kernel.
+pub fn install(program: &[BpfInstruction]) -> Result<()> {
+    let fprog = BpfProgram {
+        len: program.len() as libc::c_ushort,
+        filter: program.as_ptr(),
+    };
+    // SAFETY: prctl with PR_SET_NO_NEW_PRIVS takes no pointers
+    let ret = unsafe { libc::prctl(libc::PR_SET_NO_NEW_PRIVS, 1, 0, 0, 0) };
+    nix::errno::Errno::result(ret)?;
+    // SAFETY: fprog points to the program, which outlives the syscall
+    let ret = unsafe {
+        libc::syscall(
+            libc::SYS_seccomp,
+            libc::SECCOMP_SET_MODE_FILTER,
+            0,
+            std::ptr::addr_of!(fprog),
+        )
+    };
+    nix::errno::Errno::result(ret)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_filter_program() {
+        let filter = SyscallFilter::new(vec![257, 1, 257]);
+        assert_eq!(filter.syscalls(), &[1, 257]);
+
+        let program = filter.program().unwrap();
+        assert_eq!(program.len(), 7);
+        // a foreign architecture jumps to the allow
+        assert_eq!(program[1].jf, 3);
+        assert_eq!(program[5].k, libc::SECCOMP_RET_ALLOW);
+        // both comparisons jump to the trace
+        assert_eq!((program[3].k, program[3].jt), (1, 2));
+        assert_eq!((program[4].k, program[4].jt), (257, 1));
+        assert_eq!(program[6].k, libc::SECCOMP_RET_TRACE);
+
+        let too_large = SyscallFilter::new((0..=MAX_FILTERED_SYSCALLS as u64).collect());
+        assert!(too_large.program().is_err());
+    }
+}
@@ -0,0 +1,141 @@
+//! # Session Module
+//!
+//! Provides saving the configuration of the debugger to a session file and restoring it later.
+//!
+//! Long investigations usually build up a lot of state: [Breakpoints](crate::breakpoint::Breakpoint)
+//! and [Watchpoints](crate::watchpoint::Watchpoint) at interesting places, catchpoints,
+//! anti-anti-debugging countermeasures, the expressions that are displayed at every stop and the
+//! enabled plugins. A [`Session`] contains all of
+//! that, so it survives restarts of both the debugger and the debuggee. It is saved with
+//! [`Debugger::save_session`](crate::debugger::Debugger::save_session) and restored with
+//! [`Debugger::restore_session`](crate::debugger::Debugger::restore_session).
+//!
+//! Like in a [`BreakpointFile`](crate::bpfile::BreakpointFile), locations are saved with a
+//! [`BreakpointAnchor`] and rebased to the load addresses of the debuggee when they are restored.
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "plugins")]
+use steckrs::PluginIDOwned;
+
+use crate::antidebug::AntiDebugConfig;
+use crate::bpfile::{BreakpointAnchor, SavedBreakpoint};
+use crate::catchpoint::MemoryCatchConfig;
+use crate::debugger::{FollowFork, LaunchBreak, QuitAction, SchedulerLocking, WriteVerification};
+use crate::errors::Result;
+use crate::launchenv::LaunchEnv;
+use crate::limits::Limits;
+use crate::traceopts::PtraceOptions;
+use crate::watchpoint::WatchCondition;
+
+/// The settings of the [`Debugger`](crate::debugger::Debugger) that are saved in a [`Session`]
+///
+/// Every field corresponds to a [`Status`](crate::feedback::Status) that changes it, for example
+/// [`Status::SetAntiDebug`](crate::feedback::Status::SetAntiDebug) for `anti_debug`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    /// The [anti-anti-debugging](crate::antidebug) countermeasures
+    pub anti_debug: AntiDebugConfig,
+    /// Whether the debuggee is stopped at every syscall
+    pub catch_syscalls: bool,
+    /// The names of the syscalls that are caught, or [None] for all syscalls
+    ///
+    /// Syscalls without a name are saved by their number.
+    pub syscall_filter: Option<Vec<String>>,
+    /// The memory [catchpoints](crate::catchpoint)
+    pub catch_memory: MemoryCatchConfig,
+    /// Whether Rust panics stop the debuggee, see [`crate::rustpanic`]
+    pub catch_panics: bool,
+    /// Where a new debuggee is stopped after it was launched
+    pub launch_break: Option<LaunchBreak>,
+    /// The changes to the [environment](crate::launchenv) of new debuggees
+    pub launch_env: LaunchEnv,
+    /// Which writes into the memory of the debuggee are checked
+    pub write_verification: WriteVerification,
+    /// The [ptrace options](crate::traceopts) of the debuggee
+    pub ptrace_options: PtraceOptions,
+    /// What happens to the debuggee when the debugger quits
+    pub quit_action: QuitAction,
+    /// Which threads run while the main thread is resumed
+    pub scheduler_locking: SchedulerLocking,
+    /// Which process is debugged after the debuggee forks
+    pub follow_fork: FollowFork,
+    /// The caps on expensive requests
+    pub limits: Limits,
+}
+
+/// A [`Watchpoint`](crate::watchpoint::Watchpoint) in a [`Session`]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct SavedWatchpoint {
+    /// Where the watched memory range starts
+    pub anchor: BreakpointAnchor,
+    /// Length of the watched memory range in bytes
+    pub len: usize,
+    /// The condition that a change must fulfill to be reported
+    #[serde(default)]
+    pub condition: Option<WatchCondition>,
+}
+
+/// The contents of a session file
+///
+/// All fields are optional in the file, so a session file can also be written by hand with
+/// only the parts that are needed.
+///
+/// # Examples
+///
+/// ```
+/// use coreminer::session::Session;
+///
+/// let session: Session = serde_json::from_str(r#"{
+///     "settings": { "catch_syscalls": true, "launch_break": "Main" },
+///     "breakpoints": [
+///         { "anchor": { "Symbol": { "name": "main", "offset": 8 } }, "hardware": false }
+///     ]
+/// }"#).unwrap();
+///
+/// assert!(session.settings.catch_syscalls);
+/// assert_eq!(session.breakpoints.len(), 1);
+/// assert!(session.watchpoints.is_empty());
+/// assert!(session.displays.is_empty());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Session {
+    /// The settings of the debugger
+    pub settings: Settings,
+    /// The breakpoints of the debuggee
+    pub breakpoints: Vec<SavedBreakpoint>,
+    /// The watchpoints of the debuggee
+    pub watchpoints: Vec<SavedWatchpoint>,
+    /// The variable expressions that are displayed at every stop, see
+    /// [`Debugger::add_auto_display`](crate::debugger::Debugger::add_auto_display)
+    pub displays: Vec<String>,
+    /// The loaded plugins and whether they are enabled
+    #[cfg(feature = "plugins")]
+    pub plugins: Vec<(PluginIDOwned, bool)>,
+}
+
+impl Session {
+    /// Reads a [`Session`] from `path`
+    ///
+    /// # Errors
+    ///
+    /// This function fails if the file cannot be read or is not a valid session file.
+    pub fn read(path: impl AsRef<Path>) -> Result<Self> {
+        let data = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&data)?)
+    }
+
+    /// Writes the [`Session`] to `path`, replacing the file if it exists
+    ///
+    /// # Errors
+    ///
+    /// This function fails if the file cannot be written.
+    pub fn write(&self, path: impl AsRef<Path>) -> Result<()> {
+        let data = serde_json::to_string_pretty(self)?;
+        std::fs::write(path, data)?;
+        Ok(())
+    }
+}
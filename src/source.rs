@@ -0,0 +1,213 @@
+//! # Source Module
+//!
+//! Provides listings of the source code of the debuggee.
+//!
+//! The source of a file is taken from the debug information if the compiler embedded it into
+//! the DWARF 5 line program, which clang does with `-gembed-source`. Then no files are needed on
+//! disk, and the listing always matches the code that was compiled. Otherwise the file is read
+//! from the path the compiler recorded. If the line program has an MD5 digest of the file, it is
+//! compared with the file on disk, so that a listing of a changed file is marked as such instead
+//! of silently showing the wrong lines.
+
+use std::fmt::{self, Display};
+
+use serde::Serialize;
+
+use crate::errors::{DebuggerError, Result};
+use crate::lines::LineTable;
+
+/// Number of lines shown before and after the line of a listing
+pub const DEFAULT_CONTEXT_LINES: u64 = 5;
+
+/// Where the text of a [`SourceListing`] came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+pub enum SourceOrigin {
+    /// The source embedded in the debug information
+    Embedded,
+    /// The file on disk, which matches the recorded digest or has none recorded
+    Disk,
+    /// The file on disk, which was changed since it was compiled
+    ChangedOnDisk,
+}
+
+/// A range of lines of a source file
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+pub struct SourceListing {
+    /// Path of the source file, as recorded by the compiler
+    pub file: String,
+    /// Number of the first line in [`Self::lines`], starting at 1
+    pub first_line: u64,
+    /// The text of the lines, without line breaks
+    pub lines: Vec<String>,
+    /// The line that is marked, like the line of the current instruction
+    pub marked: Option<u64>,
+    /// Where the text came from
+    pub origin: SourceOrigin,
+}
+
+impl Display for SourceListing {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.file)?;
+        match self.origin {
+            SourceOrigin::Embedded => write!(f, " (embedded in the debug information)")?,
+            SourceOrigin::Disk => (),
+            SourceOrigin::ChangedOnDisk => write!(f, " (changed since it was compiled)")?,
+        }
+        for (number, line) in (self.first_line..).zip(&self.lines) {
+            let marker = if self.marked == Some(number) {
+                "=>"
+            } else {
+                "  "
+            };
+            write!(f, "\n{marker}{number:>5}  {line}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Lists the lines around `line` of `file`
+///
+/// # Parameters
+///
+/// * `lines` - The line table of the executable, with the recorded paths and sources
+/// * `file` - The source file, as its path or the end of its path like `hot.c`
+/// * `line` - The line in the middle of the listing, which is also marked
+/// * `context` - How many lines to show before and after `line`
+///
+/// # Errors
+///
+/// This function fails if the source is not embedded and the file cannot be read, or if `line`
+/// is past the end of the file.
+pub fn listing(lines: &LineTable, file: &str, line: u64, context: u64) -> Result<SourceListing> {
+    let path = lines.path_of(file).unwrap_or(file).to_string();
+    let recorded = lines.source_file(&path);
+
+    let (text, origin) = if let Some(text) = recorded.and_then(|f| f.source.clone()) {
+        (text, SourceOrigin::Embedded)
+    } else {
+        let data = std::fs::read(&path)
+            .map_err(|e| DebuggerError::SourceUnavailable(path.clone(), e.to_string()))?;
+        let origin = match recorded.and_then(|f| f.md5) {
+            Some(recorded) if !digest_matches(recorded, md5(&data)) => SourceOrigin::ChangedOnDisk,
+            _ => SourceOrigin::Disk,
+        };
+        (String::from_utf8_lossy(&data).into_owned(), origin)
+    };
+
+    let total = text.lines().count() as u64;
+    if line == 0 || line > total {
+        return Err(DebuggerError::LineOutOfRange(path, line));
+    }
+    let first_line = line.saturating_sub(context).max(1);
+    let last_line = line.saturating_add(context).min(total);
+    Ok(SourceListing {
+        first_line,
+        lines: text
+            .lines()
+            .skip(first_line as usize - 1)
+            .take((last_line - first_line + 1) as usize)
+            .map(str::to_string)
+            .collect(),
+        file: path,
+        marked: Some(line),
+        origin,
+    })
+}
+
+/// Checks if a digest from a line program is `digest`
+///
+/// LLVM records the bytes of the digest in order, while the GNU assembler records them as a
+/// little endian number, which reverses them.
+fn digest_matches(recorded: [u8; 16], mut digest: [u8; 16]) -> bool {
+    if recorded == digest {
+        return true;
+    }
+    digest.reverse();
+    recorded == digest
+}
+
+/// Computes the MD5 digest of `data`, as recorded for source files in DWARF 5 line programs
+#[allow(clippy::many_single_char_names)] // the names of RFC 1321
+fn md5(data: &[u8]) -> [u8; 16] {
+    const SHIFTS: [u32; 16] = [7, 12, 17, 22, 5, 9, 14, 20, 4, 11, 16, 23, 6, 10, 15, 21];
+    // the integer parts of the sines of 1 to 64, in radians, scaled by 2^32
+    let constants: Vec<u32> = (1..=64)
+        .map(|i: i32| (f64::from(i).sin().abs() * 4_294_967_296.0) as u32)
+        .collect();
+
+    let mut message = data.to_vec();
+    message.push(0x80);
+    while message.len() % 64 != 56 {
+        message.push(0);
+    }
+    message.extend_from_slice(&((data.len() as u64).wrapping_mul(8)).to_le_bytes());
+
+    let mut state: [u32; 4] = [0x6745_2301, 0xefcd_ab89, 0x98ba_dcfe, 0x1032_5476];
+    for chunk in message.chunks_exact(64) {
+        let words: Vec<u32> = chunk
+            .chunks_exact(4)
+            .map(|w| u32::from_le_bytes([w[0], w[1], w[2], w[3]]))
+            .collect();
+        let [mut a, mut b, mut c, mut d] = state;
+        for i in 0..64 {
+            let (f, g) = match i / 16 {
+                0 => ((b & c) | (!b & d), i),
+                1 => ((d & b) | (!d & c), (5 * i + 1) % 16),
+                2 => (b ^ c ^ d, (3 * i + 5) % 16),
+                _ => (c ^ (b | !d), (7 * i) % 16),
+            };
+            let rotated = a
+                .wrapping_add(f)
+                .wrapping_add(constants[i])
+                .wrapping_add(words[g])
+                .rotate_left(SHIFTS[(i / 16) * 4 + i % 4]);
+            (a, b, c, d) = (d, b.wrapping_add(rotated), b, c);
+        }
+        for (s, v) in state.iter_mut().zip([a, b, c, d]) {
+            *s = s.wrapping_add(v);
+        }
+    }
+
+    let mut digest = [0; 16];
+    for (bytes, s) in digest.chunks_exact_mut(4).zip(state) {
+        bytes.copy_from_slice(&s.to_le_bytes());
+    }
+    digest
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::lines::SourceFile;
+
+    fn hex(digest: [u8; 16]) -> String {
+        format!("{:032x}", u128::from_be_bytes(digest))
+    }
+
+    #[test]
+    fn test_md5() {
+        assert_eq!(hex(md5(b"")), "d41d8cd98f00b204e9800998ecf8427e");
+        assert_eq!(hex(md5(b"abc")), "900150983cd24fb0d6963f7d28e17f72");
+        assert_eq!(hex(md5(&[b'a'; 100])), "36a92cc94a9e0fa21f625f8bfb007adf");
+    }
+
+    #[test]
+    fn test_embedded_listing() {
+        let source = (1..=20)
+            .map(|i| format!("line {i}"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let table = LineTable::new(Vec::new()).with_files(vec![SourceFile {
+            path: "/nonexistent/src/hot.c".to_string(),
+            md5: None,
+            source: Some(source),
+        }]);
+
+        let listing = listing(&table, "hot.c", 3, 5).unwrap();
+        assert_eq!(listing.origin, SourceOrigin::Embedded);
+        assert_eq!(listing.first_line, 1);
+        assert_eq!(listing.lines.len(), 8);
+        assert_eq!(listing.lines[2], "line 3");
+        assert!(super::listing(&table, "hot.c", 21, 5).is_err());
+    }
+}
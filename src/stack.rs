@@ -94,6 +94,16 @@ impl Stack {
     pub fn words(&self) -> &[Word] {
         &self.words
     }
+
+    /// Gets the address of the first word of the stack
+    ///
+    /// # Returns
+    ///
+    /// The starting address this stack was created with
+    #[must_use]
+    pub fn start_addr(&self) -> Addr {
+        self.start_addr
+    }
 }
 
 impl Display for Stack {
@@ -0,0 +1,597 @@
+//! # Symbol Index Module
+//!
+//! Provides listings of the functions, types and global variables of a debuggee, filtered by
+//! name.
+//!
+//! The functions of an executable can be known from three places: the DWARF debug information,
+//! which also knows the source file, the symbol table of the ELF file, and the
+//! [heuristics](crate::heuristics) for stripped executables. The index merges them into one list,
+//! where a function is only taken from the most detailed source that knows it.
+//!
+//! Types are only known from the DWARF debug information. A type that is declared in a header is
+//! listed once, even if every compilation unit including the header has its own copy of it.
+//!
+//! Global and static variables are taken from the debug information, which also knows their
+//! types, and from the symbol tables of the executable and the exports of the shared libraries.
+//!
+//! Names are filtered with a [`NamePattern`], which is a glob like `str*` or `*_init?`, or a
+//! substring of the name if it has no wildcards.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt::{self, Display, Write as _};
+use std::path::Path;
+
+use object::{Object, ObjectSymbol, SymbolKind as ObjSymbolKind};
+use serde::Serialize;
+
+use crate::dbginfo::{search_through_symbols, OwnedSymbol, SymbolKind};
+use crate::errors::Result;
+use crate::heuristics::HeuristicSymbols;
+use crate::modules::LoadedModule;
+use crate::Addr;
+
+/// A filter for names
+///
+/// `*` matches any number of characters and `?` exactly one. A pattern without wildcards matches
+/// every name that contains it, the empty pattern matches everything.
+///
+/// # Examples
+///
+/// ```
+/// use coreminer::symindex::NamePattern;
+///
+/// assert!(NamePattern::new("str*").matches("strlen"));
+/// assert!(!NamePattern::new("str*").matches("mystrlen"));
+/// assert!(NamePattern::new("len").matches("mystrlen"));
+/// assert!(NamePattern::new("f?b").matches("fib"));
+/// assert!(NamePattern::new("").matches("main"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct NamePattern {
+    pattern: Vec<char>,
+}
+
+impl NamePattern {
+    /// Creates a [`NamePattern`] from its text
+    #[must_use]
+    pub fn new(pattern: &str) -> Self {
+        let mut pattern: Vec<char> = pattern.chars().collect();
+        if !pattern.contains(&'*') && !pattern.contains(&'?') {
+            pattern.insert(0, '*');
+            pattern.push('*');
+        }
+        Self { pattern }
+    }
+
+    /// Checks if `name` matches the pattern
+    #[must_use]
+    pub fn matches(&self, name: &str) -> bool {
+        let name: Vec<char> = name.chars().collect();
+        let (mut p, mut n) = (0, 0);
+        // position of the last `*` in the pattern and the name position it matched up to
+        let mut backtrack: Option<(usize, usize)> = None;
+        while n < name.len() {
+            match self.pattern.get(p) {
+                Some('*') => {
+                    backtrack = Some((p, n));
+                    p += 1;
+                }
+                Some(&c) if c == '?' || c == name[n] => {
+                    p += 1;
+                    n += 1;
+                }
+                _ => match backtrack {
+                    Some((star, matched)) => {
+                        p = star + 1;
+                        n = matched + 1;
+                        backtrack = Some((star, matched + 1));
+                    }
+                    None => return false,
+                },
+            }
+        }
+        self.pattern[p..].iter().all(|&c| c == '*')
+    }
+}
+
+/// Where a [`FunctionInfo`] was found
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+pub enum FunctionOrigin {
+    /// The DWARF debug information
+    Debug,
+    /// The symbol table of the executable
+    SymbolTable,
+    /// The [heuristics](crate::heuristics) for stripped executables
+    Heuristic,
+}
+
+/// A function of the debuggee
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+pub struct FunctionInfo {
+    /// Name of the function
+    pub name: String,
+    /// Address of the first instruction
+    pub low: Addr,
+    /// Address after the last instruction, if the size is known
+    pub high: Option<Addr>,
+    /// The source file, from the compilation unit of the function
+    pub file: Option<String>,
+    /// Where the function was found
+    pub origin: FunctionOrigin,
+}
+
+impl Display for FunctionInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.high {
+            Some(high) => write!(f, "{} - {}", self.low, high)?,
+            None => write!(f, "{}{:21}", self.low, "")?,
+        }
+        write!(f, "  {}", self.name)?;
+        if let Some(file) = &self.file {
+            write!(f, " ({file})")?;
+        }
+        Ok(())
+    }
+}
+
+/// Lists the functions whose names match `pattern`, ordered by address
+///
+/// # Parameters
+///
+/// * `debug_symbols` - The root symbols of the debug information, one per compilation unit
+/// * `symbol_tables` - The ELF files with symbol tables, like the executable, with the modules
+///   for their load biases
+/// * `heuristics` - The functions found by the heuristics
+/// * `pattern` - The filter for the names
+#[must_use]
+pub fn functions(
+    debug_symbols: &[OwnedSymbol],
+    symbol_tables: &[(&object::File<'_>, &LoadedModule)],
+    heuristics: &HeuristicSymbols,
+    pattern: &NamePattern,
+) -> Vec<FunctionInfo> {
+    let mut functions = Vec::new();
+    for unit in debug_symbols {
+        let file = unit.name().map(str::to_string);
+        for function in
+            search_through_symbols(unit.children(), |s| s.kind() == SymbolKind::Function)
+        {
+            let (Some(name), Some(low)) = (function.name(), function.low_addr()) else {
+                continue;
+            };
+            functions.push(FunctionInfo {
+                name: name.to_string(),
+                low,
+                high: function.high_addr(),
+                file: file.clone(),
+                origin: FunctionOrigin::Debug,
+            });
+        }
+    }
+
+    for (obj, module) in symbol_tables {
+        for symbol in obj.symbols() {
+            let Ok(name) = symbol.name() else {
+                continue;
+            };
+            if symbol.kind() != ObjSymbolKind::Text || symbol.address() == 0 || name.is_empty() {
+                continue;
+            }
+            let low = module.to_process(symbol.address());
+            functions.push(FunctionInfo {
+                name: name.to_string(),
+                low,
+                high: (symbol.size() > 0).then(|| low + symbol.size() as usize),
+                file: None,
+                origin: FunctionOrigin::SymbolTable,
+            });
+        }
+    }
+
+    for symbol in heuristics.symbols() {
+        functions.push(FunctionInfo {
+            name: symbol.name.clone(),
+            low: symbol.addr,
+            high: Some(symbol.addr + symbol.size as usize),
+            file: None,
+            origin: FunctionOrigin::Heuristic,
+        });
+    }
+
+    // a function is only listed from the first source that knows it, by name or by address
+    let mut names = HashSet::new();
+    let mut addrs = HashSet::new();
+    functions.retain(|f| {
+        let keep = f.origin == FunctionOrigin::Debug
+            || !(names.contains(&f.name) || addrs.contains(&f.low));
+        if keep {
+            names.insert(f.name.clone());
+            addrs.insert(f.low);
+        }
+        keep
+    });
+    functions.retain(|f| pattern.matches(&f.name));
+    functions.sort_by(|a, b| a.low.cmp(&b.low).then_with(|| a.name.cmp(&b.name)));
+    functions
+}
+
+/// A type definition of the debuggee
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+pub struct TypeInfo {
+    /// Name of the type
+    pub name: String,
+    /// What kind of type this is, like [`SymbolKind::StructType`] or [`SymbolKind::Typedef`]
+    pub kind: SymbolKind,
+    /// Size of a value of the type in bytes, if it is known
+    ///
+    /// For a typedef, this is the size of the type it names.
+    pub size: Option<usize>,
+    /// The source file in which the type was declared
+    pub file: Option<String>,
+}
+
+impl Display for TypeInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kind = match self.kind {
+            SymbolKind::StructType => "struct",
+            SymbolKind::UnionType => "union",
+            SymbolKind::EnumType => "enum",
+            SymbolKind::Typedef => "typedef",
+            _ => "base",
+        };
+        match self.size {
+            Some(size) => write!(f, "{kind:<8}{size:>6}  {}", self.name)?,
+            None => write!(f, "{kind:<8}{:>6}  {}", "?", self.name)?,
+        }
+        if let Some(file) = &self.file {
+            write!(f, " ({file})")?;
+        }
+        Ok(())
+    }
+}
+
+/// Lists the named types whose names match `pattern`, ordered by name
+///
+/// Structs, unions, enums, typedefs and base types are listed. Types without a name, like a
+/// struct that is only named by a typedef, are left out.
+///
+/// # Parameters
+///
+/// * `debug_symbols` - The root symbols of the debug information, one per compilation unit
+/// * `pattern` - The filter for the names
+#[must_use]
+pub fn types(debug_symbols: &[OwnedSymbol], pattern: &NamePattern) -> Vec<TypeInfo> {
+    let mut types = Vec::new();
+    for unit in debug_symbols {
+        let all = search_through_symbols(unit.children(), |s| {
+            matches!(
+                s.kind(),
+                SymbolKind::StructType
+                    | SymbolKind::UnionType
+                    | SymbolKind::EnumType
+                    | SymbolKind::Typedef
+                    | SymbolKind::BaseType
+            )
+        });
+        // the datatypes refer to offsets in the same compilation unit
+        let by_offset: HashMap<usize, &OwnedSymbol> = all.iter().map(|s| (s.offset(), s)).collect();
+
+        for symbol in &all {
+            let Some(name) = symbol.name() else {
+                continue;
+            };
+            if !pattern.matches(name) {
+                continue;
+            }
+            types.push(TypeInfo {
+                name: name.to_string(),
+                kind: symbol.kind(),
+                size: type_size(symbol, &by_offset),
+                file: symbol.decl_file().map(str::to_string),
+            });
+        }
+    }
+    types.sort_by(|a, b| {
+        a.name
+            .cmp(&b.name)
+            .then_with(|| a.file.cmp(&b.file))
+            .then_with(|| a.size.cmp(&b.size))
+    });
+    types.dedup();
+    types
+}
+
+/// Gets the size of a type, following typedefs to the type they name
+fn type_size(symbol: &OwnedSymbol, by_offset: &HashMap<usize, &OwnedSymbol>) -> Option<usize> {
+    let mut current = symbol;
+    // a typedef chain can not be longer than the number of types, unless the DWARF is broken
+    for _ in 0..=by_offset.len() {
+        if current.kind() != SymbolKind::Typedef {
+            return current.byte_size();
+        }
+        current = by_offset.get(&current.datatype()?)?;
+    }
+    None
+}
+
+/// A global or static variable of the debuggee
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+pub struct VariableInfo {
+    /// Name of the variable
+    pub name: String,
+    /// Address of the variable in the process
+    pub addr: Addr,
+    /// Name of the type, like `struct point *`, if the variable is in the debug information
+    pub type_name: Option<String>,
+    /// Size of the variable in bytes, if it is known
+    pub size: Option<usize>,
+    /// Path of the module that has the variable
+    pub module: String,
+    /// The source file in which the variable was declared
+    pub file: Option<String>,
+}
+
+impl Display for VariableInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}  {} {}",
+            self.addr,
+            self.type_name.as_deref().unwrap_or("?"),
+            self.name
+        )?;
+        match &self.file {
+            Some(file) => write!(f, " ({file})"),
+            None => write!(f, " ({})", module_name(&self.module)),
+        }
+    }
+}
+
+/// Checks if the file name of the module at `path` matches `pattern`
+///
+/// # Examples
+///
+/// ```
+/// use coreminer::symindex::{module_matches, NamePattern};
+///
+/// assert!(module_matches("/usr/lib/libc.so.6", &NamePattern::new("libc")));
+/// assert!(!module_matches("/usr/lib/libm.so.6", &NamePattern::new("libc")));
+/// ```
+#[must_use]
+pub fn module_matches(path: &str, pattern: &NamePattern) -> bool {
+    pattern.matches(module_name(path))
+}
+
+/// Returns the file name of the module at `path`
+fn module_name(path: &str) -> &str {
+    Path::new(path)
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or(path)
+}
+
+/// Lists the global and static variables of the executable whose names match `pattern`
+///
+/// Variables in the debug information come with their types. Data objects of the symbol table
+/// are added if the debug information does not know them, by name or by address.
+///
+/// # Parameters
+///
+/// * `debug_symbols` - The root symbols of the debug information, one per compilation unit
+/// * `obj` - The ELF file of the executable, for its symbol table
+/// * `executable` - The executable as loaded into the process, for its load bias
+/// * `pattern` - The filter for the names
+#[must_use]
+pub fn variables(
+    debug_symbols: &[OwnedSymbol],
+    obj: Option<&object::File<'_>>,
+    executable: &LoadedModule,
+    pattern: &NamePattern,
+) -> Vec<VariableInfo> {
+    let mut variables = Vec::new();
+    for unit in debug_symbols {
+        let all = search_through_symbols(unit.children(), |_| true);
+        // the datatypes refer to offsets in the same compilation unit
+        let by_offset: HashMap<usize, &OwnedSymbol> = all.iter().map(|s| (s.offset(), s)).collect();
+
+        for variable in all.iter().filter(|s| s.kind() == SymbolKind::Variable) {
+            let (Some(name), Some(addr)) = (variable.name(), static_addr(variable, executable))
+            else {
+                continue;
+            };
+            let datatype = variable.datatype().and_then(|dt| by_offset.get(&dt));
+            variables.push(VariableInfo {
+                name: name.to_string(),
+                addr,
+                type_name: Some(match datatype {
+                    Some(datatype) => type_name(datatype, &by_offset),
+                    None => "void".to_string(),
+                }),
+                size: datatype.and_then(|dt| type_size(dt, &by_offset)),
+                module: executable.path.clone(),
+                file: variable.decl_file().map(str::to_string),
+            });
+        }
+    }
+
+    if let Some(obj) = obj {
+        let mut names: HashSet<String> = variables.iter().map(|v| v.name.clone()).collect();
+        let mut addrs: HashSet<Addr> = variables.iter().map(|v| v.addr).collect();
+        for symbol in obj.symbols() {
+            let Ok(name) = symbol.name() else {
+                continue;
+            };
+            if symbol.kind() != ObjSymbolKind::Data || symbol.address() == 0 || name.is_empty() {
+                continue;
+            }
+            let addr = executable.to_process(symbol.address());
+            if !names.insert(name.to_string()) || !addrs.insert(addr) {
+                continue;
+            }
+            variables.push(VariableInfo {
+                name: name.to_string(),
+                addr,
+                type_name: None,
+                size: (symbol.size() > 0).then_some(symbol.size() as usize),
+                module: executable.path.clone(),
+                file: None,
+            });
+        }
+    }
+
+    variables.retain(|v| pattern.matches(&v.name));
+    variables.sort_by(|a, b| a.addr.cmp(&b.addr).then_with(|| a.name.cmp(&b.name)));
+    variables
+}
+
+/// Lists the data objects exported by a shared library whose names match `pattern`
+///
+/// # Errors
+///
+/// This function fails if the file of the module cannot be read or is not an ELF file.
+pub fn exported_variables(
+    module: &LoadedModule,
+    pattern: &NamePattern,
+) -> Result<Vec<VariableInfo>> {
+    let data = std::fs::read(&module.path)?;
+    let obj = object::File::parse(data.as_slice())?;
+    Ok(obj
+        .dynamic_symbols()
+        .filter(|s| s.is_definition() && s.kind() == ObjSymbolKind::Data && s.address() != 0)
+        .filter_map(|s| {
+            let name = s.name().ok().filter(|n| pattern.matches(n))?;
+            Some(VariableInfo {
+                name: name.to_string(),
+                addr: module.to_process(s.address()),
+                type_name: None,
+                size: (s.size() > 0).then_some(s.size() as usize),
+                module: module.path.clone(),
+                file: None,
+            })
+        })
+        .collect())
+}
+
+/// Gets the address of a variable with static storage
+///
+/// This is the case if its location is only a `DW_OP_addr`. Other locations are on the stack,
+/// in registers or thread local.
+fn static_addr(variable: &OwnedSymbol, executable: &LoadedModule) -> Option<Addr> {
+    let gimli::AttributeValue::Exprloc(expression) = variable.location()?.value() else {
+        return None;
+    };
+    let mut ops = expression.operations(variable.encoding());
+    match (ops.next().ok()?, ops.next().ok()?) {
+        (Some(gimli::Operation::Address { address }), None) => Some(executable.to_process(address)),
+        _ => None,
+    }
+}
+
+/// Gets the name of a type like it is written in C
+///
+/// Qualifiers like `const` are not described by [`SymbolKind`], so the type they apply to is named
+/// instead. The dimensions of arrays are written after the name, like `int [3][4]`.
+fn type_name(datatype: &OwnedSymbol, by_offset: &HashMap<usize, &OwnedSymbol>) -> String {
+    let mut pointers = String::new();
+    let mut dimensions = String::new();
+    let mut current = datatype;
+    // a type chain can not be longer than the number of types, unless the DWARF is broken
+    for _ in 0..=by_offset.len() {
+        let keyword = match current.kind() {
+            SymbolKind::StructType => "struct ",
+            SymbolKind::UnionType => "union ",
+            SymbolKind::EnumType => "enum ",
+            SymbolKind::Typedef | SymbolKind::BaseType => "",
+            kind => {
+                // the outermost type is visited first, but written last
+                match kind {
+                    SymbolKind::PointerType => pointers.insert(0, '*'),
+                    SymbolKind::ReferenceType => pointers.insert(0, '&'),
+                    SymbolKind::ArrayType => {
+                        for subrange in current
+                            .children()
+                            .iter()
+                            .filter(|c| c.kind() == SymbolKind::SubrangeType)
+                        {
+                            match subrange.count() {
+                                Some(count) => {
+                                    let _ = write!(dimensions, "[{count}]");
+                                }
+                                None => dimensions.push_str("[]"),
+                            }
+                        }
+                    }
+                    _ => (),
+                }
+                match current.datatype().and_then(|dt| by_offset.get(&dt)) {
+                    Some(next) => current = next,
+                    None => {
+                        return format!("void {pointers}{dimensions}")
+                            .trim_end()
+                            .to_string()
+                    }
+                }
+                continue;
+            }
+        };
+        let name = current.name().unwrap_or("<anonymous>");
+        return format!("{keyword}{name} {pointers}{dimensions}")
+            .trim_end()
+            .to_string();
+    }
+    "?".to_string()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_name_pattern() {
+        let cases = [
+            ("main", "main", true),
+            ("ai", "main", true),
+            ("m*n", "main", true),
+            ("m*n", "mainx", false),
+            ("*_init", "__libc_init", true),
+            ("*a*a*", "banana", true),
+            ("?", "ab", false),
+            ("a**", "a", true),
+            ("x", "", false),
+        ];
+        for (pattern, name, expected) in cases {
+            assert_eq!(
+                NamePattern::new(pattern).matches(name),
+                expected,
+                "{pattern} {name}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_types_follow_typedefs() {
+        let encoding = gimli::Encoding {
+            format: gimli::Format::Dwarf32,
+            version: 4,
+            address_size: 8,
+        };
+        let mut point = OwnedSymbol::new(10, SymbolKind::StructType, &[], encoding);
+        point.set_name(Some("point".to_string()));
+        point.set_byte_size(Some(8));
+        point.set_decl_file(Some("/src/point.h".to_string()));
+        let mut point_t = OwnedSymbol::new(20, SymbolKind::Typedef, &[], encoding);
+        point_t.set_name(Some("point_t".to_string()));
+        point_t.set_datatype(Some(10));
+        let mut unit = OwnedSymbol::new(0, SymbolKind::CompileUnit, &[point, point_t], encoding);
+        unit.set_name(Some("main.c".to_string()));
+        // the header is included by two compilation units
+        let units = [unit.clone(), unit];
+
+        let types = types(&units, &NamePattern::new("point"));
+        assert_eq!(types.len(), 2);
+        assert_eq!(types[0].name, "point");
+        assert_eq!(types[0].file.as_deref(), Some("/src/point.h"));
+        assert_eq!(types[1].kind, SymbolKind::Typedef);
+        assert_eq!(types[1].size, Some(8));
+    }
+}
@@ -0,0 +1,255 @@
+//! # Thread Module
+//!
+//! Provides the threads of a debugged process.
+//!
+//! ptrace traces threads, not processes. Attaching to the process ID of a multi-threaded process
+//! only attaches to its main thread, while all other threads keep running without the debugger
+//! noticing, and hit breakpoints without anyone handling the `SIGTRAP`. [`attach_all`] therefore
+//! attaches to every thread listed in `/proc/<pid>/task`, and the debuggee keeps all of them in
+//! its thread table.
+//!
+//! The debugger controls the main thread, as before. The other threads are stopped at the attach
+//! and resumed together with the main thread. When one of them stops on its own, it stays stopped
+//! until the debuggee is resumed the next time, where a hit [Breakpoint](crate::breakpoint::Breakpoint)
+//! is stepped over and a signal is delivered. Their stops are not reported to the UI.
+
+use std::collections::BTreeMap;
+use std::fmt::{self, Display};
+
+use nix::errno::Errno;
+use nix::sys::ptrace;
+use nix::sys::signal::Signal;
+use nix::sys::wait::{waitpid, WaitPidFlag, WaitStatus};
+use nix::unistd::Pid;
+use serde::Serialize;
+use tracing::{debug, trace};
+
+use crate::errors::Result;
+
+/// Whether a [`Thread`] runs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+pub enum ThreadState {
+    /// The thread is stopped by ptrace and its stop was handled
+    Stopped,
+    /// The thread was resumed and may have stopped since
+    Running,
+}
+
+/// A thread of the debuggee in the thread table
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+pub struct Thread {
+    /// The thread ID
+    pub tid: i32,
+    /// Whether this is the main thread, which has the process ID as thread ID
+    pub main: bool,
+    /// Whether the thread runs
+    pub state: ThreadState,
+    /// The name of the thread, as set with `pthread_setname_np` or `prctl(PR_SET_NAME)`
+    ///
+    /// The name is read when the threads are listed, see [`thread_name`].
+    pub name: Option<String>,
+    /// A signal that stopped the thread and is delivered when it is resumed
+    #[serde(skip)]
+    pub(crate) pending_signal: Option<Signal>,
+}
+
+impl Thread {
+    /// Creates the entry of a thread that is stopped
+    #[must_use]
+    pub fn stopped(tid: Pid, main: bool) -> Self {
+        Self {
+            tid: tid.as_raw(),
+            main,
+            state: ThreadState::Stopped,
+            name: None,
+            pending_signal: None,
+        }
+    }
+}
+
+impl Display for Thread {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:<8} {:<16}",
+            self.tid,
+            self.name.as_deref().unwrap_or("?")
+        )?;
+        match self.state {
+            ThreadState::Stopped => write!(f, " stopped")?,
+            ThreadState::Running => write!(f, " running")?,
+        }
+        if self.main {
+            write!(f, " (main)")?;
+        }
+        Ok(())
+    }
+}
+
+/// Lists the thread IDs of the process `pid` from `/proc/<pid>/task`, in ascending order
+///
+/// # Errors
+///
+/// This function will fail if `/proc/<pid>/task` cannot be read.
+///
+/// # Examples
+///
+/// ```
+/// use coreminer::thread::task_ids;
+/// use nix::unistd::getpid;
+///
+/// let tids = task_ids(getpid()).unwrap();
+/// assert!(tids.contains(&getpid()));
+/// ```
+pub fn task_ids(pid: Pid) -> Result<Vec<Pid>> {
+    let mut tids = Vec::new();
+    for entry in std::fs::read_dir(format!("/proc/{pid}/task"))? {
+        let entry = entry?;
+        if let Some(tid) = entry.file_name().to_str().and_then(|s| s.parse().ok()) {
+            tids.push(Pid::from_raw(tid));
+        }
+    }
+    tids.sort_unstable();
+    Ok(tids)
+}
+
+/// Reads the name of the thread `tid` of the process `pid` from `/proc/<pid>/task/<tid>/comm`
+///
+/// Threads that were not named have the name of the executable, which they inherit from the main
+/// thread. The kernel cuts names off after 15 bytes.
+///
+/// # Errors
+///
+/// This function will fail if the thread does not exist (anymore).
+///
+/// # Examples
+///
+/// ```
+/// use coreminer::thread::thread_name;
+/// use nix::unistd::{getpid, gettid};
+///
+/// let name = thread_name(getpid(), gettid()).unwrap();
+/// assert!(!name.is_empty());
+/// ```
+pub fn thread_name(pid: Pid, tid: Pid) -> Result<String> {
+    let comm = std::fs::read_to_string(format!("/proc/{pid}/task/{tid}/comm"))?;
+    Ok(comm.trim_end_matches('\n').to_string())
+}
+
+/// Attaches to all threads of the process `pid` and waits until they are stopped
+///
+/// The threads are listed again until no new ones show up, as threads can create more threads
+/// while the others are attached. Threads that exit in the meantime are left out.
+///
+/// # Returns
+///
+/// The thread table of the process, including the main thread.
+///
+/// # Errors
+///
+/// This function will fail if the threads cannot be listed, or if the debugger may not attach to
+/// the main thread, for example because of missing permissions.
+pub fn attach_all(pid: Pid) -> Result<BTreeMap<Pid, Thread>> {
+    let mut threads = BTreeMap::new();
+    loop {
+        let new: Vec<Pid> = task_ids(pid)?
+            .into_iter()
+            .filter(|tid| !threads.contains_key(tid))
+            .collect();
+        if new.is_empty() {
+            break;
+        }
+        for tid in new {
+            match ptrace::attach(tid) {
+                Ok(()) => (),
+                Err(Errno::ESRCH) if tid != pid => {
+                    trace!("thread {tid} exited before it was attached");
+                    continue;
+                }
+                Err(e) => return Err(e.into()),
+            }
+            if wait_attached(tid)? {
+                debug!("attached to thread {tid}");
+                threads.insert(tid, Thread::stopped(tid, tid == pid));
+            }
+        }
+    }
+    Ok(threads)
+}
+
+/// Waits until the thread `tid` stopped for the `SIGSTOP` of `PTRACE_ATTACH`
+///
+/// Other stops that come first are resumed without their signal.
+///
+/// # Returns
+///
+/// `false` if the thread exited instead.
+fn wait_attached(tid: Pid) -> Result<bool> {
+    loop {
+        match waitpid(tid, Some(WaitPidFlag::__WALL))? {
+            WaitStatus::Stopped(_, Signal::SIGSTOP) => return Ok(true),
+            WaitStatus::Exited(..) | WaitStatus::Signaled(..) => return Ok(false),
+            other => {
+                trace!("thread {tid} stopped before the attach: {other:?}");
+                ptrace::cont(tid, None)?;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_task_ids() {
+        let (tx, rx) = std::sync::mpsc::channel();
+        let (done_tx, done_rx) = std::sync::mpsc::channel::<()>();
+        let handle = std::thread::spawn(move || {
+            tx.send(nix::unistd::gettid()).unwrap();
+            done_rx.recv().unwrap();
+        });
+        let tid = rx.recv().unwrap();
+
+        let tids = task_ids(nix::unistd::getpid()).unwrap();
+        assert!(tids.contains(&tid));
+        assert!(tids.windows(2).all(|w| w[0] < w[1]));
+
+        done_tx.send(()).unwrap();
+        handle.join().unwrap();
+    }
+
+    #[test]
+    fn test_thread_name() {
+        let (tx, rx) = std::sync::mpsc::channel();
+        let (done_tx, done_rx) = std::sync::mpsc::channel::<()>();
+        let handle = std::thread::Builder::new()
+            .name("worker-3".to_string())
+            .spawn(move || {
+                tx.send(nix::unistd::gettid()).unwrap();
+                done_rx.recv().unwrap();
+            })
+            .unwrap();
+        let tid = rx.recv().unwrap();
+
+        let name = thread_name(nix::unistd::getpid(), tid).unwrap();
+        assert_eq!(name, "worker-3");
+
+        done_tx.send(()).unwrap();
+        handle.join().unwrap();
+    }
+
+    #[test]
+    fn test_thread_display() {
+        let mut thread = Thread::stopped(Pid::from_raw(42), true);
+        assert_eq!(
+            thread.to_string(),
+            "42       ?                stopped (main)"
+        );
+        thread.name = Some("worker-3".to_string());
+        assert_eq!(
+            thread.to_string(),
+            "42       worker-3         stopped (main)"
+        );
+    }
+}
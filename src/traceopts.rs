@@ -0,0 +1,107 @@
+//! # Trace Options Module
+//!
+//! Provides configuration of the ptrace options of a debugged process.
+//!
+//! `PTRACE_SETOPTIONS` changes how the kernel traces a process: whether it is killed when the
+//! debugger goes away, whether it stops before exiting, and whether new processes and threads it
+//! creates are traced too. The options are configured with a [`PtraceOptions`] and applied with
+//! [`Debugger::set_ptrace_options`](crate::debugger::Debugger::set_ptrace_options), both to a
+//! running debuggee and to every debuggee launched afterwards.
+//!
+//! Note that [`PtraceOptions::trace_fork`] has no effect on its own anymore: new processes of the
+//! debuggee are always traced, and handled as selected with
+//! [`Debugger::set_follow_fork`](crate::debugger::Debugger::set_follow_fork).
+
+use nix::sys::ptrace::Options;
+use serde::{Deserialize, Serialize};
+
+/// Selects the ptrace options of the debuggee
+///
+/// All options are disabled by default. `PTRACE_O_TRACESYSGOOD` is always added when syscalls are
+/// intercepted, regardless of [`PtraceOptions::trace_sysgood`].
+///
+/// # Examples
+///
+/// ```
+/// use coreminer::traceopts::PtraceOptions;
+/// use nix::sys::ptrace::Options;
+///
+/// // kill the debuggee if coreminer crashes
+/// let options = PtraceOptions {
+///     exit_kill: true,
+///     ..Default::default()
+/// };
+/// assert_eq!(options.flags(), Options::PTRACE_O_EXITKILL);
+/// assert!(PtraceOptions::default().flags().is_empty());
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct PtraceOptions {
+    /// Kill the debuggee with `SIGKILL` when the debugger exits or crashes (`PTRACE_O_EXITKILL`)
+    pub exit_kill: bool,
+    /// Mark syscall stops with bit 7 of the signal number (`PTRACE_O_TRACESYSGOOD`)
+    pub trace_sysgood: bool,
+    /// Stop the debuggee right before it exits (`PTRACE_O_TRACEEXIT`)
+    ///
+    /// At that stop, the exit status is known but the memory and registers can still be
+    /// inspected.
+    pub trace_exit: bool,
+    /// Trace new threads of the debuggee (`PTRACE_O_TRACECLONE`)
+    pub trace_clone: bool,
+    /// Trace child processes of the debuggee (`PTRACE_O_TRACEFORK` and `PTRACE_O_TRACEVFORK`)
+    pub trace_fork: bool,
+    /// Stop the debuggee after it called `execve` (`PTRACE_O_TRACEEXEC`)
+    pub trace_exec: bool,
+}
+
+impl PtraceOptions {
+    /// Converts the options to the flags for `PTRACE_SETOPTIONS`
+    #[must_use]
+    pub fn flags(&self) -> Options {
+        let mut flags = Options::empty();
+        flags.set(Options::PTRACE_O_EXITKILL, self.exit_kill);
+        flags.set(Options::PTRACE_O_TRACESYSGOOD, self.trace_sysgood);
+        flags.set(Options::PTRACE_O_TRACEEXIT, self.trace_exit);
+        flags.set(Options::PTRACE_O_TRACECLONE, self.trace_clone);
+        flags.set(
+            Options::PTRACE_O_TRACEFORK | Options::PTRACE_O_TRACEVFORK,
+            self.trace_fork,
+        );
+        flags.set(Options::PTRACE_O_TRACEEXEC, self.trace_exec);
+        flags
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_ptrace_options_flags() {
+        let options = PtraceOptions {
+            exit_kill: true,
+            trace_fork: true,
+            trace_exec: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            options.flags(),
+            Options::PTRACE_O_EXITKILL
+                | Options::PTRACE_O_TRACEFORK
+                | Options::PTRACE_O_TRACEVFORK
+                | Options::PTRACE_O_TRACEEXEC
+        );
+
+        let options = PtraceOptions {
+            trace_sysgood: true,
+            trace_exit: true,
+            trace_clone: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            options.flags(),
+            Options::PTRACE_O_TRACESYSGOOD
+                | Options::PTRACE_O_TRACEEXIT
+                | Options::PTRACE_O_TRACECLONE
+        );
+    }
+}
@@ -6,42 +6,115 @@
 //! allowing for different interface implementations (such as CLI, JSON-RPC, etc.)
 //! while maintaining a consistent API for the debugger core to interact with.
 //!
-//! The [`DebuggerUI`] trait defines the interface for UI implementations.
+//! A user interface has two sides:
+//!
+//! - A [`CommandSource`], from which the debugger pulls the next [`Status`] to process
+//! - An [`EventSink`], to which the debugger pushes [Events](Event): the [`Feedback`] for the
+//!   processed [`Status`], and things that happen on their own, like the debuggee stopping
+//!
+//! Events can be pushed at any time, not only once per command, so a UI can show a stop as soon
+//! as it happens, before the command that resumed the debuggee has returned its [`Feedback`].
+//! Anything that implements both traits is a [`DebuggerUI`].
 //!
 //! This module also includes submodules for specific UI implementations:
 //! - [`cli`]: A command-line interface implementation
+//! - [`mi`]: A subset of the GDB Machine Interface, for frontends that speak it
+//! - [`scripted`]: A UI that runs a predefined script, for automation and tests
+//! - [`transcript`]: Recording sessions of any UI into transcripts and replaying them
+//! - `tui`: A full-screen terminal interface, with the `tui` feature
+
+use serde::Serialize;
 
 use crate::errors::Result;
-use crate::feedback::{Feedback, Status};
+use crate::feedback::{Feedback, Status, StopReason};
 
 #[cfg(feature = "cli")]
 pub mod cli;
 #[cfg(feature = "cmserve")]
 pub mod json;
+#[cfg(feature = "cmserve")]
+pub mod mi;
+pub mod scripted;
+pub mod transcript;
+#[cfg(feature = "tui")]
+pub mod tui;
+
+pub use scripted::ScriptedUI;
+
+/// Something the debugger tells the user interface
+#[non_exhaustive]
+#[derive(Debug, Serialize)]
+pub enum Event {
+    /// The result of the last [`Status`] from the [`CommandSource`]
+    Feedback(Feedback),
+    /// The debuggee stopped or terminated
+    ///
+    /// This is pushed as soon as the debuggee stops, before the [`Feedback`] of the [`Status`]
+    /// that resumed it, which usually is a [`Feedback::Stopped`] with the same reason.
+    Stopped(StopReason),
+    /// Something happened that the user might want to know about, like a new thread
+    Notification(String),
+}
+
+/// Where the debugger gets its commands from
+pub trait CommandSource {
+    /// Gets the next [`Status`] that the debugger should process
+    ///
+    /// This is called after the [`Feedback`] of the previous [`Status`] was pushed to the
+    /// [`EventSink`], and may block until the user entered something.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Status)` - The command to send to the debugger
+    /// * `Err(DebuggerError)` - If no command could be read
+    ///
+    /// # Errors
+    ///
+    /// This method can fail if there are issues with user input or other
+    /// UI-specific errors.
+    fn next_status(&mut self) -> Result<Status>;
+}
+
+/// Where the debugger pushes its [Events](Event) to
+pub trait EventSink {
+    /// Presents an [`Event`] to the user
+    ///
+    /// # Errors
+    ///
+    /// This method can fail if the event cannot be shown or sent, for example because the
+    /// output was closed.
+    fn push_event(&mut self, event: Event) -> Result<()>;
+}
 
 /// Interface for debugger user interfaces
 ///
-/// [`DebuggerUI`] defines the interface that must be implemented by any user
-/// interface that wants to interact with the debugger. It provides a way for
-/// the debugger to send feedback to the UI and receive commands in return.
+/// [`DebuggerUI`] is implemented for everything that is both a [`CommandSource`] and an
+/// [`EventSink`]. The debugger pulls commands from the source and pushes the [`Feedback`] for
+/// them, as well as everything else that happens, to the sink.
 ///
 /// # Examples
 ///
 /// ```no_run
-/// use coreminer::ui::DebuggerUI;
-/// use coreminer::feedback::{Feedback,Status};
+/// use coreminer::ui::{CommandSource, Event, EventSink};
+/// use coreminer::feedback::Status;
 /// use coreminer::errors::Result;
 ///
 /// // A simple UI implementation that always returns Continue
 /// struct SimpleUI;
 ///
-/// impl DebuggerUI for SimpleUI {
-///     fn process(&mut self, feedback: Feedback) -> Result<Status> {
-///         println!("Received feedback: {}", feedback);
+/// impl CommandSource for SimpleUI {
+///     fn next_status(&mut self) -> Result<Status> {
 ///         Ok(Status::Continue)
 ///     }
 /// }
 ///
+/// impl EventSink for SimpleUI {
+///     fn push_event(&mut self, event: Event) -> Result<()> {
+///         println!("Received event: {event:?}");
+///         Ok(())
+///     }
+/// }
+///
 /// // Using the UI with a debugger
 /// # fn run_example() -> Result<()> {
 /// # use coreminer::debugger::Debugger;
@@ -52,26 +125,6 @@ pub mod json;
 /// # Ok(())
 /// # }
 /// ```
-pub trait DebuggerUI {
-    /// Processes feedback from the debugger and returns a status command
-    ///
-    /// This method is called by the debugger to send feedback to the UI
-    /// and receive a command in response. The UI implementation should
-    /// present the feedback to the user in an appropriate way and then
-    /// return a command based on user input or internal logic.
-    ///
-    /// # Parameters
-    ///
-    /// * `feedback` - The feedback from the debugger
-    ///
-    /// # Returns
-    ///
-    /// * `Ok(Status)` - The command to send to the debugger
-    /// * `Err(DebuggerError)` - If an error occurred during processing
-    ///
-    /// # Errors
-    ///
-    /// This method can fail if there are issues with user input or other
-    /// UI-specific errors.
-    fn process(&mut self, feedback: Feedback) -> Result<Status>;
-}
+pub trait DebuggerUI: CommandSource + EventSink {}
+
+impl<T: CommandSource + EventSink> DebuggerUI for T {}
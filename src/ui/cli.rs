@@ -2,17 +2,20 @@
 //!
 //! Provides a basic command-line interface for interacting with the Coreminer debugger.
 //!
-//! This module implements the [`DebuggerUI`] trait to provide an interactive
+//! This module implements the [`DebuggerUI`](super::DebuggerUI) trait to provide an interactive
 //! command-line interface with features such as:
 //!
 //! - Command history and recall
 //! - Parsing hex values for addresses and register values
 //! - Command validation and error messages
 //! - Automatic execution via stepper functionality
+//! - Executing files of commands, see [`CliUi::source`]
+//! - A non-interactive batch mode with assertions, see [`CliUi::set_batch`]
 //!
 //! The CLI interface accepts commands for controlling program execution,
 //! setting breakpoints, examining memory and registers, and other debugging tasks.
 
+use std::collections::VecDeque;
 use std::ffi::CString;
 use std::os::unix::ffi::OsStrExt;
 use std::os::unix::fs::PermissionsExt;
@@ -20,31 +23,44 @@ use std::path::{Path, PathBuf};
 use std::str::FromStr;
 
 use dialoguer::BasicHistory;
+use nix::sys::signal::Signal;
 use tracing::{error, info, trace, warn};
 
-use super::{DebuggerUI, Status};
+use super::{CommandSource, Event, EventSink, Status};
+use crate::addrexpr::AddrExpr;
+use crate::antidebug::AntiDebugConfig;
+use crate::catchpoint::MemoryCatchConfig;
+use crate::debugger::WriteVerification;
 use crate::errors::Result;
-use crate::feedback::Feedback;
+use crate::feedback::{ExitStatus, Feedback, StopReason};
+use crate::gdbremote::Resume;
+use crate::limits::{LimitKind, Limits};
+use crate::profile::DEFAULT_PROFILE_FREQUENCY;
+use crate::reverse::DEFAULT_CHECKPOINT_INTERVAL;
+use crate::seccomp::SyscallFilter;
+use crate::syscall::parse_syscall;
+use crate::traceopts::PtraceOptions;
 use crate::{Addr, Register, Word};
 
 /// Command-line interface for the debugger
 ///
-/// Implements the [`DebuggerUI`] trait to provide an interactive
+/// Implements the [`DebuggerUI`](super::DebuggerUI) trait to provide an interactive
 /// command-line interface for the debugger.
 ///
 /// # Examples
 ///
 /// ```no_run
 /// use coreminer::ui::cli::CliUi;
-/// use coreminer::ui::DebuggerUI;
+/// use coreminer::ui::{CommandSource, Event, EventSink};
 /// use coreminer::feedback::Feedback;
 /// use std::path::Path;
 ///
 /// // Create a CLI UI with no default executable
 /// let mut ui = CliUi::build(None).unwrap();
 ///
-/// // Process feedback from the debugger with user input
-/// let status = ui.process(Feedback::Ok).unwrap();
+/// // Show feedback from the debugger and get the next command from the user
+/// ui.push_event(Event::Feedback(Feedback::Ok)).unwrap();
+/// let status = ui.next_status().unwrap();
 /// ```
 pub struct CliUi {
     buf: String,
@@ -52,8 +68,18 @@ pub struct CliUi {
     history: BasicHistory,
     stepper: usize,
     default_executable: Option<PathBuf>,
+    script: VecDeque<String>,
+    scripted: bool,
+    batch: bool,
+    failed: bool,
+    last_stop: Option<StopReason>,
+    stop_shown: bool,
+    line_reader: Option<LineReader>,
 }
 
+/// Reads a line with a command, see [`CliUi::set_line_reader`]
+pub type LineReader = Box<dyn FnMut() -> Result<String>>;
+
 impl CliUi {
     /// Creates a new CLI UI instance
     ///
@@ -106,13 +132,180 @@ impl CliUi {
             history: BasicHistory::new(),
             stepper: 0,
             default_executable: default_executable.map(std::borrow::ToOwned::to_owned),
+            script: VecDeque::new(),
+            scripted: false,
+            batch: false,
+            failed: false,
+            last_stop: None,
+            stop_shown: false,
+            line_reader: None,
         };
         Ok(ui)
     }
 
+    /// Executes the commands in a file
+    ///
+    /// The file contains one command per line, written like they would be entered. Empty lines
+    /// and lines starting with `#` are ignored. The commands are executed before any further
+    /// input is read from the user, and a file that is sourced by a command in another file is
+    /// executed in place of that command. If a command fails, the rest of the commands are
+    /// skipped.
+    ///
+    /// This is also available as the `source` command.
+    ///
+    /// # Parameters
+    ///
+    /// * `path` - The file with the commands
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the file cannot be read.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use coreminer::ui::cli::CliUi;
+    /// use std::path::Path;
+    ///
+    /// let mut ui = CliUi::build(Some(Path::new("./target/debug/dummy"))).unwrap();
+    /// // a file with the lines `startbreak main`, `run`, `bt`
+    /// ui.source(Path::new("recipe.cm")).unwrap();
+    /// ```
+    pub fn source(&mut self, path: &Path) -> Result<()> {
+        let content = std::fs::read_to_string(path)?;
+        let commands = content
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'));
+
+        // in front of the remaining commands, so that nested files run in place
+        for (i, command) in commands.enumerate() {
+            self.script.insert(i, command.to_string());
+        }
+        info!("executing commands from {}", path.display());
+        Ok(())
+    }
+
+    /// Enables or disables the batch mode
+    ///
+    /// In batch mode, no input is read from the user. Only the commands from [`Self::source`]
+    /// are executed, and the debugger quits once there are none left or one of them failed.
+    /// Together with the `assert` command, this allows reproducing bugs in scripts and CI jobs,
+    /// which use [`Self::exit_code`] as the result of the run.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use coreminer::debugger::Debugger;
+    /// use coreminer::ui::cli::CliUi;
+    /// use std::path::Path;
+    ///
+    /// let mut ui = CliUi::build(Some(Path::new("./target/debug/dummy"))).unwrap();
+    /// // a file with the lines `run`, `cont`, `assert exit 0`
+    /// ui.source(Path::new("recipe.cm")).unwrap();
+    /// ui.set_batch(true);
+    ///
+    /// let mut debugger = Debugger::build(ui).unwrap();
+    /// debugger.run_debugger().unwrap();
+    /// debugger.cleanup().unwrap();
+    /// std::process::exit(debugger.ui().exit_code());
+    /// ```
+    pub fn set_batch(&mut self, batch: bool) {
+        self.batch = batch;
+    }
+
+    /// Reads the commands with `reader` instead of from the terminal
+    ///
+    /// This lets another frontend, like the TUI, use the commands of the CLI with its own input
+    /// line. The reader is called whenever the CLI needs the next command, and the help is
+    /// logged instead of printed. Commands from [`Self::source`] still come first.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use coreminer::ui::cli::CliUi;
+    /// use coreminer::ui::CommandSource;
+    /// use coreminer::feedback::Status;
+    ///
+    /// let mut ui = CliUi::build(None).unwrap();
+    /// ui.set_line_reader(|| Ok("cont".to_string()));
+    /// assert_eq!(ui.next_status().unwrap(), Status::Continue);
+    /// ```
+    pub fn set_line_reader(&mut self, reader: impl FnMut() -> Result<String> + 'static) {
+        self.line_reader = Some(Box::new(reader));
+    }
+
+    /// Gets the exit code for the result of the commands
+    ///
+    /// # Returns
+    ///
+    /// * `1` - If a command from [`Self::source`] failed, including a failed `assert`
+    /// * The exit code of the debuggee - If it exited
+    /// * `128` plus the signal number - If the debuggee was terminated by a signal, like a shell
+    ///   reports it
+    /// * `0` - Otherwise
+    #[must_use]
+    pub fn exit_code(&self) -> i32 {
+        if self.failed {
+            return 1;
+        }
+        match self.last_stop {
+            Some(StopReason::Exited(ExitStatus::Exited(code))) => code,
+            Some(StopReason::Exited(ExitStatus::Signaled { signal, .. })) => 128 + signal,
+            _ => 0,
+        }
+    }
+
+    /// Skips the remaining commands from [`Self::source`] after a command failed
+    fn abort_script(&mut self) {
+        if self.scripted {
+            self.failed = true;
+        }
+        if !self.script.is_empty() {
+            warn!("skipping the {} remaining commands", self.script.len());
+            self.script.clear();
+        }
+    }
+
+    /// Checks the assertion of an `assert` command against the last stop of the debuggee
+    ///
+    /// # Returns
+    ///
+    /// * `Some(bool)` - Whether the assertion holds
+    /// * `None` - If the assertion could not be parsed
+    fn check_assertion(&self) -> Option<bool> {
+        let what = self.buf_preparsed.get(1)?.to_lowercase();
+        let holds = match (what.as_str(), self.buf_preparsed.len()) {
+            ("exit", 2) => matches!(self.last_stop, Some(StopReason::Exited(_))),
+            ("exit", 3) => {
+                // like the timeout of cont, exit codes are decimal
+                let code = self.buf_preparsed[2].parse().ok()?;
+                self.last_stop == Some(StopReason::Exited(ExitStatus::Exited(code)))
+            }
+            ("signal", 3) => {
+                let sig = self.get_signal(2)?;
+                match self.last_stop {
+                    Some(
+                        StopReason::Signal(signal)
+                        | StopReason::Exited(ExitStatus::Signaled { signal, .. }),
+                    ) => signal == sig,
+                    _ => false,
+                }
+            }
+            ("break", 3) => {
+                let addr = Addr::from(self.get_number(2)? as usize);
+                matches!(self.last_stop, Some(StopReason::Breakpoint { addr: a, .. }) if a == addr)
+            }
+            _ => return None,
+        };
+        Some(holds)
+    }
+
     /// Gets input from the user
     ///
-    /// Uses the [`dialoguer`] crate to get input with history support.
+    /// Uses the [`dialoguer`] crate to get input with history support. If there are commands
+    /// left from [`Self::source`], the next of them is used instead. In batch mode (see
+    /// [`Self::set_batch`]), `quit` is used when there are no commands left.
     ///
     /// # Returns
     ///
@@ -137,9 +330,22 @@ impl CliUi {
     /// ```
     pub fn get_input(&mut self) -> Result<()> {
         loop {
-            self.buf = dialoguer::Input::with_theme(&dialoguer::theme::ColorfulTheme::default())
-                .history_with(&mut self.history)
-                .interact_text()?;
+            self.scripted = !self.script.is_empty();
+            self.buf = match self.script.pop_front() {
+                Some(command) => {
+                    info!("> {command}");
+                    command
+                }
+                None if self.batch => "quit".to_string(),
+                None => match self.line_reader.as_mut() {
+                    Some(reader) => reader()?,
+                    None => {
+                        dialoguer::Input::with_theme(&dialoguer::theme::ColorfulTheme::default())
+                            .history_with(&mut self.history)
+                            .interact_text()?
+                    }
+                },
+            };
             trace!("processing '{}'", self.buf);
             self.buf_preparsed = match shlex::split(&self.buf) {
                 None => {
@@ -189,6 +395,145 @@ impl CliUi {
         }
     }
 
+    /// Parses an address expression from the command line arguments
+    ///
+    /// Accepts a number like [`Self::get_number`], a symbol with an optional offset like
+    /// `main+0x10`, or a source line like `hello.c:12`, see [`AddrExpr`].
+    ///
+    /// # Parameters
+    ///
+    /// * `index` - The index of the argument to parse
+    ///
+    /// # Returns
+    ///
+    /// * `Some(AddrExpr)` - The parsed address expression
+    /// * `None` - If the expression could not be parsed or the index is out of bounds
+    fn get_addr(&self, index: usize) -> Option<AddrExpr> {
+        let raw = self.buf_preparsed.get(index)?;
+        match raw.parse() {
+            Ok(expr) => Some(expr),
+            Err(e) => {
+                warn!("Failed to parse address '{raw}': {e}");
+                None
+            }
+        }
+    }
+
+    /// Parses a number that may be negative from the command line arguments
+    ///
+    /// Works like [`Self::get_number`], but allows a leading `-`.
+    ///
+    /// # Parameters
+    ///
+    /// * `index` - The index of the argument to parse
+    ///
+    /// # Returns
+    ///
+    /// * `Some(i64)` - The parsed number
+    /// * `None` - If the number could not be parsed or the index is out of bounds
+    fn get_signed_number(&self, index: usize) -> Option<i64> {
+        if index >= self.buf_preparsed.len() {
+            return None;
+        }
+
+        let raw = &self.buf_preparsed[index];
+        let (negative, raw) = match raw.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, raw.as_str()),
+        };
+        let raw = raw.strip_prefix("0x").unwrap_or(raw);
+        trace!("raw signed number: {raw}");
+
+        match i64::from_str_radix(raw, 16) {
+            Ok(val) if negative => Some(-val),
+            Ok(val) => Some(val),
+            Err(e) => {
+                warn!("Failed to parse number '{}': {}", raw, e);
+                None
+            }
+        }
+    }
+
+    /// Parses a signal from the command line arguments
+    ///
+    /// Accepts signal names with or without the `SIG` prefix (`SIGTERM`, `term`) and signal
+    /// numbers in decimal, as they are usually written.
+    ///
+    /// # Parameters
+    ///
+    /// * `index` - The index of the argument to parse
+    ///
+    /// # Returns
+    ///
+    /// * `Some(i32)` - The number of the parsed signal
+    /// * `None` - If the signal could not be parsed or the index is out of bounds
+    fn get_signal(&self, index: usize) -> Option<i32> {
+        if index >= self.buf_preparsed.len() {
+            return None;
+        }
+
+        let raw = self.buf_preparsed[index].to_uppercase();
+        trace!("raw signal: {raw}");
+
+        if let Ok(num) = raw.parse::<i32>() {
+            return Some(num);
+        }
+        let name = if raw.starts_with("SIG") {
+            raw
+        } else {
+            format!("SIG{raw}")
+        };
+        match Signal::from_str(&name) {
+            Ok(sig) => Some(sig as i32),
+            Err(e) => {
+                warn!("Failed to parse '{}' as signal: {}", name, e);
+                None
+            }
+        }
+    }
+
+    /// Parses a sequence of bytes from the command line arguments
+    ///
+    /// The bytes are given as a hexadecimal string with two digits per byte, e.g. `b82a000000`.
+    /// An optional `0x` prefix is ignored.
+    ///
+    /// # Parameters
+    ///
+    /// * `index` - The index of the argument to parse
+    ///
+    /// # Returns
+    ///
+    /// * `Some(Vec<u8>)` - The parsed bytes
+    /// * `None` - If the bytes could not be parsed or the index is out of bounds
+    fn get_bytes(&self, index: usize) -> Option<Vec<u8>> {
+        if index >= self.buf_preparsed.len() {
+            return None;
+        }
+
+        let raw = &self.buf_preparsed[index];
+        let raw = raw.strip_prefix("0x").unwrap_or(raw);
+        trace!("raw bytes: {raw}");
+
+        if raw.is_empty() || raw.len() % 2 != 0 || !raw.is_ascii() {
+            warn!(
+                "Failed to parse '{}' as bytes: needs two hex digits per byte",
+                raw
+            );
+            return None;
+        }
+        match (0..raw.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&raw[i..i + 2], 16))
+            .collect()
+        {
+            Ok(bytes) => Some(bytes),
+            Err(e) => {
+                warn!("Failed to parse '{}' as bytes: {}", raw, e);
+                None
+            }
+        }
+    }
+
     fn get_bool(&self, index: usize) -> Option<bool> {
         if index >= self.buf_preparsed.len() {
             return None;
@@ -227,183 +572,1121 @@ impl CliUi {
     }
 }
 
-impl DebuggerUI for CliUi {
-    #[allow(clippy::pedantic)] // TODO: refactor this function
-    fn process(&mut self, feedback: Feedback) -> crate::errors::Result<Status> {
+impl EventSink for CliUi {
+    fn push_event(&mut self, event: Event) -> Result<()> {
+        let feedback = match event {
+            Event::Feedback(feedback) => feedback,
+            Event::Stopped(reason) => {
+                info!("Stopped: {reason}");
+                self.last_stop = Some(reason);
+                self.stop_shown = true;
+                return Ok(());
+            }
+            Event::Notification(message) => {
+                info!("{message}");
+                return Ok(());
+            }
+        };
+
+        let stop = match &feedback {
+            Feedback::Stopped(info) => Some(info.reason),
+            Feedback::Exit(status) => Some(StopReason::Exited(*status)),
+            Feedback::Interrupted => Some(StopReason::Interrupted),
+            _ => None,
+        };
+        if let Some(reason) = stop {
+            self.last_stop = Some(reason);
+            // the stop was already shown when it happened, but not the displayed expressions
+            if self.stop_shown {
+                if let Feedback::Stopped(info) = &feedback {
+                    for shown in &info.displays {
+                        info!("{shown}");
+                    }
+                }
+                return Ok(());
+            }
+        }
+
         if let Feedback::Error(e) = feedback {
             error!("{e}");
+            self.abort_script();
         } else if let Feedback::Disassembly(d) = feedback {
             info!("\n{d}");
         } else {
             info!("{feedback}");
         }
+        Ok(())
+    }
+}
+
+impl CommandSource for CliUi {
+    #[allow(clippy::pedantic)] // TODO: refactor this function
+    fn next_status(&mut self) -> Result<Status> {
+        self.stop_shown = false;
 
         if self.stepper > 0 {
             self.stepper -= 1;
             return Ok(Status::StepSingle);
         }
 
+        // commands that are rejected here without a status continue the loop, which is noticed
+        // at the start of the next iteration
+        let mut rejected = false;
         loop {
+            if rejected {
+                self.abort_script();
+            }
+            rejected = true;
+
             if let Err(e) = self.get_input() {
                 error!("Error getting input: {}", e);
                 continue;
             }
 
             if self.buf_preparsed.is_empty() {
+                rejected = false;
                 continue;
             }
 
-            let cmd = &self.buf_preparsed[0].to_lowercase();
+            let cmd = &self.buf_preparsed[0].to_lowercase();
+
+            if string_matches(cmd, &["cont", "c"]) {
+                if self.buf_preparsed.len() < 2 {
+                    return Ok(Status::Continue);
+                }
+
+                if self.buf_preparsed[1] == "--timeout" {
+                    if !self.ensure_args("cont --timeout", 2) {
+                        continue;
+                    }
+                    // unlike other numbers, the timeout is decimal
+                    if let Ok(ms) = self.buf_preparsed[2].parse() {
+                        return Ok(Status::ContinueTimeout(ms));
+                    } else {
+                        error!("Invalid timeout for cont");
+                        continue;
+                    }
+                }
+
+                if let Some(sig) = self.get_signal(1) {
+                    return Ok(Status::ContinueWithSignal(sig));
+                } else {
+                    error!("Invalid signal for cont");
+                    continue;
+                }
+            } else if string_matches(cmd, &["profile"]) {
+                if !self.ensure_args("profile", 1) {
+                    continue;
+                }
+                // like the timeout of cont, the duration and frequency are decimal
+                let Ok(ms) = self.buf_preparsed[1].parse() else {
+                    error!("Invalid duration for profile");
+                    continue;
+                };
+                let frequency = match self.buf_preparsed.get(2).map(|s| s.parse()) {
+                    None => DEFAULT_PROFILE_FREQUENCY,
+                    Some(Ok(frequency)) => frequency,
+                    Some(Err(_)) => {
+                        error!("Invalid frequency for profile");
+                        continue;
+                    }
+                };
+                return Ok(Status::Profile(ms, frequency));
+            } else if string_matches(cmd, &["flamegraph"]) {
+                if !self.ensure_args("flamegraph", 1) {
+                    continue;
+                }
+                let output = PathBuf::from(&self.buf_preparsed[1]);
+                let svg = self.buf_preparsed.get(2).is_some_and(|s| s == "--svg");
+                return Ok(Status::ExportFlamegraph(output, svg));
+            } else if string_matches(cmd, &["chrometrace"]) {
+                if !self.ensure_args("chrometrace", 1) {
+                    continue;
+                }
+                return Ok(Status::ExportTrace(PathBuf::from(&self.buf_preparsed[1])));
+            } else if string_matches(cmd, &["why"]) {
+                return Ok(Status::WhyStopped);
+            } else if string_matches(cmd, &["signal", "kill"]) {
+                if !self.ensure_args("signal", 1) {
+                    continue;
+                }
+
+                if let Some(sig) = self.get_signal(1) {
+                    return Ok(Status::SendSignal(sig));
+                } else {
+                    error!("Invalid signal for signal");
+                    continue;
+                }
+            } else if string_matches(cmd, &["delbreak", "dbp"]) {
+                if !self.ensure_args("delbreak", 1) {
+                    continue;
+                }
+
+                if let Some(addr) = self.get_addr(1) {
+                    return Ok(Status::DelBreakpoint(addr));
+                } else {
+                    error!("Invalid address for delbreak");
+                    continue;
+                }
+            } else if string_matches(cmd, &["enablebreak", "ebp"]) {
+                if !self.ensure_args("enablebreak", 1) {
+                    continue;
+                }
+
+                if let Some(addr) = self.get_addr(1) {
+                    return Ok(Status::EnableBreakpoint(addr));
+                } else {
+                    error!("Invalid address for enablebreak");
+                    continue;
+                }
+            } else if string_matches(cmd, &["disablebreak", "xbp"]) {
+                if !self.ensure_args("disablebreak", 1) {
+                    continue;
+                }
+
+                if let Some(addr) = self.get_addr(1) {
+                    return Ok(Status::DisableBreakpoint(addr));
+                } else {
+                    error!("Invalid address for disablebreak");
+                    continue;
+                }
+            } else if string_matches(cmd, &["logbreak", "dprintf"]) {
+                if !self.ensure_args("logbreak", 1) {
+                    continue;
+                }
+
+                let Some(addr) = self.get_addr(1) else {
+                    error!("Invalid address for logbreak");
+                    continue;
+                };
+                let log = if self.buf_preparsed.len() > 2 {
+                    match self.buf_preparsed[2..].join(" ").parse() {
+                        Ok(log) => Some(log),
+                        Err(e) => {
+                            error!("{e}");
+                            continue;
+                        }
+                    }
+                } else {
+                    None
+                };
+
+                return Ok(Status::SetLogBreakpoint(addr, log));
+            } else if string_matches(cmd, &["savebreak", "sbp"]) {
+                if !self.ensure_args("savebreak", 1) {
+                    continue;
+                }
+
+                return Ok(Status::SaveBreakpoints(PathBuf::from(
+                    &self.buf_preparsed[1],
+                )));
+            } else if string_matches(cmd, &["loadbreak", "lbp"]) {
+                if !self.ensure_args("loadbreak", 1) {
+                    continue;
+                }
+
+                return Ok(Status::LoadBreakpoints(PathBuf::from(
+                    &self.buf_preparsed[1],
+                )));
+            } else if string_matches(cmd, &["savesession"]) {
+                if !self.ensure_args("savesession", 1) {
+                    continue;
+                }
+
+                return Ok(Status::SaveSession(PathBuf::from(&self.buf_preparsed[1])));
+            } else if string_matches(cmd, &["loadsession"]) {
+                if !self.ensure_args("loadsession", 1) {
+                    continue;
+                }
+
+                return Ok(Status::RestoreSession(PathBuf::from(
+                    &self.buf_preparsed[1],
+                )));
+            } else if string_matches(cmd, &["watch", "wp"]) {
+                if !self.ensure_args("watch", 2) {
+                    continue;
+                }
+
+                let Some(addr) = self.get_addr(1) else {
+                    error!("Invalid address for watch");
+                    continue;
+                };
+
+                let len = if let Some(val) = self.get_number(2) {
+                    val as usize
+                } else {
+                    error!("Invalid length for watch");
+                    continue;
+                };
+
+                return Ok(Status::SetWatchpoint(addr, len));
+            } else if string_matches(cmd, &["delwatch", "dwp"]) {
+                if !self.ensure_args("delwatch", 1) {
+                    continue;
+                }
+
+                if let Some(addr) = self.get_addr(1) {
+                    return Ok(Status::DelWatchpoint(addr));
+                } else {
+                    error!("Invalid address for delwatch");
+                    continue;
+                }
+            } else if string_matches(cmd, &["watchcond", "wc"]) {
+                if !self.ensure_args("watchcond", 1) {
+                    continue;
+                }
+
+                let Some(addr_raw) = self.get_number(1) else {
+                    error!("Invalid address for watchcond");
+                    continue;
+                };
+                let condition = if self.buf_preparsed.len() > 2 {
+                    match self.buf_preparsed[2..].join(" ").parse() {
+                        Ok(cond) => Some(cond),
+                        Err(e) => {
+                            error!("{e}");
+                            continue;
+                        }
+                    }
+                } else {
+                    None
+                };
+
+                return Ok(Status::SetWatchCondition(
+                    Addr::from(addr_raw as usize),
+                    condition,
+                ));
+            } else if string_matches(cmd, &["guard", "pg"]) {
+                if !self.ensure_args("guard", 2) {
+                    continue;
+                }
+
+                let addr_raw = if let Some(val) = self.get_number(1) {
+                    val as usize
+                } else {
+                    error!("Invalid address for guard");
+                    continue;
+                };
+
+                let len = if let Some(val) = self.get_number(2) {
+                    val as usize
+                } else {
+                    error!("Invalid length for guard");
+                    continue;
+                };
+
+                return Ok(Status::SetPageGuard(Addr::from(addr_raw), len));
+            } else if string_matches(cmd, &["delguard", "dpg"]) {
+                if !self.ensure_args("delguard", 1) {
+                    continue;
+                }
+
+                if let Some(addr_raw) = self.get_number(1) {
+                    let addr: Addr = Addr::from(addr_raw as usize);
+                    return Ok(Status::DelPageGuard(addr));
+                } else {
+                    error!("Invalid address for delguard");
+                    continue;
+                }
+            } else if string_matches(cmd, &["antidebug"]) {
+                let mut config = AntiDebugConfig::default();
+                if self.buf_preparsed.len() < 2 {
+                    config = AntiDebugConfig::all();
+                }
+                let mut valid = true;
+                for option in &self.buf_preparsed[1..] {
+                    match option.to_lowercase().as_str() {
+                        "off" => config = AntiDebugConfig::default(),
+                        "all" => config = AntiDebugConfig::all(),
+                        "traceme" => config.fake_traceme = true,
+                        "tracerpid" => config.hide_tracer_pid = true,
+                        "int3" => config.hide_breakpoints = true,
+                        other => {
+                            error!("Unknown antidebug option: {other}");
+                            valid = false;
+                        }
+                    }
+                }
+                if !valid {
+                    continue;
+                }
+
+                return Ok(Status::SetAntiDebug(config));
+            } else if string_matches(cmd, &["verify"]) {
+                let mut verification = WriteVerification::none();
+                if self.buf_preparsed.len() < 2 {
+                    verification = WriteVerification::all();
+                }
+                let mut valid = true;
+                for option in &self.buf_preparsed[1..] {
+                    match option.to_lowercase().as_str() {
+                        "off" => verification = WriteVerification::none(),
+                        "all" => verification = WriteVerification::all(),
+                        "bp" => verification.breakpoints = true,
+                        "mem" => verification.memory = true,
+                        other => {
+                            error!("Unknown verify option: {other}");
+                            valid = false;
+                        }
+                    }
+                }
+                if !valid {
+                    continue;
+                }
+
+                return Ok(Status::SetWriteVerification(verification));
+            } else if string_matches(cmd, &["ptraceopts"]) {
+                let mut options = PtraceOptions::default();
+                let mut valid = true;
+                for option in &self.buf_preparsed[1..] {
+                    match option.to_lowercase().as_str() {
+                        "off" => options = PtraceOptions::default(),
+                        "exitkill" => options.exit_kill = true,
+                        "sysgood" => options.trace_sysgood = true,
+                        "exit" => options.trace_exit = true,
+                        "clone" => options.trace_clone = true,
+                        "fork" => options.trace_fork = true,
+                        "exec" => options.trace_exec = true,
+                        other => {
+                            error!("Unknown ptrace option: {other}");
+                            valid = false;
+                        }
+                    }
+                }
+                if !valid {
+                    continue;
+                }
+
+                return Ok(Status::SetPtraceOptions(options));
+            } else if string_matches(cmd, &["catch"]) {
+                if !self.ensure_args("catch", 1) {
+                    continue;
+                }
+                if string_matches(&self.buf_preparsed[1].to_lowercase(), &["memory", "mem"]) {
+                    let mut config = MemoryCatchConfig::default();
+                    if self.buf_preparsed.len() < 3 {
+                        config = MemoryCatchConfig::all();
+                    }
+                    let mut valid = true;
+                    for option in &self.buf_preparsed[2..] {
+                        match option.to_lowercase().as_str() {
+                            "off" => config = MemoryCatchConfig::default(),
+                            "all" => config = MemoryCatchConfig::all(),
+                            "mmap" => config.mmap = true,
+                            "mprotect" => config.mprotect = true,
+                            "brk" => config.brk = true,
+                            "exec" => config.executable_only = true,
+                            other => {
+                                error!("Unknown memory catchpoint option: {other}");
+                                valid = false;
+                            }
+                        }
+                    }
+                    if !valid {
+                        continue;
+                    }
+                    // only "exec" was given
+                    if !config.is_active() && config.executable_only {
+                        config = MemoryCatchConfig {
+                            executable_only: true,
+                            ..MemoryCatchConfig::all()
+                        };
+                    }
+
+                    return Ok(Status::CatchMemory(config));
+                }
+                if string_matches(&self.buf_preparsed[1].to_lowercase(), &["panic"]) {
+                    match self.buf_preparsed.get(2) {
+                        Some(a) if a.to_lowercase() == "off" => {
+                            return Ok(Status::CatchPanics(false));
+                        }
+                        Some(other) => error!("Unknown panic catchpoint option: {other}"),
+                        None => return Ok(Status::CatchPanics(true)),
+                    }
+                    continue;
+                }
+                if !string_matches(&self.buf_preparsed[1].to_lowercase(), &["syscall", "sys"]) {
+                    error!("Unknown catchpoint type: {}", self.buf_preparsed[1]);
+                    continue;
+                }
+
+                match self.buf_preparsed.get(2) {
+                    Some(a) if a.to_lowercase() == "off" => {
+                        return Ok(Status::CatchSyscalls(false));
+                    }
+                    Some(_) => {
+                        return Ok(Status::CatchSyscall(self.buf_preparsed[2..].to_vec()));
+                    }
+                    None => return Ok(Status::CatchSyscalls(true)),
+                }
+            } else if string_matches(cmd, &["sysfilter"]) {
+                let syscalls: std::result::Result<Vec<u64>, &String> = self.buf_preparsed[1..]
+                    .iter()
+                    .map(|name| parse_syscall(name).ok_or(name))
+                    .collect();
+                let syscalls = match syscalls {
+                    Ok(s) => s,
+                    Err(name) => {
+                        error!("Unknown syscall: {name}");
+                        continue;
+                    }
+                };
+                let filter = if syscalls.is_empty() {
+                    None
+                } else {
+                    Some(SyscallFilter::new(syscalls))
+                };
+                return Ok(Status::SetSyscallFilter(filter));
+            } else if string_matches(cmd, &["sysarg"]) {
+                if !self.ensure_args("sysarg", 2) {
+                    continue;
+                }
+
+                let index = if let Some(val) = self.get_number(1) {
+                    val as usize
+                } else {
+                    error!("Invalid index for sysarg");
+                    continue;
+                };
+
+                let value = if let Some(val) = self.get_number(2) {
+                    val
+                } else {
+                    error!("Invalid value for sysarg");
+                    continue;
+                };
+
+                return Ok(Status::SetSyscallArg(index, value));
+            } else if string_matches(cmd, &["sysret"]) {
+                if !self.ensure_args("sysret", 1) {
+                    continue;
+                }
+
+                if let Some(value) = self.get_signed_number(1) {
+                    return Ok(Status::SetSyscallReturn(value));
+                } else {
+                    error!("Invalid value for sysret");
+                    continue;
+                }
+            } else if string_matches(cmd, &["d", "dis"]) {
+                if !self.ensure_args("disassemble", 2) {
+                    continue;
+                }
+
+                let Some(addr) = self.get_addr(1) else {
+                    error!("Invalid address for disassemble");
+                    continue;
+                };
+
+                let len = if let Some(val) = self.get_number(2) {
+                    val as usize
+                } else {
+                    error!("Invalid length for disassemble");
+                    continue;
+                };
+
+                let literal = self.buf_preparsed.get(3).is_some_and(|s| s == "--literal");
+                return Ok(Status::DisassembleAt(addr, len, literal));
+            } else if string_matches(cmd, &["break", "bp"]) {
+                if !self.ensure_args("break", 1) {
+                    continue;
+                }
+
+                if let Some(addr) = self.get_addr(1) {
+                    if self.buf_preparsed.get(2).is_some_and(|s| s == "--hw") {
+                        return Ok(Status::SetHardwareBreakpoint(addr));
+                    }
+                    return Ok(Status::SetBreakpoint(addr));
+                } else {
+                    error!("Invalid address for breakpoint");
+                    continue;
+                }
+            } else if string_matches(cmd, &["set"]) {
+                if !self.ensure_args("set", 2) {
+                    continue;
+                }
+
+                if let Some(name) = self.buf_preparsed[1].strip_prefix('$') {
+                    // the `=` of `set $name = EXPR` is optional
+                    let index = if self.buf_preparsed.get(2).is_some_and(|a| a == "=") {
+                        3
+                    } else {
+                        2
+                    };
+                    if let Some(expr) = self.get_addr(index) {
+                        return Ok(Status::SetConvenience(name.to_string(), expr));
+                    }
+                    error!("Invalid value for ${name}");
+                } else if self.buf_preparsed[1] == "stepper" {
+                    match self.get_number(2) {
+                        Some(steps) => {
+                            self.stepper = steps as usize;
+                            rejected = false;
+                        }
+                        None => {
+                            error!("Invalid number for stepper");
+                        }
+                    }
+                } else {
+                    error!("Unknown subcommand for set");
+                }
+                continue;
+            } else if string_matches(cmd, &["sym", "gsym"]) {
+                if !self.ensure_args("symbol", 1) {
+                    continue;
+                }
+
+                let symbol_name: String = self.buf_preparsed[1].to_string();
+                return Ok(Status::GetSymbolsByName(symbol_name));
+            } else if string_matches(cmd, &["var"]) {
+                if !self.ensure_args("var", 1) {
+                    continue;
+                }
+
+                let symbol_name: String = self.buf_preparsed[1].to_string();
+                return Ok(match self.buf_preparsed.get(2).map(|f| f.parse()) {
+                    None => Status::ReadVariable(symbol_name),
+                    Some(Ok(frame)) => Status::ReadFrameVariable(frame, symbol_name),
+                    Some(Err(_)) => {
+                        error!("Invalid frame index");
+                        continue;
+                    }
+                });
+            } else if string_matches(cmd, &["display"]) {
+                if !self.ensure_args("display", 1) {
+                    continue;
+                }
+
+                return Ok(Status::AddAutoDisplay(self.buf_preparsed[1].to_string()));
+            } else if string_matches(cmd, &["undisplay"]) {
+                if !self.ensure_args("undisplay", 1) {
+                    continue;
+                }
+
+                let Ok(number) = self.buf_preparsed[1].parse() else {
+                    error!("Invalid number for undisplay");
+                    continue;
+                };
+                return Ok(Status::DelAutoDisplay(number));
+            } else if string_matches(cmd, &["vars"]) {
+                if !self.ensure_args("vars", 2) {
+                    continue;
+                }
+
+                let symbol_name: String = self.buf_preparsed[1].to_string();
+
+                let Some(value) = self.get_number(2) else {
+                    error!("Invalid value for variable");
+                    continue;
+                };
+                return Ok(match self.buf_preparsed.get(3).map(|f| f.parse()) {
+                    None => Status::WriteVariable(symbol_name, value as usize),
+                    Some(Ok(frame)) => {
+                        Status::WriteFrameVariable(frame, symbol_name, value as usize)
+                    }
+                    Some(Err(_)) => {
+                        error!("Invalid frame index");
+                        continue;
+                    }
+                });
+            } else if string_matches(cmd, &["run"]) {
+                // the assertions are about the new debuggee
+                self.last_stop = None;
+                if let (1, Some(default_executable)) =
+                    (self.buf_preparsed.len(), self.default_executable.as_ref())
+                {
+                    return Ok(Status::Run(
+                        default_executable.into(),
+                        vec![path_to_cstring_or_empty(default_executable)],
+                    ));
+                }
+                if !self.ensure_args("run", 1) {
+                    info!("For the run command, you can set a default executable when you launch the coreminer");
+                    continue;
+                }
+
+                let executable: PathBuf = PathBuf::from(self.buf_preparsed[1].clone());
+                let actual_args: Vec<CString> = if self.buf_preparsed.len() > 2 {
+                    let mut buf = Vec::new();
+
+                    for s in &self.buf_preparsed[2..] {
+                        buf.push(match CString::new(s.as_str()) {
+                            Ok(s) => s,
+                            Err(e) => {
+                                error!("could not make '{s}' into CString: {e}");
+                                continue;
+                            }
+                        })
+                    }
+                    buf
+                } else {
+                    Vec::new()
+                };
+                return Ok(Status::Run(executable, actual_args));
+            } else if string_matches(cmd, &["attach"]) {
+                if !self.ensure_args("attach", 1) {
+                    continue;
+                }
+                let Ok(pid) = self.buf_preparsed[1].parse::<i32>() else {
+                    error!("Invalid process ID for attach");
+                    continue;
+                };
+                // the assertions are about the new debuggee
+                self.last_stop = None;
+                return Ok(Status::Attach(pid));
+            } else if string_matches(cmd, &["core"]) {
+                if !self.ensure_args("core", 1) {
+                    continue;
+                }
+                let executable = self
+                    .buf_preparsed
+                    .get(2)
+                    .map(PathBuf::from)
+                    .or_else(|| self.default_executable.clone());
+                self.last_stop = None;
+                return Ok(Status::OpenCore(
+                    PathBuf::from(&self.buf_preparsed[1]),
+                    executable,
+                ));
+            } else if string_matches(cmd, &["list", "l"]) {
+                if self.buf_preparsed.len() < 2 {
+                    return Ok(Status::ListSource(None));
+                }
+                if let Some(addr) = self.get_addr(1) {
+                    return Ok(Status::ListSource(Some(addr)));
+                } else {
+                    error!("Invalid address for list");
+                    continue;
+                }
+            } else if string_matches(cmd, &["bt"]) {
+                return Ok(Status::Backtrace);
+            } else if string_matches(cmd, &["frame", "f"]) {
+                if !self.ensure_args("frame", 1) {
+                    continue;
+                }
+
+                // frame indices are decimal, like in the output of bt
+                if let Ok(index) = self.buf_preparsed[1].parse() {
+                    return Ok(Status::SelectFrame(index));
+                } else {
+                    error!("Invalid frame index");
+                    continue;
+                }
+            } else if string_matches(cmd, &["startbreak"]) {
+                if !self.ensure_args("startbreak", 1) {
+                    continue;
+                }
+
+                if self.buf_preparsed[1] == "off" {
+                    return Ok(Status::SetLaunchBreak(None));
+                }
+                match self.buf_preparsed[1].parse() {
+                    Ok(launch_break) => return Ok(Status::SetLaunchBreak(Some(launch_break))),
+                    Err(e) => {
+                        error!("{e}");
+                        continue;
+                    }
+                }
+            } else if string_matches(cmd, &["setenv"]) {
+                if !self.ensure_args("setenv", 2) {
+                    continue;
+                }
+                return Ok(Status::SetLaunchEnv(
+                    self.buf_preparsed[1].to_string(),
+                    Some(self.buf_preparsed[2..].join(" ")),
+                ));
+            } else if string_matches(cmd, &["unsetenv"]) {
+                if !self.ensure_args("unsetenv", 1) {
+                    continue;
+                }
+                return Ok(Status::SetLaunchEnv(
+                    self.buf_preparsed[1].to_string(),
+                    None,
+                ));
+            } else if string_matches(cmd, &["preload"]) {
+                if !self.ensure_args("preload", 1) {
+                    continue;
+                }
+                return Ok(Status::PreloadLibrary(self.buf_preparsed[1].to_string()));
+            } else if string_matches(cmd, &["libpath"]) {
+                if !self.ensure_args("libpath", 1) {
+                    continue;
+                }
+                return Ok(Status::AddLibraryPath(self.buf_preparsed[1].to_string()));
+            } else if string_matches(cmd, &["launchenv"]) {
+                return Ok(Status::GetLaunchEnv);
+            } else if string_matches(cmd, &["stdinpipe"]) {
+                if !self.ensure_args("stdinpipe", 1) {
+                    continue;
+                }
+                match self.buf_preparsed[1].as_str() {
+                    "on" => return Ok(Status::SetStdinPipe(true)),
+                    "off" => return Ok(Status::SetStdinPipe(false)),
+                    other => {
+                        error!("stdinpipe needs on or off, not {other}");
+                        continue;
+                    }
+                }
+            } else if string_matches(cmd, &["stdin"]) {
+                if !self.ensure_args("stdin", 1) {
+                    continue;
+                }
+                if self.buf_preparsed[1] == "--eof" {
+                    return Ok(Status::CloseStdin);
+                }
+                let mut line = self.buf_preparsed[1..].join(" ");
+                line.push('\n');
+                return Ok(Status::SendStdin(line.into_bytes()));
+            } else if string_matches(cmd, &["so"]) {
+                return Ok(Status::StepOut);
+            } else if string_matches(cmd, &["su", "sov"]) {
+                return Ok(Status::StepOver);
+            } else if string_matches(cmd, &["si"]) {
+                return Ok(Status::StepInto);
+            } else if string_matches(cmd, &["s", "step"]) {
+                return Ok(Status::StepSingle);
+            } else if string_matches(cmd, &["sl", "stepline"]) {
+                return Ok(Status::StepLine);
+            } else if string_matches(cmd, &["n", "next"]) {
+                return Ok(Status::NextLine);
+            } else if string_matches(cmd, &["until", "advance"]) {
+                if !self.ensure_args("until", 1) {
+                    continue;
+                }
+
+                let Some(addr) = self.get_number(1) else {
+                    error!("Invalid address for until");
+                    continue;
+                };
+                return Ok(Status::RunUntil(Addr::from(addr as usize)));
+            } else if string_matches(cmd, &["record"]) {
+                let interval = match self.buf_preparsed.get(1).map(String::as_str) {
+                    Some("stop") => return Ok(Status::StopRecording),
+                    Some(raw) => {
+                        let Ok(interval) = raw.parse() else {
+                            error!("Invalid checkpoint interval for record");
+                            continue;
+                        };
+                        interval
+                    }
+                    None => DEFAULT_CHECKPOINT_INTERVAL,
+                };
+                return Ok(Status::StartRecording(interval));
+            } else if string_matches(cmd, &["rs", "reverse-step"]) {
+                return Ok(Status::ReverseStep);
+            } else if string_matches(cmd, &["rc", "reverse-continue"]) {
+                return Ok(Status::ReverseContinue);
+            } else if string_matches(cmd, &["checkpoint"]) {
+                return Ok(Status::Checkpoint);
+            } else if string_matches(cmd, &["restore"]) {
+                if !self.ensure_args("restore", 1) {
+                    continue;
+                }
+
+                let Ok(id) = self.buf_preparsed[1].parse() else {
+                    error!("Invalid checkpoint number for restore");
+                    continue;
+                };
+                return Ok(Status::Restore(id));
+            } else if string_matches(cmd, &["delcheckpoint"]) {
+                if !self.ensure_args("delcheckpoint", 1) {
+                    continue;
+                }
 
-            if string_matches(cmd, &["cont", "c"]) {
-                return Ok(Status::Continue);
-            } else if string_matches(cmd, &["delbreak", "dbp"]) {
-                if !self.ensure_args("delbreak", 1) {
+                let Ok(id) = self.buf_preparsed[1].parse() else {
+                    error!("Invalid checkpoint number for delcheckpoint");
+                    continue;
+                };
+                return Ok(Status::DelCheckpoint(id));
+            } else if string_matches(cmd, &["sr", "steprange"]) {
+                if !self.ensure_args("steprange", 2) {
                     continue;
                 }
 
-                if let Some(addr_raw) = self.get_number(1) {
-                    let addr: Addr = Addr::from(addr_raw as usize);
-                    return Ok(Status::DelBreakpoint(addr));
+                if let (Some(start), Some(end)) = (self.get_number(1), self.get_number(2)) {
+                    return Ok(Status::StepRange(
+                        Addr::from(start as usize),
+                        Addr::from(end as usize),
+                    ));
                 } else {
-                    error!("Invalid address for delbreak");
+                    error!("Invalid range for steprange");
                     continue;
                 }
-            } else if string_matches(cmd, &["d", "dis"]) {
-                if !self.ensure_args("disassemble", 2) {
+            } else if string_matches(cmd, &["info"]) {
+                if self.buf_preparsed.len() < 2 {
+                    return Ok(Status::Infos);
+                }
+                let pattern = self.buf_preparsed.get(2).cloned().unwrap_or_default();
+                if string_matches(&self.buf_preparsed[1], &["functions", "func"]) {
+                    return Ok(Status::ListFunctions(pattern));
+                }
+                if string_matches(&self.buf_preparsed[1], &["breakpoints", "break", "bp"]) {
+                    return Ok(Status::ListBreakpoints);
+                }
+                if string_matches(&self.buf_preparsed[1], &["types"]) {
+                    return Ok(Status::ListTypes(pattern));
+                }
+                if string_matches(&self.buf_preparsed[1], &["variables", "var"]) {
+                    let module = self.buf_preparsed.get(3).cloned();
+                    return Ok(Status::ListVariables(pattern, module));
+                }
+                error!("unknown info: {}", self.buf_preparsed[1]);
+                continue;
+            } else if string_matches(cmd, &["stack"]) {
+                return Ok(Status::GetStack);
+            } else if string_matches(cmd, &["pm"]) {
+                return Ok(Status::ProcMap);
+            } else if string_matches(cmd, &["modules"]) {
+                return Ok(Status::ListModules);
+            } else if string_matches(cmd, &["add-symbol-file", "asf"]) {
+                if !self.ensure_args("add-symbol-file", 2) {
                     continue;
                 }
 
-                let addr_raw = if let Some(val) = self.get_number(1) {
-                    val as usize
+                if let Some(base) = self.get_number(2) {
+                    let path = PathBuf::from(&self.buf_preparsed[1]);
+                    return Ok(Status::AddSymbolFile(path, Addr::from(base as usize)));
                 } else {
-                    error!("Invalid address for disassemble");
+                    error!("Invalid address for add-symbol-file");
                     continue;
-                };
+                }
+            } else if string_matches(cmd, &["env", "environ"]) {
+                return Ok(Status::ShowEnviron);
+            } else if string_matches(cmd, &["fds"]) {
+                return Ok(Status::ListFileDescriptors);
+            } else if string_matches(cmd, &["ps", "procinfo"]) {
+                return Ok(Status::ProcessInfo);
+            } else if string_matches(cmd, &["auxv"]) {
+                return Ok(Status::ShowAuxv);
+            } else if string_matches(cmd, &["threads"]) {
+                return Ok(Status::ListThreads);
+            } else if string_matches(cmd, &["got"]) {
+                if self.buf_preparsed.len() < 2 {
+                    return Ok(Status::ListGot);
+                }
+                if self.buf_preparsed[1] != "hook" {
+                    error!("unknown got subcommand: {}", self.buf_preparsed[1]);
+                    continue;
+                }
+                if !self.ensure_args("got hook", 3) {
+                    continue;
+                }
 
-                let len = if let Some(val) = self.get_number(2) {
-                    val as usize
+                if let Some(addr_raw) = self.get_number(3) {
+                    let symbol = self.buf_preparsed[2].clone();
+                    return Ok(Status::HookGot(symbol, Addr::from(addr_raw as usize)));
                 } else {
-                    error!("Invalid length for disassemble");
+                    error!("Invalid address for got hook");
                     continue;
+                }
+            } else if string_matches(cmd, &["inject"]) {
+                if !self.ensure_args("inject", 1) {
+                    continue;
+                }
+
+                let run = match self.buf_preparsed.get(2).map(String::as_str) {
+                    None => false,
+                    Some("run") => true,
+                    Some(other) => {
+                        error!("unknown option for inject: {other}");
+                        continue;
+                    }
                 };
+                if let Some(code) = self.get_bytes(1) {
+                    return Ok(Status::InjectCode(code, run));
+                } else {
+                    error!("Invalid code for inject");
+                    continue;
+                }
+            } else if string_matches(cmd, &["call"]) {
+                if !self.ensure_args("call", 1) {
+                    continue;
+                }
 
-                let addr = Addr::from(addr_raw);
-                let literal = self.buf_preparsed.get(3).is_some_and(|s| s == "--literal");
-                return Ok(Status::DisassembleAt(addr, len, literal));
-            } else if string_matches(cmd, &["break", "bp"]) {
-                if !self.ensure_args("break", 1) {
+                let Some(args) = (2..self.buf_preparsed.len())
+                    .map(|i| self.get_number(i))
+                    .collect::<Option<Vec<_>>>()
+                else {
+                    error!("Invalid argument for call");
+                    continue;
+                };
+                return Ok(Status::CallFunction(self.buf_preparsed[1].clone(), args));
+            } else if string_matches(cmd, &["patch"]) {
+                if !self.ensure_args("patch", 2) {
                     continue;
                 }
 
                 if let Some(addr_raw) = self.get_number(1) {
-                    let addr: Addr = Addr::from(addr_raw as usize);
-                    return Ok(Status::SetBreakpoint(addr));
+                    let text = self.buf_preparsed[2..].join(" ");
+                    return Ok(Status::PatchAsm(Addr::from(addr_raw as usize), text));
                 } else {
-                    error!("Invalid address for breakpoint");
+                    error!("Invalid address for patch");
                     continue;
                 }
-            } else if string_matches(cmd, &["set"]) {
-                if !self.ensure_args("set", 2) {
+            } else if string_matches(cmd, &["dump"]) {
+                if !self.ensure_args("dump", 1) {
                     continue;
                 }
 
-                if self.buf_preparsed[1] == "stepper" {
-                    match self.get_number(2) {
-                        Some(steps) => {
-                            self.stepper = steps as usize;
+                let output = PathBuf::from(&self.buf_preparsed[1]);
+                let module = self.buf_preparsed.get(2).cloned();
+                return Ok(Status::DumpModule(module, output));
+            } else if string_matches(cmd, &["gcore"]) {
+                if !self.ensure_args("gcore", 1) {
+                    continue;
+                }
+
+                return Ok(Status::GenerateCore(PathBuf::from(&self.buf_preparsed[1])));
+            } else if string_matches(cmd, &["xref"]) {
+                if !self.ensure_args("xref", 1) {
+                    continue;
+                }
+
+                if let Some(addr) = self.get_addr(1) {
+                    return Ok(Status::FindXrefs(addr));
+                } else {
+                    error!("Invalid address for xref");
+                    continue;
+                }
+            } else if string_matches(cmd, &["rr"]) {
+                let addr = self.get_number(2).map(|a| Addr::from(a as usize));
+                match self.buf_preparsed.get(1).map(String::as_str) {
+                    Some("record") => {
+                        if !self.ensure_args("rr record", 3) {
+                            continue;
                         }
-                        None => {
-                            error!("Invalid number for stepper");
+                        let mut args = Vec::new();
+                        for s in &self.buf_preparsed[4..] {
+                            match CString::new(s.as_str()) {
+                                Ok(s) => args.push(s),
+                                Err(e) => error!("could not make '{s}' into CString: {e}"),
+                            }
                         }
+                        return Ok(Status::Record(
+                            PathBuf::from(&self.buf_preparsed[3]),
+                            args,
+                            PathBuf::from(&self.buf_preparsed[2]),
+                        ));
+                    }
+                    Some("replay") => {
+                        if !self.ensure_args("rr replay", 3) {
+                            continue;
+                        }
+                        let Ok(port) = self.buf_preparsed[3].parse::<u16>() else {
+                            error!("Invalid port for rr replay");
+                            continue;
+                        };
+                        return Ok(Status::Replay(PathBuf::from(&self.buf_preparsed[2]), port));
+                    }
+                    Some("c" | "continue") => return Ok(Status::ReplayResume(Resume::Continue)),
+                    Some("s" | "step") => return Ok(Status::ReplayResume(Resume::Step)),
+                    Some("rc" | "reverse-continue") => {
+                        return Ok(Status::ReplayResume(Resume::ReverseContinue))
+                    }
+                    Some("rs" | "reverse-step") => {
+                        return Ok(Status::ReplayResume(Resume::ReverseStep))
+                    }
+                    Some("regs") => return Ok(Status::ReplayRegisters),
+                    Some("read") => {
+                        let (Some(addr), Some(len)) = (addr, self.get_number(3)) else {
+                            error!("Usage: rr read ADDR LEN");
+                            continue;
+                        };
+                        return Ok(Status::ReplayReadMem(addr, len as usize));
+                    }
+                    Some("break") => {
+                        let Some(addr) = addr else {
+                            error!("Usage: rr break ADDR");
+                            continue;
+                        };
+                        return Ok(Status::ReplaySetBreakpoint(addr));
+                    }
+                    Some("delete") => {
+                        let Some(addr) = addr else {
+                            error!("Usage: rr delete ADDR");
+                            continue;
+                        };
+                        return Ok(Status::ReplayDelBreakpoint(addr));
+                    }
+                    Some("stop") => return Ok(Status::StopReplay),
+                    _ => {
+                        error!("Unknown rr command, see help");
+                        continue;
                     }
-                } else {
-                    error!("Unknown subcommand for set");
                 }
-                continue;
-            } else if string_matches(cmd, &["sym", "gsym"]) {
-                if !self.ensure_args("symbol", 1) {
+            } else if string_matches(cmd, &["callgraph"]) {
+                let output = self.buf_preparsed.get(1).map(PathBuf::from);
+                return Ok(Status::ExportCallGraph(output));
+            } else if string_matches(cmd, &["btgraph"]) {
+                let output = self.buf_preparsed.get(1).map(PathBuf::from);
+                return Ok(Status::ExportBacktraceGraph(output));
+            } else if string_matches(cmd, &["uprobe"]) {
+                if !self.ensure_args("uprobe", 1) {
                     continue;
                 }
 
-                let symbol_name: String = self.buf_preparsed[1].to_string();
-                return Ok(Status::GetSymbolsByName(symbol_name));
-            } else if string_matches(cmd, &["var"]) {
-                if !self.ensure_args("var", 1) {
+                if let Some(addr_raw) = self.get_number(1) {
+                    let capture = self.buf_preparsed.get(2).is_some_and(|s| s == "--args");
+                    return Ok(Status::SetUprobe(Addr::from(addr_raw as usize), capture));
+                } else {
+                    error!("Invalid address for uprobe");
                     continue;
                 }
-
-                let symbol_name: String = self.buf_preparsed[1].to_string();
-                return Ok(Status::ReadVariable(symbol_name));
-            } else if string_matches(cmd, &["vars"]) {
-                if !self.ensure_args("vars", 2) {
+            } else if string_matches(cmd, &["deluprobe"]) {
+                if !self.ensure_args("deluprobe", 1) {
                     continue;
                 }
 
-                let symbol_name: String = self.buf_preparsed[1].to_string();
-
-                if let Some(value) = self.get_number(2) {
-                    return Ok(Status::WriteVariable(symbol_name, value as usize));
+                if let Some(addr_raw) = self.get_number(1) {
+                    return Ok(Status::DelUprobe(Addr::from(addr_raw as usize)));
                 } else {
-                    error!("Invalid value for variable");
+                    error!("Invalid address for deluprobe");
                     continue;
                 }
-            } else if string_matches(cmd, &["run"]) {
-                if self.buf_preparsed.len() == 1 && self.default_executable.is_some() {
-                    // safe because we just checked that its some
-                    let default_executable = self.default_executable.as_ref().unwrap();
-                    return Ok(Status::Run(
-                        default_executable.into(),
-                        vec![path_to_cstring_or_empty(default_executable)],
-                    ));
+            } else if string_matches(cmd, &["uprobes"]) {
+                return Ok(Status::ReadUprobes);
+            } else if string_matches(cmd, &["perf"]) {
+                match self
+                    .buf_preparsed
+                    .get(1)
+                    .map(|s| s.to_lowercase())
+                    .as_deref()
+                {
+                    Some("start") => return Ok(Status::StartPerfCounters),
+                    Some("stop") => return Ok(Status::StopPerfCounters),
+                    None | Some("read") => return Ok(Status::ReadPerfCounters),
+                    Some(other) => {
+                        error!("Unknown perf operation: {other}, expected start, stop or read");
+                        continue;
+                    }
                 }
-                if !self.ensure_args("run", 1) {
-                    info!("For the run command, you can set a default executable when you launch the coreminer");
+            } else if string_matches(cmd, &["addr"]) {
+                if !self.ensure_args("addr", 1) {
                     continue;
                 }
 
-                let executable: PathBuf = PathBuf::from(self.buf_preparsed[1].clone());
-                let actual_args: Vec<CString> = if self.buf_preparsed.len() > 2 {
-                    let mut buf = Vec::new();
-
-                    for s in &self.buf_preparsed[2..] {
-                        buf.push(match CString::new(s.as_str()) {
-                            Ok(s) => s,
-                            Err(e) => {
-                                error!("could not make '{s}' into CString: {e}");
-                                continue;
-                            }
-                        })
-                    }
-                    buf
+                if let Some(addr) = self.get_addr(1) {
+                    return Ok(Status::ResolveAddr(addr));
                 } else {
-                    Vec::new()
-                };
-                return Ok(Status::Run(executable, actual_args));
-            } else if string_matches(cmd, &["bt"]) {
-                return Ok(Status::Backtrace);
-            } else if string_matches(cmd, &["so"]) {
-                return Ok(Status::StepOut);
-            } else if string_matches(cmd, &["su", "sov"]) {
-                return Ok(Status::StepOver);
-            } else if string_matches(cmd, &["si"]) {
-                return Ok(Status::StepInto);
-            } else if string_matches(cmd, &["s", "step"]) {
-                return Ok(Status::StepSingle);
-            } else if string_matches(cmd, &["info"]) {
-                return Ok(Status::Infos);
-            } else if string_matches(cmd, &["stack"]) {
-                return Ok(Status::GetStack);
-            } else if string_matches(cmd, &["pm"]) {
-                return Ok(Status::ProcMap);
+                    error!("Invalid address for addr");
+                    continue;
+                }
+            } else if string_matches(cmd, &["values"]) {
+                return Ok(Status::ShowValues);
             } else if string_matches(cmd, &["rmem"]) {
                 if !self.ensure_args("rmem", 1) {
                     continue;
                 }
 
-                if let Some(addr_raw) = self.get_number(1) {
-                    let addr: Addr = Addr::from(addr_raw as usize);
-                    return Ok(Status::ReadMem(addr));
+                if let Some(addr) = self.get_addr(1) {
+                    if self.buf_preparsed.len() < 3 {
+                        return Ok(Status::ReadMem(addr));
+                    }
+                    if let Some(len) = self.get_number(2) {
+                        return Ok(Status::ReadMemRange(addr, len as usize));
+                    }
+                    error!("Invalid length for rmem");
+                    continue;
                 } else {
                     error!("Invalid address for rmem");
                     continue;
@@ -413,9 +1696,7 @@ impl DebuggerUI for CliUi {
                     continue;
                 }
 
-                let addr_raw = if let Some(val) = self.get_number(1) {
-                    val as usize
-                } else {
+                let Some(addr) = self.get_addr(1) else {
                     error!("Invalid address for wmem");
                     continue;
                 };
@@ -427,7 +1708,6 @@ impl DebuggerUI for CliUi {
                     continue;
                 };
 
-                let addr: Addr = Addr::from(addr_raw);
                 return Ok(Status::WriteMem(addr, value));
             } else if string_matches(cmd, &["regs"]) {
                 if !self.ensure_args("regs", 1) {
@@ -489,7 +1769,96 @@ impl DebuggerUI for CliUi {
                 }
                 return Ok(Status::PluginGetList);
             } else if string_matches(cmd, &["help", "h", "?"]) {
-                show_help();
+                // with a line reader, like the TUI, the terminal is not ours to print to
+                show_help(self.line_reader.is_some());
+                rejected = false;
+                continue;
+            } else if string_matches(cmd, &["detach"]) {
+                return Ok(Status::Detach);
+            } else if string_matches(cmd, &["onquit"]) {
+                if !self.ensure_args("onquit", 1) {
+                    continue;
+                }
+
+                match self.buf_preparsed[1].parse() {
+                    Ok(action) => return Ok(Status::SetQuitAction(action)),
+                    Err(e) => {
+                        error!("{e}");
+                        continue;
+                    }
+                }
+            } else if string_matches(cmd, &["limits"]) {
+                let mut limits = Limits::default();
+                let mut valid = true;
+                for option in &self.buf_preparsed[1..] {
+                    let parsed = option.split_once('=').and_then(|(kind, max)| {
+                        let max = usize::from_str_radix(max.trim_start_matches("0x"), 16).ok()?;
+                        Some((kind.parse::<LimitKind>(), max))
+                    });
+                    match parsed {
+                        Some((Ok(kind), max)) => limits.set(kind, max),
+                        Some((Err(e), _)) => {
+                            error!("{e}");
+                            valid = false;
+                        }
+                        None => {
+                            error!("Invalid limit: {option}, expected KIND=MAX");
+                            valid = false;
+                        }
+                    }
+                }
+                if !valid {
+                    continue;
+                }
+
+                return Ok(Status::SetLimits(limits));
+            } else if string_matches(cmd, &["schedlock"]) {
+                if !self.ensure_args("schedlock", 1) {
+                    continue;
+                }
+
+                match self.buf_preparsed[1].parse() {
+                    Ok(locking) => return Ok(Status::SetSchedulerLocking(locking)),
+                    Err(e) => {
+                        error!("{e}");
+                        continue;
+                    }
+                }
+            } else if string_matches(cmd, &["followfork"]) {
+                if !self.ensure_args("followfork", 1) {
+                    continue;
+                }
+
+                match self.buf_preparsed[1].parse() {
+                    Ok(follow) => return Ok(Status::SetFollowFork(follow)),
+                    Err(e) => {
+                        error!("{e}");
+                        continue;
+                    }
+                }
+            } else if string_matches(cmd, &["source"]) {
+                if !self.ensure_args("source", 1) {
+                    continue;
+                }
+
+                let path = PathBuf::from(&self.buf_preparsed[1]);
+                match self.source(&path) {
+                    Ok(()) => rejected = false,
+                    Err(e) => error!("could not read {}: {e}", path.display()),
+                }
+                continue;
+            } else if string_matches(cmd, &["assert"]) {
+                match self.check_assertion() {
+                    Some(true) => {
+                        info!("assertion holds");
+                        rejected = false;
+                    }
+                    Some(false) => match &self.last_stop {
+                        Some(reason) => error!("assertion failed, last stop: {reason}"),
+                        None => error!("assertion failed, the debuggee has not stopped"),
+                    },
+                    None => error!("Invalid assertion, see 'help'"),
+                }
                 continue;
             } else if string_matches(cmd, &["q", "quit", "exit"]) {
                 return Ok(Status::DebuggerQuit);
@@ -513,47 +1882,215 @@ impl DebuggerUI for CliUi {
 /// * `true` - If the command matches any prefix
 /// * `false` - If the command does not match any prefix
 fn string_matches(cmd: &str, prefixes: &[&str]) -> bool {
-    prefixes.iter().any(|a| cmd == *a)
+    prefixes.contains(&cmd)
 }
 
 /// Shows help information for the debugger commands
 ///
-/// Prints a list of all available commands and their usage to stdout.
-fn show_help() {
-    println!(
+/// Prints a list of all available commands and their usage to stdout, or logs it if `log` is
+/// set.
+#[allow(clippy::too_many_lines)] // one line per command
+fn show_help(log: bool) {
+    let help =
     concat!(
     "\nCoreminer Debugger Help:\n",
     "\n  run PATH:str [ARGS:str ...]             - Run program at PATH with optional arguments",
-    "\n  c, cont                                 - Continue execution",
+    "\n  attach PID:dec                          - Attach to the running process PID and all",
+    "\n                                            of its threads",
+    "\n  core FILE:str [EXE:str]                 - Open the core dump FILE of a crashed process",
+    "\n                                            of EXE, detach closes it again",
+    "\n  startbreak WHERE:str                    - Stop newly run programs at WHERE, which is one",
+    "\n                                            of entry, main, off (default)",
+    "\n  setenv NAME:str VAL:str                 - Set NAME to VAL in the environment of newly",
+    "\n                                            run programs, not of the debugger",
+    "\n  unsetenv NAME:str                       - Remove NAME from the environment of newly",
+    "\n                                            run programs",
+    "\n  preload LIB:str                         - Load the library LIB first in newly run",
+    "\n                                            programs, with LD_PRELOAD",
+    "\n  libpath DIR:str                         - Search libraries in DIR first in newly run",
+    "\n                                            programs, with LD_LIBRARY_PATH",
+    "\n  launchenv                               - Show the changes to the environment of newly",
+    "\n                                            run programs",
+    "\n  stdinpipe on|off                        - Connect the stdin of newly run programs to a",
+    "\n                                            pipe instead of the terminal",
+    "\n  stdin TEXT:str ...                      - Send TEXT and a newline to the stdin pipe of",
+    "\n                                            the debuggee",
+    "\n  stdin --eof                             - Close the stdin pipe of the debuggee",
+    "\n  c, cont [SIG:sig]                       - Continue execution, optionally delivering SIG",
+    "\n  c, cont --timeout MS:dec                - Continue execution, but stop again after MS",
+    "\n                                            milliseconds (decimal)",
+    "\n  why                                     - Show why the debuggee stopped the last time",
+    "\n  profile MS:dec [HZ:dec]                 - Continue for MS while sampling the call stack",
+    "\n                                            HZ times per second (default 100)",
+    "\n  flamegraph FILE:str [--svg]             - Write the last profile into FILE as folded",
+    "\n                                            stacks, or as SVG flamegraph with --svg",
+    "\n  chrometrace FILE:str                     - Write the runs and stops of this session into",
+    "\n                                            FILE as Chrome trace, for chrome://tracing",
+    "\n  signal, kill SIG:sig                    - Send SIG to the debuggee",
     "\n  s, step                                 - Step one instruction",
+    "\n  sl, stepline                            - Step to the next source line, into calls",
+    "\n  n, next                                 - Step to the next source line, over calls",
     "\n  si                                      - Step into function call",
     "\n  su, sov                                 - Step over function call",
     "\n  so                                      - Step out of current function",
-    "\n  bp, break ADDR:num                      - Set breakpoint at address (hex)",
-    "\n  dbp, delbreak ADDR:num                  - Delete breakpoint at address (hex)",
-    "\n  d, dis ADDR:num LEN:num [--literal]     - Disassemble LEN bytes at ADDR",
+    "\n  sr, steprange START:num END:num         - Step while rip is in START..END",
+    "\n  until, advance ADDR:num                 - Continue until ADDR is reached in this frame",
+    "\n  record [N:dec]                          - Record the stops from now on, with a checkpoint",
+    "\n                                            every N stops (default 10)",
+    "\n  record stop                             - Stop recording",
+    "\n  rs, reverse-step                        - Go back to the previous recorded stop",
+    "\n  rc, reverse-continue                    - Go back to the last recorded breakpoint or",
+    "\n                                            other event",
+    "\n  checkpoint                              - Save the registers and writable memory",
+    "\n  restore N:dec                           - Restore the checkpoint N",
+    "\n  delcheckpoint N:dec                     - Delete the checkpoint N",
+    "\n  bp, break ADDR:addr [--hw]              - Set breakpoint at ADDR, use a debug",
+    "\n                                            register instead of int3 with --hw",
+    "\n  dbp, delbreak ADDR:addr                 - Delete breakpoint at ADDR",
+    "\n  ebp, enablebreak ADDR:addr              - Enable the disabled breakpoint at ADDR",
+    "\n  xbp, disablebreak ADDR:addr             - Disable breakpoint at ADDR without deleting it",
+    "\n  dprintf, logbreak ADDR:addr [FMT:str]   - Print FMT instead of stopping at the breakpoint",
+    "\n                                            at ADDR, like \"x = {x}, rax = {rax}\", or",
+    "\n                                            stop there again without FMT",
+    "\n  sbp, savebreak FILE:str                 - Save all breakpoints into FILE",
+    "\n  lbp, loadbreak FILE:str                 - Set the breakpoints saved in FILE",
+    "\n  wp, watch ADDR:addr LEN:num             - Watch LEN bytes at ADDR for changes",
+    "\n  dwp, delwatch ADDR:addr                 - Delete watchpoint at ADDR",
+    "\n  wc, watchcond ADDR:num [EXPR:str ...]   - Only stop for changes at ADDR where EXPR holds,",
+    "\n                                            like new == 0 or old < new, none for all",
+    "\n  pg, guard ADDR:num LEN:num              - Watch LEN bytes at ADDR for changes by write",
+    "\n                                            protecting their pages, for large ranges",
+    "\n  dpg, delguard ADDR:num                  - Delete page guard at address (hex)",
+    "\n  antidebug [OPTION:str ...]              - Hide the debugger, OPTION is one of",
+    "\n                                            off, all (default), traceme, tracerpid, int3",
+    "\n  verify [OPTION:str ...]                 - Read back writes to check them, OPTION is one",
+    "\n                                            of off, all (default), bp, mem",
+    "\n  ptraceopts [OPTION:str ...]             - Set the ptrace options, OPTION is one of off,",
+    "\n                                            exitkill, sysgood, exit, clone, fork, exec",
+    "\n  catch syscall [off|NAME:str ...]        - Stop at every syscall entry and exit, or only",
+    "\n                                            at the syscalls NAME, like openat write",
+    "\n  catch mem [OPTION:str ...]              - Stop after memory syscalls, OPTION is one of",
+    "\n                                            off, all (default), mmap, mprotect, brk,",
+    "\n                                            exec (only new executable memory)",
+    "\n  catch panic [off]                       - Stop Rust programs when they panic, before",
+    "\n                                            the stack is unwound",
+    "\n  sysfilter [NAME:str ...]                - Only catch the syscalls NAME or numbers, with",
+    "\n                                            seccomp at the next run, none to catch all",
+    "\n  sysarg INDEX:num VAL:num                 - Set argument INDEX of the entered syscall",
+    "\n  sysret VAL:int                          - Set return value of the exited syscall",
+    "\n  d, dis ADDR:addr LEN:num [--literal]    - Disassemble LEN bytes at ADDR",
     "\n  bt                                      - Show backtrace",
+    "\n  l, list [ADDR:addr]                     - List the source lines around ADDR or the",
+    "\n                                            current line",
+    "\n  f, frame INDEX:dec                      - Select the stack frame for var and vars",
     "\n  stack                                   - Show stack",
     "\n  info                                    - Show debugger info",
+    "\n  info breakpoints                        - List the breakpoints with their hits and lines",
+    "\n  info functions [PATTERN:str]            - List the functions matching PATTERN, like",
+    "\n                                            str* or a part of their names",
+    "\n  info types [PATTERN:str]                - List the structs, enums, typedefs and base",
+    "\n                                            types matching PATTERN, with their sizes",
+    "\n  info variables [PATTERN:str] [MOD:str]  - List the global and static variables matching",
+    "\n                                            PATTERN, only of the modules like MOD if given",
     "\n  pm                                      - Show process memory map",
+    "\n  modules                                 - Show the loaded modules, their base addresses",
+    "\n                                            and load biases",
+    "\n  add-symbol-file, asf FILE:str ADDR:num  - Add the symbols of FILE as if it was loaded",
+    "\n                                            at ADDR, for code without its own symbols",
+    "\n  env, environ                            - Show environment of the debuggee",
+    "\n  fds                                     - List open file descriptors of the debuggee",
+    "\n  ps, procinfo                            - Show status and resource usage of the debuggee",
+    "\n  auxv                                    - Show auxiliary vector (entry point etc.)",
+    "\n  threads                                 - List the threads of the debuggee and their names",
+    "\n  schedlock MODE:str                      - Let the other threads run along (off, default),",
+    "\n                                            not while stepping (step) or never (on)",
+    "\n  followfork MODE:str                     - Stay with the parent (default), the child or",
+    "\n                                            both processes when the debuggee forks",
+    "\n  limits [KIND=MAX:num ...]               - Cap expensive requests, KIND is one of read,",
+    "\n                                            disas, symbols, pointers; others are reset to",
+    "\n                                            default",
+    "\n  got                                     - List GOT entries and their targets",
+    "\n  got hook SYM:str ADDR:num               - Point the GOT entry of SYM to ADDR",
+    "\n  inject CODE:bytes [run]                 - Inject CODE into new executable memory",
+    "\n                                            and run it if 'run' is given",
+    "\n  call FUNC:addr [ARG:num ...]            - Call FUNC with integer ARGs and show the",
+    "\n                                            returned rax",
+    "\n  patch ADDR:num ASM:str ...              - Assemble ASM and write it at ADDR, separate",
+    "\n                                            instructions with ';' (nop; jmp 0x401050)",
+    "\n  dump OUT:str [MODULE:str]               - Dump MODULE (default: executable) from memory",
+    "\n                                            into the ELF file OUT",
+    "\n  gcore OUT:str                           - Save a core dump of the debuggee to OUT",
+    "\n  xref ADDR:addr                          - Find instructions referencing ADDR",
+    "\n  rr record TRACE:str PROG:str [ARG ...]  - Record PROG with rr into the directory TRACE",
+    "\n  rr replay TRACE:str PORT:dec            - Replay TRACE with rr, its gdbserver listens on",
+    "\n                                            PORT, the replay is separate from the debuggee",
+    "\n  rr c, continue                          - Continue the replay",
+    "\n  rr s, step                              - Step the replay one instruction",
+    "\n  rr rc, reverse-continue                 - Continue the replay backwards",
+    "\n  rr rs, reverse-step                     - Step the replay back one instruction",
+    "\n  rr regs                                 - Show the registers of the replay",
+    "\n  rr read ADDR:num LEN:num                - Read LEN bytes at ADDR of the replay",
+    "\n  rr break ADDR:num                       - Set a breakpoint in the replay",
+    "\n  rr delete ADDR:num                      - Delete a breakpoint of the replay",
+    "\n  rr stop                                 - Stop the replay",
+    "\n  callgraph [FILE:str]                    - Export the call graph of the executable as DOT",
+    "\n  btgraph [FILE:str]                      - Export the backtraces taken so far as DOT graph",
+    "\n  uprobe ADDR:num [--args]                - Count hits of ADDR without stopping, capture",
+    "\n                                            the argument registers with --args",
+    "\n  deluprobe ADDR:num                      - Delete the uprobe at address (hex)",
+    "\n  uprobes                                 - Show the hits of all uprobes",
+    "\n  perf [start|stop|read]                  - Reset and start, stop or read (default) the",
+    "\n                                            instruction, cycle and cache miss counters",
     "\n  regs get                                - Show register values",
     "\n  regs set REG:str VAL:num                - Set register REG to value VAL (hex)",
-    "\n  rmem ADDR:num                           - Read memory at address (hex)",
-    "\n  wmem ADDR:num VAL:num                   - Write value to memory at address (hex)",
+    "\n  rmem ADDR:addr [LEN:num]                - Read a word at ADDR, or LEN bytes as far as",
+    "\n                                            they are readable",
+    "\n  wmem ADDR:addr VAL:num                  - Write value to memory at ADDR",
+    "\n  addr EXPR:addr                          - Resolve EXPR to an address",
     "\n  sym, gsym NAME:str                      - Look up symbol by name",
-    "\n  var NAME:str                            - Read variable value",
-    "\n  vars NAME:str VAL:num                   - Write value to variable",
+    "\n  var NAME:str [FRAME:dec]                - Read variable value, in FRAME if given",
+    "\n                                            NAME can select data like config.flags,",
+    "\n                                            arr[3], *ptr or node->next, or &NAME an address",
+    "\n  vars NAME:str VAL:num [FRAME:dec]       - Write value to variable, in FRAME if given",
+    "\n  display NAME:str                        - Show the value of NAME at every stop",
+    "\n  undisplay N:dec                         - Stop showing the value with the number N",
     "\n  set stepper N                           - Set stepper to auto-step N times",
+    "\n  set $NAME:str [=] EXPR:addr             - Set the convenience variable $NAME to EXPR",
+    "\n  values                                  - Show the value history ($1, $2, ...) and the",
+    "\n                                            convenience variables",
+    "\n  detach                                  - Detach from the debuggee and leave it running",
+    "\n  onquit ACTION:str                       - Kill (default) or detach from the debuggee when",
+    "\n                                            quitting, ACTION is one of kill, detach",
     "\n  q, quit, exit                           - Exit the debugger",
     "\n  plugin ID:str [STATUS:bool]             - Show the status of a plugin or enable/disable it",
     "\n  plugins                                 - Get a list of all loaded plugins",
+    "\n  savesession FILE:str                    - Save settings, breakpoints, watchpoints and",
+    "\n                                            plugins into FILE",
+    "\n  loadsession FILE:str                    - Restore the session saved in FILE",
+    "\n  source FILE:str                         - Execute the commands in FILE, one per line",
+    "\n  assert exit [CODE:dec]                  - Fail if the debuggee has not exited (with CODE)",
+    "\n  assert signal SIG:sig                   - Fail if the debuggee did not stop or terminate",
+    "\n                                            because of SIG the last time",
+    "\n  assert break ADDR:num                   - Fail if the debuggee did not stop at the",
+    "\n                                            breakpoint at ADDR the last time",
     "\n  help, h, ?                              - Show this help",
     "\n\nAddresses and values should be in hexadecimal (with or without 0x prefix)",
     "\n\nInput Types:",
     "\n  FOO:num is a positive whole number in hexadecimal (optional 0x prefix)",
+    "\n  FOO:int is a whole number in hexadecimal that may be negative (-2, -0xd)",
+    "\n  FOO:addr is an address as FOO:num, a symbol with optional offset (main+0x10),",
+    "\n           a register or value with optional offset ($rsp+8*3, $1, $base+8) or a",
+    "\n           line of a source file (hello.c:12)",
     "\n  FOO:str is a string",
     "\n  FOO:bool either of 'true', 'false', '1', or '0'",
-    ));
+    "\n  FOO:sig is a signal name (SIGTERM, term) or number in decimal",
+    "\n  FOO:bytes is a sequence of bytes in hexadecimal, two digits per byte (b82a000000)",
+    );
+    if log {
+        info!("{help}");
+    } else {
+        println!("{help}");
+    }
 }
 
 fn path_to_cstring_or_empty(path: &Path) -> CString {
@@ -594,6 +2131,7 @@ mod test {
             history: BasicHistory::new(),
             stepper: 0,
             default_executable: None,
+            ..CliUi::build(None).unwrap()
         };
 
         assert_eq!(ui.get_number(1), Some(0x19));
@@ -605,4 +2143,144 @@ mod test {
         ui.buf_preparsed = vec!["cmd".to_string(), "ZZ".to_string()];
         assert_eq!(ui.get_number(1), None);
     }
+
+    #[test]
+    fn test_get_signed_number() {
+        let ui = CliUi {
+            buf: String::new(),
+            buf_preparsed: vec![
+                "cmd".to_string(),
+                "-2".to_string(),
+                "-0xd".to_string(),
+                "0x19".to_string(),
+                "-".to_string(),
+            ],
+            history: BasicHistory::new(),
+            stepper: 0,
+            default_executable: None,
+            ..CliUi::build(None).unwrap()
+        };
+
+        assert_eq!(ui.get_signed_number(1), Some(-2));
+        assert_eq!(ui.get_signed_number(2), Some(-0xd));
+        assert_eq!(ui.get_signed_number(3), Some(0x19));
+        assert_eq!(ui.get_signed_number(4), None);
+        assert_eq!(ui.get_signed_number(5), None); // Out of bounds
+    }
+
+    #[test]
+    fn test_get_signal() {
+        let ui = CliUi {
+            buf: String::new(),
+            buf_preparsed: vec![
+                "cmd".to_string(),
+                "SIGTERM".to_string(),
+                "usr1".to_string(),
+                "9".to_string(),
+                "NOTASIGNAL".to_string(),
+            ],
+            history: BasicHistory::new(),
+            stepper: 0,
+            default_executable: None,
+            ..CliUi::build(None).unwrap()
+        };
+
+        assert_eq!(ui.get_signal(1), Some(Signal::SIGTERM as i32));
+        assert_eq!(ui.get_signal(2), Some(Signal::SIGUSR1 as i32));
+        assert_eq!(ui.get_signal(3), Some(Signal::SIGKILL as i32));
+        assert_eq!(ui.get_signal(4), None);
+        assert_eq!(ui.get_signal(5), None); // Out of bounds
+    }
+
+    #[test]
+    fn test_get_bytes() {
+        let ui = CliUi {
+            buf: String::new(),
+            buf_preparsed: vec![
+                "cmd".to_string(),
+                "b82a000000".to_string(),
+                "0xCC90".to_string(),
+                "abc".to_string(),
+                "zz".to_string(),
+            ],
+            history: BasicHistory::new(),
+            stepper: 0,
+            default_executable: None,
+            ..CliUi::build(None).unwrap()
+        };
+
+        assert_eq!(ui.get_bytes(1), Some(vec![0xb8, 0x2a, 0, 0, 0]));
+        assert_eq!(ui.get_bytes(2), Some(vec![0xcc, 0x90]));
+        assert_eq!(ui.get_bytes(3), None); // odd number of digits
+        assert_eq!(ui.get_bytes(4), None);
+        assert_eq!(ui.get_bytes(5), None); // Out of bounds
+    }
+
+    #[test]
+    fn test_source() {
+        let path = std::env::temp_dir().join(format!("coreminer-test-{}.cm", std::process::id()));
+        std::fs::write(&path, "# a comment\nbp 401136\n\n  c  \nbt\n").unwrap();
+
+        let mut ui = CliUi::build(None).unwrap();
+        ui.script.push_back("regs get".to_string());
+        ui.source(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(ui.script, ["bp 401136", "c", "bt", "regs get"]);
+        assert!(ui.source(&path).is_err());
+    }
+
+    #[test]
+    fn test_assertions() {
+        let mut ui = CliUi::build(None).unwrap();
+        let assertion = |ui: &mut CliUi, args: &[&str]| {
+            ui.buf_preparsed = args.iter().map(ToString::to_string).collect();
+            ui.check_assertion()
+        };
+
+        ui.last_stop = Some(StopReason::Signal(Signal::SIGSEGV as i32));
+        assert_eq!(
+            assertion(&mut ui, &["assert", "signal", "segv"]),
+            Some(true)
+        );
+        assert_eq!(
+            assertion(&mut ui, &["assert", "signal", "abrt"]),
+            Some(false)
+        );
+        assert_eq!(assertion(&mut ui, &["assert", "exit"]), Some(false));
+        assert_eq!(assertion(&mut ui, &["assert", "break"]), None);
+        assert_eq!(ui.exit_code(), 0);
+
+        ui.last_stop = Some(StopReason::Breakpoint {
+            addr: Addr::from(0x0040_1136_usize),
+            hardware: true,
+        });
+        assert_eq!(
+            assertion(&mut ui, &["assert", "break", "401136"]),
+            Some(true)
+        );
+        assert_eq!(
+            assertion(&mut ui, &["assert", "break", "401137"]),
+            Some(false)
+        );
+
+        ui.last_stop = Some(StopReason::Exited(ExitStatus::Exited(3)));
+        assert_eq!(assertion(&mut ui, &["assert", "exit"]), Some(true));
+        assert_eq!(assertion(&mut ui, &["assert", "exit", "3"]), Some(true));
+        assert_eq!(assertion(&mut ui, &["assert", "exit", "0"]), Some(false));
+        assert_eq!(ui.exit_code(), 3);
+
+        ui.last_stop = Some(StopReason::Exited(ExitStatus::Signaled {
+            signal: Signal::SIGABRT as i32,
+            core_dumped: false,
+        }));
+        assert_eq!(
+            assertion(&mut ui, &["assert", "signal", "SIGABRT"]),
+            Some(true)
+        );
+        assert_eq!(ui.exit_code(), 128 + 6);
+
+        ui.failed = true;
+        assert_eq!(ui.exit_code(), 1);
+    }
 }
@@ -2,7 +2,7 @@
 //!
 //! Provides a JSON-based interface for interacting with the Coreminer debugger.
 //!
-//! This module implements the [`DebuggerUI`] trait to provide a programmatic
+//! This module implements the [`DebuggerUI`](super::DebuggerUI) trait to provide a programmatic
 //! JSON interface suitable for integration with other tools or remote debugging
 //! sessions. It communicates with clients by:
 //!
@@ -12,17 +12,49 @@
 //!
 //! This interface enables automation and integration with external tools
 //! that can communicate via JSON.
+//!
+//! ## Events
+//!
+//! Besides the feedback for each command, the debugger tells the client about things that
+//! happen on their own, like the debuggee stopping. These [Events](Event) are written as soon as
+//! they happen, as lines with an `event` field, so the feedback for a command that resumed the
+//! debuggee comes after the event of the stop:
+//!
+//! ```text
+//! {"event":{"Stopped":{"Breakpoint":{"addr":4198710,"hardware":false}}}}
+//! {"feedback":{"Stopped":{"Breakpoint":{"addr":4198710,"hardware":false}}}}
+//! ```
+//!
+//! Clients that are only interested in the feedback can skip lines without a `feedback` field.
+//!
+//! ## Chunked Responses
+//!
+//! Some feedback can get very large, like the disassembly of a whole function, the words of a
+//! big stack or a long list of symbols. If a chunk size is set with [`JsonUI::set_chunk_size`],
+//! the list of such a feedback (see [`Feedback::chunkable`]) is streamed as a sequence of chunk
+//! messages when it has more items than that, and the feedback follows as terminator with that
+//! list left empty:
+//!
+//! ```text
+//! {"chunk":{"path":"/Disassembly/vec","index":0,"items":[...]}}
+//! {"chunk":{"path":"/Disassembly/vec","index":1,"items":[...]}}
+//! {"feedback":{"Disassembly":{"vec":[]}},"chunked":{"path":"/Disassembly/vec","chunks":2,"items":700}}
+//! ```
+//!
+//! `path` is the JSON pointer of the list inside of the feedback. Clients can render the items
+//! as they arrive, or put them together into the list at `path` to get the full feedback. Other
+//! feedback is written as one line, as without a chunk size.
 
-use std::io::{BufRead, BufReader};
+use std::io::{BufRead, BufReader, Write};
 
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use tracing::{error, trace};
 
-use crate::errors::Result;
+use crate::errors::{DebuggerError, Result};
 use crate::feedback::Feedback;
 
-use super::{DebuggerUI, Status};
+use super::{CommandSource, Event, EventSink, Status};
 
 /// Input command structure for JSON interface
 ///
@@ -44,11 +76,11 @@ use super::{DebuggerUI, Status};
 /// });
 ///
 /// let input: Input = serde_json::from_value(json).unwrap();
-/// // Now input.status contains Status::SetBreakpoint(Addr::from(4194304usize))
+/// // Now input.status contains Status::SetBreakpoint(Addr::from(21958295usize).into())
 /// ```
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Input {
-    /// The [`Status`] of the [`DebuggerUI`]
+    /// The [`Status`] of the [`DebuggerUI`](super::DebuggerUI)
     ///
     /// In other words, that's what is being requested
     pub status: Status,
@@ -56,23 +88,42 @@ pub struct Input {
 
 /// JSON-based interface for the debugger
 ///
-/// Implements the [`DebuggerUI`] trait to provide a JSON-based interface
+/// Implements the [`DebuggerUI`](super::DebuggerUI) trait to provide a JSON-based interface
 /// for the debugger suitable for programmatic or remote use.
 ///
 /// # Examples
 ///
 /// ```no_run
 /// use coreminer::ui::json::JsonUI;
-/// use coreminer::ui::DebuggerUI;
+/// use coreminer::ui::{CommandSource, Event, EventSink};
 /// use coreminer::feedback::Feedback;
 ///
 /// // Create a JSON UI
 /// let mut ui = JsonUI::build().unwrap();
 ///
-/// // Process feedback from the debugger with user input
-/// let status = ui.process(Feedback::Ok).unwrap();
+/// // Write feedback from the debugger and read the next command
+/// ui.push_event(Event::Feedback(Feedback::Ok)).unwrap();
+/// let status = ui.next_status().unwrap();
 /// ```
-pub struct JsonUI;
+pub struct JsonUI {
+    chunk_size: Option<usize>,
+}
+
+/// A feedback line, which terminates a chunked response, see the [module](self) documentation
+#[derive(Serialize)]
+struct FeedbackMessage<'a> {
+    feedback: &'a Feedback,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    chunked: Option<Chunked>,
+}
+
+/// Summary of a chunked response
+#[derive(Serialize)]
+struct Chunked {
+    path: &'static str,
+    chunks: usize,
+    items: usize,
+}
 
 impl JsonUI {
     /// Creates a new JSON UI instance
@@ -94,7 +145,37 @@ impl JsonUI {
     ///
     /// Never fails.
     pub fn build() -> Result<Self> {
-        Ok(JsonUI)
+        Ok(JsonUI { chunk_size: None })
+    }
+
+    /// Sets the number of items per chunk for chunked responses
+    ///
+    /// With [`None`], which is the default, every feedback is written as one line. See the
+    /// [module](self) documentation for the format of chunked responses.
+    ///
+    /// # Parameters
+    ///
+    /// * `chunk_size` - Maximum number of list items in one message, must not be zero
+    ///
+    /// # Errors
+    ///
+    /// This function will fail if `chunk_size` is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use coreminer::ui::json::JsonUI;
+    ///
+    /// let mut ui = JsonUI::build().unwrap();
+    /// ui.set_chunk_size(Some(256)).unwrap();
+    /// assert!(ui.set_chunk_size(Some(0)).is_err());
+    /// ```
+    pub fn set_chunk_size(&mut self, chunk_size: Option<usize>) -> Result<()> {
+        if chunk_size == Some(0) {
+            return Err(DebuggerError::InvalidChunkSize);
+        }
+        self.chunk_size = chunk_size;
+        Ok(())
     }
 
     /// Formats feedback as a JSON value
@@ -125,21 +206,138 @@ impl JsonUI {
     pub fn format_feedback(feedback: &Feedback) -> Result<serde_json::Value> {
         Ok(json!({ "feedback": feedback }))
     }
+
+    /// Writes an [`Event`] as JSON lines
+    ///
+    /// [`Event::Feedback`] is written like with [`Self::write_feedback`], other events are
+    /// written as one line with an `event` field. See the [module](self) documentation for the
+    /// format.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if:
+    /// - JSON serialization fails
+    /// - Writing to `out` fails
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use coreminer::ui::json::JsonUI;
+    /// use coreminer::ui::Event;
+    /// use coreminer::feedback::StopReason;
+    ///
+    /// let ui = JsonUI::build().unwrap();
+    /// let mut out = Vec::new();
+    /// ui.write_event(&mut out, &Event::Stopped(StopReason::StepDone)).unwrap();
+    /// assert_eq!(String::from_utf8(out).unwrap(), "{\"event\":{\"Stopped\":\"StepDone\"}}\n");
+    /// ```
+    pub fn write_event(&self, mut out: impl Write, event: &Event) -> Result<()> {
+        if let Event::Feedback(feedback) = event {
+            return self.write_feedback(out, feedback);
+        }
+        writeln!(out, "{}", json!({ "event": event }))?;
+        Ok(out.flush()?)
+    }
+
+    /// Writes feedback as JSON lines, chunking its list if a chunk size is set
+    ///
+    /// See the [module](self) documentation for the format.
+    ///
+    /// # Parameters
+    ///
+    /// * `out` - Where to write the lines to
+    /// * `feedback` - The feedback to write
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if:
+    /// - JSON serialization fails
+    /// - Writing to `out` fails
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use coreminer::ui::json::JsonUI;
+    /// use coreminer::feedback::Feedback;
+    /// use coreminer::variable::VariableValue;
+    ///
+    /// let mut ui = JsonUI::build().unwrap();
+    /// ui.set_chunk_size(Some(2)).unwrap();
+    ///
+    /// let mut out = Vec::new();
+    /// let feedback = Feedback::Variable(VariableValue::Bytes(vec![1, 2, 3]));
+    /// ui.write_feedback(&mut out, &feedback).unwrap();
+    ///
+    /// let out = String::from_utf8(out).unwrap();
+    /// assert_eq!(out.lines().count(), 3);
+    /// assert!(out.starts_with(r#"{"chunk":{"path":"/Variable/Bytes","index":0,"items":[1,2]}}"#));
+    /// ```
+    pub fn write_feedback(&self, mut out: impl Write, feedback: &Feedback) -> Result<()> {
+        let chunked = self
+            .chunk_size
+            .zip(feedback.chunkable())
+            .filter(|(size, list)| list.items.len() > *size);
+        let Some((size, list)) = chunked else {
+            let message = FeedbackMessage {
+                feedback,
+                chunked: None,
+            };
+            serde_json::to_writer(&mut out, &message)?;
+            writeln!(out)?;
+            return Ok(out.flush()?);
+        };
+
+        // the items are written one by one, so the list is never serialized as a whole
+        let path = serde_json::to_string(list.path)?;
+        let len = list.items.len();
+        let mut chunks = 0;
+        for (index, start) in (0..len).step_by(size).enumerate() {
+            write!(
+                out,
+                r#"{{"chunk":{{"path":{path},"index":{index},"items":["#
+            )?;
+            for i in start..len.min(start + size) {
+                if i > start {
+                    write!(out, ",")?;
+                }
+                list.items.write_item(i, &mut out)?;
+            }
+            writeln!(out, "]}}}}")?;
+            out.flush()?;
+            chunks += 1;
+        }
+        let terminator = FeedbackMessage {
+            feedback: &list.rest,
+            chunked: Some(Chunked {
+                path: list.path,
+                chunks,
+                items: len,
+            }),
+        };
+        serde_json::to_writer(&mut out, &terminator)?;
+        writeln!(out)?;
+        Ok(out.flush()?)
+    }
+}
+
+impl EventSink for JsonUI {
+    fn push_event(&mut self, event: Event) -> Result<()> {
+        self.write_event(std::io::stdout().lock(), &event)
+    }
 }
 
-impl DebuggerUI for JsonUI {
-    fn process(&mut self, mut feedback: crate::feedback::Feedback) -> Result<super::Status> {
+impl CommandSource for JsonUI {
+    fn next_status(&mut self) -> Result<Status> {
         let mut reader = BufReader::new(std::io::stdin());
         let mut buf = Vec::new();
         loop {
-            println!("{}", Self::format_feedback(&feedback)?);
             buf.clear();
             reader.read_until(b'\n', &mut buf)?;
             let input: Input = match serde_json::from_slice(&buf) {
                 Ok(a) => a,
                 Err(e) => {
                     error!("{e}");
-                    feedback = Feedback::Error(e.into());
+                    self.write_feedback(std::io::stdout().lock(), &Feedback::Error(e.into()))?;
                     continue;
                 }
             };
@@ -148,3 +346,98 @@ impl DebuggerUI for JsonUI {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::disassemble::Disassembly;
+    use crate::stack::Stack;
+    use crate::variable::VariableValue;
+    use crate::Addr;
+
+    use serde_json::Value;
+
+    fn lines(ui: &JsonUI, feedback: &Feedback) -> Vec<Value> {
+        let mut out = Vec::new();
+        ui.write_feedback(&mut out, feedback).unwrap();
+        String::from_utf8(out)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_send_stdin_base64() {
+        let status = Status::SendStdin(b"hi\n".to_vec());
+        let json = serde_json::to_string(&status).unwrap();
+        assert_eq!(json, r#"{"SendStdin":"aGkK"}"#);
+        assert_eq!(serde_json::from_str::<Status>(&json).unwrap(), status);
+        assert!(serde_json::from_str::<Status>(r#"{"SendStdin":"not base64!"}"#).is_err());
+    }
+
+    #[test]
+    fn test_write_feedback_chunked() {
+        let mut ui = JsonUI::build().unwrap();
+        let bytes = Feedback::Variable(VariableValue::Bytes((0..7).collect()));
+        assert_eq!(
+            lines(&ui, &bytes),
+            vec![JsonUI::format_feedback(&bytes).unwrap()]
+        );
+
+        ui.set_chunk_size(Some(3)).unwrap();
+        let msgs = lines(&ui, &bytes);
+        assert_eq!(msgs.len(), 4);
+        assert_eq!(msgs[0]["chunk"]["path"], "/Variable/Bytes");
+        assert_eq!(msgs[1]["chunk"]["index"], 1);
+        assert_eq!(msgs[2]["chunk"]["items"], json!([6]));
+        assert_eq!(msgs[3]["feedback"]["Variable"]["Bytes"], json!([]));
+        assert_eq!(msgs[3]["chunked"]["chunks"], 3);
+        assert_eq!(msgs[3]["chunked"]["items"], 7);
+
+        // short lists and feedback without lists are not chunked
+        let short = Feedback::Variable(VariableValue::Bytes(vec![1, 2, 3]));
+        assert_eq!(
+            lines(&ui, &short)[0]["feedback"]["Variable"]["Bytes"],
+            json!([1, 2, 3])
+        );
+        assert_eq!(lines(&ui, &Feedback::Ok), vec![json!({ "feedback": "Ok" })]);
+    }
+
+    #[test]
+    fn test_chunkable_lists() {
+        let mut stack = Stack::new(Addr::from(0x7ffe_0000usize));
+        stack.push(1);
+        stack.push(2);
+        let code = [0x48, 0x83, 0xec, 0x08, 0xc3];
+        let feedbacks = [
+            Feedback::Variable(VariableValue::Bytes(vec![1, 2, 3])),
+            Feedback::Stack(stack),
+            Feedback::Disassembly(
+                Disassembly::disassemble(&code, Addr::from(0x1000usize), &[4]).unwrap(),
+            ),
+        ];
+
+        for feedback in &feedbacks {
+            let list = feedback.chunkable().unwrap();
+            let full = serde_json::to_value(feedback).unwrap();
+            let items = full.pointer(list.path).and_then(Value::as_array).unwrap();
+            assert_eq!(items.len(), list.items.len());
+            for (i, item) in items.iter().enumerate() {
+                let mut out = Vec::new();
+                list.items.write_item(i, &mut out).unwrap();
+                assert_eq!(&serde_json::from_slice::<Value>(&out).unwrap(), item);
+            }
+
+            // putting the items back into the rest gives the full feedback
+            let mut rest = serde_json::to_value(&list.rest).unwrap();
+            let empty = rest
+                .pointer_mut(list.path)
+                .and_then(Value::as_array_mut)
+                .unwrap();
+            assert!(empty.is_empty());
+            empty.clone_from(items);
+            assert_eq!(rest, full);
+        }
+    }
+}
@@ -0,0 +1,892 @@
+//! # GDB/MI Interface
+//!
+//! Provides a subset of the GDB Machine Interface (MI) for interacting with the Coreminer
+//! debugger.
+//!
+//! Many editor and IDE integrations, like the one of Emacs, already speak MI to GDB. This module
+//! implements the [`DebuggerUI`](super::DebuggerUI) traits with MI records, so that those
+//! frontends can drive coreminer without new glue. Commands are read from stdin, records are
+//! written to stdout:
+//!
+//! ```text
+//! (gdb)
+//! 1-file-exec-and-symbols /bin/true
+//! 1^done
+//! (gdb)
+//! 2-break-insert main
+//! 2^done,bkpt={number="1",type="breakpoint",disp="keep",enabled="y",addr="<PENDING>",pending="main",original-location="main"}
+//! (gdb)
+//! 3-exec-run
+//! 3^running
+//! *running,thread-id="all"
+//! =breakpoint-modified,bkpt={number="1",type="breakpoint",disp="keep",enabled="y",addr="0x401126",original-location="main"}
+//! *stopped,reason="breakpoint-hit",disp="keep",bkptno="1",frame={addr="0x401126"},thread-id="1",stopped-threads="all"
+//! (gdb)
+//! ```
+//!
+//! Commands that resume the debuggee answer with `^running` right away, the stop follows as a
+//! `*stopped` record. Breakpoints are numbered like in GDB, the numbers are only known to this
+//! interface, the debugger itself knows breakpoints by their address. Breakpoints that are
+//! inserted before the debuggee runs are pending, and are set by `-exec-run` before it continues
+//! the debuggee.
+//!
+//! ## Supported Commands
+//!
+//! - `-file-exec-and-symbols FILE`, `-exec-arguments ARGS...`
+//! - `-exec-run`, `-exec-continue`, `-exec-next`, `-exec-step`, `-exec-finish`,
+//!   `-exec-next-instruction`, `-exec-step-instruction`
+//! - `-break-insert [-h] LOCATION`, `-break-delete NUMBER...`
+//! - `-stack-list-frames`, `-data-evaluate-expression EXPR`,
+//!   `-data-read-memory-bytes ADDR COUNT`
+//! - `-target-attach PID`, `-target-detach`, `-gdb-exit`
+//! - `-gdb-set`, `-gdb-show`, `-gdb-version`, `-list-features`, `-enable-pretty-printing`,
+//!   `-inferior-tty-set` and `-environment-cd`, which frontends send on startup and are
+//!   accepted without effect
+//!
+//! Other commands are answered with an `^error` record.
+
+use std::collections::{BTreeMap, VecDeque};
+use std::ffi::CString;
+use std::fmt::Write as _;
+use std::io::{BufRead, BufReader, Write};
+use std::path::PathBuf;
+
+use nix::sys::signal::Signal;
+use tracing::trace;
+
+use super::{CommandSource, Event, EventSink, Status};
+use crate::addrexpr::AddrExpr;
+use crate::errors::{DebuggerError, Result};
+use crate::feedback::{ExitStatus, Feedback, StopReason};
+use crate::Addr;
+
+/// The prompt that tells the frontend that the next command can be sent
+const PROMPT: &str = "(gdb) ";
+
+/// What the frontend asked for with the command that is being processed
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Operation {
+    /// `-exec-run`, which launches the debuggee and then continues it
+    Run,
+    /// `-exec-run`, while the location of a pending breakpoint is resolved
+    PendingResolve(VecDeque<u32>),
+    /// `-exec-run`, while a pending breakpoint is set
+    PendingSet(VecDeque<u32>),
+    /// A command that resumes the debuggee and was already answered with `^running`
+    Exec,
+    /// `-break-insert`, while the location is resolved
+    BreakResolve(MiBreakpoint),
+    /// `-break-insert`, while the breakpoint at the resolved address is set
+    BreakSet(MiBreakpoint),
+    /// `-break-delete`, with the breakpoint numbers that are left
+    BreakDelete(VecDeque<u32>),
+    /// `-stack-list-frames`
+    Frames,
+    /// `-data-evaluate-expression`
+    Evaluate,
+    /// `-data-read-memory-bytes`
+    ReadMemory,
+    /// Any other command that gets a plain `^done`
+    Done,
+}
+
+/// A breakpoint as the frontend knows it
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct MiBreakpoint {
+    expr: AddrExpr,
+    location: String,
+    hardware: bool,
+    /// The address of the breakpoint, or [`None`] while it is pending
+    addr: Option<Addr>,
+}
+
+impl MiBreakpoint {
+    /// Formats the breakpoint as the `bkpt` tuple of a record
+    fn record(&self, number: u32) -> String {
+        let (addr, pending) = match self.addr {
+            Some(addr) => (addr.to_string(), String::new()),
+            None => (
+                "<PENDING>".to_string(),
+                format!(",pending={}", c_string(&self.location)),
+            ),
+        };
+        format!(
+            "bkpt={{number=\"{number}\",type=\"{}\",disp=\"keep\",enabled=\"y\",\
+             addr=\"{addr}\"{pending},original-location={}}}",
+            if self.hardware {
+                "hw breakpoint"
+            } else {
+                "breakpoint"
+            },
+            c_string(&self.location)
+        )
+    }
+
+    /// Gets the [`Status`] that sets the breakpoint at `addr`
+    fn set_status(&self, addr: Addr) -> Status {
+        if self.hardware {
+            Status::SetHardwareBreakpoint(addr.into())
+        } else {
+            Status::SetBreakpoint(addr.into())
+        }
+    }
+}
+
+/// The command that is being processed
+#[derive(Debug)]
+struct Current {
+    token: String,
+    operation: Operation,
+    /// The status to hand out next for this command, if it needs more than one
+    next: Option<Status>,
+}
+
+/// GDB/MI interface for the debugger
+///
+/// See the [module](self) documentation for the protocol.
+///
+/// # Examples
+///
+/// ```no_run
+/// use coreminer::debugger::Debugger;
+/// use coreminer::ui::mi::MiUI;
+///
+/// let mut debugger = Debugger::build(MiUI::build().unwrap()).unwrap();
+/// debugger.run_debugger().unwrap();
+/// debugger.cleanup().unwrap();
+/// ```
+pub struct MiUI {
+    input: Box<dyn BufRead>,
+    output: Box<dyn Write>,
+    executable: Option<PathBuf>,
+    arguments: Vec<CString>,
+    /// Breakpoints by the numbers the frontend knows them as
+    breakpoints: BTreeMap<u32, MiBreakpoint>,
+    next_breakpoint: u32,
+    current: Option<Current>,
+}
+
+impl MiUI {
+    /// Creates an MI interface on stdin and stdout
+    ///
+    /// # Errors
+    ///
+    /// Never fails.
+    pub fn build() -> Result<Self> {
+        Ok(Self::with_io(
+            BufReader::new(std::io::stdin()),
+            std::io::stdout(),
+        ))
+    }
+
+    /// Creates an MI interface that reads commands from `input` and writes records to `output`
+    #[must_use]
+    pub fn with_io(input: impl BufRead + 'static, output: impl Write + 'static) -> Self {
+        Self {
+            input: Box::new(input),
+            output: Box::new(output),
+            executable: None,
+            arguments: Vec::new(),
+            breakpoints: BTreeMap::new(),
+            next_breakpoint: 1,
+            current: None,
+        }
+    }
+
+    /// Writes a line to the frontend
+    fn write_line(&mut self, line: &str) -> Result<()> {
+        trace!("mi output: {line}");
+        writeln!(self.output, "{line}")?;
+        Ok(self.output.flush()?)
+    }
+
+    /// Writes a result record and the prompt for the next command
+    fn finish(&mut self, token: &str, result: &str) -> Result<()> {
+        self.write_line(&format!("{token}{result}"))?;
+        self.write_line(PROMPT)
+    }
+
+    /// Handles a command line from the frontend
+    ///
+    /// # Returns
+    ///
+    /// The [`Status`] to process for it, or [`None`] if it was answered without the debugger.
+    #[allow(clippy::too_many_lines)] // one arm per command
+    fn handle_line(&mut self, line: &str) -> Result<Option<Status>> {
+        let (token, command, args) = match parse_command(line) {
+            Ok(parsed) => parsed,
+            Err(msg) => {
+                self.finish("", &error_result(&msg))?;
+                return Ok(None);
+            }
+        };
+
+        let arg = |index: usize| args.get(index).cloned();
+        let mut operation = Operation::Done;
+        let status = match command.as_str() {
+            "file-exec-and-symbols" => {
+                self.executable = arg(0).map(PathBuf::from);
+                None
+            }
+            "exec-arguments" => {
+                self.arguments = args
+                    .iter()
+                    .map(|a| CString::new(a.as_str()))
+                    .collect::<std::result::Result<_, _>>()?;
+                None
+            }
+            "gdb-set" | "enable-pretty-printing" | "inferior-tty-set" | "environment-cd" => None,
+            "gdb-show" => {
+                self.finish(&token, "^done,value=\"\"")?;
+                return Ok(None);
+            }
+            "list-features" => {
+                self.finish(&token, "^done,features=[]")?;
+                return Ok(None);
+            }
+            "gdb-version" => {
+                let version = format!("coreminer {}\n", env!("CARGO_PKG_VERSION"));
+                self.write_line(&format!("~{}", c_string(&version)))?;
+                None
+            }
+            "gdb-exit" => {
+                self.write_line(&format!("{token}^exit"))?;
+                return Ok(Some(Status::DebuggerQuit));
+            }
+            "exec-run" => {
+                let Some(executable) = &self.executable else {
+                    self.finish(&token, &error_result("No executable file specified."))?;
+                    return Ok(None);
+                };
+                operation = Operation::Run;
+                Some(Status::Run(executable.clone(), self.arguments.clone()))
+            }
+            "exec-continue" if args.iter().any(|a| a == "--reverse") => {
+                Some(Status::ReverseContinue)
+            }
+            "exec-next" | "exec-step" if args.iter().any(|a| a == "--reverse") => {
+                Some(Status::ReverseStep)
+            }
+            "exec-continue" => Some(Status::Continue),
+            "exec-next" => Some(Status::NextLine),
+            "exec-step" => Some(Status::StepLine),
+            "exec-finish" => Some(Status::StepOut),
+            "exec-next-instruction" | "exec-step-instruction" => Some(Status::StepSingle),
+            "break-insert" => {
+                let hardware = args.iter().any(|a| a == "-h");
+                let Some(location) = args.iter().find(|a| !a.starts_with('-')).cloned() else {
+                    self.finish(&token, &error_result("-break-insert: Missing <location>"))?;
+                    return Ok(None);
+                };
+                // GDB writes addresses as *ADDR
+                let expr = match location.trim_start_matches('*').parse::<AddrExpr>() {
+                    Ok(expr) => expr,
+                    Err(e) => {
+                        self.finish(&token, &error_result(&e.to_string()))?;
+                        return Ok(None);
+                    }
+                };
+                operation = Operation::BreakResolve(MiBreakpoint {
+                    expr: expr.clone(),
+                    location,
+                    hardware,
+                    addr: None,
+                });
+                Some(Status::ResolveAddr(expr))
+            }
+            "break-delete" => {
+                let mut numbers = VecDeque::new();
+                for arg in &args {
+                    match arg.parse::<u32>() {
+                        Ok(n) if self.breakpoints.contains_key(&n) => numbers.push_back(n),
+                        _ => {
+                            let msg = format!("Bad breakpoint number '{arg}'");
+                            self.finish(&token, &error_result(&msg))?;
+                            return Ok(None);
+                        }
+                    }
+                }
+                let Some(status) = self.next_delete(&mut numbers) else {
+                    self.finish(&token, "^done")?;
+                    return Ok(None);
+                };
+                operation = Operation::BreakDelete(numbers);
+                Some(status)
+            }
+            "stack-list-frames" => {
+                operation = Operation::Frames;
+                Some(Status::Backtrace)
+            }
+            "data-evaluate-expression" => {
+                operation = Operation::Evaluate;
+                Some(Status::ReadVariable(args.join(" ")))
+            }
+            "data-read-memory-bytes" => {
+                let (Some(Ok(addr)), Some(Ok(count))) = (
+                    arg(0).map(|a| a.parse::<AddrExpr>()),
+                    arg(1).map(|c| parse_count(&c)),
+                ) else {
+                    let msg = "Usage: -data-read-memory-bytes ADDR COUNT";
+                    self.finish(&token, &error_result(msg))?;
+                    return Ok(None);
+                };
+                operation = Operation::ReadMemory;
+                Some(Status::ReadMemRange(addr, count))
+            }
+            "target-attach" => {
+                let Some(pid) = arg(0).and_then(|pid| pid.parse().ok()) else {
+                    self.finish(&token, &error_result("-target-attach: Missing <pid>"))?;
+                    return Ok(None);
+                };
+                Some(Status::Attach(pid))
+            }
+            "target-detach" => Some(Status::Detach),
+            other => {
+                let msg = format!("Undefined MI command: {other}");
+                self.finish(&token, &error_result(&msg))?;
+                return Ok(None);
+            }
+        };
+
+        let Some(status) = status else {
+            self.finish(&token, "^done")?;
+            return Ok(None);
+        };
+        if resumes(&status) || operation == Operation::Run {
+            self.write_line(&format!("{token}^running"))?;
+            self.write_line("*running,thread-id=\"all\"")?;
+            if operation != Operation::Run {
+                operation = Operation::Exec;
+            }
+        }
+        self.current = Some(Current {
+            token,
+            operation,
+            next: None,
+        });
+        Ok(Some(status))
+    }
+
+    /// Removes the breakpoints in `numbers` up to the first that is set in the debuggee
+    ///
+    /// # Returns
+    ///
+    /// The [`Status`] that deletes that breakpoint from the debuggee, which stays first in
+    /// `numbers`, or [`None`] if all breakpoints were removed.
+    fn next_delete(&mut self, numbers: &mut VecDeque<u32>) -> Option<Status> {
+        while let Some(number) = numbers.front() {
+            if let Some(addr) = self.breakpoints.get(number).and_then(|b| b.addr) {
+                return Some(Status::DelBreakpoint(addr.into()));
+            }
+            self.breakpoints.remove(number);
+            numbers.pop_front();
+        }
+        None
+    }
+
+    /// Queues the [`Status`] for the next pending breakpoint of `-exec-run` in `current`
+    ///
+    /// When all pending breakpoints are set, the debuggee is continued.
+    fn next_pending(&mut self, mut current: Current, pending: VecDeque<u32>) {
+        if let Some(breakpoint) = pending.front().and_then(|n| self.breakpoints.get(n)) {
+            current.next = Some(Status::ResolveAddr(breakpoint.expr.clone()));
+            current.operation = Operation::PendingResolve(pending);
+        } else {
+            current.next = Some(Status::Continue);
+            current.operation = Operation::Exec;
+        }
+        self.current = Some(current);
+    }
+
+    /// Handles the [`Feedback`] while `-exec-run` sets the pending breakpoints
+    ///
+    /// Breakpoints that cannot be set are reported, but do not stop `-exec-run`.
+    fn handle_pending(&mut self, mut current: Current, feedback: &Feedback) -> Result<()> {
+        let (Operation::PendingResolve(pending) | Operation::PendingSet(pending)) =
+            &mut current.operation
+        else {
+            unreachable!("only called while setting pending breakpoints")
+        };
+        let mut pending = std::mem::take(pending);
+
+        if let (Operation::PendingResolve(_), Feedback::Addr(addr)) = (&current.operation, feedback)
+        {
+            if let Some(breakpoint) = self.breakpoints.get_mut(&pending[0]) {
+                breakpoint.addr = Some(*addr);
+                current.next = Some(breakpoint.set_status(*addr));
+            }
+            current.operation = Operation::PendingSet(pending);
+            self.current = Some(current);
+            return Ok(());
+        }
+
+        let number = pending.pop_front().unwrap_or_default();
+        if let Feedback::Error(e) = feedback {
+            if let Some(breakpoint) = self.breakpoints.get_mut(&number) {
+                breakpoint.addr = None;
+            }
+            let msg = format!("Could not set breakpoint {number}: {e}\n");
+            self.write_line(&format!("&{}", c_string(&msg)))?;
+        } else if let Some(breakpoint) = self.breakpoints.get(&number) {
+            let record = breakpoint.record(number);
+            self.write_line(&format!("=breakpoint-modified,{record}"))?;
+        }
+        self.next_pending(current, pending);
+        Ok(())
+    }
+
+    /// Handles the [`Feedback`] for the [`Status`] of the current command
+    #[allow(clippy::too_many_lines)] // one arm per operation
+    fn handle_feedback(&mut self, feedback: &Feedback) -> Result<()> {
+        let Some(mut current) = self.current.take() else {
+            // the first feedback comes before any command was read
+            return self.write_line(PROMPT);
+        };
+        let token = current.token.clone();
+        // MI has no value history
+        let feedback = match feedback {
+            Feedback::Recorded { feedback, .. } => feedback.as_ref(),
+            other => other,
+        };
+
+        if matches!(
+            current.operation,
+            Operation::PendingResolve(_) | Operation::PendingSet(_)
+        ) {
+            return self.handle_pending(current, feedback);
+        }
+        match (&mut current.operation, feedback) {
+            (Operation::BreakResolve(breakpoint), Feedback::Error(DebuggerError::NoDebugee)) => {
+                // GDB keeps breakpoints before the debuggee runs as pending
+                let number = self.next_breakpoint;
+                self.next_breakpoint += 1;
+                let record = breakpoint.record(number);
+                self.breakpoints.insert(number, breakpoint.clone());
+                return self.finish(&token, &format!("^done,{record}"));
+            }
+            (_, Feedback::Error(e)) => {
+                return self.finish(&token, &error_result(&e.to_string()));
+            }
+            _ => (),
+        }
+
+        let result = match (&mut current.operation, feedback) {
+            (Operation::Run, _) => {
+                let pending = self
+                    .breakpoints
+                    .iter()
+                    .filter(|(_, b)| b.addr.is_none())
+                    .map(|(n, _)| *n)
+                    .collect();
+                self.next_pending(current, pending);
+                return Ok(());
+            }
+            (Operation::PendingResolve(_) | Operation::PendingSet(_), _) => {
+                unreachable!("pending breakpoints are handled above")
+            }
+            // the stop was already written as *stopped
+            (Operation::Exec, _) => return self.write_line(PROMPT),
+            (Operation::BreakResolve(breakpoint), Feedback::Addr(addr)) => {
+                breakpoint.addr = Some(*addr);
+                current.next = Some(breakpoint.set_status(*addr));
+                current.operation = Operation::BreakSet(breakpoint.clone());
+                self.current = Some(current);
+                return Ok(());
+            }
+            (Operation::BreakSet(breakpoint), _) => {
+                let number = self.next_breakpoint;
+                self.next_breakpoint += 1;
+                let record = breakpoint.record(number);
+                self.breakpoints.insert(number, breakpoint.clone());
+                format!("^done,{record}")
+            }
+            (Operation::BreakDelete(numbers), _) => {
+                if let Some(number) = numbers.pop_front() {
+                    self.breakpoints.remove(&number);
+                }
+                if let Some(next) = self.next_delete(numbers) {
+                    current.next = Some(next);
+                    self.current = Some(current);
+                    return Ok(());
+                }
+                "^done".to_string()
+            }
+            (Operation::Frames, Feedback::Backtrace(backtrace)) => {
+                let frames: Vec<String> = backtrace
+                    .frames
+                    .iter()
+                    .enumerate()
+                    .map(|(level, frame)| {
+                        let mut record =
+                            format!("frame={{level=\"{level}\",addr=\"{}\"", frame.addr);
+                        if let Some(name) = &frame.name {
+                            let _ = write!(record, ",func={}", c_string(name));
+                        }
+                        record.push('}');
+                        record
+                    })
+                    .collect();
+                format!("^done,stack=[{}]", frames.join(","))
+            }
+            (Operation::Evaluate, Feedback::Variable(value)) => {
+                format!("^done,value={}", c_string(&value.to_string()))
+            }
+            (Operation::ReadMemory, Feedback::Memory(read)) => {
+                let contents = read.bytes.iter().fold(String::new(), |mut hex, b| {
+                    let _ = write!(hex, "{b:02x}");
+                    hex
+                });
+                format!(
+                    "^done,memory=[{{begin=\"{}\",offset=\"0x0\",end=\"{}\",contents=\"{contents}\"}}]",
+                    read.addr,
+                    read.addr + read.bytes.len()
+                )
+            }
+            // anything else is shown as console output
+            (_, other) => {
+                if !matches!(other, Feedback::Ok) {
+                    self.write_line(&format!("~{}", c_string(&format!("{other}\n"))))?;
+                }
+                "^done".to_string()
+            }
+        };
+        self.finish(&token, &result)
+    }
+
+    /// Formats the `*stopped` record for a stop of the debuggee
+    fn stopped_record(&self, reason: &StopReason) -> String {
+        let mut record = "*stopped,".to_string();
+        match reason {
+            StopReason::Breakpoint { addr, .. } => {
+                record.push_str("reason=\"breakpoint-hit\",disp=\"keep\",");
+                if let Some((number, _)) =
+                    self.breakpoints.iter().find(|(_, b)| b.addr == Some(*addr))
+                {
+                    let _ = write!(record, "bkptno=\"{number}\",");
+                }
+                let _ = write!(record, "frame={{addr=\"{addr}\"}},");
+            }
+            StopReason::Watchpoint { addr } => {
+                let _ = write!(
+                    record,
+                    "reason=\"watchpoint-trigger\",wpt={{exp=\"{addr}\"}},"
+                );
+            }
+            StopReason::StepDone => record.push_str("reason=\"end-stepping-range\","),
+            StopReason::Signal(signal) => {
+                let _ = write!(
+                    record,
+                    "reason=\"signal-received\",signal-name=\"{}\",",
+                    signal_name(*signal)
+                );
+            }
+            StopReason::Interrupted => {
+                record.push_str("reason=\"signal-received\",signal-name=\"SIGINT\",");
+            }
+            StopReason::Syscall(stop) => {
+                let kind = if stop.ret.is_none() {
+                    "syscall-entry"
+                } else {
+                    "syscall-return"
+                };
+                let _ = write!(
+                    record,
+                    "reason=\"{kind}\",syscall-number=\"{}\",",
+                    stop.number
+                );
+            }
+            StopReason::PtraceEvent(event) => {
+                let _ = write!(record, "reason=\"ptrace-event\",event=\"{event}\",");
+            }
+            StopReason::Panic { addr } => {
+                let _ = write!(record, "reason=\"panic\",frame={{addr=\"{addr}\"}},");
+            }
+            StopReason::Exited(ExitStatus::Exited(0)) => {
+                return "*stopped,reason=\"exited-normally\"".to_string();
+            }
+            StopReason::Exited(ExitStatus::Exited(code)) => {
+                return format!("*stopped,reason=\"exited\",exit-code=\"{code:02o}\"");
+            }
+            StopReason::Exited(ExitStatus::Signaled { signal, .. }) => {
+                return format!(
+                    "*stopped,reason=\"exited-signalled\",signal-name=\"{}\"",
+                    signal_name(*signal)
+                );
+            }
+        }
+        record.push_str("thread-id=\"1\",stopped-threads=\"all\"");
+        record
+    }
+}
+
+impl CommandSource for MiUI {
+    fn next_status(&mut self) -> Result<Status> {
+        if let Some(next) = self.current.as_mut().and_then(|c| c.next.take()) {
+            return Ok(next);
+        }
+
+        let mut line = String::new();
+        loop {
+            line.clear();
+            if self.input.read_line(&mut line)? == 0 {
+                // the frontend is gone
+                return Ok(Status::DebuggerQuit);
+            }
+            trace!("mi input: {}", line.trim_end());
+            if line.trim().is_empty() {
+                self.write_line(PROMPT)?;
+                continue;
+            }
+            if let Some(status) = self.handle_line(line.trim())? {
+                return Ok(status);
+            }
+        }
+    }
+}
+
+impl EventSink for MiUI {
+    fn push_event(&mut self, event: Event) -> Result<()> {
+        match event {
+            Event::Feedback(feedback) => self.handle_feedback(&feedback),
+            Event::Stopped(reason) => {
+                let record = self.stopped_record(&reason);
+                self.write_line(&record)
+            }
+            Event::Notification(text) => {
+                self.write_line(&format!("~{}", c_string(&format!("{text}\n"))))
+            }
+        }
+    }
+}
+
+/// Checks if processing `status` resumes the debuggee
+fn resumes(status: &Status) -> bool {
+    matches!(
+        status,
+        Status::Continue
+            | Status::NextLine
+            | Status::StepLine
+            | Status::StepOver
+            | Status::StepInto
+            | Status::StepOut
+            | Status::StepSingle
+            | Status::ReverseStep
+            | Status::ReverseContinue
+    )
+}
+
+/// Formats an `^error` result with the message `msg`
+fn error_result(msg: &str) -> String {
+    format!("^error,msg={}", c_string(msg))
+}
+
+/// Gets the name of a signal, like `SIGSEGV`
+fn signal_name(signal: i32) -> String {
+    Signal::try_from(signal).map_or_else(|_| signal.to_string(), |s| s.as_str().to_string())
+}
+
+/// Parses a count, which is decimal unless it starts with `0x`
+fn parse_count(raw: &str) -> std::result::Result<usize, std::num::ParseIntError> {
+    match raw.strip_prefix("0x") {
+        Some(hex) => usize::from_str_radix(hex, 16),
+        None => raw.parse(),
+    }
+}
+
+/// Quotes `text` as an MI c-string
+///
+/// # Examples
+///
+/// ```
+/// use coreminer::ui::mi::c_string;
+///
+/// assert_eq!(c_string("say \"hi\"\n"), r#""say \"hi\"\n""#);
+/// ```
+#[must_use]
+pub fn c_string(text: &str) -> String {
+    let mut quoted = String::with_capacity(text.len() + 2);
+    quoted.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if c.is_control() => {
+                let _ = write!(quoted, "\\{:03o}", u32::from(c));
+            }
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Splits an MI command line into its token, the command without `-` and the arguments
+///
+/// Arguments are separated by whitespace, and can be quoted as c-strings.
+///
+/// # Errors
+///
+/// Fails with a message for the frontend if the line is not an MI command or has an unterminated
+/// quote.
+fn parse_command(line: &str) -> std::result::Result<(String, String, Vec<String>), String> {
+    let digits = line.len() - line.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    let (token, rest) = line.split_at(digits);
+    let Some(rest) = rest.strip_prefix('-') else {
+        return Err(format!("Undefined command: \"{rest}\""));
+    };
+
+    let mut words = Vec::new();
+    let mut chars = rest.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+            continue;
+        }
+        let mut word = String::new();
+        if c == '"' {
+            chars.next();
+            loop {
+                match chars.next() {
+                    Some('"') => break,
+                    Some('\\') => match chars.next() {
+                        Some('n') => word.push('\n'),
+                        Some('t') => word.push('\t'),
+                        Some(escaped) => word.push(escaped),
+                        None => return Err("Unterminated quote".to_string()),
+                    },
+                    Some(c) => word.push(c),
+                    None => return Err("Unterminated quote".to_string()),
+                }
+            }
+        } else {
+            while let Some(&c) = chars.peek() {
+                if c.is_whitespace() {
+                    break;
+                }
+                word.push(c);
+                chars.next();
+            }
+        }
+        words.push(word);
+    }
+
+    if words.is_empty() {
+        return Err("Missing MI command".to_string());
+    }
+    let command = words.remove(0);
+    Ok((token.to_string(), command, words))
+}
+
+#[cfg(test)]
+mod test {
+    use std::cell::RefCell;
+    use std::io::Cursor;
+    use std::rc::Rc;
+
+    use super::*;
+    use crate::debugger::Debugger;
+
+    /// Output that can be read after the UI was moved into the debugger
+    #[derive(Clone, Default)]
+    struct SharedOutput(Rc<RefCell<Vec<u8>>>);
+
+    impl Write for SharedOutput {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.borrow_mut().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_parse_command() {
+        assert_eq!(
+            parse_command("12-break-insert -h *0x401136"),
+            Ok((
+                "12".to_string(),
+                "break-insert".to_string(),
+                vec!["-h".to_string(), "*0x401136".to_string()]
+            ))
+        );
+        assert_eq!(
+            parse_command(r#"-exec-arguments "a b" "q\"uote""#)
+                .unwrap()
+                .2,
+            vec!["a b".to_string(), "q\"uote".to_string()]
+        );
+        assert!(parse_command("break main").is_err());
+        assert!(parse_command("-exec-arguments \"open").is_err());
+    }
+
+    #[test]
+    fn test_stopped_record() {
+        let mut ui = MiUI::with_io(Cursor::new(""), std::io::sink());
+        let addr = Addr::from(0x0040_1106_usize);
+        ui.breakpoints.insert(
+            3,
+            MiBreakpoint {
+                expr: AddrExpr::Absolute(addr),
+                location: "main".to_string(),
+                hardware: false,
+                addr: Some(addr),
+            },
+        );
+        assert_eq!(
+            ui.stopped_record(&StopReason::Breakpoint {
+                addr,
+                hardware: false
+            }),
+            format!(
+                "*stopped,reason=\"breakpoint-hit\",disp=\"keep\",bkptno=\"3\",\
+                 frame={{addr=\"{addr}\"}},thread-id=\"1\",stopped-threads=\"all\""
+            )
+        );
+        assert_eq!(
+            ui.stopped_record(&StopReason::Signal(11)),
+            "*stopped,reason=\"signal-received\",signal-name=\"SIGSEGV\",\
+             thread-id=\"1\",stopped-threads=\"all\""
+        );
+        assert_eq!(
+            ui.stopped_record(&StopReason::Exited(ExitStatus::Exited(9))),
+            "*stopped,reason=\"exited\",exit-code=\"11\""
+        );
+    }
+
+    #[test]
+    fn test_session() {
+        let input = "1-gdb-set mi-async on\n\
+                     2-file-exec-and-symbols /bin/true\n\
+                     3-break-delete 7\n\
+                     4-break-insert no_such_function\n\
+                     5-exec-run\n\
+                     6-gdb-exit\n";
+        let output = SharedOutput::default();
+        let ui = MiUI::with_io(Cursor::new(input), output.clone());
+        let mut debugger = Debugger::build(ui).unwrap();
+        debugger.run_debugger().unwrap();
+        debugger.cleanup().unwrap();
+
+        let output = String::from_utf8(output.0.borrow().clone()).unwrap();
+        let records: Vec<&str> = output.lines().filter(|l| *l != PROMPT).collect();
+        assert_eq!(
+            records,
+            vec![
+                "1^done",
+                "2^done",
+                "3^error,msg=\"Bad breakpoint number '7'\"",
+                "4^done,bkpt={number=\"1\",type=\"breakpoint\",disp=\"keep\",enabled=\"y\",\
+                 addr=\"<PENDING>\",pending=\"no_such_function\",original-location=\"no_such_function\"}",
+                "5^running",
+                "*running,thread-id=\"all\"",
+                "&\"Could not set breakpoint 1: No function or symbol named no_such_function\\n\"",
+                "*stopped,reason=\"exited-normally\"",
+                "6^exit",
+            ]
+        );
+    }
+}
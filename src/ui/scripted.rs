@@ -0,0 +1,308 @@
+//! # Scripted User Interface
+//!
+//! Provides a [`DebuggerUI`](super::DebuggerUI) that runs a predefined script of
+//! [Statuses](Status) instead of asking a user.
+//!
+//! [`ScriptedUI`] is for driving whole debug sessions from code, like in automation or tests. Each
+//! step of the script is a [`Status`], optionally with a check of the [`Feedback`] it produces. A
+//! failed check stops [`Debugger::run_debugger`](crate::debugger::Debugger::run_debugger) with
+//! [`DebuggerError::ScriptCheckFailed`]. All feedback and events are recorded, so they can be
+//! inspected after the session with [`Debugger::ui`](crate::debugger::Debugger::ui).
+//!
+//! When the script is done, the UI answers with [`Status::DebuggerQuit`].
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use coreminer::debugger::Debugger;
+//! use coreminer::feedback::{Feedback, Status};
+//! use coreminer::ui::ScriptedUI;
+//!
+//! let ui = ScriptedUI::new()
+//!     .then(Status::Run("/bin/true".into(), vec![]))
+//!     .expect(Status::Continue, |feedback| {
+//!         matches!(feedback, Feedback::Exit(_))
+//!     });
+//!
+//! let mut debugger = Debugger::build(ui).unwrap();
+//! debugger.run_debugger().unwrap();
+//! debugger.cleanup().unwrap();
+//!
+//! for (status, feedback) in debugger.ui().transcript() {
+//!     println!("{status:?}: {feedback}");
+//! }
+//! ```
+
+use std::collections::VecDeque;
+use std::fmt::{self, Debug};
+
+use super::{CommandSource, Event, EventSink};
+use crate::errors::{DebuggerError, Result};
+use crate::feedback::{Feedback, Status, StopReason};
+
+/// A check of the [`Feedback`] of a scripted [`Status`], which passes if it returns `true`
+pub type FeedbackCheck = Box<dyn FnMut(&Feedback) -> bool>;
+
+/// A hook that is called with every [`Event`], before it is recorded
+pub type EventHook = Box<dyn FnMut(&Event) -> Result<()>>;
+
+/// A step of the script of a [`ScriptedUI`]
+struct Step {
+    status: Status,
+    check: Option<FeedbackCheck>,
+}
+
+/// A user interface that feeds a script of [Statuses](Status) to the debugger
+///
+/// See the [module documentation](self) for an example.
+#[derive(Default)]
+pub struct ScriptedUI {
+    script: VecDeque<Step>,
+    /// The step whose [`Feedback`] comes next, with its index in the script
+    pending: Option<(usize, Step)>,
+    /// The number of steps that were handed out
+    handed_out: usize,
+    transcript: Vec<(Status, Feedback)>,
+    events: Vec<Event>,
+    hooks: Vec<EventHook>,
+}
+
+impl ScriptedUI {
+    /// Creates a [`ScriptedUI`] with an empty script
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a [`ScriptedUI`] that runs `statuses` without checks
+    #[must_use]
+    pub fn from_statuses(statuses: impl IntoIterator<Item = Status>) -> Self {
+        statuses.into_iter().fold(Self::new(), Self::then)
+    }
+
+    /// Appends `status` to the script
+    #[must_use]
+    pub fn then(mut self, status: Status) -> Self {
+        self.script.push_back(Step {
+            status,
+            check: None,
+        });
+        self
+    }
+
+    /// Appends `status` to the script, with a check of its [`Feedback`]
+    ///
+    /// If `check` returns `false`, the session is stopped with
+    /// [`DebuggerError::ScriptCheckFailed`].
+    #[must_use]
+    pub fn expect(
+        mut self,
+        status: Status,
+        check: impl FnMut(&Feedback) -> bool + 'static,
+    ) -> Self {
+        self.script.push_back(Step {
+            status,
+            check: Some(Box::new(check)),
+        });
+        self
+    }
+
+    /// Adds a hook that is called with every [`Event`], including the [`Feedback`]
+    ///
+    /// If the hook fails, the [`Event`] is not recorded and the error is returned to the
+    /// debugger, which stops the session for [`Event::Feedback`].
+    #[must_use]
+    pub fn with_hook(mut self, hook: impl FnMut(&Event) -> Result<()> + 'static) -> Self {
+        self.hooks.push(Box::new(hook));
+        self
+    }
+
+    /// Returns the scripted [Statuses](Status) that were processed, with their [`Feedback`]
+    #[must_use]
+    pub fn transcript(&self) -> &[(Status, Feedback)] {
+        &self.transcript
+    }
+
+    /// Returns the [Events](Event) other than [`Event::Feedback`], like stops and notifications
+    #[must_use]
+    pub fn events(&self) -> &[Event] {
+        &self.events
+    }
+
+    /// Returns the reasons of all stops of the debuggee, in order
+    #[must_use]
+    pub fn stops(&self) -> Vec<&StopReason> {
+        self.events
+            .iter()
+            .filter_map(|e| match e {
+                Event::Stopped(reason) => Some(reason),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Returns `true` if every step of the script was processed
+    #[must_use]
+    pub fn is_done(&self) -> bool {
+        self.script.is_empty() && self.pending.is_none()
+    }
+}
+
+impl CommandSource for ScriptedUI {
+    fn next_status(&mut self) -> Result<Status> {
+        let Some(step) = self.script.pop_front() else {
+            return Ok(Status::DebuggerQuit);
+        };
+        let status = step.status.clone();
+        self.pending = Some((self.handed_out, step));
+        self.handed_out += 1;
+        Ok(status)
+    }
+}
+
+impl EventSink for ScriptedUI {
+    fn push_event(&mut self, event: Event) -> Result<()> {
+        for hook in &mut self.hooks {
+            hook(&event)?;
+        }
+
+        match event {
+            Event::Feedback(feedback) => {
+                // the first feedback comes before any status was handed out
+                let Some((index, mut step)) = self.pending.take() else {
+                    return Ok(());
+                };
+                let passed = step.check.as_mut().map_or(true, |check| check(&feedback));
+                let shown = feedback.to_string();
+                self.transcript.push((step.status, feedback));
+                if !passed {
+                    return Err(DebuggerError::ScriptCheckFailed(index, shown));
+                }
+            }
+            other => self.events.push(other),
+        }
+        Ok(())
+    }
+}
+
+impl Debug for ScriptedUI {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ScriptedUI")
+            .field("remaining", &self.script.len())
+            .field("handed_out", &self.handed_out)
+            .field("transcript", &self.transcript)
+            .field("events", &self.events)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::debugger::{Debugger, FollowFork};
+    use crate::feedback::ExitStatus;
+
+    #[test]
+    fn test_script_without_debuggee() {
+        let ui = ScriptedUI::from_statuses([Status::Infos]).expect(Status::Continue, |feedback| {
+            matches!(feedback, Feedback::Error(DebuggerError::NoDebugee))
+        });
+        let mut debugger = Debugger::build(ui).unwrap();
+        debugger.run_debugger().unwrap();
+
+        let ui = debugger.ui();
+        assert!(ui.is_done());
+        assert_eq!(ui.transcript().len(), 2);
+        assert_eq!(ui.transcript()[1].0, Status::Continue);
+    }
+
+    #[test]
+    fn test_failed_check() {
+        let ui = ScriptedUI::new()
+            .then(Status::Infos)
+            .expect(Status::Continue, |feedback| {
+                matches!(feedback, Feedback::Ok)
+            })
+            .then(Status::Infos);
+        let mut debugger = Debugger::build(ui).unwrap();
+
+        let err = debugger.run_debugger().unwrap_err();
+        assert!(matches!(err, DebuggerError::ScriptCheckFailed(1, _)));
+        assert!(!debugger.ui().is_done());
+    }
+
+    #[test]
+    fn test_session() {
+        let ui = ScriptedUI::new()
+            .then(Status::Run("/bin/true".into(), vec![]))
+            .expect(Status::Continue, |feedback| {
+                matches!(feedback, Feedback::Exit(ExitStatus::Exited(0)))
+            });
+        let mut debugger = Debugger::build(ui).unwrap();
+        debugger.run_debugger().unwrap();
+        debugger.cleanup().unwrap();
+
+        let ui = debugger.ui();
+        assert!(ui.is_done());
+        assert!(matches!(
+            ui.stops().last(),
+            Some(StopReason::Exited(ExitStatus::Exited(0)))
+        ));
+    }
+
+    #[test]
+    fn test_stdin_pipe() {
+        let script = r#"read line && test "$line" = "hello world""#;
+        let ui = ScriptedUI::new()
+            .then(Status::SetStdinPipe(true))
+            .then(Status::Run(
+                "/bin/sh".into(),
+                vec![c"-c".into(), std::ffi::CString::new(script).unwrap()],
+            ))
+            .then(Status::SendStdin(b"hello world\n".to_vec()))
+            .then(Status::CloseStdin)
+            .expect(Status::CloseStdin, |feedback| {
+                matches!(feedback, Feedback::Error(DebuggerError::NoStdinPipe))
+            })
+            .expect(Status::Continue, |feedback| {
+                matches!(feedback, Feedback::Exit(ExitStatus::Exited(0)))
+            });
+        let mut debugger = Debugger::build(ui).unwrap();
+        debugger.run_debugger().unwrap();
+        debugger.cleanup().unwrap();
+        assert!(debugger.ui().is_done());
+    }
+
+    #[test]
+    fn test_follow_fork() {
+        let args = || vec![c"-c".into(), c"(exit 5); exit 3".into()];
+
+        // the parent is followed without stopping at the fork, only for the SIGCHLD
+        let ui = ScriptedUI::new()
+            .then(Status::Run("/bin/sh".into(), args()))
+            .expect(Status::Continue, |feedback| {
+                matches!(feedback, Feedback::Stopped(info) if matches!(info.reason, StopReason::Signal(_)))
+            })
+            .expect(Status::Continue, |feedback| {
+                matches!(feedback, Feedback::Exit(ExitStatus::Exited(3)))
+            });
+        let mut debugger = Debugger::build(ui).unwrap();
+        debugger.run_debugger().unwrap();
+        debugger.cleanup().unwrap();
+        assert!(debugger.ui().is_done());
+
+        let ui = ScriptedUI::new()
+            .then(Status::SetFollowFork(FollowFork::Child))
+            .then(Status::Run("/bin/sh".into(), args()))
+            .expect(Status::Continue, |feedback| {
+                matches!(feedback, Feedback::ForkEvent(fork) if fork.followed == fork.child)
+            })
+            .expect(Status::Continue, |feedback| {
+                matches!(feedback, Feedback::Exit(ExitStatus::Exited(5)))
+            });
+        let mut debugger = Debugger::build(ui).unwrap();
+        debugger.run_debugger().unwrap();
+        debugger.cleanup().unwrap();
+        assert!(debugger.ui().is_done());
+    }
+}
@@ -0,0 +1,316 @@
+//! # Transcript Module
+//!
+//! Provides recording of debug sessions into transcripts and replaying them.
+//!
+//! A transcript is a file with one JSON object per line, each with a [`Status`] that the debugger
+//! processed and the [`Feedback`] it gave for it:
+//!
+//! ```text
+//! {"status":{"Run":["/bin/true",[]]},"feedback":"Ok"}
+//! {"status":"Continue","feedback":{"Exit":{"Exited":0}}}
+//! ```
+//!
+//! A [`Recorder`] wraps any [`DebuggerUI`] and writes the transcript while the user works with
+//! it. A [`Replayer`] is a [`DebuggerUI`] of its own, which feeds the statuses of a transcript to a
+//! fresh debugger and compares the feedback with the recorded one. Every difference is kept as a
+//! [`Divergence`], so a replay tells if the debugger, or the debuggee, behaves differently than
+//! when the transcript was recorded.
+//!
+//! Feedback that contains process IDs or addresses of a randomized address space can differ
+//! between runs, even if nothing else changed. Disable the randomization, for example with
+//! `setarch -R`, for both recording and replaying to avoid that.
+
+use std::collections::VecDeque;
+use std::fmt::{self, Display};
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use super::{CommandSource, DebuggerUI, Event, EventSink};
+use crate::errors::Result;
+use crate::feedback::{Feedback, Status};
+
+/// A line of a transcript: a processed [`Status`] and its [`Feedback`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TranscriptEntry {
+    /// The status that was processed
+    pub status: Status,
+    /// The feedback for the status, as JSON
+    pub feedback: serde_json::Value,
+}
+
+impl TranscriptEntry {
+    /// Creates a [`TranscriptEntry`] from a [`Status`] and its [`Feedback`]
+    ///
+    /// Feedback that cannot be represented as JSON is recorded as `null`.
+    #[must_use]
+    pub fn new(status: Status, feedback: &Feedback) -> Self {
+        let feedback = serde_json::to_value(feedback).unwrap_or_else(|e| {
+            warn!("could not serialize the feedback for the transcript: {e}");
+            serde_json::Value::Null
+        });
+        Self { status, feedback }
+    }
+}
+
+/// Reads all entries of a transcript file
+///
+/// Empty lines are skipped.
+///
+/// # Errors
+///
+/// This function fails if the file cannot be read or a line is not a [`TranscriptEntry`].
+pub fn read_transcript(path: &Path) -> Result<Vec<TranscriptEntry>> {
+    let mut entries = Vec::new();
+    for line in BufReader::new(File::open(path)?).lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        entries.push(serde_json::from_str(&line)?);
+    }
+    Ok(entries)
+}
+
+/// A [`DebuggerUI`] that records the session of another one into a transcript
+///
+/// Each entry is written as soon as its feedback is known, so the transcript is complete up to
+/// the last processed status even if the debugger is killed.
+///
+/// # Examples
+///
+/// ```no_run
+/// #[cfg(feature = "cli")]
+/// # mod featguard { fn _do_thing() {
+/// use coreminer::debugger::Debugger;
+/// use coreminer::ui::cli::CliUi;
+/// use coreminer::ui::transcript::Recorder;
+///
+/// let ui = Recorder::create(CliUi::build(None).unwrap(), "session.jsonl".as_ref()).unwrap();
+/// let mut debugger = Debugger::build(ui).unwrap();
+/// debugger.run_debugger().unwrap();
+/// debugger.cleanup().unwrap();
+///
+/// # }}
+/// ```
+pub struct Recorder<UI: DebuggerUI> {
+    inner: UI,
+    output: BufWriter<File>,
+    pending: Option<Status>,
+}
+
+impl<UI: DebuggerUI> Recorder<UI> {
+    /// Creates a [`Recorder`] that writes the transcript of `inner` into a new file at `path`
+    ///
+    /// # Errors
+    ///
+    /// This function fails if the file cannot be created.
+    pub fn create(inner: UI, path: &Path) -> Result<Self> {
+        Ok(Self {
+            inner,
+            output: BufWriter::new(File::create(path)?),
+            pending: None,
+        })
+    }
+
+    /// Gets the user interface whose session is recorded
+    #[must_use]
+    pub fn inner(&self) -> &UI {
+        &self.inner
+    }
+
+    /// Returns the user interface whose session is recorded
+    pub fn into_inner(self) -> UI {
+        self.inner
+    }
+}
+
+impl<UI: DebuggerUI> CommandSource for Recorder<UI> {
+    fn next_status(&mut self) -> Result<Status> {
+        let status = self.inner.next_status()?;
+        self.pending = Some(status.clone());
+        Ok(status)
+    }
+}
+
+impl<UI: DebuggerUI> EventSink for Recorder<UI> {
+    fn push_event(&mut self, event: Event) -> Result<()> {
+        if let Event::Feedback(feedback) = &event {
+            // the first feedback comes before any status was read
+            if let Some(status) = self.pending.take() {
+                let entry = TranscriptEntry::new(status, feedback);
+                serde_json::to_writer(&mut self.output, &entry)?;
+                self.output.write_all(b"\n")?;
+                self.output.flush()?;
+            }
+        }
+        self.inner.push_event(event)
+    }
+}
+
+/// A difference between the recorded and the replayed [`Feedback`] of a [`Status`]
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Divergence {
+    /// Index of the entry in the transcript, starting at 0
+    pub index: usize,
+    /// The status whose feedback differs
+    pub status: Status,
+    /// The feedback in the transcript
+    pub expected: serde_json::Value,
+    /// The feedback of the replay
+    pub actual: serde_json::Value,
+}
+
+impl Display for Divergence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "entry {} ({:?}): expected {}, got {}",
+            self.index, self.status, self.expected, self.actual
+        )
+    }
+}
+
+/// A [`DebuggerUI`] that replays a transcript and flags where the feedback differs
+///
+/// When all statuses of the transcript were processed, it answers with
+/// [`Status::DebuggerQuit`].
+///
+/// # Examples
+///
+/// ```no_run
+/// use coreminer::debugger::Debugger;
+/// use coreminer::ui::transcript::Replayer;
+///
+/// let ui = Replayer::load("session.jsonl".as_ref()).unwrap();
+/// let mut debugger = Debugger::build(ui).unwrap();
+/// debugger.run_debugger().unwrap();
+/// debugger.cleanup().unwrap();
+///
+/// for divergence in debugger.ui().divergences() {
+///     println!("{divergence}");
+/// }
+/// ```
+#[derive(Debug, Default)]
+pub struct Replayer {
+    entries: VecDeque<TranscriptEntry>,
+    pending: Option<(usize, TranscriptEntry)>,
+    replayed: usize,
+    divergences: Vec<Divergence>,
+}
+
+impl Replayer {
+    /// Creates a [`Replayer`] for the entries of a transcript
+    #[must_use]
+    pub fn new(entries: Vec<TranscriptEntry>) -> Self {
+        Self {
+            entries: entries.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Creates a [`Replayer`] for the transcript file at `path`
+    ///
+    /// # Errors
+    ///
+    /// This function fails if the transcript cannot be read, see [`read_transcript`].
+    pub fn load(path: &Path) -> Result<Self> {
+        Ok(Self::new(read_transcript(path)?))
+    }
+
+    /// Returns the differences between the transcript and the replay so far
+    #[must_use]
+    pub fn divergences(&self) -> &[Divergence] {
+        &self.divergences
+    }
+
+    /// Returns the number of entries of the transcript that were replayed
+    #[must_use]
+    pub fn replayed(&self) -> usize {
+        self.replayed
+    }
+
+    /// Returns `true` if every entry of the transcript was replayed
+    #[must_use]
+    pub fn is_done(&self) -> bool {
+        self.entries.is_empty() && self.pending.is_none()
+    }
+}
+
+impl CommandSource for Replayer {
+    fn next_status(&mut self) -> Result<Status> {
+        let Some(entry) = self.entries.pop_front() else {
+            return Ok(Status::DebuggerQuit);
+        };
+        let status = entry.status.clone();
+        self.pending = Some((self.replayed, entry));
+        Ok(status)
+    }
+}
+
+impl EventSink for Replayer {
+    fn push_event(&mut self, event: Event) -> Result<()> {
+        let Event::Feedback(feedback) = event else {
+            return Ok(());
+        };
+        let Some((index, entry)) = self.pending.take() else {
+            return Ok(());
+        };
+        self.replayed += 1;
+
+        let actual = TranscriptEntry::new(entry.status, &feedback);
+        if actual.feedback != entry.feedback {
+            let divergence = Divergence {
+                index,
+                status: actual.status,
+                expected: entry.feedback,
+                actual: actual.feedback,
+            };
+            warn!("the replay diverged at {divergence}");
+            self.divergences.push(divergence);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::debugger::Debugger;
+    use crate::errors::DebuggerError;
+    use crate::ui::ScriptedUI;
+
+    #[test]
+    fn test_record_and_replay() {
+        let path = std::env::temp_dir().join(format!("cm-transcript-{}.jsonl", std::process::id()));
+        let script = ScriptedUI::from_statuses([Status::Infos, Status::Continue]);
+        let mut debugger = Debugger::build(Recorder::create(script, &path).unwrap()).unwrap();
+        debugger.run_debugger().unwrap();
+
+        let entries = read_transcript(&path).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[1].status, Status::Continue);
+
+        let mut debugger = Debugger::build(Replayer::load(&path).unwrap()).unwrap();
+        debugger.run_debugger().unwrap();
+        assert!(debugger.ui().is_done());
+        assert!(debugger.ui().divergences().is_empty());
+        std::fs::remove_file(&path).unwrap();
+
+        // a different feedback than recorded is flagged
+        let mut changed = entries;
+        changed[1].feedback = serde_json::to_value(Feedback::Ok).unwrap();
+        let mut debugger = Debugger::build(Replayer::new(changed)).unwrap();
+        debugger.run_debugger().unwrap();
+        let divergences = debugger.ui().divergences();
+        assert_eq!(divergences.len(), 1);
+        assert_eq!(divergences[0].index, 1);
+        assert_eq!(
+            divergences[0].actual,
+            serde_json::to_value(Feedback::Error(DebuggerError::NoDebugee)).unwrap()
+        );
+    }
+}
@@ -0,0 +1,639 @@
+//! # Terminal User Interface
+//!
+//! Provides a full-screen [`DebuggerUI`](super::DebuggerUI) for interactive reverse engineering,
+//! built with [`ratatui`].
+//!
+//! The screen is split into panes:
+//!
+//! - The disassembly at the instruction pointer, with breakpoints marked
+//! - The registers, with the ones that changed since the last command highlighted
+//! - The [`Stack`] of the current frame
+//! - The log, which shows the feedback of the commands
+//! - The command line
+//!
+//! Commands are the same as in the [CLI](super::cli), which parses them. After every command,
+//! the panes are refreshed by asking the debugger for the registers, the disassembly and the
+//! stack, whose feedback is shown in the panes instead of the log.
+//!
+//! The log pane shows what is written into a [`TuiLog`], which should be the writer of the
+//! [`tracing`] subscriber, so that the feedback logged by the CLI and the messages of the
+//! debugger end up there instead of on the screen.
+//!
+//! | Key                     | Action                                       |
+//! |-------------------------|----------------------------------------------|
+//! | `Enter`                 | Execute the command                          |
+//! | `Up`, `Down`            | Go through the previous commands             |
+//! | `PageUp`, `PageDown`    | Scroll the log                               |
+//! | `Ctrl-L`                | Redraw the screen, after the debuggee wrote to it |
+//! | `Ctrl-C`                | Clear the command line, or quit if it is empty |
+//! | `Ctrl-D`                | Quit                                         |
+//!
+//! The debuggee shares the terminal with the TUI, so its output is drawn over the panes. Run it
+//! with the stdin pipe (see [`Status::SetStdinPipe`]) so it does not read the keys meant for the
+//! command line.
+
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::io::Write;
+use std::rc::Rc;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use iced_x86::FormatterTextKind;
+use ratatui::crossterm::event::{self, KeyCode, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style, Stylize};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Paragraph};
+use ratatui::{DefaultTerminal, Frame};
+
+use super::cli::CliUi;
+use super::{CommandSource, Event, EventSink};
+use crate::disassemble::Disassembly;
+use crate::errors::Result;
+use crate::feedback::{Feedback, Status};
+use crate::regset::RegisterSet;
+use crate::stack::Stack;
+use crate::Addr;
+
+/// Number of bytes that are disassembled at the instruction pointer
+const DISASSEMBLY_BYTES: usize = 96;
+/// Number of lines that the log keeps
+const LOG_LINES: usize = 2000;
+/// Height of the log pane, with its border
+const LOG_HEIGHT: u16 = 12;
+/// Number of lines that `PageUp` and `PageDown` scroll the log
+const SCROLL_STEP: usize = 8;
+/// How long to wait for a key before drawing the log again
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// The lines shown in the log pane of a [`TuiUi`]
+///
+/// Anything written into it is split into lines, so it can be the writer of a [`tracing`]
+/// subscriber. Clones share the same lines.
+///
+/// # Examples
+///
+/// ```
+/// use std::io::Write;
+/// use coreminer::ui::tui::TuiLog;
+///
+/// let log = TuiLog::default();
+/// let mut writer = log.clone();
+/// writeln!(writer, "first\nsecond").unwrap();
+/// assert_eq!(log.lines(), ["first", "second"]);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct TuiLog(Arc<Mutex<VecDeque<String>>>);
+
+impl TuiLog {
+    /// Appends a line to the log, dropping the oldest line if the log is full
+    pub fn push(&self, line: impl Into<String>) {
+        let mut lines = self
+            .0
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        if lines.len() >= LOG_LINES {
+            lines.pop_front();
+        }
+        lines.push_back(line.into());
+    }
+
+    /// Returns a copy of the lines of the log
+    #[must_use]
+    pub fn lines(&self) -> Vec<String> {
+        self.0
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .iter()
+            .cloned()
+            .collect()
+    }
+}
+
+impl Write for TuiLog {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        // tracing writes each event at once, so lines are not split between writes
+        for line in String::from_utf8_lossy(buf).lines() {
+            // tabs are not drawn by the terminal backend
+            self.push(line.replace('\t', "    "));
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// What is shown on the screen of a [`TuiUi`]
+#[derive(Debug, Default)]
+struct Screen {
+    disassembly: Option<Disassembly>,
+    registers: Option<RegisterSet>,
+    /// The registers before the last refresh, to highlight the changed ones
+    previous_registers: Option<RegisterSet>,
+    stack: Option<Stack>,
+    /// Why the debuggee stopped the last time
+    status: Option<String>,
+    log: TuiLog,
+    /// Number of lines that the log is scrolled back
+    scroll: usize,
+    input: String,
+    history: Vec<String>,
+    /// The entry of the history that is shown in the command line
+    history_index: Option<usize>,
+}
+
+impl Screen {
+    /// Draws all panes into `frame`
+    fn render(&self, frame: &mut Frame<'_>) {
+        let [main, log, input] = Layout::vertical([
+            Constraint::Min(8),
+            Constraint::Length(LOG_HEIGHT),
+            Constraint::Length(3),
+        ])
+        .areas(frame.area());
+        let [code, registers, stack] = Layout::horizontal([
+            Constraint::Percentage(55),
+            Constraint::Length(30),
+            Constraint::Min(24),
+        ])
+        .areas(main);
+
+        let title = match &self.status {
+            Some(status) => format!(" Disassembly - {status} "),
+            None => " Disassembly ".to_string(),
+        };
+        frame.render_widget(
+            Paragraph::new(self.disassembly_lines()).block(Block::bordered().title(title)),
+            code,
+        );
+        frame.render_widget(
+            Paragraph::new(self.register_lines()).block(Block::bordered().title(" Registers ")),
+            registers,
+        );
+        let stack_text = self
+            .stack
+            .as_ref()
+            .map(|stack| stack.to_string().replace('\t', "  "))
+            .unwrap_or_default();
+        frame.render_widget(
+            Paragraph::new(stack_text).block(Block::bordered().title(" Stack ")),
+            stack,
+        );
+        self.render_log(frame, log);
+
+        frame.render_widget(
+            Paragraph::new(format!("> {}", self.input)).block(Block::bordered().title(" Command ")),
+            input,
+        );
+        let cursor = u16::try_from(self.input.chars().count() + 3).unwrap_or(u16::MAX);
+        frame.set_cursor_position((
+            input.x + cursor.min(input.width.saturating_sub(2)),
+            input.y + 1,
+        ));
+    }
+
+    /// Draws the end of the log, or an earlier part if it is scrolled back
+    fn render_log(&self, frame: &mut Frame<'_>, area: Rect) {
+        let lines = self.log.lines();
+        let height = usize::from(area.height.saturating_sub(2));
+        let end = lines.len().saturating_sub(self.scroll);
+        let start = end.saturating_sub(height);
+        let title = if self.scroll > 0 {
+            format!(" Log (scrolled back {} lines) ", self.scroll)
+        } else {
+            " Log ".to_string()
+        };
+        frame.render_widget(
+            Paragraph::new(lines[start..end].join("\n")).block(Block::bordered().title(title)),
+            area,
+        );
+    }
+
+    /// Formats the disassembly, with the instruction at the instruction pointer highlighted
+    fn disassembly_lines(&self) -> Vec<Line<'static>> {
+        let Some(disassembly) = &self.disassembly else {
+            return Vec::new();
+        };
+        let rip = self
+            .registers
+            .as_ref()
+            .and_then(|regs| regs.get("rip"))
+            .map(|rip| Addr::from(rip as usize));
+
+        let mut lines = Vec::new();
+        for (addr, raw, content, has_bp) in disassembly.inner() {
+            let mut spans = vec![Span::styled(addr.to_string(), Style::new().fg(Color::Blue))];
+            spans.push(if *has_bp {
+                Span::styled(" (*) ", Style::new().fg(Color::Red).bold())
+            } else {
+                Span::raw("     ")
+            });
+            let bytes: Vec<String> = raw.iter().map(|b| format!("{b:02x}")).collect();
+            spans.push(Span::styled(
+                format!("{:<24}", bytes.join(" ")),
+                Style::new().fg(Color::DarkGray),
+            ));
+            for (text, kind) in content {
+                spans.push(Span::styled(text.clone(), text_style(*kind)));
+            }
+
+            let line = Line::from(spans);
+            lines.push(if Some(*addr) == rip {
+                line.add_modifier(Modifier::REVERSED)
+            } else {
+                line
+            });
+        }
+        lines
+    }
+
+    /// Formats the registers, with the ones that changed since the last refresh highlighted
+    fn register_lines(&self) -> Vec<Line<'static>> {
+        let Some(registers) = &self.registers else {
+            return Vec::new();
+        };
+        registers
+            .registers()
+            .iter()
+            .map(|reg| {
+                let changed = self
+                    .previous_registers
+                    .as_ref()
+                    .and_then(|prev| prev.get(&reg.name))
+                    .is_some_and(|prev| prev != reg.value);
+                let style = if changed {
+                    Style::new().fg(Color::Yellow).bold()
+                } else {
+                    Style::new()
+                };
+                Line::from(vec![
+                    Span::raw(format!("{:<9}", reg.name)),
+                    Span::styled(
+                        format!("{:#0width$x}", reg.value, width = reg.size * 2 + 2),
+                        style,
+                    ),
+                ])
+            })
+            .collect()
+    }
+
+    /// Shows the [`Feedback`] of a refresh of the panes
+    ///
+    /// The disassembly is at the instruction pointer, so it is refreshed after the registers,
+    /// which is done by putting it in front of `queue`.
+    fn refresh(&mut self, status: &Status, feedback: Feedback, queue: &mut VecDeque<Status>) {
+        match (status, feedback) {
+            (_, Feedback::Registers(registers)) => {
+                if let Some(rip) = registers.get("rip") {
+                    queue.push_front(Status::DisassembleAt(
+                        Addr::from(rip as usize).into(),
+                        DISASSEMBLY_BYTES,
+                        false,
+                    ));
+                }
+                self.previous_registers = self.registers.replace(registers);
+            }
+            (_, Feedback::Disassembly(disassembly)) => self.disassembly = Some(disassembly),
+            (_, Feedback::Stack(stack)) => self.stack = Some(stack),
+            // without a debuggee, or not stopped, there is nothing to show
+            (Status::DumpRegisters, _) => {
+                self.registers = None;
+                self.previous_registers = None;
+                self.disassembly = None;
+            }
+            (Status::DisassembleAt(..), _) => self.disassembly = None,
+            (_, _) => self.stack = None,
+        }
+    }
+
+    /// Handles a key on the command line
+    ///
+    /// # Returns
+    ///
+    /// The command, if the key finished one
+    fn handle_key(&mut self, code: KeyCode, modifiers: KeyModifiers) -> Option<String> {
+        let ctrl = modifiers.contains(KeyModifiers::CONTROL);
+        match code {
+            KeyCode::Enter => {
+                let line = std::mem::take(&mut self.input);
+                if !line.trim().is_empty() && self.history.last() != Some(&line) {
+                    self.history.push(line.clone());
+                }
+                self.history_index = None;
+                self.scroll = 0;
+                return Some(line);
+            }
+            KeyCode::Char('c') if ctrl && !self.input.is_empty() => self.input.clear(),
+            KeyCode::Char('c' | 'd') if ctrl => return Some("quit".to_string()),
+            KeyCode::Char(c) if !ctrl => self.input.push(c),
+            KeyCode::Backspace => {
+                self.input.pop();
+            }
+            KeyCode::Up if !self.history.is_empty() => {
+                let index = self
+                    .history_index
+                    .map_or(self.history.len() - 1, |i| i.saturating_sub(1));
+                self.history_index = Some(index);
+                self.input.clone_from(&self.history[index]);
+            }
+            KeyCode::Down => match self.history_index {
+                Some(i) if i + 1 < self.history.len() => {
+                    self.history_index = Some(i + 1);
+                    self.input.clone_from(&self.history[i + 1]);
+                }
+                Some(_) => {
+                    self.history_index = None;
+                    self.input.clear();
+                }
+                None => (),
+            },
+            KeyCode::PageUp => {
+                let max = self.log.lines().len();
+                self.scroll = (self.scroll + SCROLL_STEP).min(max);
+            }
+            KeyCode::PageDown => self.scroll = self.scroll.saturating_sub(SCROLL_STEP),
+            _ => (),
+        }
+        None
+    }
+}
+
+/// Returns the style for a part of a disassembled instruction
+fn text_style(kind: FormatterTextKind) -> Style {
+    match kind {
+        FormatterTextKind::Mnemonic | FormatterTextKind::Prefix => {
+            Style::new().fg(Color::Cyan).bold()
+        }
+        FormatterTextKind::Register => Style::new().fg(Color::Green),
+        FormatterTextKind::Number | FormatterTextKind::LabelAddress => {
+            Style::new().fg(Color::Magenta)
+        }
+        FormatterTextKind::Function | FormatterTextKind::Label => Style::new().fg(Color::Yellow),
+        _ => Style::new(),
+    }
+}
+
+/// The terminal with the [`Screen`] that is drawn on it
+struct View {
+    terminal: DefaultTerminal,
+    screen: Screen,
+}
+
+impl View {
+    /// Draws the screen until the user finished a command
+    fn read_line(&mut self) -> Result<String> {
+        loop {
+            self.terminal.draw(|frame| self.screen.render(frame))?;
+            // the log is drawn again regularly, for messages while the debuggee runs
+            if !event::poll(POLL_INTERVAL)? {
+                continue;
+            }
+            let event::Event::Key(key) = event::read()? else {
+                continue;
+            };
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            if key.code == KeyCode::Char('l') && key.modifiers.contains(KeyModifiers::CONTROL) {
+                self.terminal.clear()?;
+                continue;
+            }
+            if let Some(line) = self.screen.handle_key(key.code, key.modifiers) {
+                return Ok(line);
+            }
+        }
+    }
+}
+
+impl Drop for View {
+    fn drop(&mut self) {
+        ratatui::restore();
+    }
+}
+
+/// A full-screen terminal user interface
+///
+/// See the [module documentation](self) for the layout and the keys.
+///
+/// # Examples
+///
+/// ```no_run
+/// #[cfg(feature = "tui")]
+/// # mod featguard { fn _do_thing() {
+/// use coreminer::debugger::Debugger;
+/// use coreminer::ui::cli::CliUi;
+/// use coreminer::ui::tui::{TuiLog, TuiUi};
+///
+/// let log = TuiLog::default();
+/// let writer = log.clone();
+/// tracing_subscriber::fmt()
+///     .with_ansi(false)
+///     .with_writer(move || writer.clone())
+///     .init();
+///
+/// let ui = TuiUi::build(CliUi::build(None).unwrap(), log).unwrap();
+/// let mut debugger = Debugger::build(ui).unwrap();
+/// debugger.run_debugger().unwrap();
+/// debugger.cleanup().unwrap();
+///
+/// # }}
+/// ```
+pub struct TuiUi {
+    cli: CliUi,
+    view: Rc<RefCell<View>>,
+    /// Statuses that refresh the panes, which are processed before the next command
+    refresh: VecDeque<Status>,
+    /// The refresh status whose feedback comes next
+    refreshing: Option<Status>,
+}
+
+impl TuiUi {
+    /// Creates a [`TuiUi`] and switches the terminal to the full-screen mode
+    ///
+    /// The terminal is restored when the [`TuiUi`] is dropped, or when the debugger panics.
+    ///
+    /// # Parameters
+    ///
+    /// * `cli` - The CLI that parses the commands, with the commands from
+    ///   [`CliUi::source`] that should be executed first
+    /// * `log` - The log that is shown in the log pane
+    ///
+    /// # Errors
+    ///
+    /// This function fails if the terminal cannot be switched to the full-screen mode, for
+    /// example because the output is not a terminal.
+    pub fn build(mut cli: CliUi, log: TuiLog) -> Result<Self> {
+        let terminal = ratatui::try_init()?;
+        let view = Rc::new(RefCell::new(View {
+            terminal,
+            screen: Screen {
+                log,
+                ..Default::default()
+            },
+        }));
+        let reader = Rc::clone(&view);
+        cli.set_line_reader(move || reader.borrow_mut().read_line());
+
+        Ok(Self {
+            cli,
+            view,
+            // a debuggee that was opened before, like a core dump, is shown right away
+            refresh: VecDeque::from([Status::DumpRegisters, Status::GetStack]),
+            refreshing: None,
+        })
+    }
+
+    /// Gets the CLI that parses the commands, for example for its [`CliUi::exit_code`]
+    #[must_use]
+    pub fn cli(&self) -> &CliUi {
+        &self.cli
+    }
+}
+
+impl CommandSource for TuiUi {
+    fn next_status(&mut self) -> Result<Status> {
+        if let Some(status) = self.refresh.pop_front() {
+            self.refreshing = Some(status.clone());
+            return Ok(status);
+        }
+        self.refreshing = None;
+
+        let status = self.cli.next_status()?;
+        // any command can change what the panes show, like a step or a write to a register
+        if status != Status::DebuggerQuit {
+            self.refresh
+                .extend([Status::DumpRegisters, Status::GetStack]);
+        }
+        Ok(status)
+    }
+}
+
+impl EventSink for TuiUi {
+    fn push_event(&mut self, event: Event) -> Result<()> {
+        let event = match (self.refreshing.take(), event) {
+            (Some(status), Event::Feedback(feedback)) => {
+                self.view
+                    .borrow_mut()
+                    .screen
+                    .refresh(&status, feedback, &mut self.refresh);
+                return Ok(());
+            }
+            (refreshing, event) => {
+                self.refreshing = refreshing;
+                event
+            }
+        };
+
+        let stop = match &event {
+            Event::Stopped(reason) => Some(reason.to_string()),
+            Event::Feedback(Feedback::Stopped(info)) => Some(info.reason.to_string()),
+            Event::Feedback(Feedback::Exit(status)) => Some(format!("exited: {status}")),
+            _ => None,
+        };
+        if let Some(stop) = stop {
+            self.view.borrow_mut().screen.status = Some(stop);
+        }
+        self.cli.push_event(event)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ratatui::backend::TestBackend;
+    use ratatui::Terminal;
+
+    use super::*;
+    use crate::regset::RegisterValue;
+
+    fn registers(rip: u64) -> RegisterSet {
+        RegisterSet::new(
+            "x86_64",
+            vec![
+                RegisterValue {
+                    name: "rax".to_string(),
+                    value: 0x2a,
+                    size: 8,
+                },
+                RegisterValue {
+                    name: "rip".to_string(),
+                    value: rip,
+                    size: 8,
+                },
+            ],
+        )
+    }
+
+    #[test]
+    fn test_refresh_and_render() {
+        let mut screen = Screen::default();
+        let mut queue = VecDeque::new();
+        screen.refresh(
+            &Status::DumpRegisters,
+            Feedback::Registers(registers(0x1000)),
+            &mut queue,
+        );
+        screen.refresh(
+            &Status::DumpRegisters,
+            Feedback::Registers(registers(0x1004)),
+            &mut queue,
+        );
+        assert!(matches!(
+            queue.front(),
+            Some(Status::DisassembleAt(_, DISASSEMBLY_BYTES, false))
+        ));
+        screen.log.push("Stopped: received SIGTRAP");
+        screen.input = "regs get".to_string();
+
+        let mut terminal = Terminal::new(TestBackend::new(100, 30)).unwrap();
+        terminal.draw(|frame| screen.render(frame)).unwrap();
+        let text: String = terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(ratatui::buffer::Cell::symbol)
+            .collect();
+        assert!(text.contains("0x0000000000001004"));
+        assert!(text.contains("received SIGTRAP"));
+        assert!(text.contains("> regs get"));
+
+        // only rip changed
+        let lines = screen.register_lines();
+        assert_eq!(lines[0].spans[1].style, Style::new());
+        assert_ne!(lines[1].spans[1].style, Style::new());
+
+        // a failed refresh clears the panes
+        screen.refresh(
+            &Status::DumpRegisters,
+            Feedback::Error(crate::errors::DebuggerError::NoDebugee),
+            &mut queue,
+        );
+        assert!(screen.registers.is_none());
+    }
+
+    #[test]
+    fn test_command_line() {
+        let mut screen = Screen::default();
+        for c in "bt".chars() {
+            assert_eq!(
+                screen.handle_key(KeyCode::Char(c), KeyModifiers::NONE),
+                None
+            );
+        }
+        assert_eq!(
+            screen.handle_key(KeyCode::Enter, KeyModifiers::NONE),
+            Some("bt".to_string())
+        );
+        screen.handle_key(KeyCode::Up, KeyModifiers::NONE);
+        assert_eq!(screen.input, "bt");
+        screen.handle_key(KeyCode::Down, KeyModifiers::NONE);
+        assert_eq!(screen.input, "");
+        assert_eq!(
+            screen.handle_key(KeyCode::Char('d'), KeyModifiers::CONTROL),
+            Some("quit".to_string())
+        );
+    }
+}
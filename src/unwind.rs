@@ -10,6 +10,7 @@
 //! The implementation is inspired by the `BugStalker` debugger project:
 //! <https://github.com/godzie44/BugStalker> (MIT Licensed)
 
+use crate::dbginfo::demangle;
 use crate::errors::Result;
 use crate::Addr;
 
@@ -140,7 +141,7 @@ pub fn unwind(pid: Pid) -> Result<Backtrace> {
         let ip = cursor.register(RegNum::IP)?;
         match (cursor.procedure_info(), cursor.procedure_name()) {
             (Ok(ref info), Ok(ref name)) if ip == info.start_ip() + name.offset() => {
-                let fn_name = format!("{:#}", rustc_demangle::demangle(name.name()));
+                let fn_name = demangle(name.name());
 
                 frames.push(BacktraceFrame {
                     name: Some(fn_name),
@@ -0,0 +1,479 @@
+//! # Uprobe Module
+//!
+//! Provides counting and tracing function calls of a debugged process with uprobes.
+//!
+//! A [Breakpoint](crate::breakpoint::Breakpoint) stops the debuggee every time it is hit, and
+//! each stop takes several context switches between the debuggee and the debugger. For functions
+//! that are called often, this slows the debuggee down so much that timing dependent bugs may
+//! disappear. A [`Uprobe`] is handled by the kernel instead: it is installed with
+//! `perf_event_open`, counts its hits and optionally captures the argument registers into a ring
+//! buffer, all without stopping the debuggee. The debugger reads the results whenever it likes.
+//!
+//! Uprobes are attached to a file and an offset in it, not to an address, so the address is
+//! translated with the memory map of the debuggee, see [`UprobeTarget::from_addr`]. Some limits
+//! apply:
+//!
+//! - The kernel needs to support uprobes, and the debugger needs to be allowed to use
+//!   `perf_event_open` for the debuggee, see `/proc/sys/kernel/perf_event_paranoid`.
+//! - Only the traced thread is probed, hits in other threads are not counted.
+//! - The kernel writes an `int3` to the probed address, which is visible in the memory of the
+//!   debuggee. A [Breakpoint](crate::breakpoint::Breakpoint) at the same address does not work.
+//! - If the ring buffer is full, the arguments of further hits are lost until the hits are read.
+//!   They are still counted.
+
+use std::ffi::CString;
+use std::fmt::{self, Display};
+use std::os::fd::{AsRawFd, OwnedFd};
+use std::os::unix::ffi::OsStrExt;
+use std::path::PathBuf;
+use std::ptr::NonNull;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use nix::libc;
+use nix::unistd::Pid;
+use serde::Serialize;
+use tracing::{debug, warn};
+
+use crate::errors::{DebuggerError, Result};
+use crate::memorymap::ProcessMemoryMap;
+use crate::pageguard::PAGE_SIZE;
+use crate::perf::{perf_event_open, PerfEventAttr, PERF_ATTR_SIZE_VER3};
+use crate::Addr;
+
+/// File that contains the perf event type of uprobes
+pub const UPROBE_TYPE_PATH: &str = "/sys/bus/event_source/devices/uprobe/type";
+
+/// Sample the instruction pointer
+const PERF_SAMPLE_IP: u64 = 1 << 0;
+/// Sample the process and thread ID
+const PERF_SAMPLE_TID: u64 = 1 << 1;
+/// Sample the user registers in `sample_regs_user`
+const PERF_SAMPLE_REGS_USER: u64 = 1 << 12;
+/// Record of a sample
+const PERF_RECORD_SAMPLE: u32 = 9;
+/// The perf register numbers of the argument registers `rdi`, `rsi`, `rdx`, `rcx`, `r8`, `r9`
+///
+/// See `arch/x86/include/uapi/asm/perf_regs.h` in the Linux source.
+const ARG_REGS: [u32; 6] = [5, 4, 3, 2, 16, 17];
+/// Number of pages of the ring buffer for captured hits, must be a power of two
+const DATA_PAGES: usize = 16;
+/// Offset of `data_head` in the first page of the ring buffer
+const DATA_HEAD_OFFSET: usize = 1024;
+/// Offset of `data_tail` in the first page of the ring buffer
+const DATA_TAIL_OFFSET: usize = 1032;
+
+/// Where a [`Uprobe`] is installed: a file and an offset in it
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+pub struct UprobeTarget {
+    /// The probed executable or shared library
+    pub path: PathBuf,
+    /// The offset of the probed instruction in the file
+    pub offset: u64,
+}
+
+impl UprobeTarget {
+    /// Finds the file and offset that are mapped to `addr`
+    ///
+    /// # Errors
+    ///
+    /// This function will fail if `addr` is not in a memory region that is mapped from a file.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use coreminer::uprobe::UprobeTarget;
+    /// use coreminer::memorymap::ProcessMemoryMap;
+    /// use coreminer::addr::Addr;
+    ///
+    /// let map = ProcessMemoryMap::from(proc_maps::get_process_maps(1234).unwrap());
+    /// let target = UprobeTarget::from_addr(&map, Addr::from(0x55dd73ea3fb8usize)).unwrap();
+    /// println!("{}+{:#x}", target.path.display(), target.offset);
+    /// ```
+    pub fn from_addr(map: &ProcessMemoryMap, addr: Addr) -> Result<Self> {
+        let region = map
+            .regions
+            .iter()
+            .find(|r| r.start_address <= addr && addr < r.end_address)
+            .filter(|r| r.inode != 0)
+            .ok_or(DebuggerError::NotFileBacked(addr))?;
+        let path = region
+            .path
+            .as_ref()
+            .ok_or(DebuggerError::NotFileBacked(addr))?;
+        Ok(Self {
+            path: PathBuf::from(path),
+            offset: (addr.usize() - region.start_address.usize() + region.offset) as u64,
+        })
+    }
+}
+
+impl Display for UprobeTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}+{:#x}", self.path.display(), self.offset)
+    }
+}
+
+/// A hit of a [`Uprobe`] that captures arguments
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+pub struct UprobeHit {
+    /// The process that hit the probe
+    pub pid: i32,
+    /// The thread that hit the probe
+    pub tid: i32,
+    /// The address of the probed instruction
+    pub ip: Addr,
+    /// The values of the six argument registers `rdi`, `rsi`, `rdx`, `rcx`, `r8` and `r9`
+    pub args: [u64; 6],
+}
+
+/// What a [`Uprobe`] has seen so far
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+pub struct UprobeStats {
+    /// The address at which the probe is installed
+    pub addr: Addr,
+    /// The file and offset at which the probe is installed
+    pub target: UprobeTarget,
+    /// How often the probe was hit
+    pub count: u64,
+    /// How many captured hits were lost because the ring buffer was full
+    pub lost: u64,
+    /// The hits that were captured since the last time, if arguments are captured
+    pub hits: Vec<UprobeHit>,
+}
+
+impl Display for UprobeStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({}): {} hits", self.addr, self.target, self.count)?;
+        if self.lost > 0 {
+            write!(f, ", {} lost", self.lost)?;
+        }
+        for hit in &self.hits {
+            write!(f, "\n  thread {}: {:x?}", hit.tid, hit.args)?;
+        }
+        Ok(())
+    }
+}
+
+/// A uprobe that counts the hits of an instruction of the debuggee, without stopping it
+///
+/// The probe is removed when the [`Uprobe`] is dropped.
+///
+/// # Examples
+///
+/// ```no_run
+/// use coreminer::uprobe::{Uprobe, UprobeTarget};
+/// use coreminer::memorymap::ProcessMemoryMap;
+/// use coreminer::addr::Addr;
+/// use nix::unistd::Pid;
+///
+/// let pid = Pid::from_raw(1234);
+/// let addr = Addr::from(0x55dd73ea3fb8usize);
+/// let map = ProcessMemoryMap::from(proc_maps::get_process_maps(pid.as_raw()).unwrap());
+/// let target = UprobeTarget::from_addr(&map, addr).unwrap();
+/// let mut probe = Uprobe::open(pid, addr, target, true).unwrap();
+///
+/// // ... let the debuggee run for a while ...
+///
+/// let stats = probe.stats().unwrap();
+/// println!("{} was hit {} times", stats.addr, stats.count);
+/// for hit in stats.hits {
+///     println!("first argument: {:#x}", hit.args[0]);
+/// }
+/// ```
+#[derive(Debug)]
+pub struct Uprobe {
+    addr: Addr,
+    target: UprobeTarget,
+    fd: OwnedFd,
+    ring: Option<RingBuffer>,
+    /// Number of hits that were taken from the ring buffer so far
+    captured: u64,
+}
+
+impl Uprobe {
+    /// Installs a uprobe for the thread `pid` at `target`
+    ///
+    /// # Parameters
+    ///
+    /// * `pid` - The thread to probe
+    /// * `addr` - The address of `target` in the debuggee, only used for reporting
+    /// * `target` - The file and offset to probe
+    /// * `capture` - Whether to capture the argument registers of each hit
+    ///
+    /// # Errors
+    ///
+    /// This function will fail if the kernel does not support uprobes, if `perf_event_open`
+    /// fails, for example because the debugger lacks the permission, or if the ring buffer
+    /// cannot be mapped.
+    pub fn open(pid: Pid, addr: Addr, target: UprobeTarget, capture: bool) -> Result<Self> {
+        let kind = std::fs::read_to_string(UPROBE_TYPE_PATH)
+            .ok()
+            .and_then(|s| s.trim().parse().ok())
+            .ok_or(DebuggerError::UprobesUnsupported)?;
+        let path = CString::new(target.path.as_os_str().as_bytes())
+            .map_err(|e| DebuggerError::ParseStr(e.to_string()))?;
+
+        let mut attr = PerfEventAttr {
+            kind,
+            size: PERF_ATTR_SIZE_VER3,
+            config1: path.as_ptr() as u64,
+            config2: target.offset,
+            ..Default::default()
+        };
+        if capture {
+            attr.sample_period = 1;
+            attr.wakeup_events = 1;
+            attr.sample_type = PERF_SAMPLE_IP | PERF_SAMPLE_TID | PERF_SAMPLE_REGS_USER;
+            attr.sample_regs_user = args_mask();
+        }
+
+        // the path lives until the syscall returned
+        let fd = perf_event_open(&attr, pid)?;
+        debug!("installed uprobe at {target} for {pid}");
+
+        let ring = if capture {
+            Some(RingBuffer::map(&fd)?)
+        } else {
+            None
+        };
+        Ok(Self {
+            addr,
+            target,
+            fd,
+            ring,
+            captured: 0,
+        })
+    }
+
+    /// Returns how often the probe was hit so far
+    ///
+    /// # Errors
+    ///
+    /// This function will fail if the counter cannot be read.
+    pub fn count(&self) -> Result<u64> {
+        let mut buf = [0; 8];
+        let read = nix::unistd::read(self.fd.as_raw_fd(), &mut buf)?;
+        if read != buf.len() {
+            return Err(DebuggerError::UprobesUnsupported);
+        }
+        Ok(u64::from_ne_bytes(buf))
+    }
+
+    /// Returns what the probe has seen so far
+    ///
+    /// The captured hits are removed from the ring buffer, so that there is room for new ones.
+    ///
+    /// # Errors
+    ///
+    /// This function will fail if the counter cannot be read.
+    pub fn stats(&mut self) -> Result<UprobeStats> {
+        let Some(ring) = &self.ring else {
+            return Ok(UprobeStats {
+                addr: self.addr,
+                target: self.target.clone(),
+                count: self.count()?,
+                lost: 0,
+                hits: Vec::new(),
+            });
+        };
+
+        let hits = parse_samples(&ring.take(), args_mask());
+        self.captured += hits.len() as u64;
+        // the kernel only reports lost samples once there is room again, so count them instead
+        let count = self.count()?;
+        Ok(UprobeStats {
+            addr: self.addr,
+            target: self.target.clone(),
+            count,
+            lost: count.saturating_sub(self.captured),
+            hits,
+        })
+    }
+
+    /// Returns the address at which the probe is installed
+    #[inline]
+    #[must_use]
+    pub fn addr(&self) -> Addr {
+        self.addr
+    }
+
+    /// Returns the file and offset at which the probe is installed
+    #[inline]
+    #[must_use]
+    pub fn target(&self) -> &UprobeTarget {
+        &self.target
+    }
+}
+
+/// The ring buffer that the kernel writes the captured hits of a [`Uprobe`] to
+///
+/// The first page holds the metadata, the data follows in [`DATA_PAGES`] pages.
+#[derive(Debug)]
+struct RingBuffer {
+    base: NonNull<u8>,
+}
+
+// SAFETY: the mapping is only accessed through &self and the atomics of the kernel protocol, it
+// does not depend on the thread that created it
+unsafe impl Send for RingBuffer {}
+
+impl RingBuffer {
+    /// Total size of the mapping
+    const LEN: usize = (1 + DATA_PAGES) * PAGE_SIZE;
+
+    /// Maps the ring buffer of the perf event `fd`
+    fn map(fd: &OwnedFd) -> Result<Self> {
+        // SAFETY: a new shared mapping of the perf event is created, no existing memory is
+        // affected
+        let base = unsafe {
+            libc::mmap(
+                std::ptr::null_mut(),
+                Self::LEN,
+                libc::PROT_READ | libc::PROT_WRITE,
+                libc::MAP_SHARED,
+                fd.as_raw_fd(),
+                0,
+            )
+        };
+        if base == libc::MAP_FAILED {
+            return Err(nix::errno::Errno::last().into());
+        }
+        let base = NonNull::new(base.cast()).ok_or(DebuggerError::UprobesUnsupported)?;
+        Ok(Self { base })
+    }
+
+    /// Returns the metadata field at `offset` of the first page
+    #[allow(clippy::cast_ptr_alignment)] // the mapping is page aligned and the offsets are aligned
+    fn field(&self, offset: usize) -> &AtomicU64 {
+        // SAFETY: offset is one of the aligned u64 fields of the first page, which stays mapped
+        // as long as self lives
+        unsafe { &*self.base.as_ptr().add(offset).cast::<AtomicU64>() }
+    }
+
+    /// Copies all new data out of the ring buffer and marks it as read
+    fn take(&self) -> Vec<u8> {
+        let size = DATA_PAGES * PAGE_SIZE;
+        let head = self.field(DATA_HEAD_OFFSET).load(Ordering::Acquire);
+        let tail = self.field(DATA_TAIL_OFFSET).load(Ordering::Relaxed);
+
+        let len = (head - tail) as usize;
+        let start = tail as usize % size;
+        let mut buf = Vec::with_capacity(len);
+        // SAFETY: the data pages follow the first page and are mapped as long as self lives, and
+        // the kernel does not write between tail and head
+        let data = unsafe { std::slice::from_raw_parts(self.base.as_ptr().add(PAGE_SIZE), size) };
+        if start + len <= size {
+            buf.extend_from_slice(&data[start..start + len]);
+        } else {
+            buf.extend_from_slice(&data[start..]);
+            buf.extend_from_slice(&data[..len - (size - start)]);
+        }
+
+        self.field(DATA_TAIL_OFFSET).store(head, Ordering::Release);
+        buf
+    }
+}
+
+impl Drop for RingBuffer {
+    fn drop(&mut self) {
+        // SAFETY: the mapping was created in RingBuffer::map with the same length and is not
+        // used anymore
+        if unsafe { libc::munmap(self.base.as_ptr().cast(), Self::LEN) } != 0 {
+            warn!("could not unmap the ring buffer of a uprobe");
+        }
+    }
+}
+
+/// The `sample_regs_user` mask of the argument registers
+fn args_mask() -> u64 {
+    ARG_REGS.iter().fold(0, |mask, r| mask | 1 << r)
+}
+
+/// Parses the sample records of a ring buffer into the captured hits
+///
+/// The samples contain the instruction pointer, the process and thread ID and the registers in
+/// `mask`, which must contain all of [`ARG_REGS`].
+fn parse_samples(data: &[u8], mask: u64) -> Vec<UprobeHit> {
+    let u64_at = |at: usize| {
+        data.get(at..at + 8)
+            .map(|b| u64::from_ne_bytes(b.try_into().expect("slice has 8 bytes")))
+    };
+    let u32_at = |at: usize| {
+        data.get(at..at + 4)
+            .map(|b| u32::from_ne_bytes(b.try_into().expect("slice has 4 bytes")))
+    };
+    let reg_count = mask.count_ones() as usize;
+
+    let mut hits = Vec::new();
+    let mut at = 0;
+    while let (Some(kind), Some(size)) = (u32_at(at), u32_at(at + 4)) {
+        // the size is the upper half of the second word of the header
+        let size = (size >> 16) as usize;
+        if size == 0 {
+            break;
+        }
+        let body = at + 8;
+        if kind == PERF_RECORD_SAMPLE {
+            let ip = u64_at(body);
+            let pid = u32_at(body + 8);
+            let tid = u32_at(body + 12);
+            // without an ABI, the thread had no user registers and none follow
+            let regs: Option<Vec<u64>> = match u64_at(body + 16) {
+                Some(0) => Some(vec![0; reg_count]),
+                Some(_) => (0..reg_count).map(|i| u64_at(body + 24 + i * 8)).collect(),
+                None => None,
+            };
+            if let (Some(ip), Some(pid), Some(tid), Some(regs)) = (ip, pid, tid, regs) {
+                // registers are in the order of their numbers
+                let reg = |r: u32| regs[(mask & ((1 << r) - 1)).count_ones() as usize];
+                hits.push(UprobeHit {
+                    pid: pid as i32,
+                    tid: tid as i32,
+                    ip: Addr::from(ip),
+                    args: ARG_REGS.map(reg),
+                });
+            }
+        }
+        at += size;
+    }
+    hits
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn record(kind: u32, words: &[u64]) -> Vec<u8> {
+        let size = 8 + words.len() * 8;
+        let mut buf = Vec::new();
+        buf.extend_from_slice(&kind.to_ne_bytes());
+        buf.extend_from_slice(&((size as u32) << 16).to_ne_bytes());
+        for w in words {
+            buf.extend_from_slice(&w.to_ne_bytes());
+        }
+        buf
+    }
+
+    #[test]
+    fn test_parse_samples() {
+        assert_eq!(size_of::<PerfEventAttr>(), PERF_ATTR_SIZE_VER3 as usize);
+
+        let pid_tid = (0x53a_u64 << 32) | 0x539;
+        // rcx, rdx, rsi, rdi, r8, r9 in the order of their perf numbers
+        let mut data = record(
+            PERF_RECORD_SAMPLE,
+            &[0x1136, pid_tid, 2, 0xd, 0xc, 0xb, 0xa, 0xe, 0xf],
+        );
+        // records that are not samples are skipped
+        data.extend(record(2, &[0, 3]));
+        data.extend(record(PERF_RECORD_SAMPLE, &[0x1136, pid_tid, 0]));
+
+        let hits = parse_samples(&data, args_mask());
+        assert_eq!(hits.len(), 2);
+        assert_eq!(hits[0].pid, 1337);
+        assert_eq!(hits[0].tid, 1338);
+        assert_eq!(hits[0].ip, Addr::from(0x1136usize));
+        assert_eq!(hits[0].args, [0xa, 0xb, 0xc, 0xd, 0xe, 0xf]);
+        assert_eq!(hits[1].args, [0; 6]);
+    }
+}
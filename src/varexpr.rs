@@ -0,0 +1,296 @@
+//! # Variable Expression Module
+//!
+//! Provides the parsed form of the expressions that select variables and the data inside of them.
+//!
+//! A [`VariableExpression`](crate::variable::VariableExpression) is the text that the user gives
+//! to read or write a variable. It is parsed into a [`VarExpr`], which the
+//! [`Debugger`](crate::debugger::Debugger) evaluates with the type information of the debug
+//! information, see [`Debuggee::expr_place`](crate::debuggee::Debuggee::expr_place).
+//!
+//! The text form is a subset of C:
+//!
+//! - `count`: a local variable or parameter of the function
+//! - `config.flags`: a member of a struct or union
+//! - `arr[3]` or `ptr[0x10]`: an element of an array, or the value at an offset from a pointer,
+//!   in decimal or hexadecimal
+//! - `*ptr`: the value that a pointer points to, like `ptr[0]`
+//! - `node->next`: a member of the struct that a pointer points to, like `(*node).next`
+//! - `&var`: the address of a value, which is only allowed at the start of the expression
+//!
+//! These can be combined, like `&list->items[2].name`. Parentheses group the operators, like
+//! `(*matrix)[1]`.
+
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+use crate::errors::{DebuggerError, Result};
+
+/// A parsed expression that selects a variable or data inside of it
+///
+/// # Examples
+///
+/// ```
+/// use coreminer::varexpr::VarExpr;
+///
+/// let expr: VarExpr = "node->items[2].len".parse().unwrap();
+/// assert_eq!(expr.variable(), "node");
+/// assert_eq!(
+///     expr,
+///     VarExpr::Member(
+///         Box::new(VarExpr::Index(
+///             Box::new(VarExpr::Member(
+///                 Box::new(VarExpr::Deref(Box::new(VarExpr::Variable("node".to_string())))),
+///                 "items".to_string(),
+///             )),
+///             2,
+///         )),
+///         "len".to_string(),
+///     )
+/// );
+/// assert_eq!(expr.to_string(), "node->items[2].len");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum VarExpr {
+    /// A variable of the function, by its name
+    Variable(String),
+    /// A member of a struct or union, like `a.b`
+    Member(Box<VarExpr>, String),
+    /// An element of an array or the value at an offset from a pointer, like `a[3]`
+    Index(Box<VarExpr>, usize),
+    /// The value that a pointer points to, like `*a`
+    Deref(Box<VarExpr>),
+    /// The address of a value, like `&a`
+    AddressOf(Box<VarExpr>),
+}
+
+impl VarExpr {
+    /// Returns the name of the variable that the expression starts from
+    #[must_use]
+    pub fn variable(&self) -> &str {
+        match self {
+            Self::Variable(name) => name,
+            Self::Member(inner, _)
+            | Self::Index(inner, _)
+            | Self::Deref(inner)
+            | Self::AddressOf(inner) => inner.variable(),
+        }
+    }
+}
+
+impl FromStr for VarExpr {
+    type Err = DebuggerError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut parser = Parser { rest: s };
+        let expr = if parser.eat('&') {
+            parser.unary().map(|inner| Self::AddressOf(Box::new(inner)))
+        } else {
+            parser.unary()
+        };
+        match expr {
+            Some(expr) if parser.rest.trim().is_empty() => Ok(expr),
+            _ => Err(DebuggerError::InvalidVarExpr(s.to_string())),
+        }
+    }
+}
+
+/// A recursive descent parser for the text form of a [`VarExpr`]
+///
+/// Each method consumes what it parsed from `rest` and returns [None] if the text does not
+/// match, in which case the expression is invalid as a whole.
+struct Parser<'a> {
+    rest: &'a str,
+}
+
+impl Parser<'_> {
+    /// Consumes `c` and the whitespace before it, if it is next
+    fn eat(&mut self, c: char) -> bool {
+        match self.rest.trim_start().strip_prefix(c) {
+            Some(rest) => {
+                self.rest = rest;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Consumes characters while `pred` holds and returns them, skipping whitespace before them
+    fn take_while(&mut self, pred: impl Fn(char) -> bool) -> &str {
+        let s = self.rest.trim_start();
+        let end = s.find(|c| !pred(c)).unwrap_or(s.len());
+        let (taken, rest) = s.split_at(end);
+        self.rest = rest;
+        taken
+    }
+
+    /// `unary := '*' unary | postfix`
+    fn unary(&mut self) -> Option<VarExpr> {
+        if self.eat('*') {
+            return Some(VarExpr::Deref(Box::new(self.unary()?)));
+        }
+        self.postfix()
+    }
+
+    /// `postfix := primary ('.' IDENT | '->' IDENT | '[' NUMBER ']')*`
+    fn postfix(&mut self) -> Option<VarExpr> {
+        let mut expr = self.primary()?;
+        loop {
+            if self.eat('.') {
+                expr = VarExpr::Member(Box::new(expr), self.ident()?);
+            } else if self.eat('-') {
+                if !self.rest.starts_with('>') {
+                    return None;
+                }
+                self.rest = &self.rest[1..];
+                let deref = VarExpr::Deref(Box::new(expr));
+                expr = VarExpr::Member(Box::new(deref), self.ident()?);
+            } else if self.eat('[') {
+                let index = self.number()?;
+                if !self.eat(']') {
+                    return None;
+                }
+                expr = VarExpr::Index(Box::new(expr), index);
+            } else {
+                return Some(expr);
+            }
+        }
+    }
+
+    /// `primary := IDENT | '(' unary ')'`
+    fn primary(&mut self) -> Option<VarExpr> {
+        if self.eat('(') {
+            let expr = self.unary()?;
+            return self.eat(')').then_some(expr);
+        }
+        self.ident().map(VarExpr::Variable)
+    }
+
+    /// An identifier like in C, which does not start with a digit
+    fn ident(&mut self) -> Option<String> {
+        let ident = self.take_while(|c| c.is_ascii_alphanumeric() || c == '_');
+        match ident.chars().next() {
+            Some(first) if !first.is_ascii_digit() => Some(ident.to_string()),
+            _ => None,
+        }
+    }
+
+    /// A number in decimal, or in hexadecimal with a `0x` prefix
+    fn number(&mut self) -> Option<usize> {
+        let number = self.take_while(|c| c.is_ascii_alphanumeric());
+        match number.strip_prefix("0x") {
+            Some(hex) => usize::from_str_radix(hex, 16).ok(),
+            None => number.parse().ok(),
+        }
+    }
+}
+
+impl Display for VarExpr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Variable(name) => write!(f, "{name}"),
+            Self::Member(inner, member) => match inner.as_ref() {
+                Self::Deref(pointer) => {
+                    write_postfix_operand(f, pointer)?;
+                    write!(f, "->{member}")
+                }
+                other => {
+                    write_postfix_operand(f, other)?;
+                    write!(f, ".{member}")
+                }
+            },
+            Self::Index(inner, index) => {
+                write_postfix_operand(f, inner)?;
+                write!(f, "[{index}]")
+            }
+            Self::Deref(inner) => write!(f, "*{inner}"),
+            Self::AddressOf(inner) => write!(f, "&{inner}"),
+        }
+    }
+}
+
+/// Writes the operand of `.`, `->` or `[]`, in parentheses if it starts with `*` or `&`
+fn write_postfix_operand(f: &mut fmt::Formatter<'_>, operand: &VarExpr) -> fmt::Result {
+    match operand {
+        VarExpr::Deref(_) | VarExpr::AddressOf(_) => write!(f, "({operand})"),
+        other => write!(f, "{other}"),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn var(name: &str) -> VarExpr {
+        VarExpr::Variable(name.to_string())
+    }
+
+    fn member(inner: VarExpr, name: &str) -> VarExpr {
+        VarExpr::Member(Box::new(inner), name.to_string())
+    }
+
+    #[test]
+    fn test_parse_var_expr() {
+        let cases = [
+            ("count", var("count"), "count"),
+            (
+                "config.flags",
+                member(var("config"), "flags"),
+                "config.flags",
+            ),
+            ("arr[3]", VarExpr::Index(Box::new(var("arr")), 3), "arr[3]"),
+            (
+                "ptr[0x10]",
+                VarExpr::Index(Box::new(var("ptr")), 0x10),
+                "ptr[16]",
+            ),
+            ("*ptr", VarExpr::Deref(Box::new(var("ptr"))), "*ptr"),
+            (
+                "**pp",
+                VarExpr::Deref(Box::new(VarExpr::Deref(Box::new(var("pp"))))),
+                "**pp",
+            ),
+            (
+                "node->next",
+                member(VarExpr::Deref(Box::new(var("node"))), "next"),
+                "node->next",
+            ),
+            (
+                "(*node).next",
+                member(VarExpr::Deref(Box::new(var("node"))), "next"),
+                "node->next",
+            ),
+            (
+                "(*matrix)[1]",
+                VarExpr::Index(Box::new(VarExpr::Deref(Box::new(var("matrix")))), 1),
+                "(*matrix)[1]",
+            ),
+            (
+                "*s.buf",
+                VarExpr::Deref(Box::new(member(var("s"), "buf"))),
+                "*s.buf",
+            ),
+            (
+                " & list -> items [ 2 ] ",
+                VarExpr::AddressOf(Box::new(VarExpr::Index(
+                    Box::new(member(VarExpr::Deref(Box::new(var("list"))), "items")),
+                    2,
+                ))),
+                "&list->items[2]",
+            ),
+            ("_tmp1", var("_tmp1"), "_tmp1"),
+        ];
+        for (text, expected, display) in cases {
+            let expr: VarExpr = text.parse().unwrap();
+            assert_eq!(expr, expected, "{text}");
+            assert_eq!(expr.to_string(), display);
+            assert_eq!(display.parse::<VarExpr>().unwrap(), expected);
+        }
+
+        for invalid in [
+            "", "1abc", "a.", "a.1", "a[", "a[]", "a[b]", "a[-1]", "a-b", "a b", "(a", "*&a", "a&",
+            "&&a",
+        ] {
+            assert!(invalid.parse::<VarExpr>().is_err(), "{invalid}");
+        }
+    }
+}
@@ -10,32 +10,83 @@
 //! Key components:
 //! - [`VariableExpression`]: A type for referring to variables by name
 //! - [`VariableValue`]: An enum representing different forms of variable values
+//! - [`VariablePlace`]: Where the data selected by a [`VarExpr`] is
 //! - Methods on the [`Debuggee`] for variable access
 
+use std::fmt::Display;
+
 use serde::Serialize;
 use tracing::{info, trace};
 
 use crate::dbginfo::{search_through_symbols, OwnedSymbol, SymbolKind};
 use crate::debuggee::Debuggee;
 use crate::dwarf_parse::FrameInfo;
+use crate::dwarfreg::DwarfRegister;
 use crate::errors::{DebuggerError, Result};
-use crate::{get_reg, mem_read, mem_write, set_reg, Addr, Word, WORD_BYTES};
+use crate::fpregs::{get_st, get_xmm, get_ymm, set_xmm, set_ymm, FpRegister, XMM_BYTES, YMM_BYTES};
+use crate::limits::LimitKind;
+use crate::varexpr::VarExpr;
+use crate::{get_reg, mem_write, mem_write_verified, set_reg, Addr, Register, Word, WORD_BYTES};
 
 /// A type alias for variable expressions (typically variable names)
 ///
 /// [`VariableExpression`] is used to refer to variables in the debugged program,
 /// typically by their source-level names.
 ///
-/// In the future, this might include something like dereferencing, or logic, to filter from
-/// various [`OwnedSymbols`][OwnedSymbol].
+/// Besides names, the text can select data inside of a variable, like `config.flags`, `arr[3]`,
+/// `*ptr` or `&var`. It is parsed into a [`VarExpr`] when it is evaluated, see the
+/// [varexpr module](crate::varexpr).
 pub type VariableExpression = String;
 
+/// The data selected by a [`VarExpr`], before its value is read
+///
+/// See [`Debuggee::expr_place`].
+#[derive(Debug, Clone)]
+pub struct VariablePlace {
+    /// The symbol that the data belongs to
+    ///
+    /// This is the variable or the member that was selected, or the array or pointer type for an
+    /// element or a pointee, whose datatype is the type of their elements.
+    pub symbol: OwnedSymbol,
+    /// The type of the data, with typedefs and qualifiers resolved
+    ///
+    /// This is usually the type of `symbol`, but for the rows of a multidimensional array, it is
+    /// an array type that has no entry in the debug information.
+    pub datatype: OwnedSymbol,
+    /// Where the data is
+    pub storage: PlaceStorage,
+}
+
+/// Where the data of a [`VariablePlace`] is
+#[derive(Debug, Clone)]
+pub enum PlaceStorage {
+    /// In the memory of the debuggee, at this address
+    Memory(Addr),
+    /// Somewhere else, like in a register, so the value was read already
+    Value(VariableValue),
+}
+
+impl VariablePlace {
+    /// Returns the address of the data, if it is in memory
+    #[must_use]
+    pub fn address(&self) -> Option<Addr> {
+        match self.storage {
+            PlaceStorage::Memory(addr) => Some(addr),
+            PlaceStorage::Value(_) => None,
+        }
+    }
+}
+
 /// Represents a variable value in one of several forms
 ///
 /// [`VariableValue`] encapsulates the various ways a variable's value might be
 /// represented, such as raw bytes, numeric values, or machine words. This allows
 /// the debugger to work with variables of different types and sizes.
 ///
+/// Values read from the debuggee are raw at first. [`Debuggee::typed_value`] interprets them with
+/// the type of the variable, so that the [`Display`] implementation shows them like the source
+/// code would, for example `-3` instead of `[fd, ff, ff, ff]`.
+///
 /// # Examples
 ///
 /// ```
@@ -65,6 +116,64 @@ pub enum VariableValue {
     /// DWARF numeric value
     #[serde(serialize_with = "serialize_gimli_value")]
     Numeric(gimli::Value),
+
+    /// Value of an enumeration, with the name of its enumerator, like `Color::Red`
+    ///
+    /// Values of Rust enums are named after their variant, like `Option<u32>::Some`, and have the
+    /// data of the variant as fields.
+    Enum {
+        /// Name of the enumerator, prefixed with the name of the enumeration if it has one
+        name: String,
+        /// Numeric value of the enumerator, or the discriminant of a Rust enum
+        value: i64,
+        /// Names and values of the data of a Rust enum variant, empty for other enumerations
+        fields: Vec<(String, VariableValue)>,
+    },
+
+    /// Value of a signed integer type
+    Signed(i64),
+
+    /// Value of an unsigned integer type
+    Unsigned(u64),
+
+    /// Value of a floating point type, `float` values are converted to [f64]
+    Float(f64),
+
+    /// Value of a boolean type
+    Bool(bool),
+
+    /// Value of a character type
+    Char(char),
+
+    /// Value of a struct, class or union, with the names and values of its members in the order
+    /// of their declaration
+    ///
+    /// Members of C++ base classes are grouped into a field named after the base class. Members
+    /// of anonymous structs and unions inside of the struct are fields of the struct itself.
+    Struct {
+        /// Names and values of the members
+        fields: Vec<(String, VariableValue)>,
+    },
+
+    /// Value of an array, with the values of its elements
+    ///
+    /// The elements of a multidimensional array are the arrays of its rows.
+    Array(Vec<VariableValue>),
+
+    /// Value of a pointer that was followed to the data it points to
+    ///
+    /// Pointers are only followed if the [`LimitKind::PointerDepth`] limit allows it, see
+    /// [`Debuggee::place_read`].
+    Pointer {
+        /// The address that the pointer holds
+        address: Addr,
+        /// What is at the address
+        pointee: Pointee,
+    },
+
+    /// The variable has no value at the current instruction, because the compiler optimized
+    /// it out there
+    OptimizedOut,
 }
 
 impl VariableValue {
@@ -93,7 +202,17 @@ impl VariableValue {
     pub fn byte_size(&self) -> usize {
         match self {
             Self::Bytes(b) => b.len(),
-            Self::Other(_w) => WORD_BYTES,
+            Self::Other(_)
+            | Self::Enum { .. }
+            | Self::Signed(_)
+            | Self::Unsigned(_)
+            | Self::Float(_)
+            | Self::Pointer { .. } => WORD_BYTES,
+            Self::Bool(_) => 1,
+            Self::Char(_) => 4,
+            Self::Struct { fields } => fields.iter().map(|(_, v)| v.byte_size()).sum(),
+            Self::Array(elements) => elements.iter().map(Self::byte_size).sum(),
+            Self::OptimizedOut => 0,
             Self::Numeric(v) => match v.value_type() {
                 gimli::ValueType::U8 | gimli::ValueType::I8 => 1,
                 gimli::ValueType::U16 | gimli::ValueType::I16 => 2,
@@ -116,6 +235,9 @@ impl VariableValue {
     ///
     /// This method will panic if [self] is a [`VariableValue::Bytes`] which has more bytes than a [u64] can hold.
     ///
+    /// A [`VariableValue::Struct`] or [`VariableValue::Array`] has no single number and is
+    /// converted to 0, like a value that was optimized out.
+    ///
     /// # Examples
     ///
     /// ```
@@ -140,6 +262,13 @@ impl VariableValue {
                 crate::bytes_to_u64(b).unwrap()
             }
             Self::Other(w) => crate::bytes_to_u64(&w.to_ne_bytes()).unwrap(),
+            Self::Enum { value, .. } | Self::Signed(value) => *value as u64,
+            Self::Unsigned(v) => *v,
+            Self::Pointer { address, .. } => address.u64(),
+            Self::Float(v) => v.to_bits(),
+            Self::Bool(v) => u64::from(*v),
+            Self::Char(v) => u64::from(*v),
+            Self::Struct { .. } | Self::Array(_) | Self::OptimizedOut => 0,
             Self::Numeric(v) => match v {
                 gimli::Value::U8(v) => (*v).into(),
                 gimli::Value::I8(v) => crate::bytes_to_u64(&v.to_ne_bytes()).unwrap(),
@@ -198,6 +327,83 @@ impl VariableValue {
     }
 }
 
+impl Display for VariableValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Bytes(b) => write!(f, "{b:02x?}"),
+            Self::Other(w) => write!(f, "{w:#x}"),
+            Self::Numeric(v) => match *v {
+                gimli::Value::I8(v) => write!(f, "{v}"),
+                gimli::Value::I16(v) => write!(f, "{v}"),
+                gimli::Value::I32(v) => write!(f, "{v}"),
+                gimli::Value::I64(v) => write!(f, "{v}"),
+                gimli::Value::F32(v) => write!(f, "{v}"),
+                gimli::Value::F64(v) => write!(f, "{v}"),
+                _ => write!(f, "{}", self.to_u64()),
+            },
+            Self::Enum {
+                name,
+                value,
+                fields,
+            } if fields.is_empty() => write!(f, "{name} ({value})"),
+            Self::Enum {
+                name,
+                value,
+                fields,
+            } => write!(
+                f,
+                "{name} ({value}) {}",
+                Self::Struct {
+                    fields: fields.clone()
+                }
+            ),
+            Self::Signed(v) => write!(f, "{v}"),
+            Self::Unsigned(v) => write!(f, "{v}"),
+            Self::Float(v) => write!(f, "{v}"),
+            Self::Bool(v) => write!(f, "{v}"),
+            Self::Char(v) => write!(f, "{v:?} ({})", u32::from(*v)),
+            Self::Struct { fields } if fields.is_empty() => write!(f, "{{}}"),
+            Self::Struct { fields } => {
+                write!(f, "{{ ")?;
+                for (i, (name, value)) in fields.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{name}: {value}")?;
+                }
+                write!(f, " }}")
+            }
+            Self::Array(elements) => {
+                write!(f, "[")?;
+                for (i, element) in elements.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{element}")?;
+                }
+                write!(f, "]")
+            }
+            Self::Pointer { address, pointee } => match pointee {
+                Pointee::Value(value) => write!(f, "{address} -> {value}"),
+                Pointee::Null => write!(f, "{address} (null)"),
+                Pointee::Unreadable => write!(f, "{address} (unreadable)"),
+            },
+            Self::OptimizedOut => write!(f, "<optimized out>"),
+        }
+    }
+}
+
+/// What a followed pointer points to, see [`VariableValue::Pointer`]
+#[derive(Debug, Clone, Serialize)]
+pub enum Pointee {
+    /// The value at the address, interpreted with the type the pointer points to
+    Value(Box<VariableValue>),
+    /// The pointer is null, nothing was read
+    Null,
+    /// The memory at the address cannot be read, the pointer is dangling or garbage
+    Unreadable,
+}
+
 impl From<usize> for VariableValue {
     fn from(value: usize) -> Self {
         VariableValue::Numeric(gimli::Value::Generic(value as u64))
@@ -290,6 +496,7 @@ impl Debuggee {
     /// - The variable's location cannot be determined
     /// - Memory or register access fails
     /// - The data type of the variable cannot be determined
+    /// - The variable was optimized out and has no storage to write to
     ///
     /// # Examples
     ///
@@ -319,31 +526,52 @@ impl Debuggee {
         value: &VariableValue,
     ) -> Result<()> {
         Debuggee::check_sym_variable_ok(sym)?;
-        let Some(datatype) = self.get_type_for_symbol(sym)? else {
+        let Some(datatype) = self.resolve_type(sym)? else {
             return Err(DebuggerError::NoDatatypeFound);
         };
 
-        let Some(loc_attr) = sym.location() else {
-            return Err(DebuggerError::SymbolHasNoLocation);
-        };
-        let location = self.parse_location(loc_attr, frame_info, sym.encoding())?;
+        let location = self.parse_symbol_location(sym, frame_info)?;
 
         match location {
             gimli::Location::Address { address } => {
-                let Some(byte_size) = datatype.byte_size() else {
+                let Some(byte_size) = self.type_size(&datatype)? else {
                     return Err(DebuggerError::SymbolHasNoByteSize);
                 };
-                let value_raw = value.resize_to_bytes(byte_size);
+                let value_raw = value_bytes(value, byte_size)?;
                 let addr: Addr = address.into();
                 trace!("writing to {addr}");
-                let _written = mem_write(&value_raw, self.pid, addr)?;
+                if self.verify_writes {
+                    mem_write_verified(&value_raw, self.pid, addr)?;
+                } else {
+                    mem_write(&value_raw, self.pid, addr)?;
+                }
             }
             gimli::Location::Register { register } => {
-                set_reg(self.pid, register.try_into()?, value.to_u64())?;
+                if let Ok(vector_register) = FpRegister::try_from(register) {
+                    // only the bytes of the value are written, the rest of the register stays
+                    let value_raw = match value {
+                        VariableValue::Bytes(bytes) => bytes.clone(),
+                        other => other.resize_to_bytes(
+                            datatype.byte_size().unwrap_or(WORD_BYTES).min(WORD_BYTES),
+                        ),
+                    };
+                    self.vector_register_write(vector_register, frame_info, &value_raw)?;
+                    return Ok(());
+                }
+                let register = register.try_into()?;
+                if frame_info.registers.is_some() {
+                    // the value would have to be written to where the callee saved it
+                    return Err(DebuggerError::OuterFrameRegisterWrite(register));
+                }
+                set_reg(self.pid, register, value.to_u64())?;
+            }
+            // computed values have no storage, their variable was optimized out
+            gimli::Location::Empty
+            | gimli::Location::Value { .. }
+            | gimli::Location::Bytes { .. }
+            | gimli::Location::ImplicitPointer { .. } => {
+                return Err(DebuggerError::VariableOptimizedOut)
             }
-            other => unimplemented!(
-                "writing to variable with gimli location of type {other:?} is not implemented"
-            ),
         }
 
         Ok(())
@@ -353,6 +581,9 @@ impl Debuggee {
     ///
     /// Prefer to use the more high level [`crate::debugger::Debugger::read_variable`].
     ///
+    /// The value is raw, like the bytes of a struct in memory. [`Self::typed_value`] interprets
+    /// it with the type of the variable, and [`Self::member_read`] takes members out of it.
+    ///
     /// # Parameters
     ///
     /// * `sym` - The symbol representing the variable
@@ -360,7 +591,8 @@ impl Debuggee {
     ///
     /// # Returns
     ///
-    /// * `Ok(VariableValue)` - The variable's value
+    /// * `Ok(VariableValue)` - The variable's value, or [`VariableValue::OptimizedOut`] if it has
+    ///   none at the instruction of the frame
     /// * `Err(DebuggerError)` - If the read failed
     ///
     /// # Errors
@@ -369,6 +601,7 @@ impl Debuggee {
     /// - The symbol is not a valid variable
     /// - The variable's location cannot be determined
     /// - Memory or register access fails
+    /// - The variable is larger than [`Limits::max_read_size`](crate::limits::Limits::max_read_size)
     /// - The data type of the variable cannot be determined
     ///
     /// # Examples
@@ -393,38 +626,1172 @@ impl Debuggee {
     /// # }
     /// ```
     pub fn var_read(&self, sym: &OwnedSymbol, frame_info: &FrameInfo) -> Result<VariableValue> {
-        Debuggee::check_sym_variable_ok(sym)?;
-        let Some(datatype) = self.get_type_for_symbol(sym)? else {
+        match Debuggee::check_sym_variable_ok(sym) {
+            // variables that were optimized out everywhere have no location at all, but constant
+            // ones still have their value
+            Err(DebuggerError::SymbolHasNoLocation) => {
+                return Ok(match sym.const_value() {
+                    Some(value) => VariableValue::Other(value as Word),
+                    None => VariableValue::OptimizedOut,
+                });
+            }
+            other => other?,
+        }
+        let Some(datatype) = self.resolve_type(sym)? else {
             return Err(DebuggerError::NoDatatypeFound);
         };
 
-        let Some(loc_attr) = sym.location() else {
-            return Err(DebuggerError::SymbolHasNoLocation);
+        let location = match self.parse_symbol_location(sym, frame_info) {
+            Err(DebuggerError::NoLocationAtAddr(_)) => return Ok(VariableValue::OptimizedOut),
+            other => other?,
         };
-        let location = self.parse_location(loc_attr, frame_info, sym.encoding())?;
 
         let value = match location {
+            // an implicit pointer was optimized out, only what it points to is known
+            gimli::Location::Empty | gimli::Location::ImplicitPointer { .. } => {
+                VariableValue::OptimizedOut
+            }
             gimli::Location::Value { value } => value.into(),
             gimli::Location::Bytes { value } => VariableValue::Bytes(value.to_vec()),
             gimli::Location::Address { address } => {
                 let addr: Addr = address.into();
                 info!("reading var from {addr}");
-                let Some(size) = datatype.byte_size() else {
+                let Some(size) = self.type_size(&datatype)? else {
                     return Err(DebuggerError::SymbolHasNoByteSize);
                 };
+                self.limits.check(LimitKind::ReadSize, size)?;
                 let mut buf = vec![0; size];
-                let _len = mem_read(&mut buf, self.pid, addr)?;
+                let _len = self.read_bytes(&mut buf, addr)?;
 
                 VariableValue::Bytes(buf)
             }
             gimli::Location::Register { register } => {
-                VariableValue::Other(get_reg(self.pid, register.try_into()?)? as Word)
+                if let Ok(vector_register) = FpRegister::try_from(register) {
+                    let byte_size = datatype.byte_size().unwrap_or(XMM_BYTES);
+                    VariableValue::Bytes(self.vector_register_read(
+                        vector_register,
+                        frame_info,
+                        byte_size,
+                    )?)
+                } else {
+                    match DwarfRegister::try_from(register)? {
+                        DwarfRegister::General(register) => {
+                            VariableValue::Other(frame_info.register(self.pid, register)? as Word)
+                        }
+                        // x87 registers have the 10 bytes of a long double
+                        other => VariableValue::Bytes(frame_info.dwarf_register(self.pid, other)?),
+                    }
+                }
             }
-            other => unimplemented!("gimli location of type {other:?} is not implemented"),
         };
 
         Ok(value)
     }
+
+    /// Reads the value that a function returned, right after it returned
+    ///
+    /// The value is taken from where the System V ABI for x86-64 returns it: floating point
+    /// numbers in `xmm0`, or `st0` for `long double`, and other values of up to 16 bytes in `rax`
+    /// and `rdx`. Larger values are returned in memory, whose address the function leaves in
+    /// `rax`. Small structs with floating point members are not supported, they are read from
+    /// `rax` and `rdx` like the other small values.
+    ///
+    /// The value is interpreted with the return type of the function, like a variable with
+    /// [`Self::typed_value`].
+    ///
+    /// # Parameters
+    ///
+    /// * `function` - The symbol of the function that returned
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Some(VariableValue))` - The returned value
+    /// * `Ok(None)` - If the function has no return type, like `void` functions in C
+    /// * `Err(DebuggerError)` - If the value could not be read
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if:
+    /// - The registers or the memory of the returned value cannot be read
+    /// - The returned value is larger than the [`LimitKind::ReadSize`] limit
+    pub fn return_value_read(&self, function: &OwnedSymbol) -> Result<Option<VariableValue>> {
+        let Some(datatype) = self.resolve_type(function)? else {
+            return Ok(None);
+        };
+        let size = datatype.byte_size().unwrap_or(WORD_BYTES);
+        let rax = get_reg(self.pid, Register::rax)?;
+
+        let value = if datatype.base_encoding() == Some(gimli::DW_ATE_float) {
+            if size <= XMM_BYTES / 2 {
+                VariableValue::Bytes(get_xmm(self.pid, FpRegister::xmm0)?[..size].to_vec())
+            } else {
+                VariableValue::Bytes(get_st(self.pid, 0)?.to_vec())
+            }
+        } else if size <= WORD_BYTES {
+            VariableValue::Other(rax as Word)
+        } else if size <= 2 * WORD_BYTES {
+            let rdx = get_reg(self.pid, Register::rdx)?;
+            let mut bytes = [rax.to_le_bytes(), rdx.to_le_bytes()].concat();
+            bytes.truncate(size);
+            VariableValue::Bytes(bytes)
+        } else {
+            self.limits.check(LimitKind::ReadSize, size)?;
+            let mut buf = vec![0; size];
+            self.read_bytes(&mut buf, Addr::from(rax))?;
+            VariableValue::Bytes(buf)
+        };
+
+        Ok(Some(self.typed_value(function, value)?))
+    }
+
+    /// Reads a member of a struct or union from the value of a variable
+    ///
+    /// `parent` is the symbol that `value` was read for, either the variable itself or a member
+    /// read before, so that nested members can be read one after another. Bit-fields are
+    /// extracted from their storage unit and sign extended if their type is signed.
+    ///
+    /// # Parameters
+    ///
+    /// * `parent` - The symbol of the struct value
+    /// * `value` - The value of the struct, as read by [`Self::var_read`]
+    /// * `member_name` - The name of the member
+    ///
+    /// # Returns
+    ///
+    /// * `Ok((OwnedSymbol, VariableValue))` - The symbol of the member and its value
+    /// * `Err(DebuggerError)` - If the member could not be read
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if:
+    /// - The type of `parent` cannot be found or has no member with that name
+    /// - The member is not inside of `value`, for example because `value` was not read completely
+    pub fn member_read(
+        &self,
+        parent: &OwnedSymbol,
+        value: &VariableValue,
+        member_name: &str,
+    ) -> Result<(OwnedSymbol, VariableValue)> {
+        let Some(datatype) = self.resolve_type(parent)? else {
+            return Err(DebuggerError::NoDatatypeFound);
+        };
+        let (member, base_offset) = self.find_member(&datatype, member_name)?;
+        // members of optimized out structs are just as optimized out
+        if matches!(value, VariableValue::OptimizedOut) {
+            return Ok((member, VariableValue::OptimizedOut));
+        }
+        let bytes = match value {
+            VariableValue::Bytes(b) => b.clone(),
+            other => other.to_u64().to_le_bytes().to_vec(),
+        };
+        let member_value = self.member_value(&member, base_offset, &bytes)?;
+
+        Ok((member, member_value))
+    }
+
+    /// Extracts the raw value of `member` from the bytes of its struct
+    ///
+    /// `base_offset` is the offset of the base class that the member is in, see
+    /// [`Self::find_member`].
+    fn member_value(
+        &self,
+        member: &OwnedSymbol,
+        base_offset: usize,
+        bytes: &[u8],
+    ) -> Result<VariableValue> {
+        let member_name = || member.name().unwrap_or_default().to_string();
+        let member_type = self.resolve_type(member)?;
+
+        if let (Some(bit_size), Some(bit_offset)) = (member.bit_size(), member.data_bit_offset()) {
+            let signed = member_type.as_ref().is_some_and(|t| {
+                matches!(
+                    t.base_encoding(),
+                    Some(gimli::DW_ATE_signed | gimli::DW_ATE_signed_char)
+                )
+            });
+            let bits = extract_bits(bytes, base_offset * 8 + bit_offset, bit_size, signed)
+                .ok_or_else(|| DebuggerError::MemberNotInValue(member_name()))?;
+            return Ok(VariableValue::Other(bits as Word));
+        }
+
+        // members of unions have no offset
+        let offset = base_offset + member.member_offset().unwrap_or(0);
+        let Some(member_type) = member_type else {
+            return Err(DebuggerError::NoDatatypeFound);
+        };
+        let Some(size) = self.type_size(&member_type)? else {
+            return Err(DebuggerError::SymbolHasNoByteSize);
+        };
+        let Some(member_bytes) = bytes.get(offset..offset + size) else {
+            return Err(DebuggerError::MemberNotInValue(member_name()));
+        };
+        Ok(VariableValue::Bytes(member_bytes.to_vec()))
+    }
+
+    /// Decodes the members of a struct, class or union from the raw bytes of its value
+    ///
+    /// Each member is interpreted with its type by [`Self::typed_value`], so that nested structs
+    /// are decoded as well. Static members are left out, as they are not stored in the value, and
+    /// so are virtual base classes, whose offset is only known at runtime.
+    fn struct_value(&self, datatype: &OwnedSymbol, bytes: &[u8]) -> Result<VariableValue> {
+        let mut fields = Vec::new();
+        for child in datatype.children() {
+            match child.kind() {
+                SymbolKind::Inheritance => {
+                    let (Some(offset), Some(base_type)) =
+                        (child.member_offset(), self.resolve_type(child)?)
+                    else {
+                        continue;
+                    };
+                    let size = self.type_size(&base_type)?.unwrap_or(0);
+                    // empty base classes can share their bytes with the members
+                    let Some(base_bytes) = bytes.get(offset..offset + size) else {
+                        continue;
+                    };
+                    let value =
+                        self.typed_value(child, VariableValue::Bytes(base_bytes.to_vec()))?;
+                    let name = base_type.name().unwrap_or("<base>").to_string();
+                    fields.push((name, value));
+                }
+                SymbolKind::Member if !is_static_member(datatype, child) => {
+                    let value = match self.member_value(child, 0, bytes) {
+                        Ok(value) => value,
+                        // flexible array members have no size and are behind the struct
+                        Err(
+                            DebuggerError::SymbolHasNoByteSize | DebuggerError::MemberNotInValue(_),
+                        ) => continue,
+                        Err(e) => return Err(e),
+                    };
+                    match (child.name(), self.typed_value(child, value)?) {
+                        (None, VariableValue::Struct { fields: inner }) => fields.extend(inner),
+                        (name, value) => fields.push((name.unwrap_or_default().to_string(), value)),
+                    }
+                }
+                _ => (),
+            }
+        }
+        Ok(VariableValue::Struct { fields })
+    }
+
+    /// Decodes the value of a Rust enum from the raw bytes of its value
+    ///
+    /// The variant is selected by the value of the discriminant member of the variant part
+    /// `part`. A variant without a discriminant value is selected if no other variant matches,
+    /// which is how niche-optimized enums like `Option<&T>` describe the variant that holds the
+    /// data, whose bytes are the discriminant. The data of the variant becomes the fields of the
+    /// [`VariableValue::Enum`]. Discriminants that are given as lists of ranges
+    /// (`DW_AT_discr_list`) are not supported.
+    ///
+    /// If no variant is selected, the raw bytes are returned.
+    fn variant_value(
+        &self,
+        datatype: &OwnedSymbol,
+        part: &OwnedSymbol,
+        bytes: &[u8],
+    ) -> Result<VariableValue> {
+        let variants: Vec<&OwnedSymbol> = part
+            .children()
+            .iter()
+            .filter(|c| c.kind() == SymbolKind::Variant)
+            .collect();
+        let discr_member = part
+            .discr()
+            .and_then(|offset| part.children().iter().find(|c| c.offset() == offset));
+
+        let selected = match discr_member {
+            // enums with a single variant have no discriminant
+            None => match variants.as_slice() {
+                [variant] => Some((*variant, variant.discr_value().unwrap_or(0))),
+                _ => None,
+            },
+            Some(member) => {
+                let size = self.resolve_type(member)?.and_then(|t| t.byte_size());
+                let mask = low_bytes_mask(size.unwrap_or(WORD_BYTES));
+                let raw = match self.member_value(member, 0, bytes)? {
+                    // 128 bit discriminants are compared by their lower half
+                    VariableValue::Bytes(b) if b.len() > WORD_BYTES => {
+                        crate::bytes_to_u64(&b[..WORD_BYTES]).unwrap_or_default()
+                    }
+                    other => other.to_u64() & mask,
+                };
+                variants
+                    .iter()
+                    .find(|v| v.discr_value().is_some_and(|d| d as u64 & mask == raw))
+                    .or_else(|| variants.iter().find(|v| v.discr_value().is_none()))
+                    .map(|v| (*v, v.discr_value().unwrap_or(raw as i64)))
+            }
+        };
+        let Some((variant, value)) = selected else {
+            return Ok(VariableValue::Bytes(bytes.to_vec()));
+        };
+        // the data of a variant is a member of a struct type named like the variant
+        let Some(member) = variant
+            .children()
+            .iter()
+            .find(|c| c.kind() == SymbolKind::Member)
+        else {
+            return Ok(VariableValue::Bytes(bytes.to_vec()));
+        };
+
+        let variant_name = member.name().unwrap_or_default();
+        let data = self.member_value(member, 0, bytes)?;
+        let fields = match self.typed_value(member, data)? {
+            VariableValue::Struct { fields } => fields,
+            other => vec![(variant_name.to_string(), other)],
+        };
+        Ok(VariableValue::Enum {
+            name: match datatype.name() {
+                Some(type_name) => format!("{type_name}::{variant_name}"),
+                None => variant_name.to_string(),
+            },
+            value,
+            fields,
+        })
+    }
+
+    /// Decodes the elements of an array from its raw value
+    ///
+    /// Arrays whose number of elements is not known, like variable length arrays, are returned
+    /// unchanged, and so are values that have fewer bytes than the array.
+    fn array_value(&self, datatype: &OwnedSymbol, value: VariableValue) -> Result<VariableValue> {
+        let (Some(count), Some(element_type)) =
+            (array_count(datatype), self.array_element_type(datatype)?)
+        else {
+            return Ok(value);
+        };
+        let Some(element_size) = self.type_size(&element_type)?.filter(|size| *size > 0) else {
+            return Ok(value);
+        };
+        let bytes = match &value {
+            VariableValue::Bytes(b) => b.clone(),
+            other => other.to_u64().to_le_bytes().to_vec(),
+        };
+        if bytes.len() < count * element_size {
+            return Ok(value);
+        }
+
+        let elements = bytes
+            .chunks_exact(element_size)
+            .take(count)
+            .map(|element| {
+                self.value_of_type(
+                    datatype,
+                    &element_type,
+                    VariableValue::Bytes(element.to_vec()),
+                )
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(VariableValue::Array(elements))
+    }
+
+    /// Returns the type of the elements of an array type
+    ///
+    /// For a multidimensional array, the elements are its rows, whose type is the array type
+    /// without its first dimension. That type has no entry in the debug information, it is made
+    /// up from a copy of the array type.
+    fn array_element_type(&self, array: &OwnedSymbol) -> Result<Option<OwnedSymbol>> {
+        let mut subranges = array
+            .children()
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| c.kind() == SymbolKind::SubrangeType)
+            .map(|(i, _)| i);
+        let (Some(first), Some(_)) = (subranges.next(), subranges.next()) else {
+            return self.resolve_type(array);
+        };
+        let mut row = array.clone();
+        let mut children = array.children().to_vec();
+        children.remove(first);
+        row.set_children(children);
+        row.set_byte_size(None);
+        Ok(Some(row))
+    }
+
+    /// Returns the size of a value of the resolved type `datatype` in bytes
+    ///
+    /// The size of an array is computed from its dimensions and the size of its elements, as it
+    /// often has no size of its own in the debug information. It is [None] if a dimension is not
+    /// known.
+    fn type_size(&self, datatype: &OwnedSymbol) -> Result<Option<usize>> {
+        if datatype.byte_size().is_some() || datatype.kind() != SymbolKind::ArrayType {
+            return Ok(datatype.byte_size());
+        }
+        let Some(element_type) = self.resolve_type(datatype)? else {
+            return Ok(None);
+        };
+        let Some(mut size) = self.type_size(&element_type)? else {
+            return Ok(None);
+        };
+        for subrange in datatype
+            .children()
+            .iter()
+            .filter(|c| c.kind() == SymbolKind::SubrangeType)
+        {
+            let Some(count) = subrange.count() else {
+                return Ok(None);
+            };
+            size = size.saturating_mul(count);
+        }
+        Ok(Some(size))
+    }
+
+    /// Finds the member with the name `member_name` in the struct, class or union type
+    /// `datatype`
+    ///
+    /// If the type has no such member itself, the members of its C++ base classes are searched,
+    /// in the order of the bases. Returns the member and the offset of the base class that it is
+    /// in, which is zero for members of the type itself.
+    ///
+    /// # Errors
+    ///
+    /// Fails with [`DebuggerError::NoSuchMember`] if there is no such member, and with
+    /// [`DebuggerError::StaticMember`] if the member is static and not part of the value.
+    fn find_member(
+        &self,
+        datatype: &OwnedSymbol,
+        member_name: &str,
+    ) -> Result<(OwnedSymbol, usize)> {
+        self.find_member_in(datatype, member_name)?
+            .ok_or_else(|| DebuggerError::NoSuchMember(member_name.to_string()))
+    }
+
+    /// Searches `datatype` and its base classes for a member, see [`Self::find_member`]
+    fn find_member_in(
+        &self,
+        datatype: &OwnedSymbol,
+        member_name: &str,
+    ) -> Result<Option<(OwnedSymbol, usize)>> {
+        if let Some(member) = datatype
+            .children()
+            .iter()
+            .find(|m| m.kind() == SymbolKind::Member && m.name() == Some(member_name))
+        {
+            if is_static_member(datatype, member) {
+                return Err(DebuggerError::StaticMember(member_name.to_string()));
+            }
+            return Ok(Some((member.clone(), 0)));
+        }
+
+        for base in datatype
+            .children()
+            .iter()
+            .filter(|b| b.kind() == SymbolKind::Inheritance)
+        {
+            // virtual bases have an offset that is only known at runtime
+            let Some(base_offset) = base.member_offset() else {
+                trace!("skipping a virtual base class while looking for {member_name}");
+                continue;
+            };
+            let Some(base_type) = self.resolve_type(base)? else {
+                continue;
+            };
+            if let Some((member, offset)) = self.find_member_in(&base_type, member_name)? {
+                return Ok(Some((member, base_offset + offset)));
+            }
+        }
+
+        // members of anonymous structs and unions are used like members of the outer type
+        for anonymous in datatype
+            .children()
+            .iter()
+            .filter(|m| m.kind() == SymbolKind::Member && m.name().is_none())
+        {
+            let Some(anonymous_type) = self.resolve_type(anonymous)? else {
+                continue;
+            };
+            if let Some((member, offset)) = self.find_member_in(&anonymous_type, member_name)? {
+                let anonymous_offset = anonymous.member_offset().unwrap_or(0);
+                return Ok(Some((member, anonymous_offset + offset)));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Evaluates a [`VarExpr`] to the place of the data that it selects
+    ///
+    /// `var` is the symbol of the variable that the expression starts from, see
+    /// [`VarExpr::variable`]. Members of data in memory are at the offset of the member, and
+    /// elements of arrays and pointees of pointers are found with the size of their type as the
+    /// stride. Data that is not in memory, like a struct in registers or a bit-field, is read
+    /// right away, and data inside of it is taken from the value that was read.
+    ///
+    /// The expression must not take an address with [`VarExpr::AddressOf`], as the address is no
+    /// place in the debuggee. Use [`VariablePlace::address`] for the expression inside instead.
+    ///
+    /// # Parameters
+    ///
+    /// * `var` - The symbol of the variable of the expression
+    /// * `frame_info` - Stack frame information
+    /// * `expr` - The expression to evaluate
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(VariablePlace)` - Where the selected data is
+    /// * `Err(DebuggerError)` - If the expression could not be evaluated
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if:
+    /// - The variable cannot be read
+    /// - A member is not found in the type of its struct
+    /// - An index or dereference is used on something other than an array or pointer
+    /// - A pointer that is followed cannot be read
+    /// - The expression takes an address
+    pub fn expr_place(
+        &self,
+        var: &OwnedSymbol,
+        frame_info: &FrameInfo,
+        expr: &VarExpr,
+    ) -> Result<VariablePlace> {
+        match expr {
+            VarExpr::Variable(_) => {
+                let Some(datatype) = self.resolve_type(var)? else {
+                    return Err(DebuggerError::NoDatatypeFound);
+                };
+                let storage = match self.parse_symbol_location(var, frame_info) {
+                    Ok(gimli::Location::Address { address }) => {
+                        PlaceStorage::Memory(address.into())
+                    }
+                    _ => PlaceStorage::Value(self.var_read(var, frame_info)?),
+                };
+                Ok(VariablePlace {
+                    symbol: var.clone(),
+                    datatype,
+                    storage,
+                })
+            }
+            VarExpr::Member(inner, name) => {
+                let parent = self.expr_place(var, frame_info, inner)?;
+                let parent = self.referenced_place(parent)?;
+                let (member, base_offset) = self.find_member(&parent.datatype, name)?;
+                let Some(datatype) = self.resolve_type(&member)? else {
+                    return Err(DebuggerError::NoDatatypeFound);
+                };
+                let storage = match parent.storage {
+                    // bit-fields do not start at a byte, they are extracted from the value
+                    PlaceStorage::Memory(addr) if member.bit_size().is_none() => {
+                        PlaceStorage::Memory(
+                            addr + base_offset + member.member_offset().unwrap_or(0),
+                        )
+                    }
+                    PlaceStorage::Value(VariableValue::OptimizedOut) => {
+                        PlaceStorage::Value(VariableValue::OptimizedOut)
+                    }
+                    _ => {
+                        let bytes = match self.place_value(&parent)? {
+                            VariableValue::Bytes(b) => b,
+                            other => other.to_u64().to_le_bytes().to_vec(),
+                        };
+                        PlaceStorage::Value(self.member_value(&member, base_offset, &bytes)?)
+                    }
+                };
+                Ok(VariablePlace {
+                    symbol: member,
+                    datatype,
+                    storage,
+                })
+            }
+            VarExpr::Index(inner, index) => {
+                let base = self.expr_place(var, frame_info, inner)?;
+                self.element_place(self.referenced_place(base)?, *index, inner)
+            }
+            VarExpr::Deref(inner) => {
+                let base = self.expr_place(var, frame_info, inner)?;
+                self.element_place(self.referenced_place(base)?, 0, inner)
+            }
+            VarExpr::AddressOf(_) => Err(DebuggerError::InvalidVarExpr(expr.to_string())),
+        }
+    }
+
+    /// Finds the element with the number `index` of an array or of the data a pointer points to
+    ///
+    /// `base_expr` is the expression of `base`, which is named in errors.
+    fn element_place(
+        &self,
+        base: VariablePlace,
+        index: usize,
+        base_expr: &VarExpr,
+    ) -> Result<VariablePlace> {
+        let element_type = match base.datatype.kind() {
+            SymbolKind::ArrayType => self.array_element_type(&base.datatype)?,
+            SymbolKind::PointerType => self.resolve_type(&base.datatype)?,
+            _ => return Err(DebuggerError::NotIndexable(base_expr.to_string())),
+        };
+        // pointers to void have no element type
+        let Some(element_type) = element_type else {
+            return Err(DebuggerError::NoDatatypeFound);
+        };
+        let Some(element_size) = self.type_size(&element_type)? else {
+            return Err(DebuggerError::SymbolHasNoByteSize);
+        };
+        let offset = index * element_size;
+
+        let storage = match (base.datatype.kind(), &base.storage) {
+            (SymbolKind::ArrayType, PlaceStorage::Memory(addr)) => {
+                PlaceStorage::Memory(*addr + offset)
+            }
+            (SymbolKind::ArrayType, PlaceStorage::Value(VariableValue::OptimizedOut)) => {
+                PlaceStorage::Value(VariableValue::OptimizedOut)
+            }
+            (SymbolKind::ArrayType, PlaceStorage::Value(value)) => {
+                let bytes = match value {
+                    VariableValue::Bytes(b) => b.clone(),
+                    other => other.to_u64().to_le_bytes().to_vec(),
+                };
+                let Some(element) = bytes.get(offset..offset + element_size) else {
+                    return Err(DebuggerError::MemberNotInValue(format!(
+                        "{base_expr}[{index}]"
+                    )));
+                };
+                PlaceStorage::Value(VariableValue::Bytes(element.to_vec()))
+            }
+            _ => {
+                let pointer = self.place_value(&base)?;
+                if matches!(pointer, VariableValue::OptimizedOut) {
+                    return Err(DebuggerError::VariableOptimizedOut);
+                }
+                PlaceStorage::Memory(Addr::from(pointer.to_u64()) + offset)
+            }
+        };
+        Ok(VariablePlace {
+            symbol: base.datatype,
+            datatype: element_type,
+            storage,
+        })
+    }
+
+    /// Follows a C++ reference, so that the data it refers to is used like the reference itself
+    ///
+    /// Places of other types are returned unchanged.
+    fn referenced_place(&self, place: VariablePlace) -> Result<VariablePlace> {
+        if place.datatype.kind() != SymbolKind::ReferenceType {
+            return Ok(place);
+        }
+        let pointer = self.place_value(&place)?;
+        if matches!(pointer, VariableValue::OptimizedOut) {
+            return Err(DebuggerError::VariableOptimizedOut);
+        }
+        let Some(datatype) = self.resolve_type(&place.datatype)? else {
+            return Err(DebuggerError::NoDatatypeFound);
+        };
+        Ok(VariablePlace {
+            symbol: place.datatype,
+            datatype,
+            storage: PlaceStorage::Memory(Addr::from(pointer.to_u64())),
+        })
+    }
+
+    /// Reads the raw value of the data at a place
+    fn place_value(&self, place: &VariablePlace) -> Result<VariableValue> {
+        let addr = match &place.storage {
+            PlaceStorage::Value(value) => return Ok(value.clone()),
+            PlaceStorage::Memory(addr) => *addr,
+        };
+        let Some(size) = self.type_size(&place.datatype)? else {
+            return Err(DebuggerError::SymbolHasNoByteSize);
+        };
+        self.limits.check(LimitKind::ReadSize, size)?;
+        let mut buf = vec![0; size];
+        self.read_bytes(&mut buf, addr)?;
+        Ok(VariableValue::Bytes(buf))
+    }
+
+    /// Reads the value of the data at a place and interprets it with its type
+    ///
+    /// If the data is a pointer and the [`LimitKind::PointerDepth`] limit is not 0, the pointer
+    /// is followed with [`Self::follow_pointer`].
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if the memory of the place cannot be read, if its type has no size
+    /// or if it is larger than the [`LimitKind::ReadSize`] limit.
+    pub fn place_read(&self, place: &VariablePlace) -> Result<VariableValue> {
+        let value = self.place_value(place)?;
+        let value = self.value_of_type(&place.symbol, &place.datatype, value)?;
+        self.follow_pointer(
+            &place.datatype,
+            value,
+            self.limits.get(LimitKind::PointerDepth),
+        )
+    }
+
+    /// Follows the pointer `value` of the resolved type `datatype` to the data it points to
+    ///
+    /// The data is read with the type the pointer points to, and if it is a pointer itself, it is
+    /// followed as well, up to `depth` pointers in total. Null pointers and pointers to memory
+    /// that cannot be read give a [`VariableValue::Pointer`] without a value, so that a bad
+    /// pointer does not make the whole read fail.
+    ///
+    /// Values of other types, pointers to `void` or functions and a `depth` of 0 leave `value`
+    /// unchanged. Pointers inside of structs and arrays are not followed.
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if the type the pointer points to is larger than the
+    /// [`LimitKind::ReadSize`] limit.
+    pub fn follow_pointer(
+        &self,
+        datatype: &OwnedSymbol,
+        value: VariableValue,
+        depth: usize,
+    ) -> Result<VariableValue> {
+        if depth == 0
+            || datatype.kind() != SymbolKind::PointerType
+            || matches!(value, VariableValue::OptimizedOut)
+        {
+            return Ok(value);
+        }
+        let Some(pointee_type) = self.resolve_type(datatype)? else {
+            return Ok(value);
+        };
+        let Some(size) = self.type_size(&pointee_type)? else {
+            return Ok(value);
+        };
+
+        let address = Addr::from(value.to_u64() as usize);
+        if address.usize() == 0 {
+            return Ok(VariableValue::Pointer {
+                address,
+                pointee: Pointee::Null,
+            });
+        }
+        self.limits.check(LimitKind::ReadSize, size)?;
+        let mut buf = vec![0; size];
+        let pointee = match self.read_bytes(&mut buf, address) {
+            Ok(n) if n == size => {
+                let pointee =
+                    self.value_of_type(datatype, &pointee_type, VariableValue::Bytes(buf))?;
+                let pointee = self.follow_pointer(&pointee_type, pointee, depth - 1)?;
+                Pointee::Value(Box::new(pointee))
+            }
+            Ok(n) => {
+                trace!("only {n} of {size} bytes at {address} could be read");
+                Pointee::Unreadable
+            }
+            Err(e) => {
+                trace!("could not read the pointee at {address}: {e}");
+                Pointee::Unreadable
+            }
+        };
+        Ok(VariableValue::Pointer { address, pointee })
+    }
+
+    /// Writes a value to the data at a place, in the size of its type
+    ///
+    /// Only data in memory can be written. Variables in registers are written with
+    /// [`Self::var_write`].
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if the place is not in memory, if its type has no size, if the
+    /// value does not fit into it or if the memory cannot be written. `expr` is the expression of
+    /// the place, which is named in errors.
+    pub fn place_write(
+        &self,
+        place: &VariablePlace,
+        expr: &VarExpr,
+        value: &VariableValue,
+    ) -> Result<()> {
+        let Some(addr) = place.address() else {
+            return Err(DebuggerError::NotAddressable(expr.to_string()));
+        };
+        let Some(byte_size) = self.type_size(&place.datatype)? else {
+            return Err(DebuggerError::SymbolHasNoByteSize);
+        };
+        let value_raw = value_bytes(value, byte_size)?;
+        trace!("writing {expr} to {addr}");
+        if self.verify_writes {
+            mem_write_verified(&value_raw, self.pid, addr)?;
+        } else {
+            mem_write(&value_raw, self.pid, addr)?;
+        }
+        Ok(())
+    }
+
+    /// Writes a value to one element of an array variable
+    ///
+    /// This is like writing the expression `name[index]` with [`Self::place_write`], the array
+    /// has to be in memory.
+    ///
+    /// # Parameters
+    ///
+    /// * `sym` - The symbol representing the array variable
+    /// * `frame_info` - Stack frame information
+    /// * `index` - The index of the element
+    /// * `value` - The value to write
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if the variable is not an array or pointer in memory, if the value
+    /// does not fit into the element or if the memory cannot be written.
+    pub fn var_element_write(
+        &self,
+        sym: &OwnedSymbol,
+        frame_info: &FrameInfo,
+        index: usize,
+        value: &VariableValue,
+    ) -> Result<()> {
+        let name = sym.name().unwrap_or_default().to_string();
+        let expr = VarExpr::Index(Box::new(VarExpr::Variable(name)), index);
+        let place = self.expr_place(sym, frame_info, &expr)?;
+        self.place_write(&place, &expr, value)
+    }
+
+    /// Interprets the value of a variable with its type
+    ///
+    /// Values of base types are decoded with their encoding (`DW_AT_encoding`) and byte size,
+    /// for example as a signed integer or a floating point number. Values of enumerations are
+    /// named with [`Self::name_enum_value`], and values of Rust enums with the variant that their
+    /// discriminant selects. Values of structs, classes and unions are decoded member by member
+    /// into a [`VariableValue::Struct`], and arrays element by element into a
+    /// [`VariableValue::Array`]. Values of other types, like pointers, and values that do not fit
+    /// their type are returned unchanged.
+    ///
+    /// # Parameters
+    ///
+    /// * `sym` - The symbol that `value` was read for, a variable or member
+    /// * `value` - The raw value of the variable, as read by [`Self::var_read`]
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(VariableValue)` - The typed or the unchanged value
+    /// * `Err(DebuggerError)` - If the type of the variable could not be looked up
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if the symbol tree is inconsistent.
+    pub fn typed_value(&self, sym: &OwnedSymbol, value: VariableValue) -> Result<VariableValue> {
+        let Some(datatype) = self.resolve_type(sym)? else {
+            return Ok(value);
+        };
+        self.value_of_type(sym, &datatype, value)
+    }
+
+    /// Interprets a value with the resolved type `datatype`, see [`Self::typed_value`]
+    ///
+    /// `sym` is the symbol that the value belongs to, whose type can have a name that `datatype`
+    /// lacks, like a typedef of an anonymous enumeration.
+    fn value_of_type(
+        &self,
+        sym: &OwnedSymbol,
+        datatype: &OwnedSymbol,
+        value: VariableValue,
+    ) -> Result<VariableValue> {
+        if matches!(value, VariableValue::OptimizedOut) {
+            return Ok(value);
+        }
+        if datatype
+            .children()
+            .iter()
+            .any(|e| e.kind() == SymbolKind::Enumerator)
+        {
+            return self.name_enum_value(sym, value);
+        }
+        if let (SymbolKind::StructType | SymbolKind::UnionType, Some(size)) =
+            (datatype.kind(), datatype.byte_size())
+        {
+            let bytes = match &value {
+                VariableValue::Bytes(b) => b.clone(),
+                // small structs can be kept in a register
+                other => {
+                    let mut bytes = other.to_u64().to_le_bytes().to_vec();
+                    bytes.truncate(size);
+                    bytes
+                }
+            };
+            if let Some(part) = datatype
+                .children()
+                .iter()
+                .find(|c| c.kind() == SymbolKind::VariantPart)
+            {
+                return self.variant_value(datatype, part, &bytes);
+            }
+            return self.struct_value(datatype, &bytes);
+        }
+        if datatype.kind() == SymbolKind::ArrayType {
+            return self.array_value(datatype, value);
+        }
+        let (Some(encoding), Some(size)) = (datatype.base_encoding(), datatype.byte_size()) else {
+            return Ok(value);
+        };
+        Ok(decode_base_value(&value, encoding, size).unwrap_or(value))
+    }
+
+    /// Names the value of a variable if its type is an enumeration
+    ///
+    /// The enumerator with the same value is looked up in the type of `sym`. If there is one,
+    /// a [`VariableValue::Enum`] is returned, otherwise `value` is returned unchanged, as it
+    /// might be a combination of flags or simply invalid.
+    ///
+    /// # Parameters
+    ///
+    /// * `sym` - The symbol that `value` was read for, a variable or member
+    /// * `value` - The value of the variable
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(VariableValue)` - The named or the unchanged value
+    /// * `Err(DebuggerError)` - If the type of the variable could not be looked up
+    ///
+    /// # Errors
+    ///
+    /// This function can fail if the symbol tree is inconsistent.
+    pub fn name_enum_value(
+        &self,
+        sym: &OwnedSymbol,
+        value: VariableValue,
+    ) -> Result<VariableValue> {
+        let Some(datatype) = self.resolve_type(sym)? else {
+            return Ok(value);
+        };
+        let enumerators: Vec<&OwnedSymbol> = datatype
+            .children()
+            .iter()
+            .filter(|e| e.kind() == SymbolKind::Enumerator)
+            .collect();
+        if enumerators.is_empty() {
+            return Ok(value);
+        }
+
+        // only compare the bytes of the enumeration, the rest of a register can be garbage
+        let mask = low_bytes_mask(datatype.byte_size().unwrap_or(WORD_BYTES));
+        let raw = match &value {
+            VariableValue::Bytes(b) if b.len() > WORD_BYTES => return Ok(value),
+            VariableValue::OptimizedOut => return Ok(value),
+            other => other.to_u64() & mask,
+        };
+
+        let Some((enumerator, enum_value)) = enumerators.iter().find_map(|e| {
+            e.const_value()
+                .filter(|v| *v as u64 & mask == raw)
+                .map(|v| (e, v))
+        }) else {
+            return Ok(value);
+        };
+        let enumerator_name = enumerator.name().unwrap_or_default();
+        // anonymous enumerations are often named by a typedef
+        let type_name = match datatype.name() {
+            Some(name) => Some(name.to_string()),
+            None => self
+                .get_type_for_symbol(sym)?
+                .and_then(|t| t.name().map(str::to_string)),
+        };
+        Ok(VariableValue::Enum {
+            name: match type_name {
+                Some(type_name) => format!("{type_name}::{enumerator_name}"),
+                None => enumerator_name.to_string(),
+            },
+            value: enum_value,
+            fields: Vec::new(),
+        })
+    }
+
+    /// Finds the type of a symbol, looking through typedefs and qualifiers like `const`
+    ///
+    /// Those are types without a size of their own that only refer to another type. Arrays often
+    /// have no size either, but are types of their own.
+    fn resolve_type(&self, sym: &OwnedSymbol) -> Result<Option<OwnedSymbol>> {
+        let mut datatype = self.get_type_for_symbol(sym)?;
+        while let Some(t) = &datatype {
+            if t.byte_size().is_some()
+                || t.datatype().is_none()
+                || t.kind() == SymbolKind::ArrayType
+            {
+                break;
+            }
+            datatype = self.get_type_for_symbol(t)?;
+        }
+        Ok(datatype)
+    }
+
+    /// Reads the lowest `len` bytes of a vector register that holds a variable
+    ///
+    /// Values of up to 16 bytes are read from the `xmm` register, larger ones from the `ymm`
+    /// register.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the frame is not the innermost one, since the vector registers are not preserved
+    /// across calls, if the value is too large for a vector register, or if the register cannot be
+    /// read.
+    fn vector_register_read(
+        &self,
+        register: FpRegister,
+        frame_info: &FrameInfo,
+        len: usize,
+    ) -> Result<Vec<u8>> {
+        if frame_info.registers.is_some() {
+            return Err(DebuggerError::OuterFrameVectorRegister(register));
+        }
+        let value = if len <= XMM_BYTES {
+            get_xmm(self.pid, register)?.to_vec()
+        } else if len <= YMM_BYTES {
+            get_ymm(self.pid, register)?.to_vec()
+        } else {
+            return Err(DebuggerError::VectorValueTooLarge(len));
+        };
+        Ok(value[..len].to_vec())
+    }
+
+    /// Writes a variable to the lowest bytes of a vector register
+    ///
+    /// This is the counterpart of [`Self::vector_register_read`].
+    ///
+    /// # Errors
+    ///
+    /// Fails if the frame is not the innermost one, if the value is too large for a vector
+    /// register, or if the register cannot be written.
+    fn vector_register_write(
+        &self,
+        register: FpRegister,
+        frame_info: &FrameInfo,
+        value: &[u8],
+    ) -> Result<()> {
+        if frame_info.registers.is_some() {
+            return Err(DebuggerError::OuterFrameVectorRegister(register));
+        }
+        if value.len() <= XMM_BYTES {
+            set_xmm(self.pid, register, value)
+        } else {
+            set_ymm(self.pid, register, value)
+        }
+    }
+}
+
+/// Returns a mask of the lowest `size` bytes of a [`Word`], or of the whole word if it is larger
+fn low_bytes_mask(size: usize) -> u64 {
+    if size >= WORD_BYTES {
+        u64::MAX
+    } else {
+        (1 << (size * 8)) - 1
+    }
+}
+
+/// Returns the number of elements in the first dimension of an array type, if it is known
+fn array_count(array: &OwnedSymbol) -> Option<usize> {
+    array
+        .children()
+        .iter()
+        .find(|c| c.kind() == SymbolKind::SubrangeType)
+        .and_then(OwnedSymbol::count)
+}
+
+/// Converts a value to the `size` bytes that are written for it
+///
+/// Numbers are truncated to the size. Values of more than 8 bytes, like whole structs or arrays,
+/// can only be written from raw bytes of the exact size.
+fn value_bytes(value: &VariableValue, size: usize) -> Result<Vec<u8>> {
+    match value {
+        VariableValue::Bytes(b) if b.len() == size => Ok(b.clone()),
+        VariableValue::Bytes(b) if b.len() > WORD_BYTES => {
+            Err(DebuggerError::ValueDoesNotFit(size))
+        }
+        _ if size > WORD_BYTES => Err(DebuggerError::ValueDoesNotFit(size)),
+        other => Ok(other.resize_to_bytes(size)),
+    }
+}
+
+/// Returns `true` if `member` is a static member of the C++ class `datatype`
+///
+/// Static members are declared as members without a location, they are stored somewhere else.
+fn is_static_member(datatype: &OwnedSymbol, member: &OwnedSymbol) -> bool {
+    datatype.kind() == SymbolKind::StructType
+        && member.member_offset().is_none()
+        && member.data_bit_offset().is_none()
+}
+
+/// Decodes the lowest `size` bytes of `value` as a value of a base type with `encoding`
+///
+/// Returns [None] for encodings and sizes that have no matching [`VariableValue`], like `long
+/// double`, or if `value` has less than `size` bytes.
+fn decode_base_value(
+    value: &VariableValue,
+    encoding: gimli::DwAte,
+    size: usize,
+) -> Option<VariableValue> {
+    if encoding == gimli::DW_ATE_float && (size == 10 || size == 16) {
+        return match value {
+            VariableValue::Bytes(b) if b.len() >= 10 => Some(VariableValue::Float(
+                x87_extended_to_f64(b[..10].try_into().ok()?),
+            )),
+            _ => None,
+        };
+    }
+    if size == 0 || size > WORD_BYTES {
+        return None;
+    }
+    let raw = match value {
+        VariableValue::Bytes(b) if b.len() < size => return None,
+        VariableValue::Bytes(b) => crate::bytes_to_u64(&b[..size]).ok()?,
+        other => other.to_u64(),
+    };
+    let unused = (WORD_BYTES - size) * 8;
+    let unsigned = (raw << unused) >> unused;
+    let signed = ((raw << unused) as i64) >> unused;
+
+    Some(match encoding {
+        gimli::DW_ATE_signed => VariableValue::Signed(signed),
+        gimli::DW_ATE_unsigned => VariableValue::Unsigned(unsigned),
+        gimli::DW_ATE_boolean => VariableValue::Bool(unsigned != 0),
+        gimli::DW_ATE_float if size == 4 => {
+            VariableValue::Float(f32::from_bits(unsigned as u32).into())
+        }
+        gimli::DW_ATE_float if size == 8 => VariableValue::Float(f64::from_bits(unsigned)),
+        // `char` in C is a one byte character, but signed or unsigned depending on the platform
+        gimli::DW_ATE_signed_char | gimli::DW_ATE_unsigned_char if size == 1 => {
+            VariableValue::Char(char::from(unsigned as u8))
+        }
+        gimli::DW_ATE_UTF => VariableValue::Char(char::from_u32(unsigned as u32)?),
+        _ => return None,
+    })
+}
+
+/// Converts an x87 extended precision number, used for `long double`, to the nearest [f64]
+#[allow(clippy::cast_precision_loss)] // the mantissa has more bits than an f64, it is rounded
+fn x87_extended_to_f64(bytes: [u8; 10]) -> f64 {
+    let mut mantissa = [0; 8];
+    mantissa.copy_from_slice(&bytes[..8]);
+    // unlike in an f64, the integer bit of the mantissa is stored explicitly
+    let mantissa = u64::from_le_bytes(mantissa);
+    let sign_exponent = u16::from_le_bytes([bytes[8], bytes[9]]);
+    let sign = if sign_exponent & 0x8000 == 0 {
+        1.0
+    } else {
+        -1.0
+    };
+    let exponent = i32::from(sign_exponent & 0x7fff);
+
+    if exponent == 0x7fff {
+        return if mantissa << 1 == 0 {
+            sign * f64::INFINITY
+        } else {
+            f64::NAN
+        };
+    }
+    // denormals have the exponent of the smallest normal number
+    let exponent = exponent.max(1) - 16383 - 63;
+    // scaled in two steps, so that neither factor overflows before the result does
+    sign * mantissa as f64 * 2f64.powi(exponent / 2) * 2f64.powi(exponent - exponent / 2)
+}
+
+/// Extracts `bit_size` bits at `bit_offset` from `bytes`, which are in little endian order
+///
+/// Returns [None] if the bits are not inside of `bytes` or there are more than 64 of them.
+fn extract_bits(bytes: &[u8], bit_offset: usize, bit_size: usize, signed: bool) -> Option<u64> {
+    if bit_size == 0 || bit_size > 64 {
+        return None;
+    }
+    let storage = bytes.get(bit_offset / 8..(bit_offset + bit_size).div_ceil(8))?;
+    let mut raw = [0; 16];
+    raw[..storage.len()].copy_from_slice(storage);
+    let value = (u128::from_le_bytes(raw) >> (bit_offset % 8)) as u64;
+
+    let unused = 64 - bit_size;
+    Some(if signed {
+        (((value << unused) as i64) >> unused) as u64
+    } else {
+        (value << unused) >> unused
+    })
 }
 
 fn serialize_gimli_value<S>(
@@ -451,6 +1818,290 @@ where
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::feedback::Feedback;
+
+    #[test]
+    fn test_enum_value() {
+        let v = VariableValue::Enum {
+            name: "Color::Neg".to_string(),
+            value: -4,
+            fields: Vec::new(),
+        };
+        assert_eq!(v.to_u64(), -4i64 as u64);
+        assert_eq!(v.resize_to_bytes(4), vec![0xfc, 0xff, 0xff, 0xff]);
+        assert_eq!(
+            Feedback::Variable(v).to_string(),
+            "Variable: Color::Neg (-4)"
+        );
+
+        let v = VariableValue::Enum {
+            name: "Option<u32>::Some".to_string(),
+            value: 1,
+            fields: vec![("__0".to_string(), VariableValue::Unsigned(7))],
+        };
+        assert_eq!(v.to_string(), "Option<u32>::Some (1) { __0: 7 }");
+    }
+
+    fn symbol(
+        offset: usize,
+        kind: SymbolKind,
+        name: &str,
+        children: &[OwnedSymbol],
+    ) -> OwnedSymbol {
+        let encoding = gimli::Encoding {
+            format: gimli::Format::Dwarf32,
+            version: 4,
+            address_size: 8,
+        };
+        let mut sym = OwnedSymbol::new(offset, kind, children, encoding);
+        sym.set_name(Some(name.to_string()));
+        sym
+    }
+
+    fn member(offset: usize, name: &str, datatype: usize, member_offset: usize) -> OwnedSymbol {
+        let mut member = symbol(offset, SymbolKind::Member, name, &[]);
+        member.set_datatype(Some(datatype));
+        member.set_member_offset(Some(member_offset));
+        member
+    }
+
+    fn variant(offset: usize, discr_value: Option<i64>, data: OwnedSymbol) -> OwnedSymbol {
+        let mut variant = symbol(offset, SymbolKind::Variant, "", &[data]);
+        variant.set_discr_value(discr_value);
+        variant
+    }
+
+    fn sized(mut sym: OwnedSymbol, byte_size: usize) -> OwnedSymbol {
+        sym.set_byte_size(Some(byte_size));
+        sym
+    }
+
+    #[test]
+    fn test_variant_value() {
+        let mut u8_type = sized(symbol(1, SymbolKind::BaseType, "u8", &[]), 1);
+        u8_type.set_base_encoding(Some(gimli::DW_ATE_unsigned));
+        let mut u32_type = sized(symbol(2, SymbolKind::BaseType, "u32", &[]), 4);
+        u32_type.set_base_encoding(Some(gimli::DW_ATE_unsigned));
+        let mut u64_type = sized(symbol(3, SymbolKind::BaseType, "u64", &[]), 8);
+        u64_type.set_base_encoding(Some(gimli::DW_ATE_unsigned));
+
+        // enum Shape { Circle(u32), Empty } with the discriminant in a u8 tag before the data
+        let circle = sized(
+            symbol(
+                10,
+                SymbolKind::StructType,
+                "Circle",
+                &[member(11, "__0", 2, 4)],
+            ),
+            8,
+        );
+        let empty = sized(symbol(12, SymbolKind::StructType, "Empty", &[]), 8);
+        let mut shape_part = symbol(
+            21,
+            SymbolKind::VariantPart,
+            "",
+            &[
+                member(22, "tag", 1, 0),
+                variant(23, Some(0), member(24, "Circle", 10, 0)),
+                variant(25, Some(1), member(26, "Empty", 12, 0)),
+            ],
+        );
+        shape_part.set_discr(Some(22));
+        let shape = sized(
+            symbol(20, SymbolKind::StructType, "Shape", &[shape_part]),
+            8,
+        );
+
+        // Option<NonZeroU64>, where the data is the discriminant and zero is None
+        let some = sized(
+            symbol(
+                30,
+                SymbolKind::StructType,
+                "Some",
+                &[member(31, "__0", 3, 0)],
+            ),
+            8,
+        );
+        let none = sized(symbol(32, SymbolKind::StructType, "None", &[]), 8);
+        let mut option_part = symbol(
+            41,
+            SymbolKind::VariantPart,
+            "",
+            &[
+                member(42, "tag", 3, 0),
+                variant(43, Some(0), member(44, "None", 32, 0)),
+                variant(45, None, member(46, "Some", 30, 0)),
+            ],
+        );
+        option_part.set_discr(Some(42));
+        let option = sized(
+            symbol(
+                40,
+                SymbolKind::StructType,
+                "Option<NonZeroU64>",
+                &[option_part],
+            ),
+            8,
+        );
+
+        let symbols = vec![u8_type, u32_type, u64_type, circle, empty, some, none];
+        let executable = crate::modules::LoadedModule {
+            path: String::new(),
+            base: Addr::from(0usize),
+            bias: 0,
+        };
+        let dbge = Debuggee::from_symbols(nix::unistd::getpid(), symbols, executable);
+        let decode = |datatype: &OwnedSymbol, bytes: &[u8]| {
+            dbge.variant_value(datatype, &datatype.children()[0], bytes)
+                .unwrap()
+                .to_string()
+        };
+
+        assert_eq!(
+            decode(&shape, &[0, 0xff, 0xff, 0xff, 7, 0, 0, 0]),
+            "Shape::Circle (0) { __0: 7 }"
+        );
+        assert_eq!(
+            decode(&shape, &[1, 0, 0, 0, 7, 0, 0, 0]),
+            "Shape::Empty (1)"
+        );
+        // no variant has the discriminant, so the raw bytes are kept
+        assert!(matches!(
+            dbge.variant_value(&shape, &shape.children()[0], &[2; 8]),
+            Ok(VariableValue::Bytes(_))
+        ));
+
+        assert_eq!(decode(&option, &[0; 8]), "Option<NonZeroU64>::None (0)");
+        assert_eq!(
+            decode(&option, &0x1234u64.to_le_bytes()),
+            "Option<NonZeroU64>::Some (4660) { __0: 4660 }"
+        );
+    }
+
+    #[test]
+    fn test_struct_value() {
+        let v = VariableValue::Struct {
+            fields: vec![
+                ("x".to_string(), VariableValue::Signed(-1)),
+                (
+                    "inner".to_string(),
+                    VariableValue::Struct {
+                        fields: vec![("ok".to_string(), VariableValue::Bool(true))],
+                    },
+                ),
+                (
+                    "empty".to_string(),
+                    VariableValue::Struct { fields: vec![] },
+                ),
+            ],
+        };
+        assert_eq!(v.to_string(), "{ x: -1, inner: { ok: true }, empty: {} }");
+        assert_eq!(v.byte_size(), WORD_BYTES + 1);
+        assert_eq!(v.to_u64(), 0);
+    }
+
+    #[test]
+    fn test_pointer_value() {
+        let address = Addr::from(0x7ffd_1000_usize);
+        let v = VariableValue::Pointer {
+            address,
+            pointee: Pointee::Value(Box::new(VariableValue::Pointer {
+                address: Addr::from(0usize),
+                pointee: Pointee::Null,
+            })),
+        };
+        assert_eq!(
+            v.to_string(),
+            "0x000000007ffd1000 -> 0x0000000000000000 (null)"
+        );
+        assert_eq!(v.to_u64(), 0x7ffd_1000);
+        assert_eq!(v.byte_size(), WORD_BYTES);
+
+        let v = VariableValue::Pointer {
+            address,
+            pointee: Pointee::Unreadable,
+        };
+        assert_eq!(v.to_string(), "0x000000007ffd1000 (unreadable)");
+    }
+
+    #[test]
+    fn test_array_value() {
+        let v = VariableValue::Array(vec![
+            VariableValue::Array(vec![VariableValue::Signed(1), VariableValue::Signed(-2)]),
+            VariableValue::Array(vec![]),
+        ]);
+        assert_eq!(v.to_string(), "[[1, -2], []]");
+        assert_eq!(v.to_u64(), 0);
+
+        assert_eq!(
+            value_bytes(&VariableValue::from(0x1234usize), 2).unwrap(),
+            vec![0x34, 0x12]
+        );
+        assert_eq!(
+            value_bytes(&VariableValue::Bytes(vec![7; 12]), 12).unwrap(),
+            vec![7; 12]
+        );
+        assert!(value_bytes(&VariableValue::from(1usize), 12).is_err());
+        assert!(value_bytes(&VariableValue::Bytes(vec![7; 12]), 4).is_err());
+    }
+
+    #[test]
+    fn test_typed_values() {
+        let raw = VariableValue::Bytes(vec![0xfd, 0xff, 0xff, 0xff]);
+        let v = decode_base_value(&raw, gimli::DW_ATE_signed, 4).unwrap();
+        assert_eq!(v.to_string(), "-3");
+        let v = decode_base_value(&raw, gimli::DW_ATE_unsigned, 2).unwrap();
+        assert_eq!(v.to_string(), "65533");
+        let v = decode_base_value(&raw, gimli::DW_ATE_boolean, 1).unwrap();
+        assert_eq!(v.to_string(), "true");
+
+        let raw = VariableValue::Bytes(1.5f32.to_le_bytes().to_vec());
+        let v = decode_base_value(&raw, gimli::DW_ATE_float, 4).unwrap();
+        assert_eq!(v.to_string(), "1.5");
+        let raw = VariableValue::Other((-2.25f64).to_bits() as Word);
+        let v = decode_base_value(&raw, gimli::DW_ATE_float, 8).unwrap();
+        assert_eq!(v.to_string(), "-2.25");
+
+        // long double 3.0, as stored by gcc with padding
+        let raw = VariableValue::Bytes(vec![0, 0, 0, 0, 0, 0, 0, 0xc0, 0, 0x40, 0, 0, 0, 0, 0, 0]);
+        let v = decode_base_value(&raw, gimli::DW_ATE_float, 16).unwrap();
+        assert_eq!(v.to_string(), "3");
+
+        let raw = VariableValue::Bytes(vec![b'A']);
+        let v = decode_base_value(&raw, gimli::DW_ATE_signed_char, 1).unwrap();
+        assert_eq!(v.to_string(), "'A' (65)");
+
+        // too few bytes for the type
+        assert!(decode_base_value(&raw, gimli::DW_ATE_signed, 4).is_none());
+    }
+
+    #[test]
+    fn test_optimized_out() {
+        let v = VariableValue::OptimizedOut;
+        assert_eq!(v.to_u64(), 0);
+        assert_eq!(v.to_string(), "<optimized out>");
+        assert_eq!(
+            Feedback::Variable(v).to_string(),
+            "Variable: <optimized out>"
+        );
+    }
+
+    #[test]
+    fn test_extract_bits() {
+        // struct { unsigned a : 3; unsigned b : 5; int c : 4; } with a = 5, b = 17, c = -3
+        let bytes = [0b1000_1101, 0b0000_1101, 0, 0];
+        assert_eq!(extract_bits(&bytes, 0, 3, false), Some(5));
+        assert_eq!(extract_bits(&bytes, 3, 5, false), Some(17));
+        assert_eq!(extract_bits(&bytes, 8, 4, true), Some(-3i64 as u64));
+        assert_eq!(extract_bits(&bytes, 8, 4, false), Some(13));
+
+        // across byte boundaries
+        assert_eq!(extract_bits(&[0xf0, 0x0f], 4, 8, false), Some(0xff));
+        assert_eq!(extract_bits(&[0xff; 9], 4, 64, false), Some(u64::MAX));
+
+        assert_eq!(extract_bits(&bytes, 30, 4, false), None);
+        assert_eq!(extract_bits(&bytes, 0, 0, false), None);
+    }
 
     #[test]
     fn test_variable_value_sizing() {
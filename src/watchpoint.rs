@@ -0,0 +1,507 @@
+//! # Watchpoint Module
+//!
+//! Provides software watchpoints (data breakpoints) on arbitrary memory ranges of a debugged process.
+//!
+//! Unlike [Breakpoints](crate::breakpoint::Breakpoint), which stop the debuggee when a certain
+//! instruction is executed, a [`Watchpoint`] stops the debuggee when the contents of a memory range
+//! change. This is implemented purely in software: the debugger takes a snapshot of the watched
+//! range and compares it to the current contents after every single step. When the contents
+//! differ, the instruction that was executed last is the one responsible for the change.
+//!
+//! This approach works for buffers of any size, but it is slow, since the debuggee needs to be
+//! single stepped while a [`Watchpoint`] is active.
+//!
+//! A [`Watchpoint`] can have a [`WatchCondition`], like `new == 0`. Changes for which the
+//! condition does not hold are not reported, the debuggee just keeps running.
+
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+use nix::unistd::Pid;
+use serde::{Deserialize, Serialize};
+use tracing::trace;
+
+use crate::errors::{DebuggerError, Result};
+use crate::{mem_read, Addr};
+
+/// The widest memory range in bytes that a [`WatchCondition`] can be evaluated for
+pub const MAX_CONDITION_WIDTH: usize = 8;
+
+/// Watches a range of memory in the debugged process for changes
+///
+/// A [`Watchpoint`] stores a snapshot of the watched memory range. The snapshot is compared
+/// against the current contents of the range with [`Watchpoint::check`]. If the contents changed,
+/// a [`WatchpointHit`] is created and the snapshot is updated to the new contents.
+///
+/// # Examples
+///
+/// ```no_run
+/// use coreminer::watchpoint::Watchpoint;
+/// use coreminer::addr::Addr;
+/// use nix::unistd::Pid;
+///
+/// // Watch 16 bytes at address 0x00007ffd2b4a1f30 of the process with PID 1234
+/// let pid = Pid::from_raw(1234);
+/// let mut wp = Watchpoint::new(pid, Addr::from(0x00007ffd2b4a1f30usize), 16).unwrap();
+///
+/// // ... let the debuggee execute an instruction at 0x000055dd73ea3fb8 ...
+///
+/// if let Some(hit) = wp.check(pid, Addr::from(0x000055dd73ea3fb8usize)).unwrap() {
+///     println!("{} changed the watched memory", hit.instruction);
+/// }
+/// ```
+#[derive(Debug, Clone, Hash, Serialize)]
+pub struct Watchpoint {
+    addr: Addr,
+    len: usize,
+    snapshot: Vec<u8>,
+    condition: Option<WatchCondition>,
+}
+
+/// Describes a change of a memory range that is watched by a [`Watchpoint`]
+#[derive(Debug, Clone, Hash, PartialEq, Eq, Serialize)]
+pub struct WatchpointHit {
+    /// Start address of the watched memory range
+    pub addr: Addr,
+    /// Length of the watched memory range in bytes
+    pub len: usize,
+    /// Address of the instruction that changed the watched memory
+    pub instruction: Addr,
+    /// Contents of the watched memory before the change
+    pub old: Vec<u8>,
+    /// Contents of the watched memory after the change
+    pub new: Vec<u8>,
+}
+
+impl Watchpoint {
+    /// Creates a new [`Watchpoint`] and takes the initial snapshot of the memory range
+    ///
+    /// # Parameters
+    ///
+    /// * `pid` - Process ID of the target process
+    /// * `addr` - Start address of the memory range to watch
+    /// * `len` - Length of the memory range to watch in bytes
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Watchpoint)` - The new watchpoint
+    /// * `Err(DebuggerError)` - If the memory range could not be read
+    ///
+    /// # Errors
+    ///
+    /// This function will fail if the memory of the debuggee cannot be read.
+    pub fn new(pid: Pid, addr: Addr, len: usize) -> Result<Self> {
+        let mut wp = Self {
+            addr,
+            len,
+            snapshot: Vec::new(),
+            condition: None,
+        };
+        wp.snapshot = wp.read_range(pid)?;
+        Ok(wp)
+    }
+
+    /// Checks if the watched memory range has changed since the last snapshot
+    ///
+    /// If the contents changed, the snapshot is updated to the current contents. A change for
+    /// which the [`WatchCondition`] of the [`Watchpoint`] does not hold is not reported.
+    ///
+    /// # Parameters
+    ///
+    /// * `pid` - Process ID of the target process
+    /// * `instruction` - Address of the instruction that was executed last
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Some(WatchpointHit))` - If the contents of the range have changed
+    /// * `Ok(None)` - If the contents of the range are still the same, or the condition does not
+    ///   hold for the change
+    /// * `Err(DebuggerError)` - If the memory range could not be read
+    ///
+    /// # Errors
+    ///
+    /// This function will fail if the memory of the debuggee cannot be read.
+    pub fn check(&mut self, pid: Pid, instruction: Addr) -> Result<Option<WatchpointHit>> {
+        let current = self.read_range(pid)?;
+        Ok(self.compare(current, instruction))
+    }
+
+    /// Compares the snapshot with `current` and updates the snapshot if they differ
+    fn compare(&mut self, current: Vec<u8>, instruction: Addr) -> Option<WatchpointHit> {
+        if current == self.snapshot {
+            return None;
+        }
+        let old = std::mem::replace(&mut self.snapshot, current);
+        let hit = WatchpointHit {
+            addr: self.addr,
+            len: self.len,
+            instruction,
+            old,
+            new: self.snapshot.clone(),
+        };
+        if self
+            .condition
+            .as_ref()
+            .map_or(true, |cond| cond.matches(&hit))
+        {
+            Some(hit)
+        } else {
+            trace!(
+                "watched memory at {} changed, but the condition {} does not hold",
+                self.addr,
+                self.condition.as_ref().expect("a condition was checked")
+            );
+            None
+        }
+    }
+
+    /// Sets or removes the [`WatchCondition`] that a change must fulfill to be reported
+    ///
+    /// # Errors
+    ///
+    /// This function will fail if a condition is given and the watched memory range is wider
+    /// than [`MAX_CONDITION_WIDTH`].
+    pub fn set_condition(&mut self, condition: Option<WatchCondition>) -> Result<()> {
+        if condition.is_some() && !(1..=MAX_CONDITION_WIDTH).contains(&self.len) {
+            return Err(DebuggerError::WatchConditionTooWide(self.len));
+        }
+        self.condition = condition;
+        Ok(())
+    }
+
+    /// Returns the [`WatchCondition`] that a change must fulfill to be reported, if there is one
+    #[inline]
+    #[must_use]
+    pub fn condition(&self) -> Option<&WatchCondition> {
+        self.condition.as_ref()
+    }
+
+    /// Reads the current contents of the watched memory range
+    fn read_range(&self, pid: Pid) -> Result<Vec<u8>> {
+        let mut buf = vec![0; self.len];
+        let read = mem_read(&mut buf, pid, self.addr)?;
+        buf.truncate(read);
+        Ok(buf)
+    }
+
+    /// Returns the start address of the watched memory range
+    #[inline]
+    #[must_use]
+    pub fn addr(&self) -> Addr {
+        self.addr
+    }
+
+    /// Returns the length of the watched memory range in bytes
+    #[inline]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the watched memory range has a length of zero
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the last snapshot of the watched memory range
+    #[inline]
+    #[must_use]
+    pub fn snapshot(&self) -> &[u8] {
+        &self.snapshot
+    }
+}
+
+/// A comparison operator of a [`WatchCondition`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CompareOp {
+    /// `==`
+    Eq,
+    /// `!=`
+    Ne,
+    /// `<`
+    Lt,
+    /// `<=`
+    Le,
+    /// `>`
+    Gt,
+    /// `>=`
+    Ge,
+}
+
+impl CompareOp {
+    /// The operators with their symbols, two character symbols first so that they are found
+    /// before their prefixes
+    const SYMBOLS: [(&'static str, Self); 6] = [
+        ("==", Self::Eq),
+        ("!=", Self::Ne),
+        ("<=", Self::Le),
+        (">=", Self::Ge),
+        ("<", Self::Lt),
+        (">", Self::Gt),
+    ];
+
+    /// Applies the operator to `lhs` and `rhs`
+    fn apply(self, lhs: i64, rhs: i64) -> bool {
+        match self {
+            Self::Eq => lhs == rhs,
+            Self::Ne => lhs != rhs,
+            Self::Lt => lhs < rhs,
+            Self::Le => lhs <= rhs,
+            Self::Gt => lhs > rhs,
+            Self::Ge => lhs >= rhs,
+        }
+    }
+}
+
+impl Display for CompareOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let symbol = Self::SYMBOLS
+            .iter()
+            .find(|(_, op)| op == self)
+            .map_or("?", |(symbol, _)| symbol);
+        write!(f, "{symbol}")
+    }
+}
+
+/// A side of the comparison of a [`WatchCondition`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Operand {
+    /// The contents of the watched memory range after the change
+    New,
+    /// The contents of the watched memory range before the change
+    Old,
+    /// A constant
+    Value(i64),
+}
+
+impl Operand {
+    /// Returns the value of the operand for `hit`
+    ///
+    /// The contents of the range are read as a signed little endian integer of the width of the
+    /// range. Constants are cut to that width as well, so that `0xff` and `-1` are the same for a
+    /// range of one byte.
+    fn value(self, hit: &WatchpointHit) -> i64 {
+        match self {
+            Self::New => sign_extend(&hit.new),
+            Self::Old => sign_extend(&hit.old),
+            Self::Value(v) => {
+                let width = hit.new.len().min(MAX_CONDITION_WIDTH);
+                sign_extend(&v.to_le_bytes()[..width])
+            }
+        }
+    }
+}
+
+impl FromStr for Operand {
+    type Err = DebuggerError;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let s = s.trim();
+        let invalid = || DebuggerError::InvalidWatchCondition(s.to_string());
+        match s {
+            "new" => return Ok(Self::New),
+            "old" => return Ok(Self::Old),
+            _ => (),
+        }
+        let (negative, digits) = match s.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, s),
+        };
+        let magnitude = match digits.strip_prefix("0x") {
+            Some(hex) => u64::from_str_radix(hex, 16),
+            None => digits.parse::<u64>(),
+        }
+        .map_err(|_| invalid())?;
+        // constants are cut to the width of the range anyway, so u64 values may wrap
+        let value = magnitude as i64;
+        Ok(Self::Value(if negative {
+            value.wrapping_neg()
+        } else {
+            value
+        }))
+    }
+}
+
+impl Display for Operand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::New => write!(f, "new"),
+            Self::Old => write!(f, "old"),
+            Self::Value(v) => write!(f, "{v}"),
+        }
+    }
+}
+
+/// A condition that a change of a [`Watchpoint`] must fulfill to be reported
+///
+/// A condition compares two [Operands](Operand) with a [`CompareOp`]. It is parsed from
+/// expressions like `new == 0`, `old < new` or `!= 0x10`. If the left side is left out, it is the
+/// new contents of the range. The contents are compared as signed integers, so the watched memory
+/// range can be at most [`MAX_CONDITION_WIDTH`] bytes wide.
+///
+/// # Examples
+///
+/// ```
+/// use coreminer::watchpoint::{WatchCondition, WatchpointHit};
+/// use coreminer::addr::Addr;
+///
+/// let cond: WatchCondition = "== 0".parse().unwrap();
+/// assert_eq!(cond.to_string(), "new == 0");
+///
+/// let hit = WatchpointHit {
+///     addr: Addr::from(0x7ffd2b4a1f30usize),
+///     len: 4,
+///     instruction: Addr::from(0x55dd73ea3fb8usize),
+///     old: vec![1, 0, 0, 0],
+///     new: vec![0, 0, 0, 0],
+/// };
+/// assert!(cond.matches(&hit));
+/// assert!("new < old".parse::<WatchCondition>().unwrap().matches(&hit));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct WatchCondition {
+    /// The left side of the comparison
+    pub lhs: Operand,
+    /// How the sides are compared
+    pub op: CompareOp,
+    /// The right side of the comparison
+    pub rhs: Operand,
+}
+
+impl WatchCondition {
+    /// Returns `true` if the condition holds for the change `hit`
+    #[must_use]
+    pub fn matches(&self, hit: &WatchpointHit) -> bool {
+        self.op.apply(self.lhs.value(hit), self.rhs.value(hit))
+    }
+}
+
+impl FromStr for WatchCondition {
+    type Err = DebuggerError;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let (at, symbol, op) = CompareOp::SYMBOLS
+            .iter()
+            .filter_map(|(symbol, op)| s.find(symbol).map(|at| (at, *symbol, *op)))
+            .min_by_key(|(at, symbol, _)| (*at, std::cmp::Reverse(symbol.len())))
+            .ok_or_else(|| DebuggerError::InvalidWatchCondition(s.to_string()))?;
+
+        let lhs = s[..at].trim();
+        let lhs = if lhs.is_empty() {
+            Operand::New
+        } else {
+            lhs.parse()?
+        };
+        let rhs = s[at + symbol.len()..].parse()?;
+        Ok(Self { lhs, op, rhs })
+    }
+}
+
+impl TryFrom<String> for WatchCondition {
+    type Error = DebuggerError;
+
+    fn try_from(value: String) -> std::result::Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<WatchCondition> for String {
+    fn from(value: WatchCondition) -> Self {
+        value.to_string()
+    }
+}
+
+impl Display for WatchCondition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {} {}", self.lhs, self.op, self.rhs)
+    }
+}
+
+/// Reads `bytes` as a signed little endian integer and sign extends it to an [`i64`]
+fn sign_extend(bytes: &[u8]) -> i64 {
+    let Some(last) = bytes.last() else {
+        return 0;
+    };
+    let fill = if last & 0x80 == 0 { 0 } else { 0xff };
+    let mut buf = [fill; MAX_CONDITION_WIDTH];
+    let width = bytes.len().min(MAX_CONDITION_WIDTH);
+    buf[..width].copy_from_slice(&bytes[..width]);
+    i64::from_le_bytes(buf)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_compare_snapshot() {
+        let mut wp = Watchpoint {
+            addr: Addr::from(0x1000usize),
+            len: 4,
+            snapshot: vec![1, 2, 3, 4],
+            condition: None,
+        };
+
+        assert!(wp
+            .compare(vec![1, 2, 3, 4], Addr::from(0x40usize))
+            .is_none());
+
+        let hit = wp
+            .compare(vec![1, 2, 9, 4], Addr::from(0x44usize))
+            .expect("changed contents were not detected");
+        assert_eq!(hit.instruction, Addr::from(0x44usize));
+        assert_eq!(hit.old, vec![1, 2, 3, 4]);
+        assert_eq!(hit.new, vec![1, 2, 9, 4]);
+        assert_eq!(wp.snapshot(), &[1, 2, 9, 4]);
+
+        // the snapshot was updated, so the same contents are no change anymore
+        assert!(wp
+            .compare(vec![1, 2, 9, 4], Addr::from(0x48usize))
+            .is_none());
+    }
+
+    #[test]
+    fn test_condition() {
+        let mut wp = Watchpoint {
+            addr: Addr::from(0x1000usize),
+            len: 2,
+            snapshot: vec![5, 0],
+            condition: None,
+        };
+        wp.set_condition(Some("new == 0".parse().unwrap())).unwrap();
+
+        // the change is not reported, but the snapshot is updated
+        assert!(wp.compare(vec![4, 0], Addr::from(0x40usize)).is_none());
+        assert_eq!(wp.snapshot(), &[4, 0]);
+        assert!(wp.compare(vec![0, 0], Addr::from(0x44usize)).is_some());
+
+        wp.set_condition(Some("< old".parse().unwrap())).unwrap();
+        assert!(wp
+            .compare(vec![0xff, 0xff], Addr::from(0x48usize))
+            .is_some());
+        wp.set_condition(Some("new==0xffff".parse().unwrap()))
+            .unwrap();
+        assert!(wp
+            .compare(vec![0xff, 0xff], Addr::from(0x4cusize))
+            .is_none());
+        assert!(wp.compare(vec![0, 0], Addr::from(0x50usize)).is_none());
+        assert!(wp
+            .compare(vec![0xff, 0xff], Addr::from(0x54usize))
+            .is_some());
+
+        let cond: WatchCondition = "old>=-3".parse().unwrap();
+        assert_eq!(cond.lhs, Operand::Old);
+        assert_eq!(cond.op, CompareOp::Ge);
+        assert_eq!(cond.rhs, Operand::Value(-3));
+        assert_eq!(cond.to_string(), "old >= -3");
+        assert!("new = 0".parse::<WatchCondition>().is_err());
+        assert!("new == zero".parse::<WatchCondition>().is_err());
+
+        wp.len = 16;
+        assert!(wp.set_condition(Some(cond)).is_err());
+        assert!(wp.set_condition(None).is_ok());
+    }
+}
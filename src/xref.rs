@@ -0,0 +1,269 @@
+//! # Cross-Reference Module
+//!
+//! Provides searching for instructions that reference an address.
+//!
+//! To find out where a function is called from or where a global variable is used, the
+//! executable memory of the debuggee is disassembled and every instruction is checked for a
+//! reference to the address in question. The control-flow metadata of the disassembler tells
+//! calls apart from jumps, and memory operands (RIP-relative or absolute) as well as immediates
+//! are compared with the address too.
+//!
+//! The disassembly is a linear sweep, so data embedded in the code, or padding between
+//! functions, can occasionally produce bogus instructions. Only references with a target that
+//! is known without executing the code are found: `call rax` or `jmp [rbx+8]` reference nothing
+//! as far as this module is concerned.
+
+use std::fmt::{self, Display};
+
+use iced_x86::{Decoder, DecoderOptions, FlowControl, Formatter, Instruction, OpKind, Register};
+use serde::Serialize;
+
+use crate::disassemble::{formatter, CODE_BITNESS};
+use crate::Addr;
+
+/// The way an instruction references an address
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+pub enum XrefKind {
+    /// A direct `call`
+    Call,
+    /// A direct unconditional `jmp`
+    Jump,
+    /// A conditional jump, like `jne` or `loop`
+    ConditionalJump,
+    /// A memory operand, like `mov rax, [rip+0x2f10]` or `lea rdi, [rel 0x4010]`
+    Memory,
+    /// An immediate operand, like `mov edi, 0x401136`
+    Immediate,
+}
+
+/// An instruction that references an address
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+pub struct Xref {
+    /// Address of the referencing instruction
+    pub addr: Addr,
+    /// The referenced address
+    pub target: Addr,
+    /// How the address is referenced
+    pub kind: XrefKind,
+    /// The disassembled instruction
+    pub instruction: String,
+}
+
+/// Finds all instructions in a piece of machine code that reference `target`
+///
+/// # Parameters
+///
+/// * `data` - The machine code
+/// * `first_addr` - The address of the first byte of `data`
+/// * `target` - The address to look for
+///
+/// # Returns
+///
+/// All referencing instructions, in the order they appear in `data`.
+///
+/// # Examples
+///
+/// ```
+/// use coreminer::xref::{find_xrefs, XrefKind};
+/// use coreminer::addr::Addr;
+///
+/// let code = [
+///     0xe8, 0x0b, 0x00, 0x00, 0x00, // call 0x1010
+///     0x74, 0x09,                   // je 0x1010
+///     0xc3,                         // ret
+/// ];
+/// let xrefs = find_xrefs(&code, Addr::from(0x1000usize), Addr::from(0x1010usize));
+/// assert_eq!(xrefs.len(), 2);
+/// assert_eq!(xrefs[0].kind, XrefKind::Call);
+/// assert_eq!(xrefs[1].kind, XrefKind::ConditionalJump);
+/// ```
+#[must_use]
+pub fn find_xrefs(data: &[u8], first_addr: Addr, target: Addr) -> Vec<Xref> {
+    let mut decoder = Decoder::with_ip(CODE_BITNESS, data, first_addr.u64(), DecoderOptions::NONE);
+    let mut formatter = formatter();
+    let mut instruction = Instruction::default();
+    let mut xrefs = Vec::new();
+
+    while decoder.can_decode() {
+        decoder.decode_out(&mut instruction);
+        if instruction.is_invalid() {
+            continue;
+        }
+        let Some(kind) = reference_kind(&instruction, target.u64()) else {
+            continue;
+        };
+
+        let mut text = String::new();
+        formatter.format(&instruction, &mut text);
+        xrefs.push(Xref {
+            addr: Addr::from(instruction.ip()),
+            target,
+            kind,
+            instruction: text,
+        });
+    }
+
+    xrefs
+}
+
+/// Finds all direct calls in a piece of machine code
+///
+/// This is used to build a [`CallGraph`](crate::dot::CallGraph). Calls with a target that is only
+/// known at runtime, like `call rax`, are not found.
+///
+/// # Parameters
+///
+/// * `data` - The machine code
+/// * `first_addr` - The address of the first byte of `data`
+///
+/// # Returns
+///
+/// All direct calls, in the order they appear in `data`, with the called address as target.
+///
+/// # Examples
+///
+/// ```
+/// use coreminer::xref::find_calls;
+/// use coreminer::addr::Addr;
+///
+/// let code = [
+///     0xe8, 0x0b, 0x00, 0x00, 0x00, // call 0x1010
+///     0xff, 0xd0,                   // call rax
+///     0xc3,                         // ret
+/// ];
+/// let calls = find_calls(&code, Addr::from(0x1000usize));
+/// assert_eq!(calls.len(), 1);
+/// assert_eq!(calls[0].target, Addr::from(0x1010usize));
+/// ```
+#[must_use]
+pub fn find_calls(data: &[u8], first_addr: Addr) -> Vec<Xref> {
+    let mut decoder = Decoder::with_ip(CODE_BITNESS, data, first_addr.u64(), DecoderOptions::NONE);
+    let mut formatter = formatter();
+    let mut instruction = Instruction::default();
+    let mut calls = Vec::new();
+
+    while decoder.can_decode() {
+        decoder.decode_out(&mut instruction);
+        if instruction.is_invalid()
+            || instruction.flow_control() != FlowControl::Call
+            || instruction.near_branch_target() == 0
+        {
+            continue;
+        }
+
+        let mut text = String::new();
+        formatter.format(&instruction, &mut text);
+        calls.push(Xref {
+            addr: Addr::from(instruction.ip()),
+            target: Addr::from(instruction.near_branch_target()),
+            kind: XrefKind::Call,
+            instruction: text,
+        });
+    }
+
+    calls
+}
+
+/// Checks whether an instruction references `target` and how
+fn reference_kind(instruction: &Instruction, target: u64) -> Option<XrefKind> {
+    let branch_kind = match instruction.flow_control() {
+        FlowControl::Call => Some(XrefKind::Call),
+        FlowControl::UnconditionalBranch => Some(XrefKind::Jump),
+        FlowControl::ConditionalBranch => Some(XrefKind::ConditionalJump),
+        _ => None,
+    };
+    if let Some(kind) = branch_kind {
+        // far branches have no near branch target, which is 0 then
+        if instruction.near_branch_target() == target {
+            return Some(kind);
+        }
+    }
+
+    for i in 0..instruction.op_count() {
+        match instruction.op_kind(i) {
+            OpKind::Memory => {
+                let referenced = if instruction.is_ip_rel_memory_operand() {
+                    Some(instruction.ip_rel_memory_address())
+                } else if instruction.memory_base() == Register::None
+                    && instruction.memory_index() == Register::None
+                {
+                    Some(instruction.memory_displacement64())
+                } else {
+                    None
+                };
+                if referenced == Some(target) {
+                    return Some(XrefKind::Memory);
+                }
+            }
+            OpKind::Immediate32to64 | OpKind::Immediate32 | OpKind::Immediate64
+                if instruction.immediate(i) == target =>
+            {
+                return Some(XrefKind::Immediate);
+            }
+            _ => (),
+        }
+    }
+
+    None
+}
+
+impl Display for XrefKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Call => "call",
+            Self::Jump => "jump",
+            Self::ConditionalJump => "cond-jump",
+            Self::Memory => "memory",
+            Self::Immediate => "immediate",
+        };
+        write!(f, "{name}")
+    }
+}
+
+impl Display for Xref {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}  {:<9}  {}", self.addr, self.kind, self.instruction)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::assemble::assemble;
+
+    #[test]
+    fn test_find_xrefs() {
+        let base = Addr::from(0x40_1000usize);
+        let target = Addr::from(0x40_2000usize);
+        let code = assemble(
+            "call 0x402000; jmp 0x402000; jne 0x402000; call 0x402008; \
+             lea rdi, [rip+0x402000]; mov rax, [0x402000]; mov edi, 0x402000; \
+             mov rax, [rcx+0x402000]; call rax; ret",
+            base,
+        )
+        .expect("could not assemble the test code");
+
+        let xrefs = find_xrefs(&code, base, target);
+        let kinds: Vec<XrefKind> = xrefs.iter().map(|x| x.kind).collect();
+        assert_eq!(
+            kinds,
+            [
+                XrefKind::Call,
+                XrefKind::Jump,
+                XrefKind::ConditionalJump,
+                XrefKind::Memory,
+                XrefKind::Memory,
+                XrefKind::Immediate,
+            ]
+        );
+        assert_eq!(xrefs[0].addr, base);
+        assert!(xrefs.iter().all(|x| x.target == target));
+        assert!(xrefs[0].instruction.starts_with("call"));
+
+        assert!(find_xrefs(&code, base, Addr::from(0x40_3000usize)).is_empty());
+
+        let calls = find_calls(&code, base);
+        let targets: Vec<Addr> = calls.iter().map(|x| x.target).collect();
+        assert_eq!(targets, [target, Addr::from(0x40_2008usize)]);
+    }
+}